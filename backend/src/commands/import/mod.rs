@@ -4,8 +4,40 @@ use futures_util::StreamExt;
 use openssl::symm::Cipher;
 use serde::Deserialize;
 use shared::extensions::commands::CliCommandGroupBuilder;
+use sqlx::Row;
+use std::{collections::HashMap, sync::Arc};
 
 mod pterodactyl;
+mod report;
+
+/// The Laravel based panels that can be imported from, they all share the Pterodactyl schema
+/// with a few differences that are handled where they come up.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum SourcePanel {
+    Pterodactyl,
+    Jexactyl,
+    Pelican,
+}
+
+impl SourcePanel {
+    #[inline]
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Pterodactyl => "pterodactyl",
+            Self::Jexactyl => "jexactyl",
+            Self::Pelican => "pelican",
+        }
+    }
+
+    #[inline]
+    pub fn default_environment(self) -> &'static str {
+        match self {
+            Self::Pterodactyl => "/var/www/pterodactyl/.env",
+            Self::Jexactyl => "/var/www/jexactyl/.env",
+            Self::Pelican => "/var/www/pelican/.env",
+        }
+    }
+}
 
 static BASE64_ENGINE: base64::engine::general_purpose::GeneralPurpose =
     base64::engine::general_purpose::GeneralPurpose::new(
@@ -86,38 +118,48 @@ fn decrypt_laravel_value(
     extract_php_serialized_string(&result)
 }
 
+/// Limits the rows of a source table that are processed, the checkpoint is bound to the `?`
+/// placeholder of the where clause.
+#[derive(Default)]
+pub struct TableFilter {
+    pub sql_where: Option<String>,
+    pub checkpoint: Option<chrono::NaiveDateTime>,
+}
+
 pub async fn process_table<T, Fut: Future<Output = Result<T, anyhow::Error>>>(
     source_database: &sqlx::Pool<sqlx::MySql>,
     table: &str,
-    sql_where: Option<&str>,
+    filter: &TableFilter,
     compute: impl Fn(Vec<sqlx::mysql::MySqlRow>) -> Fut,
     page_size: usize,
 ) -> Result<Vec<T>, anyhow::Error> {
     tracing::info!(table, "starting processing");
 
-    let total: i64 = sqlx::query_scalar(&format!(
-        "SELECT COUNT(*) FROM `{table}` {}",
-        if let Some(where_clause) = sql_where {
-            format!("WHERE {where_clause}")
-        } else {
-            String::new()
-        }
-    ))
-    .fetch_one(source_database)
-    .await
-    .context("failed to count total rows for table")?;
+    let where_clause = if let Some(sql_where) = &filter.sql_where {
+        format!("WHERE {sql_where}")
+    } else {
+        String::new()
+    };
+
+    let count_query = format!("SELECT COUNT(*) FROM `{table}` {where_clause}");
+    let mut count_query = sqlx::query_scalar(&count_query);
+    if let Some(checkpoint) = filter.checkpoint {
+        count_query = count_query.bind(checkpoint);
+    }
+
+    let total: i64 = count_query
+        .fetch_one(source_database)
+        .await
+        .context("failed to count total rows for table")?;
 
     tracing::info!(table, total, "total rows to process");
 
-    let query = format!(
-        "SELECT * FROM `{table}` {}",
-        if let Some(where_clause) = sql_where {
-            format!("WHERE {where_clause}")
-        } else {
-            String::new()
-        }
-    );
-    let mut query_rows = sqlx::query(&query).fetch(source_database);
+    let query = format!("SELECT * FROM `{table}` {where_clause}");
+    let mut query = sqlx::query(&query);
+    if let Some(checkpoint) = filter.checkpoint {
+        query = query.bind(checkpoint);
+    }
+    let mut query_rows = query.fetch(source_database);
 
     let mut offset: usize = 0;
     let mut results = Vec::new();
@@ -159,10 +201,247 @@ pub async fn process_table<T, Fut: Future<Output = Result<T, anyhow::Error>>>(
     Ok(results)
 }
 
+/// Keeps track of which source rows were imported as which rows of this panel, and up to which
+/// change every table was processed, so an interrupted or repeated import only picks up the rows
+/// that changed since the last run.
+///
+/// Rows deleted in the source panel cannot be detected this way and are kept here, only the links
+/// of imported mounts and the steps of changed schedules are replaced as a whole, everything else
+/// deleted in the source panel has to be removed here by hand.
+pub struct ImportMappings {
+    database: Arc<shared::database::Database>,
+    source: &'static str,
+    full: bool,
+}
+
+impl ImportMappings {
+    pub fn new(database: Arc<shared::database::Database>, panel: SourcePanel, full: bool) -> Self {
+        Self {
+            database,
+            source: panel.name(),
+            full,
+        }
+    }
+
+    /// Returns the filter limiting the table to the rows changed since its checkpoint, together
+    /// with the newest change of the table, which becomes the next checkpoint. Rows without an
+    /// `updated_at` cannot be compared and are always processed.
+    pub async fn begin(
+        &self,
+        source_database: &sqlx::Pool<sqlx::MySql>,
+        table: &str,
+        sql_where: Option<&str>,
+    ) -> Result<(TableFilter, Option<chrono::DateTime<chrono::Utc>>), anyhow::Error> {
+        let latest: Option<chrono::DateTime<chrono::Utc>> =
+            sqlx::query_scalar(&format!("SELECT MAX(updated_at) FROM `{table}`"))
+                .fetch_one(source_database)
+                .await
+                .context("failed to fetch latest change of table")?;
+
+        let checkpoint: Option<chrono::NaiveDateTime> = if self.full {
+            None
+        } else {
+            sqlx::query_scalar(
+                r#"
+                SELECT import_checkpoints.updated
+                FROM import_checkpoints
+                WHERE import_checkpoints.source = $1 AND import_checkpoints.source_table = $2
+                "#,
+            )
+            .bind(self.source)
+            .bind(table)
+            .fetch_optional(self.database.read())
+            .await?
+        };
+
+        let sql_where = match (checkpoint, sql_where) {
+            (Some(_), Some(sql_where)) => Some(format!(
+                "({sql_where}) AND (updated_at IS NULL OR updated_at >= ?)"
+            )),
+            (Some(_), None) => Some("(updated_at IS NULL OR updated_at >= ?)".to_string()),
+            (None, sql_where) => sql_where.map(String::from),
+        };
+
+        if let Some(checkpoint) = checkpoint {
+            tracing::info!(table, %checkpoint, "only processing rows changed since checkpoint");
+        }
+
+        Ok((
+            TableFilter {
+                sql_where,
+                checkpoint,
+            },
+            latest,
+        ))
+    }
+
+    /// Stores the newest change of a fully processed table as its checkpoint.
+    pub async fn checkpoint(
+        &self,
+        table: &str,
+        latest: Option<chrono::DateTime<chrono::Utc>>,
+    ) -> Result<(), anyhow::Error> {
+        let latest = match latest {
+            Some(latest) => latest,
+            None => return Ok(()),
+        };
+
+        sqlx::query(
+            r#"
+            INSERT INTO import_checkpoints (source, source_table, updated)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (source, source_table) DO UPDATE SET updated = EXCLUDED.updated
+            "#,
+        )
+        .bind(self.source)
+        .bind(table)
+        .bind(latest.naive_utc())
+        .execute(self.database.write())
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn record(
+        &self,
+        table: &str,
+        mappings: impl IntoIterator<Item = (u32, uuid::Uuid)>,
+    ) -> Result<(), anyhow::Error> {
+        let (source_ids, target_uuids): (Vec<i64>, Vec<uuid::Uuid>) = mappings
+            .into_iter()
+            .map(|(source_id, target_uuid)| (source_id as i64, target_uuid))
+            .unzip();
+
+        if source_ids.is_empty() {
+            return Ok(());
+        }
+
+        sqlx::query(
+            r#"
+            INSERT INTO import_mappings (source, source_table, source_id, target_uuid)
+            SELECT $1, $2, source_id, target_uuid
+            FROM UNNEST($3::bigint[], $4::uuid[]) AS mappings (source_id, target_uuid)
+            ON CONFLICT (source, source_table, source_id) DO UPDATE SET target_uuid = EXCLUDED.target_uuid
+            "#,
+        )
+        .bind(self.source)
+        .bind(table)
+        .bind(source_ids)
+        .bind(target_uuids)
+        .execute(self.database.write())
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn load(&self, table: &str) -> Result<HashMap<u32, uuid::Uuid>, anyhow::Error> {
+        let rows = sqlx::query(
+            r#"
+            SELECT import_mappings.source_id, import_mappings.target_uuid
+            FROM import_mappings
+            WHERE import_mappings.source = $1 AND import_mappings.source_table = $2
+            "#,
+        )
+        .bind(self.source)
+        .bind(table)
+        .fetch_all(self.database.read())
+        .await?;
+
+        let mut mappings = HashMap::with_capacity(rows.len());
+        for row in rows {
+            mappings.insert(
+                row.try_get::<i64, _>("source_id")? as u32,
+                row.try_get("target_uuid")?,
+            );
+        }
+
+        Ok(mappings)
+    }
+
+    /// Records the mappings of the processed rows and checkpoints the table, then returns the
+    /// mappings of every row imported so far, including the ones of previous runs.
+    pub async fn finish(
+        &self,
+        table: &str,
+        latest: Option<chrono::DateTime<chrono::Utc>>,
+        mappings: Vec<HashMap<u32, uuid::Uuid>>,
+    ) -> Result<Vec<HashMap<u32, uuid::Uuid>>, anyhow::Error> {
+        self.record(table, mappings.into_iter().flatten()).await?;
+        self.checkpoint(table, latest).await?;
+
+        Ok(vec![self.load(table).await?])
+    }
+}
+
+/// Converts the subuser permissions of the source panel, unknown permissions are dropped.
+fn convert_subuser_permissions(raw_permissions: Vec<String>) -> Vec<&'static str> {
+    let mut permissions = std::collections::HashSet::with_capacity(raw_permissions.len());
+
+    for permission in raw_permissions {
+        permissions.insert(match permission.as_str() {
+            "control.console" => "control.console",
+            "control.start" => "control.start",
+            "control.stop" => "control.stop",
+            "control.restart" => "control.restart",
+            "user.create" => "subusers.create",
+            "user.read" => "subusers.read",
+            "user.update" => "subusers.update",
+            "user.delete" => "subusers.delete",
+            "file.create" => "files.create",
+            "file.read" => "files.read",
+            "file.read-content" => "files.read-content",
+            "file.update" => "files.update",
+            "file.delete" => "files.delete",
+            "file.archive" => "files.archive",
+            "backup.create" => "backups.create",
+            "backup.read" => "backups.read",
+            "backup.download" => "backups.download",
+            "backup.restore" => "backups.restore",
+            "backup.delete" => "backups.delete",
+            "allocation.create" => "allocations.create",
+            "allocation.read" => "allocations.read",
+            "allocation.update" => "allocations.update",
+            "allocation.delete" => "allocations.delete",
+            "startup.read" => "startup.read",
+            "startup.update" => "startup.update",
+            "startup.docker-image" => "startup.docker-image",
+            "database.create" => "databases.create",
+            "database.read" => "databases.read",
+            "database.update" => "databases.update",
+            "database.view_password" => "databases.read-password",
+            "database.delete" => "databases.delete",
+            "schedule.create" => "schedules.create",
+            "schedule.read" => "schedules.read",
+            "schedule.update" => "schedules.update",
+            "schedule.delete" => "schedules.delete",
+            "settings.rename" => "settings.rename",
+            "settings.reinstall" => "settings.install",
+            "activity.read" => "activity.read",
+            _ => continue,
+        });
+
+        if permission == "control.console" {
+            permissions.insert("control.read-console");
+        }
+    }
+
+    permissions.into_iter().collect()
+}
+
 pub fn commands(cli: CliCommandGroupBuilder) -> CliCommandGroupBuilder {
     cli.add_command(
         "pterodactyl",
         "Imports data from a Pterodactyl panel.",
-        pterodactyl::PterodactylCommand,
+        pterodactyl::PterodactylCommand(SourcePanel::Pterodactyl),
+    )
+    .add_command(
+        "pelican",
+        "Imports data from a Pelican panel.",
+        pterodactyl::PterodactylCommand(SourcePanel::Pelican),
+    )
+    .add_command(
+        "jexactyl",
+        "Imports data from a Jexactyl panel.",
+        pterodactyl::PterodactylCommand(SourcePanel::Jexactyl),
     )
 }
@@ -1,9 +1,13 @@
-use super::{BASE64_ENGINE, decrypt_laravel_value, process_table};
+use super::{
+    BASE64_ENGINE, ImportMappings, SourcePanel, TableFilter, convert_subuser_permissions,
+    decrypt_laravel_value, process_table, report::DryRunReport,
+};
 use anyhow::Context;
 use base64::Engine;
 use clap::{Args, FromArgMatches};
 use colored::Colorize;
 use compact_str::ToCompactString;
+use shared::prelude::SqlxErrorExt;
 use sqlx::Row;
 use std::{
     collections::{HashMap, HashSet},
@@ -16,14 +20,34 @@ pub struct PterodactylArgs {
     #[arg(
         short = 'e',
         long = "environment",
-        help = "the environment variable file location for the pterodactyl panel",
-        default_value = "/var/www/pterodactyl/.env",
+        help = "the environment variable file location for the source panel, defaults to /var/www/<panel>/.env",
         value_hint = clap::ValueHint::FilePath
     )]
-    environment: String,
+    environment: Option<String>,
+    #[arg(
+        long = "dry-run",
+        help = "whether to run the import without keeping any of its changes and report them instead",
+        default_value = "false"
+    )]
+    dry_run: bool,
+    #[arg(
+        long = "full",
+        help = "whether to ignore the checkpoints of previous runs and process every row again",
+        default_value = "false"
+    )]
+    full: bool,
+    #[arg(
+        long = "force-password-reset",
+        help = "whether to drop the imported password hashes, so imported users have to set a password that passes the password policy through a password reset",
+        default_value = "false"
+    )]
+    force_password_reset: bool,
 }
 
-pub struct PterodactylCommand;
+/// Imports the data of a Laravel based panel, the schema differences between the supported
+/// panels are handled inline.
+#[derive(Clone, Copy)]
+pub struct PterodactylCommand(pub SourcePanel);
 
 impl shared::extensions::commands::CliCommand<PterodactylArgs> for PterodactylCommand {
     fn get_command(&self, command: clap::Command) -> clap::Command {
@@ -31,7 +55,9 @@ impl shared::extensions::commands::CliCommand<PterodactylArgs> for PterodactylCo
     }
 
     fn get_executor(self) -> Box<shared::extensions::commands::ExecutorFunc> {
-        Box::new(|env, arg_matches| {
+        let panel = self.0;
+
+        Box::new(move |env, arg_matches| {
             Box::pin(async move {
                 let args = PterodactylArgs::from_arg_matches(&arg_matches)?;
 
@@ -49,10 +75,14 @@ impl shared::extensions::commands::CliCommand<PterodactylArgs> for PterodactylCo
                     }
                 };
 
-                if let Err(err) = dotenvy::from_path(args.environment) {
+                if let Err(err) = dotenvy::from_path(
+                    args.environment
+                        .as_deref()
+                        .unwrap_or(panel.default_environment()),
+                ) {
                     eprintln!(
                         "{}: {:#?}",
-                        "failed to read pterodactyl environment file".red(),
+                        format!("failed to read {} environment file", panel.name()).red(),
                         err
                     );
 
@@ -64,7 +94,7 @@ impl shared::extensions::commands::CliCommand<PterodactylArgs> for PterodactylCo
                     Err(err) => {
                         eprintln!(
                             "{}: {:#?}",
-                            "failed to read pterodactyl environment APP_URL".red(),
+                            format!("failed to read {} environment APP_URL", panel.name()).red(),
                             err
                         );
 
@@ -78,7 +108,7 @@ impl shared::extensions::commands::CliCommand<PterodactylArgs> for PterodactylCo
                     Ok(Err(err)) => {
                         eprintln!(
                             "{}: {:#?}",
-                            "failed to read pterodactyl environment APP_KEY".red(),
+                            format!("failed to read {} environment APP_KEY", panel.name()).red(),
                             err
                         );
 
@@ -87,7 +117,7 @@ impl shared::extensions::commands::CliCommand<PterodactylArgs> for PterodactylCo
                     Err(err) => {
                         eprintln!(
                             "{}: {:#?}",
-                            "failed to read pterodactyl environment APP_KEY".red(),
+                            format!("failed to read {} environment APP_KEY", panel.name()).red(),
                             err
                         );
 
@@ -99,7 +129,7 @@ impl shared::extensions::commands::CliCommand<PterodactylArgs> for PterodactylCo
                     Err(err) => {
                         eprintln!(
                             "{}: {:#?}",
-                            "failed to read pterodactyl environment DB_HOST".red(),
+                            format!("failed to read {} environment DB_HOST", panel.name()).red(),
                             err
                         );
 
@@ -112,7 +142,7 @@ impl shared::extensions::commands::CliCommand<PterodactylArgs> for PterodactylCo
                     Ok(Err(err)) => {
                         eprintln!(
                             "{}: {:#?}",
-                            "failed to read pterodactyl environment DB_PORT".red(),
+                            format!("failed to read {} environment DB_PORT", panel.name()).red(),
                             err
                         );
 
@@ -121,7 +151,7 @@ impl shared::extensions::commands::CliCommand<PterodactylArgs> for PterodactylCo
                     Err(err) => {
                         eprintln!(
                             "{}: {:#?}",
-                            "failed to read pterodactyl environment DB_PORT".red(),
+                            format!("failed to read {} environment DB_PORT", panel.name()).red(),
                             err
                         );
 
@@ -133,7 +163,8 @@ impl shared::extensions::commands::CliCommand<PterodactylArgs> for PterodactylCo
                     Err(err) => {
                         eprintln!(
                             "{}: {:#?}",
-                            "failed to read pterodactyl environment DB_DATABASE".red(),
+                            format!("failed to read {} environment DB_DATABASE", panel.name())
+                                .red(),
                             err
                         );
 
@@ -145,7 +176,8 @@ impl shared::extensions::commands::CliCommand<PterodactylArgs> for PterodactylCo
                     Err(err) => {
                         eprintln!(
                             "{}: {:#?}",
-                            "failed to read pterodactyl environment DB_USERNAME".red(),
+                            format!("failed to read {} environment DB_USERNAME", panel.name())
+                                .red(),
                             err
                         );
 
@@ -157,7 +189,8 @@ impl shared::extensions::commands::CliCommand<PterodactylArgs> for PterodactylCo
                     Err(err) => {
                         eprintln!(
                             "{}: {:#?}",
-                            "failed to read pterodactyl environment DB_PASSWORD".red(),
+                            format!("failed to read {} environment DB_PASSWORD", panel.name())
+                                .red(),
                             err
                         );
 
@@ -179,7 +212,7 @@ impl shared::extensions::commands::CliCommand<PterodactylArgs> for PterodactylCo
                     Err(err) => {
                         eprintln!(
                             "{}: {:#?}",
-                            "failed to connect to pterodactyl database".red(),
+                            format!("failed to connect to {} database", panel.name()).red(),
                             err
                         );
 
@@ -188,7 +221,11 @@ impl shared::extensions::commands::CliCommand<PterodactylArgs> for PterodactylCo
                 };
 
                 let cache = Arc::new(shared::cache::Cache::new(&env).await);
-                let database = Arc::new(shared::database::Database::new(&env, cache.clone()).await);
+                let database = Arc::new(if args.dry_run {
+                    shared::database::Database::new_dry_run(&env, cache.clone()).await
+                } else {
+                    shared::database::Database::new(&env, cache.clone()).await
+                });
                 let settings = Arc::new(
                     shared::settings::Settings::new(database.clone())
                         .await
@@ -196,10 +233,47 @@ impl shared::extensions::commands::CliCommand<PterodactylArgs> for PterodactylCo
                         .unwrap(),
                 );
 
-                if let Err(err) = process_table(
+                let report = if args.dry_run {
+                    Some(DryRunReport::new(&database).await?)
+                } else {
+                    None
+                };
+
+                let mappings = ImportMappings::new(database.clone(), panel, args.full);
+
+                if panel == SourcePanel::Pelican {
+                    // pelican keeps its settings in the environment file instead of the database
+                    let mut settings = settings.get_mut().await?;
+
+                    settings.oobe_step = None;
+                    settings.app.url = source_app_url.to_compact_string();
+                    if let Ok(app_name) = std::env::var("APP_NAME") {
+                        settings.app.name = app_name.into();
+                    }
+
+                    if std::env::var("MAIL_MAILER").is_ok_and(|m| m == "smtp")
+                        && let Ok(smtp_host) = std::env::var("MAIL_HOST")
+                        && let Ok(Ok(smtp_port)) =
+                            std::env::var("MAIL_PORT").map(|p| p.parse::<u16>())
+                        && let Ok(from_address) = std::env::var("MAIL_FROM_ADDRESS")
+                    {
+                        settings.mail_mode = shared::settings::MailMode::Smtp {
+                            host: smtp_host.into(),
+                            port: smtp_port,
+                            username: std::env::var("MAIL_USERNAME").ok().map(Into::into),
+                            password: std::env::var("MAIL_PASSWORD").ok().map(Into::into),
+                            use_tls: std::env::var("MAIL_ENCRYPTION").is_ok_and(|e| e == "tls")
+                                || std::env::var("MAIL_SCHEME").is_ok_and(|s| s == "smtps"),
+                            from_address: from_address.into(),
+                            from_name: std::env::var("MAIL_FROM_NAME").ok().map(Into::into),
+                        };
+                    }
+
+                    settings.save().await?;
+                } else if let Err(err) = process_table(
                     &source_database,
                     "settings",
-                    None,
+                    &TableFilter::default(),
                     async |rows| {
                         let mut settings = settings.get_mut().await?;
 
@@ -250,10 +324,32 @@ impl shared::extensions::commands::CliCommand<PterodactylArgs> for PterodactylCo
                     std::process::exit(1);
                 }
 
+                // pelican replaced the root_admin column with a role
+                let root_admin_ids: Arc<HashSet<u64>> =
+                    Arc::new(if panel == SourcePanel::Pelican {
+                        sqlx::query_scalar(
+                            r#"
+                        SELECT model_has_roles.model_id
+                        FROM model_has_roles
+                        JOIN roles ON roles.id = model_has_roles.role_id
+                        WHERE roles.name = 'Root Admin'
+                        "#,
+                        )
+                        .fetch_all(&source_database)
+                        .await
+                        .unwrap_or_default()
+                        .into_iter()
+                        .collect()
+                    } else {
+                        HashSet::new()
+                    });
+
+                let force_password_reset = args.force_password_reset;
+                let (filter, latest) = mappings.begin(&source_database, "users", None).await?;
                 let user_mappings = match process_table(
                     &source_database,
                     "users",
-                    None,
+                    &filter,
                     async |rows| {
                         let mut mapping = HashMap::with_capacity(rows.len());
                         let mut futures = Vec::with_capacity(rows.len());
@@ -265,25 +361,51 @@ impl shared::extensions::commands::CliCommand<PterodactylArgs> for PterodactylCo
                             mapping.insert(id, *uuid.as_uuid());
 
                             let source_app_key = source_app_key.clone();
+                            let root_admin_ids = root_admin_ids.clone();
                             let database = database.clone();
                             futures.push(async move {
                                 let external_id: Option<&str> = row.try_get("external_id")?;
                                 let username: &str = row.try_get("username")?;
                                 let email: &str = row.try_get("email")?;
-                                let name_first: &str = row.try_get("name_first")?;
-                                let name_last: &str = row.try_get("name_last")?;
+                                // pelican dropped the split name columns, the username takes their place
+                                let name_first: &str = row.try_get("name_first").unwrap_or(username);
+                                let name_last: &str = row.try_get("name_last").unwrap_or("");
                                 let password: &str = row.try_get("password")?;
-                                let admin: bool = row.try_get("root_admin")?;
-                                let totp_enabled: bool = row.try_get("use_totp")?;
-                                let totp_secret: Option<compact_str::CompactString> = row.try_get::<Option<&str>, _>("totp_secret")?
+                                let admin: bool = row
+                                    .try_get("root_admin")
+                                    .unwrap_or_else(|_| root_admin_ids.contains(&(id as u64)));
+                                let totp_secret: Option<compact_str::CompactString> = row
+                                    .try_get::<Option<&str>, _>("totp_secret")
+                                    .or_else(|_| row.try_get::<Option<&str>, _>("mfa_app_secret"))?
                                     .and_then(|s| decrypt_laravel_value(s, &source_app_key).ok());
+                                let totp_enabled: bool = row.try_get("use_totp").unwrap_or(totp_secret.is_some());
                                 let created: chrono::DateTime<chrono::Utc> = row.try_get("created_at")?;
 
-                                sqlx::query(
+                                // the savepoint keeps a rejected insert from aborting the transaction of a dry run
+                                let mut transaction = database.write().begin().await?;
+                                let result = sqlx::query(
                                     r#"
                                     INSERT INTO users (uuid, external_id, username, email, name_first, name_last, password, admin, totp_enabled, totp_secret, created)
-                                    VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
-                                    ON CONFLICT DO NOTHING
+                                    SELECT $1, $2, $3, $4, $5, $6, CASE WHEN $12 THEN NULL ELSE $7 END, $8, $9, $10, $11
+                                    WHERE NOT EXISTS (
+                                        SELECT 1 FROM users
+                                        WHERE users.uuid != $1
+                                            AND (lower(users.username) = lower($3) OR lower(users.email) = lower($4) OR users.external_id = $2)
+                                    )
+                                    ON CONFLICT (uuid) DO UPDATE SET
+                                        external_id = EXCLUDED.external_id,
+                                        username = EXCLUDED.username,
+                                        email = EXCLUDED.email,
+                                        name_first = EXCLUDED.name_first,
+                                        name_last = EXCLUDED.name_last,
+                                        password = CASE
+                                            WHEN NOT $12 THEN $7
+                                            WHEN users.password = $7 THEN NULL
+                                            ELSE users.password
+                                        END,
+                                        admin = EXCLUDED.admin,
+                                        totp_enabled = EXCLUDED.totp_enabled,
+                                        totp_secret = EXCLUDED.totp_secret
                                     "#
                                 )
                                 .bind(uuid.as_uuid())
@@ -297,8 +419,25 @@ impl shared::extensions::commands::CliCommand<PterodactylArgs> for PterodactylCo
                                 .bind(totp_enabled)
                                 .bind(totp_secret)
                                 .bind(created)
-                                .execute(database.write())
-                                .await?;
+                                // the source hashes can not be checked against the password policy, a forced
+                                // reset leaves users without a password until they set one through a password
+                                // reset, which applies the policy. passwords already changed on this panel are kept
+                                .bind(force_password_reset)
+                                .execute(&mut *transaction)
+                                .await;
+
+                                match result {
+                                    Ok(result) if result.rows_affected() > 0 => transaction.commit().await?,
+                                    Ok(_) => {
+                                        transaction.commit().await?;
+                                        tracing::warn!(user = %uuid, "skipping user, username or email is already taken");
+                                    }
+                                    Err(err) if err.is_unique_violation() => {
+                                        transaction.rollback().await?;
+                                        tracing::warn!(user = %uuid, "skipping user, username or email is already taken");
+                                    }
+                                    Err(err) => return Err(err.into()),
+                                }
 
                                 Ok::<(), anyhow::Error>(())
                             });
@@ -312,17 +451,24 @@ impl shared::extensions::commands::CliCommand<PterodactylArgs> for PterodactylCo
                 )
                 .await
                 {
-                    Ok(user_mappings) => Arc::new(user_mappings),
+                    Ok(user_mappings) => Arc::new(mappings.finish("users", latest, user_mappings).await?),
                     Err(err) => {
                         tracing::error!("failed to process users table: {:?}", err);
                         std::process::exit(1);
                     }
                 };
 
+                let (filter, latest) = mappings
+                    .begin(
+                        &source_database,
+                        "user_ssh_keys",
+                        Some("deleted_at IS NULL"),
+                    )
+                    .await?;
                 if let Err(err) = process_table(
                     &source_database,
                     "user_ssh_keys",
-                    Some("deleted_at IS NULL"),
+                    &filter,
                     async |rows| {
                         let mut futures = Vec::with_capacity(rows.len());
 
@@ -376,7 +522,7 @@ impl shared::extensions::commands::CliCommand<PterodactylArgs> for PterodactylCo
                                     r#"
                                     INSERT INTO user_ssh_keys (user_uuid, name, fingerprint, public_key, created)
                                     VALUES ($1, $2, $3, $4, $5)
-                                    ON CONFLICT DO NOTHING
+                                    ON CONFLICT (user_uuid, fingerprint) DO UPDATE SET name = EXCLUDED.name
                                     "#,
                                 )
                                 .bind(user_uuid)
@@ -406,17 +552,33 @@ impl shared::extensions::commands::CliCommand<PterodactylArgs> for PterodactylCo
                     tracing::error!("failed to process ssh keys table: {:?}", err);
                     std::process::exit(1);
                 }
-                /*if let Err(err) = process_table(
+                mappings.checkpoint("user_ssh_keys", latest).await?;
+
+                let api_key_user_permissions = shared::permissions::list_base_user_permissions();
+                let api_key_server_permissions =
+                    shared::permissions::list_base_server_permissions();
+                let (filter, latest) = mappings
+                    .begin(
+                        &source_database,
+                        "api_keys",
+                        Some("user_id IS NOT NULL AND key_type = 1"),
+                    )
+                    .await?;
+                if let Err(err) = process_table(
                     &source_database,
                     "api_keys",
-                    Some("user_id IS NOT NULL AND key_type = 1"),
+                    &filter,
                     async |rows| {
                         for row in rows {
-                            let id: u32 = row.try_get("id")?;
                             let user_id: u32 = row.try_get("user_id")?;
-                            let name = format!("imported-{}", id);
+                            let identifier: &str = row.try_get("identifier")?;
                             let token: &str = row.try_get("token")?;
-                            let last_used: Option<chrono::DateTime<chrono::Utc>> = row.try_get("last_used_at")?;
+                            let memo: Option<&str> = row.try_get("memo")?;
+                            let allowed_ips: Option<&str> = row.try_get("allowed_ips")?;
+                            let last_used: Option<chrono::DateTime<chrono::Utc>> =
+                                row.try_get("last_used_at")?;
+                            let expires: Option<chrono::DateTime<chrono::Utc>> =
+                                row.try_get("expires_at").unwrap_or_default();
                             let created: chrono::DateTime<chrono::Utc> = row.try_get("created_at")?;
 
                             let user_uuid = match user_mappings.iter().find(|m| m.contains_key(&user_id)) {
@@ -429,18 +591,45 @@ impl shared::extensions::commands::CliCommand<PterodactylArgs> for PterodactylCo
                                 Err(_) => continue,
                             };
 
-                            println!("importing api key for user {}", token);
+                            // the identifier and token together make up the key the clients already use,
+                            // the identifier lines up with the stored start of the key
+                            let key = format!("{identifier}{token}");
+                            if identifier.len() != 16 || key.len() != 48 {
+                                continue;
+                            }
+
+                            let allowed_ips: Vec<sqlx::types::ipnetwork::IpNetwork> = allowed_ips
+                                .and_then(|ips| serde_json::from_str::<Vec<String>>(ips).ok())
+                                .unwrap_or_default()
+                                .into_iter()
+                                .filter_map(|ip| sqlx::types::ipnetwork::IpNetwork::from_str(&ip).ok())
+                                .collect();
+
+                            let name = match memo {
+                                Some(memo) if !memo.is_empty() => shared::utils::slice_up_to(memo, 31),
+                                _ => identifier,
+                            };
 
                             sqlx::query(
                                 r#"
-                                INSERT INTO user_api_keys (user_uuid, name, key_start, key, last_used, created)
-                                VALUES ($1, $2, $3, crypt($4, gen_salt('xdes', 321)), $5, $6)
-                                ON CONFLICT DO NOTHING
+                                INSERT INTO user_api_keys (user_uuid, name, key_start, key, allowed_ips, user_permissions, admin_permissions, server_permissions, last_used, expires, created)
+                                VALUES ($1, $2, $3, crypt($4, gen_salt('xdes', 321)), $5, $6, $7, $8, $9, $10, $11)
+                                ON CONFLICT (user_uuid, key_start) DO UPDATE SET
+                                    allowed_ips = EXCLUDED.allowed_ips,
+                                    last_used = EXCLUDED.last_used,
+                                    expires = EXCLUDED.expires
                                 "#,
                             )
                             .bind(user_uuid)
                             .bind(name)
+                            .bind(identifier)
+                            .bind(key)
+                            .bind(allowed_ips)
+                            .bind(&api_key_user_permissions)
+                            .bind(&[] as &[&str])
+                            .bind(&api_key_server_permissions)
                             .bind(last_used)
+                            .bind(expires)
                             .bind(created)
                             .execute(database.write())
                             .await?;
@@ -452,83 +641,127 @@ impl shared::extensions::commands::CliCommand<PterodactylArgs> for PterodactylCo
                 )
                 .await
                 {
-                    tracing::error!("failed to process table: {:?}", err);
+                    tracing::error!("failed to process api keys table: {:?}", err);
                     std::process::exit(1);
-                }*/
+                }
+                mappings.checkpoint("api_keys", latest).await?;
+
+                // the generated backup configuration is recorded as well, so a re-run does not create another
+                let backup_configuration_mappings = mappings.load("backup_configurations").await?;
+                let backup_configuration_uuid: uuid::Uuid = match backup_configuration_mappings
+                    .get(&0)
+                {
+                    Some(backup_configuration_uuid) => *backup_configuration_uuid,
+                    None => {
+                        let row = sqlx::query(
+                            r#"
+                            INSERT INTO backup_configurations (name, description, backup_disk, backup_configs)
+                            VALUES ($1, $2, $3, $4)
+                            RETURNING uuid
+                            "#,
+                        )
+                        .bind("global")
+                        .bind("automatically generated by import")
+                        .bind(shared::models::server_backup::BackupDisk::Local)
+                        .bind(
+                            serde_json::to_value(
+                                shared::models::backup_configurations::BackupConfigs::default(),
+                            )
+                            .unwrap(),
+                        )
+                        .fetch_one(database.write())
+                        .await
+                        .unwrap();
 
-                let backup_configuration_uuid: uuid::Uuid = {
+                        let backup_configuration_uuid = row.get("uuid");
+                        mappings
+                            .record("backup_configurations", [(0, backup_configuration_uuid)])
+                            .await?;
+
+                        backup_configuration_uuid
+                    }
+                };
+
+                // pelican has no locations, its nodes are all put into a single generated one
+                let default_location_uuid: Option<uuid::Uuid> = if panel == SourcePanel::Pelican {
                     let row = sqlx::query(
                         r#"
-                        INSERT INTO backup_configurations (name, description, backup_disk, backup_configs)
-                        VALUES ($1, $2, $3, $4)
+                        INSERT INTO locations (backup_configuration_uuid, name, description)
+                        VALUES ($1, $2, $3)
+                        ON CONFLICT (name) DO UPDATE SET name = EXCLUDED.name
                         RETURNING uuid
                         "#,
                     )
-                    .bind("global")
+                    .bind(backup_configuration_uuid)
+                    .bind("default")
                     .bind("automatically generated by import")
-                    .bind(shared::models::server_backup::BackupDisk::Local)
-                    .bind(
-                        serde_json::to_value(
-                            shared::models::backup_configurations::BackupConfigs::default(),
-                        )
-                        .unwrap(),
-                    )
                     .fetch_one(database.write())
-                    .await
-                    .unwrap();
+                    .await?;
 
-                    row.get("uuid")
+                    Some(row.get("uuid"))
+                } else {
+                    None
                 };
 
-                let location_mappings = match process_table(
-                    &source_database,
-                    "locations",
-                    None,
-                    async |rows| {
-                        let mut mapping: HashMap<u32, uuid::Uuid> =
-                            HashMap::with_capacity(rows.len());
+                let location_mappings = if panel == SourcePanel::Pelican {
+                    Arc::new(Vec::new())
+                } else {
+                    let (filter, latest) =
+                        mappings.begin(&source_database, "locations", None).await?;
+
+                    match process_table(
+                        &source_database,
+                        "locations",
+                        &filter,
+                        async |rows| {
+                            let mut mapping: HashMap<u32, uuid::Uuid> =
+                                HashMap::with_capacity(rows.len());
+
+                            for row in rows {
+                                let id: u32 = row.try_get("id")?;
+                                let short_name: &str = row.try_get("short")?;
+                                let description: Option<&str> = row.try_get("long")?;
+                                let created: chrono::DateTime<chrono::Utc> =
+                                    row.try_get("created_at")?;
 
-                        for row in rows {
-                            let id: u32 = row.try_get("id")?;
-                            let short_name: &str = row.try_get("short")?;
-                            let description: Option<&str> = row.try_get("long")?;
-                            let created: chrono::DateTime<chrono::Utc> =
-                                row.try_get("created_at")?;
+                                let row = sqlx::query(
+                                    r#"
+                                    INSERT INTO locations (backup_configuration_uuid, name, description, created)
+                                    VALUES ($1, $2, $3, $4)
+                                    ON CONFLICT (name) DO UPDATE SET description = EXCLUDED.description
+                                    RETURNING uuid
+                                    "#,
+                                )
+                                .bind(backup_configuration_uuid)
+                                .bind(short_name)
+                                .bind(description)
+                                .bind(created)
+                                .fetch_one(database.write())
+                                .await?;
 
-                            let row = sqlx::query(
-                                r#"
-                                INSERT INTO locations (backup_configuration_uuid, name, description, created)
-                                VALUES ($1, $2, $3, $4)
-                                ON CONFLICT (name) DO UPDATE SET name = EXCLUDED.name
-                                RETURNING uuid
-                                "#,
-                            )
-                            .bind(backup_configuration_uuid)
-                            .bind(short_name)
-                            .bind(description)
-                            .bind(created)
-                            .fetch_one(database.write())
-                            .await?;
+                                mapping.insert(id, row.get("uuid"));
+                            }
 
-                            mapping.insert(id, row.get("uuid"));
+                            Ok(mapping)
+                        },
+                        128,
+                    )
+                    .await
+                    {
+                        Ok(location_mappings) => {
+                            Arc::new(mappings.finish("locations", latest, location_mappings).await?)
+                        }
+                        Err(err) => {
+                            tracing::error!("failed to process locations table: {:?}", err);
+                            std::process::exit(1);
                         }
-
-                        Ok(mapping)
-                    },
-                    128,
-                )
-                .await
-                {
-                    Ok(location_mappings) => Arc::new(location_mappings),
-                    Err(err) => {
-                        tracing::error!("failed to process locations table: {:?}", err);
-                        std::process::exit(1);
                     }
                 };
+                let (filter, latest) = mappings.begin(&source_database, "nodes", None).await?;
                 let node_mappings = match process_table(
                     &source_database,
                     "nodes",
-                    None,
+                    &filter,
                     async |rows| {
                         let mut mapping = HashMap::with_capacity(rows.len());
                         let mut futures = Vec::with_capacity(rows.len());
@@ -546,21 +779,39 @@ impl shared::extensions::commands::CliCommand<PterodactylArgs> for PterodactylCo
                                 let name: &str = row.try_get("name")?;
                                 let description: Option<&str> = row.try_get("description")?;
                                 let public: bool = row.try_get("public")?;
+                                // jexactyl only deploys onto nodes that are marked as deployable as well
+                                let public = if panel == SourcePanel::Jexactyl {
+                                    public && row.try_get("deployable").unwrap_or(true)
+                                } else {
+                                    public
+                                };
                                 let maintenance_mode: bool = row.try_get("maintenance_mode")?;
-                                let location_id: u32 = row.try_get("location_id")?;
                                 let fqdn: &str = row.try_get("fqdn")?;
                                 let scheme: &str = row.try_get("scheme")?;
                                 let memory: u64 = row.try_get("memory")?;
                                 let disk: u64 = row.try_get("disk")?;
+                                let memory_overallocate: i32 = row.try_get("memory_overallocate")?;
+                                let disk_overallocate: i32 = row.try_get("disk_overallocate")?;
                                 let token_id: &str = row.try_get("daemon_token_id")?;
                                 let token: &str = row.try_get("daemon_token")?;
-                                let daemon_listen: u16 = row.try_get("daemonListen")?;
-                                let daemon_sftp: u16 = row.try_get("daemonSFTP")?;
+                                let daemon_listen: u16 = row
+                                    .try_get("daemon_connect")
+                                    .or_else(|_| row.try_get("daemon_listen"))
+                                    .or_else(|_| row.try_get("daemonListen"))?;
+                                let daemon_sftp: u16 = row
+                                    .try_get("daemon_sftp")
+                                    .or_else(|_| row.try_get("daemonSFTP"))?;
                                 let created: chrono::DateTime<chrono::Utc> = row.try_get("created_at")?;
 
-                                let location_uuid = match location_mappings.iter().find(|m| m.contains_key(&location_id)) {
-                                    Some(location_uuid) => location_uuid.get(&location_id).unwrap(),
-                                    None => return Ok(()),
+                                let location_uuid = match row.try_get::<u32, _>("location_id") {
+                                    Ok(location_id) => match location_mappings.iter().find(|m| m.contains_key(&location_id)) {
+                                        Some(location_uuid) => *location_uuid.get(&location_id).unwrap(),
+                                        None => return Ok(()),
+                                    },
+                                    Err(_) => match default_location_uuid {
+                                        Some(location_uuid) => location_uuid,
+                                        None => return Ok(()),
+                                    },
                                 };
 
                                 let token = match decrypt_laravel_value(token, &source_app_key) {
@@ -575,9 +826,19 @@ impl shared::extensions::commands::CliCommand<PterodactylArgs> for PterodactylCo
 
                                 sqlx::query(
                                     r#"
-                                    INSERT INTO nodes (uuid, name, description, deployment_enabled, maintenance_enabled, location_uuid, url, sftp_port, memory, disk, token_id, token, created)
-                                    VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13)
-                                    ON CONFLICT DO NOTHING
+                                    INSERT INTO nodes (uuid, name, description, deployment_enabled, maintenance_enabled, location_uuid, url, sftp_port, memory, disk, memory_overallocation, disk_overallocation, token_id, token, created)
+                                    VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15)
+                                    ON CONFLICT (uuid) DO UPDATE SET
+                                        name = EXCLUDED.name,
+                                        description = EXCLUDED.description,
+                                        deployment_enabled = EXCLUDED.deployment_enabled,
+                                        maintenance_enabled = EXCLUDED.maintenance_enabled,
+                                        url = EXCLUDED.url,
+                                        sftp_port = EXCLUDED.sftp_port,
+                                        memory = EXCLUDED.memory,
+                                        disk = EXCLUDED.disk,
+                                        memory_overallocation = EXCLUDED.memory_overallocation,
+                                        disk_overallocation = EXCLUDED.disk_overallocation
                                     "#
                                 )
                                 .bind(uuid.as_uuid())
@@ -590,6 +851,9 @@ impl shared::extensions::commands::CliCommand<PterodactylArgs> for PterodactylCo
                                 .bind(daemon_sftp as i32)
                                 .bind(memory as i64)
                                 .bind(disk as i64)
+                                // negative values disable the limit in both panels
+                                .bind(memory_overallocate.clamp(-1, i16::MAX as i32) as i16)
+                                .bind(disk_overallocate.clamp(-1, i16::MAX as i32) as i16)
                                 .bind(token_id)
                                 .bind(database.encrypt(token).await.unwrap())
                                 .bind(created)
@@ -608,7 +872,7 @@ impl shared::extensions::commands::CliCommand<PterodactylArgs> for PterodactylCo
                 )
                 .await
                 {
-                    Ok(node_mappings) => node_mappings,
+                    Ok(node_mappings) => mappings.finish("nodes", latest, node_mappings).await?,
                     Err(err) => {
                         tracing::error!("failed to process nodes table: {:?}", err);
                         std::process::exit(1);
@@ -616,74 +880,103 @@ impl shared::extensions::commands::CliCommand<PterodactylArgs> for PterodactylCo
                 };
                 drop(location_mappings);
 
-                let nest_mappings = match process_table(
-                    &source_database,
-                    "nests",
-                    None,
-                    async |rows| {
-                        let mut mapping = HashMap::with_capacity(rows.len());
+                // pelican has no nests, its eggs are all put into a single generated one
+                let default_nest_uuid: Option<uuid::Uuid> = if panel == SourcePanel::Pelican {
+                    let row = sqlx::query(
+                        r#"
+                        INSERT INTO nests (author, name, description)
+                        VALUES ($1, $2, $3)
+                        ON CONFLICT (name) DO UPDATE SET name = EXCLUDED.name
+                        RETURNING uuid
+                        "#,
+                    )
+                    .bind("import")
+                    .bind("Pelican")
+                    .bind("automatically generated by import")
+                    .fetch_one(database.write())
+                    .await?;
 
-                        for row in rows {
-                            let id: u32 = row.try_get("id")?;
-                            let uuid: uuid::fmt::Hyphenated = row.try_get("uuid")?;
-                            let author: &str = row.try_get("author")?;
-                            let name: &str = row.try_get("name")?;
-                            let description: Option<&str> = row.try_get("description")?;
-                            let created: chrono::DateTime<chrono::Utc> =
-                                row.try_get("created_at")?;
+                    Some(row.get("uuid"))
+                } else {
+                    None
+                };
 
-                            sqlx::query(
-                                r#"
-                                INSERT INTO nests (uuid, author, name, description, created)
-                                VALUES ($1, $2, $3, $4, $5)
-                                ON CONFLICT DO NOTHING
-                                "#,
-                            )
-                            .bind(uuid.as_uuid())
-                            .bind(author)
-                            .bind(name)
-                            .bind(description)
-                            .bind(created)
-                            .execute(database.write())
-                            .await?;
+                let nest_mappings = if panel == SourcePanel::Pelican {
+                    Vec::new()
+                } else {
+                    let (filter, latest) = mappings.begin(&source_database, "nests", None).await?;
 
-                            mapping.insert(id, *uuid.as_uuid());
-                        }
+                    match process_table(
+                        &source_database,
+                        "nests",
+                        &filter,
+                        async |rows| {
+                            let mut mapping = HashMap::with_capacity(rows.len());
 
-                        Ok(mapping)
-                    },
-                    256,
-                )
-                .await
-                {
-                    Ok(nest_mappings) => nest_mappings,
-                    Err(err) => {
-                        tracing::error!("failed to process nests table: {:?}", err);
-                        std::process::exit(1);
+                            for row in rows {
+                                let id: u32 = row.try_get("id")?;
+                                let uuid: uuid::fmt::Hyphenated = row.try_get("uuid")?;
+                                let author: &str = row.try_get("author")?;
+                                let name: &str = row.try_get("name")?;
+                                let description: Option<&str> = row.try_get("description")?;
+                                let created: chrono::DateTime<chrono::Utc> =
+                                    row.try_get("created_at")?;
+
+                                sqlx::query(
+                                    r#"
+                                    INSERT INTO nests (uuid, author, name, description, created)
+                                    VALUES ($1, $2, $3, $4, $5)
+                                    ON CONFLICT (uuid) DO UPDATE SET
+                                        author = EXCLUDED.author,
+                                        name = EXCLUDED.name,
+                                        description = EXCLUDED.description
+                                    "#,
+                                )
+                                .bind(uuid.as_uuid())
+                                .bind(author)
+                                .bind(name)
+                                .bind(description)
+                                .bind(created)
+                                .execute(database.write())
+                                .await?;
+
+                                mapping.insert(id, *uuid.as_uuid());
+                            }
+
+                            Ok(mapping)
+                        },
+                        256,
+                    )
+                    .await
+                    {
+                        Ok(nest_mappings) => {
+                            mappings.finish("nests", latest, nest_mappings).await?
+                        }
+                        Err(err) => {
+                            tracing::error!("failed to process nests table: {:?}", err);
+                            std::process::exit(1);
+                        }
                     }
                 };
+                let (filter, latest) = mappings.begin(&source_database, "eggs", None).await?;
                 let egg_mappings = match process_table(
                     &source_database,
                     "eggs",
-                    None,
+                    &filter,
                     async |rows| {
                         let mut mapping = HashMap::with_capacity(rows.len());
 
                         for row in rows {
                             let id: u32 = row.try_get("id")?;
                             let uuid: uuid::fmt::Hyphenated = row.try_get("uuid")?;
-                            let nest_id: u32 = row.try_get("nest_id")?;
                             let author: &str = row.try_get("author")?;
                             let name: &str = row.try_get("name")?;
                             let description: Option<&str> = row.try_get("description")?;
                             let features: Option<serde_json::Value> = row.try_get("features")?;
                             let docker_images: serde_json::Value = row.try_get("docker_images")?;
-                            let file_denylist: Option<serde_json::Value> =
-                                row.try_get("file_denylist")?;
-                            let config_files: Option<serde_json::Value> =
-                                row.try_get("config_files")?;
-                            let config_startup: Option<serde_json::Value> =
-                                row.try_get("config_startup")?;
+                            let file_denylist: Option<serde_json::Value> = row.try_get("file_denylist")?;
+                            let config_files: Option<serde_json::Value> = row.try_get("config_files")?;
+                            let config_startup: Option<serde_json::Value> = row.try_get("config_startup")?;
                             let config_stop: Option<compact_str::CompactString> = row.try_get("config_stop")?;
                             let config_script = shared::models::nest_egg::NestEggConfigScript {
                                 container: row.try_get("script_container")?,
@@ -692,14 +985,18 @@ impl shared::extensions::commands::CliCommand<PterodactylArgs> for PterodactylCo
                             };
                             let startup: &str = row.try_get("startup")?;
                             let force_outgoing_ip: bool = row.try_get("force_outgoing_ip")?;
-                            let created: chrono::DateTime<chrono::Utc> =
-                                row.try_get("created_at")?;
+                            let created: chrono::DateTime<chrono::Utc> = row.try_get("created_at")?;
 
-                            let nest_uuid =
-                                match nest_mappings.iter().find(|m| m.contains_key(&nest_id)) {
-                                    Some(nest_uuid) => nest_uuid.get(&nest_id).unwrap(),
+                            let nest_uuid = match row.try_get::<u32, _>("nest_id") {
+                                Ok(nest_id) => match nest_mappings.iter().find(|m| m.contains_key(&nest_id)) {
+                                    Some(nest_uuid) => *nest_uuid.get(&nest_id).unwrap(),
                                     None => continue,
-                                };
+                                },
+                                Err(_) => match default_nest_uuid {
+                                    Some(nest_uuid) => nest_uuid,
+                                    None => continue,
+                                },
+                            };
 
                             let features: Vec<String> = serde_json::from_value(
                                 features.unwrap_or_else(|| serde_json::Value::Array(vec![])),
@@ -710,33 +1007,30 @@ impl shared::extensions::commands::CliCommand<PterodactylArgs> for PterodactylCo
                             )
                             .unwrap_or_default();
 
-                            let config_files: Vec<
-                                shared::models::nest_egg::ProcessConfigurationFile,
-                            > = serde_json::from_value(config_files.unwrap_or_default())
-                                .unwrap_or_default();
+                            let config_files: Vec<shared::models::nest_egg::ProcessConfigurationFile> =
+                                serde_json::from_value(config_files.unwrap_or_default()).unwrap_or_default();
                             let mut config_startup: shared::models::nest_egg::NestEggConfigStartup =
-                                serde_json::from_value(config_startup.unwrap_or_default())
-                                    .unwrap_or_default();
+                                serde_json::from_value(config_startup.unwrap_or_default()).unwrap_or_default();
                             let config_stop: shared::models::nest_egg::NestEggConfigStop =
-                                serde_json::from_str(config_stop.as_deref().unwrap_or(""))
-                                    .unwrap_or_else(|_| {
-                                        shared::models::nest_egg::NestEggConfigStop {
-                                            r#type: if config_stop.as_deref() == Some("^C") || config_stop.as_deref() == Some("^^C") {
-                                                "signal".into()
-                                            } else {
-                                                "command".into()
-                                            },
-                                            value: match config_stop.as_deref() {
-                                                Some("^C") => Some("SIGINT".into()),
-                                                Some("^^C") => Some("SIGKILL".into()),
-                                                _ => config_stop,
-                                            },
-                                        }
-                                    });
-                            let config_allocations =
-                                shared::models::nest_egg::NestEggConfigAllocations {
-                                    user_self_assign: Default::default(),
-                                };
+                                serde_json::from_str(config_stop.as_deref().unwrap_or("")).unwrap_or_else(
+                                    |_| shared::models::nest_egg::NestEggConfigStop {
+                                        r#type: if config_stop.as_deref() == Some("^C")
+                                            || config_stop.as_deref() == Some("^^C")
+                                        {
+                                            "signal".into()
+                                        } else {
+                                            "command".into()
+                                        },
+                                        value: match config_stop.as_deref() {
+                                            Some("^C") => Some("SIGINT".into()),
+                                            Some("^^C") => Some("SIGKILL".into()),
+                                            _ => config_stop,
+                                        },
+                                    },
+                                );
+                            let config_allocations = shared::models::nest_egg::NestEggConfigAllocations {
+                                user_self_assign: Default::default(),
+                            };
 
                             if config_startup.done.is_empty() {
                                 config_startup.done.push("".into());
@@ -750,7 +1044,19 @@ impl shared::extensions::commands::CliCommand<PterodactylArgs> for PterodactylCo
                                     config_script, config_allocations, startup, force_outgoing_ip, created
                                 )
                                 VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16)
-                                ON CONFLICT DO NOTHING
+                                ON CONFLICT (uuid) DO UPDATE SET
+                                    author = EXCLUDED.author,
+                                    name = EXCLUDED.name,
+                                    description = EXCLUDED.description,
+                                    features = EXCLUDED.features,
+                                    docker_images = EXCLUDED.docker_images,
+                                    file_denylist = EXCLUDED.file_denylist,
+                                    config_files = EXCLUDED.config_files,
+                                    config_startup = EXCLUDED.config_startup,
+                                    config_stop = EXCLUDED.config_stop,
+                                    config_script = EXCLUDED.config_script,
+                                    startup = EXCLUDED.startup,
+                                    force_outgoing_ip = EXCLUDED.force_outgoing_ip
                                 "#,
                             )
                             .bind(uuid.as_uuid())
@@ -781,17 +1087,20 @@ impl shared::extensions::commands::CliCommand<PterodactylArgs> for PterodactylCo
                 )
                 .await
                 {
-                    Ok(egg_mappings) => egg_mappings,
+                    Ok(egg_mappings) => mappings.finish("eggs", latest, egg_mappings).await?,
                     Err(err) => {
                         tracing::error!("failed to process eggs table: {:?}", err);
                         std::process::exit(1);
                     }
                 };
                 drop(nest_mappings);
+                let (filter, latest) = mappings
+                    .begin(&source_database, "egg_variables", None)
+                    .await?;
                 let egg_variable_mappings = match process_table(
                     &source_database,
                     "egg_variables",
-                    None,
+                    &filter,
                     async |rows| {
                         let mut mapping: HashMap<u32, uuid::Uuid> = HashMap::with_capacity(rows.len());
 
@@ -804,7 +1113,14 @@ impl shared::extensions::commands::CliCommand<PterodactylArgs> for PterodactylCo
                             let default_value: Option<&str> = row.try_get("default_value")?;
                             let user_viewable: bool = row.try_get("user_viewable")?;
                             let user_editable: bool = row.try_get("user_editable")?;
-                            let rules: &str = row.try_get("rules")?;
+                            // pelican stores the rules as a json array instead of a pipe separated string
+                            let rules: Vec<compact_str::CompactString> = match row.try_get::<&str, _>("rules") {
+                                Ok(rules) if rules.starts_with('[') => {
+                                    serde_json::from_str(rules).unwrap_or_default()
+                                }
+                                Ok(rules) => rules.split('|').map(compact_str::CompactString::from).collect(),
+                                Err(_) => serde_json::from_value(row.try_get("rules")?).unwrap_or_default(),
+                            };
                             let created: chrono::DateTime<chrono::Utc> = row.try_get("created_at")?;
 
                             let egg_uuid = match egg_mappings.iter().find(|m| m.contains_key(&egg_id)) {
@@ -812,8 +1128,6 @@ impl shared::extensions::commands::CliCommand<PterodactylArgs> for PterodactylCo
                                 None => continue,
                             };
 
-                            let rules = rules.split('|').map(compact_str::CompactString::from).collect::<Vec<_>>();
-
                             if rule_validator::validate_rules(&rules).is_err() {
                                 continue;
                             }
@@ -822,7 +1136,13 @@ impl shared::extensions::commands::CliCommand<PterodactylArgs> for PterodactylCo
                                 r#"
                                 INSERT INTO nest_egg_variables (egg_uuid, name, description, env_variable, default_value, user_viewable, user_editable, rules, created)
                                 VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
-                                ON CONFLICT (egg_uuid, env_variable) DO UPDATE SET env_variable = EXCLUDED.env_variable
+                                ON CONFLICT (egg_uuid, env_variable) DO UPDATE SET
+                                    name = EXCLUDED.name,
+                                    description = EXCLUDED.description,
+                                    default_value = EXCLUDED.default_value,
+                                    user_viewable = EXCLUDED.user_viewable,
+                                    user_editable = EXCLUDED.user_editable,
+                                    rules = EXCLUDED.rules
                                 RETURNING uuid
                                 "#,
                             )
@@ -847,17 +1167,24 @@ impl shared::extensions::commands::CliCommand<PterodactylArgs> for PterodactylCo
                 )
                 .await
                 {
-                    Ok(egg_variable_mappings) => egg_variable_mappings,
+                    Ok(egg_variable_mappings) => {
+                        mappings
+                            .finish("egg_variables", latest, egg_variable_mappings)
+                            .await?
+                    }
                     Err(err) => {
                         tracing::error!("failed to process egg_variables table: {:?}", err);
                         std::process::exit(1);
                     }
                 };
 
+                let (filter, latest) = mappings
+                    .begin(&source_database, "database_hosts", None)
+                    .await?;
                 let database_host_mappings = match process_table(
                     &source_database,
                     "database_hosts",
-                    None,
+                    &filter,
                     async |rows| {
                         let mut mapping: HashMap<u32, uuid::Uuid> = HashMap::with_capacity(rows.len());
 
@@ -903,17 +1230,22 @@ impl shared::extensions::commands::CliCommand<PterodactylArgs> for PterodactylCo
                 )
                 .await
                 {
-                    Ok(nest_mappings) => nest_mappings,
+                    Ok(database_host_mappings) => {
+                        mappings
+                            .finish("database_hosts", latest, database_host_mappings)
+                            .await?
+                    }
                     Err(err) => {
                         tracing::error!("failed to process nests table: {:?}", err);
                         std::process::exit(1);
                     }
                 };
 
-                let server_mappings = match process_table(
+                let (filter, latest) = mappings.begin(&source_database, "servers", None).await?;
+                let changed_servers = match process_table(
                     &source_database,
                     "servers",
-                    None,
+                    &filter,
                     async |rows| {
                         let mut mapping = HashMap::with_capacity(rows.len());
                         let mut futures = Vec::with_capacity(rows.len());
@@ -949,6 +1281,17 @@ impl shared::extensions::commands::CliCommand<PterodactylArgs> for PterodactylCo
                                 let backup_limit: u32 = row.try_get("backup_limit")?;
                                 let created: chrono::DateTime<chrono::Utc> = row.try_get("created_at")?;
 
+                                // jexactyl counts down the days until a renewable server has to be renewed
+                                let expires_at = if panel == SourcePanel::Jexactyl
+                                    && row.try_get("renewable").unwrap_or(false)
+                                {
+                                    row.try_get::<i32, _>("renewal")
+                                        .ok()
+                                        .map(|days| chrono::Utc::now() + chrono::Duration::days(days as i64))
+                                } else {
+                                    None
+                                };
+
                                 let node_uuid = match node_mappings.iter().find(|m| m.contains_key(&node_id)) {
                                     Some(node_uuid) => node_uuid.get(&node_id).unwrap(),
                                     None => return Ok(()),
@@ -980,10 +1323,29 @@ impl shared::extensions::commands::CliCommand<PterodactylArgs> for PterodactylCo
                                     INSERT INTO servers (
                                         uuid, uuid_short, external_id, node_uuid, name, description, status, suspended,
                                         owner_uuid, memory, swap, disk, io_weight, cpu, pinned_cpus, allocation_limit,
-                                        database_limit, backup_limit, egg_uuid, startup, image, created
+                                        database_limit, backup_limit, egg_uuid, startup, image, expires_at, created
                                     )
-                                    VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18, $19, $20, $21, $22)
-                                    ON CONFLICT DO NOTHING
+                                    VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18, $19, $20, $21, $22, $23)
+                                    ON CONFLICT (uuid) DO UPDATE SET
+                                        external_id = EXCLUDED.external_id,
+                                        node_uuid = EXCLUDED.node_uuid,
+                                        name = EXCLUDED.name,
+                                        description = EXCLUDED.description,
+                                        status = EXCLUDED.status,
+                                        suspended = EXCLUDED.suspended,
+                                        owner_uuid = EXCLUDED.owner_uuid,
+                                        memory = EXCLUDED.memory,
+                                        swap = EXCLUDED.swap,
+                                        disk = EXCLUDED.disk,
+                                        io_weight = EXCLUDED.io_weight,
+                                        cpu = EXCLUDED.cpu,
+                                        allocation_limit = EXCLUDED.allocation_limit,
+                                        database_limit = EXCLUDED.database_limit,
+                                        backup_limit = EXCLUDED.backup_limit,
+                                        egg_uuid = EXCLUDED.egg_uuid,
+                                        startup = EXCLUDED.startup,
+                                        image = EXCLUDED.image,
+                                        expires_at = COALESCE(EXCLUDED.expires_at, servers.expires_at)
                                     "#,
                                 )
                                 .bind(uuid.as_uuid())
@@ -1007,6 +1369,7 @@ impl shared::extensions::commands::CliCommand<PterodactylArgs> for PterodactylCo
                                 .bind(egg_uuid)
                                 .bind(startup)
                                 .bind(image)
+                                .bind(expires_at)
                                 .bind(created)
                                 .execute(database.write())
                                 .await?;
@@ -1023,16 +1386,29 @@ impl shared::extensions::commands::CliCommand<PterodactylArgs> for PterodactylCo
                 )
                 .await
                 {
-                    Ok(nest_mappings) => nest_mappings,
+                    Ok(changed_servers) => changed_servers,
                     Err(err) => {
                         tracing::error!("failed to process servers table: {:?}", err);
                         std::process::exit(1);
                     }
                 };
+                let server_mappings = Arc::new(
+                    mappings
+                        .finish(
+                            "servers",
+                            latest,
+                            changed_servers
+                                .iter()
+                                .map(|m| m.iter().map(|(id, (uuid, _))| (*id, *uuid)).collect())
+                                .collect(),
+                        )
+                        .await?,
+                );
+                let (filter, latest) = mappings.begin(&source_database, "databases", None).await?;
                 if let Err(err) = process_table(
                     &source_database,
                     "databases",
-                    None,
+                    &filter,
                     async |rows| {
                         let mut futures = Vec::with_capacity(rows.len());
 
@@ -1050,7 +1426,7 @@ impl shared::extensions::commands::CliCommand<PterodactylArgs> for PterodactylCo
                                 let created: chrono::DateTime<chrono::Utc> = row.try_get("created_at")?;
 
                                 let server_uuid = match server_mappings.iter().find(|m| m.contains_key(&server_id)) {
-                                    Some(server_uuid) => server_uuid.get(&server_id).unwrap().0,
+                                    Some(server_uuid) => *server_uuid.get(&server_id).unwrap(),
                                     None => return Ok(()),
                                 };
 
@@ -1068,7 +1444,10 @@ impl shared::extensions::commands::CliCommand<PterodactylArgs> for PterodactylCo
                                     r#"
                                     INSERT INTO server_databases (server_uuid, database_host_uuid, name, username, password, created)
                                     VALUES ($1, $2, $3, $4, $5, $6)
-                                    ON CONFLICT DO NOTHING
+                                    ON CONFLICT (server_uuid, name) DO UPDATE SET
+                                        database_host_uuid = EXCLUDED.database_host_uuid,
+                                        username = EXCLUDED.username,
+                                        password = EXCLUDED.password
                                     "#,
                                 )
                                 .bind(server_uuid)
@@ -1095,23 +1474,37 @@ impl shared::extensions::commands::CliCommand<PterodactylArgs> for PterodactylCo
                     tracing::error!("failed to process databases table: {:?}", err);
                     std::process::exit(1);
                 }
+                mappings.checkpoint("databases", latest).await?;
+                let (filter, latest) = mappings
+                    .begin(&source_database, "server_variables", None)
+                    .await?;
+                let secret_variable_uuids: HashSet<uuid::Uuid> = sqlx::query_scalar(
+                    r#"
+                    SELECT nest_egg_variables.uuid
+                    FROM nest_egg_variables
+                    WHERE nest_egg_variables.secret = true
+                    "#,
+                )
+                .fetch_all(database.read())
+                .await?
+                .into_iter()
+                .collect();
                 if let Err(err) = process_table(
                     &source_database,
                     "server_variables",
-                    None,
+                    &filter,
                     async |rows| {
                         for row in rows {
                             let server_id: u32 = row.try_get("server_id")?;
                             let variable_id: u32 = row.try_get("variable_id")?;
                             let variable_value: Option<&str> = row.try_get("variable_value")?;
-                            let created: Option<chrono::DateTime<chrono::Utc>> =
-                                row.try_get("created_at")?;
+                            let created: Option<chrono::DateTime<chrono::Utc>> = row.try_get("created_at")?;
 
-                            let server_uuid =
-                                match server_mappings.iter().find(|m| m.contains_key(&server_id)) {
-                                    Some(server_uuid) => server_uuid.get(&server_id).unwrap().0,
-                                    None => continue,
-                                };
+                            let server_uuid = match server_mappings.iter().find(|m| m.contains_key(&server_id))
+                            {
+                                Some(server_uuid) => *server_uuid.get(&server_id).unwrap(),
+                                None => continue,
+                            };
 
                             let variable_uuid = match egg_variable_mappings
                                 .iter()
@@ -1121,16 +1514,31 @@ impl shared::extensions::commands::CliCommand<PterodactylArgs> for PterodactylCo
                                 None => continue,
                             };
 
+                            let (variable_value, value_encrypted) =
+                                if secret_variable_uuids.contains(variable_uuid) {
+                                    (
+                                        Some(""),
+                                        Some(
+                                            database
+                                                .encrypt(variable_value.unwrap_or_default().to_string())
+                                                .await?,
+                                        ),
+                                    )
+                                } else {
+                                    (variable_value, None)
+                                };
+
                             sqlx::query(
                                 r#"
-                                INSERT INTO server_variables (server_uuid, variable_uuid, value, created)
-                                VALUES ($1, $2, $3, $4)
-                                ON CONFLICT DO NOTHING
+                                INSERT INTO server_variables (server_uuid, variable_uuid, value, value_encrypted, created)
+                                VALUES ($1, $2, $3, $4, $5)
+                                ON CONFLICT (server_uuid, variable_uuid) DO UPDATE SET value = EXCLUDED.value, value_encrypted = EXCLUDED.value_encrypted
                                 "#,
                             )
                             .bind(server_uuid)
                             .bind(variable_uuid)
                             .bind(variable_value)
+                            .bind(value_encrypted)
                             .bind(created.unwrap_or_else(chrono::Utc::now))
                             .execute(database.write())
                             .await?;
@@ -1145,10 +1553,14 @@ impl shared::extensions::commands::CliCommand<PterodactylArgs> for PterodactylCo
                     tracing::error!("failed to process databases table: {:?}", err);
                     std::process::exit(1);
                 }
+                mappings.checkpoint("server_variables", latest).await?;
+                shared::models::server_variable::ServerVariable::sync_encryption(&database, None)
+                    .await?;
+                let (filter, latest) = mappings.begin(&source_database, "backups", None).await?;
                 if let Err(err) = process_table(
                     &source_database,
                     "backups",
-                    None,
+                    &filter,
                     async |rows| {
                         let mut futures = Vec::with_capacity(rows.len());
 
@@ -1170,7 +1582,7 @@ impl shared::extensions::commands::CliCommand<PterodactylArgs> for PterodactylCo
                                 let deleted: Option<chrono::DateTime<chrono::Utc>> = row.try_get("deleted_at")?;
 
                                 let server_uuid = match server_mappings.iter().find(|m| m.contains_key(&server_id)) {
-                                    Some(server_uuid) => server_uuid.get(&server_id).unwrap().0,
+                                    Some(server_uuid) => *server_uuid.get(&server_id).unwrap(),
                                     None => return Ok(()),
                                 };
 
@@ -1183,7 +1595,14 @@ impl shared::extensions::commands::CliCommand<PterodactylArgs> for PterodactylCo
                                     r#"
                                     INSERT INTO server_backups (uuid, server_uuid, node_uuid, backup_configuration_uuid, name, successful, browsable, streaming, locked, ignored_files, disk, checksum, bytes, completed, deleted, created)
                                     VALUES ($1, $2, (SELECT node_uuid FROM servers WHERE uuid = $2), $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15)
-                                    ON CONFLICT DO NOTHING
+                                    ON CONFLICT (uuid) DO UPDATE SET
+                                        name = EXCLUDED.name,
+                                        successful = EXCLUDED.successful,
+                                        locked = EXCLUDED.locked,
+                                        checksum = EXCLUDED.checksum,
+                                        bytes = EXCLUDED.bytes,
+                                        completed = EXCLUDED.completed,
+                                        deleted = EXCLUDED.deleted
                                     "#,
                                 )
                                 .bind(uuid.as_uuid())
@@ -1227,10 +1646,12 @@ impl shared::extensions::commands::CliCommand<PterodactylArgs> for PterodactylCo
                     tracing::error!("failed to process backups table: {:?}", err);
                     std::process::exit(1);
                 }
+                mappings.checkpoint("backups", latest).await?;
+                let (filter, latest) = mappings.begin(&source_database, "subusers", None).await?;
                 if let Err(err) = process_table(
                     &source_database,
                     "subusers",
-                    None,
+                    &filter,
                     async |rows| {
                         let mut futures = Vec::with_capacity(rows.len());
 
@@ -1251,72 +1672,24 @@ impl shared::extensions::commands::CliCommand<PterodactylArgs> for PterodactylCo
 
                                 let server_uuid = match server_mappings.iter().find(|m| m.contains_key(&server_id))
                                 {
-                                    Some(server_uuid) => server_uuid.get(&server_id).unwrap().0,
+                                    Some(server_uuid) => *server_uuid.get(&server_id).unwrap(),
                                     None => return Ok(()),
                                 };
 
-                                let raw_permissions: Vec<String> =
-                                    serde_json::from_value(permissions).unwrap_or_default();
-                                let mut permissions = HashSet::with_capacity(raw_permissions.len());
-
-                                for permission in raw_permissions {
-                                    permissions.insert(match permission.as_str() {
-                                        "control.console" => "control.console",
-                                        "control.start" => "control.start",
-                                        "control.stop" => "control.stop",
-                                        "control.restart" => "control.restart",
-                                        "user.create" => "subusers.create",
-                                        "user.read" => "subusers.read",
-                                        "user.update" => "subusers.update",
-                                        "user.delete" => "subusers.delete",
-                                        "file.create" => "files.create",
-                                        "file.read" => "files.read",
-                                        "file.read-content" => "files.read-content",
-                                        "file.update" => "files.update",
-                                        "file.delete" => "files.delete",
-                                        "file.archive" => "files.archive",
-                                        "backup.create" => "backups.create",
-                                        "backup.read" => "backups.read",
-                                        "backup.download" => "backups.download",
-                                        "backup.restore" => "backups.restore",
-                                        "backup.delete" => "backups.delete",
-                                        "allocation.create" => "allocations.create",
-                                        "allocation.read" => "allocations.read",
-                                        "allocation.update" => "allocations.update",
-                                        "allocation.delete" => "allocations.delete",
-                                        "startup.read" => "startup.read",
-                                        "startup.update" => "startup.update",
-                                        "startup.docker-image" => "startup.docker-image",
-                                        "database.create" => "databases.create",
-                                        "database.read" => "databases.read",
-                                        "database.update" => "databases.update",
-                                        "database.view_password" => "databases.read-password",
-                                        "database.delete" => "databases.delete",
-                                        "schedule.create" => "schedules.create",
-                                        "schedule.read" => "schedules.read",
-                                        "schedule.update" => "schedules.update",
-                                        "schedule.delete" => "schedules.delete",
-                                        "settings.rename" => "settings.rename",
-                                        "settings.reinstall" => "settings.install",
-                                        "activity.read" => "activity.read",
-                                        _ => continue,
-                                    });
-
-                                    if permission == "control.console" {
-                                        permissions.insert("control.read-console");
-                                    }
-                                }
+                                let permissions = convert_subuser_permissions(
+                                    serde_json::from_value(permissions).unwrap_or_default(),
+                                );
 
                                 sqlx::query(
                                     r#"
                                     INSERT INTO server_subusers (server_uuid, user_uuid, permissions, ignored_files, created)
                                     VALUES ($1, $2, $3, $4, $5)
-                                    ON CONFLICT DO NOTHING
+                                    ON CONFLICT (server_uuid, user_uuid) DO UPDATE SET permissions = EXCLUDED.permissions
                                     "#,
                                 )
                                 .bind(server_uuid)
                                 .bind(user_uuid)
-                                .bind(permissions.into_iter().collect::<Vec<&str>>())
+                                .bind(permissions)
                                 .bind(&[] as &[&str])
                                 .bind(created)
                                 .execute(database.write())
@@ -1337,12 +1710,13 @@ impl shared::extensions::commands::CliCommand<PterodactylArgs> for PterodactylCo
                     tracing::error!("failed to process subusers table: {:?}", err);
                     std::process::exit(1);
                 }
+                mappings.checkpoint("subusers", latest).await?;
                 drop(user_mappings);
 
                 let mount_mappings = match process_table(
                     &source_database,
                     "mounts",
-                    None,
+                    &TableFilter::default(),
                     async |rows| {
                         let mut mapping = HashMap::with_capacity(rows.len());
 
@@ -1354,13 +1728,19 @@ impl shared::extensions::commands::CliCommand<PterodactylArgs> for PterodactylCo
                             let source: &str = row.try_get("source")?;
                             let target: &str = row.try_get("target")?;
                             let read_only: bool = row.try_get("read_only")?;
-                            let user_mountable: bool = row.try_get("user_mountable")?;
+                            let user_mountable: bool = row.try_get("user_mountable").unwrap_or_default();
 
                             sqlx::query(
                                 r#"
                                 INSERT INTO mounts (uuid, name, description, source, target, read_only, user_mountable, created)
                                 VALUES ($1, $2, $3, $4, $5, $6, $7, NOW())
-                                ON CONFLICT DO NOTHING
+                                ON CONFLICT (uuid) DO UPDATE SET
+                                    name = EXCLUDED.name,
+                                    description = EXCLUDED.description,
+                                    source = EXCLUDED.source,
+                                    target = EXCLUDED.target,
+                                    read_only = EXCLUDED.read_only,
+                                    user_mountable = EXCLUDED.user_mountable
                                 "#,
                             )
                             .bind(uuid.as_uuid())
@@ -1388,10 +1768,25 @@ impl shared::extensions::commands::CliCommand<PterodactylArgs> for PterodactylCo
                         std::process::exit(1);
                     }
                 };
+
+                // the links of imported mounts mirror the source panel, so links removed there are removed here
+                let imported_mounts = mount_mappings
+                    .iter()
+                    .flat_map(|m| m.values().copied())
+                    .collect::<Vec<_>>();
+                for table in ["nest_egg_mounts", "node_mounts", "server_mounts"] {
+                    sqlx::query(&format!(
+                        "DELETE FROM {table} WHERE {table}.mount_uuid = ANY($1)"
+                    ))
+                    .bind(&imported_mounts)
+                    .execute(database.write())
+                    .await?;
+                }
+
                 if let Err(err) = process_table(
                     &source_database,
                     "egg_mount",
-                    None,
+                    &TableFilter::default(),
                     async |rows| {
                         for row in rows {
                             let egg_id: u32 = row.try_get("egg_id")?;
@@ -1413,7 +1808,6 @@ impl shared::extensions::commands::CliCommand<PterodactylArgs> for PterodactylCo
                                 r#"
                                 INSERT INTO nest_egg_mounts (egg_uuid, mount_uuid, created)
                                 VALUES ($1, $2, NOW())
-                                ON CONFLICT DO NOTHING
                                 "#,
                             )
                             .bind(egg_uuid)
@@ -1434,7 +1828,7 @@ impl shared::extensions::commands::CliCommand<PterodactylArgs> for PterodactylCo
                 if let Err(err) = process_table(
                     &source_database,
                     "mount_node",
-                    None,
+                    &TableFilter::default(),
                     async |rows| {
                         for row in rows {
                             let node_id: u32 = row.try_get("node_id")?;
@@ -1456,7 +1850,6 @@ impl shared::extensions::commands::CliCommand<PterodactylArgs> for PterodactylCo
                                 r#"
                                 INSERT INTO node_mounts (node_uuid, mount_uuid, created)
                                 VALUES ($1, $2, NOW())
-                                ON CONFLICT DO NOTHING
                                 "#,
                             )
                             .bind(node_uuid)
@@ -1477,7 +1870,7 @@ impl shared::extensions::commands::CliCommand<PterodactylArgs> for PterodactylCo
                 if let Err(err) = process_table(
                     &source_database,
                     "mount_server",
-                    None,
+                    &TableFilter::default(),
                     async |rows| {
                         for row in rows {
                             let server_id: u32 = row.try_get("server_id")?;
@@ -1485,7 +1878,7 @@ impl shared::extensions::commands::CliCommand<PterodactylArgs> for PterodactylCo
 
                             let server_uuid =
                                 match server_mappings.iter().find(|m| m.contains_key(&server_id)) {
-                                    Some(server_uuid) => server_uuid.get(&server_id).unwrap().0,
+                                    Some(server_uuid) => *server_uuid.get(&server_id).unwrap(),
                                     None => continue,
                                 };
 
@@ -1499,7 +1892,6 @@ impl shared::extensions::commands::CliCommand<PterodactylArgs> for PterodactylCo
                                 r#"
                                 INSERT INTO server_mounts (server_uuid, mount_uuid, created)
                                 VALUES ($1, $2, NOW())
-                                ON CONFLICT DO NOTHING
                                 "#,
                             )
                             .bind(server_uuid)
@@ -1519,10 +1911,11 @@ impl shared::extensions::commands::CliCommand<PterodactylArgs> for PterodactylCo
                 }
                 drop(mount_mappings);
 
+                let (filter, latest) = mappings.begin(&source_database, "schedules", None).await?;
                 let schedule_mappings = match process_table(
                     &source_database,
                     "schedules",
-                    None,
+                    &filter,
                     async |rows| {
                         let mut mapping: HashMap<u32, uuid::Uuid> = HashMap::with_capacity(rows.len());
 
@@ -1541,7 +1934,7 @@ impl shared::extensions::commands::CliCommand<PterodactylArgs> for PterodactylCo
                             let created: chrono::DateTime<chrono::Utc> = row.try_get("created_at")?;
 
                             let server_uuid = match server_mappings.iter().find(|m| m.contains_key(&server_id)) {
-                                Some(server_uuid) => server_uuid.get(&server_id).unwrap().0,
+                                Some(server_uuid) => *server_uuid.get(&server_id).unwrap(),
                                 None => continue,
                             };
 
@@ -1557,7 +1950,11 @@ impl shared::extensions::commands::CliCommand<PterodactylArgs> for PterodactylCo
                                 r#"
                                 INSERT INTO server_schedules (server_uuid, name, enabled, triggers, condition, last_run, created)
                                 VALUES ($1, $2, $3, $4, $5, $6, $7)
-                                ON CONFLICT (server_uuid, name) DO UPDATE SET name = EXCLUDED.name
+                                ON CONFLICT (server_uuid, name) DO UPDATE SET
+                                    enabled = EXCLUDED.enabled,
+                                    triggers = EXCLUDED.triggers,
+                                    condition = EXCLUDED.condition,
+                                    last_run = EXCLUDED.last_run
                                 RETURNING uuid
                                 "#,
                             )
@@ -1601,10 +1998,52 @@ impl shared::extensions::commands::CliCommand<PterodactylArgs> for PterodactylCo
                         std::process::exit(1);
                     }
                 };
+                let schedule_mappings = mappings
+                    .finish("schedules", latest, schedule_mappings)
+                    .await?;
+
+                let (filter, latest) = mappings.begin(&source_database, "tasks", None).await?;
+                let changed_schedules: Vec<uuid::Uuid> = match &filter.sql_where {
+                    Some(sql_where) => {
+                        let query =
+                            format!("SELECT DISTINCT schedule_id FROM tasks WHERE {sql_where}");
+                        let mut query = sqlx::query(&query);
+                        if let Some(checkpoint) = filter.checkpoint {
+                            query = query.bind(checkpoint);
+                        }
+
+                        query
+                            .fetch_all(&source_database)
+                            .await?
+                            .into_iter()
+                            .filter_map(|row| row.try_get::<u32, _>("schedule_id").ok())
+                            .filter_map(|id| {
+                                schedule_mappings.iter().find_map(|m| m.get(&id).copied())
+                            })
+                            .collect()
+                    }
+                    None => schedule_mappings
+                        .iter()
+                        .flat_map(|m| m.values().copied())
+                        .collect(),
+                };
+                let filter = TableFilter {
+                    sql_where: filter.sql_where.map(|sql_where| {
+                        format!("schedule_id IN (SELECT schedule_id FROM tasks WHERE {sql_where})")
+                    }),
+                    checkpoint: filter.checkpoint,
+                };
+
+                // tasks are re-imported per schedule, so steps of changed schedules are replaced as a whole
+                sqlx::query("DELETE FROM server_schedule_steps WHERE schedule_uuid = ANY($1)")
+                    .bind(&changed_schedules)
+                    .execute(database.write())
+                    .await?;
+
                 if let Err(err) = process_table(
                     &source_database,
                     "tasks",
-                    None,
+                    &filter,
                     async |rows| {
                         for row in rows {
                             let schedule_id: u32 = row.try_get("schedule_id")?;
@@ -1613,8 +2052,7 @@ impl shared::extensions::commands::CliCommand<PterodactylArgs> for PterodactylCo
                             let payload: &str = row.try_get("payload")?;
                             let time_offset: u32 = row.try_get("time_offset")?;
                             let continue_on_failure: bool = row.try_get("continue_on_failure")?;
-                            let created: chrono::DateTime<chrono::Utc> =
-                                row.try_get("created_at")?;
+                            let created: chrono::DateTime<chrono::Utc> = row.try_get("created_at")?;
 
                             let schedule_uuid = match schedule_mappings
                                 .iter()
@@ -1634,12 +2072,10 @@ impl shared::extensions::commands::CliCommand<PterodactylArgs> for PterodactylCo
                             }
 
                             match action {
-                                "command" => {
-                                    actions.push(wings_api::ScheduleActionInner::SendCommand {
-                                        command: wings_api::ScheduleDynamicParameter::Raw(payload.into()),
-                                        ignore_failure: continue_on_failure,
-                                    })
-                                }
+                                "command" => actions.push(wings_api::ScheduleActionInner::SendCommand {
+                                    command: wings_api::ScheduleDynamicParameter::Raw(payload.into()),
+                                    ignore_failure: continue_on_failure,
+                                }),
                                 "power" => {
                                     let power_action = match payload {
                                         "start" => wings_api::ServerPowerAction::Start,
@@ -1654,17 +2090,15 @@ impl shared::extensions::commands::CliCommand<PterodactylArgs> for PterodactylCo
                                         ignore_failure: continue_on_failure,
                                     });
                                 }
-                                "backup" => {
-                                    actions.push(wings_api::ScheduleActionInner::CreateBackup {
-                                        name: None,
-                                        ignored_files: payload
-                                            .split('\n')
-                                            .map(compact_str::CompactString::from)
-                                            .collect::<Vec<_>>(),
-                                        foreground: true,
-                                        ignore_failure: continue_on_failure,
-                                    })
-                                }
+                                "backup" => actions.push(wings_api::ScheduleActionInner::CreateBackup {
+                                    name: None,
+                                    ignored_files: payload
+                                        .split('\n')
+                                        .map(compact_str::CompactString::from)
+                                        .collect::<Vec<_>>(),
+                                    foreground: true,
+                                    ignore_failure: continue_on_failure,
+                                }),
                                 _ => continue,
                             }
 
@@ -1673,7 +2107,6 @@ impl shared::extensions::commands::CliCommand<PterodactylArgs> for PterodactylCo
                                     r#"
                                     INSERT INTO server_schedule_steps (schedule_uuid, action, order_, created)
                                     VALUES ($1, $2, $3, $4)
-                                    ON CONFLICT DO NOTHING
                                     "#,
                                 )
                                 .bind(schedule_uuid)
@@ -1694,13 +2127,19 @@ impl shared::extensions::commands::CliCommand<PterodactylArgs> for PterodactylCo
                     tracing::error!("failed to process schedule tasks table: {:?}", err);
                     std::process::exit(1);
                 }
+                mappings.checkpoint("tasks", latest).await?;
                 drop(schedule_mappings);
 
-                if let Err(err) = process_table(
+                let (filter, latest) = mappings
+                    .begin(&source_database, "allocations", None)
+                    .await?;
+                let allocation_mappings = match process_table(
                     &source_database,
                     "allocations",
-                    None,
+                    &filter,
                     async |rows| {
+                        let mut mapping: HashMap<u32, uuid::Uuid> = HashMap::with_capacity(rows.len());
+
                         for row in rows {
                             let id: u32 = row.try_get("id")?;
                             let node_id: u32 = row.try_get("node_id")?;
@@ -1738,7 +2177,7 @@ impl shared::extensions::commands::CliCommand<PterodactylArgs> for PterodactylCo
                                 r#"
                                 INSERT INTO node_allocations (node_uuid, ip, ip_alias, port, created)
                                 VALUES ($1, $2, $3, $4, NOW())
-                                ON CONFLICT (node_uuid, host(ip), port) DO UPDATE SET port = EXCLUDED.port
+                                ON CONFLICT (node_uuid, host(ip), port) DO UPDATE SET ip_alias = EXCLUDED.ip_alias
                                 RETURNING uuid
                                 "#,
                             )
@@ -1749,12 +2188,14 @@ impl shared::extensions::commands::CliCommand<PterodactylArgs> for PterodactylCo
                             .fetch_one(database.write())
                             .await?;
 
-                            if let Some((server_uuid, allocation_id)) = server_uuid {
+                            if let Some(server_uuid) = server_uuid {
                                 let row = sqlx::query(
                                     r#"
                                     INSERT INTO server_allocations (server_uuid, allocation_uuid, notes, created)
                                     VALUES ($1, $2, $3, $4)
-                                    ON CONFLICT (allocation_uuid) DO UPDATE SET allocation_uuid = EXCLUDED.allocation_uuid
+                                    ON CONFLICT (allocation_uuid) DO UPDATE SET
+                                        server_uuid = EXCLUDED.server_uuid,
+                                        notes = EXCLUDED.notes
                                     RETURNING uuid
                                     "#,
                                 )
@@ -1765,37 +2206,64 @@ impl shared::extensions::commands::CliCommand<PterodactylArgs> for PterodactylCo
                                 .fetch_one(database.write())
                                 .await?;
 
-                                if id == *allocation_id {
-                                    sqlx::query(
-                                        r#"
-                                        UPDATE servers
-                                        SET allocation_uuid = $1
-                                        WHERE servers.uuid = $2
-                                        "#,
-                                    )
+                                mapping.insert(id, row.get("uuid"));
+                            } else {
+                                sqlx::query("DELETE FROM server_allocations WHERE allocation_uuid = $1")
                                     .bind(row.get::<uuid::Uuid, _>("uuid"))
-                                    .bind(server_uuid)
                                     .execute(database.write())
                                     .await?;
-                                }
                             }
                         }
 
-                        Ok(())
+                        Ok(mapping)
                     },
                     100,
                 )
                 .await
                 {
-                    tracing::error!("failed to process allocations table: {:?}", err);
-                    std::process::exit(1);
-                }
+                    Ok(allocation_mappings) => allocation_mappings,
+                    Err(err) => {
+                        tracing::error!("failed to process allocations table: {:?}", err);
+                        std::process::exit(1);
+                    }
+                };
+                mappings
+                    .finish("allocations", latest, allocation_mappings)
+                    .await?;
+
+                let (server_uuids, allocation_ids): (Vec<uuid::Uuid>, Vec<i64>) = changed_servers
+                    .iter()
+                    .flat_map(|m| m.values())
+                    .map(|(server_uuid, allocation_id)| (*server_uuid, *allocation_id as i64))
+                    .unzip();
+
+                sqlx::query(
+                    r#"
+                    UPDATE servers
+                    SET allocation_uuid = import_mappings.target_uuid
+                    FROM UNNEST($2::uuid[], $3::bigint[]) AS changed (server_uuid, allocation_id)
+                    JOIN import_mappings
+                        ON import_mappings.source = $1
+                        AND import_mappings.source_table = 'allocations'
+                        AND import_mappings.source_id = changed.allocation_id
+                    WHERE servers.uuid = changed.server_uuid
+                    "#,
+                )
+                .bind(panel.name())
+                .bind(&server_uuids)
+                .bind(&allocation_ids)
+                .execute(database.write())
+                .await?;
 
                 tracing::info!(
                     "finished processing import, took {:.2} seconds",
                     start_time.elapsed().as_secs_f32()
                 );
 
+                if let Some(report) = report {
+                    report.finish(&database).await?;
+                }
+
                 Ok(())
             })
         })
@@ -0,0 +1,101 @@
+use colored::Colorize;
+
+/// The tables the import writes to, in the order they are imported.
+const TABLES: &[&str] = &[
+    "users",
+    "user_ssh_keys",
+    "user_api_keys",
+    "locations",
+    "nodes",
+    "nests",
+    "nest_eggs",
+    "nest_egg_variables",
+    "database_hosts",
+    "servers",
+    "server_databases",
+    "server_variables",
+    "server_backups",
+    "server_subusers",
+    "mounts",
+    "nest_egg_mounts",
+    "node_mounts",
+    "server_mounts",
+    "server_schedules",
+    "server_schedule_steps",
+    "node_allocations",
+    "server_allocations",
+];
+
+/// Compares the imported tables before and after a dry run. The dry run goes through the regular
+/// import inside a transaction, so the rows written by it can be told apart by their `xmin`
+/// belonging to a transaction that is still in progress. Only our own transaction and its
+/// savepoints can be in progress while their rows are visible to us.
+pub struct DryRunReport {
+    counts: Vec<i64>,
+}
+
+impl DryRunReport {
+    pub async fn new(database: &shared::database::Database) -> Result<Self, anyhow::Error> {
+        let mut counts = Vec::with_capacity(TABLES.len());
+
+        for table in TABLES {
+            counts.push(
+                sqlx::query_scalar(&format!("SELECT COUNT(*) FROM {table}"))
+                    .fetch_one(database.write())
+                    .await?,
+            );
+        }
+
+        Ok(Self { counts })
+    }
+
+    /// Prints how many rows every table would have after the import and how many of them the
+    /// import created or updated, then discards everything the import wrote.
+    pub async fn finish(self, database: &shared::database::Database) -> Result<(), anyhow::Error> {
+        println!();
+        println!(
+            "{}",
+            format!(
+                "{:<22} {:>10} {:>10} {:>10}",
+                "table", "before", "after", "written"
+            )
+            .bold()
+        );
+
+        for (table, before) in TABLES.iter().zip(self.counts) {
+            let (after, written): (i64, i64) = sqlx::query_as(&format!(
+                r#"
+                WITH current AS (
+                    SELECT txid_current() AS xid
+                )
+                SELECT
+                    COUNT(*),
+                    COUNT(*) FILTER (WHERE txid_status(
+                        CASE WHEN {table}.xmin::text::bigint > current.xid % 4294967296
+                            THEN current.xid - current.xid % 4294967296 - 4294967296 + {table}.xmin::text::bigint
+                            ELSE current.xid - current.xid % 4294967296 + {table}.xmin::text::bigint
+                        END
+                    ) = 'in progress')
+                FROM {table}, current
+                "#
+            ))
+            .fetch_one(database.write())
+            .await?;
+
+            println!(
+                "{:<22} {:>10} {} {}",
+                table,
+                before,
+                format!("{:>10}", after).green(),
+                format!("{:>10}", written).yellow()
+            );
+        }
+        println!();
+
+        database.rollback().await?;
+
+        tracing::info!("dry run, nothing was imported.");
+
+        Ok(())
+    }
+}
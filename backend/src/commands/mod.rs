@@ -3,6 +3,7 @@ use shared::extensions::commands::CliCommandGroupBuilder;
 mod diagnostics;
 mod extensions;
 mod import;
+mod rotate_encryption_key;
 mod service_install;
 mod version;
 
@@ -22,6 +23,11 @@ pub fn commands(cli: CliCommandGroupBuilder) -> CliCommandGroupBuilder {
         "Gets Diagnostic Data for the Panel.",
         diagnostics::DiagnosticsCommand,
     )
+    .add_command(
+        "rotate-encryption-key",
+        "Re-encrypts all encrypted values of the Panel with a new encryption key.",
+        rotate_encryption_key::RotateEncryptionKeyCommand,
+    )
     .add_group(
         "import",
         "Import data from other panel software into Calagopus.",
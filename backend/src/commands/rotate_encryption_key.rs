@@ -0,0 +1,482 @@
+use clap::{Args, FromArgMatches};
+use colored::Colorize;
+use shared::{database::Database, models::backup_configurations::BackupConfigs};
+use sqlx::Row;
+use std::sync::Arc;
+
+#[derive(Args)]
+pub struct RotateEncryptionKeyArgs {
+    #[arg(
+        long = "old-key",
+        help = "the encryption key the values are currently encrypted with, defaults to APP_ENCRYPTION_KEY"
+    )]
+    old_key: Option<String>,
+    #[arg(
+        long = "new-key",
+        help = "the encryption key to re-encrypt the values with"
+    )]
+    new_key: String,
+    #[arg(
+        long = "verify",
+        help = "whether to check that every value decrypts with the new key before committing",
+        default_value = "false"
+    )]
+    verify: bool,
+    #[arg(
+        long = "dry-run",
+        help = "whether to roll back the rotation instead of committing it",
+        default_value = "false"
+    )]
+    dry_run: bool,
+}
+
+pub struct RotateEncryptionKeyCommand;
+
+impl shared::extensions::commands::CliCommand<RotateEncryptionKeyArgs>
+    for RotateEncryptionKeyCommand
+{
+    fn get_command(&self, command: clap::Command) -> clap::Command {
+        command
+    }
+
+    fn get_executor(self) -> Box<shared::extensions::commands::ExecutorFunc> {
+        Box::new(|env, arg_matches| {
+            Box::pin(async move {
+                let args = RotateEncryptionKeyArgs::from_arg_matches(&arg_matches)?;
+
+                let env = match env {
+                    Some(env) => env,
+                    None => {
+                        eprintln!("{}", "no env found".red());
+                        std::process::exit(1);
+                    }
+                };
+
+                let old_key = args
+                    .old_key
+                    .unwrap_or_else(|| env.app_encryption_key.clone());
+                if args.new_key.is_empty() || args.new_key.to_lowercase() == "changeme" {
+                    eprintln!("{}", "the new encryption key is not allowed.".red());
+                    std::process::exit(1);
+                }
+                if old_key == args.new_key {
+                    eprintln!(
+                        "{}",
+                        "the new encryption key is the same as the old one.".red()
+                    );
+                    std::process::exit(1);
+                }
+
+                let cache = Arc::new(shared::cache::Cache::new(&env).await);
+                let database = Database::new(&env, cache).await;
+
+                let rotator = KeyRotator {
+                    old_key,
+                    new_key: args.new_key,
+                };
+
+                let mut transaction = database.write().begin().await?;
+
+                println!("{}", "rotating encryption key".bright_cyan());
+                let mut failed = rotator.rotate(&mut transaction).await?;
+
+                if args.verify {
+                    println!("{}", "verifying encrypted values".bright_cyan());
+                    failed += rotator.verify(&mut transaction).await?;
+                }
+
+                if failed > 0 {
+                    transaction.rollback().await?;
+
+                    eprintln!(
+                        "{}",
+                        format!("{failed} values could not be processed, nothing was changed.")
+                            .red()
+                    );
+                    std::process::exit(1);
+                }
+
+                if args.dry_run {
+                    transaction.rollback().await?;
+
+                    println!("{}", "dry run, nothing was changed.".yellow());
+                } else {
+                    transaction.commit().await?;
+
+                    println!(
+                        "{}",
+                        "encryption key rotated, update APP_ENCRYPTION_KEY on every instance."
+                            .green()
+                    );
+                }
+
+                Ok(())
+            })
+        })
+    }
+}
+
+/// A `bytea` column holding values encrypted by [`Database::encrypt`].
+struct EncryptedColumn {
+    table: &'static str,
+    primary_key: &'static [&'static str],
+    column: &'static str,
+}
+
+const ENCRYPTED_COLUMNS: &[EncryptedColumn] = &[
+    EncryptedColumn {
+        table: "nodes",
+        primary_key: &["uuid"],
+        column: "token",
+    },
+    EncryptedColumn {
+        table: "database_hosts",
+        primary_key: &["uuid"],
+        column: "password",
+    },
+    EncryptedColumn {
+        table: "server_databases",
+        primary_key: &["uuid"],
+        column: "password",
+    },
+    EncryptedColumn {
+        table: "server_variables",
+        primary_key: &["server_uuid", "variable_uuid"],
+        column: "value_encrypted",
+    },
+    EncryptedColumn {
+        table: "oauth_providers",
+        primary_key: &["uuid"],
+        column: "client_secret",
+    },
+    EncryptedColumn {
+        table: "saml_providers",
+        primary_key: &["uuid"],
+        column: "sp_private_key",
+    },
+    EncryptedColumn {
+        table: "webhooks",
+        primary_key: &["uuid"],
+        column: "secret",
+    },
+];
+
+/// Settings holding base32 encoded values encrypted by [`Database::encrypt`], empty when unset.
+const ENCRYPTED_SETTINGS: &[&str] = &[
+    "storage_s3_access_key",
+    "storage_s3_secret_key",
+    "mail_smtp_username",
+    "mail_smtp_password",
+    "ldap_bind_password",
+];
+
+#[derive(Default)]
+struct RotationCounts {
+    rotated: usize,
+    already_rotated: usize,
+    failed: usize,
+}
+
+impl RotationCounts {
+    fn print(&self, name: &str) {
+        println!(
+            "  {} {} rotated, {} already rotated, {}",
+            format!("{name}:").bright_black(),
+            self.rotated,
+            self.already_rotated,
+            if self.failed > 0 {
+                format!("{} failed", self.failed).red()
+            } else {
+                "0 failed".normal()
+            }
+        );
+    }
+}
+
+struct KeyRotator {
+    old_key: String,
+    new_key: String,
+}
+
+impl KeyRotator {
+    /// Re-encrypts a value with the new key, values that already decrypt with the new key
+    /// (written by instances running on the new key during the rollout) are left untouched.
+    fn reencrypt(&self, data: &[u8], counts: &mut RotationCounts) -> Option<Vec<u8>> {
+        match Database::decrypt_with_keys(data, &self.old_key, None) {
+            Ok(decrypted) => match Database::encrypt_with_key(&decrypted, &self.new_key) {
+                Ok(encrypted) => {
+                    counts.rotated += 1;
+                    Some(encrypted)
+                }
+                Err(err) => {
+                    tracing::error!("failed to encrypt value: {:?}", err);
+                    counts.failed += 1;
+                    None
+                }
+            },
+            Err(_) if Database::decrypt_with_keys(data, &self.new_key, None).is_ok() => {
+                counts.already_rotated += 1;
+                None
+            }
+            Err(err) => {
+                tracing::error!("failed to decrypt value: {:?}", err);
+                counts.failed += 1;
+                None
+            }
+        }
+    }
+
+    fn reencrypt_base32(&self, data: &str, counts: &mut RotationCounts) -> Option<String> {
+        match base32::decode(base32::Alphabet::Z, data) {
+            Some(data) => self
+                .reencrypt(&data, counts)
+                .map(|encrypted| base32::encode(base32::Alphabet::Z, &encrypted)),
+            None => {
+                counts.failed += 1;
+                None
+            }
+        }
+    }
+
+    fn verify_base32(&self, data: &str) -> bool {
+        base32::decode(base32::Alphabet::Z, data)
+            .is_some_and(|data| Database::decrypt_with_keys(&data, &self.new_key, None).is_ok())
+    }
+
+    /// Re-encrypts every encrypted value, returning the amount of values that failed.
+    async fn rotate(
+        &self,
+        transaction: &mut sqlx::PgTransaction<'_>,
+    ) -> Result<usize, anyhow::Error> {
+        let mut failed = 0;
+
+        for encrypted_column in ENCRYPTED_COLUMNS {
+            let mut counts = RotationCounts::default();
+
+            let rows = sqlx::query(&format!(
+                "SELECT {}, {} FROM {} WHERE {} IS NOT NULL",
+                encrypted_column.primary_key.join(", "),
+                encrypted_column.column,
+                encrypted_column.table,
+                encrypted_column.column,
+            ))
+            .fetch_all(&mut **transaction)
+            .await?;
+
+            let update_sql = format!(
+                "UPDATE {} SET {} = $1 WHERE {}",
+                encrypted_column.table,
+                encrypted_column.column,
+                encrypted_column
+                    .primary_key
+                    .iter()
+                    .enumerate()
+                    .map(|(i, key)| format!("{key} = ${}", i + 2))
+                    .collect::<Vec<_>>()
+                    .join(" AND "),
+            );
+
+            for row in rows {
+                let value: Vec<u8> = row.try_get(encrypted_column.column)?;
+                let encrypted = match self.reencrypt(&value, &mut counts) {
+                    Some(encrypted) => encrypted,
+                    None => continue,
+                };
+
+                let mut query = sqlx::query(&update_sql).bind(encrypted);
+                for key in encrypted_column.primary_key {
+                    query = query.bind(row.try_get::<uuid::Uuid, _>(*key)?);
+                }
+                query.execute(&mut **transaction).await?;
+            }
+
+            counts.print(&format!(
+                "{}.{}",
+                encrypted_column.table, encrypted_column.column
+            ));
+            failed += counts.failed;
+        }
+
+        let mut counts = RotationCounts::default();
+        let rows = sqlx::query(
+            r#"
+            SELECT backup_configurations.uuid, backup_configurations.backup_configs
+            FROM backup_configurations
+            "#,
+        )
+        .fetch_all(&mut **transaction)
+        .await?;
+
+        for row in rows {
+            let mut backup_configs: BackupConfigs =
+                serde_json::from_value(row.try_get("backup_configs")?)?;
+            let mut changed = false;
+
+            if let Some(s3) = &mut backup_configs.s3
+                && let Some(secret_key) = self.reencrypt_base32(&s3.secret_key, &mut counts)
+            {
+                s3.secret_key = secret_key.into();
+                changed = true;
+            }
+            if let Some(restic) = &mut backup_configs.restic {
+                for value in restic.environment.values_mut() {
+                    if let Some(encrypted) = self.reencrypt_base32(value, &mut counts) {
+                        *value = encrypted.into();
+                        changed = true;
+                    }
+                }
+            }
+
+            if changed {
+                sqlx::query(
+                    r#"
+                    UPDATE backup_configurations
+                    SET backup_configs = $2
+                    WHERE backup_configurations.uuid = $1
+                    "#,
+                )
+                .bind(row.try_get::<uuid::Uuid, _>("uuid")?)
+                .bind(serde_json::to_value(&backup_configs)?)
+                .execute(&mut **transaction)
+                .await?;
+            }
+        }
+
+        counts.print("backup_configurations.backup_configs");
+        failed += counts.failed;
+
+        let mut counts = RotationCounts::default();
+        let rows = sqlx::query(
+            r#"
+            SELECT settings.key, settings.value
+            FROM settings
+            WHERE settings.key = ANY($1) AND settings.value != ''
+            "#,
+        )
+        .bind(ENCRYPTED_SETTINGS)
+        .fetch_all(&mut **transaction)
+        .await?;
+
+        for row in rows {
+            let value: String = row.try_get("value")?;
+            let encrypted = match self.reencrypt_base32(&value, &mut counts) {
+                Some(encrypted) => encrypted,
+                None => continue,
+            };
+
+            sqlx::query(
+                r#"
+                UPDATE settings
+                SET value = $2
+                WHERE settings.key = $1
+                "#,
+            )
+            .bind(row.try_get::<String, _>("key")?)
+            .bind(encrypted)
+            .execute(&mut **transaction)
+            .await?;
+        }
+
+        counts.print("settings");
+        failed += counts.failed;
+
+        Ok(failed)
+    }
+
+    /// Checks that every encrypted value decrypts with the new key, returning the amount of
+    /// values that do not.
+    async fn verify(
+        &self,
+        transaction: &mut sqlx::PgTransaction<'_>,
+    ) -> Result<usize, anyhow::Error> {
+        let mut failed = 0;
+
+        for encrypted_column in ENCRYPTED_COLUMNS {
+            let values: Vec<Vec<u8>> = sqlx::query_scalar(&format!(
+                "SELECT {} FROM {} WHERE {} IS NOT NULL",
+                encrypted_column.column, encrypted_column.table, encrypted_column.column,
+            ))
+            .fetch_all(&mut **transaction)
+            .await?;
+
+            let table_failed = values
+                .iter()
+                .filter(|value| Database::decrypt_with_keys(value, &self.new_key, None).is_err())
+                .count();
+
+            print_verification(
+                &format!("{}.{}", encrypted_column.table, encrypted_column.column),
+                values.len(),
+                table_failed,
+            );
+            failed += table_failed;
+        }
+
+        let values: Vec<serde_json::Value> = sqlx::query_scalar(
+            r#"
+            SELECT backup_configurations.backup_configs
+            FROM backup_configurations
+            "#,
+        )
+        .fetch_all(&mut **transaction)
+        .await?;
+
+        let mut total = 0;
+        let mut table_failed = 0;
+        for value in values {
+            let backup_configs: BackupConfigs = serde_json::from_value(value)?;
+
+            if let Some(s3) = &backup_configs.s3 {
+                total += 1;
+                if !self.verify_base32(&s3.secret_key) {
+                    table_failed += 1;
+                }
+            }
+            if let Some(restic) = &backup_configs.restic {
+                for value in restic.environment.values() {
+                    total += 1;
+                    if !self.verify_base32(value) {
+                        table_failed += 1;
+                    }
+                }
+            }
+        }
+
+        print_verification("backup_configurations.backup_configs", total, table_failed);
+        failed += table_failed;
+
+        let values: Vec<String> = sqlx::query_scalar(
+            r#"
+            SELECT settings.value
+            FROM settings
+            WHERE settings.key = ANY($1) AND settings.value != ''
+            "#,
+        )
+        .bind(ENCRYPTED_SETTINGS)
+        .fetch_all(&mut **transaction)
+        .await?;
+
+        let table_failed = values
+            .iter()
+            .filter(|value| !self.verify_base32(value))
+            .count();
+
+        print_verification("settings", values.len(), table_failed);
+        failed += table_failed;
+
+        Ok(failed)
+    }
+}
+
+fn print_verification(name: &str, total: usize, failed: usize) {
+    println!(
+        "  {} {} verified, {}",
+        format!("{name}:").bright_black(),
+        total - failed,
+        if failed > 0 {
+            format!("{failed} failed").red()
+        } else {
+            "0 failed".normal()
+        }
+    );
+}
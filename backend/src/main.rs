@@ -10,6 +10,7 @@ use axum::{
 };
 use colored::Colorize;
 use compact_str::ToCompactString;
+use futures_util::StreamExt;
 use rand::Rng;
 use sentry_tower::SentryHttpLayer;
 use sha2::Digest;
@@ -283,6 +284,24 @@ async fn main() {
         }
     }
 
+    // runs regardless of who applied the migrations, so installs using the standalone
+    // migrator do not keep secret variables in plaintext
+    match shared::models::server_variable::ServerVariable::sync_encryption(&database, None).await {
+        Ok(0) => {}
+        Ok(synced_variables) => {
+            tracing::info!(
+                "updated encryption of {} secret server variables.",
+                synced_variables
+            );
+        }
+        Err(err) => {
+            tracing::error!(
+                "failed to update encryption of secret server variables: {:?}",
+                err
+            );
+        }
+    }
+
     let background_tasks =
         Arc::new(shared::extensions::background_tasks::BackgroundTaskManager::default());
     let shutdown_handlers =
@@ -295,7 +314,8 @@ async fn main() {
     );
     let storage = Arc::new(shared::storage::Storage::new(settings.clone()));
     let captcha = Arc::new(shared::captcha::Captcha::new(settings.clone()));
-    let mail = Arc::new(shared::mail::Mail::new(settings.clone()));
+    let ldap = Arc::new(shared::ldap::Ldap::new(settings.clone(), database.clone()));
+    let mail = Arc::new(shared::mail::Mail::new(settings.clone(), database.clone()));
 
     let state = Arc::new(shared::AppState {
         start_time: Instant::now(),
@@ -324,12 +344,15 @@ async fn main() {
         jwt,
         storage,
         captcha,
+        ldap,
         mail,
         database: database.clone(),
         cache: cache.clone(),
         env,
     });
 
+    shared::models::webhook::Webhook::register_event_handlers().await;
+
     let (routes, background_task_builder, shutdown_handler_builder) =
         extensions.init(state.clone()).await;
     let mut extension_router = OpenApiRouter::new().with_state(state.clone());
@@ -538,12 +561,219 @@ async fn main() {
                 );
             }
 
+            let deleted_webhook_deliveries =
+                shared::models::webhook_delivery::WebhookDelivery::delete_older_than(
+                    &state.database,
+                    chrono::Utc::now() - chrono::Duration::days(admin_retention_days as i64),
+                )
+                .await?;
+            if deleted_webhook_deliveries > 0 {
+                tracing::info!(
+                    "deleted {} old webhook deliveries",
+                    deleted_webhook_deliveries
+                );
+            }
+
             tokio::time::sleep(std::time::Duration::from_hours(1)).await;
 
             Ok(())
         })
         .await;
 
+    background_task_builder
+        .add_task("send_webhook_deliveries", async |state| {
+            let deliveries =
+                shared::models::webhook_delivery::WebhookDelivery::claim_due(&state.database, 50)
+                    .await?;
+            let idle = deliveries.is_empty();
+
+            futures_util::stream::iter(deliveries)
+                .for_each_concurrent(8, |(mut delivery, webhook)| {
+                    let state = state.clone();
+
+                    async move {
+                        if let Err(err) = delivery.attempt(&state, &webhook).await {
+                            tracing::warn!(
+                                webhook = %webhook.uuid,
+                                delivery = %delivery.uuid,
+                                attempts = delivery.attempts,
+                                "webhook delivery failed: {:?}",
+                                err
+                            );
+                        }
+                    }
+                })
+                .await;
+
+            if idle {
+                tokio::time::sleep(std::time::Duration::from_secs(2)).await;
+            }
+
+            Ok(())
+        })
+        .await;
+
+    background_task_builder
+        .add_task("resume_server_bulk_operations", async |state| {
+            use shared::models::server_bulk_operation::ServerBulkOperation;
+
+            for operation in ServerBulkOperation::claim_orphaned(&state.database).await? {
+                let uuid = operation.uuid;
+
+                if let Err(err) = operation.resume(state.clone()).await {
+                    tracing::error!(
+                        bulk_operation = %uuid,
+                        "failed to resume bulk operation: {:?}",
+                        err
+                    );
+                }
+            }
+
+            tokio::time::sleep(std::time::Duration::from_secs(60)).await;
+
+            Ok(())
+        })
+        .await;
+
+    background_task_builder
+        .add_task("process_server_expiry", async |state| {
+            use shared::models::{DeletableModel, server::Server};
+
+            for mut server in Server::all_pending_unsuspension(&state.database).await? {
+                server.unsuspend(&state.database).await?;
+
+                if let Err(err) = shared::models::admin_activity::AdminActivity::log(
+                    &state.database,
+                    None,
+                    None,
+                    "server:unsuspend",
+                    None,
+                    serde_json::json!({
+                        "uuid": server.uuid,
+                        "name": server.name,
+                    }),
+                )
+                .await
+                {
+                    tracing::warn!(server = %server.uuid, "failed to log server unsuspension: {:?}", err);
+                }
+
+                let server_uuid = server.uuid;
+                if let Err(err) = server.sync(&state.database).await {
+                    tracing::warn!(server = %server_uuid, "failed to sync unsuspended server: {:?}", err);
+                }
+            }
+
+            let (notice_days, grace_period_days, delete_servers) = state
+                .settings
+                .get_as(|settings| {
+                    (
+                        settings.server.expiry_notice_days,
+                        settings.server.expiry_grace_period_days,
+                        settings.server.expiry_delete_servers,
+                    )
+                })
+                .await?;
+            let grace_period = chrono::Duration::days(grace_period_days as i64);
+            let now = chrono::Utc::now().naive_utc();
+
+            if notice_days > 0 {
+                for mut server in Server::all_pending_expiry_notice(
+                    &state.database,
+                    now + chrono::Duration::days(notice_days as i64),
+                )
+                .await?
+                {
+                    let Some(expires_at) = server.expires_at else {
+                        continue;
+                    };
+
+                    state
+                        .mail
+                        .send_template(
+                            server.owner.email.clone(),
+                            shared::mail::MailTemplateIdentifier::ServerExpiring,
+                            &server.owner.language,
+                            serde_json::json!({
+                                "user": shared::mail::user_context(&server.owner),
+                                "server": shared::mail::server_context(&server),
+                                "expires_at": expires_at.and_utc().to_rfc2822(),
+                                "delete_at": delete_servers
+                                    .then(|| (expires_at + grace_period).and_utc().to_rfc2822()),
+                            }),
+                        )
+                        .await;
+
+                    server.mark_expiry_notified(&state.database).await?;
+                }
+            }
+
+            for mut server in Server::all_expired_unsuspended(&state.database).await? {
+                server
+                    .suspend(&state.database, Some("server expired"), None, None)
+                    .await?;
+
+                if let Err(err) = shared::models::admin_activity::AdminActivity::log(
+                    &state.database,
+                    None,
+                    None,
+                    "server:expire",
+                    None,
+                    serde_json::json!({
+                        "uuid": server.uuid,
+                        "name": server.name,
+                        "expires_at": server.expires_at.map(|expires_at| expires_at.and_utc()),
+                    }),
+                )
+                .await
+                {
+                    tracing::warn!(server = %server.uuid, "failed to log server expiry: {:?}", err);
+                }
+
+                let server_uuid = server.uuid;
+                if let Err(err) = server.sync(&state.database).await {
+                    tracing::warn!(server = %server_uuid, "failed to sync expired server: {:?}", err);
+                }
+            }
+
+            if delete_servers {
+                for server in Server::all_expired_before(&state.database, now - grace_period).await? {
+                    if let Err(err) = server
+                        .delete(
+                            &state,
+                            shared::models::server::DeleteServerOptions { force: false },
+                        )
+                        .await
+                    {
+                        tracing::warn!(server = %server.uuid, "failed to delete expired server: {:?}", err);
+                        continue;
+                    }
+
+                    if let Err(err) = shared::models::admin_activity::AdminActivity::log(
+                        &state.database,
+                        None,
+                        None,
+                        "server:delete",
+                        None,
+                        serde_json::json!({
+                            "uuid": server.uuid,
+                            "name": server.name,
+                            "expired": true,
+                        }),
+                    )
+                    .await
+                    {
+                        tracing::warn!(server = %server.uuid, "failed to log expired server deletion: {:?}", err);
+                    }
+                }
+            }
+
+            tokio::time::sleep(std::time::Duration::from_mins(5)).await;
+
+            Ok(())
+        })
+        .await;
+
     background_tasks
         .merge_builder(background_task_builder)
         .await;
@@ -615,7 +845,7 @@ async fn main() {
             ),
         )
         .fallback(|state: GetState, req: Request<Body>| async move {
-            if !req.uri().path().starts_with("/api") {
+            if !req.uri().path().starts_with("/api") && !req.uri().path().starts_with("/scim") {
                 let path = &req.uri().path()[1.min(req.uri().path().len())..];
 
                 let (is_index, entry) = match FRONTEND_ASSETS.get_entry(path) {
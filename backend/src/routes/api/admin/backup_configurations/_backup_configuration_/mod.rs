@@ -15,6 +15,7 @@ use utoipa_axum::{router::OpenApiRouter, routes};
 mod backups;
 mod locations;
 mod nodes;
+mod reveal;
 mod servers;
 mod stats;
 
@@ -225,7 +226,11 @@ mod patch {
         if let Some(backup_disk) = data.backup_disk {
             backup_configuration.backup_disk = backup_disk;
         }
-        if let Some(backup_configs) = data.backup_configs {
+        if let Some(mut backup_configs) = data.backup_configs {
+            let mut existing_backup_configs = backup_configuration.backup_configs.clone();
+            existing_backup_configs.decrypt(&state.database).await?;
+            backup_configs.restore_censored(&existing_backup_configs);
+
             backup_configuration.backup_configs = backup_configs;
             backup_configuration
                 .backup_configs
@@ -289,6 +294,7 @@ pub fn router(state: &State) -> OpenApiRouter<State> {
         .nest("/locations", locations::router(state))
         .nest("/nodes", nodes::router(state))
         .nest("/servers", servers::router(state))
+        .nest("/reveal", reveal::router(state))
         .route_layer(axum::middleware::from_fn_with_state(state.clone(), auth))
         .with_state(state.clone())
 }
@@ -0,0 +1,58 @@
+use super::State;
+use utoipa_axum::{router::OpenApiRouter, routes};
+
+mod post {
+    use crate::routes::api::admin::backup_configurations::_backup_configuration_::GetBackupConfiguration;
+    use serde::Serialize;
+    use shared::{
+        ApiError, GetState,
+        models::{admin_activity::GetAdminActivityLogger, user::GetPermissionManager},
+        response::{ApiResponse, ApiResponseResult},
+    };
+    use utoipa::ToSchema;
+
+    #[derive(ToSchema, Serialize)]
+    struct Response {
+        backup_configs: shared::models::backup_configurations::BackupConfigs,
+    }
+
+    #[utoipa::path(post, path = "/", responses(
+        (status = OK, body = inline(Response)),
+        (status = NOT_FOUND, body = ApiError),
+    ), params(
+        (
+            "backup_configuration" = uuid::Uuid,
+            description = "The backup configuration ID",
+            example = "123e4567-e89b-12d3-a456-426614174000",
+        ),
+    ))]
+    pub async fn route(
+        state: GetState,
+        permissions: GetPermissionManager,
+        backup_configuration: GetBackupConfiguration,
+        activity_logger: GetAdminActivityLogger,
+    ) -> ApiResponseResult {
+        permissions.has_admin_permission("backup-configurations.reveal")?;
+
+        let mut backup_configs = backup_configuration.backup_configs.clone();
+        backup_configs.decrypt(&state.database).await?;
+
+        activity_logger
+            .log(
+                "backup-configuration:reveal",
+                serde_json::json!({
+                    "uuid": backup_configuration.uuid,
+                    "name": backup_configuration.name,
+                }),
+            )
+            .await;
+
+        ApiResponse::new_serialized(Response { backup_configs }).ok()
+    }
+}
+
+pub fn router(state: &State) -> OpenApiRouter<State> {
+    OpenApiRouter::new()
+        .routes(routes!(post::route))
+        .with_state(state.clone())
+}
@@ -0,0 +1,278 @@
+use super::State;
+use axum::{
+    extract::{Path, Request},
+    http::StatusCode,
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use shared::{
+    GetState,
+    models::{ByUuid, mail_template::MailTemplate, user::GetPermissionManager},
+    response::ApiResponse,
+};
+use utoipa_axum::{router::OpenApiRouter, routes};
+
+mod test;
+
+pub type GetMailTemplate = shared::extract::ConsumingExtension<MailTemplate>;
+
+pub async fn auth(
+    state: GetState,
+    permissions: GetPermissionManager,
+    Path(mail_template): Path<uuid::Uuid>,
+    mut req: Request,
+    next: Next,
+) -> Result<Response, StatusCode> {
+    if let Err(err) = permissions.has_admin_permission("mail-templates.read") {
+        return Ok(err.into_response());
+    }
+
+    let mail_template = MailTemplate::by_uuid_optional(&state.database, mail_template).await;
+    let mail_template = match mail_template {
+        Ok(Some(mail_template)) => mail_template,
+        Ok(None) => {
+            return Ok(ApiResponse::error("mail template not found")
+                .with_status(StatusCode::NOT_FOUND)
+                .into_response());
+        }
+        Err(err) => return Ok(ApiResponse::from(err).into_response()),
+    };
+
+    req.extensions_mut().insert(mail_template);
+
+    Ok(next.run(req).await)
+}
+
+mod get {
+    use crate::routes::api::admin::mail_templates::_mail_template_::GetMailTemplate;
+    use serde::Serialize;
+    use shared::{
+        ApiError,
+        models::user::GetPermissionManager,
+        response::{ApiResponse, ApiResponseResult},
+    };
+    use utoipa::ToSchema;
+
+    #[derive(ToSchema, Serialize)]
+    struct Response {
+        mail_template: shared::models::mail_template::AdminApiMailTemplate,
+    }
+
+    #[utoipa::path(get, path = "/", responses(
+        (status = OK, body = inline(Response)),
+        (status = NOT_FOUND, body = ApiError),
+    ), params(
+        (
+            "mail_template" = uuid::Uuid,
+            description = "The mail template ID",
+            example = "123e4567-e89b-12d3-a456-426614174000",
+        ),
+    ))]
+    pub async fn route(
+        permissions: GetPermissionManager,
+        mail_template: GetMailTemplate,
+    ) -> ApiResponseResult {
+        permissions.has_admin_permission("mail-templates.read")?;
+
+        ApiResponse::new_serialized(Response {
+            mail_template: mail_template.0.into_admin_api_object(),
+        })
+        .ok()
+    }
+}
+
+mod delete {
+    use crate::routes::api::admin::mail_templates::_mail_template_::GetMailTemplate;
+    use serde::Serialize;
+    use shared::{
+        ApiError, GetState,
+        models::{
+            DeletableModel, admin_activity::GetAdminActivityLogger, user::GetPermissionManager,
+        },
+        response::{ApiResponse, ApiResponseResult},
+    };
+    use utoipa::ToSchema;
+
+    #[derive(ToSchema, Serialize)]
+    struct Response {}
+
+    #[utoipa::path(delete, path = "/", responses(
+        (status = OK, body = inline(Response)),
+        (status = NOT_FOUND, body = ApiError),
+    ), params(
+        (
+            "mail_template" = uuid::Uuid,
+            description = "The mail template ID",
+            example = "123e4567-e89b-12d3-a456-426614174000",
+        ),
+    ))]
+    pub async fn route(
+        state: GetState,
+        permissions: GetPermissionManager,
+        mail_template: GetMailTemplate,
+        activity_logger: GetAdminActivityLogger,
+    ) -> ApiResponseResult {
+        permissions.has_admin_permission("mail-templates.delete")?;
+
+        mail_template.delete(&state, ()).await?;
+
+        activity_logger
+            .log(
+                "mail-template:delete",
+                serde_json::json!({
+                    "uuid": mail_template.uuid,
+                    "identifier": mail_template.identifier,
+                    "language": mail_template.language,
+                }),
+            )
+            .await;
+
+        ApiResponse::new_serialized(Response {}).ok()
+    }
+}
+
+mod patch {
+    use crate::routes::api::admin::mail_templates::_mail_template_::GetMailTemplate;
+    use axum::http::StatusCode;
+    use serde::{Deserialize, Serialize};
+    use shared::{
+        ApiError, GetState,
+        mail::RenderedMail,
+        models::{admin_activity::GetAdminActivityLogger, user::GetPermissionManager},
+        prelude::SqlxErrorExt,
+        response::{ApiResponse, ApiResponseResult},
+    };
+    use utoipa::ToSchema;
+    use validator::Validate;
+
+    #[derive(ToSchema, Validate, Deserialize)]
+    pub struct Payload {
+        #[validate(length(min = 2, max = 15))]
+        #[schema(min_length = 2, max_length = 15)]
+        language: Option<compact_str::CompactString>,
+
+        #[validate(length(min = 1, max = 255))]
+        #[schema(min_length = 1, max_length = 255)]
+        subject: Option<String>,
+        #[validate(length(min = 1, max = 65536))]
+        #[schema(min_length = 1, max_length = 65536)]
+        body_html: Option<String>,
+        #[validate(length(max = 65536))]
+        #[schema(max_length = 65536)]
+        body_text: Option<String>,
+    }
+
+    #[derive(ToSchema, Serialize)]
+    struct Response {}
+
+    #[utoipa::path(patch, path = "/", responses(
+        (status = OK, body = inline(Response)),
+        (status = NOT_FOUND, body = ApiError),
+        (status = BAD_REQUEST, body = ApiError),
+        (status = CONFLICT, body = ApiError),
+    ), params(
+        (
+            "mail_template" = uuid::Uuid,
+            description = "The mail template ID",
+            example = "123e4567-e89b-12d3-a456-426614174000",
+        ),
+    ), request_body = inline(Payload))]
+    pub async fn route(
+        state: GetState,
+        permissions: GetPermissionManager,
+        mut mail_template: GetMailTemplate,
+        activity_logger: GetAdminActivityLogger,
+        shared::Payload(data): shared::Payload<Payload>,
+    ) -> ApiResponseResult {
+        if let Err(errors) = shared::utils::validate_data(&data) {
+            return ApiResponse::new_serialized(ApiError::new_strings_value(errors))
+                .with_status(StatusCode::BAD_REQUEST)
+                .ok();
+        }
+
+        permissions.has_admin_permission("mail-templates.update")?;
+
+        if let Some(language) = data.language {
+            mail_template.language = language;
+        }
+        if let Some(subject) = data.subject {
+            mail_template.subject = subject;
+        }
+        if let Some(body_html) = data.body_html {
+            mail_template.body_html = body_html;
+        }
+        if let Some(body_text) = data.body_text {
+            if body_text.is_empty() {
+                mail_template.body_text = None;
+            } else {
+                mail_template.body_text = Some(body_text);
+            }
+        }
+
+        if let Err(err) = RenderedMail::check(
+            &mail_template.subject,
+            &mail_template.body_html,
+            mail_template.body_text.as_deref(),
+        ) {
+            return ApiResponse::error(&format!("invalid mail template: {err}"))
+                .with_status(StatusCode::BAD_REQUEST)
+                .ok();
+        }
+
+        match sqlx::query(
+            r#"
+            UPDATE mail_templates
+            SET language = $2, subject = $3, body_html = $4, body_text = $5
+            WHERE mail_templates.uuid = $1
+            "#,
+        )
+        .bind(mail_template.uuid)
+        .bind(&mail_template.language)
+        .bind(&mail_template.subject)
+        .bind(&mail_template.body_html)
+        .bind(&mail_template.body_text)
+        .execute(state.database.write())
+        .await
+        {
+            Ok(_) => {}
+            Err(err) if err.is_unique_violation() => {
+                return ApiResponse::error(
+                    "mail template with identifier and language already exists",
+                )
+                .with_status(StatusCode::CONFLICT)
+                .ok();
+            }
+            Err(err) => {
+                tracing::error!("failed to update mail template: {:?}", err);
+
+                return ApiResponse::error("failed to update mail template")
+                    .with_status(StatusCode::INTERNAL_SERVER_ERROR)
+                    .ok();
+            }
+        }
+
+        activity_logger
+            .log(
+                "mail-template:update",
+                serde_json::json!({
+                    "uuid": mail_template.uuid,
+                    "identifier": mail_template.identifier,
+                    "language": mail_template.language,
+                    "subject": mail_template.subject,
+                }),
+            )
+            .await;
+
+        ApiResponse::new_serialized(Response {}).ok()
+    }
+}
+
+pub fn router(state: &State) -> OpenApiRouter<State> {
+    OpenApiRouter::new()
+        .routes(routes!(get::route))
+        .routes(routes!(delete::route))
+        .routes(routes!(patch::route))
+        .nest("/test", test::router(state))
+        .route_layer(axum::middleware::from_fn_with_state(state.clone(), auth))
+        .with_state(state.clone())
+}
@@ -0,0 +1,77 @@
+use super::State;
+use utoipa_axum::{router::OpenApiRouter, routes};
+
+mod post {
+    use crate::routes::api::admin::mail_templates::_mail_template_::GetMailTemplate;
+    use axum::http::StatusCode;
+    use serde::Serialize;
+    use shared::{
+        ApiError, GetState,
+        mail::MailTemplateIdentifier,
+        models::user::{GetPermissionManager, GetUser},
+        response::{ApiResponse, ApiResponseResult},
+    };
+    use utoipa::ToSchema;
+
+    #[derive(ToSchema, Serialize)]
+    struct Response {}
+
+    #[utoipa::path(post, path = "/", responses(
+        (status = OK, body = inline(Response)),
+        (status = NOT_FOUND, body = ApiError),
+        (status = EXPECTATION_FAILED, body = ApiError),
+    ), params(
+        (
+            "mail_template" = uuid::Uuid,
+            description = "The mail template ID",
+            example = "123e4567-e89b-12d3-a456-426614174000",
+        ),
+    ))]
+    pub async fn route(
+        state: GetState,
+        permissions: GetPermissionManager,
+        user: GetUser,
+        mail_template: GetMailTemplate,
+    ) -> ApiResponseResult {
+        permissions.has_admin_permission("mail-templates.test")?;
+
+        let app_url = state.settings.get().await?.app.url.clone();
+        let mut context = match MailTemplateIdentifier::from_identifier(&mail_template.identifier) {
+            Some(identifier) => identifier.example_context(&app_url),
+            None => serde_json::json!({}),
+        };
+        context["user"] = shared::mail::user_context(&user);
+
+        let mail = match state
+            .mail
+            .render_raw(
+                &mail_template.subject,
+                &mail_template.body_html,
+                mail_template.body_text.as_deref(),
+                context,
+            )
+            .await
+        {
+            Ok(mail) => mail,
+            Err(err) => {
+                return ApiResponse::error(&format!("invalid mail template: {err}"))
+                    .with_status(StatusCode::EXPECTATION_FAILED)
+                    .ok();
+            }
+        };
+
+        if let Err(err) = state.mail.try_send(user.email.clone(), mail).await {
+            return ApiResponse::error(&format!("failed to send test mail: {err}"))
+                .with_status(StatusCode::EXPECTATION_FAILED)
+                .ok();
+        }
+
+        ApiResponse::new_serialized(Response {}).ok()
+    }
+}
+
+pub fn router(state: &State) -> OpenApiRouter<State> {
+    OpenApiRouter::new()
+        .routes(routes!(post::route))
+        .with_state(state.clone())
+}
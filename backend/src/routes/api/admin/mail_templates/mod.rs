@@ -0,0 +1,199 @@
+use super::State;
+use utoipa_axum::{router::OpenApiRouter, routes};
+
+mod _mail_template_;
+mod preview;
+
+mod get {
+    use axum::{extract::Query, http::StatusCode};
+    use serde::Serialize;
+    use shared::{
+        ApiError, GetState,
+        models::{
+            Pagination, PaginationParamsWithSearch, mail_template::MailTemplate,
+            user::GetPermissionManager,
+        },
+        response::{ApiResponse, ApiResponseResult},
+    };
+    use utoipa::ToSchema;
+
+    #[derive(ToSchema, Serialize)]
+    struct Response {
+        #[schema(inline)]
+        mail_templates: Pagination<shared::models::mail_template::AdminApiMailTemplate>,
+    }
+
+    #[utoipa::path(get, path = "/", responses(
+        (status = OK, body = inline(Response)),
+    ), params(
+        (
+            "page" = i64, Query,
+            description = "The page number",
+            example = "1",
+        ),
+        (
+            "per_page" = i64, Query,
+            description = "The number of items per page",
+            example = "10",
+        ),
+        (
+            "search" = Option<String>, Query,
+            description = "Search term for items",
+        ),
+    ))]
+    pub async fn route(
+        state: GetState,
+        permissions: GetPermissionManager,
+        Query(params): Query<PaginationParamsWithSearch>,
+    ) -> ApiResponseResult {
+        if let Err(errors) = shared::utils::validate_data(&params) {
+            return ApiResponse::new_serialized(ApiError::new_strings_value(errors))
+                .with_status(StatusCode::BAD_REQUEST)
+                .ok();
+        }
+
+        permissions.has_admin_permission("mail-templates.read")?;
+
+        let mail_templates = MailTemplate::all_with_pagination(
+            &state.database,
+            params.page,
+            params.per_page,
+            params.search.as_deref(),
+        )
+        .await?;
+
+        ApiResponse::new_serialized(Response {
+            mail_templates: Pagination {
+                total: mail_templates.total,
+                per_page: mail_templates.per_page,
+                page: mail_templates.page,
+                data: mail_templates
+                    .data
+                    .into_iter()
+                    .map(|mail_template| mail_template.into_admin_api_object())
+                    .collect(),
+            },
+        })
+        .ok()
+    }
+}
+
+mod post {
+    use axum::http::StatusCode;
+    use serde::{Deserialize, Serialize};
+    use shared::{
+        ApiError, GetState,
+        mail::{MailTemplateIdentifier, RenderedMail},
+        models::{
+            admin_activity::GetAdminActivityLogger, mail_template::MailTemplate,
+            user::GetPermissionManager,
+        },
+        response::{ApiResponse, ApiResponseResult},
+    };
+    use utoipa::ToSchema;
+    use validator::Validate;
+
+    #[derive(ToSchema, Validate, Deserialize)]
+    pub struct Payload {
+        identifier: MailTemplateIdentifier,
+        #[validate(length(min = 2, max = 15))]
+        #[schema(min_length = 2, max_length = 15)]
+        language: compact_str::CompactString,
+
+        #[validate(length(min = 1, max = 255))]
+        #[schema(min_length = 1, max_length = 255)]
+        subject: String,
+        #[validate(length(min = 1, max = 65536))]
+        #[schema(min_length = 1, max_length = 65536)]
+        body_html: String,
+        #[validate(length(min = 1, max = 65536))]
+        #[schema(min_length = 1, max_length = 65536)]
+        body_text: Option<String>,
+    }
+
+    #[derive(ToSchema, Serialize)]
+    struct Response {
+        mail_template: shared::models::mail_template::AdminApiMailTemplate,
+    }
+
+    #[utoipa::path(post, path = "/", responses(
+        (status = OK, body = inline(Response)),
+        (status = BAD_REQUEST, body = ApiError),
+        (status = CONFLICT, body = ApiError),
+    ), request_body = inline(Payload))]
+    pub async fn route(
+        state: GetState,
+        permissions: GetPermissionManager,
+        activity_logger: GetAdminActivityLogger,
+        shared::Payload(data): shared::Payload<Payload>,
+    ) -> ApiResponseResult {
+        if let Err(errors) = shared::utils::validate_data(&data) {
+            return ApiResponse::new_serialized(ApiError::new_strings_value(errors))
+                .with_status(StatusCode::BAD_REQUEST)
+                .ok();
+        }
+
+        permissions.has_admin_permission("mail-templates.create")?;
+
+        if let Err(err) =
+            RenderedMail::check(&data.subject, &data.body_html, data.body_text.as_deref())
+        {
+            return ApiResponse::error(&format!("invalid mail template: {err}"))
+                .with_status(StatusCode::BAD_REQUEST)
+                .ok();
+        }
+
+        let mail_template = match MailTemplate::create(
+            &state.database,
+            data.identifier.identifier(),
+            &data.language,
+            &data.subject,
+            &data.body_html,
+            data.body_text.as_deref(),
+        )
+        .await
+        {
+            Ok(mail_template) => mail_template,
+            Err(err) if err.is_unique_violation() => {
+                return ApiResponse::error(
+                    "mail template with identifier and language already exists",
+                )
+                .with_status(StatusCode::CONFLICT)
+                .ok();
+            }
+            Err(err) => {
+                tracing::error!("failed to create mail template: {:?}", err);
+
+                return ApiResponse::error("failed to create mail template")
+                    .with_status(StatusCode::INTERNAL_SERVER_ERROR)
+                    .ok();
+            }
+        };
+
+        activity_logger
+            .log(
+                "mail-template:create",
+                serde_json::json!({
+                    "uuid": mail_template.uuid,
+                    "identifier": mail_template.identifier,
+                    "language": mail_template.language,
+                    "subject": mail_template.subject,
+                }),
+            )
+            .await;
+
+        ApiResponse::new_serialized(Response {
+            mail_template: mail_template.into_admin_api_object(),
+        })
+        .ok()
+    }
+}
+
+pub fn router(state: &State) -> OpenApiRouter<State> {
+    OpenApiRouter::new()
+        .routes(routes!(get::route))
+        .routes(routes!(post::route))
+        .nest("/preview", preview::router(state))
+        .nest("/{mail_template}", _mail_template_::router(state))
+        .with_state(state.clone())
+}
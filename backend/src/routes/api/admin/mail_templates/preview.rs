@@ -0,0 +1,99 @@
+use super::State;
+use utoipa_axum::{router::OpenApiRouter, routes};
+
+mod post {
+    use axum::http::StatusCode;
+    use serde::{Deserialize, Serialize};
+    use shared::{
+        ApiError, GetState,
+        mail::MailTemplateIdentifier,
+        models::user::{GetPermissionManager, GetUser},
+        response::{ApiResponse, ApiResponseResult},
+    };
+    use utoipa::ToSchema;
+    use validator::Validate;
+
+    #[derive(ToSchema, Validate, Deserialize)]
+    pub struct Payload {
+        identifier: MailTemplateIdentifier,
+
+        #[validate(length(min = 1, max = 255))]
+        #[schema(min_length = 1, max_length = 255)]
+        subject: Option<String>,
+        #[validate(length(min = 1, max = 65536))]
+        #[schema(min_length = 1, max_length = 65536)]
+        body_html: Option<String>,
+        #[validate(length(min = 1, max = 65536))]
+        #[schema(min_length = 1, max_length = 65536)]
+        body_text: Option<String>,
+    }
+
+    #[derive(ToSchema, Serialize)]
+    struct Response {
+        subject: String,
+        body_html: String,
+        body_text: Option<String>,
+    }
+
+    #[utoipa::path(post, path = "/", responses(
+        (status = OK, body = inline(Response)),
+        (status = BAD_REQUEST, body = ApiError),
+    ), request_body = inline(Payload))]
+    pub async fn route(
+        state: GetState,
+        permissions: GetPermissionManager,
+        user: GetUser,
+        shared::Payload(data): shared::Payload<Payload>,
+    ) -> ApiResponseResult {
+        if let Err(errors) = shared::utils::validate_data(&data) {
+            return ApiResponse::new_serialized(ApiError::new_strings_value(errors))
+                .with_status(StatusCode::BAD_REQUEST)
+                .ok();
+        }
+
+        permissions.has_admin_permission("mail-templates.read")?;
+
+        let app_url = state.settings.get().await?.app.url.clone();
+        let mut context = data.identifier.example_context(&app_url);
+        context["user"] = shared::mail::user_context(&user);
+
+        let mail = match state
+            .mail
+            .render_raw(
+                data.subject
+                    .as_deref()
+                    .unwrap_or(data.identifier.default_subject()),
+                data.body_html
+                    .as_deref()
+                    .unwrap_or(data.identifier.default_body_html()),
+                match &data.body_text {
+                    Some(body_text) => Some(body_text),
+                    None if data.body_html.is_none() => Some(data.identifier.default_body_text()),
+                    None => None,
+                },
+                context,
+            )
+            .await
+        {
+            Ok(mail) => mail,
+            Err(err) => {
+                return ApiResponse::error(&format!("invalid mail template: {err}"))
+                    .with_status(StatusCode::BAD_REQUEST)
+                    .ok();
+            }
+        };
+
+        ApiResponse::new_serialized(Response {
+            subject: mail.subject,
+            body_html: mail.body_html,
+            body_text: mail.body_text,
+        })
+        .ok()
+    }
+}
+
+pub fn router(state: &State) -> OpenApiRouter<State> {
+    OpenApiRouter::new()
+        .routes(routes!(post::route))
+        .with_state(state.clone())
+}
@@ -22,16 +22,20 @@ mod database_hosts;
 mod egg_repositories;
 mod extensions;
 mod locations;
+mod mail_templates;
 mod mounts;
 mod nests;
 mod nodes;
 mod oauth_providers;
 mod roles;
+mod saml_providers;
+mod server_templates;
 mod servers;
 mod settings;
 mod stats;
 mod system;
 mod users;
+mod webhooks;
 
 pub async fn auth(
     state: GetState,
@@ -52,6 +56,16 @@ pub async fn auth(
             .into_response());
     }
 
+    if let AuthMethod::Session(session) = &*auth
+        && session.impersonator_uuid.is_some()
+    {
+        return Ok(ApiResponse::error(
+            "the admin area is not available while impersonating a user",
+        )
+        .with_status(StatusCode::FORBIDDEN)
+        .into_response());
+    }
+
     req.extensions_mut().insert(AdminActivityLogger {
         state: Arc::clone(&state),
         user_uuid: user.uuid,
@@ -74,6 +88,7 @@ pub fn router(state: &State) -> OpenApiRouter<State> {
         .nest("/settings", settings::router(state))
         .nest("/locations", locations::router(state))
         .nest("/servers", servers::router(state))
+        .nest("/server-templates", server_templates::router(state))
         .nest("/nodes", nodes::router(state))
         .nest("/nests", nests::router(state))
         .nest("/egg-repositories", egg_repositories::router(state))
@@ -83,7 +98,10 @@ pub fn router(state: &State) -> OpenApiRouter<State> {
             backup_configurations::router(state),
         )
         .nest("/oauth-providers", oauth_providers::router(state))
+        .nest("/saml-providers", saml_providers::router(state))
+        .nest("/mail-templates", mail_templates::router(state))
         .nest("/mounts", mounts::router(state))
+        .nest("/webhooks", webhooks::router(state))
         .nest("/users", users::router(state))
         .nest("/roles", roles::router(state))
         .nest("/extensions", extensions::router(state))
@@ -86,7 +86,7 @@ mod patch {
         ApiError, GetState,
         models::{
             admin_activity::GetAdminActivityLogger, nest_egg_variable::NestEggVariable,
-            user::GetPermissionManager,
+            server_variable::ServerVariable, user::GetPermissionManager,
         },
         prelude::SqlxErrorExt,
         response::{ApiResponse, ApiResponseResult},
@@ -199,6 +199,9 @@ mod patch {
         if let Some(user_editable) = data.user_editable {
             egg_variable.user_editable = user_editable;
         }
+        let secret_changed = data
+            .secret
+            .is_some_and(|secret| secret != egg_variable.secret);
         if let Some(secret) = data.secret {
             egg_variable.secret = secret;
         }
@@ -241,6 +244,10 @@ mod patch {
             }
         }
 
+        if secret_changed {
+            ServerVariable::sync_encryption(&state.database, Some(egg_variable.uuid)).await?;
+        }
+
         activity_logger
             .log(
                 "nest:egg.variable.update",
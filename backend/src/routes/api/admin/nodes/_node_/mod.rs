@@ -16,7 +16,9 @@ mod allocations;
 mod backups;
 mod mounts;
 mod reset_token;
+mod reveal_token;
 mod servers;
+mod system;
 
 pub async fn auth(
     state: GetState,
@@ -199,6 +201,19 @@ mod patch {
 
         memory: Option<i64>,
         disk: Option<i64>,
+        #[validate(range(min = 0))]
+        #[schema(minimum = 0)]
+        cpu: Option<i32>,
+
+        #[validate(range(min = -1))]
+        #[schema(minimum = -1)]
+        memory_overallocation: Option<i16>,
+        #[validate(range(min = -1))]
+        #[schema(minimum = -1)]
+        disk_overallocation: Option<i16>,
+        #[validate(range(min = -1))]
+        #[schema(minimum = -1)]
+        cpu_overallocation: Option<i16>,
     }
 
     #[derive(ToSchema, Serialize)]
@@ -308,30 +323,49 @@ mod patch {
         if let Some(disk) = data.disk {
             node.disk = disk;
         }
+        if let Some(cpu) = data.cpu {
+            node.cpu = cpu;
+        }
+        if let Some(memory_overallocation) = data.memory_overallocation {
+            node.memory_overallocation = memory_overallocation;
+        }
+        if let Some(disk_overallocation) = data.disk_overallocation {
+            node.disk_overallocation = disk_overallocation;
+        }
+        if let Some(cpu_overallocation) = data.cpu_overallocation {
+            node.cpu_overallocation = cpu_overallocation;
+        }
 
-        match sqlx::query!(
+        match sqlx::query(
             "UPDATE nodes
             SET location_uuid = $1, backup_configuration_uuid = $2, name = $3,
                 description = $4, deployment_enabled = $5, maintenance_enabled = $6, public_url = $7,
                 url = $8, sftp_host = $9, sftp_port = $10,
-                memory = $11, disk = $12
-            WHERE nodes.uuid = $13",
-            node.location.uuid,
+                memory = $11, disk = $12, cpu = $13,
+                memory_overallocation = $14, disk_overallocation = $15, cpu_overallocation = $16
+            WHERE nodes.uuid = $17",
+        )
+        .bind(node.location.uuid)
+        .bind(
             node.backup_configuration
                 .as_ref()
                 .map(|backup_configuration| backup_configuration.uuid),
-            &node.name,
-            node.description.as_deref(),
-            node.deployment_enabled,
-            node.maintenance_enabled,
-            node.public_url.as_ref().map(|url| url.to_string()),
-            &node.url.to_compact_string(),
-            node.sftp_host.as_deref(),
-            node.sftp_port,
-            node.memory,
-            node.disk,
-            node.uuid,
         )
+        .bind(&node.name)
+        .bind(node.description.as_deref())
+        .bind(node.deployment_enabled)
+        .bind(node.maintenance_enabled)
+        .bind(node.public_url.as_ref().map(|url| url.to_string()))
+        .bind(node.url.to_compact_string())
+        .bind(node.sftp_host.as_deref())
+        .bind(node.sftp_port)
+        .bind(node.memory)
+        .bind(node.disk)
+        .bind(node.cpu)
+        .bind(node.memory_overallocation)
+        .bind(node.disk_overallocation)
+        .bind(node.cpu_overallocation)
+        .bind(node.uuid)
         .execute(state.database.write())
         .await
         {
@@ -367,6 +401,10 @@ mod patch {
                     "sftp_port": node.sftp_port,
                     "memory": node.memory,
                     "disk": node.disk,
+                    "cpu": node.cpu,
+                    "memory_overallocation": node.memory_overallocation,
+                    "disk_overallocation": node.disk_overallocation,
+                    "cpu_overallocation": node.cpu_overallocation,
                 }),
             )
             .await;
@@ -381,6 +419,8 @@ pub fn router(state: &State) -> OpenApiRouter<State> {
         .routes(routes!(delete::route))
         .routes(routes!(patch::route))
         .nest("/reset-token", reset_token::router(state))
+        .nest("/reveal-token", reveal_token::router(state))
+        .nest("/system", system::router(state))
         .nest("/allocations", allocations::router(state))
         .nest("/servers", servers::router(state))
         .nest("/mounts", mounts::router(state))
@@ -0,0 +1,57 @@
+use super::State;
+use utoipa_axum::{router::OpenApiRouter, routes};
+
+mod get {
+    use axum::{body::Body, extract::Path, http::StatusCode};
+    use shared::{
+        ApiError, GetState,
+        models::{node::GetNode, user::GetPermissionManager},
+        response::{ApiResponse, ApiResponseResult},
+    };
+
+    #[utoipa::path(get, path = "/", responses(
+        (status = OK, body = String),
+        (status = BAD_REQUEST, body = ApiError),
+        (status = NOT_FOUND, body = ApiError),
+    ), params(
+        (
+            "node" = uuid::Uuid,
+            description = "The node ID",
+            example = "123e4567-e89b-12d3-a456-426614174000",
+        ),
+        (
+            "file" = String,
+            description = "The log file name",
+            example = "wings.log",
+        ),
+    ))]
+    pub async fn route(
+        state: GetState,
+        permissions: GetPermissionManager,
+        node: GetNode,
+        Path((_node, file)): Path<(uuid::Uuid, String)>,
+    ) -> ApiResponseResult {
+        permissions.has_admin_permission("nodes.read")?;
+
+        if file.contains(['/', '\\']) || file.starts_with('.') {
+            return ApiResponse::error("invalid log file name")
+                .with_status(StatusCode::BAD_REQUEST)
+                .ok();
+        }
+
+        let content = node
+            .api_client(&state.database)
+            .get_system_logs_file(&file)
+            .await?;
+
+        ApiResponse::new(Body::from(content))
+            .with_header("Content-Type", "text/plain")
+            .ok()
+    }
+}
+
+pub fn router(state: &State) -> OpenApiRouter<State> {
+    OpenApiRouter::new()
+        .routes(routes!(get::route))
+        .with_state(state.clone())
+}
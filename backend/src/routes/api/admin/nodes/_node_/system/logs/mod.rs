@@ -0,0 +1,53 @@
+use super::State;
+use utoipa_axum::{router::OpenApiRouter, routes};
+
+mod _file_;
+
+mod get {
+    use serde::Serialize;
+    use shared::{
+        ApiError, GetState,
+        models::{node::GetNode, user::GetPermissionManager},
+        response::{ApiResponse, ApiResponseResult},
+    };
+    use utoipa::ToSchema;
+
+    #[derive(ToSchema, Serialize)]
+    struct Response {
+        #[schema(inline)]
+        log_files: Vec<wings_api::system_logs::get::Response200LogFiles>,
+    }
+
+    #[utoipa::path(get, path = "/", responses(
+        (status = OK, body = inline(Response)),
+        (status = NOT_FOUND, body = ApiError),
+    ), params(
+        (
+            "node" = uuid::Uuid,
+            description = "The node ID",
+            example = "123e4567-e89b-12d3-a456-426614174000",
+        ),
+    ))]
+    pub async fn route(
+        state: GetState,
+        permissions: GetPermissionManager,
+        node: GetNode,
+    ) -> ApiResponseResult {
+        permissions.has_admin_permission("nodes.read")?;
+
+        let log_files = node
+            .api_client(&state.database)
+            .get_system_logs()
+            .await?
+            .log_files;
+
+        ApiResponse::new_serialized(Response { log_files }).ok()
+    }
+}
+
+pub fn router(state: &State) -> OpenApiRouter<State> {
+    OpenApiRouter::new()
+        .routes(routes!(get::route))
+        .nest("/{file}", _file_::router(state))
+        .with_state(state.clone())
+}
@@ -110,6 +110,23 @@ mod post {
 
         memory: i64,
         disk: i64,
+        #[validate(range(min = 0))]
+        #[schema(minimum = 0)]
+        #[serde(default)]
+        cpu: i32,
+
+        #[validate(range(min = -1))]
+        #[schema(minimum = -1)]
+        #[serde(default)]
+        memory_overallocation: i16,
+        #[validate(range(min = -1))]
+        #[schema(minimum = -1)]
+        #[serde(default)]
+        disk_overallocation: i16,
+        #[validate(range(min = -1))]
+        #[schema(minimum = -1)]
+        #[serde(default)]
+        cpu_overallocation: i16,
     }
 
     #[derive(ToSchema, Serialize)]
@@ -179,6 +196,10 @@ mod post {
             data.sftp_port as i32,
             data.memory,
             data.disk,
+            data.cpu,
+            data.memory_overallocation,
+            data.disk_overallocation,
+            data.cpu_overallocation,
         )
         .await
         {
@@ -214,6 +235,10 @@ mod post {
                     "sftp_port": node.sftp_port,
                     "memory": node.memory,
                     "disk": node.disk,
+                    "cpu": node.cpu,
+                    "memory_overallocation": node.memory_overallocation,
+                    "disk_overallocation": node.disk_overallocation,
+                    "cpu_overallocation": node.cpu_overallocation,
                 }),
             )
             .await;
@@ -12,6 +12,7 @@ use shared::{
 };
 use utoipa_axum::{router::OpenApiRouter, routes};
 
+mod reveal;
 mod users;
 
 pub type GetOAuthProvider = shared::extract::ConsumingExtension<OAuthProvider>;
@@ -47,7 +48,7 @@ mod get {
     use crate::routes::api::admin::oauth_providers::_oauth_provider_::GetOAuthProvider;
     use serde::Serialize;
     use shared::{
-        ApiError, GetState,
+        ApiError,
         models::user::GetPermissionManager,
         response::{ApiResponse, ApiResponseResult},
     };
@@ -69,17 +70,13 @@ mod get {
         ),
     ))]
     pub async fn route(
-        state: GetState,
         permissions: GetPermissionManager,
         oauth_provider: GetOAuthProvider,
     ) -> ApiResponseResult {
         permissions.has_admin_permission("oauth-providers.read")?;
 
         ApiResponse::new_serialized(Response {
-            oauth_provider: oauth_provider
-                .0
-                .into_admin_api_object(&state.database)
-                .await?,
+            oauth_provider: oauth_provider.0.into_admin_api_object(),
         })
         .ok()
     }
@@ -141,7 +138,13 @@ mod patch {
     use serde::{Deserialize, Serialize};
     use shared::{
         ApiError, GetState,
-        models::{admin_activity::GetAdminActivityLogger, user::GetPermissionManager},
+        models::{
+            ByUuid,
+            admin_activity::GetAdminActivityLogger,
+            oauth_provider::{OAuthProviderRoleMapping, oidc::OidcDiscovery},
+            role::Role,
+            user::GetPermissionManager,
+        },
         prelude::SqlxErrorExt,
         response::{ApiResponse, ApiResponseResult},
     };
@@ -197,6 +200,17 @@ mod patch {
         #[validate(length(max = 255))]
         #[schema(max_length = 255)]
         name_last_path: Option<String>,
+
+        #[validate(length(max = 255))]
+        #[schema(max_length = 255)]
+        oidc_issuer_url: Option<String>,
+        oidc_logout_enabled: Option<bool>,
+
+        #[validate(length(max = 255))]
+        #[schema(max_length = 255)]
+        role_claim_path: Option<String>,
+        #[schema(inline)]
+        role_mappings: Option<Vec<OAuthProviderRoleMapping>>,
     }
 
     #[derive(ToSchema, Serialize)]
@@ -303,32 +317,93 @@ mod patch {
                 oauth_provider.name_last_path = Some(name_last_path);
             }
         }
+        if let Some(oidc_issuer_url) = data.oidc_issuer_url {
+            if oidc_issuer_url.is_empty() {
+                oauth_provider.oidc_issuer_url = None;
+            } else {
+                let discovery = match OidcDiscovery::fetch(&state, &oidc_issuer_url).await {
+                    Ok(discovery) => discovery,
+                    Err(err) => {
+                        return ApiResponse::error(&format!(
+                            "failed to fetch oidc discovery document: {err}"
+                        ))
+                        .with_status(StatusCode::BAD_REQUEST)
+                        .ok();
+                    }
+                };
+
+                oauth_provider.auth_url = discovery.authorization_endpoint;
+                oauth_provider.token_url = discovery.token_endpoint;
+                oauth_provider.info_url = discovery.userinfo_endpoint.unwrap_or_default();
+                oauth_provider.oidc_issuer_url = Some(oidc_issuer_url);
+            }
+        }
+        if let Some(oidc_logout_enabled) = data.oidc_logout_enabled {
+            oauth_provider.oidc_logout_enabled = oidc_logout_enabled;
+        }
+        if let Some(role_claim_path) = data.role_claim_path {
+            if role_claim_path.is_empty() {
+                oauth_provider.role_claim_path = None;
+            } else {
+                oauth_provider.role_claim_path = Some(role_claim_path);
+            }
+        }
+        if let Some(role_mappings) = data.role_mappings {
+            for mapping in &role_mappings {
+                if Role::by_uuid_optional(&state.database, mapping.role_uuid)
+                    .await?
+                    .is_none()
+                {
+                    return ApiResponse::error("role mapping role not found")
+                        .with_status(StatusCode::NOT_FOUND)
+                        .ok();
+                }
+            }
+
+            oauth_provider.role_mappings = role_mappings;
+        }
+
+        if oauth_provider.oidc_logout_enabled && !oauth_provider.is_oidc() {
+            return ApiResponse::error("oidc logout requires an oidc issuer url")
+                .with_status(StatusCode::BAD_REQUEST)
+                .ok();
+        }
 
-        match sqlx::query!(
-            "UPDATE oauth_providers
+        match sqlx::query(
+            r#"
+            UPDATE oauth_providers
             SET name = $2, description = $3, client_id = $4, client_secret = $5, auth_url = $6,
-                token_url = $7, scopes = $8, identifier_path = $9, email_path = $10, username_path = $11,
-                name_first_path = $12, name_last_path = $13, enabled = $14, login_only = $15,
-                link_viewable = $16, user_manageable = $17
-            WHERE oauth_providers.uuid = $1",
-            oauth_provider.uuid,
-            &oauth_provider.name,
-            oauth_provider.description.as_deref(),
-            &oauth_provider.client_id,
-            oauth_provider.client_secret,
-            oauth_provider.auth_url,
-            oauth_provider.token_url,
-            &oauth_provider.scopes as &[compact_str::CompactString],
-            oauth_provider.identifier_path,
-            oauth_provider.email_path,
-            oauth_provider.username_path,
-            oauth_provider.name_first_path,
-            oauth_provider.name_last_path,
-            oauth_provider.enabled,
-            oauth_provider.login_only,
-            oauth_provider.link_viewable,
-            oauth_provider.user_manageable
+                token_url = $7, info_url = $8, scopes = $9, identifier_path = $10, email_path = $11,
+                username_path = $12, name_first_path = $13, name_last_path = $14,
+                oidc_issuer_url = $15, oidc_logout_enabled = $16, role_claim_path = $17,
+                role_mappings = $18, enabled = $19, login_only = $20, link_viewable = $21,
+                user_manageable = $22, basic_auth = $23
+            WHERE oauth_providers.uuid = $1
+            "#,
         )
+        .bind(oauth_provider.uuid)
+        .bind(&oauth_provider.name)
+        .bind(oauth_provider.description.as_deref())
+        .bind(&oauth_provider.client_id)
+        .bind(&oauth_provider.client_secret)
+        .bind(&oauth_provider.auth_url)
+        .bind(&oauth_provider.token_url)
+        .bind(&oauth_provider.info_url)
+        .bind(&oauth_provider.scopes)
+        .bind(&oauth_provider.identifier_path)
+        .bind(&oauth_provider.email_path)
+        .bind(&oauth_provider.username_path)
+        .bind(&oauth_provider.name_first_path)
+        .bind(&oauth_provider.name_last_path)
+        .bind(&oauth_provider.oidc_issuer_url)
+        .bind(oauth_provider.oidc_logout_enabled)
+        .bind(&oauth_provider.role_claim_path)
+        .bind(serde_json::to_value(&oauth_provider.role_mappings)?)
+        .bind(oauth_provider.enabled)
+        .bind(oauth_provider.login_only)
+        .bind(oauth_provider.link_viewable)
+        .bind(oauth_provider.user_manageable)
+        .bind(oauth_provider.basic_auth)
         .execute(state.database.write())
         .await
         {
@@ -372,6 +447,11 @@ mod patch {
                     "username_path": oauth_provider.username_path,
                     "name_first_path": oauth_provider.name_first_path,
                     "name_last_path": oauth_provider.name_last_path,
+
+                    "oidc_issuer_url": oauth_provider.oidc_issuer_url,
+                    "oidc_logout_enabled": oauth_provider.oidc_logout_enabled,
+                    "role_claim_path": oauth_provider.role_claim_path,
+                    "role_mappings": oauth_provider.role_mappings,
                 }),
             )
             .await;
@@ -385,6 +465,7 @@ pub fn router(state: &State) -> OpenApiRouter<State> {
         .routes(routes!(get::route))
         .routes(routes!(delete::route))
         .routes(routes!(patch::route))
+        .nest("/reveal", reveal::router(state))
         .nest("/users", users::router(state))
         .route_layer(axum::middleware::from_fn_with_state(state.clone(), auth))
         .with_state(state.clone())
@@ -0,0 +1,57 @@
+use super::State;
+use utoipa_axum::{router::OpenApiRouter, routes};
+
+mod post {
+    use crate::routes::api::admin::oauth_providers::_oauth_provider_::GetOAuthProvider;
+    use serde::Serialize;
+    use shared::{
+        ApiError, GetState,
+        models::{admin_activity::GetAdminActivityLogger, user::GetPermissionManager},
+        response::{ApiResponse, ApiResponseResult},
+    };
+    use utoipa::ToSchema;
+
+    #[derive(ToSchema, Serialize)]
+    struct Response {
+        client_secret: compact_str::CompactString,
+    }
+
+    #[utoipa::path(post, path = "/", responses(
+        (status = OK, body = inline(Response)),
+        (status = NOT_FOUND, body = ApiError),
+    ), params(
+        (
+            "oauth_provider" = uuid::Uuid,
+            description = "The oauth provider ID",
+            example = "123e4567-e89b-12d3-a456-426614174000",
+        ),
+    ))]
+    pub async fn route(
+        state: GetState,
+        permissions: GetPermissionManager,
+        oauth_provider: GetOAuthProvider,
+        activity_logger: GetAdminActivityLogger,
+    ) -> ApiResponseResult {
+        permissions.has_admin_permission("oauth-providers.reveal")?;
+
+        let client_secret = oauth_provider.reveal_client_secret(&state.database).await?;
+
+        activity_logger
+            .log(
+                "oauth-provider:reveal",
+                serde_json::json!({
+                    "uuid": oauth_provider.uuid,
+                    "name": oauth_provider.name,
+                }),
+            )
+            .await;
+
+        ApiResponse::new_serialized(Response { client_secret }).ok()
+    }
+}
+
+pub fn router(state: &State) -> OpenApiRouter<State> {
+    OpenApiRouter::new()
+        .routes(routes!(post::route))
+        .with_state(state.clone())
+}
@@ -62,11 +62,16 @@ mod get {
         .await?;
 
         ApiResponse::new_serialized(Response {
-            oauth_providers: oauth_providers
-                .try_async_map(|oauth_provider| {
-                    oauth_provider.into_admin_api_object(&state.database)
-                })
-                .await?,
+            oauth_providers: Pagination {
+                total: oauth_providers.total,
+                per_page: oauth_providers.per_page,
+                page: oauth_providers.page,
+                data: oauth_providers
+                    .data
+                    .into_iter()
+                    .map(|oauth_provider| oauth_provider.into_admin_api_object())
+                    .collect(),
+            },
         })
         .ok()
     }
@@ -78,7 +83,10 @@ mod post {
     use shared::{
         ApiError, GetState,
         models::{
-            admin_activity::GetAdminActivityLogger, oauth_provider::OAuthProvider,
+            ByUuid,
+            admin_activity::GetAdminActivityLogger,
+            oauth_provider::{OAuthProvider, OAuthProviderRoleMapping, oidc::OidcDiscovery},
+            role::Role,
             user::GetPermissionManager,
         },
         response::{ApiResponse, ApiResponseResult},
@@ -107,22 +115,28 @@ mod post {
         #[schema(min_length = 3, max_length = 255)]
         client_secret: compact_str::CompactString,
 
+        #[validate(url, length(max = 255))]
+        #[schema(format = "uri", max_length = 255)]
+        oidc_issuer_url: Option<String>,
+        #[serde(default)]
+        oidc_logout_enabled: bool,
+
         #[validate(length(min = 3, max = 255))]
         #[schema(min_length = 3, max_length = 255)]
-        auth_url: String,
+        auth_url: Option<String>,
         #[validate(length(min = 3, max = 255))]
         #[schema(min_length = 3, max_length = 255)]
-        token_url: String,
+        token_url: Option<String>,
         #[validate(length(min = 3, max = 255))]
         #[schema(min_length = 3, max_length = 255)]
-        info_url: String,
+        info_url: Option<String>,
         #[validate(length(max = 255))]
         #[schema(max_length = 255)]
         scopes: Vec<compact_str::CompactString>,
 
         #[validate(length(min = 3, max = 255))]
         #[schema(min_length = 3, max_length = 255)]
-        identifier_path: String,
+        identifier_path: Option<String>,
         #[validate(length(min = 3, max = 255))]
         #[schema(min_length = 3, max_length = 255)]
         email_path: Option<String>,
@@ -135,6 +149,13 @@ mod post {
         #[validate(length(min = 3, max = 255))]
         #[schema(min_length = 3, max_length = 255)]
         name_last_path: Option<String>,
+
+        #[validate(length(min = 3, max = 255))]
+        #[schema(min_length = 3, max_length = 255)]
+        role_claim_path: Option<String>,
+        #[serde(default)]
+        #[schema(inline)]
+        role_mappings: Vec<OAuthProviderRoleMapping>,
     }
 
     #[derive(ToSchema, Serialize)]
@@ -151,7 +172,7 @@ mod post {
         state: GetState,
         permissions: GetPermissionManager,
         activity_logger: GetAdminActivityLogger,
-        shared::Payload(data): shared::Payload<Payload>,
+        shared::Payload(mut data): shared::Payload<Payload>,
     ) -> ApiResponseResult {
         if let Err(errors) = shared::utils::validate_data(&data) {
             return ApiResponse::new_serialized(ApiError::new_strings_value(errors))
@@ -161,21 +182,79 @@ mod post {
 
         permissions.has_admin_permission("oauth-providers.create")?;
 
+        for mapping in &data.role_mappings {
+            if Role::by_uuid_optional(&state.database, mapping.role_uuid)
+                .await?
+                .is_none()
+            {
+                return ApiResponse::error("role mapping role not found")
+                    .with_status(StatusCode::NOT_FOUND)
+                    .ok();
+            }
+        }
+
+        if let Some(oidc_issuer_url) = &data.oidc_issuer_url {
+            let discovery = match OidcDiscovery::fetch(&state, oidc_issuer_url).await {
+                Ok(discovery) => discovery,
+                Err(err) => {
+                    return ApiResponse::error(&format!(
+                        "failed to fetch oidc discovery document: {err}"
+                    ))
+                    .with_status(StatusCode::BAD_REQUEST)
+                    .ok();
+                }
+            };
+
+            data.auth_url = Some(discovery.authorization_endpoint);
+            data.token_url = Some(discovery.token_endpoint);
+            data.info_url = Some(discovery.userinfo_endpoint.unwrap_or_default());
+
+            data.identifier_path.get_or_insert_with(|| "$.sub".into());
+            data.email_path.get_or_insert_with(|| "$.email".into());
+            data.username_path
+                .get_or_insert_with(|| "$.preferred_username".into());
+            data.name_first_path
+                .get_or_insert_with(|| "$.given_name".into());
+            data.name_last_path
+                .get_or_insert_with(|| "$.family_name".into());
+        } else if data.oidc_logout_enabled {
+            return ApiResponse::error("oidc logout requires an oidc issuer url")
+                .with_status(StatusCode::BAD_REQUEST)
+                .ok();
+        }
+
+        let (Some(auth_url), Some(token_url), Some(info_url), Some(identifier_path)) = (
+            &data.auth_url,
+            &data.token_url,
+            &data.info_url,
+            &data.identifier_path,
+        ) else {
+            return ApiResponse::error(
+                "auth_url, token_url, info_url and identifier_path are required without an oidc issuer url",
+            )
+            .with_status(StatusCode::BAD_REQUEST)
+            .ok();
+        };
+
         let oauth_provider = match OAuthProvider::create(
             &state.database,
             &data.name,
             data.description.as_deref(),
             &data.client_id,
             &data.client_secret,
-            &data.auth_url,
-            &data.token_url,
-            &data.info_url,
+            auth_url,
+            token_url,
+            info_url,
             &data.scopes,
-            &data.identifier_path,
+            identifier_path,
             data.email_path.as_deref(),
             data.username_path.as_deref(),
             data.name_first_path.as_deref(),
             data.name_last_path.as_deref(),
+            data.oidc_issuer_url.as_deref(),
+            data.oidc_logout_enabled,
+            data.role_claim_path.as_deref(),
+            &data.role_mappings,
             data.enabled,
             data.login_only,
             data.link_viewable,
@@ -224,14 +303,17 @@ mod post {
                     "username_path": oauth_provider.username_path,
                     "name_first_path": oauth_provider.name_first_path,
                     "name_last_path": oauth_provider.name_last_path,
+
+                    "oidc_issuer_url": oauth_provider.oidc_issuer_url,
+                    "oidc_logout_enabled": oauth_provider.oidc_logout_enabled,
+                    "role_claim_path": oauth_provider.role_claim_path,
+                    "role_mappings": oauth_provider.role_mappings,
                 }),
             )
             .await;
 
         ApiResponse::new_serialized(Response {
-            oauth_provider: oauth_provider
-                .into_admin_api_object(&state.database)
-                .await?,
+            oauth_provider: oauth_provider.into_admin_api_object(),
         })
         .ok()
     }
@@ -151,6 +151,7 @@ mod patch {
         description: Option<compact_str::CompactString>,
 
         require_two_factor: Option<bool>,
+        require_passkey: Option<bool>,
 
         #[validate(custom(function = "shared::permissions::validate_admin_permissions"))]
         admin_permissions: Option<Vec<compact_str::CompactString>>,
@@ -201,6 +202,9 @@ mod patch {
         if let Some(require_two_factor) = data.require_two_factor {
             role.require_two_factor = require_two_factor;
         }
+        if let Some(require_passkey) = data.require_passkey {
+            role.require_passkey = require_passkey;
+        }
         if let Some(admin_permissions) = data.admin_permissions {
             role.admin_permissions = Arc::new(admin_permissions);
         }
@@ -208,17 +212,21 @@ mod patch {
             role.server_permissions = Arc::new(server_permissions);
         }
 
-        match sqlx::query!(
-            "UPDATE roles
-            SET name = $2, description = $3, require_two_factor = $4, admin_permissions = $5, server_permissions = $6
-            WHERE roles.uuid = $1",
-            role.uuid,
-            &role.name,
-            role.description.as_deref(),
-            role.require_two_factor,
-            &*role.admin_permissions as &[compact_str::CompactString],
-            &*role.server_permissions as &[compact_str::CompactString]
+        match sqlx::query(
+            r#"
+            UPDATE roles
+            SET name = $2, description = $3, require_two_factor = $4, require_passkey = $5,
+                admin_permissions = $6, server_permissions = $7
+            WHERE roles.uuid = $1
+            "#,
         )
+        .bind(role.uuid)
+        .bind(&role.name)
+        .bind(role.description.as_deref())
+        .bind(role.require_two_factor)
+        .bind(role.require_passkey)
+        .bind(&*role.admin_permissions)
+        .bind(&*role.server_permissions)
         .execute(state.database.write())
         .await
         {
@@ -245,6 +253,7 @@ mod patch {
                     "name": role.name,
                     "description": role.description,
                     "require_two_factor": role.require_two_factor,
+                    "require_passkey": role.require_passkey,
                     "admin_permissions": role.admin_permissions,
                     "server_permissions": role.server_permissions,
                 }),
@@ -95,6 +95,8 @@ mod post {
         description: Option<compact_str::CompactString>,
 
         require_two_factor: bool,
+        #[serde(default)]
+        require_passkey: bool,
 
         #[validate(custom(function = "shared::permissions::validate_admin_permissions"))]
         admin_permissions: Vec<compact_str::CompactString>,
@@ -131,6 +133,7 @@ mod post {
             &data.name,
             data.description.as_deref(),
             data.require_two_factor,
+            data.require_passkey,
             &data.admin_permissions,
             &data.server_permissions,
         )
@@ -159,6 +162,7 @@ mod post {
                     "name": role.name,
                     "description": role.description,
                     "require_two_factor": role.require_two_factor,
+                    "require_passkey": role.require_passkey,
                     "admin_permissions": role.admin_permissions,
                     "server_permissions": role.server_permissions,
                 }),
@@ -0,0 +1,370 @@
+use super::State;
+use axum::{
+    extract::{Path, Request},
+    http::StatusCode,
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use shared::{
+    GetState,
+    models::{ByUuid, saml_provider::SamlProvider, user::GetPermissionManager},
+    response::ApiResponse,
+};
+use utoipa_axum::{router::OpenApiRouter, routes};
+
+mod users;
+
+pub type GetSamlProvider = shared::extract::ConsumingExtension<SamlProvider>;
+
+pub async fn auth(
+    state: GetState,
+    permissions: GetPermissionManager,
+    Path(saml_provider): Path<uuid::Uuid>,
+    mut req: Request,
+    next: Next,
+) -> Result<Response, StatusCode> {
+    if let Err(err) = permissions.has_admin_permission("saml-providers.read") {
+        return Ok(err.into_response());
+    }
+
+    let saml_provider = SamlProvider::by_uuid_optional(&state.database, saml_provider).await;
+    let saml_provider = match saml_provider {
+        Ok(Some(saml_provider)) => saml_provider,
+        Ok(None) => {
+            return Ok(ApiResponse::error("saml provider not found")
+                .with_status(StatusCode::NOT_FOUND)
+                .into_response());
+        }
+        Err(err) => return Ok(ApiResponse::from(err).into_response()),
+    };
+
+    req.extensions_mut().insert(saml_provider);
+
+    Ok(next.run(req).await)
+}
+
+mod get {
+    use crate::routes::api::admin::saml_providers::_saml_provider_::GetSamlProvider;
+    use serde::Serialize;
+    use shared::{
+        ApiError,
+        models::user::GetPermissionManager,
+        response::{ApiResponse, ApiResponseResult},
+    };
+    use utoipa::ToSchema;
+
+    #[derive(ToSchema, Serialize)]
+    struct Response {
+        saml_provider: shared::models::saml_provider::AdminApiSamlProvider,
+    }
+
+    #[utoipa::path(get, path = "/", responses(
+        (status = OK, body = inline(Response)),
+        (status = NOT_FOUND, body = ApiError),
+    ), params(
+        (
+            "saml_provider" = uuid::Uuid,
+            description = "The saml provider ID",
+            example = "123e4567-e89b-12d3-a456-426614174000",
+        ),
+    ))]
+    pub async fn route(
+        permissions: GetPermissionManager,
+        saml_provider: GetSamlProvider,
+    ) -> ApiResponseResult {
+        permissions.has_admin_permission("saml-providers.read")?;
+
+        ApiResponse::new_serialized(Response {
+            saml_provider: saml_provider.0.into_admin_api_object(),
+        })
+        .ok()
+    }
+}
+
+mod delete {
+    use crate::routes::api::admin::saml_providers::_saml_provider_::GetSamlProvider;
+    use serde::Serialize;
+    use shared::{
+        ApiError, GetState,
+        models::{
+            DeletableModel, admin_activity::GetAdminActivityLogger, user::GetPermissionManager,
+        },
+        response::{ApiResponse, ApiResponseResult},
+    };
+    use utoipa::ToSchema;
+
+    #[derive(ToSchema, Serialize)]
+    struct Response {}
+
+    #[utoipa::path(delete, path = "/", responses(
+        (status = OK, body = inline(Response)),
+        (status = NOT_FOUND, body = ApiError),
+        (status = CONFLICT, body = ApiError),
+    ), params(
+        (
+            "saml_provider" = uuid::Uuid,
+            description = "The saml provider ID",
+            example = "123e4567-e89b-12d3-a456-426614174000",
+        ),
+    ))]
+    pub async fn route(
+        state: GetState,
+        permissions: GetPermissionManager,
+        saml_provider: GetSamlProvider,
+        activity_logger: GetAdminActivityLogger,
+    ) -> ApiResponseResult {
+        permissions.has_admin_permission("saml-providers.delete")?;
+
+        saml_provider.delete(&state, ()).await?;
+
+        activity_logger
+            .log(
+                "saml-provider:delete",
+                serde_json::json!({
+                    "uuid": saml_provider.uuid,
+                    "name": saml_provider.name,
+                }),
+            )
+            .await;
+
+        ApiResponse::new_serialized(Response {}).ok()
+    }
+}
+
+mod patch {
+    use crate::routes::api::admin::saml_providers::_saml_provider_::GetSamlProvider;
+    use axum::http::StatusCode;
+    use serde::{Deserialize, Serialize};
+    use shared::{
+        ApiError, GetState,
+        models::{admin_activity::GetAdminActivityLogger, user::GetPermissionManager},
+        prelude::SqlxErrorExt,
+        response::{ApiResponse, ApiResponseResult},
+    };
+    use utoipa::ToSchema;
+    use validator::Validate;
+
+    #[derive(ToSchema, Validate, Deserialize)]
+    pub struct Payload {
+        #[validate(length(min = 3, max = 255))]
+        #[schema(min_length = 3, max_length = 255)]
+        name: Option<compact_str::CompactString>,
+        #[validate(length(max = 1024))]
+        #[schema(max_length = 1024)]
+        description: Option<compact_str::CompactString>,
+        enabled: Option<bool>,
+        login_only: Option<bool>,
+        link_viewable: Option<bool>,
+        user_manageable: Option<bool>,
+
+        #[validate(length(min = 3, max = 255))]
+        #[schema(min_length = 3, max_length = 255)]
+        idp_entity_id: Option<String>,
+        #[validate(url, length(max = 255))]
+        #[schema(format = "uri", max_length = 255)]
+        idp_sso_url: Option<String>,
+        #[validate(length(min = 3))]
+        #[schema(min_length = 3)]
+        idp_certificate: Option<String>,
+
+        #[validate(length(max = 255))]
+        #[schema(max_length = 255)]
+        identifier_attribute: Option<String>,
+        #[validate(length(max = 255))]
+        #[schema(max_length = 255)]
+        email_attribute: Option<String>,
+        #[validate(length(max = 255))]
+        #[schema(max_length = 255)]
+        username_attribute: Option<String>,
+        #[validate(length(max = 255))]
+        #[schema(max_length = 255)]
+        name_first_attribute: Option<String>,
+        #[validate(length(max = 255))]
+        #[schema(max_length = 255)]
+        name_last_attribute: Option<String>,
+    }
+
+    #[derive(ToSchema, Serialize)]
+    struct Response {}
+
+    #[utoipa::path(patch, path = "/", responses(
+        (status = OK, body = inline(Response)),
+        (status = NOT_FOUND, body = ApiError),
+        (status = BAD_REQUEST, body = ApiError),
+        (status = CONFLICT, body = ApiError),
+    ), params(
+        (
+            "saml_provider" = uuid::Uuid,
+            description = "The saml provider ID",
+            example = "123e4567-e89b-12d3-a456-426614174000",
+        ),
+    ), request_body = inline(Payload))]
+    pub async fn route(
+        state: GetState,
+        permissions: GetPermissionManager,
+        mut saml_provider: GetSamlProvider,
+        activity_logger: GetAdminActivityLogger,
+        shared::Payload(data): shared::Payload<Payload>,
+    ) -> ApiResponseResult {
+        if let Err(errors) = shared::utils::validate_data(&data) {
+            return ApiResponse::new_serialized(ApiError::new_strings_value(errors))
+                .with_status(StatusCode::BAD_REQUEST)
+                .ok();
+        }
+
+        permissions.has_admin_permission("saml-providers.update")?;
+
+        if let Some(name) = data.name {
+            saml_provider.name = name;
+        }
+        if let Some(description) = data.description {
+            if description.is_empty() {
+                saml_provider.description = None;
+            } else {
+                saml_provider.description = Some(description);
+            }
+        }
+        if let Some(enabled) = data.enabled {
+            saml_provider.enabled = enabled;
+        }
+        if let Some(login_only) = data.login_only {
+            saml_provider.login_only = login_only;
+        }
+        if let Some(link_viewable) = data.link_viewable {
+            saml_provider.link_viewable = link_viewable;
+        }
+        if let Some(user_manageable) = data.user_manageable {
+            saml_provider.user_manageable = user_manageable;
+        }
+        if let Some(idp_entity_id) = data.idp_entity_id {
+            saml_provider.idp_entity_id = idp_entity_id;
+        }
+        if let Some(idp_sso_url) = data.idp_sso_url {
+            saml_provider.idp_sso_url = idp_sso_url;
+        }
+        if let Some(idp_certificate) = data.idp_certificate {
+            saml_provider.idp_certificate =
+                match shared::saml::normalize_certificate(&idp_certificate) {
+                    Ok(idp_certificate) => idp_certificate,
+                    Err(err) => {
+                        return ApiResponse::error(&format!("invalid idp certificate: {err}"))
+                            .with_status(StatusCode::BAD_REQUEST)
+                            .ok();
+                    }
+                };
+        }
+        if let Some(identifier_attribute) = data.identifier_attribute {
+            if identifier_attribute.is_empty() {
+                saml_provider.identifier_attribute = None;
+            } else {
+                saml_provider.identifier_attribute = Some(identifier_attribute);
+            }
+        }
+        if let Some(email_attribute) = data.email_attribute {
+            if email_attribute.is_empty() {
+                saml_provider.email_attribute = None;
+            } else {
+                saml_provider.email_attribute = Some(email_attribute);
+            }
+        }
+        if let Some(username_attribute) = data.username_attribute {
+            if username_attribute.is_empty() {
+                saml_provider.username_attribute = None;
+            } else {
+                saml_provider.username_attribute = Some(username_attribute);
+            }
+        }
+        if let Some(name_first_attribute) = data.name_first_attribute {
+            if name_first_attribute.is_empty() {
+                saml_provider.name_first_attribute = None;
+            } else {
+                saml_provider.name_first_attribute = Some(name_first_attribute);
+            }
+        }
+        if let Some(name_last_attribute) = data.name_last_attribute {
+            if name_last_attribute.is_empty() {
+                saml_provider.name_last_attribute = None;
+            } else {
+                saml_provider.name_last_attribute = Some(name_last_attribute);
+            }
+        }
+
+        match sqlx::query(
+            r#"
+            UPDATE saml_providers
+            SET name = $2, description = $3, idp_entity_id = $4, idp_sso_url = $5,
+                idp_certificate = $6, identifier_attribute = $7, email_attribute = $8,
+                username_attribute = $9, name_first_attribute = $10, name_last_attribute = $11,
+                enabled = $12, login_only = $13, link_viewable = $14, user_manageable = $15
+            WHERE saml_providers.uuid = $1
+            "#,
+        )
+        .bind(saml_provider.uuid)
+        .bind(&saml_provider.name)
+        .bind(saml_provider.description.as_deref())
+        .bind(&saml_provider.idp_entity_id)
+        .bind(&saml_provider.idp_sso_url)
+        .bind(&saml_provider.idp_certificate)
+        .bind(&saml_provider.identifier_attribute)
+        .bind(&saml_provider.email_attribute)
+        .bind(&saml_provider.username_attribute)
+        .bind(&saml_provider.name_first_attribute)
+        .bind(&saml_provider.name_last_attribute)
+        .bind(saml_provider.enabled)
+        .bind(saml_provider.login_only)
+        .bind(saml_provider.link_viewable)
+        .bind(saml_provider.user_manageable)
+        .execute(state.database.write())
+        .await
+        {
+            Ok(_) => {}
+            Err(err) if err.is_unique_violation() => {
+                return ApiResponse::error("saml provider with name already exists")
+                    .with_status(StatusCode::CONFLICT)
+                    .ok();
+            }
+            Err(err) => {
+                tracing::error!("failed to update saml provider: {:?}", err);
+
+                return ApiResponse::error("failed to update saml provider")
+                    .with_status(StatusCode::INTERNAL_SERVER_ERROR)
+                    .ok();
+            }
+        }
+
+        activity_logger
+            .log(
+                "saml-provider:update",
+                serde_json::json!({
+                    "name": saml_provider.name,
+                    "description": saml_provider.description,
+                    "enabled": saml_provider.enabled,
+                    "login_only": saml_provider.login_only,
+                    "link_viewable": saml_provider.link_viewable,
+                    "user_manageable": saml_provider.user_manageable,
+
+                    "idp_entity_id": saml_provider.idp_entity_id,
+                    "idp_sso_url": saml_provider.idp_sso_url,
+
+                    "identifier_attribute": saml_provider.identifier_attribute,
+                    "email_attribute": saml_provider.email_attribute,
+                    "username_attribute": saml_provider.username_attribute,
+                    "name_first_attribute": saml_provider.name_first_attribute,
+                    "name_last_attribute": saml_provider.name_last_attribute,
+                }),
+            )
+            .await;
+
+        ApiResponse::new_serialized(Response {}).ok()
+    }
+}
+
+pub fn router(state: &State) -> OpenApiRouter<State> {
+    OpenApiRouter::new()
+        .routes(routes!(get::route))
+        .routes(routes!(delete::route))
+        .routes(routes!(patch::route))
+        .nest("/users", users::router(state))
+        .route_layer(axum::middleware::from_fn_with_state(state.clone(), auth))
+        .with_state(state.clone())
+}
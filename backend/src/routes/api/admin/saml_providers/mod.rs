@@ -0,0 +1,247 @@
+use super::State;
+use utoipa_axum::{router::OpenApiRouter, routes};
+
+mod _saml_provider_;
+
+mod get {
+    use axum::{extract::Query, http::StatusCode};
+    use serde::Serialize;
+    use shared::{
+        ApiError, GetState,
+        models::{
+            Pagination, PaginationParamsWithSearch, saml_provider::SamlProvider,
+            user::GetPermissionManager,
+        },
+        response::{ApiResponse, ApiResponseResult},
+    };
+    use utoipa::ToSchema;
+
+    #[derive(ToSchema, Serialize)]
+    struct Response {
+        #[schema(inline)]
+        saml_providers: Pagination<shared::models::saml_provider::AdminApiSamlProvider>,
+    }
+
+    #[utoipa::path(get, path = "/", responses(
+        (status = OK, body = inline(Response)),
+    ), params(
+        (
+            "page" = i64, Query,
+            description = "The page number",
+            example = "1",
+        ),
+        (
+            "per_page" = i64, Query,
+            description = "The number of items per page",
+            example = "10",
+        ),
+        (
+            "search" = Option<String>, Query,
+            description = "Search term for items",
+        ),
+    ))]
+    pub async fn route(
+        state: GetState,
+        permissions: GetPermissionManager,
+        Query(params): Query<PaginationParamsWithSearch>,
+    ) -> ApiResponseResult {
+        if let Err(errors) = shared::utils::validate_data(&params) {
+            return ApiResponse::new_serialized(ApiError::new_strings_value(errors))
+                .with_status(StatusCode::BAD_REQUEST)
+                .ok();
+        }
+
+        permissions.has_admin_permission("saml-providers.read")?;
+
+        let saml_providers = SamlProvider::all_with_pagination(
+            &state.database,
+            params.page,
+            params.per_page,
+            params.search.as_deref(),
+        )
+        .await?;
+
+        ApiResponse::new_serialized(Response {
+            saml_providers: Pagination {
+                total: saml_providers.total,
+                per_page: saml_providers.per_page,
+                page: saml_providers.page,
+                data: saml_providers
+                    .data
+                    .into_iter()
+                    .map(|saml_provider| saml_provider.into_admin_api_object())
+                    .collect(),
+            },
+        })
+        .ok()
+    }
+}
+
+mod post {
+    use axum::http::StatusCode;
+    use serde::{Deserialize, Serialize};
+    use shared::{
+        ApiError, GetState,
+        models::{
+            admin_activity::GetAdminActivityLogger, saml_provider::SamlProvider,
+            user::GetPermissionManager,
+        },
+        response::{ApiResponse, ApiResponseResult},
+    };
+    use utoipa::ToSchema;
+    use validator::Validate;
+
+    #[derive(ToSchema, Validate, Deserialize)]
+    pub struct Payload {
+        #[validate(length(min = 3, max = 255))]
+        #[schema(min_length = 3, max_length = 255)]
+        name: compact_str::CompactString,
+        #[validate(length(max = 1024))]
+        #[schema(max_length = 1024)]
+        description: Option<compact_str::CompactString>,
+        enabled: bool,
+        login_only: bool,
+        link_viewable: bool,
+        user_manageable: bool,
+
+        #[validate(length(min = 3, max = 255))]
+        #[schema(min_length = 3, max_length = 255)]
+        idp_entity_id: String,
+        #[validate(url, length(max = 255))]
+        #[schema(format = "uri", max_length = 255)]
+        idp_sso_url: String,
+        #[validate(length(min = 3))]
+        #[schema(min_length = 3)]
+        idp_certificate: String,
+
+        #[validate(length(min = 1, max = 255))]
+        #[schema(min_length = 1, max_length = 255)]
+        identifier_attribute: Option<String>,
+        #[validate(length(min = 1, max = 255))]
+        #[schema(min_length = 1, max_length = 255)]
+        email_attribute: Option<String>,
+        #[validate(length(min = 1, max = 255))]
+        #[schema(min_length = 1, max_length = 255)]
+        username_attribute: Option<String>,
+        #[validate(length(min = 1, max = 255))]
+        #[schema(min_length = 1, max_length = 255)]
+        name_first_attribute: Option<String>,
+        #[validate(length(min = 1, max = 255))]
+        #[schema(min_length = 1, max_length = 255)]
+        name_last_attribute: Option<String>,
+    }
+
+    #[derive(ToSchema, Serialize)]
+    struct Response {
+        saml_provider: shared::models::saml_provider::AdminApiSamlProvider,
+    }
+
+    #[utoipa::path(post, path = "/", responses(
+        (status = OK, body = inline(Response)),
+        (status = BAD_REQUEST, body = ApiError),
+        (status = CONFLICT, body = ApiError),
+    ), request_body = inline(Payload))]
+    pub async fn route(
+        state: GetState,
+        permissions: GetPermissionManager,
+        activity_logger: GetAdminActivityLogger,
+        shared::Payload(data): shared::Payload<Payload>,
+    ) -> ApiResponseResult {
+        if let Err(errors) = shared::utils::validate_data(&data) {
+            return ApiResponse::new_serialized(ApiError::new_strings_value(errors))
+                .with_status(StatusCode::BAD_REQUEST)
+                .ok();
+        }
+
+        permissions.has_admin_permission("saml-providers.create")?;
+
+        let idp_certificate = match shared::saml::normalize_certificate(&data.idp_certificate) {
+            Ok(idp_certificate) => idp_certificate,
+            Err(err) => {
+                return ApiResponse::error(&format!("invalid idp certificate: {err}"))
+                    .with_status(StatusCode::BAD_REQUEST)
+                    .ok();
+            }
+        };
+
+        let settings = state.settings.get().await?;
+        let common_name = settings.app.name.to_string();
+        drop(settings);
+
+        let (sp_certificate, sp_private_key) =
+            tokio::task::spawn_blocking(move || shared::saml::generate_key_pair(&common_name))
+                .await??;
+
+        let saml_provider = match SamlProvider::create(
+            &state.database,
+            &data.name,
+            data.description.as_deref(),
+            &data.idp_entity_id,
+            &data.idp_sso_url,
+            &idp_certificate,
+            &sp_certificate,
+            &sp_private_key,
+            data.identifier_attribute.as_deref(),
+            data.email_attribute.as_deref(),
+            data.username_attribute.as_deref(),
+            data.name_first_attribute.as_deref(),
+            data.name_last_attribute.as_deref(),
+            data.enabled,
+            data.login_only,
+            data.link_viewable,
+            data.user_manageable,
+        )
+        .await
+        {
+            Ok(saml_provider) => saml_provider,
+            Err(err) if err.is_unique_violation() => {
+                return ApiResponse::error("saml provider with name already exists")
+                    .with_status(StatusCode::CONFLICT)
+                    .ok();
+            }
+            Err(err) => {
+                tracing::error!("failed to create saml provider: {:?}", err);
+
+                return ApiResponse::error("failed to create saml provider")
+                    .with_status(StatusCode::INTERNAL_SERVER_ERROR)
+                    .ok();
+            }
+        };
+
+        activity_logger
+            .log(
+                "saml-provider:create",
+                serde_json::json!({
+                    "name": saml_provider.name,
+                    "description": saml_provider.description,
+                    "enabled": saml_provider.enabled,
+                    "login_only": saml_provider.login_only,
+                    "link_viewable": saml_provider.link_viewable,
+                    "user_manageable": saml_provider.user_manageable,
+
+                    "idp_entity_id": saml_provider.idp_entity_id,
+                    "idp_sso_url": saml_provider.idp_sso_url,
+
+                    "identifier_attribute": saml_provider.identifier_attribute,
+                    "email_attribute": saml_provider.email_attribute,
+                    "username_attribute": saml_provider.username_attribute,
+                    "name_first_attribute": saml_provider.name_first_attribute,
+                    "name_last_attribute": saml_provider.name_last_attribute,
+                }),
+            )
+            .await;
+
+        ApiResponse::new_serialized(Response {
+            saml_provider: saml_provider.into_admin_api_object(),
+        })
+        .ok()
+    }
+}
+
+pub fn router(state: &State) -> OpenApiRouter<State> {
+    OpenApiRouter::new()
+        .routes(routes!(get::route))
+        .routes(routes!(post::route))
+        .nest("/{saml_provider}", _saml_provider_::router(state))
+        .with_state(state.clone())
+}
@@ -0,0 +1,417 @@
+use super::State;
+use axum::{
+    extract::{Path, Request},
+    http::StatusCode,
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use shared::{
+    GetState,
+    models::{ByUuid, server_template::ServerTemplate, user::GetPermissionManager},
+    response::ApiResponse,
+};
+use utoipa_axum::{router::OpenApiRouter, routes};
+
+pub type GetServerTemplate = shared::extract::ConsumingExtension<ServerTemplate>;
+
+pub async fn auth(
+    state: GetState,
+    permissions: GetPermissionManager,
+    Path(template): Path<uuid::Uuid>,
+    mut req: Request,
+    next: Next,
+) -> Result<Response, StatusCode> {
+    if let Err(err) = permissions.has_admin_permission("server-templates.read") {
+        return Ok(err.into_response());
+    }
+
+    let template = ServerTemplate::by_uuid_optional(&state.database, template).await;
+    let template = match template {
+        Ok(Some(template)) => template,
+        Ok(None) => {
+            return Ok(ApiResponse::error("server template not found")
+                .with_status(StatusCode::NOT_FOUND)
+                .into_response());
+        }
+        Err(err) => return Ok(ApiResponse::from(err).into_response()),
+    };
+
+    req.extensions_mut().insert(template);
+
+    Ok(next.run(req).await)
+}
+
+mod get {
+    use crate::routes::api::admin::server_templates::_template_::GetServerTemplate;
+    use serde::Serialize;
+    use shared::{
+        ApiError,
+        models::user::GetPermissionManager,
+        response::{ApiResponse, ApiResponseResult},
+    };
+    use utoipa::ToSchema;
+
+    #[derive(ToSchema, Serialize)]
+    struct Response {
+        template: shared::models::server_template::AdminApiServerTemplate,
+    }
+
+    #[utoipa::path(get, path = "/", responses(
+        (status = OK, body = inline(Response)),
+        (status = NOT_FOUND, body = ApiError),
+    ), params(
+        (
+            "template" = uuid::Uuid,
+            description = "The server template ID",
+            example = "123e4567-e89b-12d3-a456-426614174000",
+        ),
+    ))]
+    pub async fn route(
+        permissions: GetPermissionManager,
+        template: GetServerTemplate,
+    ) -> ApiResponseResult {
+        permissions.has_admin_permission("server-templates.read")?;
+
+        ApiResponse::new_serialized(Response {
+            template: template.0.into_admin_api_object(),
+        })
+        .ok()
+    }
+}
+
+mod delete {
+    use crate::routes::api::admin::server_templates::_template_::GetServerTemplate;
+    use serde::Serialize;
+    use shared::{
+        ApiError, GetState,
+        models::{
+            DeletableModel, admin_activity::GetAdminActivityLogger, user::GetPermissionManager,
+        },
+        response::{ApiResponse, ApiResponseResult},
+    };
+    use utoipa::ToSchema;
+
+    #[derive(ToSchema, Serialize)]
+    struct Response {}
+
+    #[utoipa::path(delete, path = "/", responses(
+        (status = OK, body = inline(Response)),
+        (status = NOT_FOUND, body = ApiError),
+    ), params(
+        (
+            "template" = uuid::Uuid,
+            description = "The server template ID",
+            example = "123e4567-e89b-12d3-a456-426614174000",
+        ),
+    ))]
+    pub async fn route(
+        state: GetState,
+        permissions: GetPermissionManager,
+        activity_logger: GetAdminActivityLogger,
+        template: GetServerTemplate,
+    ) -> ApiResponseResult {
+        permissions.has_admin_permission("server-templates.delete")?;
+
+        template.delete(&state, ()).await?;
+
+        activity_logger
+            .log(
+                "server-template:delete",
+                serde_json::json!({
+                    "uuid": template.uuid,
+                    "name": template.name,
+                }),
+            )
+            .await;
+
+        ApiResponse::new_serialized(Response {}).ok()
+    }
+}
+
+mod patch {
+    use crate::routes::api::admin::server_templates::_template_::GetServerTemplate;
+    use axum::http::StatusCode;
+    use serde::{Deserialize, Serialize};
+    use shared::{
+        ApiError, GetState,
+        models::{
+            ByUuid, admin_activity::GetAdminActivityLogger,
+            backup_configurations::BackupConfiguration, nest_egg::NestEgg,
+            server_template::ServerTemplate, user::GetPermissionManager,
+        },
+        prelude::SqlxErrorExt,
+        response::{ApiResponse, ApiResponseResult},
+    };
+    use std::{collections::BTreeMap, str::FromStr};
+    use utoipa::ToSchema;
+    use validator::Validate;
+
+    #[derive(ToSchema, Validate, Deserialize)]
+    pub struct Payload {
+        #[validate(length(min = 3, max = 255))]
+        #[schema(min_length = 3, max_length = 255)]
+        name: Option<compact_str::CompactString>,
+        #[validate(length(max = 1024))]
+        #[schema(max_length = 1024)]
+        description: Option<compact_str::CompactString>,
+
+        egg_uuid: Option<uuid::Uuid>,
+        backup_configuration_uuid: Option<uuid::Uuid>,
+
+        #[validate(nested)]
+        limits: Option<shared::models::server::ApiServerLimits>,
+        pinned_cpus: Option<Vec<i16>>,
+
+        #[validate(length(min = 1, max = 8192))]
+        #[schema(min_length = 1, max_length = 8192)]
+        startup: Option<compact_str::CompactString>,
+        #[validate(length(min = 2, max = 255))]
+        #[schema(min_length = 2, max_length = 255)]
+        image: Option<compact_str::CompactString>,
+        #[validate(length(max = 255))]
+        #[schema(max_length = 255)]
+        timezone: Option<compact_str::CompactString>,
+
+        hugepages_passthrough_enabled: Option<bool>,
+        kvm_passthrough_enabled: Option<bool>,
+
+        #[validate(nested)]
+        feature_limits: Option<shared::models::server::ApiServerFeatureLimits>,
+        variables: Option<BTreeMap<compact_str::CompactString, compact_str::CompactString>>,
+        deployment: Option<Option<shared::models::server::ApiServerDeployment>>,
+    }
+
+    #[derive(ToSchema, Serialize)]
+    struct Response {}
+
+    #[utoipa::path(patch, path = "/", responses(
+        (status = OK, body = inline(Response)),
+        (status = NOT_FOUND, body = ApiError),
+        (status = BAD_REQUEST, body = ApiError),
+        (status = CONFLICT, body = ApiError),
+    ), params(
+        (
+            "template" = uuid::Uuid,
+            description = "The server template ID",
+            example = "123e4567-e89b-12d3-a456-426614174000",
+        ),
+    ), request_body = inline(Payload))]
+    pub async fn route(
+        state: GetState,
+        permissions: GetPermissionManager,
+        activity_logger: GetAdminActivityLogger,
+        mut template: GetServerTemplate,
+        shared::Payload(data): shared::Payload<Payload>,
+    ) -> ApiResponseResult {
+        if let Err(errors) = shared::utils::validate_data(&data) {
+            return ApiResponse::new_serialized(ApiError::new_strings_value(errors))
+                .with_status(StatusCode::BAD_REQUEST)
+                .ok();
+        }
+
+        permissions.has_admin_permission("server-templates.update")?;
+
+        if let Some(name) = data.name {
+            template.name = name;
+        }
+        if let Some(description) = data.description {
+            if description.is_empty() {
+                template.description = None;
+            } else {
+                template.description = Some(description);
+            }
+        }
+        if let Some(egg_uuid) = data.egg_uuid {
+            if NestEgg::by_uuid_optional(&state.database, egg_uuid)
+                .await?
+                .is_none()
+            {
+                return ApiResponse::error("egg not found")
+                    .with_status(StatusCode::NOT_FOUND)
+                    .ok();
+            }
+
+            template.egg_uuid = egg_uuid;
+        }
+        if let Some(backup_configuration_uuid) = data.backup_configuration_uuid {
+            if backup_configuration_uuid.is_nil() {
+                template.backup_configuration_uuid = None;
+            } else {
+                if BackupConfiguration::by_uuid_optional(&state.database, backup_configuration_uuid)
+                    .await?
+                    .is_none()
+                {
+                    return ApiResponse::error("backup configuration not found")
+                        .with_status(StatusCode::NOT_FOUND)
+                        .ok();
+                }
+
+                template.backup_configuration_uuid = Some(backup_configuration_uuid);
+            }
+        }
+        if let Some(limits) = &data.limits {
+            template.cpu = limits.cpu;
+            template.memory = limits.memory;
+            template.swap = limits.swap;
+            template.disk = limits.disk;
+            template.io_weight = limits.io_weight;
+        }
+        if let Some(pinned_cpus) = data.pinned_cpus {
+            template.pinned_cpus = pinned_cpus;
+        }
+        if let Some(startup) = data.startup {
+            template.startup = startup;
+        }
+        if let Some(image) = data.image {
+            template.image = image;
+        }
+        if let Some(timezone) = data.timezone {
+            if timezone.is_empty() {
+                template.timezone = None;
+            } else {
+                if chrono_tz::Tz::from_str(&timezone).is_err() {
+                    return ApiResponse::error("invalid timezone")
+                        .with_status(StatusCode::BAD_REQUEST)
+                        .ok();
+                }
+
+                template.timezone = Some(timezone);
+            }
+        }
+        if let Some(hugepages_passthrough_enabled) = data.hugepages_passthrough_enabled {
+            template.hugepages_passthrough_enabled = hugepages_passthrough_enabled;
+        }
+        if let Some(kvm_passthrough_enabled) = data.kvm_passthrough_enabled {
+            template.kvm_passthrough_enabled = kvm_passthrough_enabled;
+        }
+        if let Some(feature_limits) = &data.feature_limits {
+            template.allocation_limit = feature_limits.allocations;
+            template.database_limit = feature_limits.databases;
+            template.backup_limit = feature_limits.backups;
+            template.schedule_limit = feature_limits.schedules;
+        }
+        if let Some(variables) = data.variables {
+            template.variables = variables;
+        }
+        if let Some(deployment) = data.deployment {
+            if let Some(deployment) = &deployment {
+                if let Err(errors) = shared::utils::validate_data(deployment) {
+                    return ApiResponse::new_serialized(ApiError::new_strings_value(errors))
+                        .with_status(StatusCode::BAD_REQUEST)
+                        .ok();
+                }
+
+                if deployment
+                    .port_ranges
+                    .iter()
+                    .any(|port_range| port_range.start > port_range.end)
+                {
+                    return ApiResponse::error("port range start must not be after its end")
+                        .with_status(StatusCode::BAD_REQUEST)
+                        .ok();
+                }
+            }
+
+            template.deployment = deployment;
+        }
+
+        ServerTemplate::validate_variables(&state.database, template.egg_uuid, &template.variables)
+            .await?;
+
+        match sqlx::query(
+            r#"
+            UPDATE server_templates
+            SET
+                name = $2, description = $3, egg_uuid = $4, backup_configuration_uuid = $5,
+                memory = $6, swap = $7, disk = $8, io_weight = $9, cpu = $10, pinned_cpus = $11,
+                startup = $12, image = $13, timezone = $14,
+                hugepages_passthrough_enabled = $15, kvm_passthrough_enabled = $16,
+                allocation_limit = $17, database_limit = $18, backup_limit = $19, schedule_limit = $20,
+                variables = $21, deployment = $22
+            WHERE server_templates.uuid = $1
+            "#,
+        )
+        .bind(template.uuid)
+        .bind(&template.name)
+        .bind(template.description.as_deref())
+        .bind(template.egg_uuid)
+        .bind(template.backup_configuration_uuid)
+        .bind(template.memory)
+        .bind(template.swap)
+        .bind(template.disk)
+        .bind(template.io_weight)
+        .bind(template.cpu)
+        .bind(&template.pinned_cpus)
+        .bind(&template.startup)
+        .bind(&template.image)
+        .bind(template.timezone.as_deref())
+        .bind(template.hugepages_passthrough_enabled)
+        .bind(template.kvm_passthrough_enabled)
+        .bind(template.allocation_limit)
+        .bind(template.database_limit)
+        .bind(template.backup_limit)
+        .bind(template.schedule_limit)
+        .bind(serde_json::to_value(&template.variables)?)
+        .bind(
+            template
+                .deployment
+                .as_ref()
+                .map(serde_json::to_value)
+                .transpose()?,
+        )
+        .execute(state.database.write())
+        .await
+        {
+            Ok(_) => {}
+            Err(err) if err.is_unique_violation() => {
+                return ApiResponse::error("server template with name already exists")
+                    .with_status(StatusCode::CONFLICT)
+                    .ok();
+            }
+            Err(err) => {
+                tracing::error!("failed to update server template: {:?}", err);
+
+                return ApiResponse::error("failed to update server template")
+                    .with_status(StatusCode::INTERNAL_SERVER_ERROR)
+                    .ok();
+            }
+        }
+
+        activity_logger
+            .log(
+                "server-template:update",
+                serde_json::json!({
+                    "uuid": template.uuid,
+                    "name": template.name,
+                    "description": template.description,
+                    "egg_uuid": template.egg_uuid,
+                    "backup_configuration_uuid": template.backup_configuration_uuid,
+
+                    "limits": data.limits,
+                    "pinned_cpus": template.pinned_cpus,
+                    "startup": template.startup,
+                    "image": template.image,
+                    "timezone": template.timezone,
+
+                    "hugepages_passthrough_enabled": template.hugepages_passthrough_enabled,
+                    "kvm_passthrough_enabled": template.kvm_passthrough_enabled,
+
+                    "feature_limits": data.feature_limits,
+                    "variables": template.variables,
+                    "deployment": template.deployment,
+                }),
+            )
+            .await;
+
+        ApiResponse::new_serialized(Response {}).ok()
+    }
+}
+
+pub fn router(state: &State) -> OpenApiRouter<State> {
+    OpenApiRouter::new()
+        .routes(routes!(get::route))
+        .routes(routes!(delete::route))
+        .routes(routes!(patch::route))
+        .route_layer(axum::middleware::from_fn_with_state(state.clone(), auth))
+        .with_state(state.clone())
+}
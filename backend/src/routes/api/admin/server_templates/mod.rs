@@ -0,0 +1,271 @@
+use super::State;
+use utoipa_axum::{router::OpenApiRouter, routes};
+
+mod _template_;
+
+mod get {
+    use axum::{extract::Query, http::StatusCode};
+    use serde::Serialize;
+    use shared::{
+        ApiError, GetState,
+        models::{
+            Pagination, PaginationParamsWithSearch, server_template::ServerTemplate,
+            user::GetPermissionManager,
+        },
+        response::{ApiResponse, ApiResponseResult},
+    };
+    use utoipa::ToSchema;
+
+    #[derive(ToSchema, Serialize)]
+    struct Response {
+        #[schema(inline)]
+        templates: Pagination<shared::models::server_template::AdminApiServerTemplate>,
+    }
+
+    #[utoipa::path(get, path = "/", responses(
+        (status = OK, body = inline(Response)),
+    ), params(
+        (
+            "page" = i64, Query,
+            description = "The page number",
+            example = "1",
+        ),
+        (
+            "per_page" = i64, Query,
+            description = "The number of items per page",
+            example = "10",
+        ),
+        (
+            "search" = Option<String>, Query,
+            description = "Search term for items",
+        ),
+    ))]
+    pub async fn route(
+        state: GetState,
+        permissions: GetPermissionManager,
+        Query(params): Query<PaginationParamsWithSearch>,
+    ) -> ApiResponseResult {
+        if let Err(errors) = shared::utils::validate_data(&params) {
+            return ApiResponse::new_serialized(ApiError::new_strings_value(errors))
+                .with_status(StatusCode::BAD_REQUEST)
+                .ok();
+        }
+
+        permissions.has_admin_permission("server-templates.read")?;
+
+        let templates = ServerTemplate::all_with_pagination(
+            &state.database,
+            params.page,
+            params.per_page,
+            params.search.as_deref(),
+        )
+        .await?;
+
+        ApiResponse::new_serialized(Response {
+            templates: Pagination {
+                total: templates.total,
+                per_page: templates.per_page,
+                page: templates.page,
+                data: templates
+                    .data
+                    .into_iter()
+                    .map(|template| template.into_admin_api_object())
+                    .collect(),
+            },
+        })
+        .ok()
+    }
+}
+
+mod post {
+    use axum::http::StatusCode;
+    use serde::{Deserialize, Serialize};
+    use shared::{
+        ApiError, GetState,
+        models::{
+            ByUuid, admin_activity::GetAdminActivityLogger,
+            backup_configurations::BackupConfiguration, nest_egg::NestEgg,
+            server_template::ServerTemplate, user::GetPermissionManager,
+        },
+        response::{ApiResponse, ApiResponseResult},
+    };
+    use std::collections::BTreeMap;
+    use utoipa::ToSchema;
+    use validator::Validate;
+
+    #[derive(ToSchema, Validate, Deserialize)]
+    pub struct Payload {
+        #[validate(length(min = 3, max = 255))]
+        #[schema(min_length = 3, max_length = 255)]
+        name: compact_str::CompactString,
+        #[validate(length(max = 1024))]
+        #[schema(max_length = 1024)]
+        description: Option<compact_str::CompactString>,
+
+        egg_uuid: uuid::Uuid,
+        backup_configuration_uuid: Option<uuid::Uuid>,
+
+        #[validate(nested)]
+        limits: shared::models::server::ApiServerLimits,
+        #[serde(default)]
+        pinned_cpus: Vec<i16>,
+
+        #[validate(length(min = 1, max = 8192))]
+        #[schema(min_length = 1, max_length = 8192)]
+        startup: compact_str::CompactString,
+        #[validate(length(min = 2, max = 255))]
+        #[schema(min_length = 2, max_length = 255)]
+        image: compact_str::CompactString,
+        #[schema(min_length = 3, max_length = 255, value_type = String)]
+        timezone: Option<chrono_tz::Tz>,
+
+        #[serde(default)]
+        hugepages_passthrough_enabled: bool,
+        #[serde(default)]
+        kvm_passthrough_enabled: bool,
+
+        #[validate(nested)]
+        feature_limits: shared::models::server::ApiServerFeatureLimits,
+        #[serde(default)]
+        variables: BTreeMap<compact_str::CompactString, compact_str::CompactString>,
+        #[validate(nested)]
+        deployment: Option<shared::models::server::ApiServerDeployment>,
+    }
+
+    #[derive(ToSchema, Serialize)]
+    struct Response {
+        template: shared::models::server_template::AdminApiServerTemplate,
+    }
+
+    #[utoipa::path(post, path = "/", responses(
+        (status = OK, body = inline(Response)),
+        (status = BAD_REQUEST, body = ApiError),
+        (status = NOT_FOUND, body = ApiError),
+        (status = CONFLICT, body = ApiError),
+    ), request_body = inline(Payload))]
+    pub async fn route(
+        state: GetState,
+        permissions: GetPermissionManager,
+        activity_logger: GetAdminActivityLogger,
+        shared::Payload(data): shared::Payload<Payload>,
+    ) -> ApiResponseResult {
+        if let Err(errors) = shared::utils::validate_data(&data) {
+            return ApiResponse::new_serialized(ApiError::new_strings_value(errors))
+                .with_status(StatusCode::BAD_REQUEST)
+                .ok();
+        }
+
+        permissions.has_admin_permission("server-templates.create")?;
+
+        if NestEgg::by_uuid_optional(&state.database, data.egg_uuid)
+            .await?
+            .is_none()
+        {
+            return ApiResponse::error("egg not found")
+                .with_status(StatusCode::NOT_FOUND)
+                .ok();
+        }
+
+        let backup_configuration_uuid = match data.backup_configuration_uuid {
+            Some(backup_configuration_uuid) if !backup_configuration_uuid.is_nil() => {
+                if BackupConfiguration::by_uuid_optional(&state.database, backup_configuration_uuid)
+                    .await?
+                    .is_none()
+                {
+                    return ApiResponse::error("backup configuration not found")
+                        .with_status(StatusCode::NOT_FOUND)
+                        .ok();
+                }
+
+                Some(backup_configuration_uuid)
+            }
+            _ => None,
+        };
+
+        if let Some(deployment) = &data.deployment
+            && deployment
+                .port_ranges
+                .iter()
+                .any(|port_range| port_range.start > port_range.end)
+        {
+            return ApiResponse::error("port range start must not be after its end")
+                .with_status(StatusCode::BAD_REQUEST)
+                .ok();
+        }
+
+        ServerTemplate::validate_variables(&state.database, data.egg_uuid, &data.variables).await?;
+
+        let template = match ServerTemplate::create(
+            &state.database,
+            &data.name,
+            data.description.as_deref(),
+            data.egg_uuid,
+            backup_configuration_uuid,
+            &data.limits,
+            &data.pinned_cpus,
+            &data.startup,
+            &data.image,
+            data.timezone.as_ref().map(|tz| tz.name()),
+            data.hugepages_passthrough_enabled,
+            data.kvm_passthrough_enabled,
+            &data.feature_limits,
+            &data.variables,
+            data.deployment.as_ref(),
+        )
+        .await
+        {
+            Ok(template) => template,
+            Err(err) if err.is_unique_violation() => {
+                return ApiResponse::error("server template with name already exists")
+                    .with_status(StatusCode::CONFLICT)
+                    .ok();
+            }
+            Err(err) => {
+                tracing::error!("failed to create server template: {:?}", err);
+
+                return ApiResponse::error("failed to create server template")
+                    .with_status(StatusCode::INTERNAL_SERVER_ERROR)
+                    .ok();
+            }
+        };
+
+        activity_logger
+            .log(
+                "server-template:create",
+                serde_json::json!({
+                    "uuid": template.uuid,
+                    "name": template.name,
+                    "description": template.description,
+                    "egg_uuid": template.egg_uuid,
+                    "backup_configuration_uuid": template.backup_configuration_uuid,
+
+                    "limits": data.limits,
+                    "pinned_cpus": template.pinned_cpus,
+                    "startup": template.startup,
+                    "image": template.image,
+                    "timezone": template.timezone,
+
+                    "hugepages_passthrough_enabled": template.hugepages_passthrough_enabled,
+                    "kvm_passthrough_enabled": template.kvm_passthrough_enabled,
+
+                    "feature_limits": data.feature_limits,
+                    "variables": template.variables,
+                    "deployment": template.deployment,
+                }),
+            )
+            .await;
+
+        ApiResponse::new_serialized(Response {
+            template: template.into_admin_api_object(),
+        })
+        .ok()
+    }
+}
+
+pub fn router(state: &State) -> OpenApiRouter<State> {
+    OpenApiRouter::new()
+        .routes(routes!(get::route))
+        .routes(routes!(post::route))
+        .nest("/{template}", _template_::router(state))
+        .with_state(state.clone())
+}
@@ -0,0 +1,371 @@
+use super::State;
+use utoipa_axum::{router::OpenApiRouter, routes};
+
+mod post {
+    use axum::http::StatusCode;
+    use serde::{Deserialize, Serialize};
+    use shared::{
+        ApiError, GetState,
+        models::{
+            ByUuid,
+            admin_activity::GetAdminActivityLogger,
+            node::{Node, NodeResources},
+            server::{GetServer, Server},
+            server_backup::{BackupDisk, ServerBackup},
+            server_mount::ServerMount,
+            server_schedule::ServerSchedule,
+            server_variable::ServerVariable,
+            user::{GetPermissionManager, User},
+        },
+        response::{ApiResponse, ApiResponseResult},
+    };
+    use utoipa::ToSchema;
+    use validator::Validate;
+
+    #[derive(ToSchema, Validate, Deserialize)]
+    pub struct Payload {
+        node_uuid: Option<uuid::Uuid>,
+        owner_uuid: Option<uuid::Uuid>,
+        allocation_uuid: Option<uuid::Uuid>,
+        #[serde(default)]
+        allocation_uuids: Vec<uuid::Uuid>,
+
+        #[validate(length(min = 3, max = 255))]
+        #[schema(min_length = 3, max_length = 255)]
+        name: Option<compact_str::CompactString>,
+
+        #[serde(default)]
+        start_on_completion: bool,
+        #[serde(default)]
+        skip_installer: bool,
+        #[serde(default)]
+        restore_latest_backup: bool,
+    }
+
+    #[derive(ToSchema, Serialize)]
+    struct Response {
+        server: shared::models::server::AdminApiServer,
+        warning: Option<compact_str::CompactString>,
+    }
+
+    /// Waits until the node reports the freshly created server, giving up after 30 seconds.
+    async fn wait_for_node_server(
+        database: &shared::database::Database,
+        node: &Node,
+        server_uuid: uuid::Uuid,
+    ) -> Result<(), anyhow::Error> {
+        let client = node.api_client(database);
+
+        for _ in 0..30 {
+            if client.get_servers_server(server_uuid).await.is_ok() {
+                return Ok(());
+            }
+
+            tokio::time::sleep(std::time::Duration::from_secs(1)).await;
+        }
+
+        Err(anyhow::anyhow!("node did not finish creating the server"))
+    }
+
+    #[utoipa::path(post, path = "/", responses(
+        (status = OK, body = inline(Response)),
+        (status = BAD_REQUEST, body = ApiError),
+        (status = NOT_FOUND, body = ApiError),
+        (status = CONFLICT, body = ApiError),
+    ), params(
+        (
+            "server" = uuid::Uuid,
+            description = "The server ID",
+            example = "123e4567-e89b-12d3-a456-426614174000",
+        ),
+    ), request_body = inline(Payload))]
+    pub async fn route(
+        state: GetState,
+        permissions: GetPermissionManager,
+        server: GetServer,
+        activity_logger: GetAdminActivityLogger,
+        shared::Payload(data): shared::Payload<Payload>,
+    ) -> ApiResponseResult {
+        if let Err(errors) = shared::utils::validate_data(&data) {
+            return ApiResponse::new_serialized(ApiError::new_strings_value(errors))
+                .with_status(StatusCode::BAD_REQUEST)
+                .ok();
+        }
+
+        permissions.has_admin_permission("servers.create")?;
+
+        let node = match data.node_uuid {
+            Some(node_uuid) if node_uuid != server.node.uuid => {
+                match Node::by_uuid_optional(&state.database, node_uuid).await? {
+                    Some(node) => node,
+                    None => {
+                        return ApiResponse::error("node not found")
+                            .with_status(StatusCode::NOT_FOUND)
+                            .ok();
+                    }
+                }
+            }
+            _ => server.node.fetch_cached(&state.database).await?,
+        };
+
+        if let Some(resource) = node
+            .exceeded_resource(
+                &state.database,
+                NodeResources {
+                    memory: server.memory,
+                    disk: server.disk,
+                    cpu: server.cpu as i64,
+                },
+                None,
+            )
+            .await?
+        {
+            return ApiResponse::error(&format!("node does not have enough {resource} available"))
+                .with_status(StatusCode::CONFLICT)
+                .ok();
+        }
+
+        let owner = match data.owner_uuid {
+            Some(owner_uuid) if owner_uuid != server.owner.uuid => {
+                match User::by_uuid_optional(&state.database, owner_uuid).await? {
+                    Some(user) => user,
+                    None => {
+                        return ApiResponse::error("owner not found")
+                            .with_status(StatusCode::NOT_FOUND)
+                            .ok();
+                    }
+                }
+            }
+            _ => server.owner.clone(),
+        };
+
+        if owner.email_verification_blocks_servers(&*state.settings.get().await?) {
+            return ApiResponse::error("server owner has not verified their email address")
+                .with_status(StatusCode::BAD_REQUEST)
+                .ok();
+        }
+
+        let backup = if data.restore_latest_backup {
+            let backup =
+                match ServerBackup::latest_successful_by_server_uuid(&state.database, server.uuid)
+                    .await?
+                {
+                    Some(backup) => backup,
+                    None => {
+                        return ApiResponse::error("server does not have a successful backup")
+                            .with_status(StatusCode::NOT_FOUND)
+                            .ok();
+                    }
+                };
+
+            // snapshots belong to the dataset or subvolume of the source server
+            if matches!(backup.disk, BackupDisk::Zfs | BackupDisk::Btrfs) {
+                return ApiResponse::error(
+                    "latest backup is a filesystem snapshot and cannot be restored into another server",
+                )
+                .with_status(StatusCode::BAD_REQUEST)
+                .ok();
+            }
+
+            // only backups stored off the node can be restored onto another node
+            if !matches!(backup.disk, BackupDisk::S3 | BackupDisk::Restic)
+                && backup.node.uuid != node.uuid
+            {
+                return ApiResponse::error(
+                    "latest backup is stored on another node and cannot be restored onto this node",
+                )
+                .with_status(StatusCode::BAD_REQUEST)
+                .ok();
+            }
+
+            Some(backup)
+        } else {
+            None
+        };
+
+        let mut variables = ServerVariable::all_by_server_uuid_egg_uuid(
+            &state.database,
+            server.uuid,
+            server.egg.uuid,
+        )
+        .await?;
+        ServerVariable::decrypt_all(&state.database, &mut variables).await?;
+
+        let server_variables = variables
+            .iter()
+            .map(|variable| (&variable.variable, variable.value.as_str()))
+            .collect::<Vec<_>>();
+
+        let name = match &data.name {
+            Some(name) => name.clone(),
+            None => compact_str::format_compact!("{} (clone)", server.name),
+        };
+
+        let mut transaction = state.database.write().begin().await?;
+
+        let clone_uuid = match Server::create_with_transaction(
+            &mut transaction,
+            &state.database,
+            &node,
+            owner.uuid,
+            server.egg.uuid,
+            server
+                .backup_configuration
+                .as_ref()
+                .map(|backup_configuration| backup_configuration.uuid),
+            data.allocation_uuid,
+            &data.allocation_uuids,
+            None,
+            data.skip_installer || backup.is_some(),
+            &name,
+            server.description.as_deref(),
+            &shared::models::server::ApiServerLimits {
+                cpu: server.cpu,
+                memory: server.memory,
+                swap: server.swap,
+                disk: server.disk,
+                io_weight: server.io_weight,
+            },
+            &server.pinned_cpus,
+            &server.startup,
+            &server.image,
+            server.timezone.as_deref(),
+            server.hugepages_passthrough_enabled,
+            server.kvm_passthrough_enabled,
+            &shared::models::server::ApiServerFeatureLimits {
+                allocations: server.allocation_limit,
+                databases: server.database_limit,
+                backups: server.backup_limit,
+                schedules: server.schedule_limit,
+            },
+            &server_variables,
+        )
+        .await
+        {
+            Ok(server_uuid) => server_uuid,
+            Err(err) if err.is_unique_violation() => {
+                return ApiResponse::error("server with allocation(s) already exists")
+                    .with_status(StatusCode::CONFLICT)
+                    .ok();
+            }
+            Err(err) => {
+                tracing::error!("failed to clone server: {:?}", err);
+
+                return ApiResponse::error(&format!("failed to clone server: {err}"))
+                    .with_status(StatusCode::INTERNAL_SERVER_ERROR)
+                    .ok();
+            }
+        };
+
+        let mounts = ServerMount::copy_by_server_uuid(
+            &mut transaction,
+            server.uuid,
+            clone_uuid,
+            node.uuid,
+            server.egg.uuid,
+        )
+        .await?;
+        let schedules = ServerSchedule::copy_by_server_uuid(
+            &state.database,
+            &mut transaction,
+            server.uuid,
+            clone_uuid,
+            server.schedule_limit,
+        )
+        .await?;
+
+        transaction.commit().await?;
+
+        if let Err(err) = Server::create_on_node(
+            &state.database,
+            &node,
+            clone_uuid,
+            data.start_on_completion && backup.is_none(),
+            data.skip_installer || backup.is_some(),
+        )
+        .await
+        {
+            return ApiResponse::error(&format!("failed to clone server: {err}"))
+                .with_status(StatusCode::INTERNAL_SERVER_ERROR)
+                .ok();
+        }
+
+        let clone = Server::by_uuid(&state.database, clone_uuid).await?;
+
+        let backup_uuid = backup.as_ref().map(|backup| backup.uuid);
+        let mut warning = None;
+
+        if let Some(backup) = backup {
+            sqlx::query(
+                r#"
+                UPDATE servers
+                SET status = 'RESTORING_BACKUP'
+                WHERE servers.uuid = $1 AND servers.status IS NULL
+                "#,
+            )
+            .bind(clone.uuid)
+            .execute(state.database.write())
+            .await?;
+
+            // wings sets the server up in the background, restoring before it knows the server fails
+            let result = match wait_for_node_server(&state.database, &node, clone.uuid).await {
+                Ok(()) => backup.restore(&state.database, clone.clone(), false).await,
+                Err(err) => Err(err),
+            };
+
+            if let Err(err) = result {
+                tracing::error!(server = %clone.uuid, backup = ?backup_uuid, "failed to restore backup into cloned server: {:?}", err);
+
+                sqlx::query(
+                    r#"
+                    UPDATE servers
+                    SET status = NULL
+                    WHERE servers.uuid = $1 AND servers.status = 'RESTORING_BACKUP'
+                    "#,
+                )
+                .bind(clone.uuid)
+                .execute(state.database.write())
+                .await?;
+
+                warning = Some(compact_str::format_compact!(
+                    "server was cloned, but restoring the backup failed: {err}"
+                ));
+            }
+        }
+
+        activity_logger
+            .log(
+                "server:clone",
+                serde_json::json!({
+                    "uuid": clone.uuid,
+                    "source_uuid": server.uuid,
+                    "node_uuid": node.uuid,
+                    "owner_uuid": owner.uuid,
+
+                    "allocation_uuid": data.allocation_uuid,
+                    "allocation_uuids": data.allocation_uuids,
+
+                    "name": clone.name,
+                    "mounts": mounts,
+                    "schedules": schedules,
+                    "backup_uuid": backup_uuid,
+                    "warning": warning,
+                }),
+            )
+            .await;
+
+        ApiResponse::new_serialized(Response {
+            warning,
+            server: clone
+                .into_admin_api_object(&state.database, &state.storage.retrieve_urls().await?)
+                .await?,
+        })
+        .ok()
+    }
+}
+
+pub fn router(state: &State) -> OpenApiRouter<State> {
+    OpenApiRouter::new()
+        .routes(routes!(post::route))
+        .with_state(state.clone())
+}
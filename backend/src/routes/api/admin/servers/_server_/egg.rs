@@ -0,0 +1,294 @@
+use super::State;
+use utoipa_axum::{router::OpenApiRouter, routes};
+
+mod post {
+    use axum::http::StatusCode;
+    use serde::{Deserialize, Serialize};
+    use shared::{
+        ApiError, GetState,
+        models::{
+            ByUuid, admin_activity::GetAdminActivityLogger, nest_egg::NestEgg,
+            nest_egg_variable::NestEggVariable, server::GetServer, server_variable::ServerVariable,
+            user::GetPermissionManager,
+        },
+        response::{ApiResponse, ApiResponseResult},
+    };
+    use std::collections::HashMap;
+    use utoipa::ToSchema;
+    use validator::Validate;
+
+    #[derive(ToSchema, Validate, Serialize, Deserialize)]
+    pub struct PayloadVariable {
+        #[validate(length(min = 1, max = 255))]
+        #[schema(min_length = 1, max_length = 255)]
+        env_variable: String,
+        #[validate(length(max = 4096))]
+        #[schema(max_length = 4096)]
+        value: String,
+    }
+
+    #[derive(ToSchema, Validate, Deserialize)]
+    pub struct Payload {
+        egg_uuid: uuid::Uuid,
+        #[schema(inline)]
+        #[serde(default)]
+        variables: Vec<PayloadVariable>,
+
+        #[serde(default)]
+        reset_startup: bool,
+        #[serde(default)]
+        reset_image: bool,
+        #[serde(default)]
+        reinstall: bool,
+        #[serde(default)]
+        truncate_directory: bool,
+
+        #[serde(default)]
+        preview: bool,
+    }
+
+    #[derive(ToSchema, Serialize)]
+    struct ResponseVariable {
+        name: compact_str::CompactString,
+        env_variable: compact_str::CompactString,
+        value: String,
+        is_secret: bool,
+    }
+
+    #[derive(ToSchema, Serialize)]
+    struct Response {
+        #[schema(inline)]
+        migrated_variables: Vec<ResponseVariable>,
+        #[schema(inline)]
+        new_variables: Vec<ResponseVariable>,
+        #[schema(inline)]
+        dropped_variables: Vec<ResponseVariable>,
+    }
+
+    #[utoipa::path(post, path = "/", responses(
+        (status = OK, body = inline(Response)),
+        (status = BAD_REQUEST, body = ApiError),
+        (status = NOT_FOUND, body = ApiError),
+        (status = CONFLICT, body = ApiError),
+    ), params(
+        (
+            "server" = uuid::Uuid,
+            description = "The server ID",
+            example = "123e4567-e89b-12d3-a456-426614174000",
+        ),
+    ), request_body = inline(Payload))]
+    pub async fn route(
+        state: GetState,
+        permissions: GetPermissionManager,
+        mut server: GetServer,
+        activity_logger: GetAdminActivityLogger,
+        shared::Payload(data): shared::Payload<Payload>,
+    ) -> ApiResponseResult {
+        if let Err(errors) = shared::utils::validate_data(&data) {
+            return ApiResponse::new_serialized(ApiError::new_strings_value(errors))
+                .with_status(StatusCode::BAD_REQUEST)
+                .ok();
+        }
+
+        permissions.has_admin_permission("servers.update")?;
+        permissions.has_admin_permission("servers.variables")?;
+
+        let reveal = permissions
+            .has_admin_permission("servers.variables-reveal")
+            .is_ok();
+
+        if data.egg_uuid == server.egg.uuid {
+            return ApiResponse::error("server already uses this egg")
+                .with_status(StatusCode::BAD_REQUEST)
+                .ok();
+        }
+
+        let egg = match NestEgg::by_uuid_optional(&state.database, data.egg_uuid).await? {
+            Some(egg) => egg,
+            None => {
+                return ApiResponse::error("egg not found")
+                    .with_status(StatusCode::NOT_FOUND)
+                    .ok();
+            }
+        };
+
+        if data.reinstall && server.status.is_some() {
+            return ApiResponse::error(
+                "server is already installing or in an invalid state for reinstalling",
+            )
+            .with_status(StatusCode::CONFLICT)
+            .ok();
+        }
+
+        let image = if data.reset_image {
+            match egg.docker_images.values().next() {
+                Some(image) => Some(image.clone()),
+                None => {
+                    return ApiResponse::error("egg does not have any docker images")
+                        .with_status(StatusCode::BAD_REQUEST)
+                        .ok();
+                }
+            }
+        } else {
+            None
+        };
+
+        let mut old_variables = ServerVariable::all_by_server_uuid_egg_uuid(
+            &state.database,
+            server.uuid,
+            server.egg.uuid,
+        )
+        .await?;
+        ServerVariable::decrypt_all(&state.database, &mut old_variables).await?;
+
+        let egg_variables = NestEggVariable::all_by_egg_uuid(&state.database, egg.uuid).await?;
+
+        // variables are carried over by their env name, values given in the payload take precedence
+        let mut variables = Vec::new();
+        variables.reserve_exact(egg_variables.len());
+        let mut migrated = Vec::new();
+
+        for variable in egg_variables {
+            let old_variable = old_variables
+                .iter()
+                .find(|v| v.variable.env_variable == variable.env_variable);
+
+            let value = if let Some(value) = data
+                .variables
+                .iter()
+                .find(|v| v.env_variable == variable.env_variable)
+            {
+                value.value.clone()
+            } else if let Some(old_variable) = old_variable {
+                old_variable.value.clone()
+            } else {
+                variable.default_value.clone().unwrap_or_default()
+            };
+
+            migrated.push(old_variable.is_some());
+            variables.push((variable, value));
+        }
+
+        let mut validator_variables = HashMap::new();
+        validator_variables.reserve(variables.len());
+
+        for (variable, value) in variables.iter() {
+            validator_variables.insert(
+                variable.env_variable.as_str(),
+                (variable.rules.as_slice(), value.as_str()),
+            );
+        }
+
+        let validator = match rule_validator::Validator::new(validator_variables) {
+            Ok(validator) => validator,
+            Err(error) => {
+                return ApiResponse::error(&error)
+                    .with_status(StatusCode::BAD_REQUEST)
+                    .ok();
+            }
+        };
+
+        if let Err(error) = validator.validate() {
+            return ApiResponse::error(&error)
+                .with_status(StatusCode::BAD_REQUEST)
+                .ok();
+        }
+
+        let into_response_variable = |variable: &NestEggVariable, value: &str| ResponseVariable {
+            name: variable.name.clone(),
+            env_variable: variable.env_variable.clone(),
+            value: if variable.secret && !reveal {
+                String::new()
+            } else {
+                value.to_string()
+            },
+            is_secret: variable.secret,
+        };
+
+        let mut response = Response {
+            migrated_variables: Vec::new(),
+            new_variables: Vec::new(),
+            dropped_variables: old_variables
+                .iter()
+                .filter(|old_variable| {
+                    !variables.iter().any(|(variable, _)| {
+                        variable.env_variable == old_variable.variable.env_variable
+                    })
+                })
+                .map(|old_variable| {
+                    into_response_variable(&old_variable.variable, &old_variable.value)
+                })
+                .collect(),
+        };
+
+        for ((variable, value), migrated) in variables.iter().zip(migrated) {
+            if migrated {
+                response
+                    .migrated_variables
+                    .push(into_response_variable(variable, value));
+            } else {
+                response
+                    .new_variables
+                    .push(into_response_variable(variable, value));
+            }
+        }
+
+        if data.preview {
+            return ApiResponse::new_serialized(response).ok();
+        }
+
+        let old_egg_uuid = server.egg.uuid;
+        let startup = data.reset_startup.then(|| egg.startup.clone());
+
+        server
+            .change_egg(&state.database, egg, &variables, startup, image)
+            .await?;
+
+        activity_logger
+            .log(
+                "server:egg.update",
+                serde_json::json!({
+                    "uuid": server.uuid,
+                    "old_egg_uuid": old_egg_uuid,
+                    "egg_uuid": server.egg.uuid,
+
+                    "startup": server.startup,
+                    "image": server.image,
+                    "reinstall": data.reinstall,
+
+                    "new_variables": response
+                        .new_variables
+                        .iter()
+                        .map(|variable| &variable.env_variable)
+                        .collect::<Vec<_>>(),
+                    "dropped_variables": response
+                        .dropped_variables
+                        .iter()
+                        .map(|variable| &variable.env_variable)
+                        .collect::<Vec<_>>(),
+                }),
+            )
+            .await;
+
+        if data.reinstall {
+            server.0.clone().sync(&state.database).await?;
+            server
+                .install(&state, data.truncate_directory, None)
+                .await?;
+        } else {
+            tokio::spawn(async move {
+                if let Err(err) = server.0.sync(&state.database).await {
+                    tracing::error!("failed to sync server on node: {:?}", err);
+                }
+            });
+        }
+
+        ApiResponse::new_serialized(response).ok()
+    }
+}
+
+pub fn router(state: &State) -> OpenApiRouter<State> {
+    OpenApiRouter::new()
+        .routes(routes!(post::route))
+        .with_state(state.clone())
+}
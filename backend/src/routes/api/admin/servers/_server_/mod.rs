@@ -14,6 +14,8 @@ use utoipa_axum::{router::OpenApiRouter, routes};
 
 mod allocations;
 mod clear_state;
+mod clone;
+mod egg;
 mod mounts;
 mod transfer;
 mod variables;
@@ -188,6 +190,7 @@ mod patch {
             admin_activity::GetAdminActivityLogger,
             backup_configurations::BackupConfiguration,
             nest_egg::NestEgg,
+            node::NodeResources,
             server::GetServer,
             user::{GetPermissionManager, User},
         },
@@ -204,6 +207,11 @@ mod patch {
         backup_configuration_uuid: Option<uuid::Uuid>,
 
         suspended: Option<bool>,
+        #[validate(length(max = 1024))]
+        #[schema(max_length = 1024)]
+        suspension_reason: Option<compact_str::CompactString>,
+        unsuspend_at: Option<Option<chrono::DateTime<chrono::Utc>>>,
+        expires_at: Option<Option<chrono::DateTime<chrono::Utc>>>,
 
         #[validate(length(max = 255))]
         #[schema(max_length = 255)]
@@ -311,8 +319,47 @@ mod patch {
                 ));
             }
         }
-        if let Some(suspended) = data.suspended {
-            server.suspended = suspended;
+        if let Some(Some(unsuspend_at)) = data.unsuspend_at
+            && unsuspend_at <= chrono::Utc::now()
+        {
+            return ApiResponse::error("unsuspend_at must be in the future")
+                .with_status(StatusCode::BAD_REQUEST)
+                .ok();
+        }
+        if data.suspended.unwrap_or(server.suspended) {
+            if !server.suspended || data.suspension_reason.is_some() || data.unsuspend_at.is_some()
+            {
+                let suspension_reason = match &data.suspension_reason {
+                    Some(reason) if reason.is_empty() => None,
+                    Some(reason) => Some(reason.clone()),
+                    None => server.suspension_reason.clone(),
+                };
+                let suspended_by_uuid = if server.suspended {
+                    server.suspended_by_uuid
+                } else {
+                    Some(activity_logger.user_uuid)
+                };
+                let unsuspend_at = match data.unsuspend_at {
+                    Some(unsuspend_at) => unsuspend_at.map(|dt| dt.naive_utc()),
+                    None => server.unsuspend_at,
+                };
+
+                server.set_suspended(
+                    suspension_reason.as_deref(),
+                    suspended_by_uuid,
+                    unsuspend_at,
+                );
+            }
+        } else if server.suspended {
+            server.set_unsuspended();
+        }
+        if let Some(expires_at) = data.expires_at {
+            let expires_at = expires_at.map(|dt| dt.naive_utc());
+
+            if server.expires_at != expires_at {
+                server.expires_at = expires_at;
+                server.expiry_notified = false;
+            }
         }
         if let Some(external_id) = &data.external_id {
             if external_id.is_empty() {
@@ -332,6 +379,31 @@ mod patch {
             }
         }
         if let Some(limits) = &data.limits {
+            if limits.memory > server.memory || limits.disk > server.disk || limits.cpu > server.cpu
+            {
+                let requested = NodeResources {
+                    memory: limits.memory,
+                    disk: limits.disk,
+                    cpu: limits.cpu as i64,
+                };
+
+                for node in std::iter::once(&server.node).chain(server.destination_node.as_ref()) {
+                    let node = node.fetch_cached(&state.database).await?;
+
+                    if let Some(resource) = node
+                        .exceeded_resource(&state.database, requested, Some(server.uuid))
+                        .await?
+                    {
+                        return ApiResponse::error(&format!(
+                            "node {} does not have enough {resource} available",
+                            node.name
+                        ))
+                        .with_status(StatusCode::CONFLICT)
+                        .ok();
+                    }
+                }
+            }
+
             server.cpu = limits.cpu;
             server.memory = limits.memory;
             server.swap = limits.swap;
@@ -373,6 +445,10 @@ mod patch {
             server.schedule_limit = feature_limits.schedules;
         }
 
+        // suspension and expiry are written together with the other fields, after everything
+        // has been validated
+        let mut transaction = state.database.write().begin().await?;
+
         match sqlx::query!(
             "UPDATE servers
             SET
@@ -410,7 +486,7 @@ mod patch {
             server.schedule_limit,
             server.uuid,
         )
-        .execute(state.database.write())
+        .execute(&mut *transaction)
         .await
         {
             Ok(_) => {}
@@ -423,6 +499,26 @@ mod patch {
             }
         }
 
+        sqlx::query(
+            r#"
+            UPDATE servers
+            SET suspension_reason = $2, suspended_by_uuid = $3, suspended_at = $4, unsuspend_at = $5,
+                expires_at = $6, expiry_notified = $7
+            WHERE servers.uuid = $1
+            "#,
+        )
+        .bind(server.uuid)
+        .bind(&server.suspension_reason)
+        .bind(server.suspended_by_uuid)
+        .bind(server.suspended_at)
+        .bind(server.unsuspend_at)
+        .bind(server.expires_at)
+        .bind(server.expiry_notified)
+        .execute(&mut *transaction)
+        .await?;
+
+        transaction.commit().await?;
+
         activity_logger
             .log(
                 "server:update",
@@ -431,6 +527,11 @@ mod patch {
                     "owner_uuid": server.owner.uuid,
                     "egg_uuid": server.egg.uuid,
 
+                    "suspended": server.suspended,
+                    "suspension_reason": server.suspension_reason,
+                    "unsuspend_at": server.unsuspend_at,
+                    "expires_at": server.expires_at,
+
                     "external_id": server.external_id,
                     "name": server.name,
                     "description": server.description,
@@ -467,6 +568,8 @@ pub fn router(state: &State) -> OpenApiRouter<State> {
         .nest("/transfer", transfer::router(state))
         .nest("/allocations", allocations::router(state))
         .nest("/clear-state", clear_state::router(state))
+        .nest("/clone", clone::router(state))
+        .nest("/egg", egg::router(state))
         .route_layer(axum::middleware::from_fn_with_state(state.clone(), auth))
         .with_state(state.clone())
 }
@@ -9,8 +9,11 @@ mod post {
         ApiError, GetState,
         jwt::BasePayload,
         models::{
-            ByUuid, EventEmittingModel, admin_activity::GetAdminActivityLogger, node::Node,
-            server::GetServer, user::GetPermissionManager,
+            ByUuid, EventEmittingModel,
+            admin_activity::GetAdminActivityLogger,
+            node::{Node, NodeResources},
+            server::GetServer,
+            user::GetPermissionManager,
         },
         response::{ApiResponse, ApiResponseResult},
     };
@@ -75,6 +78,25 @@ mod post {
             }
         };
 
+        if let Some(resource) = destination_node
+            .exceeded_resource(
+                &state.database,
+                NodeResources {
+                    memory: server.memory,
+                    disk: server.disk,
+                    cpu: server.cpu as i64,
+                },
+                Some(server.uuid),
+            )
+            .await?
+        {
+            return ApiResponse::error(&format!(
+                "destination node does not have enough {resource} available"
+            ))
+            .with_status(StatusCode::CONFLICT)
+            .ok();
+        }
+
         let mut transaction = state.database.write().begin().await?;
 
         let destination_allocation_uuid = if let Some(allocation_uuid) = data.allocation_uuid {
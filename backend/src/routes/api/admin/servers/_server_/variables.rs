@@ -32,6 +32,10 @@ mod get {
     ) -> ApiResponseResult {
         permissions.has_admin_permission("servers.variables")?;
 
+        let reveal = permissions
+            .has_admin_permission("servers.variables-reveal")
+            .is_ok();
+
         let variables = ServerVariable::all_by_server_uuid_egg_uuid(
             &state.database,
             server.uuid,
@@ -39,11 +43,15 @@ mod get {
         )
         .await?;
 
+        let mut api_variables = Vec::new();
+        api_variables.reserve_exact(variables.len());
+
+        for variable in variables {
+            api_variables.push(variable.into_api_object(&state.database, reveal).await?);
+        }
+
         ApiResponse::new_serialized(Response {
-            variables: variables
-                .into_iter()
-                .map(|variable| variable.into_api_object())
-                .collect(),
+            variables: api_variables,
         })
         .ok()
     }
@@ -108,12 +116,13 @@ mod put {
 
         permissions.has_admin_permission("servers.variables")?;
 
-        let variables = ServerVariable::all_by_server_uuid_egg_uuid(
+        let mut variables = ServerVariable::all_by_server_uuid_egg_uuid(
             &state.database,
             server.uuid,
             server.egg.uuid,
         )
         .await?;
+        ServerVariable::decrypt_all(&state.database, &mut variables).await?;
 
         let mut validator_variables = HashMap::new();
         validator_variables.reserve(variables.len());
@@ -151,22 +160,25 @@ mod put {
                 .ok();
         }
 
+        let mut logged_variables = Vec::new();
+        logged_variables.reserve_exact(data.variables.len());
+
         for data_variable in &data.variables {
-            let variable_uuid = match variables
+            let variable = match variables
                 .iter()
                 .find(|v| v.variable.env_variable == data_variable.env_variable)
             {
-                Some(variable) => variable.variable.uuid,
+                Some(variable) => &variable.variable,
                 None => continue,
             };
 
-            ServerVariable::create(
-                &state.database,
-                server.uuid,
-                variable_uuid,
-                &data_variable.value,
-            )
-            .await?;
+            ServerVariable::create(&state.database, server.uuid, variable, &data_variable.value)
+                .await?;
+
+            logged_variables.push(serde_json::json!({
+                "env_variable": data_variable.env_variable,
+                "value": if variable.secret { "" } else { data_variable.value.as_str() },
+            }));
         }
 
         activity_logger
@@ -174,7 +186,7 @@ mod put {
                 "server:variables.update",
                 serde_json::json!({
                     "uuid": server.uuid,
-                    "variables": data.variables
+                    "variables": logged_variables
                 }),
             )
             .await;
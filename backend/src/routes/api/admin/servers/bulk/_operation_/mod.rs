@@ -0,0 +1,90 @@
+use super::State;
+use axum::{
+    extract::{Path, Request},
+    http::StatusCode,
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use shared::{
+    GetState,
+    models::{server_bulk_operation::ServerBulkOperation, user::GetPermissionManager},
+    response::ApiResponse,
+};
+use utoipa_axum::{router::OpenApiRouter, routes};
+
+mod results;
+
+pub type GetServerBulkOperation = shared::extract::ConsumingExtension<ServerBulkOperation>;
+
+pub async fn auth(
+    state: GetState,
+    permissions: GetPermissionManager,
+    Path(operation): Path<uuid::Uuid>,
+    mut req: Request,
+    next: Next,
+) -> Result<Response, StatusCode> {
+    if let Err(err) = permissions.has_admin_permission("servers.bulk") {
+        return Ok(err.into_response());
+    }
+
+    let operation = ServerBulkOperation::by_uuid_optional(&state.database, operation).await;
+    let operation = match operation {
+        Ok(Some(operation)) => operation,
+        Ok(None) => {
+            return Ok(ApiResponse::error("bulk operation not found")
+                .with_status(StatusCode::NOT_FOUND)
+                .into_response());
+        }
+        Err(err) => return Ok(ApiResponse::from(err).into_response()),
+    };
+
+    req.extensions_mut().insert(operation);
+
+    Ok(next.run(req).await)
+}
+
+mod get {
+    use crate::routes::api::admin::servers::bulk::_operation_::GetServerBulkOperation;
+    use serde::Serialize;
+    use shared::{
+        ApiError,
+        models::user::GetPermissionManager,
+        response::{ApiResponse, ApiResponseResult},
+    };
+    use utoipa::ToSchema;
+
+    #[derive(ToSchema, Serialize)]
+    struct Response {
+        operation: shared::models::server_bulk_operation::AdminApiServerBulkOperation,
+    }
+
+    #[utoipa::path(get, path = "/", responses(
+        (status = OK, body = inline(Response)),
+        (status = NOT_FOUND, body = ApiError),
+    ), params(
+        (
+            "operation" = uuid::Uuid,
+            description = "The bulk operation ID",
+            example = "123e4567-e89b-12d3-a456-426614174000",
+        ),
+    ))]
+    pub async fn route(
+        permissions: GetPermissionManager,
+        operation: GetServerBulkOperation,
+    ) -> ApiResponseResult {
+        permissions.has_admin_permission("servers.bulk")?;
+
+        ApiResponse::new_serialized(Response {
+            operation: operation.0.into_admin_api_object(),
+        })
+        .ok()
+    }
+}
+
+pub fn router(state: &State) -> OpenApiRouter<State> {
+    OpenApiRouter::new()
+        .routes(routes!(get::route))
+        .nest("/results", results::router(state))
+        .route_layer(axum::middleware::from_fn_with_state(state.clone(), auth))
+        .with_state(state.clone())
+}
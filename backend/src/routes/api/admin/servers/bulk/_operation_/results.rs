@@ -0,0 +1,107 @@
+use super::State;
+use utoipa_axum::{router::OpenApiRouter, routes};
+
+mod get {
+    use crate::routes::api::admin::servers::bulk::_operation_::GetServerBulkOperation;
+    use axum::{extract::Query, http::StatusCode};
+    use serde::{Deserialize, Serialize};
+    use shared::{
+        ApiError, GetState,
+        models::{
+            Pagination, server_bulk_operation::ServerBulkOperationResult,
+            user::GetPermissionManager,
+        },
+        response::{ApiResponse, ApiResponseResult},
+    };
+    use utoipa::ToSchema;
+    use validator::Validate;
+
+    #[derive(ToSchema, Validate, Deserialize)]
+    pub struct Params {
+        #[validate(range(min = 1))]
+        #[serde(default = "Pagination::default_page")]
+        pub page: i64,
+        #[validate(range(min = 1, max = 100))]
+        #[serde(default = "Pagination::default_per_page")]
+        pub per_page: i64,
+
+        #[serde(default)]
+        failed: bool,
+    }
+
+    #[derive(ToSchema, Serialize)]
+    struct Response {
+        #[schema(inline)]
+        results:
+            Pagination<shared::models::server_bulk_operation::AdminApiServerBulkOperationResult>,
+    }
+
+    #[utoipa::path(get, path = "/", responses(
+        (status = OK, body = inline(Response)),
+        (status = NOT_FOUND, body = ApiError),
+    ), params(
+        (
+            "operation" = uuid::Uuid,
+            description = "The bulk operation ID",
+            example = "123e4567-e89b-12d3-a456-426614174000",
+        ),
+        (
+            "page" = i64, Query,
+            description = "The page number",
+            example = "1",
+        ),
+        (
+            "per_page" = i64, Query,
+            description = "The number of items per page",
+            example = "10",
+        ),
+        (
+            "failed" = bool, Query,
+            description = "Only show servers the operation failed on",
+            example = "false",
+        ),
+    ))]
+    pub async fn route(
+        state: GetState,
+        permissions: GetPermissionManager,
+        operation: GetServerBulkOperation,
+        Query(params): Query<Params>,
+    ) -> ApiResponseResult {
+        if let Err(errors) = shared::utils::validate_data(&params) {
+            return ApiResponse::new_serialized(ApiError::new_strings_value(errors))
+                .with_status(StatusCode::BAD_REQUEST)
+                .ok();
+        }
+
+        permissions.has_admin_permission("servers.bulk")?;
+
+        let results = ServerBulkOperationResult::by_bulk_operation_uuid_with_pagination(
+            &state.database,
+            operation.uuid,
+            params.page,
+            params.per_page,
+            params.failed,
+        )
+        .await?;
+
+        ApiResponse::new_serialized(Response {
+            results: Pagination {
+                total: results.total,
+                per_page: results.per_page,
+                page: results.page,
+                data: results
+                    .data
+                    .into_iter()
+                    .map(|result| result.into_admin_api_object())
+                    .collect(),
+            },
+        })
+        .ok()
+    }
+}
+
+pub fn router(state: &State) -> OpenApiRouter<State> {
+    OpenApiRouter::new()
+        .routes(routes!(get::route))
+        .with_state(state.clone())
+}
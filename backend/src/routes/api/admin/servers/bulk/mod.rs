@@ -0,0 +1,188 @@
+use super::State;
+use utoipa_axum::{router::OpenApiRouter, routes};
+
+mod _operation_;
+
+mod get {
+    use axum::{extract::Query, http::StatusCode};
+    use serde::Serialize;
+    use shared::{
+        ApiError, GetState,
+        models::{
+            Pagination, PaginationParams, server_bulk_operation::ServerBulkOperation,
+            user::GetPermissionManager,
+        },
+        response::{ApiResponse, ApiResponseResult},
+    };
+    use utoipa::ToSchema;
+
+    #[derive(ToSchema, Serialize)]
+    struct Response {
+        #[schema(inline)]
+        operations: Pagination<shared::models::server_bulk_operation::AdminApiServerBulkOperation>,
+    }
+
+    #[utoipa::path(get, path = "/", responses(
+        (status = OK, body = inline(Response)),
+        (status = UNAUTHORIZED, body = ApiError),
+    ), params(
+        (
+            "page" = i64, Query,
+            description = "The page number",
+            example = "1",
+        ),
+        (
+            "per_page" = i64, Query,
+            description = "The number of items per page",
+            example = "10",
+        ),
+    ))]
+    pub async fn route(
+        state: GetState,
+        permissions: GetPermissionManager,
+        Query(params): Query<PaginationParams>,
+    ) -> ApiResponseResult {
+        if let Err(errors) = shared::utils::validate_data(&params) {
+            return ApiResponse::new_serialized(ApiError::new_strings_value(errors))
+                .with_status(StatusCode::BAD_REQUEST)
+                .ok();
+        }
+
+        permissions.has_admin_permission("servers.bulk")?;
+
+        let operations =
+            ServerBulkOperation::all_with_pagination(&state.database, params.page, params.per_page)
+                .await?;
+
+        ApiResponse::new_serialized(Response {
+            operations: Pagination {
+                total: operations.total,
+                per_page: operations.per_page,
+                page: operations.page,
+                data: operations
+                    .data
+                    .into_iter()
+                    .map(|operation| operation.into_admin_api_object())
+                    .collect(),
+            },
+        })
+        .ok()
+    }
+}
+
+mod post {
+    use axum::http::StatusCode;
+    use serde::{Deserialize, Serialize};
+    use shared::{
+        ApiError, GetState,
+        models::{
+            admin_activity::GetAdminActivityLogger,
+            server::Server,
+            server_bulk_operation::{
+                ServerBulkOperation, ServerBulkOperationAction, ServerBulkOperationFilter,
+            },
+            user::GetPermissionManager,
+        },
+        response::{ApiResponse, ApiResponseResult},
+    };
+    use utoipa::ToSchema;
+    use validator::Validate;
+
+    fn default_concurrency() -> i16 {
+        4
+    }
+
+    #[derive(ToSchema, Validate, Deserialize)]
+    pub struct Payload {
+        filter: ServerBulkOperationFilter,
+        action: ServerBulkOperationAction,
+
+        #[validate(range(min = 1, max = 32))]
+        #[schema(minimum = 1, maximum = 32)]
+        #[serde(default = "default_concurrency")]
+        concurrency: i16,
+    }
+
+    #[derive(ToSchema, Serialize)]
+    struct Response {
+        operation: shared::models::server_bulk_operation::AdminApiServerBulkOperation,
+    }
+
+    #[utoipa::path(post, path = "/", responses(
+        (status = OK, body = inline(Response)),
+        (status = BAD_REQUEST, body = ApiError),
+        (status = UNAUTHORIZED, body = ApiError),
+    ), request_body = inline(Payload))]
+    pub async fn route(
+        state: GetState,
+        permissions: GetPermissionManager,
+        activity_logger: GetAdminActivityLogger,
+        shared::Payload(data): shared::Payload<Payload>,
+    ) -> ApiResponseResult {
+        if let Err(errors) = shared::utils::validate_data(&data) {
+            return ApiResponse::new_serialized(ApiError::new_strings_value(errors))
+                .with_status(StatusCode::BAD_REQUEST)
+                .ok();
+        }
+
+        permissions.has_admin_permission("servers.bulk")?;
+        permissions.has_admin_permission(data.action.permission())?;
+
+        if let Err(error) = data.action.validate() {
+            return ApiResponse::error(error)
+                .with_status(StatusCode::BAD_REQUEST)
+                .ok();
+        }
+
+        if data.filter.is_empty() {
+            return ApiResponse::error("filter must contain at least one condition")
+                .with_status(StatusCode::BAD_REQUEST)
+                .ok();
+        }
+
+        let servers = Server::all_by_bulk_filter(&state.database, &data.filter).await?;
+        if servers.is_empty() {
+            return ApiResponse::error("no servers matched the filter")
+                .with_status(StatusCode::BAD_REQUEST)
+                .ok();
+        }
+
+        let operation = ServerBulkOperation::create(
+            &state.database,
+            Some(activity_logger.user_uuid),
+            &data.action,
+            &data.filter,
+            data.concurrency,
+            &servers.iter().map(|server| server.uuid).collect::<Vec<_>>(),
+        )
+        .await?;
+
+        activity_logger
+            .log(
+                "server:bulk-operation.create",
+                serde_json::json!({
+                    "uuid": operation.uuid,
+                    "action": operation.action.redacted(),
+                    "filter": operation.filter,
+                    "concurrency": operation.concurrency,
+                    "total": operation.total,
+                }),
+            )
+            .await;
+
+        operation.clone().spawn(state.0, servers);
+
+        ApiResponse::new_serialized(Response {
+            operation: operation.into_admin_api_object(),
+        })
+        .ok()
+    }
+}
+
+pub fn router(state: &State) -> OpenApiRouter<State> {
+    OpenApiRouter::new()
+        .routes(routes!(get::route))
+        .routes(routes!(post::route))
+        .nest("/{operation}", _operation_::router(state))
+        .with_state(state.clone())
+}
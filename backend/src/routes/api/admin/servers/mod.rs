@@ -2,6 +2,7 @@ use super::State;
 use utoipa_axum::{router::OpenApiRouter, routes};
 
 mod _server_;
+mod bulk;
 mod external;
 
 mod get {
@@ -85,13 +86,15 @@ mod post {
             backup_configurations::BackupConfiguration,
             nest_egg::NestEgg,
             nest_egg_variable::NestEggVariable,
-            node::Node,
+            node::{Node, NodeResources},
+            node_allocation::NodeAllocation,
             server::Server,
+            server_template::ServerTemplate,
             user::{GetPermissionManager, User},
         },
         response::{ApiResponse, ApiResponseResult},
     };
-    use std::collections::HashMap;
+    use std::{collections::HashMap, str::FromStr};
     use utoipa::ToSchema;
     use validator::Validate;
 
@@ -105,15 +108,81 @@ mod post {
         value: String,
     }
 
+    /// Server limits where every field is optional, so a template can fill in the missing ones.
+    #[derive(ToSchema, Validate, Deserialize, Default)]
+    pub struct PayloadLimits {
+        #[validate(range(min = 0))]
+        #[schema(minimum = 0)]
+        cpu: Option<i32>,
+        #[validate(range(min = 0))]
+        #[schema(minimum = 0)]
+        memory: Option<i64>,
+        #[validate(range(min = -1))]
+        #[schema(minimum = -1)]
+        swap: Option<i64>,
+        #[validate(range(min = 0))]
+        #[schema(minimum = 0)]
+        disk: Option<i64>,
+        #[validate(range(min = 0, max = 1000))]
+        #[schema(minimum = 0, maximum = 1000)]
+        io_weight: Option<i16>,
+    }
+
+    impl PayloadLimits {
+        fn into_limits(self) -> Option<shared::models::server::ApiServerLimits> {
+            Some(shared::models::server::ApiServerLimits {
+                cpu: self.cpu?,
+                memory: self.memory?,
+                swap: self.swap?,
+                disk: self.disk?,
+                io_weight: self.io_weight,
+            })
+        }
+    }
+
+    /// Server feature limits where every field is optional, so a template can fill in the
+    /// missing ones.
+    #[derive(ToSchema, Validate, Deserialize, Default)]
+    pub struct PayloadFeatureLimits {
+        #[validate(range(min = 0))]
+        #[schema(minimum = 0)]
+        allocations: Option<i32>,
+        #[validate(range(min = 0))]
+        #[schema(minimum = 0)]
+        databases: Option<i32>,
+        #[validate(range(min = 0))]
+        #[schema(minimum = 0)]
+        backups: Option<i32>,
+        #[validate(range(min = 0))]
+        #[schema(minimum = 0)]
+        schedules: Option<i32>,
+    }
+
+    impl PayloadFeatureLimits {
+        fn into_feature_limits(self) -> Option<shared::models::server::ApiServerFeatureLimits> {
+            Some(shared::models::server::ApiServerFeatureLimits {
+                allocations: self.allocations?,
+                databases: self.databases?,
+                backups: self.backups?,
+                schedules: self.schedules?,
+            })
+        }
+    }
+
     #[derive(ToSchema, Validate, Deserialize)]
     pub struct Payload {
-        node_uuid: uuid::Uuid,
+        template_uuid: Option<uuid::Uuid>,
+
+        node_uuid: Option<uuid::Uuid>,
         owner_uuid: uuid::Uuid,
-        egg_uuid: uuid::Uuid,
+        egg_uuid: Option<uuid::Uuid>,
         backup_configuration_uuid: Option<uuid::Uuid>,
 
         allocation_uuid: Option<uuid::Uuid>,
+        #[serde(default)]
         allocation_uuids: Vec<uuid::Uuid>,
+        #[validate(nested)]
+        deployment: Option<shared::models::server::ApiServerDeployment>,
 
         start_on_completion: bool,
         skip_installer: bool,
@@ -128,24 +197,31 @@ mod post {
         #[schema(max_length = 1024)]
         description: Option<compact_str::CompactString>,
 
-        limits: shared::models::server::ApiServerLimits,
-        pinned_cpus: Vec<i16>,
+        #[validate(nested)]
+        #[schema(inline)]
+        limits: Option<PayloadLimits>,
+        pinned_cpus: Option<Vec<i16>>,
 
         #[validate(length(min = 1, max = 8192))]
         #[schema(min_length = 1, max_length = 8192)]
-        startup: compact_str::CompactString,
+        startup: Option<compact_str::CompactString>,
         #[validate(length(min = 2, max = 255))]
         #[schema(min_length = 2, max_length = 255)]
-        image: compact_str::CompactString,
+        image: Option<compact_str::CompactString>,
         #[schema(min_length = 3, max_length = 255, value_type = String)]
         timezone: Option<chrono_tz::Tz>,
 
-        hugepages_passthrough_enabled: bool,
-        kvm_passthrough_enabled: bool,
+        hugepages_passthrough_enabled: Option<bool>,
+        kvm_passthrough_enabled: Option<bool>,
 
-        feature_limits: shared::models::server::ApiServerFeatureLimits,
+        #[validate(nested)]
+        #[schema(inline)]
+        feature_limits: Option<PayloadFeatureLimits>,
         #[schema(inline)]
+        #[serde(default)]
         variables: Vec<PayloadVariable>,
+
+        expires_at: Option<chrono::DateTime<chrono::Utc>>,
     }
 
     #[derive(ToSchema, Serialize)]
@@ -163,7 +239,7 @@ mod post {
         state: GetState,
         permissions: GetPermissionManager,
         activity_logger: GetAdminActivityLogger,
-        shared::Payload(data): shared::Payload<Payload>,
+        shared::Payload(mut data): shared::Payload<Payload>,
     ) -> ApiResponseResult {
         if let Err(errors) = shared::utils::validate_data(&data) {
             return ApiResponse::new_serialized(ApiError::new_strings_value(errors))
@@ -173,15 +249,198 @@ mod post {
 
         permissions.has_admin_permission("servers.create")?;
 
-        let node = match Node::by_uuid_optional(&state.database, data.node_uuid).await? {
-            Some(node) => node,
-            None => {
-                return ApiResponse::error("node not found")
-                    .with_status(StatusCode::NOT_FOUND)
+        if let Some(template_uuid) = data.template_uuid {
+            let template =
+                match ServerTemplate::by_uuid_optional(&state.database, template_uuid).await? {
+                    Some(template) => template,
+                    None => {
+                        return ApiResponse::error("server template not found")
+                            .with_status(StatusCode::NOT_FOUND)
+                            .ok();
+                    }
+                };
+
+            // fields given in the payload override the template, the startup, image and
+            // variables of the template only apply to its own egg
+            let template_egg = *data.egg_uuid.get_or_insert(template.egg_uuid) == template.egg_uuid;
+
+            if data.node_uuid.is_none() && data.deployment.is_none() {
+                data.deployment = template.deployment.clone();
+            }
+            if data.backup_configuration_uuid.is_none() {
+                data.backup_configuration_uuid = template.backup_configuration_uuid;
+            }
+            let limits = data.limits.get_or_insert_default();
+            limits.cpu.get_or_insert(template.cpu);
+            limits.memory.get_or_insert(template.memory);
+            limits.swap.get_or_insert(template.swap);
+            limits.disk.get_or_insert(template.disk);
+            if limits.io_weight.is_none() {
+                limits.io_weight = template.io_weight;
+            }
+            data.pinned_cpus
+                .get_or_insert_with(|| template.pinned_cpus.clone());
+            if template_egg {
+                data.startup.get_or_insert_with(|| template.startup.clone());
+                data.image.get_or_insert_with(|| template.image.clone());
+            }
+            if data.timezone.is_none() {
+                data.timezone = template
+                    .timezone
+                    .as_deref()
+                    .and_then(|timezone| chrono_tz::Tz::from_str(timezone).ok());
+            }
+            data.hugepages_passthrough_enabled
+                .get_or_insert(template.hugepages_passthrough_enabled);
+            data.kvm_passthrough_enabled
+                .get_or_insert(template.kvm_passthrough_enabled);
+            let feature_limits = data.feature_limits.get_or_insert_default();
+            feature_limits
+                .allocations
+                .get_or_insert(template.allocation_limit);
+            feature_limits
+                .databases
+                .get_or_insert(template.database_limit);
+            feature_limits.backups.get_or_insert(template.backup_limit);
+            feature_limits
+                .schedules
+                .get_or_insert(template.schedule_limit);
+
+            if template_egg {
+                for (env_variable, value) in template.variables {
+                    if !data
+                        .variables
+                        .iter()
+                        .any(|variable| variable.env_variable == env_variable)
+                    {
+                        data.variables.push(PayloadVariable {
+                            env_variable: env_variable.into(),
+                            value: value.into(),
+                        });
+                    }
+                }
+            }
+        }
+
+        let (Some(egg_uuid), Some(limits), Some(startup), Some(image), Some(feature_limits)) = (
+            data.egg_uuid,
+            data.limits.take().and_then(PayloadLimits::into_limits),
+            data.startup.take(),
+            data.image.take(),
+            data.feature_limits
+                .take()
+                .and_then(PayloadFeatureLimits::into_feature_limits),
+        ) else {
+            return ApiResponse::error(
+                "egg_uuid, limits, startup, image and feature_limits are required unless a template provides them",
+            )
+            .with_status(StatusCode::BAD_REQUEST)
+            .ok();
+        };
+        let pinned_cpus = data.pinned_cpus.take().unwrap_or_default();
+        let hugepages_passthrough_enabled = data.hugepages_passthrough_enabled.unwrap_or(false);
+        let kvm_passthrough_enabled = data.kvm_passthrough_enabled.unwrap_or(false);
+
+        // allocations picked for a deployment stay locked until the server is inserted
+        let mut transaction = state.database.write().begin().await?;
+
+        let (node, allocation_uuid, allocation_uuids) = match (&data.deployment, data.node_uuid) {
+            (Some(deployment), _) => {
+                if deployment
+                    .port_ranges
+                    .iter()
+                    .any(|port_range| port_range.start > port_range.end)
+                {
+                    return ApiResponse::error("port range start must not be after its end")
+                        .with_status(StatusCode::BAD_REQUEST)
+                        .ok();
+                }
+
+                let port_ranges = deployment
+                    .port_ranges
+                    .iter()
+                    .map(|port_range| (port_range.start as i32, port_range.end as i32))
+                    .collect::<Vec<_>>();
+
+                let nodes = Node::all_deployable_by_location_uuids(
+                    &state.database,
+                    &deployment.location_uuids,
+                    limits.memory,
+                    limits.disk,
+                    limits.cpu as i64,
+                )
+                .await?;
+
+                let mut deployment_target = None;
+                for node in nodes {
+                    let allocations = NodeAllocation::lock_available_by_node_uuid_port_ranges(
+                        &mut transaction,
+                        node.uuid,
+                        &port_ranges,
+                        deployment.allocations as i64,
+                    )
+                    .await?;
+
+                    if allocations.len() == deployment.allocations as usize {
+                        deployment_target = Some((node, allocations));
+                        break;
+                    }
+                }
+
+                let Some((node, allocations)) = deployment_target else {
+                    return ApiResponse::error(
+                        "no node with enough free resources and allocations found for deployment",
+                    )
+                    .with_status(StatusCode::CONFLICT)
+                    .ok();
+                };
+
+                let mut allocation_uuids = allocations
+                    .into_iter()
+                    .map(|allocation| allocation.uuid)
+                    .collect::<Vec<_>>();
+                let allocation_uuid = if allocation_uuids.is_empty() {
+                    None
+                } else {
+                    Some(allocation_uuids.remove(0))
+                };
+
+                (node, allocation_uuid, allocation_uuids)
+            }
+            (None, Some(node_uuid)) => {
+                match Node::by_uuid_optional(&state.database, node_uuid).await? {
+                    Some(node) => (node, data.allocation_uuid, data.allocation_uuids.clone()),
+                    None => {
+                        return ApiResponse::error("node not found")
+                            .with_status(StatusCode::NOT_FOUND)
+                            .ok();
+                    }
+                }
+            }
+            (None, None) => {
+                return ApiResponse::error("either node_uuid or deployment is required")
+                    .with_status(StatusCode::BAD_REQUEST)
                     .ok();
             }
         };
 
+        if let Some(resource) = node
+            .exceeded_resource(
+                &state.database,
+                NodeResources {
+                    memory: limits.memory,
+                    disk: limits.disk,
+                    cpu: limits.cpu as i64,
+                },
+                None,
+            )
+            .await?
+        {
+            return ApiResponse::error(&format!("node does not have enough {resource} available"))
+                .with_status(StatusCode::CONFLICT)
+                .ok();
+        }
+
         let owner = match User::by_uuid_optional(&state.database, data.owner_uuid).await? {
             Some(user) => user,
             None => {
@@ -191,7 +450,13 @@ mod post {
             }
         };
 
-        let egg = match NestEgg::by_uuid_optional(&state.database, data.egg_uuid).await? {
+        if owner.email_verification_blocks_servers(&*state.settings.get().await?) {
+            return ApiResponse::error("server owner has not verified their email address")
+                .with_status(StatusCode::BAD_REQUEST)
+                .ok();
+        }
+
+        let egg = match NestEgg::by_uuid_optional(&state.database, egg_uuid).await? {
             Some(egg) => egg,
             None => {
                 return ApiResponse::error("egg not found")
@@ -256,47 +521,47 @@ mod post {
                 .ok();
         }
 
-        let mut server_variables = HashMap::new();
+        let mut server_variables = Vec::new();
         server_variables.reserve(variables.len());
 
         for data_variable in &data.variables {
-            let variable_uuid = match variables
+            let variable = match variables
                 .iter()
                 .find(|v| v.env_variable == data_variable.env_variable)
             {
-                Some(variable) => variable.uuid,
+                Some(variable) => variable,
                 None => continue,
             };
 
-            server_variables.insert(variable_uuid, data_variable.value.as_str());
+            server_variables.push((variable, data_variable.value.as_str()));
         }
 
-        let server = match Server::create(
+        let server_uuid = match Server::create_with_transaction(
+            &mut transaction,
             &state.database,
             &node,
             owner.uuid,
             egg.uuid,
             backup_configuration.map(|backup_configuration| backup_configuration.uuid),
-            data.allocation_uuid,
-            &data.allocation_uuids,
+            allocation_uuid,
+            &allocation_uuids,
             data.external_id.as_deref(),
-            data.start_on_completion,
             data.skip_installer,
             &data.name,
             data.description.as_deref(),
-            &data.limits,
-            &data.pinned_cpus,
-            &data.startup,
-            &data.image,
+            &limits,
+            &pinned_cpus,
+            &startup,
+            &image,
             data.timezone.as_ref().map(|tz| tz.name()),
-            data.hugepages_passthrough_enabled,
-            data.kvm_passthrough_enabled,
-            &data.feature_limits,
+            hugepages_passthrough_enabled,
+            kvm_passthrough_enabled,
+            &feature_limits,
             &server_variables,
         )
         .await
         {
-            Ok(server_uuid) => Server::by_uuid(&state.database, server_uuid).await?,
+            Ok(server_uuid) => server_uuid,
             Err(err) if err.is_unique_violation() => {
                 return ApiResponse::error("server with allocation(s) already exists")
                     .with_status(StatusCode::CONFLICT)
@@ -311,17 +576,43 @@ mod post {
             }
         };
 
+        transaction.commit().await?;
+
+        if let Err(err) = Server::create_on_node(
+            &state.database,
+            &node,
+            server_uuid,
+            data.start_on_completion,
+            data.skip_installer,
+        )
+        .await
+        {
+            return ApiResponse::error(&format!("failed to create server: {err}"))
+                .with_status(StatusCode::INTERNAL_SERVER_ERROR)
+                .ok();
+        }
+
+        let mut server = Server::by_uuid(&state.database, server_uuid).await?;
+
+        if let Some(expires_at) = data.expires_at {
+            server
+                .update_expires_at(&state.database, Some(expires_at.naive_utc()))
+                .await?;
+        }
+
         activity_logger
             .log(
                 "server:create",
                 serde_json::json!({
                     "uuid": server.uuid,
+                    "template_uuid": data.template_uuid,
                     "node_uuid": node.uuid,
                     "owner_uuid": owner.uuid,
                     "egg_uuid": egg.uuid,
 
-                    "allocation_uuid": data.allocation_uuid,
-                    "allocation_uuids": data.allocation_uuids,
+                    "allocation_uuid": allocation_uuid,
+                    "allocation_uuids": allocation_uuids,
+                    "deployment": data.deployment,
                     "external_id": data.external_id,
 
                     "start_on_completion": data.start_on_completion,
@@ -329,17 +620,24 @@ mod post {
 
                     "name": data.name,
                     "description": data.description,
-                    "limits": data.limits,
-                    "pinned_cpus": data.pinned_cpus,
-                    "startup": data.startup,
-                    "image": data.image,
+                    "limits": limits,
+                    "pinned_cpus": pinned_cpus,
+                    "startup": startup,
+                    "image": image,
                     "timezone": data.timezone,
 
-                    "hugepages_passthrough_enabled": data.hugepages_passthrough_enabled,
-                    "kvm_passthrough_enabled": data.kvm_passthrough_enabled,
+                    "hugepages_passthrough_enabled": hugepages_passthrough_enabled,
+                    "kvm_passthrough_enabled": kvm_passthrough_enabled,
 
-                    "feature_limits": data.feature_limits,
-                    "variables": data.variables,
+                    "feature_limits": feature_limits,
+                    "expires_at": data.expires_at,
+                    "variables": server_variables
+                        .iter()
+                        .map(|(variable, value)| serde_json::json!({
+                            "env_variable": variable.env_variable,
+                            "value": if variable.secret { "" } else { *value },
+                        }))
+                        .collect::<Vec<_>>(),
                 }),
             )
             .await;
@@ -358,6 +656,7 @@ pub fn router(state: &State) -> OpenApiRouter<State> {
         .routes(routes!(get::route))
         .routes(routes!(post::route))
         .nest("/{server}", _server_::router(state))
+        .nest("/bulk", bulk::router(state))
         .nest("/external", external::router(state))
         .with_state(state.clone())
 }
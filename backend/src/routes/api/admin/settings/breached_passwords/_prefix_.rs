@@ -0,0 +1,72 @@
+use super::State;
+use utoipa_axum::{router::OpenApiRouter, routes};
+
+mod put {
+    use axum::{extract::Path, http::StatusCode};
+    use serde::Serialize;
+    use shared::{
+        ApiError, GetState,
+        models::{
+            admin_activity::GetAdminActivityLogger, breached_password_range::BreachedPasswordRange,
+            user::GetPermissionManager,
+        },
+        response::{ApiResponse, ApiResponseResult},
+    };
+    use utoipa::ToSchema;
+
+    #[derive(ToSchema, Serialize)]
+    struct Response {
+        hashes: usize,
+    }
+
+    #[utoipa::path(put, path = "/", responses(
+        (status = OK, body = inline(Response)),
+        (status = BAD_REQUEST, body = ApiError),
+    ), params(
+        (
+            "prefix" = String,
+            description = "The first five characters of the SHA-1 hashes in this range",
+            example = "21BD1",
+        ),
+    ), request_body = String)]
+    pub async fn route(
+        state: GetState,
+        permissions: GetPermissionManager,
+        activity_logger: GetAdminActivityLogger,
+        Path(prefix): Path<String>,
+        body: String,
+    ) -> ApiResponseResult {
+        permissions.has_admin_permission("settings.update")?;
+
+        if prefix.len() != 5 || !prefix.chars().all(|c| c.is_ascii_hexdigit()) {
+            return ApiResponse::error("invalid range prefix")
+                .with_status(StatusCode::BAD_REQUEST)
+                .ok();
+        }
+
+        let suffixes = BreachedPasswordRange::parse_suffixes(&body)?;
+        let range =
+            BreachedPasswordRange::create_or_replace(&state.database, &prefix, &suffixes).await?;
+
+        activity_logger
+            .log(
+                "settings:breached-passwords.upload",
+                serde_json::json!({
+                    "prefix": range.prefix,
+                    "hashes": range.suffixes.len(),
+                }),
+            )
+            .await;
+
+        ApiResponse::new_serialized(Response {
+            hashes: range.suffixes.len(),
+        })
+        .ok()
+    }
+}
+
+pub fn router(state: &State) -> OpenApiRouter<State> {
+    OpenApiRouter::new()
+        .routes(routes!(put::route))
+        .with_state(state.clone())
+}
@@ -0,0 +1,81 @@
+use super::State;
+use utoipa_axum::{router::OpenApiRouter, routes};
+
+mod _prefix_;
+
+mod get {
+    use serde::Serialize;
+    use shared::{
+        GetState,
+        models::{breached_password_range::BreachedPasswordRange, user::GetPermissionManager},
+        response::{ApiResponse, ApiResponseResult},
+    };
+    use utoipa::ToSchema;
+
+    #[derive(ToSchema, Serialize)]
+    struct Response {
+        ranges: i64,
+        hashes: i64,
+    }
+
+    #[utoipa::path(get, path = "/", responses(
+        (status = OK, body = inline(Response)),
+    ))]
+    pub async fn route(state: GetState, permissions: GetPermissionManager) -> ApiResponseResult {
+        permissions.has_admin_permission("settings.read")?;
+
+        let (ranges, hashes) = BreachedPasswordRange::count(&state.database).await?;
+
+        ApiResponse::new_serialized(Response { ranges, hashes }).ok()
+    }
+}
+
+mod delete {
+    use serde::Serialize;
+    use shared::{
+        GetState,
+        models::{
+            admin_activity::GetAdminActivityLogger, breached_password_range::BreachedPasswordRange,
+            user::GetPermissionManager,
+        },
+        response::{ApiResponse, ApiResponseResult},
+    };
+    use utoipa::ToSchema;
+
+    #[derive(ToSchema, Serialize)]
+    struct Response {
+        deleted: u64,
+    }
+
+    #[utoipa::path(delete, path = "/", responses(
+        (status = OK, body = inline(Response)),
+    ))]
+    pub async fn route(
+        state: GetState,
+        permissions: GetPermissionManager,
+        activity_logger: GetAdminActivityLogger,
+    ) -> ApiResponseResult {
+        permissions.has_admin_permission("settings.update")?;
+
+        let deleted = BreachedPasswordRange::delete_all(&state.database).await?;
+
+        activity_logger
+            .log(
+                "settings:breached-passwords.clear",
+                serde_json::json!({
+                    "ranges": deleted,
+                }),
+            )
+            .await;
+
+        ApiResponse::new_serialized(Response { deleted }).ok()
+    }
+}
+
+pub fn router(state: &State) -> OpenApiRouter<State> {
+    OpenApiRouter::new()
+        .routes(routes!(get::route))
+        .routes(routes!(delete::route))
+        .nest("/{prefix}", _prefix_::router(state))
+        .with_state(state.clone())
+}
@@ -1,6 +1,10 @@
 use super::State;
 use utoipa_axum::{router::OpenApiRouter, routes};
 
+mod breached_passwords;
+mod scim_token;
+mod test_ldap;
+
 mod get {
     use serde::Serialize;
     use shared::{
@@ -36,7 +40,9 @@ mod put {
     use serde::{Deserialize, Serialize};
     use shared::{
         ApiError, GetState,
-        models::{admin_activity::GetAdminActivityLogger, user::GetPermissionManager},
+        models::{
+            ByUuid, admin_activity::GetAdminActivityLogger, role::Role, user::GetPermissionManager,
+        },
         response::{ApiResponse, ApiResponseResult},
     };
     use utoipa::ToSchema;
@@ -54,6 +60,7 @@ mod put {
         )]
         language: Option<compact_str::CompactString>,
         two_factor_requirement: Option<shared::settings::TwoFactorRequirement>,
+        email_verification: Option<shared::settings::EmailVerificationMode>,
         telemetry_enabled: Option<bool>,
         registration_enabled: Option<bool>,
     }
@@ -66,7 +73,7 @@ mod put {
         rp_origin: Option<compact_str::CompactString>,
     }
 
-    #[derive(ToSchema, Deserialize)]
+    #[derive(ToSchema, Validate, Deserialize)]
     pub struct PayloadServer {
         max_file_manager_view_size: Option<u64>,
         max_file_manager_content_search_size: Option<u64>,
@@ -75,6 +82,12 @@ mod put {
 
         allow_overwriting_custom_docker_image: Option<bool>,
         allow_editing_startup_command: Option<bool>,
+
+        #[validate(range(max = 365))]
+        expiry_notice_days: Option<u16>,
+        #[validate(range(max = 3650))]
+        expiry_grace_period_days: Option<u16>,
+        expiry_delete_servers: Option<bool>,
     }
 
     #[derive(ToSchema, Validate, Deserialize)]
@@ -90,6 +103,33 @@ mod put {
         server_log_schedule_activity: Option<bool>,
     }
 
+    #[derive(ToSchema, Validate, Deserialize)]
+    pub struct PayloadSecurity {
+        lockout_enabled: Option<bool>,
+        #[validate(range(min = 1, max = 100))]
+        lockout_threshold: Option<u16>,
+        #[validate(range(min = 1, max = 525600))]
+        lockout_duration_minutes: Option<u32>,
+        #[validate(range(min = 1, max = 525600))]
+        lockout_max_duration_minutes: Option<u32>,
+
+        #[validate(range(min = 8, max = 512))]
+        password_min_length: Option<u16>,
+        password_require_uppercase: Option<bool>,
+        password_require_lowercase: Option<bool>,
+        password_require_number: Option<bool>,
+        password_require_symbol: Option<bool>,
+        password_forbid_user_details: Option<bool>,
+        #[validate(range(max = 24))]
+        password_history_count: Option<u16>,
+        password_check_breached: Option<bool>,
+    }
+
+    #[derive(ToSchema, Validate, Deserialize)]
+    pub struct PayloadScim {
+        enabled: Option<bool>,
+    }
+
     #[derive(ToSchema, Validate, Deserialize)]
     pub struct Payload {
         oobe_step: Option<compact_str::CompactString>,
@@ -97,6 +137,7 @@ mod put {
         storage_driver: Option<shared::settings::StorageDriver>,
         mail_mode: Option<shared::settings::MailMode>,
         captcha_provider: Option<shared::settings::CaptchaProvider>,
+        ldap_mode: Option<shared::settings::LdapMode>,
 
         #[schema(inline)]
         app: Option<PayloadApp>,
@@ -106,6 +147,10 @@ mod put {
         server: Option<PayloadServer>,
         #[schema(inline)]
         activity: Option<PayloadActivity>,
+        #[schema(inline)]
+        security: Option<PayloadSecurity>,
+        #[schema(inline)]
+        scim: Option<PayloadScim>,
     }
 
     #[derive(ToSchema, Serialize)]
@@ -113,6 +158,8 @@ mod put {
 
     #[utoipa::path(put, path = "/", responses(
         (status = OK, body = inline(Response)),
+        (status = BAD_REQUEST, body = ApiError),
+        (status = NOT_FOUND, body = ApiError),
     ), request_body = inline(Payload))]
     pub async fn route(
         state: GetState,
@@ -128,6 +175,19 @@ mod put {
 
         permissions.has_admin_permission("settings.update")?;
 
+        if let Some(shared::settings::LdapMode::Ldap(ldap)) = &data.ldap_mode {
+            for mapping in &ldap.group_role_mappings {
+                if Role::by_uuid_optional(&state.database, mapping.role_uuid)
+                    .await?
+                    .is_none()
+                {
+                    return ApiResponse::error("ldap group role mapping role not found")
+                        .with_status(StatusCode::NOT_FOUND)
+                        .ok();
+                }
+            }
+        }
+
         let mut settings = state.settings.get_mut().await?;
 
         if let Some(oobe_step) = data.oobe_step {
@@ -148,6 +208,9 @@ mod put {
         if let Some(captcha_provider) = data.captcha_provider {
             settings.captcha_provider = captcha_provider;
         }
+        if let Some(ldap_mode) = data.ldap_mode {
+            settings.ldap_mode = ldap_mode;
+        }
         if let Some(app) = data.app {
             if let Some(name) = app.name {
                 settings.app.name = name;
@@ -161,6 +224,9 @@ mod put {
             if let Some(two_factor_requirement) = app.two_factor_requirement {
                 settings.app.two_factor_requirement = two_factor_requirement;
             }
+            if let Some(email_verification) = app.email_verification {
+                settings.app.email_verification = email_verification;
+            }
             if let Some(telemetry_enabled) = app.telemetry_enabled {
                 settings.app.telemetry_enabled = telemetry_enabled;
             }
@@ -201,6 +267,15 @@ mod put {
             if let Some(allow_editing_startup_command) = server.allow_editing_startup_command {
                 settings.server.allow_editing_startup_command = allow_editing_startup_command;
             }
+            if let Some(expiry_notice_days) = server.expiry_notice_days {
+                settings.server.expiry_notice_days = expiry_notice_days;
+            }
+            if let Some(expiry_grace_period_days) = server.expiry_grace_period_days {
+                settings.server.expiry_grace_period_days = expiry_grace_period_days;
+            }
+            if let Some(expiry_delete_servers) = server.expiry_delete_servers {
+                settings.server.expiry_delete_servers = expiry_delete_servers;
+            }
         }
         if let Some(activity) = data.activity {
             if let Some(admin_log_retention_days) = activity.admin_log_retention_days {
@@ -219,6 +294,49 @@ mod put {
                 settings.activity.server_log_schedule_activity = server_log_schedule_activity;
             }
         }
+        if let Some(security) = data.security {
+            if let Some(lockout_enabled) = security.lockout_enabled {
+                settings.security.lockout_enabled = lockout_enabled;
+            }
+            if let Some(lockout_threshold) = security.lockout_threshold {
+                settings.security.lockout_threshold = lockout_threshold;
+            }
+            if let Some(lockout_duration_minutes) = security.lockout_duration_minutes {
+                settings.security.lockout_duration_minutes = lockout_duration_minutes;
+            }
+            if let Some(lockout_max_duration_minutes) = security.lockout_max_duration_minutes {
+                settings.security.lockout_max_duration_minutes = lockout_max_duration_minutes;
+            }
+            if let Some(password_min_length) = security.password_min_length {
+                settings.security.password_min_length = password_min_length;
+            }
+            if let Some(password_require_uppercase) = security.password_require_uppercase {
+                settings.security.password_require_uppercase = password_require_uppercase;
+            }
+            if let Some(password_require_lowercase) = security.password_require_lowercase {
+                settings.security.password_require_lowercase = password_require_lowercase;
+            }
+            if let Some(password_require_number) = security.password_require_number {
+                settings.security.password_require_number = password_require_number;
+            }
+            if let Some(password_require_symbol) = security.password_require_symbol {
+                settings.security.password_require_symbol = password_require_symbol;
+            }
+            if let Some(password_forbid_user_details) = security.password_forbid_user_details {
+                settings.security.password_forbid_user_details = password_forbid_user_details;
+            }
+            if let Some(password_history_count) = security.password_history_count {
+                settings.security.password_history_count = password_history_count;
+            }
+            if let Some(password_check_breached) = security.password_check_breached {
+                settings.security.password_check_breached = password_check_breached;
+            }
+        }
+        if let Some(scim) = data.scim
+            && let Some(enabled) = scim.enabled
+        {
+            settings.scim.enabled = enabled;
+        }
 
         let settings_json = settings.censored();
         settings.save().await?;
@@ -233,5 +351,8 @@ pub fn router(state: &State) -> OpenApiRouter<State> {
     OpenApiRouter::new()
         .routes(routes!(get::route))
         .routes(routes!(put::route))
+        .nest("/breached-passwords", breached_passwords::router(state))
+        .nest("/scim-token", scim_token::router(state))
+        .nest("/test-ldap", test_ldap::router(state))
         .with_state(state.clone())
 }
@@ -0,0 +1,84 @@
+use super::State;
+use utoipa_axum::{router::OpenApiRouter, routes};
+
+mod post {
+    use rand::distr::SampleString;
+    use serde::Serialize;
+    use shared::{
+        ApiError, GetState,
+        models::{admin_activity::GetAdminActivityLogger, user::GetPermissionManager},
+        response::{ApiResponse, ApiResponseResult},
+    };
+    use utoipa::ToSchema;
+
+    #[derive(ToSchema, Serialize)]
+    struct Response {
+        token: String,
+    }
+
+    #[utoipa::path(post, path = "/", responses(
+        (status = OK, body = inline(Response)),
+        (status = UNAUTHORIZED, body = ApiError),
+    ))]
+    pub async fn route(
+        state: GetState,
+        permissions: GetPermissionManager,
+        activity_logger: GetAdminActivityLogger,
+    ) -> ApiResponseResult {
+        permissions.has_admin_permission("settings.update")?;
+
+        let token = rand::distr::Alphanumeric.sample_string(&mut rand::rng(), 64);
+
+        let mut settings = state.settings.get_mut().await?;
+        settings.scim.token_hash = Some(shared::scim::hash_token(&token));
+        settings.save().await?;
+
+        activity_logger
+            .log("settings:scim-token:create", serde_json::json!({}))
+            .await;
+
+        ApiResponse::new_serialized(Response { token }).ok()
+    }
+}
+
+mod delete {
+    use serde::Serialize;
+    use shared::{
+        ApiError, GetState,
+        models::{admin_activity::GetAdminActivityLogger, user::GetPermissionManager},
+        response::{ApiResponse, ApiResponseResult},
+    };
+    use utoipa::ToSchema;
+
+    #[derive(ToSchema, Serialize)]
+    struct Response {}
+
+    #[utoipa::path(delete, path = "/", responses(
+        (status = OK, body = inline(Response)),
+        (status = UNAUTHORIZED, body = ApiError),
+    ))]
+    pub async fn route(
+        state: GetState,
+        permissions: GetPermissionManager,
+        activity_logger: GetAdminActivityLogger,
+    ) -> ApiResponseResult {
+        permissions.has_admin_permission("settings.update")?;
+
+        let mut settings = state.settings.get_mut().await?;
+        settings.scim.token_hash = None;
+        settings.save().await?;
+
+        activity_logger
+            .log("settings:scim-token:delete", serde_json::json!({}))
+            .await;
+
+        ApiResponse::new_serialized(Response {}).ok()
+    }
+}
+
+pub fn router(state: &State) -> OpenApiRouter<State> {
+    OpenApiRouter::new()
+        .routes(routes!(post::route))
+        .routes(routes!(delete::route))
+        .with_state(state.clone())
+}
@@ -0,0 +1,116 @@
+use super::State;
+use utoipa_axum::{router::OpenApiRouter, routes};
+
+mod post {
+    use axum::http::StatusCode;
+    use serde::{Deserialize, Serialize};
+    use shared::{
+        ApiError, GetState,
+        ldap::Ldap,
+        models::user::GetPermissionManager,
+        response::{ApiResponse, ApiResponseResult},
+    };
+    use utoipa::ToSchema;
+    use validator::Validate;
+
+    #[derive(ToSchema, Validate, Deserialize)]
+    pub struct Payload {
+        ldap_mode: Option<shared::settings::LdapMode>,
+
+        #[validate(length(min = 1, max = 255))]
+        #[schema(min_length = 1, max_length = 255)]
+        username: String,
+        #[validate(length(min = 1, max = 512))]
+        #[schema(min_length = 1, max_length = 512)]
+        password: String,
+    }
+
+    #[derive(ToSchema, Serialize)]
+    struct ResponseUser {
+        dn: String,
+        identifier: compact_str::CompactString,
+
+        username: compact_str::CompactString,
+        email: compact_str::CompactString,
+        name_first: compact_str::CompactString,
+        name_last: compact_str::CompactString,
+
+        groups: Vec<String>,
+        role_uuid: Option<uuid::Uuid>,
+    }
+
+    #[derive(ToSchema, Serialize)]
+    struct Response {
+        #[schema(inline)]
+        user: ResponseUser,
+    }
+
+    #[utoipa::path(post, path = "/", responses(
+        (status = OK, body = inline(Response)),
+        (status = BAD_REQUEST, body = ApiError),
+        (status = NOT_FOUND, body = ApiError),
+    ), request_body = inline(Payload))]
+    pub async fn route(
+        state: GetState,
+        permissions: GetPermissionManager,
+        shared::Payload(data): shared::Payload<Payload>,
+    ) -> ApiResponseResult {
+        if let Err(errors) = shared::utils::validate_data(&data) {
+            return ApiResponse::new_serialized(ApiError::new_strings_value(errors))
+                .with_status(StatusCode::BAD_REQUEST)
+                .ok();
+        }
+
+        permissions.has_admin_permission("settings.update")?;
+
+        let ldap_mode = match data.ldap_mode {
+            Some(ldap_mode) => ldap_mode,
+            None => state.settings.get().await?.ldap_mode.clone(),
+        };
+
+        let group_role_mappings = match &ldap_mode {
+            shared::settings::LdapMode::None => {
+                return ApiResponse::error("ldap is not enabled")
+                    .with_status(StatusCode::BAD_REQUEST)
+                    .ok();
+            }
+            shared::settings::LdapMode::Ldap(ldap) => &ldap.group_role_mappings,
+        };
+
+        let user = match Ldap::authenticate(&ldap_mode, &data.username, &data.password).await {
+            Ok(Some(user)) => user,
+            Ok(None) => {
+                return ApiResponse::error("invalid username or password")
+                    .with_status(StatusCode::NOT_FOUND)
+                    .ok();
+            }
+            Err(err) => {
+                return ApiResponse::error(&format!("failed to query ldap server: {err}"))
+                    .with_status(StatusCode::BAD_REQUEST)
+                    .ok();
+            }
+        };
+
+        let role_uuid = user.mapped_role_uuid(group_role_mappings);
+
+        ApiResponse::new_serialized(Response {
+            user: ResponseUser {
+                dn: user.dn,
+                identifier: user.identifier,
+                username: user.username,
+                email: user.email,
+                name_first: user.name_first,
+                name_last: user.name_last,
+                groups: user.groups,
+                role_uuid,
+            },
+        })
+        .ok()
+    }
+}
+
+pub fn router(state: &State) -> OpenApiRouter<State> {
+    OpenApiRouter::new()
+        .routes(routes!(post::route))
+        .with_state(state.clone())
+}
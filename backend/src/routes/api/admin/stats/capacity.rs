@@ -0,0 +1,136 @@
+use super::State;
+use utoipa_axum::{router::OpenApiRouter, routes};
+
+mod get {
+    use serde::Serialize;
+    use shared::{
+        GetState,
+        models::{node::Node, user::GetPermissionManager},
+        response::{ApiResponse, ApiResponseResult},
+    };
+    use utoipa::ToSchema;
+
+    #[derive(ToSchema, Serialize, Clone, Copy)]
+    struct ResponseResource {
+        total: i64,
+        limit: Option<i64>,
+        allocated: i64,
+        available: Option<i64>,
+    }
+
+    impl ResponseResource {
+        fn new(total: i64, limit: Option<i64>, allocated: i64) -> Self {
+            Self {
+                total,
+                limit,
+                allocated,
+                available: limit.map(|limit| (limit - allocated).max(0)),
+            }
+        }
+
+        fn merge(self, other: Self) -> Self {
+            let limit = self.limit.zip(other.limit).map(|(a, b)| a + b);
+
+            Self::new(
+                self.total + other.total,
+                limit,
+                self.allocated + other.allocated,
+            )
+        }
+    }
+
+    #[derive(ToSchema, Serialize)]
+    struct ResponseNode {
+        uuid: uuid::Uuid,
+        location_uuid: uuid::Uuid,
+        name: compact_str::CompactString,
+
+        #[schema(inline)]
+        memory: ResponseResource,
+        #[schema(inline)]
+        disk: ResponseResource,
+        #[schema(inline)]
+        cpu: ResponseResource,
+    }
+
+    #[derive(ToSchema, Serialize)]
+    struct ResponseLocation {
+        uuid: uuid::Uuid,
+        name: compact_str::CompactString,
+        nodes: i64,
+
+        #[schema(inline)]
+        memory: ResponseResource,
+        #[schema(inline)]
+        disk: ResponseResource,
+        #[schema(inline)]
+        cpu: ResponseResource,
+    }
+
+    #[derive(ToSchema, Serialize)]
+    struct Response {
+        #[schema(inline)]
+        nodes: Vec<ResponseNode>,
+        #[schema(inline)]
+        locations: Vec<ResponseLocation>,
+    }
+
+    #[utoipa::path(get, path = "/", responses(
+        (status = OK, body = inline(Response)),
+    ))]
+    pub async fn route(state: GetState, permissions: GetPermissionManager) -> ApiResponseResult {
+        permissions.has_admin_permission("stats.read")?;
+
+        let nodes = Node::all_with_committed_resources(&state.database).await?;
+
+        let mut response_nodes = Vec::with_capacity(nodes.len());
+        let mut response_locations: Vec<ResponseLocation> = Vec::new();
+
+        for (node, committed) in nodes {
+            let limits = node.effective_limits();
+
+            let response_node = ResponseNode {
+                uuid: node.uuid,
+                location_uuid: node.location.uuid,
+                name: node.name,
+                memory: ResponseResource::new(node.memory, limits.memory, committed.memory),
+                disk: ResponseResource::new(node.disk, limits.disk, committed.disk),
+                cpu: ResponseResource::new(node.cpu as i64, limits.cpu, committed.cpu),
+            };
+
+            match response_locations
+                .iter_mut()
+                .find(|location| location.uuid == node.location.uuid)
+            {
+                Some(location) => {
+                    location.nodes += 1;
+                    location.memory = location.memory.merge(response_node.memory);
+                    location.disk = location.disk.merge(response_node.disk);
+                    location.cpu = location.cpu.merge(response_node.cpu);
+                }
+                None => response_locations.push(ResponseLocation {
+                    uuid: node.location.uuid,
+                    name: node.location.name,
+                    nodes: 1,
+                    memory: response_node.memory,
+                    disk: response_node.disk,
+                    cpu: response_node.cpu,
+                }),
+            }
+
+            response_nodes.push(response_node);
+        }
+
+        ApiResponse::new_serialized(Response {
+            nodes: response_nodes,
+            locations: response_locations,
+        })
+        .ok()
+    }
+}
+
+pub fn router(state: &State) -> OpenApiRouter<State> {
+    OpenApiRouter::new()
+        .routes(routes!(get::route))
+        .with_state(state.clone())
+}
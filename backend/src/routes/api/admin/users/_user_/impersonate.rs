@@ -0,0 +1,141 @@
+use super::State;
+use utoipa_axum::{router::OpenApiRouter, routes};
+
+mod post {
+    use crate::routes::api::admin::users::_user_::GetParamUser;
+    use axum::http::StatusCode;
+    use serde::Serialize;
+    use shared::{
+        ApiError, GetState,
+        models::{
+            admin_activity::GetAdminActivityLogger,
+            user::{GetPermissionManager, GetUser},
+            user_activity::UserActivity,
+            user_session::UserSession,
+        },
+        response::{ApiResponse, ApiResponseResult},
+    };
+    use tower_cookies::{Cookie, Cookies};
+    use utoipa::ToSchema;
+
+    #[derive(ToSchema, Serialize)]
+    struct Response {}
+
+    #[utoipa::path(post, path = "/", responses(
+        (status = OK, body = inline(Response)),
+        (status = BAD_REQUEST, body = ApiError),
+        (status = FORBIDDEN, body = ApiError),
+    ), params(
+        (
+            "user" = uuid::Uuid,
+            description = "The user ID",
+            example = "123e4567-e89b-12d3-a456-426614174000",
+        ),
+    ))]
+    pub async fn route(
+        state: GetState,
+        ip: shared::GetIp,
+        headers: axum::http::HeaderMap,
+        cookies: Cookies,
+        permissions: GetPermissionManager,
+        impersonator: GetUser,
+        user: GetParamUser,
+        activity_logger: GetAdminActivityLogger,
+    ) -> ApiResponseResult {
+        permissions.has_admin_permission("users.impersonate")?;
+
+        if user.uuid == impersonator.uuid {
+            return ApiResponse::error("you cannot impersonate yourself")
+                .with_status(StatusCode::BAD_REQUEST)
+                .ok();
+        }
+
+        let user_has_admin_access = user.admin
+            || user
+                .role
+                .as_ref()
+                .is_some_and(|role| !role.admin_permissions.is_empty());
+        if user_has_admin_access && !impersonator.admin {
+            return ApiResponse::error("only admins can impersonate users with admin access")
+                .with_status(StatusCode::FORBIDDEN)
+                .ok();
+        }
+
+        let key = UserSession::create_impersonation(
+            &state.database,
+            user.uuid,
+            impersonator.uuid,
+            ip.0.into(),
+            headers
+                .get("User-Agent")
+                .map(|ua| shared::utils::slice_up_to(ua.to_str().unwrap_or("unknown"), 255))
+                .unwrap_or("unknown"),
+        )
+        .await?;
+
+        let settings = state.settings.get().await?;
+        let secure = settings.app.url.starts_with("https://");
+        drop(settings);
+
+        if let Some(session) = cookies.get("session") {
+            cookies.add(
+                Cookie::build(("impersonator_session", session.value().to_string()))
+                    .http_only(true)
+                    .same_site(tower_cookies::cookie::SameSite::Lax)
+                    .secure(secure)
+                    .path("/")
+                    .expires(
+                        tower_cookies::cookie::time::OffsetDateTime::now_utc()
+                            + tower_cookies::cookie::time::Duration::days(30),
+                    )
+                    .build(),
+            );
+        }
+
+        cookies.add(
+            Cookie::build(("session", key))
+                .http_only(true)
+                .same_site(tower_cookies::cookie::SameSite::Lax)
+                .secure(secure)
+                .path("/")
+                .expires(
+                    tower_cookies::cookie::time::OffsetDateTime::now_utc()
+                        + tower_cookies::cookie::time::Duration::hours(1),
+                )
+                .build(),
+        );
+
+        if let Err(err) = UserActivity::log(
+            &state.database,
+            user.uuid,
+            None,
+            "impersonation:start",
+            Some(ip.0.into()),
+            serde_json::json!({
+                "impersonator": impersonator.uuid,
+            }),
+        )
+        .await
+        {
+            tracing::warn!(user = %user.uuid, "failed to log user activity: {:?}", err);
+        }
+
+        activity_logger
+            .log(
+                "user:impersonate",
+                serde_json::json!({
+                    "user": user.uuid,
+                    "username": user.username,
+                }),
+            )
+            .await;
+
+        ApiResponse::new_serialized(Response {}).ok()
+    }
+}
+
+pub fn router(state: &State) -> OpenApiRouter<State> {
+    OpenApiRouter::new()
+        .routes(routes!(post::route))
+        .with_state(state.clone())
+}
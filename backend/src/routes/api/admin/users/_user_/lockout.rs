@@ -0,0 +1,62 @@
+use super::State;
+use utoipa_axum::{router::OpenApiRouter, routes};
+
+mod delete {
+    use crate::routes::api::admin::users::_user_::GetParamUser;
+    use axum::http::StatusCode;
+    use serde::Serialize;
+    use shared::{
+        ApiError, GetState,
+        models::{admin_activity::GetAdminActivityLogger, user::GetPermissionManager},
+        response::{ApiResponse, ApiResponseResult},
+    };
+    use utoipa::ToSchema;
+
+    #[derive(ToSchema, Serialize)]
+    struct Response {}
+
+    #[utoipa::path(delete, path = "/", responses(
+        (status = OK, body = inline(Response)),
+        (status = CONFLICT, body = ApiError),
+    ), params(
+        (
+            "user" = uuid::Uuid,
+            description = "The user ID",
+            example = "123e4567-e89b-12d3-a456-426614174000",
+        ),
+    ))]
+    pub async fn route(
+        state: GetState,
+        permissions: GetPermissionManager,
+        user: GetParamUser,
+        activity_logger: GetAdminActivityLogger,
+    ) -> ApiResponseResult {
+        permissions.has_admin_permission("users.unlock")?;
+
+        if user.failed_login_attempts == 0 && user.locked_until.is_none() {
+            return ApiResponse::error("user has no failed login attempts")
+                .with_status(StatusCode::CONFLICT)
+                .ok();
+        }
+
+        user.reset_failed_logins(&state.database).await?;
+
+        activity_logger
+            .log(
+                "user:unlock",
+                serde_json::json!({
+                    "failed_login_attempts": user.failed_login_attempts,
+                    "locked_until": user.locked_until.map(|dt| dt.and_utc()),
+                }),
+            )
+            .await;
+
+        ApiResponse::new_serialized(Response {}).ok()
+    }
+}
+
+pub fn router(state: &State) -> OpenApiRouter<State> {
+    OpenApiRouter::new()
+        .routes(routes!(delete::route))
+        .with_state(state.clone())
+}
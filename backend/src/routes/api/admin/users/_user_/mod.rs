@@ -17,6 +17,8 @@ use std::ops::{Deref, DerefMut};
 use utoipa_axum::{router::OpenApiRouter, routes};
 
 mod activity;
+mod impersonate;
+mod lockout;
 mod oauth_links;
 mod servers;
 mod two_factor;
@@ -223,6 +225,7 @@ mod patch {
         password: Option<compact_str::CompactString>,
 
         admin: Option<bool>,
+        active: Option<bool>,
 
         #[validate(
             length(min = 5, max = 15),
@@ -348,6 +351,12 @@ mod patch {
             }
         }
 
+        if let Some(active) = data.active
+            && active != user.active
+        {
+            user.set_active(&state.database, active).await?;
+        }
+
         activity_logger
             .log(
                 "user:update",
@@ -359,6 +368,7 @@ mod patch {
                     "name_first": user.name_first,
                     "name_last": user.name_last,
                     "admin": user.admin,
+                    "active": user.active,
                     "language": user.language,
                 }),
             )
@@ -374,6 +384,8 @@ pub fn router(state: &State) -> OpenApiRouter<State> {
         .routes(routes!(delete::route))
         .routes(routes!(patch::route))
         .nest("/two-factor", two_factor::router(state))
+        .nest("/lockout", lockout::router(state))
+        .nest("/impersonate", impersonate::router(state))
         .nest("/servers", servers::router(state))
         .nest("/activity", activity::router(state))
         .nest("/oauth-links", oauth_links::router(state))
@@ -95,6 +95,7 @@ mod get {
             Server::by_user_uuid_with_pagination(
                 &state.database,
                 user.uuid,
+                None,
                 params.page,
                 params.per_page,
                 params.search.as_deref(),
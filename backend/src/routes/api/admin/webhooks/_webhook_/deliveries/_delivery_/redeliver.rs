@@ -0,0 +1,88 @@
+use super::State;
+use utoipa_axum::{router::OpenApiRouter, routes};
+
+mod post {
+    use crate::routes::api::admin::webhooks::_webhook_::GetWebhook;
+    use axum::{extract::Path, http::StatusCode};
+    use serde::Serialize;
+    use shared::{
+        ApiError, GetState,
+        models::{
+            admin_activity::GetAdminActivityLogger, user::GetPermissionManager,
+            webhook_delivery::WebhookDelivery,
+        },
+        response::{ApiResponse, ApiResponseResult},
+    };
+    use utoipa::ToSchema;
+
+    #[derive(ToSchema, Serialize)]
+    struct Response {
+        delivery: shared::models::webhook_delivery::AdminApiWebhookDelivery,
+    }
+
+    #[utoipa::path(post, path = "/", responses(
+        (status = OK, body = inline(Response)),
+        (status = NOT_FOUND, body = ApiError),
+    ), params(
+        (
+            "webhook" = uuid::Uuid,
+            description = "The webhook ID",
+            example = "123e4567-e89b-12d3-a456-426614174000",
+        ),
+        (
+            "delivery" = uuid::Uuid,
+            description = "The delivery ID",
+            example = "123e4567-e89b-12d3-a456-426614174000",
+        ),
+    ))]
+    pub async fn route(
+        state: GetState,
+        permissions: GetPermissionManager,
+        activity_logger: GetAdminActivityLogger,
+        webhook: GetWebhook,
+        Path((_webhook, delivery)): Path<(uuid::Uuid, uuid::Uuid)>,
+    ) -> ApiResponseResult {
+        permissions.has_admin_permission("webhooks.deliveries")?;
+
+        let delivery =
+            match WebhookDelivery::by_webhook_uuid_uuid(&state.database, webhook.uuid, delivery)
+                .await?
+            {
+                Some(delivery) => delivery,
+                None => {
+                    return ApiResponse::error("delivery not found")
+                        .with_status(StatusCode::NOT_FOUND)
+                        .ok();
+                }
+            };
+
+        let redelivery = WebhookDelivery::create(
+            &state.database,
+            webhook.uuid,
+            &delivery.event,
+            &delivery.payload,
+        )
+        .await?;
+
+        activity_logger
+            .log(
+                "webhook:redeliver",
+                serde_json::json!({
+                    "uuid": webhook.uuid,
+                    "delivery_uuid": delivery.uuid,
+                }),
+            )
+            .await;
+
+        ApiResponse::new_serialized(Response {
+            delivery: redelivery.into_admin_api_object(),
+        })
+        .ok()
+    }
+}
+
+pub fn router(state: &State) -> OpenApiRouter<State> {
+    OpenApiRouter::new()
+        .routes(routes!(post::route))
+        .with_state(state.clone())
+}
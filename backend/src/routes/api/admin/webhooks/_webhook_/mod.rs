@@ -0,0 +1,281 @@
+use super::State;
+use axum::{
+    extract::{Path, Request},
+    http::StatusCode,
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use shared::{
+    GetState,
+    models::{ByUuid, user::GetPermissionManager, webhook::Webhook},
+    response::ApiResponse,
+};
+use utoipa_axum::{router::OpenApiRouter, routes};
+
+mod deliveries;
+mod reset_secret;
+mod reveal;
+
+pub type GetWebhook = shared::extract::ConsumingExtension<Webhook>;
+
+pub async fn auth(
+    state: GetState,
+    permissions: GetPermissionManager,
+    Path(webhook): Path<uuid::Uuid>,
+    mut req: Request,
+    next: Next,
+) -> Result<Response, StatusCode> {
+    if let Err(err) = permissions.has_admin_permission("webhooks.read") {
+        return Ok(err.into_response());
+    }
+
+    let webhook = Webhook::by_uuid_optional(&state.database, webhook).await;
+    let webhook = match webhook {
+        Ok(Some(webhook)) => webhook,
+        Ok(None) => {
+            return Ok(ApiResponse::error("webhook not found")
+                .with_status(StatusCode::NOT_FOUND)
+                .into_response());
+        }
+        Err(err) => return Ok(ApiResponse::from(err).into_response()),
+    };
+
+    req.extensions_mut().insert(webhook);
+
+    Ok(next.run(req).await)
+}
+
+mod get {
+    use crate::routes::api::admin::webhooks::_webhook_::GetWebhook;
+    use serde::Serialize;
+    use shared::{
+        ApiError,
+        models::user::GetPermissionManager,
+        response::{ApiResponse, ApiResponseResult},
+    };
+    use utoipa::ToSchema;
+
+    #[derive(ToSchema, Serialize)]
+    struct Response {
+        webhook: shared::models::webhook::AdminApiWebhook,
+    }
+
+    #[utoipa::path(get, path = "/", responses(
+        (status = OK, body = inline(Response)),
+        (status = NOT_FOUND, body = ApiError),
+    ), params(
+        (
+            "webhook" = uuid::Uuid,
+            description = "The webhook ID",
+            example = "123e4567-e89b-12d3-a456-426614174000",
+        ),
+    ))]
+    pub async fn route(
+        permissions: GetPermissionManager,
+        webhook: GetWebhook,
+    ) -> ApiResponseResult {
+        permissions.has_admin_permission("webhooks.read")?;
+
+        ApiResponse::new_serialized(Response {
+            webhook: webhook.0.into_admin_api_object(),
+        })
+        .ok()
+    }
+}
+
+mod delete {
+    use crate::routes::api::admin::webhooks::_webhook_::GetWebhook;
+    use serde::Serialize;
+    use shared::{
+        ApiError, GetState,
+        models::{
+            DeletableModel, admin_activity::GetAdminActivityLogger, user::GetPermissionManager,
+        },
+        response::{ApiResponse, ApiResponseResult},
+    };
+    use utoipa::ToSchema;
+
+    #[derive(ToSchema, Serialize)]
+    struct Response {}
+
+    #[utoipa::path(delete, path = "/", responses(
+        (status = OK, body = inline(Response)),
+        (status = NOT_FOUND, body = ApiError),
+    ), params(
+        (
+            "webhook" = uuid::Uuid,
+            description = "The webhook ID",
+            example = "123e4567-e89b-12d3-a456-426614174000",
+        ),
+    ))]
+    pub async fn route(
+        state: GetState,
+        permissions: GetPermissionManager,
+        activity_logger: GetAdminActivityLogger,
+        webhook: GetWebhook,
+    ) -> ApiResponseResult {
+        permissions.has_admin_permission("webhooks.delete")?;
+
+        webhook.delete(&state, ()).await?;
+
+        activity_logger
+            .log(
+                "webhook:delete",
+                serde_json::json!({
+                    "uuid": webhook.uuid,
+                    "name": webhook.name,
+                }),
+            )
+            .await;
+
+        ApiResponse::new_serialized(Response {}).ok()
+    }
+}
+
+mod patch {
+    use crate::routes::api::admin::webhooks::_webhook_::GetWebhook;
+    use axum::http::StatusCode;
+    use serde::{Deserialize, Serialize};
+    use shared::{
+        ApiError, GetState,
+        models::{admin_activity::GetAdminActivityLogger, user::GetPermissionManager},
+        prelude::SqlxErrorExt,
+        response::{ApiResponse, ApiResponseResult},
+    };
+    use utoipa::ToSchema;
+    use validator::Validate;
+
+    #[derive(ToSchema, Validate, Deserialize)]
+    pub struct Payload {
+        #[validate(length(min = 3, max = 255))]
+        #[schema(min_length = 3, max_length = 255)]
+        name: Option<compact_str::CompactString>,
+        #[validate(length(max = 1024))]
+        #[schema(max_length = 1024)]
+        description: Option<compact_str::CompactString>,
+
+        #[validate(url)]
+        #[schema(example = "https://example.com/webhook", format = "uri")]
+        url: Option<compact_str::CompactString>,
+        #[validate(
+            length(min = 1),
+            custom(function = "shared::models::webhook::validate_webhook_events")
+        )]
+        #[schema(min_items = 1)]
+        events: Option<Vec<compact_str::CompactString>>,
+
+        enabled: Option<bool>,
+    }
+
+    #[derive(ToSchema, Serialize)]
+    struct Response {}
+
+    #[utoipa::path(patch, path = "/", responses(
+        (status = OK, body = inline(Response)),
+        (status = NOT_FOUND, body = ApiError),
+        (status = BAD_REQUEST, body = ApiError),
+        (status = CONFLICT, body = ApiError),
+    ), params(
+        (
+            "webhook" = uuid::Uuid,
+            description = "The webhook ID",
+            example = "123e4567-e89b-12d3-a456-426614174000",
+        ),
+    ), request_body = inline(Payload))]
+    pub async fn route(
+        state: GetState,
+        permissions: GetPermissionManager,
+        activity_logger: GetAdminActivityLogger,
+        mut webhook: GetWebhook,
+        shared::Payload(data): shared::Payload<Payload>,
+    ) -> ApiResponseResult {
+        permissions.has_admin_permission("webhooks.update")?;
+
+        if let Err(errors) = shared::utils::validate_data(&data) {
+            return ApiResponse::new_serialized(ApiError::new_strings_value(errors))
+                .with_status(StatusCode::BAD_REQUEST)
+                .ok();
+        }
+
+        if let Some(name) = data.name {
+            webhook.name = name;
+        }
+        if let Some(description) = data.description {
+            if description.is_empty() {
+                webhook.description = None;
+            } else {
+                webhook.description = Some(description);
+            }
+        }
+        if let Some(url) = data.url {
+            webhook.url = url;
+        }
+        if let Some(events) = data.events {
+            webhook.events = events;
+        }
+        if let Some(enabled) = data.enabled {
+            webhook.enabled = enabled;
+        }
+
+        match sqlx::query(
+            r#"
+            UPDATE webhooks
+            SET name = $1, description = $2, url = $3, events = $4, enabled = $5
+            WHERE webhooks.uuid = $6
+            "#,
+        )
+        .bind(&webhook.name)
+        .bind(webhook.description.as_deref())
+        .bind(&webhook.url)
+        .bind(&webhook.events)
+        .bind(webhook.enabled)
+        .bind(webhook.uuid)
+        .execute(state.database.write())
+        .await
+        {
+            Ok(_) => {}
+            Err(err) if err.is_unique_violation() => {
+                return ApiResponse::error("webhook with name already exists")
+                    .with_status(StatusCode::CONFLICT)
+                    .ok();
+            }
+            Err(err) => {
+                tracing::error!("failed to update webhook: {:?}", err);
+
+                return ApiResponse::error("failed to update webhook")
+                    .with_status(StatusCode::INTERNAL_SERVER_ERROR)
+                    .ok();
+            }
+        }
+
+        activity_logger
+            .log(
+                "webhook:update",
+                serde_json::json!({
+                    "uuid": webhook.uuid,
+                    "name": webhook.name,
+                    "description": webhook.description,
+
+                    "url": webhook.url,
+                    "events": webhook.events,
+
+                    "enabled": webhook.enabled,
+                }),
+            )
+            .await;
+
+        ApiResponse::new_serialized(Response {}).ok()
+    }
+}
+
+pub fn router(state: &State) -> OpenApiRouter<State> {
+    OpenApiRouter::new()
+        .routes(routes!(get::route))
+        .routes(routes!(delete::route))
+        .routes(routes!(patch::route))
+        .nest("/deliveries", deliveries::router(state))
+        .nest("/reset-secret", reset_secret::router(state))
+        .nest("/reveal", reveal::router(state))
+        .route_layer(axum::middleware::from_fn_with_state(state.clone(), auth))
+        .with_state(state.clone())
+}
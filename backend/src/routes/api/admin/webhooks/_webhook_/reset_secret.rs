@@ -0,0 +1,56 @@
+use super::State;
+use utoipa_axum::{router::OpenApiRouter, routes};
+
+mod post {
+    use crate::routes::api::admin::webhooks::_webhook_::GetWebhook;
+    use serde::Serialize;
+    use shared::{
+        ApiError, GetState,
+        models::{admin_activity::GetAdminActivityLogger, user::GetPermissionManager},
+        response::{ApiResponse, ApiResponseResult},
+    };
+    use utoipa::ToSchema;
+
+    #[derive(ToSchema, Serialize)]
+    struct Response {
+        secret: compact_str::CompactString,
+    }
+
+    #[utoipa::path(post, path = "/", responses(
+        (status = OK, body = inline(Response)),
+        (status = NOT_FOUND, body = ApiError),
+    ), params(
+        (
+            "webhook" = uuid::Uuid,
+            description = "The webhook ID",
+            example = "123e4567-e89b-12d3-a456-426614174000",
+        ),
+    ))]
+    pub async fn route(
+        state: GetState,
+        permissions: GetPermissionManager,
+        activity_logger: GetAdminActivityLogger,
+        mut webhook: GetWebhook,
+    ) -> ApiResponseResult {
+        permissions.has_admin_permission("webhooks.update")?;
+
+        let secret = webhook.reset_secret(&state.database).await?;
+
+        activity_logger
+            .log(
+                "webhook:reset-secret",
+                serde_json::json!({
+                    "uuid": webhook.uuid,
+                }),
+            )
+            .await;
+
+        ApiResponse::new_serialized(Response { secret }).ok()
+    }
+}
+
+pub fn router(state: &State) -> OpenApiRouter<State> {
+    OpenApiRouter::new()
+        .routes(routes!(post::route))
+        .with_state(state.clone())
+}
@@ -0,0 +1,12 @@
+use super::State;
+use utoipa_axum::router::OpenApiRouter;
+
+mod resend;
+mod verify;
+
+pub fn router(state: &State) -> OpenApiRouter<State> {
+    OpenApiRouter::new()
+        .nest("/verify", verify::router(state))
+        .nest("/resend", resend::router(state))
+        .with_state(state.clone())
+}
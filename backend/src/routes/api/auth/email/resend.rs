@@ -0,0 +1,93 @@
+use super::State;
+use utoipa_axum::{router::OpenApiRouter, routes};
+
+mod post {
+    use axum::http::StatusCode;
+    use serde::{Deserialize, Serialize};
+    use shared::{
+        ApiError, GetState,
+        models::{user::User, user_email_verification::UserEmailVerification},
+        response::{ApiResponse, ApiResponseResult},
+        settings::EmailVerificationMode,
+    };
+    use utoipa::ToSchema;
+    use validator::Validate;
+
+    #[derive(ToSchema, Validate, Deserialize)]
+    pub struct Payload {
+        #[validate(email)]
+        #[schema(format = "email")]
+        email: String,
+
+        captcha: Option<String>,
+    }
+
+    #[derive(ToSchema, Serialize)]
+    struct Response {}
+
+    #[utoipa::path(post, path = "/", responses(
+        (status = OK, body = inline(Response)),
+        (status = BAD_REQUEST, body = ApiError),
+    ), request_body = inline(Payload))]
+    pub async fn route(
+        state: GetState,
+        ip: shared::GetIp,
+        shared::Payload(data): shared::Payload<Payload>,
+    ) -> ApiResponseResult {
+        if let Err(errors) = shared::utils::validate_data(&data) {
+            return ApiResponse::new_serialized(ApiError::new_strings_value(errors))
+                .with_status(StatusCode::BAD_REQUEST)
+                .ok();
+        }
+
+        state
+            .cache
+            .ratelimit("auth/email/resend", 10, 3600, ip.to_string())
+            .await?;
+        state
+            .cache
+            .ratelimit("auth/email/resend:email", 3, 3600, &data.email)
+            .await?;
+
+        if let Err(error) = state.captcha.verify(ip, data.captcha).await {
+            return ApiResponse::error(&error)
+                .with_status(StatusCode::BAD_REQUEST)
+                .ok();
+        }
+
+        if state.settings.get().await?.app.email_verification == EmailVerificationMode::Disabled {
+            return ApiResponse::new_serialized(Response {}).ok();
+        }
+
+        let user = match User::by_email(&state.database, &data.email).await? {
+            Some(user) if !user.email_verified => user,
+            _ => return ApiResponse::new_serialized(Response {}).ok(),
+        };
+
+        tokio::spawn(async move {
+            if let Err(err) = UserEmailVerification::send(
+                &state.database,
+                &state.settings,
+                &state.mail,
+                &user,
+                &user.email,
+            )
+            .await
+            {
+                tracing::warn!(
+                    user = %user.uuid,
+                    "failed to send email verification: {:#?}",
+                    err
+                );
+            }
+        });
+
+        ApiResponse::new_serialized(Response {}).ok()
+    }
+}
+
+pub fn router(state: &State) -> OpenApiRouter<State> {
+    OpenApiRouter::new()
+        .routes(routes!(post::route))
+        .with_state(state.clone())
+}
@@ -0,0 +1,101 @@
+use super::State;
+use utoipa_axum::{router::OpenApiRouter, routes};
+
+mod post {
+    use axum::http::StatusCode;
+    use serde::{Deserialize, Serialize};
+    use shared::{
+        ApiError, GetState,
+        models::{user_activity::UserActivity, user_email_verification::UserEmailVerification},
+        prelude::SqlxErrorExt,
+        response::{ApiResponse, ApiResponseResult},
+    };
+    use utoipa::ToSchema;
+    use validator::Validate;
+
+    #[derive(ToSchema, Validate, Deserialize)]
+    pub struct Payload {
+        #[validate(length(min = 96, max = 96))]
+        #[schema(min_length = 96, max_length = 96)]
+        token: String,
+    }
+
+    #[derive(ToSchema, Serialize)]
+    struct Response {}
+
+    #[utoipa::path(post, path = "/", responses(
+        (status = OK, body = inline(Response)),
+        (status = BAD_REQUEST, body = ApiError),
+        (status = CONFLICT, body = ApiError),
+    ), request_body = inline(Payload))]
+    pub async fn route(
+        state: GetState,
+        ip: shared::GetIp,
+        shared::Payload(data): shared::Payload<Payload>,
+    ) -> ApiResponseResult {
+        if let Err(errors) = shared::utils::validate_data(&data) {
+            return ApiResponse::new_serialized(ApiError::new_strings_value(errors))
+                .with_status(StatusCode::BAD_REQUEST)
+                .ok();
+        }
+
+        let verification =
+            match UserEmailVerification::delete_by_token(&state.database, &data.token).await? {
+                Some(verification) => verification,
+                None => {
+                    return ApiResponse::error("invalid or expired token")
+                        .with_status(StatusCode::BAD_REQUEST)
+                        .ok();
+                }
+            };
+
+        match sqlx::query(
+            r#"
+            UPDATE users
+            SET email = $2, email_verified = true
+            WHERE users.uuid = $1
+            "#,
+        )
+        .bind(verification.user.uuid)
+        .bind(&verification.email)
+        .execute(state.database.write())
+        .await
+        {
+            Ok(_) => {}
+            Err(err) if err.is_unique_violation() => {
+                return ApiResponse::error("email already in use")
+                    .with_status(StatusCode::CONFLICT)
+                    .ok();
+            }
+            Err(err) => return Err(err.into()),
+        }
+
+        if let Err(err) = UserActivity::log(
+            &state.database,
+            verification.user.uuid,
+            None,
+            "auth:verify-email",
+            Some(ip.0.into()),
+            serde_json::json!({
+                "old": verification.user.email,
+                "new": verification.email,
+            }),
+        )
+        .await
+        {
+            tracing::warn!(
+                user = %verification.user.uuid,
+                "failed to log user activity: {:#?}",
+                err
+            );
+        }
+
+        ApiResponse::new_serialized(Response {}).ok()
+    }
+}
+
+pub fn router(state: &State) -> OpenApiRouter<State> {
+    OpenApiRouter::new()
+        .routes(routes!(post::route))
+        .with_state(state.clone())
+}
@@ -46,6 +46,7 @@ mod post {
         (status = OK, body = inline(Response)),
         (status = BAD_REQUEST, body = ApiError),
         (status = NOT_FOUND, body = ApiError),
+        (status = TOO_MANY_REQUESTS, body = ApiError),
     ), request_body = inline(Payload))]
     pub async fn route(
         state: GetState,
@@ -80,6 +81,18 @@ mod post {
 
         let user = User::by_uuid(&state.database, payload.user_uuid).await?;
 
+        if user.is_locked() {
+            return ApiResponse::error("account is temporarily locked, try again later")
+                .with_status(StatusCode::TOO_MANY_REQUESTS)
+                .ok();
+        }
+
+        if !user.active {
+            return ApiResponse::error("account has been deactivated")
+                .with_status(StatusCode::FORBIDDEN)
+                .ok();
+        }
+
         match data.code.len() {
             6 => {
                 let user_totp_secret = match &user.totp_secret {
@@ -119,6 +132,8 @@ mod post {
                 let matched_step_idx = match matched_step_idx {
                     Some(idx) => idx,
                     None => {
+                        user.record_failed_login(&state, Some(ip.0)).await?;
+
                         return ApiResponse::error("invalid confirmation code")
                             .with_status(StatusCode::BAD_REQUEST)
                             .ok();
@@ -183,6 +198,8 @@ mod post {
                         );
                     }
                 } else {
+                    user.record_failed_login(&state, Some(ip.0)).await?;
+
                     return ApiResponse::error("invalid recovery code")
                         .with_status(StatusCode::BAD_REQUEST)
                         .ok();
@@ -195,6 +212,8 @@ mod post {
             }
         }
 
+        user.reset_failed_logins(&state.database).await?;
+
         let key = UserSession::create(
             &state.database,
             user.uuid,
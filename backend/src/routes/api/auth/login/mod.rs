@@ -42,6 +42,7 @@ mod post {
     #[utoipa::path(post, path = "/", responses(
         (status = OK, body = inline(Response)),
         (status = BAD_REQUEST, body = ApiError),
+        (status = TOO_MANY_REQUESTS, body = ApiError),
     ), request_body = inline(Payload))]
     pub async fn route(
         state: GetState,
@@ -67,26 +68,61 @@ mod post {
                 .ok();
         }
 
+        let account = if data.user.contains('@') {
+            User::by_email(&state.database, &data.user).await?
+        } else {
+            User::by_username(&state.database, &data.user).await?
+        };
+
+        if let Some(account) = &account
+            && account.is_locked()
+        {
+            return ApiResponse::error("account is temporarily locked, try again later")
+                .with_status(StatusCode::TOO_MANY_REQUESTS)
+                .ok();
+        }
+
         let user = if data.user.contains('@') {
-            match User::by_email_password(&state.database, &data.user, &data.password).await? {
-                Some(user) => user,
-                None => {
-                    return ApiResponse::error("invalid username or password")
-                        .with_status(StatusCode::BAD_REQUEST)
-                        .ok();
-                }
-            }
+            User::by_email_password(&state.database, &data.user, &data.password).await?
         } else {
-            match User::by_username_password(&state.database, &data.user, &data.password).await? {
-                Some(user) => user,
+            User::by_username_password(&state.database, &data.user, &data.password).await?
+        };
+
+        let (user, using) = match user {
+            Some(user) => (user, "password"),
+            None => match state.ldap.login(&data.user, &data.password).await? {
+                Some(user) => (user, "ldap"),
                 None => {
+                    if let Some(account) = account {
+                        account.record_failed_login(&state, Some(ip.0)).await?;
+                    }
+
                     return ApiResponse::error("invalid username or password")
                         .with_status(StatusCode::BAD_REQUEST)
                         .ok();
                 }
-            }
+            },
         };
 
+        if !user.active {
+            return ApiResponse::error("account has been deactivated")
+                .with_status(StatusCode::FORBIDDEN)
+                .ok();
+        }
+
+        // users without a passkey may still log in with their password to register one
+        if user.require_passkey() && user.has_passkey(&state.database).await? {
+            return ApiResponse::error("passkey login is required for this account")
+                .with_status(StatusCode::FORBIDDEN)
+                .ok();
+        }
+
+        if user.email_verification_blocks_login(&*state.settings.get().await?) {
+            return ApiResponse::error("email address has not been verified")
+                .with_status(StatusCode::BAD_REQUEST)
+                .ok();
+        }
+
         if user.totp_enabled {
             let token = state.jwt.create(&TwoFactorRequiredJwt {
                 base: BasePayload {
@@ -116,6 +152,8 @@ mod post {
 
             ApiResponse::new_serialized(Response::TwoFactorRequired { token }).ok()
         } else {
+            user.reset_failed_logins(&state.database).await?;
+
             let key = UserSession::create(
                 &state.database,
                 user.uuid,
@@ -151,7 +189,7 @@ mod post {
                 "auth:success",
                 Some(ip.0.into()),
                 serde_json::json!({
-                    "using": "password",
+                    "using": using,
                 }),
             )
             .await
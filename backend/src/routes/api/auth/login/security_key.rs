@@ -14,7 +14,7 @@ mod get {
 
     #[derive(ToSchema, Deserialize)]
     pub struct Params {
-        user: String,
+        user: Option<String>,
     }
 
     #[derive(ToSchema, Serialize)]
@@ -29,20 +29,38 @@ mod get {
         (status = BAD_REQUEST, body = ApiError),
     ), params(
         (
-            "user" = String, Query,
-            description = "The user to get a security key challenge for",
+            "user" = Option<String>, Query,
+            description = "The user to get a security key challenge for, leave out for a discoverable (usernameless) challenge",
             example = "/",
         ),
     ))]
     pub async fn route(state: GetState, Query(data): Query<Params>) -> ApiResponseResult {
         let webauthn = state.settings.get_webauthn().await?;
 
+        let Some(user) = data.user else {
+            let (options, authentication) = webauthn.start_discoverable_authentication()?;
+            let uuid = uuid::Uuid::new_v4();
+
+            state
+                .cache
+                .client
+                .set_with_options(
+                    format!("security_key_discoverable_authentication::{uuid}"),
+                    serde_json::to_string(&authentication)?,
+                    None,
+                    SetExpiration::Ex(options.public_key.timeout.unwrap_or(300000) as u64 / 1000),
+                )
+                .await?;
+
+            return ApiResponse::new_serialized(Response { uuid, options }).ok();
+        };
+
         let raw_passkeys = sqlx::query!(
             "SELECT user_security_keys.passkey
             FROM user_security_keys
             JOIN users ON users.uuid = user_security_keys.user_uuid
             WHERE user_security_keys.passkey IS NOT NULL AND (lower(users.email) = lower($1) OR lower(users.username) = lower($1))",
-            data.user
+            user
         )
         .fetch_all(state.database.read())
         .await?;
@@ -87,7 +105,10 @@ mod post {
     };
     use tower_cookies::{Cookie, Cookies};
     use utoipa::ToSchema;
-    use webauthn_rs::prelude::{PasskeyAuthentication, PublicKeyCredential};
+    use webauthn_rs::prelude::{
+        CredentialID, DiscoverableAuthentication, DiscoverableKey, PasskeyAuthentication,
+        PublicKeyCredential, WebauthnError,
+    };
 
     #[derive(ToSchema, Deserialize)]
     pub struct Payload {
@@ -101,6 +122,17 @@ mod post {
         user: shared::models::user::ApiFullUser,
     }
 
+    fn finish_error(err: WebauthnError) -> ApiResponseResult {
+        tracing::error!("failed to finish security key authentication: {:?}", err);
+
+        ApiResponse::error(&format!(
+            "failed to finish security key authentication: {}",
+            err
+        ))
+        .with_status(StatusCode::BAD_REQUEST)
+        .ok()
+    }
+
     #[utoipa::path(post, path = "/", responses(
         (status = OK, body = inline(Response)),
         (status = BAD_REQUEST, body = ApiError),
@@ -119,54 +151,101 @@ mod post {
 
         let webauthn = state.settings.get_webauthn().await?;
 
-        let authentication: PasskeyAuthentication = match state
+        let authentication_key = format!("security_key_authentication::{}", data.uuid);
+        let discoverable_authentication_key =
+            format!("security_key_discoverable_authentication::{}", data.uuid);
+
+        let (passkey, (user, security_key)) = if let Ok(authentication) = state
             .cache
             .client
-            .get::<String>(format!("security_key_authentication::{}", data.uuid))
+            .get::<String>(authentication_key.as_str())
             .await
         {
-            Ok(authentication) => {
-                state
-                    .cache
-                    .client
-                    .del(format!("security_key_authentication::{}", data.uuid))
-                    .await?;
-
-                serde_json::from_str(&authentication)?
-            }
-            Err(_) => {
-                return ApiResponse::error("invalid or expired challenge")
-                    .with_status(StatusCode::BAD_REQUEST)
-                    .ok();
-            }
-        };
+            state.cache.client.del(authentication_key.as_str()).await?;
+            let authentication: PasskeyAuthentication = serde_json::from_str(&authentication)?;
 
-        let passkey = match webauthn
-            .finish_passkey_authentication(&data.public_key_credential, &authentication)
-        {
-            Ok(passkey) => passkey,
-            Err(err) => {
-                tracing::error!("failed to finish security key authentication: {:?}", err);
-
-                return ApiResponse::error(&format!(
-                    "failed to finish security key authentication: {}",
-                    err
-                ))
-                .with_status(StatusCode::BAD_REQUEST)
-                .ok();
-            }
-        };
+            let passkey = match webauthn
+                .finish_passkey_authentication(&data.public_key_credential, &authentication)
+            {
+                Ok(passkey) => passkey,
+                Err(err) => return finish_error(err),
+            };
 
-        let (user, security_key) =
             match User::by_credential_id(&state.database, passkey.cred_id()).await? {
-                Some(user) => user,
+                Some(user) => (passkey, user),
                 None => {
                     return ApiResponse::error("user not found")
                         .with_status(StatusCode::NOT_FOUND)
                         .ok();
                 }
+            }
+        } else if let Ok(authentication) = state
+            .cache
+            .client
+            .get::<String>(discoverable_authentication_key.as_str())
+            .await
+        {
+            state
+                .cache
+                .client
+                .del(discoverable_authentication_key.as_str())
+                .await?;
+            let authentication: DiscoverableAuthentication = serde_json::from_str(&authentication)?;
+
+            let (user_uuid, credential_id) =
+                match webauthn.identify_discoverable_authentication(&data.public_key_credential) {
+                    Ok(identity) => identity,
+                    Err(err) => return finish_error(err),
+                };
+
+            let (user, security_key) = match User::by_credential_id(
+                &state.database,
+                &CredentialID::from(credential_id.to_vec()),
+            )
+            .await?
+            {
+                Some((user, security_key)) if user.uuid == user_uuid => (user, security_key),
+                _ => {
+                    return ApiResponse::error("user not found")
+                        .with_status(StatusCode::NOT_FOUND)
+                        .ok();
+                }
+            };
+
+            let Some(db_passkey) = &security_key.passkey else {
+                return ApiResponse::error("user not found")
+                    .with_status(StatusCode::NOT_FOUND)
+                    .ok();
             };
 
+            let passkey = match webauthn.finish_discoverable_authentication(
+                &data.public_key_credential,
+                authentication,
+                &[DiscoverableKey::from(db_passkey)],
+            ) {
+                Ok(passkey) => passkey,
+                Err(err) => return finish_error(err),
+            };
+
+            (passkey, (user, security_key))
+        } else {
+            return ApiResponse::error("invalid or expired challenge")
+                .with_status(StatusCode::BAD_REQUEST)
+                .ok();
+        };
+
+        if !user.active {
+            return ApiResponse::error("account has been deactivated")
+                .with_status(StatusCode::FORBIDDEN)
+                .ok();
+        }
+
+        if user.email_verification_blocks_login(&*state.settings.get().await?) {
+            return ApiResponse::error("email address has not been verified")
+                .with_status(StatusCode::BAD_REQUEST)
+                .ok();
+        }
+
         if let Some(mut db_passkey) = security_key.passkey {
             db_passkey.update_credential(&passkey);
 
@@ -1,16 +1,20 @@
 use super::State;
 use utoipa_axum::router::OpenApiRouter;
 
+mod email;
 mod login;
 mod oauth;
 mod password;
 mod register;
+mod saml;
 
 pub fn router(state: &State) -> OpenApiRouter<State> {
     OpenApiRouter::new()
         .nest("/login", login::router(state))
         .nest("/register", register::router(state))
         .nest("/password", password::router(state))
+        .nest("/email", email::router(state))
         .nest("/oauth", oauth::router(state))
+        .nest("/saml", saml::router(state))
         .with_state(state.clone())
 }
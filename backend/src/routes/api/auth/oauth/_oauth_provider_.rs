@@ -6,19 +6,26 @@ use axum::{
     routing::get,
 };
 use oauth2::{
-    AuthUrl, AuthorizationCode, ClientId, ClientSecret, HttpRequest, HttpResponse, RedirectUrl,
-    TokenResponse, TokenUrl, basic::BasicClient,
+    AuthUrl, AuthorizationCode, ClientId, ClientSecret, HttpRequest, HttpResponse,
+    PkceCodeVerifier, RedirectUrl, TokenResponse, TokenUrl,
 };
 use rand::distr::SampleString;
-use rustis::commands::StringCommands;
+use rustis::commands::{GenericCommands, SetExpiration, StringCommands};
 use serde::Deserialize;
 use shared::{
     GetState,
     models::{
-        ByUuid, oauth_provider::OAuthProvider, user::User, user_activity::UserActivity,
-        user_oauth_link::UserOAuthLink, user_session::UserSession,
+        ByUuid,
+        oauth_provider::{
+            OAuthProvider,
+            oidc::{OAuthClient, OAuthRedirectState, OidcLogoutState},
+        },
+        user::User,
+        user_activity::UserActivity,
+        user_oauth_link::UserOAuthLink,
+        user_session::UserSession,
     },
-    response::ApiResponse,
+    response::{ApiResponse, DisplayError},
 };
 use tower_cookies::{Cookie, Cookies};
 use utoipa_axum::router::OpenApiRouter;
@@ -29,6 +36,156 @@ pub struct Params {
     state: String,
 }
 
+/// Exchanges the authorization code for tokens and returns the user info of the provider.
+///
+/// For OIDC providers the claims of the validated id token are returned instead,
+/// along with the raw id token for logging out at the provider later on.
+async fn exchange_code(
+    state: &shared::State,
+    oauth_provider: &OAuthProvider,
+    code: String,
+    redirect_state: OAuthRedirectState,
+) -> Result<(serde_json::Value, Option<String>), anyhow::Error> {
+    let discovery = oauth_provider.fetch_oidc_discovery(state).await?;
+
+    let settings = state.settings.get().await?;
+
+    let client = OAuthClient::new(ClientId::new(oauth_provider.client_id.to_string()))
+        .set_client_secret(ClientSecret::new(
+            state
+                .database
+                .decrypt(oauth_provider.client_secret.clone())
+                .await?
+                .into(),
+        ))
+        .set_auth_uri(AuthUrl::new(match &discovery {
+            Some(discovery) => discovery.authorization_endpoint.clone(),
+            None => oauth_provider.auth_url.clone(),
+        })?)
+        .set_token_uri(TokenUrl::new(match &discovery {
+            Some(discovery) => discovery.token_endpoint.clone(),
+            None => oauth_provider.token_url.clone(),
+        })?)
+        .set_auth_type(if oauth_provider.basic_auth {
+            oauth2::AuthType::BasicAuth
+        } else {
+            oauth2::AuthType::RequestBody
+        })
+        .set_redirect_uri(RedirectUrl::new(format!(
+            "{}/api/auth/oauth/{}",
+            settings.app.url.trim_end_matches('/'),
+            oauth_provider.uuid
+        ))?);
+
+    drop(settings);
+
+    let http_client = |req: HttpRequest| {
+        let client = state.client.clone();
+        async move {
+            let response = client.execute(req.try_into()?).await?;
+            let response = axum::http::Response::from(response);
+            let (parts, body) = response.into_parts();
+
+            let data = axum::body::to_bytes(axum::body::Body::new(body), usize::MAX)
+                .await
+                .unwrap_or_default();
+
+            Ok::<_, reqwest::Error>(HttpResponse::from_parts(parts, data.into()))
+        }
+    };
+
+    let mut request = client.exchange_code(AuthorizationCode::new(code));
+    if let Some(pkce_verifier) = redirect_state.pkce_verifier {
+        request = request.set_pkce_verifier(PkceCodeVerifier::new(pkce_verifier));
+    }
+
+    let token = request.request_async(&http_client).await?;
+
+    let discovery = match discovery {
+        Some(discovery) => discovery,
+        None => {
+            let info: serde_json::Value = state
+                .client
+                .get(&oauth_provider.info_url)
+                .bearer_auth(token.access_token().secret())
+                .send()
+                .await?
+                .json()
+                .await?;
+
+            return Ok((info, None));
+        }
+    };
+
+    let id_token = token
+        .extra_fields()
+        .id_token
+        .clone()
+        .ok_or_else(|| DisplayError::new("oauth provider did not return an id token"))?;
+    let nonce = redirect_state
+        .nonce
+        .ok_or_else(|| DisplayError::new("oauth nonce not found, please try again"))?;
+
+    let mut claims = oauth_provider
+        .validate_id_token(state, &discovery, &id_token, &nonce)
+        .await?;
+
+    // the id token is authoritative, userinfo only fills in claims it does not carry
+    if let Some(userinfo_endpoint) = &discovery.userinfo_endpoint {
+        let userinfo: serde_json::Value = state
+            .client
+            .get(userinfo_endpoint)
+            .bearer_auth(token.access_token().secret())
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+
+        if let (Some(claims), serde_json::Value::Object(userinfo)) =
+            (claims.as_object_mut(), userinfo)
+            && userinfo.get("sub") == claims.get("sub")
+        {
+            for (key, value) in userinfo {
+                claims.entry(key).or_insert(value);
+            }
+        }
+    }
+
+    Ok((claims, Some(id_token)))
+}
+
+/// Remembers the id token of a new session so logging out can also end the session at the provider.
+async fn store_oidc_logout_state(
+    state: &shared::State,
+    oauth_provider: &OAuthProvider,
+    session_key: &str,
+    id_token: Option<String>,
+) -> Result<(), anyhow::Error> {
+    let id_token = match id_token {
+        Some(id_token) if oauth_provider.oidc_logout_enabled => id_token,
+        _ => return Ok(()),
+    };
+
+    let key_id = session_key.split(':').next().unwrap_or_default();
+
+    state
+        .cache
+        .client
+        .set_with_options(
+            format!("oidc_logout::{key_id}"),
+            serde_json::to_string(&OidcLogoutState {
+                oauth_provider_uuid: oauth_provider.uuid,
+                id_token,
+            })?,
+            None,
+            SetExpiration::Ex(30 * 24 * 60 * 60),
+        )
+        .await?;
+
+    Ok(())
+}
+
 pub fn router(state: &State) -> OpenApiRouter<State> {
     OpenApiRouter::new()
         .route("/", get(|state: GetState,
@@ -58,54 +215,25 @@ pub fn router(state: &State) -> OpenApiRouter<State> {
                     .ok();
             }
 
-            if state
+            let redirect_state: OAuthRedirectState = match state
                 .cache
                 .client
-                .get::<u16>(format!("oauth_state::{}", params.state))
+                .get::<String>(format!("oauth_state::{}", params.state))
                 .await
-                .is_err()
             {
-                return ApiResponse::error("oauth csrf state not found, please try again")
-                    .with_status(StatusCode::NOT_FOUND)
-                    .ok();
-            }
-
-            let settings = state.settings.get().await?;
-
-            let client = BasicClient::new(ClientId::new(oauth_provider.client_id.to_string()))
-                .set_client_secret(ClientSecret::new(
+                Ok(redirect_state) => {
                     state
-                        .database
-                        .decrypt(oauth_provider.client_secret.clone())
-                        .await?.into(),
-                ))
-                .set_auth_uri(AuthUrl::new(oauth_provider.auth_url.clone())?)
-                .set_token_uri(TokenUrl::new(oauth_provider.token_url.clone())?)
-                .set_auth_type(if oauth_provider.basic_auth {
-                    oauth2::AuthType::BasicAuth
-                } else {
-                    oauth2::AuthType::RequestBody
-                })
-                .set_redirect_uri(RedirectUrl::new(format!(
-                    "{}/api/auth/oauth/{}",
-                    settings.app.url.trim_end_matches('/'),
-                    oauth_provider.uuid
-                ))?);
-
-            drop(settings);
-
-            let http_client = |req: HttpRequest| {
-                let client = state.client.clone();
-                async move {
-                    let response = client.execute(req.try_into()?).await?;
-                    let response = axum::http::Response::from(response);
-                    let (parts, body) = response.into_parts();
-
-                    let data = axum::body::to_bytes(axum::body::Body::new(body), usize::MAX)
-                        .await
-                        .unwrap_or_default();
+                        .cache
+                        .client
+                        .del(format!("oauth_state::{}", params.state))
+                        .await?;
 
-                    Ok::<_, reqwest::Error>(HttpResponse::from_parts(parts, data.into()))
+                    serde_json::from_str(&redirect_state).unwrap_or_default()
+                }
+                Err(_) => {
+                    return ApiResponse::error("oauth csrf state not found, please try again")
+                        .with_status(StatusCode::NOT_FOUND)
+                        .ok();
                 }
             };
 
@@ -132,6 +260,14 @@ pub fn router(state: &State) -> OpenApiRouter<State> {
                         }
                     };
 
+                if session.impersonator_uuid.is_some() {
+                    return ApiResponse::error(
+                        "this action is not available while impersonating a user",
+                    )
+                    .with_status(StatusCode::FORBIDDEN)
+                    .ok();
+                }
+
                 state
                     .database
                     .batch_action("update_user_session", session.uuid, {
@@ -179,19 +315,8 @@ pub fn router(state: &State) -> OpenApiRouter<State> {
                         .build(),
                 );
 
-                let token = client
-                    .exchange_code(AuthorizationCode::new(params.0.code))
-                    .request_async(&http_client)
-                    .await?;
-
-                let info: serde_json::Value = state
-                    .client
-                    .get(&oauth_provider.info_url)
-                    .bearer_auth(token.access_token().secret())
-                    .send()
-                    .await?
-                    .json()
-                    .await?;
+                let (info, _) =
+                    exchange_code(&state, &oauth_provider, params.0.code, redirect_state).await?;
 
                 let identifier = match oauth_provider.extract_identifier(&info) {
                     Ok(identifier) => identifier,
@@ -228,19 +353,8 @@ pub fn router(state: &State) -> OpenApiRouter<State> {
                     .with_status(StatusCode::TEMPORARY_REDIRECT)
                     .ok()
             } else {
-                let token = client
-                    .exchange_code(AuthorizationCode::new(params.0.code))
-                    .request_async(&http_client)
-                    .await?;
-
-                let info: serde_json::Value = state
-                    .client
-                    .get(&oauth_provider.info_url)
-                    .bearer_auth(token.access_token().secret())
-                    .send()
-                    .await?
-                    .json()
-                    .await?;
+                let (info, id_token) =
+                    exchange_code(&state, &oauth_provider, params.0.code, redirect_state).await?;
 
                 let identifier = match oauth_provider.extract_identifier(&info) {
                     Ok(identifier) => identifier,
@@ -255,6 +369,28 @@ pub fn router(state: &State) -> OpenApiRouter<State> {
                     Some(oauth_link) => {
                         let user = oauth_link.user.fetch(&state.database).await?;
 
+                        if !user.active {
+                            return ApiResponse::error("account has been deactivated")
+                                .with_status(StatusCode::FORBIDDEN)
+                                .ok();
+                        }
+
+                        let role_uuid = match oauth_provider.extract_role_uuid(&info) {
+                            Ok(role_uuid) => role_uuid,
+                            Err(err) => {
+                                return ApiResponse::error(&err.to_string())
+                                    .with_status(StatusCode::INTERNAL_SERVER_ERROR)
+                                    .ok();
+                            }
+                        };
+
+                        // a missing role claim keeps the current role instead of clearing it
+                        if let Some(role_uuid) = role_uuid
+                            && user.role.as_ref().map(|role| role.uuid) != role_uuid
+                        {
+                            User::update_role_uuid(&state.database, user.uuid, role_uuid).await?;
+                        }
+
                         let key = UserSession::create(
                             &state.database,
                             user.uuid,
@@ -266,6 +402,8 @@ pub fn router(state: &State) -> OpenApiRouter<State> {
                         )
                         .await?;
 
+                        store_oidc_logout_state(&state, &oauth_provider, &key, id_token).await?;
+
                         let settings = state.settings.get().await?;
                         let secure = settings.app.url.starts_with("https://");
                         drop(settings);
@@ -357,10 +495,19 @@ pub fn router(state: &State) -> OpenApiRouter<State> {
                             }
                         };
 
+                        let role_uuid = match oauth_provider.extract_role_uuid(&info) {
+                            Ok(role_uuid) => role_uuid.flatten(),
+                            Err(err) => {
+                                return ApiResponse::error(&err.to_string())
+                                    .with_status(StatusCode::INTERNAL_SERVER_ERROR)
+                                    .ok();
+                            }
+                        };
+
                         let password = rand::distr::Alphanumeric.sample_string(&mut rand::rng(), 16);
                         let user = match User::create(
                             &state.database,
-                            None,
+                            role_uuid,
                             None,
                             &username,
                             &email,
@@ -400,6 +547,8 @@ pub fn router(state: &State) -> OpenApiRouter<State> {
                         )
                         .await?;
 
+                        store_oidc_logout_state(&state, &oauth_provider, &key, id_token).await?;
+
                         cookies.add(
                             Cookie::build(("session", key))
                                 .http_only(true)
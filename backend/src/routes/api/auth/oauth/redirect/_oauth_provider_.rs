@@ -3,11 +3,17 @@ use utoipa_axum::{router::OpenApiRouter, routes};
 
 mod get {
     use axum::{body::Body, extract::Path, http::StatusCode};
-    use oauth2::{AuthUrl, ClientId, CsrfToken, RedirectUrl, Scope, basic::BasicClient};
+    use oauth2::{
+        AuthUrl, ClientId, CsrfToken, PkceCodeChallenge, RedirectUrl, Scope, basic::BasicClient,
+    };
+    use rand::distr::SampleString;
     use rustis::commands::{SetExpiration, StringCommands};
     use shared::{
         ApiError, GetState,
-        models::{ByUuid, oauth_provider::OAuthProvider},
+        models::{
+            ByUuid,
+            oauth_provider::{OAuthProvider, oidc::OAuthRedirectState},
+        },
         response::{ApiResponse, ApiResponseResult},
     };
 
@@ -46,10 +52,15 @@ mod get {
                 .ok();
         }
 
+        let discovery = oauth_provider.fetch_oidc_discovery(&state).await?;
+
         let settings = state.settings.get().await?;
 
         let client = BasicClient::new(ClientId::new(oauth_provider.client_id.to_string()))
-            .set_auth_uri(AuthUrl::new(oauth_provider.auth_url.clone())?)
+            .set_auth_uri(AuthUrl::new(match &discovery {
+                Some(discovery) => discovery.authorization_endpoint.clone(),
+                None => oauth_provider.auth_url.clone(),
+            })?)
             .set_redirect_uri(RedirectUrl::new(format!(
                 "{}/api/auth/oauth/{}",
                 settings.app.url.trim_end_matches('/'),
@@ -58,7 +69,24 @@ mod get {
 
         drop(settings);
 
+        let mut redirect_state = OAuthRedirectState::default();
+
         let mut url = client.authorize_url(CsrfToken::new_random);
+        if discovery.is_some() {
+            let (pkce_challenge, pkce_verifier) = PkceCodeChallenge::new_random_sha256();
+            let nonce = rand::distr::Alphanumeric.sample_string(&mut rand::rng(), 32);
+
+            url = url
+                .set_pkce_challenge(pkce_challenge)
+                .add_extra_param("nonce", nonce.clone());
+
+            if !oauth_provider.scopes.iter().any(|scope| scope == "openid") {
+                url = url.add_scope(Scope::new("openid".into()));
+            }
+
+            redirect_state.nonce = Some(nonce);
+            redirect_state.pkce_verifier = Some(pkce_verifier.into_secret());
+        }
         for scope in oauth_provider.scopes {
             url = url.add_scope(Scope::new(scope.into()));
         }
@@ -70,7 +98,7 @@ mod get {
             .client
             .set_with_options(
                 format!("oauth_state::{}", csrf_state.secret()),
-                0,
+                serde_json::to_string(&redirect_state)?,
                 None,
                 SetExpiration::Ex(10 * 60),
             )
@@ -85,18 +85,6 @@ mod post {
                 }
             };
 
-            let mail = shared::mail::MAIL_PASSWORD_RESET
-                .replace("{{app_name}}", &settings.app.name)
-                .replace("{{user_username}}", &user.username)
-                .replace(
-                    "{{reset_link}}",
-                    &format!(
-                        "{}/auth/reset-password?token={}",
-                        settings.app.url,
-                        urlencoding::encode(&token),
-                    ),
-                );
-
             UserActivity::log(
                 &state.database,
                 user.uuid,
@@ -113,12 +101,23 @@ mod post {
             .await
             .ok();
 
+            let reset_link = format!(
+                "{}/auth/reset-password?token={}",
+                settings.app.url,
+                urlencoding::encode(&token),
+            );
+            drop(settings);
+
             state
                 .mail
-                .send(
-                    user.email,
-                    format!("{} - Password Reset", settings.app.name).into(),
-                    mail,
+                .send_template(
+                    user.email.clone(),
+                    shared::mail::MailTemplateIdentifier::PasswordReset,
+                    &user.language,
+                    serde_json::json!({
+                        "user": shared::mail::user_context(&user),
+                        "reset_link": reset_link,
+                    }),
                 )
                 .await;
         });
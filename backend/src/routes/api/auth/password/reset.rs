@@ -6,7 +6,7 @@ mod post {
     use serde::{Deserialize, Serialize};
     use shared::{
         ApiError, GetState,
-        models::{user_activity::UserActivity, user_password_reset::UserPasswordReset},
+        models::{user::User, user_activity::UserActivity, user_password_reset::UserPasswordReset},
         response::{ApiResponse, ApiResponseResult},
     };
     use utoipa::ToSchema;
@@ -40,7 +40,7 @@ mod post {
                 .ok();
         }
 
-        let token = match UserPasswordReset::delete_by_token(&state.database, &data.token).await? {
+        let token = match UserPasswordReset::by_token(&state.database, &data.token).await? {
             Some(token) => token,
             None => {
                 return ApiResponse::error("invalid or expired token")
@@ -49,6 +49,21 @@ mod post {
             }
         };
 
+        User::validate_password_policy(
+            &state.database,
+            &state
+                .settings
+                .get_as(|settings| settings.security.clone())
+                .await?,
+            Some(token.user.uuid),
+            &token.user.username,
+            &token.user.email,
+            &data.new_password,
+        )
+        .await?;
+
+        token.delete(&state.database).await?;
+
         if let Err(err) = UserActivity::log(
             &state.database,
             token.user.uuid,
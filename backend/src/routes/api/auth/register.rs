@@ -6,8 +6,12 @@ mod post {
     use serde::{Deserialize, Serialize};
     use shared::{
         ApiError, GetState,
-        models::{ByUuid, user::User, user_session::UserSession},
+        models::{
+            ByUuid, user::User, user_email_verification::UserEmailVerification,
+            user_session::UserSession,
+        },
         response::{ApiResponse, ApiResponseResult},
+        settings::EmailVerificationMode,
     };
     use tower_cookies::{Cookie, Cookies};
     use utoipa::ToSchema;
@@ -41,6 +45,7 @@ mod post {
     #[derive(ToSchema, Serialize)]
     struct Response {
         user: shared::models::user::ApiFullUser,
+        email_verification_required: bool,
     }
 
     #[utoipa::path(post, path = "/", responses(
@@ -67,6 +72,8 @@ mod post {
                 .ok();
         }
         let secure = settings.app.url.starts_with("https://");
+        let email_verification = settings.app.email_verification;
+        let security = settings.security.clone();
         drop(settings);
 
         state
@@ -80,6 +87,16 @@ mod post {
                 .ok();
         }
 
+        User::validate_password_policy(
+            &state.database,
+            &security,
+            None,
+            &data.username,
+            &data.email,
+            &data.password,
+        )
+        .await?;
+
         let user = match User::create_automatic_admin(
             &state.database,
             None,
@@ -89,6 +106,7 @@ mod post {
             &data.name_first,
             &data.name_last,
             &data.password,
+            email_verification == EmailVerificationMode::Disabled,
         )
         .await
         {
@@ -107,6 +125,32 @@ mod post {
             }
         };
 
+        if email_verification != EmailVerificationMode::Disabled {
+            if let Err(err) = UserEmailVerification::send(
+                &state.database,
+                &state.settings,
+                &state.mail,
+                &user,
+                &user.email,
+            )
+            .await
+            {
+                tracing::warn!(
+                    user = %user.uuid,
+                    "failed to send email verification: {:#?}",
+                    err
+                );
+            }
+        }
+
+        if email_verification == EmailVerificationMode::RequiredForLogin {
+            return ApiResponse::new_serialized(Response {
+                user: user.into_api_full_object(&state.storage.retrieve_urls().await?),
+                email_verification_required: true,
+            })
+            .ok();
+        }
+
         let key = UserSession::create(
             &state.database,
             user.uuid,
@@ -133,6 +177,7 @@ mod post {
 
         ApiResponse::new_serialized(Response {
             user: user.into_api_full_object(&state.storage.retrieve_urls().await?),
+            email_verification_required: false,
         })
         .ok()
     }
@@ -0,0 +1,54 @@
+use super::State;
+use utoipa_axum::{router::OpenApiRouter, routes};
+
+mod get {
+    use axum::{body::Body, extract::Path, http::StatusCode};
+    use shared::{
+        ApiError, GetState,
+        models::{ByUuid, saml_provider::SamlProvider},
+        response::{ApiResponse, ApiResponseResult},
+    };
+
+    #[utoipa::path(get, path = "/", responses(
+        (status = OK, body = String),
+        (status = NOT_FOUND, body = ApiError),
+    ), params(
+        (
+            "saml_provider" = uuid::Uuid,
+            description = "The saml provider ID",
+            example = "123e4567-e89b-12d3-a456-426614174000",
+        ),
+    ))]
+    pub async fn route(
+        state: GetState,
+        Path(saml_provider): Path<uuid::Uuid>,
+    ) -> ApiResponseResult {
+        let saml_provider =
+            match SamlProvider::by_uuid_optional_cached(&state.database, saml_provider).await? {
+                Some(saml_provider) => saml_provider,
+                None => {
+                    return ApiResponse::error("saml provider not found")
+                        .with_status(StatusCode::NOT_FOUND)
+                        .ok();
+                }
+            };
+
+        let settings = state.settings.get().await?;
+        let metadata = shared::saml::metadata(
+            &saml_provider.sp_entity_id(&settings.app.url),
+            &saml_provider.acs_url(&settings.app.url),
+            &saml_provider.sp_certificate,
+        );
+        drop(settings);
+
+        ApiResponse::new(Body::from(metadata))
+            .with_header("Content-Type", "application/samlmetadata+xml")
+            .ok()
+    }
+}
+
+pub fn router(state: &State) -> OpenApiRouter<State> {
+    OpenApiRouter::new()
+        .routes(routes!(get::route))
+        .with_state(state.clone())
+}
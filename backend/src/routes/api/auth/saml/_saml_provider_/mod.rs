@@ -0,0 +1,293 @@
+use super::State;
+use axum::{Form, body::Body, extract::Path, http::StatusCode, routing::post};
+use rand::distr::SampleString;
+use rustis::commands::StringCommands;
+use serde::Deserialize;
+use shared::{
+    GetState,
+    models::{
+        ByUuid,
+        saml_provider::{SamlProvider, SamlRedirectState},
+        user::User,
+        user_activity::UserActivity,
+        user_saml_link::UserSamlLink,
+        user_session::UserSession,
+    },
+    response::ApiResponse,
+};
+use tower_cookies::{Cookie, Cookies};
+use utoipa_axum::router::OpenApiRouter;
+
+mod metadata;
+
+#[derive(Deserialize)]
+pub struct Params {
+    #[serde(rename = "SAMLResponse")]
+    saml_response: String,
+    #[serde(rename = "RelayState")]
+    relay_state: String,
+}
+
+pub fn router(state: &State) -> OpenApiRouter<State> {
+    OpenApiRouter::new()
+        .route("/", post(|state: GetState,
+            ip: shared::GetIp,
+            headers: axum::http::HeaderMap,
+            cookies: Cookies,
+            Path(saml_provider): Path<uuid::Uuid>,
+            Form(params): Form<Params>| async move {
+            state
+                .cache
+                .ratelimit(format!("auth/saml/{}", saml_provider), 6, 300, ip.to_string())
+                .await?;
+
+            let saml_provider =
+                match SamlProvider::by_uuid_optional_cached(&state.database, saml_provider).await? {
+                    Some(saml_provider) => saml_provider,
+                    None => {
+                        return ApiResponse::error("saml provider not found")
+                            .with_status(StatusCode::NOT_FOUND)
+                            .ok();
+                    }
+                };
+
+            if !saml_provider.enabled {
+                return ApiResponse::error("saml provider not found")
+                    .with_status(StatusCode::NOT_FOUND)
+                    .ok();
+            }
+
+            // the relay state is the id of the request, unsolicited responses are not accepted and
+            // taking the state atomically makes sure a response can only be used once
+            let redirect_state: SamlRedirectState = match state
+                .cache
+                .client
+                .getdel::<String>(format!("saml_state::{}", params.relay_state))
+                .await
+            {
+                Ok(redirect_state) => serde_json::from_str(&redirect_state).unwrap_or_default(),
+                Err(_) => {
+                    return ApiResponse::error("saml request state not found, please try again")
+                        .with_status(StatusCode::NOT_FOUND)
+                        .ok();
+                }
+            };
+
+            let settings = state.settings.get().await?;
+            let sp_entity_id = saml_provider.sp_entity_id(&settings.app.url);
+            let acs_url = saml_provider.acs_url(&settings.app.url);
+            drop(settings);
+
+            let assertion = match shared::saml::validate_response(
+                &params.saml_response,
+                &shared::saml::ResponseValidation {
+                    idp_entity_id: &saml_provider.idp_entity_id,
+                    idp_certificate: &saml_provider.idp_certificate,
+                    sp_entity_id: &sp_entity_id,
+                    acs_url: &acs_url,
+                    request_id: &params.relay_state,
+                },
+            ) {
+                Ok(assertion) => assertion,
+                Err(err) => {
+                    tracing::debug!(saml_provider = %saml_provider.uuid, "invalid saml response: {:?}", err);
+
+                    return ApiResponse::error("invalid saml response")
+                        .with_status(StatusCode::UNAUTHORIZED)
+                        .ok();
+                }
+            };
+
+            let identifier = match saml_provider.extract_identifier(&assertion) {
+                Ok(identifier) => identifier,
+                Err(err) => {
+                    return ApiResponse::error(&err.to_string())
+                        .with_status(StatusCode::INTERNAL_SERVER_ERROR)
+                        .ok();
+                }
+            };
+
+            if let Some(user_uuid) = redirect_state.link_user_uuid {
+                UserSamlLink::create(&state.database, user_uuid, saml_provider.uuid, &identifier)
+                    .await?;
+
+                if let Err(err) = UserActivity::log(
+                    &state.database,
+                    user_uuid,
+                    None,
+                    "account:saml-links.create",
+                    Some(ip.0.into()),
+                    serde_json::json!({
+                        "provider": saml_provider.name,
+                        "identifier": identifier,
+                    }),
+                )
+                .await
+                {
+                    tracing::warn!(user = %user_uuid, "failed to log user activity: {:?}", err);
+                }
+
+                let settings = state.settings.get().await?;
+
+                return ApiResponse::new(Body::empty())
+                    .with_header("Location", format!("{}/account/saml-links", settings.app.url.trim_end_matches('/')))
+                    .with_status(StatusCode::SEE_OTHER)
+                    .ok();
+            }
+
+            let (user, saml_link_uuid) = match UserSamlLink::by_saml_provider_uuid_identifier(&state.database, saml_provider.uuid, &identifier).await? {
+                Some(saml_link) => (saml_link.user.fetch(&state.database).await?, Some(saml_link.uuid)),
+                None => {
+                    let settings = state.settings.get().await?;
+                    if !settings.app.registration_enabled {
+                        return ApiResponse::error("registration is disabled")
+                            .with_status(StatusCode::BAD_REQUEST)
+                            .ok();
+                    }
+
+                    let username = match saml_provider.extract_username(&assertion) {
+                        Ok(username) => username,
+                        Err(err) => {
+                            return ApiResponse::error(&err.to_string())
+                                .with_status(StatusCode::INTERNAL_SERVER_ERROR)
+                                .ok();
+                        }
+                    };
+                    let email = match saml_provider.extract_email(&assertion) {
+                        Ok(email) => email,
+                        Err(err) => {
+                            return ApiResponse::error(&err.to_string())
+                                .with_status(StatusCode::INTERNAL_SERVER_ERROR)
+                                .ok();
+                        }
+                    };
+                    let name_first = match saml_provider.extract_name_first(&assertion) {
+                        Ok(name_first) => name_first,
+                        Err(err) => {
+                            return ApiResponse::error(&err.to_string())
+                                .with_status(StatusCode::INTERNAL_SERVER_ERROR)
+                                .ok();
+                        }
+                    };
+                    let name_last = match saml_provider.extract_name_last(&assertion) {
+                        Ok(name_last) => name_last,
+                        Err(err) => {
+                            return ApiResponse::error(&err.to_string())
+                                .with_status(StatusCode::INTERNAL_SERVER_ERROR)
+                                .ok();
+                        }
+                    };
+
+                    let password = rand::distr::Alphanumeric.sample_string(&mut rand::rng(), 16);
+                    let user = match User::create(
+                        &state.database,
+                        None,
+                        None,
+                        &username,
+                        &email,
+                        &name_first,
+                        &name_last,
+                        &password,
+                        false,
+                        &settings.app.language,
+                    )
+                    .await
+                    {
+                        Ok(user_uuid) => User::by_uuid(&state.database, user_uuid).await?,
+                        Err(err) if err.is_unique_violation() => {
+                            return ApiResponse::error("user with username or email already exists")
+                                .with_status(StatusCode::BAD_REQUEST)
+                                .ok();
+                        }
+                        Err(err) => {
+                            tracing::error!("failed to create user: {:?}", err);
+
+                            return ApiResponse::error("failed to create user")
+                                .with_status(StatusCode::INTERNAL_SERVER_ERROR)
+                                .ok();
+                        }
+                    };
+
+                    drop(settings);
+
+                    UserSamlLink::create(&state.database, user.uuid, saml_provider.uuid, &identifier)
+                        .await?;
+
+                    (user, None)
+                }
+            };
+
+            if !user.active {
+                return ApiResponse::error("account has been deactivated")
+                    .with_status(StatusCode::FORBIDDEN)
+                    .ok();
+            }
+
+            let key = UserSession::create(
+                &state.database,
+                user.uuid,
+                ip.0.into(),
+                headers
+                    .get("User-Agent")
+                    .map(|ua| shared::utils::slice_up_to(ua.to_str().unwrap_or("unknown"), 255))
+                    .unwrap_or("unknown"),
+            )
+            .await?;
+
+            let settings = state.settings.get().await?;
+            let secure = settings.app.url.starts_with("https://");
+            drop(settings);
+
+            cookies.add(
+                Cookie::build(("session", key))
+                    .http_only(true)
+                    .same_site(tower_cookies::cookie::SameSite::Strict)
+                    .secure(secure)
+                    .path("/")
+                    .expires(
+                        tower_cookies::cookie::time::OffsetDateTime::now_utc()
+                            + tower_cookies::cookie::time::Duration::days(30),
+                    )
+                    .build(),
+            );
+
+            if let Some(saml_link_uuid) = saml_link_uuid {
+                if let Err(err) = UserActivity::log(
+                    &state.database,
+                    user.uuid,
+                    None,
+                    "auth:success",
+                    Some(ip.0.into()),
+                    serde_json::json!({
+                        "using": "saml",
+                        "saml_provider": saml_provider.name,
+                    }),
+                )
+                .await
+                {
+                    tracing::warn!(user = %user.uuid, "failed to log user activity: {:?}", err);
+                }
+
+                sqlx::query(
+                    r#"
+                    UPDATE user_saml_links
+                    SET last_used = NOW()
+                    WHERE user_saml_links.uuid = $1
+                    "#,
+                )
+                .bind(saml_link_uuid)
+                .execute(state.database.write())
+                .await?;
+            }
+
+            let settings = state.settings.get().await?;
+
+            // the response was posted, so the browser has to be told to follow up with a GET
+            ApiResponse::new(Body::empty())
+                .with_header("Location", &settings.app.url)
+                .with_status(StatusCode::SEE_OTHER)
+                .ok()
+        }))
+        .nest("/metadata", metadata::router(state))
+        .with_state(state.clone())
+}
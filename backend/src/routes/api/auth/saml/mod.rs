@@ -0,0 +1,54 @@
+use super::State;
+use utoipa_axum::{router::OpenApiRouter, routes};
+
+mod _saml_provider_;
+mod redirect;
+
+mod get {
+    use serde::Serialize;
+    use shared::{
+        ApiError, GetState,
+        models::saml_provider::{ApiSamlProvider, SamlProvider},
+        response::{ApiResponse, ApiResponseResult},
+    };
+    use utoipa::ToSchema;
+
+    #[derive(ToSchema, Serialize)]
+    struct Response {
+        saml_providers: Vec<ApiSamlProvider>,
+    }
+
+    #[utoipa::path(get, path = "/", responses(
+        (status = OK, body = inline(Response)),
+        (status = NOT_FOUND, body = ApiError),
+    ))]
+    pub async fn route(state: GetState, ip: shared::GetIp) -> ApiResponseResult {
+        state
+            .cache
+            .ratelimit("auth/saml", 12, 60, ip.to_string())
+            .await?;
+
+        let saml_providers = state
+            .cache
+            .cached("saml_providers::usable", 60, || async {
+                SamlProvider::all_by_usable(&state.database).await
+            })
+            .await?;
+
+        ApiResponse::new_serialized(Response {
+            saml_providers: saml_providers
+                .into_iter()
+                .map(|saml_provider| saml_provider.into_api_object())
+                .collect(),
+        })
+        .ok()
+    }
+}
+
+pub fn router(state: &State) -> OpenApiRouter<State> {
+    OpenApiRouter::new()
+        .routes(routes!(get::route))
+        .nest("/redirect", redirect::router(state))
+        .nest("/{saml_provider}", _saml_provider_::router(state))
+        .with_state(state.clone())
+}
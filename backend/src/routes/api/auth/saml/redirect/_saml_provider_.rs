@@ -0,0 +1,130 @@
+use super::State;
+use utoipa_axum::{router::OpenApiRouter, routes};
+
+mod get {
+    use axum::{body::Body, extract::Path, http::StatusCode};
+    use rustis::commands::{SetExpiration, StringCommands};
+    use shared::{
+        ApiError, GetState,
+        models::{
+            ByUuid,
+            saml_provider::{SamlProvider, SamlRedirectState},
+            user::User,
+        },
+        response::{ApiResponse, ApiResponseResult},
+    };
+    use tower_cookies::Cookies;
+
+    #[utoipa::path(get, path = "/", responses(
+        (status = TEMPORARY_REDIRECT, body = String),
+        (status = NOT_FOUND, body = ApiError),
+    ))]
+    pub async fn route(
+        state: GetState,
+        ip: shared::GetIp,
+        cookies: Cookies,
+        Path(saml_provider): Path<uuid::Uuid>,
+    ) -> ApiResponseResult {
+        state
+            .cache
+            .ratelimit(
+                format!("auth/saml/redirect/{}", saml_provider),
+                6,
+                300,
+                ip.to_string(),
+            )
+            .await?;
+
+        let saml_provider =
+            match SamlProvider::by_uuid_optional_cached(&state.database, saml_provider).await? {
+                Some(saml_provider) => saml_provider,
+                None => {
+                    return ApiResponse::error("saml provider not found")
+                        .with_status(StatusCode::NOT_FOUND)
+                        .ok();
+                }
+            };
+
+        if !saml_provider.enabled {
+            return ApiResponse::error("saml provider not found")
+                .with_status(StatusCode::NOT_FOUND)
+                .ok();
+        }
+
+        let mut redirect_state = SamlRedirectState::default();
+
+        // the response is posted back cross-site, so the session cookie will not be sent along
+        // with it and the user that is linking has to be remembered here instead
+        if let Some(session_id) = cookies.get("session") {
+            if !saml_provider.user_manageable {
+                return ApiResponse::error("you cannot link with this saml provider")
+                    .with_status(StatusCode::CONFLICT)
+                    .ok();
+            }
+
+            if session_id.value().len() != 81 {
+                return ApiResponse::error("invalid authorization cookie")
+                    .with_status(StatusCode::UNAUTHORIZED)
+                    .ok();
+            }
+
+            let (user, session) =
+                match User::by_session(&state.database, session_id.value()).await? {
+                    Some(data) => data,
+                    None => {
+                        return ApiResponse::error("invalid session")
+                            .with_status(StatusCode::UNAUTHORIZED)
+                            .ok();
+                    }
+                };
+
+            if session.impersonator_uuid.is_some() {
+                return ApiResponse::error(
+                    "this action is not available while impersonating a user",
+                )
+                .with_status(StatusCode::FORBIDDEN)
+                .ok();
+            }
+
+            redirect_state.link_user_uuid = Some(user.uuid);
+        }
+
+        let settings = state.settings.get().await?;
+        let sp_entity_id = saml_provider.sp_entity_id(&settings.app.url);
+        let acs_url = saml_provider.acs_url(&settings.app.url);
+        drop(settings);
+
+        let request_id = format!("_{}", uuid::Uuid::new_v4().simple());
+        let private_key = state.database.decrypt(saml_provider.sp_private_key).await?;
+
+        let authn_request_url = shared::saml::authn_request_url(
+            &saml_provider.idp_sso_url,
+            &sp_entity_id,
+            &acs_url,
+            &request_id,
+            &private_key,
+        )?;
+
+        state
+            .cache
+            .client
+            .set_with_options(
+                format!("saml_state::{request_id}"),
+                serde_json::to_string(&redirect_state)?,
+                None,
+                SetExpiration::Ex(10 * 60),
+            )
+            .await?;
+
+        ApiResponse::new(Body::empty())
+            .with_header("Location", authn_request_url)
+            .with_status(StatusCode::TEMPORARY_REDIRECT)
+            .ok()
+    }
+}
+
+pub fn router(state: &State) -> OpenApiRouter<State> {
+    OpenApiRouter::new()
+        .routes(routes!(get::route))
+        .with_state(state.clone())
+}
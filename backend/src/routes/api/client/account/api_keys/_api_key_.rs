@@ -75,6 +75,7 @@ mod patch {
             user::{AuthMethod, GetAuthMethod, GetPermissionManager, GetUser},
             user_activity::GetUserActivityLogger,
             user_api_key::UserApiKey,
+            user_server_group::UserServerGroup,
         },
         prelude::SqlxErrorExt,
         response::{ApiResponse, ApiResponseResult},
@@ -98,6 +99,11 @@ mod patch {
         #[validate(custom(function = "shared::permissions::validate_server_permissions"))]
         server_permissions: Option<Vec<compact_str::CompactString>>,
 
+        #[validate(length(max = 100))]
+        #[schema(max_length = 100)]
+        server_uuids: Option<Option<Vec<uuid::Uuid>>>,
+        server_group_uuid: Option<Option<uuid::Uuid>>,
+
         expires: Option<Option<chrono::DateTime<chrono::Utc>>>,
     }
 
@@ -182,6 +188,56 @@ mod patch {
             api_key.expires = expires.map(|dt| dt.naive_utc());
         }
 
+        let update_server_scope = data.server_uuids.is_some() || data.server_group_uuid.is_some();
+        if let Some(server_uuids) = data.server_uuids {
+            api_key.server_uuids = server_uuids;
+        }
+        if let Some(server_group_uuid) = data.server_group_uuid {
+            api_key.server_group_uuid = server_group_uuid;
+        }
+
+        if update_server_scope {
+            if api_key.server_uuids.is_some() && api_key.server_group_uuid.is_some() {
+                return ApiResponse::error(
+                    "server_uuids: cannot be combined with server_group_uuid",
+                )
+                .with_status(StatusCode::BAD_REQUEST)
+                .ok();
+            }
+
+            api_key.server_group_server_uuids = match api_key.server_group_uuid {
+                Some(server_group_uuid) => {
+                    match UserServerGroup::by_user_uuid_uuid(
+                        &state.database,
+                        user.uuid,
+                        server_group_uuid,
+                    )
+                    .await?
+                    {
+                        Some(server_group) => Some(server_group.server_order),
+                        None => {
+                            return ApiResponse::error("server group not found")
+                                .with_status(StatusCode::NOT_FOUND)
+                                .ok();
+                        }
+                    }
+                }
+                None => None,
+            };
+
+            if permissions.server_scope().is_some()
+                && !api_key.server_scope().is_some_and(|server_uuids| {
+                    server_uuids
+                        .iter()
+                        .all(|server_uuid| permissions.can_access_server(*server_uuid))
+                })
+            {
+                return ApiResponse::error("server_uuids: more servers than self")
+                    .with_status(StatusCode::BAD_REQUEST)
+                    .ok();
+            }
+        }
+
         match sqlx::query!(
             "UPDATE user_api_keys
             SET name = $2, allowed_ips = $3, user_permissions = $4, admin_permissions = $5, server_permissions = $6, expires = $7
@@ -212,6 +268,10 @@ mod patch {
             }
         }
 
+        if update_server_scope {
+            api_key.update_server_scope(&state.database).await?;
+        }
+
         activity_logger
             .log(
                 "user:api-key.update",
@@ -223,6 +283,8 @@ mod patch {
                     "user_permissions": api_key.user_permissions,
                     "admin_permissions": api_key.admin_permissions,
                     "server_permissions": api_key.server_permissions,
+                    "server_uuids": api_key.server_uuids,
+                    "server_group_uuid": api_key.server_group_uuid,
                     "expires": api_key.expires,
                 }),
             )
@@ -89,6 +89,7 @@ mod post {
             user::{AuthMethod, GetAuthMethod, GetPermissionManager, GetUser},
             user_activity::GetUserActivityLogger,
             user_api_key::UserApiKey,
+            user_server_group::UserServerGroup,
         },
         response::{ApiResponse, ApiResponseResult},
     };
@@ -110,6 +111,11 @@ mod post {
         #[validate(custom(function = "shared::permissions::validate_server_permissions"))]
         server_permissions: Vec<compact_str::CompactString>,
 
+        #[validate(length(max = 100))]
+        #[schema(max_length = 100)]
+        server_uuids: Option<Vec<uuid::Uuid>>,
+        server_group_uuid: Option<uuid::Uuid>,
+
         expires: Option<chrono::DateTime<chrono::Utc>>,
     }
 
@@ -160,6 +166,44 @@ mod post {
                 .ok();
         }
 
+        if data.server_uuids.is_some() && data.server_group_uuid.is_some() {
+            return ApiResponse::error("server_uuids: cannot be combined with server_group_uuid")
+                .with_status(StatusCode::BAD_REQUEST)
+                .ok();
+        }
+
+        let server_scope = match data.server_group_uuid {
+            Some(server_group_uuid) => {
+                match UserServerGroup::by_user_uuid_uuid(
+                    &state.database,
+                    user.uuid,
+                    server_group_uuid,
+                )
+                .await?
+                {
+                    Some(server_group) => Some(server_group.server_order),
+                    None => {
+                        return ApiResponse::error("server group not found")
+                            .with_status(StatusCode::NOT_FOUND)
+                            .ok();
+                    }
+                }
+            }
+            None => data.server_uuids.clone(),
+        };
+
+        if permissions.server_scope().is_some()
+            && !server_scope.as_ref().is_some_and(|server_uuids| {
+                server_uuids
+                    .iter()
+                    .all(|server_uuid| permissions.can_access_server(*server_uuid))
+            })
+        {
+            return ApiResponse::error("server_uuids: more servers than self")
+                .with_status(StatusCode::BAD_REQUEST)
+                .ok();
+        }
+
         let (key, api_key) = match UserApiKey::create(
             &state.database,
             user.uuid,
@@ -168,6 +212,8 @@ mod post {
             &data.user_permissions,
             &data.admin_permissions,
             &data.server_permissions,
+            data.server_uuids.as_deref(),
+            data.server_group_uuid,
             data.expires.map(|dt| dt.naive_utc()),
         )
         .await
@@ -198,6 +244,8 @@ mod post {
                     "user_permissions": api_key.user_permissions,
                     "admin_permissions": api_key.admin_permissions,
                     "server_permissions": api_key.server_permissions,
+                    "server_uuids": api_key.server_uuids,
+                    "server_group_uuid": api_key.server_group_uuid,
                     "expires": api_key.expires,
                 }),
             )
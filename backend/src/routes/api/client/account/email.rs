@@ -1,106 +0,0 @@
-use super::State;
-use utoipa_axum::{router::OpenApiRouter, routes};
-
-mod put {
-    use axum::http::StatusCode;
-    use serde::{Deserialize, Serialize};
-    use shared::{
-        ApiError, GetState,
-        models::{
-            user::{GetPermissionManager, GetUser},
-            user_activity::GetUserActivityLogger,
-        },
-        prelude::SqlxErrorExt,
-        response::{ApiResponse, ApiResponseResult},
-    };
-    use utoipa::ToSchema;
-    use validator::Validate;
-
-    #[derive(ToSchema, Validate, Deserialize)]
-    pub struct Payload {
-        #[validate(email)]
-        #[schema(format = "email")]
-        email: String,
-        #[validate(length(max = 512))]
-        #[schema(max_length = 512)]
-        password: String,
-    }
-
-    #[derive(ToSchema, Serialize)]
-    struct Response {}
-
-    #[utoipa::path(put, path = "/", responses(
-        (status = OK, body = inline(Response)),
-        (status = UNAUTHORIZED, body = ApiError),
-        (status = CONFLICT, body = ApiError),
-    ), request_body = inline(Payload))]
-    pub async fn route(
-        state: GetState,
-        permissions: GetPermissionManager,
-        user: GetUser,
-        activity_logger: GetUserActivityLogger,
-        shared::Payload(data): shared::Payload<Payload>,
-    ) -> ApiResponseResult {
-        if let Err(errors) = shared::utils::validate_data(&data) {
-            return ApiResponse::new_serialized(ApiError::new_strings_value(errors))
-                .with_status(StatusCode::BAD_REQUEST)
-                .ok();
-        }
-
-        permissions.has_user_permission("account.email")?;
-
-        if !user
-            .validate_password(&state.database, &data.password)
-            .await?
-        {
-            return ApiResponse::error("invalid password")
-                .with_status(StatusCode::UNAUTHORIZED)
-                .ok();
-        }
-
-        if user.email != data.email {
-            match sqlx::query!(
-                "UPDATE users
-                SET email = $1
-                WHERE users.uuid = $2",
-                data.email,
-                user.uuid
-            )
-            .execute(state.database.write())
-            .await
-            {
-                Ok(_) => {}
-                Err(err) if err.is_unique_violation() => {
-                    return ApiResponse::error("email already in use")
-                        .with_status(StatusCode::CONFLICT)
-                        .ok();
-                }
-                Err(err) => {
-                    tracing::error!("failed to update user email: {:?}", err);
-
-                    return ApiResponse::error("failed to update user email")
-                        .with_status(StatusCode::INTERNAL_SERVER_ERROR)
-                        .ok();
-                }
-            }
-
-            activity_logger
-                .log(
-                    "account:email-changed",
-                    serde_json::json!({
-                        "old": user.email,
-                        "new": data.email,
-                    }),
-                )
-                .await;
-        }
-
-        ApiResponse::new_serialized(Response {}).ok()
-    }
-}
-
-pub fn router(state: &State) -> OpenApiRouter<State> {
-    OpenApiRouter::new()
-        .routes(routes!(put::route))
-        .with_state(state.clone())
-}
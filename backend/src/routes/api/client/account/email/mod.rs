@@ -0,0 +1,200 @@
+use super::State;
+use utoipa_axum::{router::OpenApiRouter, routes};
+
+mod resend;
+
+mod get {
+    use serde::Serialize;
+    use shared::{
+        GetState,
+        models::{user::GetUser, user_email_verification::UserEmailVerification},
+        response::{ApiResponse, ApiResponseResult},
+    };
+    use utoipa::ToSchema;
+
+    #[derive(ToSchema, Serialize)]
+    struct Response {
+        email: compact_str::CompactString,
+        email_verified: bool,
+        pending_email: Option<compact_str::CompactString>,
+    }
+
+    #[utoipa::path(get, path = "/", responses(
+        (status = OK, body = inline(Response)),
+    ))]
+    pub async fn route(state: GetState, user: GetUser) -> ApiResponseResult {
+        let pending_email =
+            UserEmailVerification::pending_email_by_user_uuid(&state.database, user.uuid)
+                .await?
+                .filter(|pending_email| *pending_email != user.email);
+
+        ApiResponse::new_serialized(Response {
+            email: user.email.clone(),
+            email_verified: user.email_verified,
+            pending_email,
+        })
+        .ok()
+    }
+}
+
+mod put {
+    use axum::http::StatusCode;
+    use serde::{Deserialize, Serialize};
+    use shared::{
+        ApiError, GetState,
+        models::{
+            user::{GetPermissionManager, GetUser, User, UserPasskeyConfirmation},
+            user_activity::GetUserActivityLogger,
+            user_email_verification::UserEmailVerification,
+        },
+        prelude::SqlxErrorExt,
+        response::{ApiResponse, ApiResponseResult},
+        settings::EmailVerificationMode,
+    };
+    use utoipa::ToSchema;
+    use validator::Validate;
+
+    #[derive(ToSchema, Validate, Deserialize)]
+    pub struct Payload {
+        #[validate(email)]
+        #[schema(format = "email")]
+        email: String,
+        #[validate(length(max = 512))]
+        #[schema(max_length = 512)]
+        #[serde(default)]
+        password: String,
+        security_key: Option<UserPasskeyConfirmation>,
+    }
+
+    #[derive(ToSchema, Serialize)]
+    struct Response {
+        verification_pending: bool,
+    }
+
+    #[utoipa::path(put, path = "/", responses(
+        (status = OK, body = inline(Response)),
+        (status = UNAUTHORIZED, body = ApiError),
+        (status = CONFLICT, body = ApiError),
+    ), request_body = inline(Payload))]
+    pub async fn route(
+        state: GetState,
+        permissions: GetPermissionManager,
+        user: GetUser,
+        activity_logger: GetUserActivityLogger,
+        shared::Payload(data): shared::Payload<Payload>,
+    ) -> ApiResponseResult {
+        if let Err(errors) = shared::utils::validate_data(&data) {
+            return ApiResponse::new_serialized(ApiError::new_strings_value(errors))
+                .with_status(StatusCode::BAD_REQUEST)
+                .ok();
+        }
+
+        permissions.has_user_permission("account.email")?;
+
+        if !user
+            .validate_password_or_passkey(
+                &state.database,
+                &state.settings,
+                &data.password,
+                data.security_key.as_ref(),
+            )
+            .await?
+        {
+            return ApiResponse::error("invalid password or security key")
+                .with_status(StatusCode::UNAUTHORIZED)
+                .ok();
+        }
+
+        if user.email == data.email {
+            return ApiResponse::new_serialized(Response {
+                verification_pending: false,
+            })
+            .ok();
+        }
+
+        let email_verification = state.settings.get().await?.app.email_verification;
+
+        if email_verification != EmailVerificationMode::Disabled {
+            if User::by_email(&state.database, &data.email)
+                .await?
+                .is_some()
+            {
+                return ApiResponse::error("email already in use")
+                    .with_status(StatusCode::CONFLICT)
+                    .ok();
+            }
+
+            UserEmailVerification::send(
+                &state.database,
+                &state.settings,
+                &state.mail,
+                &user,
+                &data.email,
+            )
+            .await?;
+
+            activity_logger
+                .log(
+                    "account:email-change-requested",
+                    serde_json::json!({
+                        "old": user.email,
+                        "new": data.email,
+                    }),
+                )
+                .await;
+
+            return ApiResponse::new_serialized(Response {
+                verification_pending: true,
+            })
+            .ok();
+        }
+
+        match sqlx::query!(
+            "UPDATE users
+                SET email = $1
+                WHERE users.uuid = $2",
+            data.email,
+            user.uuid
+        )
+        .execute(state.database.write())
+        .await
+        {
+            Ok(_) => {}
+            Err(err) if err.is_unique_violation() => {
+                return ApiResponse::error("email already in use")
+                    .with_status(StatusCode::CONFLICT)
+                    .ok();
+            }
+            Err(err) => {
+                tracing::error!("failed to update user email: {:?}", err);
+
+                return ApiResponse::error("failed to update user email")
+                    .with_status(StatusCode::INTERNAL_SERVER_ERROR)
+                    .ok();
+            }
+        }
+
+        activity_logger
+            .log(
+                "account:email-changed",
+                serde_json::json!({
+                    "old": user.email,
+                    "new": data.email,
+                }),
+            )
+            .await;
+
+        ApiResponse::new_serialized(Response {
+            verification_pending: false,
+        })
+        .ok()
+    }
+}
+
+pub fn router(state: &State) -> OpenApiRouter<State> {
+    OpenApiRouter::new()
+        .routes(routes!(get::route))
+        .routes(routes!(put::route))
+        .nest("/resend", resend::router(state))
+        .with_state(state.clone())
+}
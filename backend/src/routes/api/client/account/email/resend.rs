@@ -0,0 +1,63 @@
+use super::State;
+use utoipa_axum::{router::OpenApiRouter, routes};
+
+mod post {
+    use axum::http::StatusCode;
+    use serde::Serialize;
+    use shared::{
+        ApiError, GetState,
+        models::{user::GetUser, user_email_verification::UserEmailVerification},
+        response::{ApiResponse, ApiResponseResult},
+        settings::EmailVerificationMode,
+    };
+    use utoipa::ToSchema;
+
+    #[derive(ToSchema, Serialize)]
+    struct Response {}
+
+    #[utoipa::path(post, path = "/", responses(
+        (status = OK, body = inline(Response)),
+        (status = BAD_REQUEST, body = ApiError),
+    ))]
+    pub async fn route(state: GetState, user: GetUser) -> ApiResponseResult {
+        if state.settings.get().await?.app.email_verification == EmailVerificationMode::Disabled {
+            return ApiResponse::error("email verification is disabled")
+                .with_status(StatusCode::BAD_REQUEST)
+                .ok();
+        }
+
+        state
+            .cache
+            .ratelimit(
+                "client/account/email/resend",
+                3,
+                3600,
+                user.uuid.to_string(),
+            )
+            .await?;
+
+        let email =
+            match UserEmailVerification::pending_email_by_user_uuid(&state.database, user.uuid)
+                .await?
+            {
+                Some(pending_email) => pending_email,
+                None if !user.email_verified => user.email.clone(),
+                None => {
+                    return ApiResponse::error("no email address is waiting for verification")
+                        .with_status(StatusCode::BAD_REQUEST)
+                        .ok();
+                }
+            };
+
+        UserEmailVerification::send(&state.database, &state.settings, &state.mail, &user, &email)
+            .await?;
+
+        ApiResponse::new_serialized(Response {}).ok()
+    }
+}
+
+pub fn router(state: &State) -> OpenApiRouter<State> {
+    OpenApiRouter::new()
+        .routes(routes!(post::route))
+        .with_state(state.clone())
+}
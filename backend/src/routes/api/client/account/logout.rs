@@ -3,12 +3,16 @@ use utoipa_axum::{router::OpenApiRouter, routes};
 
 mod post {
     use axum::http::StatusCode;
+    use rustis::commands::{GenericCommands, StringCommands};
     use serde::Serialize;
     use shared::{
         ApiError, GetState,
         models::{
-            DeletableModel,
-            user::{AuthMethod, GetAuthMethod},
+            ByUuid, DeletableModel,
+            admin_activity::AdminActivity,
+            oauth_provider::{OAuthProvider, oidc::OidcLogoutState},
+            user::{AuthMethod, GetAuthMethod, GetUser},
+            user_activity::UserActivity,
         },
         response::{ApiResponse, ApiResponseResult},
     };
@@ -16,7 +20,9 @@ mod post {
     use utoipa::ToSchema;
 
     #[derive(ToSchema, Serialize)]
-    struct Response {}
+    struct Response {
+        redirect_url: Option<String>,
+    }
 
     #[utoipa::path(post, path = "/", responses(
         (status = OK, body = inline(Response)),
@@ -24,6 +30,8 @@ mod post {
     ))]
     pub async fn route(
         state: GetState,
+        ip: shared::GetIp,
+        user: GetUser,
         auth: GetAuthMethod,
         cookies: Cookies,
     ) -> ApiResponseResult {
@@ -40,8 +48,118 @@ mod post {
 
         session.delete(&state, ()).await?;
 
+        if let Some(impersonator_uuid) = session.impersonator_uuid {
+            if let Err(err) = UserActivity::log(
+                &state.database,
+                user.uuid,
+                None,
+                "impersonation:end",
+                Some(ip.0.into()),
+                serde_json::json!({
+                    "impersonator": impersonator_uuid,
+                }),
+            )
+            .await
+            {
+                tracing::warn!(user = %user.uuid, "failed to log user activity: {:?}", err);
+            }
+
+            if let Err(err) = AdminActivity::log(
+                &state.database,
+                Some(impersonator_uuid),
+                None,
+                "user:impersonate.end",
+                Some(ip.0.into()),
+                serde_json::json!({
+                    "user": user.uuid,
+                }),
+            )
+            .await
+            {
+                tracing::warn!(user = %impersonator_uuid, "failed to log admin activity: {:?}", err);
+            }
+
+            if let Some(impersonator_session) = cookies.get("impersonator_session") {
+                let secure = state
+                    .settings
+                    .get_as(|settings| settings.app.url.starts_with("https://"))
+                    .await?;
+
+                cookies.add(
+                    Cookie::build(("session", impersonator_session.value().to_string()))
+                        .http_only(true)
+                        .same_site(tower_cookies::cookie::SameSite::Lax)
+                        .secure(secure)
+                        .path("/")
+                        .expires(
+                            tower_cookies::cookie::time::OffsetDateTime::now_utc()
+                                + tower_cookies::cookie::time::Duration::days(30),
+                        )
+                        .build(),
+                );
+                cookies.add(
+                    Cookie::build(("impersonator_session", ""))
+                        .http_only(true)
+                        .same_site(tower_cookies::cookie::SameSite::Lax)
+                        .secure(secure)
+                        .path("/")
+                        .expires(
+                            tower_cookies::cookie::time::OffsetDateTime::now_utc()
+                                + tower_cookies::cookie::time::Duration::seconds(2),
+                        )
+                        .build(),
+                );
+
+                return ApiResponse::new_serialized(Response { redirect_url: None }).ok();
+            }
+        }
+
+        let logout_state = match cookies
+            .get("session")
+            .and_then(|cookie| cookie.value().split(':').next().map(str::to_string))
+        {
+            Some(key_id) => {
+                let key = format!("oidc_logout::{key_id}");
+
+                match state.cache.client.get::<String>(&key).await {
+                    Ok(logout_state) => {
+                        state.cache.client.del(&key).await?;
+
+                        serde_json::from_str::<OidcLogoutState>(&logout_state).ok()
+                    }
+                    Err(_) => None,
+                }
+            }
+            None => None,
+        };
+
         let settings = state.settings.get().await?;
 
+        let mut redirect_url = None;
+        if let Some(logout_state) = logout_state
+            && let Some(oauth_provider) = OAuthProvider::by_uuid_optional_cached(
+                &state.database,
+                logout_state.oauth_provider_uuid,
+            )
+            .await?
+        {
+            match oauth_provider.fetch_oidc_discovery(&state).await {
+                Ok(Some(discovery)) => {
+                    redirect_url = oauth_provider
+                        .oidc_logout_url(&discovery, &logout_state.id_token, &settings.app.url)?
+                        .map(|url| url.to_string());
+                }
+                Ok(None) => {}
+                Err(err) => {
+                    tracing::warn!(
+                        oauth_provider = %oauth_provider.uuid,
+                        "failed to fetch oidc discovery document for logout: {:?}",
+                        err
+                    );
+                }
+            }
+        }
+
         cookies.add(
             Cookie::build(("session", ""))
                 .http_only(true)
@@ -55,7 +173,7 @@ mod post {
                 .build(),
         );
 
-        ApiResponse::new_serialized(Response {}).ok()
+        ApiResponse::new_serialized(Response { redirect_url }).ok()
     }
 }
 
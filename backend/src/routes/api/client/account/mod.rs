@@ -8,6 +8,7 @@ mod email;
 mod logout;
 mod oauth_links;
 mod password;
+mod saml_links;
 mod security_keys;
 mod sessions;
 mod ssh_keys;
@@ -17,7 +18,10 @@ mod get {
     use serde::Serialize;
     use shared::{
         GetState,
-        models::user::GetUser,
+        models::{
+            ByUuid,
+            user::{AuthMethod, GetAuthMethod, GetUser, User},
+        },
         response::{ApiResponse, ApiResponseResult},
     };
     use utoipa::ToSchema;
@@ -25,16 +29,29 @@ mod get {
     #[derive(ToSchema, Serialize)]
     struct Response {
         user: shared::models::user::ApiFullUser,
+        impersonator: Option<shared::models::user::ApiUser>,
     }
 
     #[utoipa::path(get, path = "/", responses(
         (status = OK, body = inline(Response)),
     ))]
-    pub async fn route(state: GetState, user: GetUser) -> ApiResponseResult {
+    pub async fn route(state: GetState, user: GetUser, auth: GetAuthMethod) -> ApiResponseResult {
+        let impersonator = match &auth.0 {
+            AuthMethod::Session(session) => match session.impersonator_uuid {
+                Some(impersonator_uuid) => {
+                    User::by_uuid_optional(&state.database, impersonator_uuid).await?
+                }
+                None => None,
+            },
+            AuthMethod::ApiKey(_) => None,
+        };
+
+        let storage_url_retriever = state.storage.retrieve_urls().await?;
+
         ApiResponse::new_serialized(Response {
-            user: user
-                .0
-                .into_api_full_object(&state.storage.retrieve_urls().await?),
+            user: user.0.into_api_full_object(&storage_url_retriever),
+            impersonator: impersonator
+                .map(|impersonator| impersonator.into_api_object(&storage_url_retriever)),
         })
         .ok()
     }
@@ -161,6 +178,7 @@ pub fn router(state: &State) -> OpenApiRouter<State> {
         .nest("/two-factor", two_factor::router(state))
         .nest("/security-keys", security_keys::router(state))
         .nest("/oauth-links", oauth_links::router(state))
+        .nest("/saml-links", saml_links::router(state))
         .nest("/api-keys", api_keys::router(state))
         .nest("/ssh-keys", ssh_keys::router(state))
         .nest("/sessions", sessions::router(state))
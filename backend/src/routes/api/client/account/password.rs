@@ -7,7 +7,7 @@ mod put {
     use shared::{
         ApiError, GetState,
         models::{
-            user::{GetPermissionManager, GetUser},
+            user::{GetPermissionManager, GetUser, User, UserPasskeyConfirmation},
             user_activity::GetUserActivityLogger,
         },
         response::{ApiResponse, ApiResponseResult},
@@ -22,7 +22,9 @@ mod put {
         new_password: String,
         #[validate(length(max = 512))]
         #[schema(max_length = 512)]
+        #[serde(default)]
         password: String,
+        security_key: Option<UserPasskeyConfirmation>,
     }
 
     #[derive(ToSchema, Serialize)]
@@ -48,14 +50,32 @@ mod put {
         permissions.has_user_permission("account.password")?;
 
         if !user
-            .validate_password(&state.database, &data.password)
+            .validate_password_or_passkey(
+                &state.database,
+                &state.settings,
+                &data.password,
+                data.security_key.as_ref(),
+            )
             .await?
         {
-            return ApiResponse::error("invalid password")
+            return ApiResponse::error("invalid password or security key")
                 .with_status(StatusCode::UNAUTHORIZED)
                 .ok();
         }
 
+        User::validate_password_policy(
+            &state.database,
+            &state
+                .settings
+                .get_as(|settings| settings.security.clone())
+                .await?,
+            Some(user.uuid),
+            &user.username,
+            &user.email,
+            &data.new_password,
+        )
+        .await?;
+
         user.update_password(&state.database, &data.new_password)
             .await?;
 
@@ -67,8 +87,84 @@ mod put {
     }
 }
 
+mod delete {
+    use axum::http::StatusCode;
+    use serde::{Deserialize, Serialize};
+    use shared::{
+        ApiError, GetState,
+        models::{
+            user::{GetPermissionManager, GetUser},
+            user_activity::GetUserActivityLogger,
+        },
+        response::{ApiResponse, ApiResponseResult},
+    };
+    use utoipa::ToSchema;
+    use validator::Validate;
+
+    #[derive(ToSchema, Validate, Deserialize)]
+    pub struct Payload {
+        #[validate(length(max = 512))]
+        #[schema(max_length = 512)]
+        password: String,
+    }
+
+    #[derive(ToSchema, Serialize)]
+    struct Response {}
+
+    #[utoipa::path(delete, path = "/", responses(
+        (status = OK, body = inline(Response)),
+        (status = BAD_REQUEST, body = ApiError),
+        (status = UNAUTHORIZED, body = ApiError),
+    ), request_body = inline(Payload))]
+    pub async fn route(
+        state: GetState,
+        permissions: GetPermissionManager,
+        user: GetUser,
+        activity_logger: GetUserActivityLogger,
+        shared::Payload(data): shared::Payload<Payload>,
+    ) -> ApiResponseResult {
+        if let Err(errors) = shared::utils::validate_data(&data) {
+            return ApiResponse::new_serialized(ApiError::new_strings_value(errors))
+                .with_status(StatusCode::BAD_REQUEST)
+                .ok();
+        }
+
+        permissions.has_user_permission("account.password")?;
+
+        if !user.has_password {
+            return ApiResponse::error("account does not have a password")
+                .with_status(StatusCode::BAD_REQUEST)
+                .ok();
+        }
+
+        if !user
+            .validate_password(&state.database, &data.password)
+            .await?
+        {
+            return ApiResponse::error("invalid password")
+                .with_status(StatusCode::UNAUTHORIZED)
+                .ok();
+        }
+
+        if !user.has_passkey(&state.database).await? {
+            return ApiResponse::error("a passkey is required to remove the password")
+                .with_status(StatusCode::BAD_REQUEST)
+                .ok();
+        }
+
+        user.remove_password(&state.database).await?;
+
+        activity_logger
+            .log("account:password-removed", serde_json::json!({}))
+            .await;
+
+        ApiResponse::new_serialized(Response {}).ok()
+    }
+}
+
 pub fn router(state: &State) -> OpenApiRouter<State> {
     OpenApiRouter::new()
         .routes(routes!(put::route))
+        .routes(routes!(delete::route))
         .with_state(state.clone())
 }
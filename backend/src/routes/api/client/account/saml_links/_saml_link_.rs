@@ -0,0 +1,125 @@
+use super::State;
+use utoipa_axum::{router::OpenApiRouter, routes};
+
+mod get {
+    use axum::{extract::Path, http::StatusCode};
+    use serde::Serialize;
+    use shared::{
+        ApiError, GetState,
+        models::{
+            user::{GetPermissionManager, GetUser},
+            user_saml_link::UserSamlLink,
+        },
+        response::{ApiResponse, ApiResponseResult},
+    };
+    use utoipa::ToSchema;
+
+    #[derive(ToSchema, Serialize)]
+    struct Response {
+        saml_link: shared::models::user_saml_link::ApiUserSamlLink,
+    }
+
+    #[utoipa::path(get, path = "/", responses(
+        (status = OK, body = inline(Response)),
+        (status = NOT_FOUND, body = ApiError),
+    ), params(
+        (
+            "saml_link" = uuid::Uuid,
+            description = "The saml link ID",
+            example = "123e4567-e89b-12d3-a456-426614174000",
+        ),
+    ))]
+    pub async fn route(
+        state: GetState,
+        permissions: GetPermissionManager,
+        user: GetUser,
+        Path(saml_link): Path<uuid::Uuid>,
+    ) -> ApiResponseResult {
+        permissions.has_user_permission("saml-links.read")?;
+
+        let saml_link =
+            match UserSamlLink::by_user_uuid_uuid(&state.database, user.uuid, saml_link).await? {
+                Some(saml_link) => saml_link,
+                None => {
+                    return ApiResponse::error("saml link not found")
+                        .with_status(StatusCode::NOT_FOUND)
+                        .ok();
+                }
+            };
+
+        ApiResponse::new_serialized(Response {
+            saml_link: saml_link.into_api_object(&state.database).await?,
+        })
+        .ok()
+    }
+}
+
+mod delete {
+    use axum::{extract::Path, http::StatusCode};
+    use serde::Serialize;
+    use shared::{
+        ApiError, GetState,
+        models::{
+            DeletableModel,
+            user::{GetPermissionManager, GetUser},
+            user_activity::GetUserActivityLogger,
+            user_saml_link::UserSamlLink,
+        },
+        response::{ApiResponse, ApiResponseResult},
+    };
+    use utoipa::ToSchema;
+
+    #[derive(ToSchema, Serialize)]
+    struct Response {}
+
+    #[utoipa::path(delete, path = "/", responses(
+        (status = OK, body = inline(Response)),
+        (status = NOT_FOUND, body = ApiError),
+    ), params(
+        (
+            "saml_link" = uuid::Uuid,
+            description = "The saml link ID",
+            example = "123e4567-e89b-12d3-a456-426614174000",
+        ),
+    ))]
+    pub async fn route(
+        state: GetState,
+        permissions: GetPermissionManager,
+        user: GetUser,
+        activity_logger: GetUserActivityLogger,
+        Path(saml_link): Path<uuid::Uuid>,
+    ) -> ApiResponseResult {
+        permissions.has_user_permission("saml-links.delete")?;
+
+        let saml_link =
+            match UserSamlLink::by_user_uuid_uuid(&state.database, user.uuid, saml_link).await? {
+                Some(saml_link) => saml_link,
+                None => {
+                    return ApiResponse::error("saml link not found")
+                        .with_status(StatusCode::NOT_FOUND)
+                        .ok();
+                }
+            };
+
+        saml_link.delete(&state, ()).await?;
+
+        activity_logger
+            .log(
+                "saml-link:delete",
+                serde_json::json!({
+                    "uuid": saml_link.uuid,
+                    "saml_provider": saml_link.saml_provider.fetch_cached(&state.database).await?.name,
+                }),
+            )
+            .await;
+
+        ApiResponse::new_serialized(Response {}).ok()
+    }
+}
+
+pub fn router(state: &State) -> OpenApiRouter<State> {
+    OpenApiRouter::new()
+        .routes(routes!(get::route))
+        .routes(routes!(delete::route))
+        .with_state(state.clone())
+}
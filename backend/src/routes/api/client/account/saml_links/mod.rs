@@ -0,0 +1,76 @@
+use super::State;
+use utoipa_axum::{router::OpenApiRouter, routes};
+
+mod _saml_link_;
+
+mod get {
+    use axum::{extract::Query, http::StatusCode};
+    use serde::Serialize;
+    use shared::{
+        ApiError, GetState,
+        models::{
+            Pagination, PaginationParams,
+            user::{GetPermissionManager, GetUser},
+            user_saml_link::UserSamlLink,
+        },
+        response::{ApiResponse, ApiResponseResult},
+    };
+    use utoipa::ToSchema;
+
+    #[derive(ToSchema, Serialize)]
+    struct Response {
+        #[schema(inline)]
+        saml_links: Pagination<shared::models::user_saml_link::ApiUserSamlLink>,
+    }
+
+    #[utoipa::path(get, path = "/", responses(
+        (status = OK, body = inline(Response)),
+    ), params(
+        (
+            "page" = i64, Query,
+            description = "The page number",
+            example = "1",
+        ),
+        (
+            "per_page" = i64, Query,
+            description = "The number of items per page",
+            example = "10",
+        ),
+    ))]
+    pub async fn route(
+        state: GetState,
+        permissions: GetPermissionManager,
+        user: GetUser,
+        Query(params): Query<PaginationParams>,
+    ) -> ApiResponseResult {
+        if let Err(errors) = shared::utils::validate_data(&params) {
+            return ApiResponse::new_serialized(ApiError::new_strings_value(errors))
+                .with_status(StatusCode::BAD_REQUEST)
+                .ok();
+        }
+
+        permissions.has_user_permission("saml-links.read")?;
+
+        let saml_links = UserSamlLink::filtered_by_user_uuid_with_pagination(
+            &state.database,
+            user.uuid,
+            params.page,
+            params.per_page,
+        )
+        .await?;
+
+        ApiResponse::new_serialized(Response {
+            saml_links: saml_links
+                .try_async_map(|saml_link| saml_link.into_api_object(&state.database))
+                .await?,
+        })
+        .ok()
+    }
+}
+
+pub fn router(state: &State) -> OpenApiRouter<State> {
+    OpenApiRouter::new()
+        .routes(routes!(get::route))
+        .nest("/{saml_link}", _saml_link_::router(state))
+        .with_state(state.clone())
+}
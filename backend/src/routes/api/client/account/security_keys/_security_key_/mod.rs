@@ -23,6 +23,7 @@ mod delete {
 
     #[utoipa::path(delete, path = "/", responses(
         (status = OK, body = inline(Response)),
+        (status = BAD_REQUEST, body = ApiError),
         (status = NOT_FOUND, body = ApiError),
     ), params(
         (
@@ -54,6 +55,29 @@ mod delete {
                 }
             };
 
+        if !user.has_password && security_key.passkey.is_some() {
+            let remaining_passkeys: i64 = sqlx::query_scalar(
+                r#"
+                SELECT COUNT(*)
+                FROM user_security_keys
+                WHERE user_security_keys.user_uuid = $1 AND user_security_keys.uuid != $2
+                    AND user_security_keys.passkey IS NOT NULL
+                "#,
+            )
+            .bind(user.uuid)
+            .bind(security_key.uuid)
+            .fetch_one(state.database.read())
+            .await?;
+
+            if remaining_passkeys == 0 {
+                return ApiResponse::error(
+                    "cannot delete the last passkey of an account without a password",
+                )
+                .with_status(StatusCode::BAD_REQUEST)
+                .ok();
+            }
+        }
+
         security_key.delete(&state, ()).await?;
 
         if security_key.registration.is_none() {
@@ -0,0 +1,45 @@
+use super::State;
+use utoipa_axum::{router::OpenApiRouter, routes};
+
+mod get {
+    use axum::http::StatusCode;
+    use serde::Serialize;
+    use shared::{
+        ApiError, GetState,
+        models::user::GetUser,
+        response::{ApiResponse, ApiResponseResult},
+    };
+    use utoipa::ToSchema;
+    use webauthn_rs::prelude::RequestChallengeResponse;
+
+    #[derive(ToSchema, Serialize)]
+    struct Response {
+        uuid: uuid::Uuid,
+        #[schema(value_type = serde_json::Value)]
+        options: RequestChallengeResponse,
+    }
+
+    #[utoipa::path(get, path = "/", responses(
+        (status = OK, body = inline(Response)),
+        (status = BAD_REQUEST, body = ApiError),
+    ))]
+    pub async fn route(state: GetState, user: GetUser) -> ApiResponseResult {
+        if !user.has_passkey(&state.database).await? {
+            return ApiResponse::error("account does not have a passkey")
+                .with_status(StatusCode::BAD_REQUEST)
+                .ok();
+        }
+
+        let (uuid, options) = user
+            .start_passkey_confirmation(&state.database, &state.settings)
+            .await?;
+
+        ApiResponse::new_serialized(Response { uuid, options }).ok()
+    }
+}
+
+pub fn router(state: &State) -> OpenApiRouter<State> {
+    OpenApiRouter::new()
+        .routes(routes!(get::route))
+        .with_state(state.clone())
+}
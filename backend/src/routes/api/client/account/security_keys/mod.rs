@@ -2,6 +2,7 @@ use super::State;
 use utoipa_axum::{router::OpenApiRouter, routes};
 
 mod _security_key_;
+mod confirm;
 
 mod get {
     use axum::{extract::Query, http::StatusCode};
@@ -93,7 +94,6 @@ mod post {
     };
     use utoipa::ToSchema;
     use validator::Validate;
-    use webauthn_rs::prelude::CreationChallengeResponse;
 
     #[derive(ToSchema, Validate, Deserialize)]
     pub struct Payload {
@@ -105,8 +105,7 @@ mod post {
     #[derive(ToSchema, Serialize)]
     struct Response {
         security_key: shared::models::user_security_key::ApiUserSecurityKey,
-        #[schema(value_type = serde_json::Value)]
-        options: CreationChallengeResponse,
+        options: serde_json::Value,
     }
 
     #[utoipa::path(post, path = "/", responses(
@@ -151,6 +150,15 @@ mod post {
             ),
         )?;
 
+        // ask for a discoverable credential so the key can be used for usernameless logins
+        let mut options = serde_json::to_value(options)?;
+        if let Some(authenticator_selection) = options
+            .pointer_mut("/publicKey/authenticatorSelection")
+            .and_then(|selection| selection.as_object_mut())
+        {
+            authenticator_selection.insert("residentKey".into(), "preferred".into());
+        }
+
         let security_key =
             match UserSecurityKey::create(&state.database, user.uuid, &data.name, registration)
                 .await
@@ -182,6 +190,7 @@ pub fn router(state: &State) -> OpenApiRouter<State> {
     OpenApiRouter::new()
         .routes(routes!(get::route))
         .routes(routes!(post::route))
+        .nest("/confirm", confirm::router(state))
         .nest("/{security_key}", _security_key_::router(state))
         .with_state(state.clone())
 }
@@ -130,9 +130,10 @@ mod post {
                 .ok();
         }
 
-        if !user
-            .validate_password(&state.database, &data.password)
-            .await?
+        if user.has_password
+            && !user
+                .validate_password(&state.database, &data.password)
+                .await?
         {
             return ApiResponse::error("invalid password")
                 .with_status(StatusCode::UNAUTHORIZED)
@@ -227,9 +228,10 @@ mod delete {
                 .ok();
         }
 
-        if !user
-            .validate_password(&state.database, &data.password)
-            .await?
+        if user.has_password
+            && !user
+                .validate_password(&state.database, &data.password)
+                .await?
         {
             return ApiResponse::error("invalid password")
                 .with_status(StatusCode::UNAUTHORIZED)
@@ -7,8 +7,9 @@ use axum::{
 };
 use shared::{
     models::{
+        admin_activity::AdminActivity,
         user::{AuthMethod, PermissionManager, User},
-        user_activity::UserActivityLogger,
+        user_activity::{UserActivity, UserActivityLogger},
     },
     response::ApiResponse,
 };
@@ -20,6 +21,36 @@ mod account;
 mod permissions;
 pub mod servers;
 
+/// Blocks users whose role requires a passkey until they registered one, only the paths needed to
+/// register one stay reachable.
+async fn require_passkey(
+    database: &shared::database::Database,
+    user: &User,
+    matched_path: &MatchedPath,
+) -> Option<Response> {
+    const IGNORED_PASSKEY_PATHS: &[&str] = &[
+        "/api/client/account",
+        "/api/client/account/security-keys",
+        "/api/client/account/security-keys/{security_key}",
+        "/api/client/account/security-keys/{security_key}/challenge",
+        "/api/client/account/logout",
+    ];
+
+    if IGNORED_PASSKEY_PATHS.contains(&matched_path.as_str()) || !user.require_passkey() {
+        return None;
+    }
+
+    match user.has_passkey(database).await {
+        Ok(true) => None,
+        Ok(false) => Some(
+            ApiResponse::error("passkey required")
+                .with_status(StatusCode::FORBIDDEN)
+                .into_response(),
+        ),
+        Err(err) => Some(ApiResponse::from(err).into_response()),
+    }
+}
+
 pub async fn auth(
     state: GetState,
     ip: shared::GetIp,
@@ -41,6 +72,19 @@ pub async fn auth(
         "/api/client/account/two-factor",
         "/api/client/account/logout",
     ];
+    const BLOCKED_IMPERSONATION_PATHS: &[&str] = &[
+        "/api/client/account/email",
+        "/api/client/account/password",
+        "/api/client/account/two-factor",
+        "/api/client/account/security-keys",
+        "/api/client/account/api-keys",
+        "/api/client/account/ssh-keys",
+        "/api/client/account/oauth-links",
+        "/api/client/account/saml-links",
+        "/api/client/account/sessions",
+    ];
+
+    let mut impersonation = None;
 
     if let Some(session_id) = cookies.get("session") {
         if session_id.value().len() != 81 {
@@ -120,11 +164,32 @@ pub async fn auth(
                 .into_response());
         }
 
+        if let Some(response) = require_passkey(&state.database, &user, &matched_path).await {
+            return Ok(response);
+        }
+
+        if session.impersonator_uuid.is_some()
+            && BLOCKED_IMPERSONATION_PATHS
+                .iter()
+                .any(|path| matched_path.as_str().starts_with(path))
+        {
+            return Ok(ApiResponse::error(
+                "this action is not available while impersonating a user",
+            )
+            .with_status(StatusCode::FORBIDDEN)
+            .into_response());
+        }
+
+        impersonation = session
+            .impersonator_uuid
+            .map(|impersonator_uuid| (impersonator_uuid, user.uuid));
+
         req.extensions_mut().insert(PermissionManager::new(&user));
         req.extensions_mut().insert(UserActivityLogger {
             state: Arc::clone(&state),
             user_uuid: user.uuid,
             api_key_uuid: None,
+            impersonator_uuid: session.impersonator_uuid,
             ip: ip.0,
         });
         req.extensions_mut().insert(user);
@@ -203,12 +268,17 @@ pub async fn auth(
                 .into_response());
         }
 
+        if let Some(response) = require_passkey(&state.database, &user, &matched_path).await {
+            return Ok(response);
+        }
+
         req.extensions_mut()
             .insert(PermissionManager::new(&user).add_api_key(&api_key));
         req.extensions_mut().insert(UserActivityLogger {
             state: Arc::clone(&state),
             user_uuid: user.uuid,
             api_key_uuid: Some(api_key.uuid),
+            impersonator_uuid: None,
             ip: ip.0,
         });
         req.extensions_mut().insert(user);
@@ -219,7 +289,51 @@ pub async fn auth(
             .into_response());
     }
 
-    Ok(next.run(req).await)
+    let method = req.method().clone();
+    let response = next.run(req).await;
+
+    // every request made while impersonating is audited, including reads
+    if let Some((impersonator_uuid, user_uuid)) = impersonation {
+        let data = serde_json::json!({
+            "method": method.as_str(),
+            "path": matched_path.as_str(),
+            "status": response.status().as_u16(),
+        });
+
+        if let Err(err) = UserActivity::log(
+            &state.database,
+            user_uuid,
+            None,
+            "impersonation:request",
+            Some(ip.0.into()),
+            serde_json::json!({
+                "impersonator": impersonator_uuid,
+                "request": data,
+            }),
+        )
+        .await
+        {
+            tracing::warn!(user = %user_uuid, "failed to log user activity: {:?}", err);
+        }
+
+        if let Err(err) = AdminActivity::log(
+            &state.database,
+            Some(impersonator_uuid),
+            None,
+            "user:impersonate.request",
+            Some(ip.0.into()),
+            serde_json::json!({
+                "user": user_uuid,
+                "request": data,
+            }),
+        )
+        .await
+        {
+            tracing::warn!(user = %impersonator_uuid, "failed to log admin activity: {:?}", err);
+        }
+    }
+
+    Ok(response)
 }
 
 pub fn router(state: &State) -> OpenApiRouter<State> {
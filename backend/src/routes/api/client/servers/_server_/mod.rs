@@ -45,8 +45,8 @@ pub async fn auth(
 ) -> Result<Response, StatusCode> {
     let server = Server::by_user_identifier(&state.database, &user, &server[0]).await;
     let server = match server {
-        Ok(Some(server)) => server,
-        Ok(None) => {
+        Ok(Some(server)) if permissions.can_access_server(server.uuid) => server,
+        Ok(_) => {
             return Ok(ApiResponse::error("server not found")
                 .with_status(StatusCode::NOT_FOUND)
                 .into_response());
@@ -32,6 +32,8 @@ mod get {
     ) -> ApiResponseResult {
         permissions.has_server_permission("startup.read")?;
 
+        let reveal = permissions.has_server_permission("startup.reveal").is_ok();
+
         let variables = ServerVariable::all_by_server_uuid_egg_uuid(
             &state.database,
             server.uuid,
@@ -39,12 +41,18 @@ mod get {
         )
         .await?;
 
+        let mut api_variables = Vec::new();
+        api_variables.reserve_exact(variables.len());
+
+        for variable in variables
+            .into_iter()
+            .filter(|variable| variable.variable.user_viewable)
+        {
+            api_variables.push(variable.into_api_object(&state.database, reveal).await?);
+        }
+
         ApiResponse::new_serialized(Response {
-            variables: variables
-                .into_iter()
-                .filter(|variable| variable.variable.user_viewable)
-                .map(|variable| variable.into_api_object())
-                .collect(),
+            variables: api_variables,
         })
         .ok()
     }
@@ -111,12 +119,13 @@ mod put {
 
         permissions.has_server_permission("startup.update")?;
 
-        let variables = ServerVariable::all_by_server_uuid_egg_uuid(
+        let mut variables = ServerVariable::all_by_server_uuid_egg_uuid(
             &state.database,
             server.uuid,
             server.egg.uuid,
         )
         .await?;
+        ServerVariable::decrypt_all(&state.database, &mut variables).await?;
 
         let mut validator_variables = HashMap::new();
         validator_variables.reserve(variables.len());
@@ -155,29 +164,32 @@ mod put {
                 .ok();
         }
 
+        let mut logged_variables = Vec::new();
+        logged_variables.reserve_exact(data.variables.len());
+
         for data_variable in &data.variables {
-            let variable_uuid = match variables
+            let variable = match variables
                 .iter()
                 .find(|v| v.variable.env_variable == data_variable.env_variable)
             {
-                Some(variable) if variable.variable.user_editable => variable.variable.uuid,
+                Some(variable) if variable.variable.user_editable => &variable.variable,
                 _ => continue,
             };
 
-            ServerVariable::create(
-                &state.database,
-                server.uuid,
-                variable_uuid,
-                &data_variable.value,
-            )
-            .await?;
+            ServerVariable::create(&state.database, server.uuid, variable, &data_variable.value)
+                .await?;
+
+            logged_variables.push(serde_json::json!({
+                "env_variable": data_variable.env_variable,
+                "value": if variable.secret { "" } else { data_variable.value.as_str() },
+            }));
         }
 
         activity_logger
             .log(
                 "server:startup.variables",
                 serde_json::json!({
-                    "variables": data.variables
+                    "variables": logged_variables
                 }),
             )
             .await;
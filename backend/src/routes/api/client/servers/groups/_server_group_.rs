@@ -69,10 +69,13 @@ mod get {
                 }
             };
 
+        let mut server_order = server_group.server_order;
+        server_order.retain(|server_uuid| permissions.can_access_server(*server_uuid));
+
         let servers = Server::by_user_uuid_server_order_with_pagination(
             &state.database,
             user.uuid,
-            &server_group.server_order,
+            &server_order,
             params.page,
             params.per_page,
             params.search.as_deref(),
@@ -157,6 +160,15 @@ mod patch {
             server_group.name = name;
         }
         if let Some(server_order) = data.server_order {
+            if !server_order
+                .iter()
+                .all(|server_uuid| permissions.can_access_server(*server_uuid))
+            {
+                return ApiResponse::error("server_order: servers outside of api key scope")
+                    .with_status(StatusCode::BAD_REQUEST)
+                    .ok();
+            }
+
             server_group.server_order = server_order;
         }
 
@@ -92,6 +92,7 @@ mod get {
             Server::by_not_user_uuid_with_pagination(
                 &state.database,
                 user.uuid,
+                permissions.server_scope(),
                 params.page,
                 params.per_page,
                 params.search.as_deref(),
@@ -101,6 +102,7 @@ mod get {
             Server::by_user_uuid_with_pagination(
                 &state.database,
                 user.uuid,
+                permissions.server_scope(),
                 params.page,
                 params.per_page,
                 params.search.as_deref(),
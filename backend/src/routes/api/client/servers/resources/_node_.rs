@@ -70,6 +70,7 @@ mod get {
         if let Some(server_uuids) = server_uuids {
             resources.retain(|uuid, _| server_uuids.contains(uuid));
         }
+        resources.retain(|uuid, _| permissions.can_access_server(*uuid));
 
         ApiResponse::new_serialized(Response { resources }).ok()
     }
@@ -52,14 +52,15 @@ mod put {
                 .ok();
         }
 
-        let variables = ServerVariable::all_by_server_uuid_egg_uuid(
+        let mut variables = ServerVariable::all_by_server_uuid_egg_uuid(
             &state.database,
             server.uuid,
             server.egg.uuid,
         )
         .await?;
+        ServerVariable::decrypt_all(&state.database, &mut variables).await?;
 
-        let variable_uuid = if let Some(variable) = variables
+        let variable = if let Some(variable) = variables
             .iter()
             .find(|variable| variable.variable.env_variable == data.env_variable)
         {
@@ -69,7 +70,7 @@ mod put {
                     .ok();
             }
 
-            variable.variable.uuid
+            &variable.variable
         } else {
             return ApiResponse::error("variable not found")
                 .with_status(StatusCode::NOT_FOUND)
@@ -108,7 +109,7 @@ mod put {
                 .ok();
         }
 
-        ServerVariable::create(&state.database, server.uuid, variable_uuid, &data.value).await?;
+        ServerVariable::create(&state.database, server.uuid, variable, &data.value).await?;
 
         if let Err(err) = ServerActivity::log_remote(
             &state.database,
@@ -121,7 +122,7 @@ mod put {
                 "variables": [
                     {
                         "env_variable": data.env_variable,
-                        "value": data.value,
+                        "value": if variable.secret { "" } else { data.value.as_str() },
                     }
                 ]
             }),
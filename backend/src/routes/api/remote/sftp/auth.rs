@@ -62,14 +62,38 @@ mod post {
 
         let user = match data.r#type {
             AuthenticationType::Password => {
-                match User::by_username_password(&state.database, user, &data.password).await? {
-                    Some(user) => user,
-                    None => {
-                        return ApiResponse::error("user not found")
-                            .with_status(StatusCode::EXPECTATION_FAILED)
-                            .ok();
-                    }
+                let account = User::by_username(&state.database, user).await?;
+
+                if let Some(account) = &account
+                    && account.is_locked()
+                {
+                    return ApiResponse::error("account is temporarily locked")
+                        .with_status(StatusCode::EXPECTATION_FAILED)
+                        .ok();
                 }
+
+                let user = match User::by_username_password(&state.database, user, &data.password)
+                    .await?
+                {
+                    Some(user) => user,
+                    None => match state.ldap.login(user, &data.password).await? {
+                        Some(user) => user,
+                        None => {
+                            if let Some(account) = account {
+                                account.record_failed_login(&state, None).await?;
+                            }
+
+                            return ApiResponse::error("user not found")
+                                .with_status(StatusCode::EXPECTATION_FAILED)
+                                .ok();
+                        }
+                    },
+                };
+
+                // a successful login clears earlier failed attempts, like it does on the web
+                user.reset_failed_logins(&state.database).await?;
+
+                user
             }
             AuthenticationType::PublicKey => {
                 let public_key = match russh::keys::PublicKey::from_openssh(&data.password) {
@@ -91,6 +115,13 @@ mod post {
                 }
             }
         };
+
+        if !user.active {
+            return ApiResponse::error("user has been deactivated")
+                .with_status(StatusCode::EXPECTATION_FAILED)
+                .ok();
+        }
+
         let server = match Server::by_user_identifier(&state.database, &user, server).await? {
             Some(server) => server,
             None => {
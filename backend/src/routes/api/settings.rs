@@ -15,6 +15,7 @@ mod get {
         name: &'a str,
         language: &'a str,
         registration_enabled: bool,
+        email_verification: shared::settings::EmailVerificationMode,
     }
 
     #[derive(ToSchema, Serialize)]
@@ -28,6 +29,16 @@ mod get {
         allow_editing_startup_command: bool,
     }
 
+    #[derive(ToSchema, Serialize)]
+    struct ResponsePasswordPolicy {
+        min_length: u16,
+        require_uppercase: bool,
+        require_lowercase: bool,
+        require_number: bool,
+        require_symbol: bool,
+        forbid_user_details: bool,
+    }
+
     #[derive(ToSchema, Serialize)]
     struct Response<'a> {
         version: &'a str,
@@ -40,6 +51,8 @@ mod get {
         app: ResponseApp<'a>,
         #[schema(inline)]
         server: ResponseServer,
+        #[schema(inline)]
+        password_policy: ResponsePasswordPolicy,
     }
 
     #[utoipa::path(get, path = "/", responses(
@@ -58,6 +71,7 @@ mod get {
                 name: &settings.app.name,
                 language: &settings.app.language,
                 registration_enabled: settings.app.registration_enabled,
+                email_verification: settings.app.email_verification,
             },
             server: ResponseServer {
                 max_file_manager_view_size: settings.server.max_file_manager_view_size,
@@ -72,6 +86,14 @@ mod get {
                     .allow_overwriting_custom_docker_image,
                 allow_editing_startup_command: settings.server.allow_editing_startup_command,
             },
+            password_policy: ResponsePasswordPolicy {
+                min_length: settings.security.password_min_length,
+                require_uppercase: settings.security.password_require_uppercase,
+                require_lowercase: settings.security.password_require_lowercase,
+                require_number: settings.security.password_require_number,
+                require_symbol: settings.security.password_require_symbol,
+                forbid_user_details: settings.security.password_forbid_user_details,
+            },
         })
         .ok()
     }
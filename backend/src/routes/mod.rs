@@ -1,10 +1,12 @@
 use utoipa_axum::router::OpenApiRouter;
 
 pub mod api;
+mod scim;
 pub use shared::{GetState, State};
 
 pub fn router(state: &State) -> OpenApiRouter<State> {
     OpenApiRouter::new()
         .nest("/api", api::router(state))
+        .nest("/scim/v2", scim::router(state))
         .with_state(state.clone())
 }
@@ -0,0 +1,359 @@
+use super::State;
+use axum::{
+    extract::{Path, Request},
+    http::StatusCode,
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use shared::{
+    GetState,
+    models::{ByUuid, role::Role},
+    response::ApiResponse,
+};
+use utoipa_axum::{router::OpenApiRouter, routes};
+
+pub type GetRole = shared::extract::ConsumingExtension<Role>;
+
+pub async fn auth(
+    state: GetState,
+    Path(group): Path<Vec<String>>,
+    mut req: Request,
+    next: Next,
+) -> Result<Response, StatusCode> {
+    let role = match uuid::Uuid::parse_str(&group[0]) {
+        Ok(role) => Role::by_uuid_optional(&state.database, role).await,
+        Err(_) => Ok(None),
+    };
+    let role = match role {
+        Ok(Some(role)) => role,
+        Ok(None) => {
+            return Ok(
+                shared::scim::error(StatusCode::NOT_FOUND, None, "group not found").into_response(),
+            );
+        }
+        Err(err) => return Ok(ApiResponse::from(err).into_response()),
+    };
+
+    req.extensions_mut().insert(role);
+
+    Ok(next.run(req).await)
+}
+
+mod get {
+    use super::GetRole;
+    use shared::{
+        GetState,
+        models::user::User,
+        response::ApiResponseResult,
+        scim::{ScimError, ScimGroup},
+    };
+
+    #[utoipa::path(get, path = "/", responses(
+        (status = OK, body = ScimGroup),
+        (status = NOT_FOUND, body = ScimError),
+    ), params(
+        (
+            "group" = uuid::Uuid,
+            description = "The group (role) ID",
+            example = "123e4567-e89b-12d3-a456-426614174000",
+        ),
+    ))]
+    pub async fn route(state: GetState, role: GetRole) -> ApiResponseResult {
+        let members = User::all_by_role_uuid(&state.database, role.0.uuid).await?;
+        let settings = state.settings.get().await?;
+
+        shared::scim::response(role.0.into_scim_object(&settings.app.url, Some(members))).ok()
+    }
+}
+
+mod put {
+    use super::GetRole;
+    use axum::http::StatusCode;
+    use shared::{
+        GetState,
+        models::user::User,
+        response::ApiResponseResult,
+        scim::{ScimError, ScimGroup, ScimGroupPayload, ScimPayload},
+    };
+
+    #[utoipa::path(put, path = "/", responses(
+        (status = OK, body = ScimGroup),
+        (status = BAD_REQUEST, body = ScimError),
+        (status = NOT_FOUND, body = ScimError),
+        (status = CONFLICT, body = ScimError),
+    ), params(
+        (
+            "group" = uuid::Uuid,
+            description = "The group (role) ID",
+            example = "123e4567-e89b-12d3-a456-426614174000",
+        ),
+    ), request_body = ScimGroupPayload)]
+    pub async fn route(
+        state: GetState,
+        ip: shared::GetIp,
+        role: GetRole,
+        ScimPayload(data): ScimPayload<ScimGroupPayload>,
+    ) -> ApiResponseResult {
+        let mut role = role.0;
+
+        super::super::check_display_name(&data.display_name)?;
+        let member_uuids = super::super::member_uuids(&data.members)?;
+
+        if role.name != data.display_name {
+            match role.update_name(&state.database, &data.display_name).await {
+                Ok(_) => {}
+                Err(err) if err.is_unique_violation() => {
+                    return shared::scim::error(
+                        StatusCode::CONFLICT,
+                        Some("uniqueness"),
+                        "role with name already exists",
+                    )
+                    .ok();
+                }
+                Err(err) => return Err(err.into()),
+            }
+        }
+
+        role.remove_users(&state.database, None).await?;
+        role.add_users(&state.database, &member_uuids).await?;
+
+        crate::routes::scim::log_activity(
+            &state,
+            ip,
+            "scim:update-group",
+            serde_json::json!({
+                "uuid": role.uuid,
+                "name": role.name,
+                "members": member_uuids,
+            }),
+        )
+        .await;
+
+        let members = User::all_by_role_uuid(&state.database, role.uuid).await?;
+        let settings = state.settings.get().await?;
+
+        shared::scim::response(role.into_scim_object(&settings.app.url, Some(members))).ok()
+    }
+}
+
+mod patch {
+    use super::GetRole;
+    use axum::{body::Body, http::StatusCode};
+    use shared::{
+        GetState,
+        response::{ApiResponse, ApiResponseResult},
+        scim::{PatchOp, PatchPath, PatchRequest, ScimError, ScimPayload, ScimReference},
+    };
+
+    #[utoipa::path(patch, path = "/", responses(
+        (status = NO_CONTENT),
+        (status = BAD_REQUEST, body = ScimError),
+        (status = NOT_FOUND, body = ScimError),
+        (status = CONFLICT, body = ScimError),
+    ), params(
+        (
+            "group" = uuid::Uuid,
+            description = "The group (role) ID",
+            example = "123e4567-e89b-12d3-a456-426614174000",
+        ),
+    ), request_body = PatchRequest)]
+    pub async fn route(
+        state: GetState,
+        ip: shared::GetIp,
+        role: GetRole,
+        ScimPayload(data): ScimPayload<PatchRequest>,
+    ) -> ApiResponseResult {
+        let mut role = role.0;
+
+        let invalid_value = |attribute: &str| {
+            shared::scim::error(
+                StatusCode::BAD_REQUEST,
+                Some("invalidValue"),
+                &format!("invalid value for `{attribute}`"),
+            )
+        };
+        let members = |value: &serde_json::Value| {
+            let members: Vec<ScimReference> = match value {
+                serde_json::Value::Array(_) => serde_json::from_value(value.clone()),
+                _ => serde_json::from_value(serde_json::Value::Array(vec![value.clone()])),
+            }
+            .map_err(|_| invalid_value("members"))?;
+
+            super::super::member_uuids(&members)
+        };
+
+        // operations without a path carry the attributes as an object value
+        let mut changes = Vec::new();
+        for operation in &data.operations {
+            match &operation.path {
+                Some(path) => {
+                    let path: PatchPath = path.parse().map_err(|err: anyhow::Error| {
+                        shared::scim::error(
+                            StatusCode::BAD_REQUEST,
+                            Some("invalidPath"),
+                            &err.to_string(),
+                        )
+                    })?;
+
+                    changes.push((path, operation.op, operation.value.clone()));
+                }
+                None => {
+                    let Some(values) = operation.value.as_object() else {
+                        return shared::scim::error(
+                            StatusCode::BAD_REQUEST,
+                            Some("invalidValue"),
+                            "operations without a path require an object value",
+                        )
+                        .ok();
+                    };
+
+                    for (path, value) in values {
+                        let path: PatchPath = path.parse().map_err(|err: anyhow::Error| {
+                            shared::scim::error(
+                                StatusCode::BAD_REQUEST,
+                                Some("invalidPath"),
+                                &err.to_string(),
+                            )
+                        })?;
+
+                        changes.push((path, operation.op, value.clone()));
+                    }
+                }
+            }
+        }
+
+        let mut added = Vec::new();
+        let mut removed = Vec::new();
+        let mut removed_all = false;
+
+        for (path, op, value) in changes {
+            match path.attribute.to_ascii_lowercase().as_str() {
+                "displayname" if op != PatchOp::Remove => {
+                    let display_name =
+                        value.as_str().ok_or_else(|| invalid_value("displayName"))?;
+                    super::super::check_display_name(display_name)?;
+
+                    match role.update_name(&state.database, display_name).await {
+                        Ok(_) => {}
+                        Err(err) if err.is_unique_violation() => {
+                            return shared::scim::error(
+                                StatusCode::CONFLICT,
+                                Some("uniqueness"),
+                                "role with name already exists",
+                            )
+                            .ok();
+                        }
+                        Err(err) => return Err(err.into()),
+                    }
+                }
+                "members" => match op {
+                    PatchOp::Add => {
+                        let member_uuids = members(&value)?;
+                        role.add_users(&state.database, &member_uuids).await?;
+
+                        added.extend(member_uuids);
+                    }
+                    PatchOp::Replace => {
+                        let member_uuids = members(&value)?;
+                        role.remove_users(&state.database, None).await?;
+                        role.add_users(&state.database, &member_uuids).await?;
+
+                        removed_all = true;
+                        added.extend(member_uuids);
+                    }
+                    PatchOp::Remove => {
+                        // members are removed either through a `members[value eq "..."]` path
+                        // or by listing them in the value, removing everyone otherwise
+                        let member_uuids = match &path.filter {
+                            Some(filter) => match filter.eq_value("value") {
+                                Some(member) => Some(members(&serde_json::json!({
+                                    "value": member,
+                                }))?),
+                                None => return Err(invalid_value("members")),
+                            },
+                            None if value.is_null() => None,
+                            None => Some(members(&value)?),
+                        };
+
+                        role.remove_users(&state.database, member_uuids.as_deref())
+                            .await?;
+
+                        match member_uuids {
+                            Some(member_uuids) => removed.extend(member_uuids),
+                            None => removed_all = true,
+                        }
+                    }
+                },
+                _ => {}
+            }
+        }
+
+        crate::routes::scim::log_activity(
+            &state,
+            ip,
+            "scim:update-group",
+            serde_json::json!({
+                "uuid": role.uuid,
+                "name": role.name,
+                "added_members": added,
+                "removed_members": removed,
+                "removed_all_members": removed_all,
+            }),
+        )
+        .await;
+
+        ApiResponse::new(Body::empty())
+            .with_status(StatusCode::NO_CONTENT)
+            .ok()
+    }
+}
+
+mod delete {
+    use super::GetRole;
+    use axum::{body::Body, http::StatusCode};
+    use shared::{
+        GetState,
+        models::DeletableModel,
+        response::{ApiResponse, ApiResponseResult},
+        scim::ScimError,
+    };
+
+    #[utoipa::path(delete, path = "/", responses(
+        (status = NO_CONTENT),
+        (status = NOT_FOUND, body = ScimError),
+    ), params(
+        (
+            "group" = uuid::Uuid,
+            description = "The group (role) ID",
+            example = "123e4567-e89b-12d3-a456-426614174000",
+        ),
+    ))]
+    pub async fn route(state: GetState, ip: shared::GetIp, role: GetRole) -> ApiResponseResult {
+        role.0.delete(&state, ()).await?;
+
+        crate::routes::scim::log_activity(
+            &state,
+            ip,
+            "scim:delete-group",
+            serde_json::json!({
+                "uuid": role.0.uuid,
+                "name": role.0.name,
+            }),
+        )
+        .await;
+
+        ApiResponse::new(Body::empty())
+            .with_status(StatusCode::NO_CONTENT)
+            .ok()
+    }
+}
+
+pub fn router(state: &State) -> OpenApiRouter<State> {
+    OpenApiRouter::new()
+        .routes(routes!(get::route))
+        .routes(routes!(put::route))
+        .routes(routes!(patch::route))
+        .routes(routes!(delete::route))
+        .route_layer(axum::middleware::from_fn_with_state(state.clone(), auth))
+        .with_state(state.clone())
+}
@@ -0,0 +1,206 @@
+use super::State;
+use axum::http::StatusCode;
+use shared::{response::ApiResponse, scim::ScimReference};
+use utoipa_axum::{router::OpenApiRouter, routes};
+
+mod _group_;
+
+/// Groups are backed by roles, so their names follow the same rules.
+fn check_display_name(display_name: &str) -> Result<(), ApiResponse> {
+    if !(3..=255).contains(&display_name.chars().count()) {
+        return Err(shared::scim::error(
+            StatusCode::BAD_REQUEST,
+            Some("invalidValue"),
+            "displayName: must be between 3 and 255 characters",
+        ));
+    }
+
+    Ok(())
+}
+
+fn member_uuids(members: &[ScimReference]) -> Result<Vec<uuid::Uuid>, ApiResponse> {
+    members
+        .iter()
+        .map(|member| {
+            uuid::Uuid::parse_str(&member.value).map_err(|_| {
+                shared::scim::error(
+                    StatusCode::BAD_REQUEST,
+                    Some("invalidValue"),
+                    &format!("invalid member `{}`", member.value),
+                )
+            })
+        })
+        .collect()
+}
+
+mod get {
+    use axum::{extract::Query, http::StatusCode};
+    use shared::{
+        GetState,
+        models::{
+            role::{Role, SCIM_FILTER_ATTRIBUTES},
+            user::User,
+        },
+        response::ApiResponseResult,
+        scim::{Filter, ListParams, ListResponse, ScimError, ScimGroup},
+    };
+
+    #[utoipa::path(get, path = "/", responses(
+        (status = OK, body = ListResponse<ScimGroup>),
+        (status = BAD_REQUEST, body = ScimError),
+    ), params(
+        (
+            "filter" = Option<String>, Query,
+            description = "A SCIM filter, comparisons can be joined with `and`",
+            example = "displayName eq \"Staff\"",
+        ),
+        (
+            "startIndex" = Option<i64>, Query,
+            description = "The 1-based index of the first result",
+            example = "1",
+        ),
+        (
+            "count" = Option<i64>, Query,
+            description = "The maximum number of results",
+            example = "100",
+        ),
+        (
+            "excludedAttributes" = Option<String>, Query,
+            description = "Comma separated attributes to leave out, only `members` is supported",
+            example = "members",
+        ),
+    ))]
+    pub async fn route(state: GetState, Query(params): Query<ListParams>) -> ApiResponseResult {
+        let filter = match params.filter.as_deref().map(str::parse::<Filter>) {
+            Some(Ok(filter)) => Some(filter),
+            Some(Err(err)) => {
+                return shared::scim::error(
+                    StatusCode::BAD_REQUEST,
+                    Some("invalidFilter"),
+                    &err.to_string(),
+                )
+                .ok();
+            }
+            None => None,
+        };
+
+        if let Some(filter) = &filter
+            && let Err(err) = filter.to_sql(SCIM_FILTER_ATTRIBUTES, 1)
+        {
+            return shared::scim::error(
+                StatusCode::BAD_REQUEST,
+                Some("invalidFilter"),
+                &err.to_string(),
+            )
+            .ok();
+        }
+
+        let (total, roles) = Role::all_with_scim_filter(
+            &state.database,
+            filter.as_ref(),
+            params.start_index(),
+            params.count(),
+        )
+        .await?;
+
+        let base_url = state.settings.get().await?.app.url.clone();
+        let mut groups = Vec::with_capacity(roles.len());
+
+        for role in roles {
+            let members = if params.is_excluded("members") {
+                None
+            } else {
+                Some(User::all_by_role_uuid(&state.database, role.uuid).await?)
+            };
+
+            groups.push(role.into_scim_object(&base_url, members));
+        }
+
+        shared::scim::response(ListResponse::new(total, params.start_index(), groups)).ok()
+    }
+}
+
+mod post {
+    use axum::http::StatusCode;
+    use shared::{
+        GetState,
+        models::{role::Role, user::User},
+        response::ApiResponseResult,
+        scim::{ScimError, ScimGroup, ScimGroupPayload, ScimPayload},
+    };
+
+    #[utoipa::path(post, path = "/", responses(
+        (status = CREATED, body = ScimGroup),
+        (status = BAD_REQUEST, body = ScimError),
+        (status = CONFLICT, body = ScimError),
+    ), request_body = ScimGroupPayload)]
+    pub async fn route(
+        state: GetState,
+        ip: shared::GetIp,
+        ScimPayload(data): ScimPayload<ScimGroupPayload>,
+    ) -> ApiResponseResult {
+        super::check_display_name(&data.display_name)?;
+        let member_uuids = super::member_uuids(&data.members)?;
+
+        let role = match Role::create(
+            &state.database,
+            &data.display_name,
+            None,
+            false,
+            false,
+            &[],
+            &[],
+        )
+        .await
+        {
+            Ok(role) => role,
+            Err(err) if err.is_unique_violation() => {
+                return shared::scim::error(
+                    StatusCode::CONFLICT,
+                    Some("uniqueness"),
+                    "role with name already exists",
+                )
+                .ok();
+            }
+            Err(err) => {
+                tracing::error!("failed to create scim group: {:?}", err);
+
+                return shared::scim::error(
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    None,
+                    "failed to create group",
+                )
+                .ok();
+            }
+        };
+
+        role.add_users(&state.database, &member_uuids).await?;
+
+        crate::routes::scim::log_activity(
+            &state,
+            ip,
+            "scim:create-group",
+            serde_json::json!({
+                "uuid": role.uuid,
+                "name": role.name,
+                "members": member_uuids,
+            }),
+        )
+        .await;
+
+        let members = User::all_by_role_uuid(&state.database, role.uuid).await?;
+        let settings = state.settings.get().await?;
+
+        shared::scim::response(role.into_scim_object(&settings.app.url, Some(members)))
+            .with_status(StatusCode::CREATED)
+            .ok()
+    }
+}
+
+pub fn router(state: &State) -> OpenApiRouter<State> {
+    OpenApiRouter::new()
+        .routes(routes!(get::route))
+        .routes(routes!(post::route))
+        .nest("/{group}", _group_::router(state))
+        .with_state(state.clone())
+}
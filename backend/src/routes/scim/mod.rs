@@ -0,0 +1,74 @@
+use super::{GetState, State};
+use axum::{
+    extract::Request,
+    http::StatusCode,
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use shared::models::admin_activity::AdminActivity;
+use utoipa_axum::router::OpenApiRouter;
+
+mod groups;
+mod service_provider_config;
+mod users;
+
+pub async fn auth(state: GetState, req: Request, next: Next) -> Result<Response, StatusCode> {
+    let token = match req
+        .headers()
+        .get("Authorization")
+        .and_then(|h| h.to_str().ok())
+        .and_then(|h| h.strip_prefix("Bearer "))
+    {
+        Some(token) => token,
+        None => {
+            return Ok(shared::scim::error(
+                StatusCode::UNAUTHORIZED,
+                None,
+                "invalid authorization header",
+            )
+            .into_response());
+        }
+    };
+
+    let settings = match state.settings.get().await {
+        Ok(settings) => settings,
+        Err(err) => return Ok(shared::response::ApiResponse::from(err).into_response()),
+    };
+
+    if !settings.scim.enabled {
+        return Ok(
+            shared::scim::error(StatusCode::NOT_FOUND, None, "scim is disabled").into_response(),
+        );
+    }
+
+    if !shared::scim::verify_token(&settings.scim, token.trim()) {
+        return Ok(
+            shared::scim::error(StatusCode::UNAUTHORIZED, None, "invalid token").into_response(),
+        );
+    }
+
+    drop(settings);
+
+    Ok(next.run(req).await)
+}
+
+/// Logs a change made by the identity provider as admin activity without a user.
+async fn log_activity(state: &State, ip: shared::GetIp, event: &str, data: serde_json::Value) {
+    if let Err(err) =
+        AdminActivity::log(&state.database, None, None, event, Some(ip.0.into()), data).await
+    {
+        tracing::warn!("failed to log scim activity: {:?}", err);
+    }
+}
+
+pub fn router(state: &State) -> OpenApiRouter<State> {
+    OpenApiRouter::new()
+        .nest(
+            "/ServiceProviderConfig",
+            service_provider_config::router(state),
+        )
+        .nest("/Users", users::router(state))
+        .nest("/Groups", groups::router(state))
+        .route_layer(axum::middleware::from_fn_with_state(state.clone(), auth))
+        .with_state(state.clone())
+}
@@ -0,0 +1,84 @@
+use super::State;
+use utoipa_axum::{router::OpenApiRouter, routes};
+
+mod get {
+    use serde::Serialize;
+    use shared::response::ApiResponseResult;
+    use utoipa::ToSchema;
+
+    #[derive(ToSchema, Serialize)]
+    struct Supported {
+        supported: bool,
+    }
+
+    #[derive(ToSchema, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    struct FilterSupported {
+        supported: bool,
+        max_results: i64,
+    }
+
+    #[derive(ToSchema, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    struct BulkSupported {
+        supported: bool,
+        max_operations: i64,
+        max_payload_size: i64,
+    }
+
+    #[derive(ToSchema, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    struct AuthenticationScheme {
+        r#type: &'static str,
+        name: &'static str,
+        description: &'static str,
+    }
+
+    #[derive(ToSchema, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    struct Response {
+        #[schema(value_type = Vec<String>)]
+        schemas: [&'static str; 1],
+        patch: Supported,
+        bulk: BulkSupported,
+        filter: FilterSupported,
+        change_password: Supported,
+        sort: Supported,
+        etag: Supported,
+        authentication_schemes: [AuthenticationScheme; 1],
+    }
+
+    #[utoipa::path(get, path = "/", responses(
+        (status = OK, body = inline(Response)),
+    ))]
+    pub async fn route() -> ApiResponseResult {
+        shared::scim::response(Response {
+            schemas: [shared::scim::SCHEMA_SERVICE_PROVIDER_CONFIG],
+            patch: Supported { supported: true },
+            bulk: BulkSupported {
+                supported: false,
+                max_operations: 0,
+                max_payload_size: 0,
+            },
+            filter: FilterSupported {
+                supported: true,
+                max_results: shared::scim::MAX_RESULTS,
+            },
+            change_password: Supported { supported: false },
+            sort: Supported { supported: false },
+            etag: Supported { supported: false },
+            authentication_schemes: [AuthenticationScheme {
+                r#type: "oauthbearertoken",
+                name: "Bearer Token",
+                description: "Authentication using the SCIM token generated in the admin settings",
+            }],
+        })
+        .ok()
+    }
+}
+
+pub fn router(state: &State) -> OpenApiRouter<State> {
+    OpenApiRouter::new()
+        .routes(routes!(get::route))
+        .with_state(state.clone())
+}
@@ -0,0 +1,338 @@
+use super::State;
+use axum::{
+    extract::{Path, Request},
+    http::StatusCode,
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use shared::{
+    GetState,
+    models::{ByUuid, user::User},
+    response::ApiResponse,
+    scim::{PatchOp, PatchPath},
+};
+use utoipa_axum::{router::OpenApiRouter, routes};
+
+#[derive(Clone)]
+pub struct ScimParamUser(pub User);
+
+pub type GetScimParamUser = shared::extract::ConsumingExtension<ScimParamUser>;
+
+pub async fn auth(
+    state: GetState,
+    Path(user): Path<Vec<String>>,
+    mut req: Request,
+    next: Next,
+) -> Result<Response, StatusCode> {
+    let user = match uuid::Uuid::parse_str(&user[0]) {
+        Ok(user) => User::by_uuid_optional(&state.database, user).await,
+        Err(_) => Ok(None),
+    };
+    let user = match user {
+        Ok(Some(user)) => user,
+        Ok(None) => {
+            return Ok(
+                shared::scim::error(StatusCode::NOT_FOUND, None, "user not found").into_response(),
+            );
+        }
+        Err(err) => return Ok(ApiResponse::from(err).into_response()),
+    };
+
+    req.extensions_mut().insert(ScimParamUser(user));
+
+    Ok(next.run(req).await)
+}
+
+/// Applies a single PATCH operation to the user attributes. Attributes the panel does not
+/// store, like titles or enterprise extension attributes, are ignored.
+fn apply_patch(
+    attributes: &mut super::UserAttributes,
+    path: &PatchPath,
+    op: PatchOp,
+    value: &serde_json::Value,
+) -> Result<(), ApiResponse> {
+    let invalid_value = |attribute: &str| {
+        shared::scim::error(
+            StatusCode::BAD_REQUEST,
+            Some("invalidValue"),
+            &format!("invalid value for `{attribute}`"),
+        )
+    };
+    let string_value = |attribute: &str| {
+        value
+            .as_str()
+            .map(compact_str::CompactString::from)
+            .ok_or_else(|| invalid_value(attribute))
+    };
+
+    match path.attribute.to_ascii_lowercase().as_str() {
+        "active" => {
+            if op != PatchOp::Remove {
+                attributes.active =
+                    shared::scim::value_as_bool(value).ok_or_else(|| invalid_value("active"))?;
+            }
+        }
+        "username" => {
+            if op != PatchOp::Remove {
+                attributes.username = string_value("userName")?;
+            }
+        }
+        "externalid" => {
+            if op == PatchOp::Remove || value.is_null() {
+                attributes.external_id = None;
+            } else {
+                attributes.external_id = Some(string_value("externalId")?);
+            }
+        }
+        "name" if op != PatchOp::Remove => match path.sub_attribute.as_deref() {
+            Some(sub_attribute) if sub_attribute.eq_ignore_ascii_case("givenName") => {
+                attributes.name_first = string_value("name.givenName")?;
+            }
+            Some(sub_attribute) if sub_attribute.eq_ignore_ascii_case("familyName") => {
+                attributes.name_last = string_value("name.familyName")?;
+            }
+            Some(_) => {}
+            None => {
+                let name: shared::scim::ScimName =
+                    serde_json::from_value(value.clone()).map_err(|_| invalid_value("name"))?;
+
+                if let Some(given_name) = name.given_name {
+                    attributes.name_first = given_name;
+                }
+                if let Some(family_name) = name.family_name {
+                    attributes.name_last = family_name;
+                }
+            }
+        },
+        "emails" if op != PatchOp::Remove => {
+            if path.sub_attribute.is_some() {
+                attributes.email = string_value("emails.value")?.into();
+            } else {
+                let emails: Vec<shared::scim::ScimEmail> = match value {
+                    serde_json::Value::Array(_) => serde_json::from_value(value.clone()),
+                    _ => serde_json::from_value(serde_json::Value::Array(vec![value.clone()])),
+                }
+                .map_err(|_| invalid_value("emails"))?;
+
+                if let Some(email) = shared::scim::primary_email(&emails) {
+                    attributes.email = email.to_string();
+                }
+            }
+        }
+        _ => {}
+    }
+
+    Ok(())
+}
+
+mod get {
+    use super::GetScimParamUser;
+    use shared::{
+        GetState,
+        response::ApiResponseResult,
+        scim::{ScimError, ScimUser},
+    };
+
+    #[utoipa::path(get, path = "/", responses(
+        (status = OK, body = ScimUser),
+        (status = NOT_FOUND, body = ScimError),
+    ), params(
+        (
+            "user" = uuid::Uuid,
+            description = "The user ID",
+            example = "123e4567-e89b-12d3-a456-426614174000",
+        ),
+    ))]
+    pub async fn route(state: GetState, user: GetScimParamUser) -> ApiResponseResult {
+        let settings = state.settings.get().await?;
+
+        shared::scim::response(user.0.0.into_scim_object(&settings.app.url)).ok()
+    }
+}
+
+mod put {
+    use super::{super::UserAttributes, GetScimParamUser};
+    use shared::{
+        GetState,
+        response::ApiResponseResult,
+        scim::{ScimError, ScimPayload, ScimUser, ScimUserPayload},
+    };
+
+    #[utoipa::path(put, path = "/", responses(
+        (status = OK, body = ScimUser),
+        (status = BAD_REQUEST, body = ScimError),
+        (status = NOT_FOUND, body = ScimError),
+        (status = CONFLICT, body = ScimError),
+    ), params(
+        (
+            "user" = uuid::Uuid,
+            description = "The user ID",
+            example = "123e4567-e89b-12d3-a456-426614174000",
+        ),
+    ), request_body = ScimUserPayload)]
+    pub async fn route(
+        state: GetState,
+        ip: shared::GetIp,
+        user: GetScimParamUser,
+        ScimPayload(data): ScimPayload<ScimUserPayload>,
+    ) -> ApiResponseResult {
+        let mut user = user.0.0;
+
+        let attributes = UserAttributes::from_payload(&data);
+        attributes.check()?;
+        attributes.save(&state, &mut user).await?;
+
+        if let Some(password) = &data.password {
+            user.update_password(&state.database, password).await?;
+        }
+
+        crate::routes::scim::log_activity(
+            &state,
+            ip,
+            "scim:update-user",
+            attributes.to_activity(user.uuid),
+        )
+        .await;
+
+        let settings = state.settings.get().await?;
+
+        shared::scim::response(user.into_scim_object(&settings.app.url)).ok()
+    }
+}
+
+mod patch {
+    use super::{super::UserAttributes, GetScimParamUser};
+    use axum::http::StatusCode;
+    use shared::{
+        GetState,
+        response::ApiResponseResult,
+        scim::{PatchPath, PatchRequest, ScimError, ScimPayload, ScimUser},
+    };
+
+    #[utoipa::path(patch, path = "/", responses(
+        (status = OK, body = ScimUser),
+        (status = BAD_REQUEST, body = ScimError),
+        (status = NOT_FOUND, body = ScimError),
+        (status = CONFLICT, body = ScimError),
+    ), params(
+        (
+            "user" = uuid::Uuid,
+            description = "The user ID",
+            example = "123e4567-e89b-12d3-a456-426614174000",
+        ),
+    ), request_body = PatchRequest)]
+    pub async fn route(
+        state: GetState,
+        ip: shared::GetIp,
+        user: GetScimParamUser,
+        ScimPayload(data): ScimPayload<PatchRequest>,
+    ) -> ApiResponseResult {
+        let mut user = user.0.0;
+        let mut attributes = UserAttributes::from_user(&user);
+
+        for operation in &data.operations {
+            let invalid_path = |err: anyhow::Error| {
+                shared::scim::error(
+                    StatusCode::BAD_REQUEST,
+                    Some("invalidPath"),
+                    &err.to_string(),
+                )
+            };
+
+            match &operation.path {
+                Some(path) => {
+                    let path: PatchPath = path.parse().map_err(invalid_path)?;
+
+                    super::apply_patch(&mut attributes, &path, operation.op, &operation.value)?;
+                }
+                None => {
+                    let Some(values) = operation.value.as_object() else {
+                        return shared::scim::error(
+                            StatusCode::BAD_REQUEST,
+                            Some("invalidValue"),
+                            "operations without a path require an object value",
+                        )
+                        .ok();
+                    };
+
+                    for (path, value) in values {
+                        let path: PatchPath = path.parse().map_err(invalid_path)?;
+
+                        super::apply_patch(&mut attributes, &path, operation.op, value)?;
+                    }
+                }
+            }
+        }
+
+        attributes.check()?;
+        attributes.save(&state, &mut user).await?;
+
+        crate::routes::scim::log_activity(
+            &state,
+            ip,
+            "scim:update-user",
+            attributes.to_activity(user.uuid),
+        )
+        .await;
+
+        let settings = state.settings.get().await?;
+
+        shared::scim::response(user.into_scim_object(&settings.app.url)).ok()
+    }
+}
+
+mod delete {
+    use super::GetScimParamUser;
+    use axum::{body::Body, http::StatusCode};
+    use shared::{
+        GetState,
+        response::{ApiResponse, ApiResponseResult},
+        scim::ScimError,
+    };
+
+    #[utoipa::path(delete, path = "/", responses(
+        (status = NO_CONTENT),
+        (status = NOT_FOUND, body = ScimError),
+    ), params(
+        (
+            "user" = uuid::Uuid,
+            description = "The user ID",
+            example = "123e4567-e89b-12d3-a456-426614174000",
+        ),
+    ))]
+    pub async fn route(
+        state: GetState,
+        ip: shared::GetIp,
+        user: GetScimParamUser,
+    ) -> ApiResponseResult {
+        let mut user = user.0.0;
+
+        // users are only deactivated, deleting them would take their servers with them
+        user.set_active(&state.database, false).await?;
+
+        crate::routes::scim::log_activity(
+            &state,
+            ip,
+            "scim:deactivate-user",
+            serde_json::json!({
+                "uuid": user.uuid,
+                "username": user.username,
+            }),
+        )
+        .await;
+
+        ApiResponse::new(Body::empty())
+            .with_status(StatusCode::NO_CONTENT)
+            .ok()
+    }
+}
+
+pub fn router(state: &State) -> OpenApiRouter<State> {
+    OpenApiRouter::new()
+        .routes(routes!(get::route))
+        .routes(routes!(put::route))
+        .routes(routes!(patch::route))
+        .routes(routes!(delete::route))
+        .route_layer(axum::middleware::from_fn_with_state(state.clone(), auth))
+        .with_state(state.clone())
+}
@@ -0,0 +1,313 @@
+use super::State;
+use axum::http::StatusCode;
+use shared::{
+    models::user::User,
+    prelude::SqlxErrorExt,
+    response::ApiResponse,
+    scim::{ScimName, ScimUserPayload},
+};
+use utoipa_axum::{router::OpenApiRouter, routes};
+use validator::Validate;
+
+mod _user_;
+
+/// The user attributes that can be managed through SCIM, validated the same way the admin
+/// user endpoints validate them.
+#[derive(Validate)]
+struct UserAttributes {
+    #[validate(length(max = 255))]
+    external_id: Option<compact_str::CompactString>,
+    #[validate(
+        length(min = 3, max = 15),
+        regex(path = "*shared::models::user::USERNAME_REGEX")
+    )]
+    username: compact_str::CompactString,
+    #[validate(email)]
+    email: String,
+    #[validate(length(min = 2, max = 255))]
+    name_first: compact_str::CompactString,
+    #[validate(length(min = 2, max = 255))]
+    name_last: compact_str::CompactString,
+    active: bool,
+}
+
+impl UserAttributes {
+    fn from_payload(data: &ScimUserPayload) -> Self {
+        let ScimName {
+            given_name,
+            family_name,
+            ..
+        } = &data.name;
+
+        Self {
+            external_id: data.external_id.clone().filter(|id| !id.is_empty()),
+            username: data.user_name.clone(),
+            email: shared::scim::primary_email(&data.emails)
+                .map(|email| email.to_string())
+                .unwrap_or_default(),
+            name_first: given_name.clone().unwrap_or_else(|| data.user_name.clone()),
+            name_last: family_name
+                .clone()
+                .unwrap_or_else(|| data.user_name.clone()),
+            active: data.active.unwrap_or(true),
+        }
+    }
+
+    fn from_user(user: &User) -> Self {
+        Self {
+            external_id: user.external_id.clone(),
+            username: user.username.clone(),
+            email: user.email.to_string(),
+            name_first: user.name_first.clone(),
+            name_last: user.name_last.clone(),
+            active: user.active,
+        }
+    }
+
+    fn check(&self) -> Result<(), ApiResponse> {
+        if let Err(errors) = shared::utils::validate_data(self) {
+            return Err(shared::scim::error(
+                StatusCode::BAD_REQUEST,
+                Some("invalidValue"),
+                &errors.join(", "),
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Writes the attributes to the user, activating or deactivating the account if needed.
+    async fn save(&self, state: &State, user: &mut User) -> Result<(), ApiResponse> {
+        match sqlx::query(
+            r#"
+            UPDATE users
+            SET external_id = $2, username = $3, email = $4, name_first = $5, name_last = $6
+            WHERE users.uuid = $1
+            "#,
+        )
+        .bind(user.uuid)
+        .bind(self.external_id.as_deref())
+        .bind(&self.username)
+        .bind(&self.email)
+        .bind(&self.name_first)
+        .bind(&self.name_last)
+        .execute(state.database.write())
+        .await
+        {
+            Ok(_) => {}
+            Err(err) if err.is_unique_violation() => {
+                return Err(shared::scim::error(
+                    StatusCode::CONFLICT,
+                    Some("uniqueness"),
+                    "user with email/username/external_id already exists",
+                ));
+            }
+            Err(err) => {
+                tracing::error!("failed to update scim user: {:?}", err);
+
+                return Err(shared::scim::error(
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    None,
+                    "failed to update user",
+                ));
+            }
+        }
+
+        user.external_id = self.external_id.clone();
+        user.username = self.username.clone();
+        user.email = self.email.as_str().into();
+        user.name_first = self.name_first.clone();
+        user.name_last = self.name_last.clone();
+
+        if user.active != self.active {
+            user.set_active(&state.database, self.active)
+                .await
+                .map_err(ApiResponse::from)?;
+        }
+
+        Ok(())
+    }
+
+    fn to_activity(&self, uuid: uuid::Uuid) -> serde_json::Value {
+        serde_json::json!({
+            "uuid": uuid,
+            "external_id": self.external_id,
+            "username": self.username,
+            "email": self.email,
+            "name_first": self.name_first,
+            "name_last": self.name_last,
+            "active": self.active,
+        })
+    }
+}
+
+mod get {
+    use axum::{extract::Query, http::StatusCode};
+    use shared::{
+        GetState,
+        models::user::{SCIM_FILTER_ATTRIBUTES, User},
+        response::ApiResponseResult,
+        scim::{Filter, ListParams, ListResponse, ScimError, ScimUser},
+    };
+
+    #[utoipa::path(get, path = "/", responses(
+        (status = OK, body = ListResponse<ScimUser>),
+        (status = BAD_REQUEST, body = ScimError),
+    ), params(
+        (
+            "filter" = Option<String>, Query,
+            description = "A SCIM filter, comparisons can be joined with `and`",
+            example = "userName eq \"john\"",
+        ),
+        (
+            "startIndex" = Option<i64>, Query,
+            description = "The 1-based index of the first result",
+            example = "1",
+        ),
+        (
+            "count" = Option<i64>, Query,
+            description = "The maximum number of results",
+            example = "100",
+        ),
+    ))]
+    pub async fn route(state: GetState, Query(params): Query<ListParams>) -> ApiResponseResult {
+        let filter = match params.filter.as_deref().map(str::parse::<Filter>) {
+            Some(Ok(filter)) => Some(filter),
+            Some(Err(err)) => {
+                return shared::scim::error(
+                    StatusCode::BAD_REQUEST,
+                    Some("invalidFilter"),
+                    &err.to_string(),
+                )
+                .ok();
+            }
+            None => None,
+        };
+
+        if let Some(filter) = &filter
+            && let Err(err) = filter.to_sql(SCIM_FILTER_ATTRIBUTES, 1)
+        {
+            return shared::scim::error(
+                StatusCode::BAD_REQUEST,
+                Some("invalidFilter"),
+                &err.to_string(),
+            )
+            .ok();
+        }
+
+        let (total, users) = User::all_with_scim_filter(
+            &state.database,
+            filter.as_ref(),
+            params.start_index(),
+            params.count(),
+        )
+        .await?;
+
+        let settings = state.settings.get().await?;
+
+        shared::scim::response(ListResponse::new(
+            total,
+            params.start_index(),
+            users
+                .into_iter()
+                .map(|user| user.into_scim_object(&settings.app.url))
+                .collect(),
+        ))
+        .ok()
+    }
+}
+
+mod post {
+    use super::UserAttributes;
+    use axum::http::StatusCode;
+    use rand::distr::SampleString;
+    use shared::{
+        GetState,
+        models::{ByUuid, user::User},
+        response::ApiResponseResult,
+        scim::{ScimError, ScimPayload, ScimUser, ScimUserPayload},
+    };
+
+    #[utoipa::path(post, path = "/", responses(
+        (status = CREATED, body = ScimUser),
+        (status = BAD_REQUEST, body = ScimError),
+        (status = CONFLICT, body = ScimError),
+    ), request_body = ScimUserPayload)]
+    pub async fn route(
+        state: GetState,
+        ip: shared::GetIp,
+        ScimPayload(data): ScimPayload<ScimUserPayload>,
+    ) -> ApiResponseResult {
+        let attributes = UserAttributes::from_payload(&data);
+        attributes.check()?;
+
+        let password = data
+            .password
+            .unwrap_or_else(|| rand::distr::Alphanumeric.sample_string(&mut rand::rng(), 32));
+
+        let settings = state.settings.get().await?;
+        let language = settings.app.language.clone();
+        drop(settings);
+
+        let mut user = match User::create(
+            &state.database,
+            None,
+            attributes.external_id.as_deref(),
+            &attributes.username,
+            &attributes.email,
+            &attributes.name_first,
+            &attributes.name_last,
+            &password,
+            false,
+            &language,
+        )
+        .await
+        {
+            Ok(user_uuid) => User::by_uuid(&state.database, user_uuid).await?,
+            Err(err) if err.is_unique_violation() => {
+                return shared::scim::error(
+                    StatusCode::CONFLICT,
+                    Some("uniqueness"),
+                    "user with email/username/external_id already exists",
+                )
+                .ok();
+            }
+            Err(err) => {
+                tracing::error!("failed to create scim user: {:?}", err);
+
+                return shared::scim::error(
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    None,
+                    "failed to create user",
+                )
+                .ok();
+            }
+        };
+
+        if !attributes.active {
+            user.set_active(&state.database, false).await?;
+        }
+
+        crate::routes::scim::log_activity(
+            &state,
+            ip,
+            "scim:create-user",
+            attributes.to_activity(user.uuid),
+        )
+        .await;
+
+        let settings = state.settings.get().await?;
+
+        shared::scim::response(user.into_scim_object(&settings.app.url))
+            .with_status(StatusCode::CREATED)
+            .ok()
+    }
+}
+
+pub fn router(state: &State) -> OpenApiRouter<State> {
+    OpenApiRouter::new()
+        .routes(routes!(get::route))
+        .routes(routes!(post::route))
+        .nest("/{user}", _user_::router(state))
+        .with_state(state.clone())
+}
@@ -0,0 +1,165 @@
+use clap::Args;
+use colored::Colorize;
+use std::sync::Arc;
+
+#[derive(Args)]
+pub struct DownArgs {
+    #[arg(
+        long = "to",
+        help = "the name or id of the migration to revert to, this migration itself stays applied"
+    )]
+    to: String,
+    #[arg(
+        long = "live",
+        help = "whether to use the on-disk migrations instead of the embedded migrations",
+        default_value = "false"
+    )]
+    live: bool,
+    #[arg(
+        long = "dry-run",
+        help = "whether to only print the sql that would run instead of running it",
+        default_value = "false"
+    )]
+    dry_run: bool,
+    #[arg(
+        long = "yes",
+        help = "whether to revert migrations with destructive statements without asking for confirmation",
+        default_value = "false"
+    )]
+    yes: bool,
+}
+
+pub async fn run(env: Option<Arc<shared::env::Env>>, args: DownArgs) -> Result<(), anyhow::Error> {
+    let env = match env {
+        Some(env) => env,
+        None => {
+            eprintln!(
+                "{}",
+                "please setup the panel environment before using this tool.".red()
+            );
+
+            std::process::exit(1);
+        }
+    };
+
+    let cache = Arc::new(shared::cache::Cache::new(&env).await);
+    let database = Arc::new(shared::database::Database::new(&env, cache.clone()).await);
+
+    crate::ensure_migrations_table(database.write()).await?;
+
+    tracing::info!("fetching applied migrations...");
+    let applied_migrations = crate::fetch_applied_migrations(database.write()).await?;
+
+    let migrations = super::collect_migrations(args.live).await?;
+    tracing::info!("found {} migrations.", migrations.len());
+
+    let target = match migrations.iter().find(|m| {
+        m.name == args.to
+            || m.snapshot.id.to_string() == args.to
+            || m.name.split('_').next() == Some(args.to.as_str())
+    }) {
+        Some(target) => target,
+        None => {
+            eprintln!("{}: {}", "migration not found".red(), args.to);
+            std::process::exit(1);
+        }
+    };
+
+    if !applied_migrations
+        .iter()
+        .any(|am| am.id == target.snapshot.id)
+    {
+        eprintln!(
+            "{}: {}",
+            "migration is not applied, nothing to revert to".red(),
+            target.name
+        );
+        std::process::exit(1);
+    }
+
+    let mut to_revert: Vec<&crate::Migration> = migrations
+        .iter()
+        .filter(|m| {
+            m.date > target.date && applied_migrations.iter().any(|am| am.id == m.snapshot.id)
+        })
+        .collect();
+    to_revert.reverse();
+
+    revert_migrations(&database, &to_revert, args.dry_run, args.yes).await
+}
+
+/// Reverts the given migrations in order, newest first.
+///
+/// Nothing is reverted if any of the migrations is missing a down migration,
+/// so a rollback never stops halfway because of it.
+pub async fn revert_migrations(
+    database: &shared::database::Database,
+    migrations: &[&crate::Migration],
+    dry_run: bool,
+    confirmed: bool,
+) -> Result<(), anyhow::Error> {
+    if migrations.is_empty() {
+        tracing::info!("no migrations to revert.");
+        return Ok(());
+    }
+
+    let irreversible: Vec<&str> = migrations
+        .iter()
+        .filter(|m| m.down_sql.is_none())
+        .map(|m| m.name.as_str())
+        .collect();
+    if !irreversible.is_empty() {
+        eprintln!(
+            "{}: {}",
+            "the following migrations have no down migration and cannot be reverted".red(),
+            irreversible.join(", ")
+        );
+        std::process::exit(1);
+    }
+
+    if dry_run {
+        for migration in migrations {
+            println!("-- revert {}", migration.name.bright_yellow());
+            println!(
+                "{}",
+                migration.down_sql.as_deref().unwrap_or_default().trim()
+            );
+            println!();
+        }
+
+        tracing::info!(
+            "dry run, {} migrations would be reverted.",
+            migrations.len()
+        );
+        return Ok(());
+    }
+
+    if !super::confirm_destructive(
+        migrations
+            .iter()
+            .map(|m| (m.name.as_str(), m.down_sql.as_deref().unwrap_or_default())),
+        confirmed,
+    ) {
+        eprintln!("{}", "aborted, no migrations were reverted.".red());
+        std::process::exit(1);
+    }
+
+    let mut reverted_migrations = 0;
+    for migration in migrations {
+        tracing::info!(name = %migration.name, "reverting migration");
+
+        if let Err(err) = crate::revert_migration(database.write(), migration).await {
+            eprintln!("{}: {}", "failed to revert migration".red(), err);
+            std::process::exit(1);
+        }
+
+        tracing::info!(name = %migration.name, "successfully reverted migration");
+        tracing::info!("");
+
+        reverted_migrations += 1;
+    }
+
+    tracing::info!("reverted {} migrations.", reverted_migrations);
+
+    Ok(())
+}
@@ -22,6 +22,18 @@ pub struct MigrateArgs {
         default_value = "0"
     )]
     limit: usize,
+    #[arg(
+        long = "dry-run",
+        help = "whether to only print the sql that would run instead of running it",
+        default_value = "false"
+    )]
+    dry_run: bool,
+    #[arg(
+        long = "yes",
+        help = "whether to apply migrations with destructive statements without asking for confirmation",
+        default_value = "false"
+    )]
+    yes: bool,
 
     #[arg(
         long = "unsafe-apply-all",
@@ -41,12 +53,23 @@ pub struct MigrateCommand;
 
 impl shared::extensions::commands::CliCommand<MigrateArgs> for MigrateCommand {
     fn get_command(&self, command: clap::Command) -> clap::Command {
-        command
+        command.args_conflicts_with_subcommands(true).subcommand(
+            super::down::DownArgs::augment_args(
+                clap::Command::new("down")
+                    .about("Reverts applied database migrations down to the given migration."),
+            ),
+        )
     }
 
     fn get_executor(self) -> Box<shared::extensions::commands::ExecutorFunc> {
         Box::new(|env, arg_matches| {
             Box::pin(async move {
+                if let Some(("down", arg_matches)) = arg_matches.subcommand() {
+                    let args = super::down::DownArgs::from_arg_matches(arg_matches)?;
+
+                    return super::down::run(env, args).await;
+                }
+
                 let args = MigrateArgs::from_arg_matches(&arg_matches)?;
 
                 let env = match env {
@@ -69,34 +92,11 @@ impl shared::extensions::commands::CliCommand<MigrateArgs> for MigrateCommand {
                 tracing::info!("fetching applied migrations...");
                 let applied_migrations = crate::fetch_applied_migrations(database.write()).await?;
 
-                let migrations = if args.live {
-                    let live_path = match () {
-                        _ if tokio::fs::metadata("migrations").await.is_ok() => "migrations",
-                        _ if tokio::fs::metadata("database/migrations").await.is_ok() => {
-                            "database/migrations"
-                        }
-                        _ if tokio::fs::metadata("../database/migrations").await.is_ok() => {
-                            "../database/migrations"
-                        }
-                        _ => {
-                            tracing::error!(
-                                "failed to find live migrations folder, expected one of: ./migrations, ./database/migrations, ../database/migrations"
-                            );
-                            std::process::exit(1);
-                        }
-                    };
-
-                    tracing::info!("collecting migrations from filesystem...");
-                    crate::collect_migrations(live_path).await?
-                } else {
-                    tracing::info!("collecting embedded migrations...");
-                    crate::collect_embedded_migrations()?
-                };
+                let migrations = super::collect_migrations(args.live).await?;
 
                 tracing::info!("found {} migrations.", migrations.len());
 
-                let mut ran_migrations = 0;
-                for migration in migrations
+                let pending_migrations: Vec<crate::Migration> = migrations
                     .into_iter()
                     .filter(|m| {
                         args.unsafe_apply_all
@@ -107,7 +107,38 @@ impl shared::extensions::commands::CliCommand<MigrateArgs> for MigrateCommand {
                     } else {
                         args.limit
                     })
+                    .collect();
+
+                if args.dry_run {
+                    for migration in &pending_migrations {
+                        println!("-- apply {}", migration.name.bright_green());
+                        println!("{}", migration.sql.trim());
+                        println!();
+                    }
+
+                    tracing::info!(
+                        "dry run, {} migrations would be applied.",
+                        pending_migrations.len()
+                    );
+                    return Ok(());
+                }
+
+                // a fresh database has nothing to lose, so only upgrades need to be confirmed
+                if !args.unsafe_skip_run
+                    && !applied_migrations.is_empty()
+                    && !super::confirm_destructive(
+                        pending_migrations
+                            .iter()
+                            .map(|m| (m.name.as_str(), m.sql.as_str())),
+                        args.yes,
+                    )
                 {
+                    eprintln!("{}", "aborted, no migrations were applied.".red());
+                    std::process::exit(1);
+                }
+
+                let mut ran_migrations = 0;
+                for migration in pending_migrations {
                     tracing::info!(
                         tables = ?migration.snapshot.tables().len(),
                         enums = ?migration.snapshot.enums().len(),
@@ -1,6 +1,10 @@
+use colored::Colorize;
+use dialoguer::{Confirm, theme::ColorfulTheme};
 use shared::extensions::commands::CliCommandGroupBuilder;
 
+mod down;
 mod migrate;
+mod rollback;
 mod status;
 mod version;
 
@@ -15,9 +19,87 @@ pub fn commands(cli: CliCommandGroupBuilder) -> CliCommandGroupBuilder {
         "Applies pending database migrations.",
         migrate::MigrateCommand,
     )
+    .add_command(
+        "rollback",
+        "Reverts the most recently applied database migrations.",
+        rollback::RollbackCommand,
+    )
     .add_command(
         "version",
         "Prints the current executable version and exits.",
         version::VersionCommand,
     )
 }
+
+/// Collects either the on-disk or the embedded migrations, sorted by date.
+async fn collect_migrations(live: bool) -> Result<Vec<crate::Migration>, std::io::Error> {
+    if !live {
+        tracing::info!("collecting embedded migrations...");
+        return crate::collect_embedded_migrations();
+    }
+
+    let live_path = match () {
+        _ if tokio::fs::metadata("migrations").await.is_ok() => "migrations",
+        _ if tokio::fs::metadata("database/migrations").await.is_ok() => "database/migrations",
+        _ if tokio::fs::metadata("../database/migrations").await.is_ok() => {
+            "../database/migrations"
+        }
+        _ => {
+            tracing::error!(
+                "failed to find live migrations folder, expected one of: ./migrations, ./database/migrations, ../database/migrations"
+            );
+            std::process::exit(1);
+        }
+    };
+
+    tracing::info!("collecting migrations from filesystem...");
+    crate::collect_migrations(live_path).await
+}
+
+/// Prints the destructive statements of the given migrations and asks for confirmation
+/// before continuing, unless it was already given on the command line.
+fn confirm_destructive<'a>(
+    migrations: impl IntoIterator<Item = (&'a str, &'a str)>,
+    confirmed: bool,
+) -> bool {
+    let mut found = false;
+    for (name, sql) in migrations {
+        let statements = crate::destructive_statements(sql);
+        if statements.is_empty() {
+            continue;
+        }
+
+        if !found {
+            println!(
+                "{}",
+                "the following statements may permanently delete data:".yellow()
+            );
+            found = true;
+        }
+
+        println!("  {}", name.bright_yellow());
+        for statement in statements {
+            println!("    {statement};");
+        }
+    }
+
+    if !found || confirmed {
+        return true;
+    }
+
+    match Confirm::with_theme(&ColorfulTheme::default())
+        .with_prompt("do you want to continue?")
+        .default(false)
+        .interact()
+    {
+        Ok(confirm) => confirm,
+        Err(_) => {
+            eprintln!(
+                "{}",
+                "unable to ask for confirmation, pass --yes to run destructive migrations.".red()
+            );
+
+            false
+        }
+    }
+}
@@ -0,0 +1,79 @@
+use clap::{Args, FromArgMatches};
+use colored::Colorize;
+use std::sync::Arc;
+
+#[derive(Args)]
+pub struct RollbackArgs {
+    #[arg(
+        long = "steps",
+        help = "the amount of applied migrations to revert",
+        default_value = "1"
+    )]
+    steps: usize,
+    #[arg(
+        long = "live",
+        help = "whether to use the on-disk migrations instead of the embedded migrations",
+        default_value = "false"
+    )]
+    live: bool,
+    #[arg(
+        long = "dry-run",
+        help = "whether to only print the sql that would run instead of running it",
+        default_value = "false"
+    )]
+    dry_run: bool,
+    #[arg(
+        long = "yes",
+        help = "whether to revert migrations with destructive statements without asking for confirmation",
+        default_value = "false"
+    )]
+    yes: bool,
+}
+
+pub struct RollbackCommand;
+
+impl shared::extensions::commands::CliCommand<RollbackArgs> for RollbackCommand {
+    fn get_command(&self, command: clap::Command) -> clap::Command {
+        command
+    }
+
+    fn get_executor(self) -> Box<shared::extensions::commands::ExecutorFunc> {
+        Box::new(|env, arg_matches| {
+            Box::pin(async move {
+                let args = RollbackArgs::from_arg_matches(&arg_matches)?;
+
+                let env = match env {
+                    Some(env) => env,
+                    None => {
+                        eprintln!(
+                            "{}",
+                            "please setup the panel environment before using this tool.".red()
+                        );
+
+                        std::process::exit(1);
+                    }
+                };
+
+                let cache = Arc::new(shared::cache::Cache::new(&env).await);
+                let database = Arc::new(shared::database::Database::new(&env, cache.clone()).await);
+
+                crate::ensure_migrations_table(database.write()).await?;
+
+                tracing::info!("fetching applied migrations...");
+                let applied_migrations = crate::fetch_applied_migrations(database.write()).await?;
+
+                let migrations = super::collect_migrations(args.live).await?;
+                tracing::info!("found {} migrations.", migrations.len());
+
+                let to_revert: Vec<&crate::Migration> = migrations
+                    .iter()
+                    .rev()
+                    .filter(|m| applied_migrations.iter().any(|am| am.id == m.snapshot.id))
+                    .take(args.steps)
+                    .collect();
+
+                super::down::revert_migrations(&database, &to_revert, args.dry_run, args.yes).await
+            })
+        })
+    }
+}
@@ -44,29 +44,7 @@ impl shared::extensions::commands::CliCommand<StatusArgs> for StatusCommand {
                 tracing::info!("fetching applied migrations...");
                 let applied_migrations = crate::fetch_applied_migrations(database.write()).await?;
 
-                let migrations = if args.live {
-                    let live_path = match () {
-                        _ if tokio::fs::metadata("migrations").await.is_ok() => "migrations",
-                        _ if tokio::fs::metadata("database/migrations").await.is_ok() => {
-                            "database/migrations"
-                        }
-                        _ if tokio::fs::metadata("../database/migrations").await.is_ok() => {
-                            "../database/migrations"
-                        }
-                        _ => {
-                            tracing::error!(
-                                "failed to find live migrations folder, expected one of: ./migrations, ./database/migrations, ../database/migrations"
-                            );
-                            std::process::exit(1);
-                        }
-                    };
-
-                    tracing::info!("collecting migrations from filesystem...");
-                    crate::collect_migrations(live_path).await?
-                } else {
-                    tracing::info!("collecting embedded migrations...");
-                    crate::collect_embedded_migrations()?
-                };
+                let migrations = super::collect_migrations(args.live).await?;
 
                 tracing::info!("found {} migrations.", migrations.len());
                 tracing::info!("migration status:");
@@ -0,0 +1,4 @@
+ALTER TABLE "nodes" DROP COLUMN "cpu_overallocation";
+ALTER TABLE "nodes" DROP COLUMN "disk_overallocation";
+ALTER TABLE "nodes" DROP COLUMN "memory_overallocation";
+ALTER TABLE "nodes" DROP COLUMN "cpu";
\ No newline at end of file
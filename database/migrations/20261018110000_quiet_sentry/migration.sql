@@ -0,0 +1,4 @@
+ALTER TABLE "nodes" ADD COLUMN "cpu" integer DEFAULT 0 NOT NULL;
+ALTER TABLE "nodes" ADD COLUMN "memory_overallocation" smallint DEFAULT 0 NOT NULL;
+ALTER TABLE "nodes" ADD COLUMN "disk_overallocation" smallint DEFAULT 0 NOT NULL;
+ALTER TABLE "nodes" ADD COLUMN "cpu_overallocation" smallint DEFAULT 0 NOT NULL;
\ No newline at end of file
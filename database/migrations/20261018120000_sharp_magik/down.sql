@@ -0,0 +1,2 @@
+DROP INDEX "users_ldap_identifier_idx";
+ALTER TABLE "users" DROP COLUMN "ldap_identifier";
\ No newline at end of file
@@ -0,0 +1,2 @@
+ALTER TABLE "users" ADD COLUMN "ldap_identifier" varchar(255);
+CREATE UNIQUE INDEX "users_ldap_identifier_idx" ON "users" USING btree ("ldap_identifier");
\ No newline at end of file
@@ -0,0 +1,5 @@
+ALTER TABLE "oauth_providers" DROP COLUMN "role_mappings";
+ALTER TABLE "oauth_providers" DROP COLUMN "role_claim_path";
+ALTER TABLE "oauth_providers" DROP COLUMN "oidc_logout_enabled";
+ALTER TABLE "oauth_providers" DROP COLUMN "oidc_issuer_url";
+ALTER TABLE "oauth_providers" ALTER COLUMN "info_url" SET DATA TYPE varchar(64);
\ No newline at end of file
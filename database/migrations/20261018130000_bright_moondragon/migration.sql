@@ -0,0 +1,5 @@
+ALTER TABLE "oauth_providers" ALTER COLUMN "info_url" SET DATA TYPE varchar(255);
+ALTER TABLE "oauth_providers" ADD COLUMN "oidc_issuer_url" varchar(255);
+ALTER TABLE "oauth_providers" ADD COLUMN "oidc_logout_enabled" boolean DEFAULT false NOT NULL;
+ALTER TABLE "oauth_providers" ADD COLUMN "role_claim_path" varchar(255);
+ALTER TABLE "oauth_providers" ADD COLUMN "role_mappings" jsonb DEFAULT '[]'::jsonb NOT NULL;
\ No newline at end of file
@@ -0,0 +1,2 @@
+DROP TABLE "user_saml_links";
+DROP TABLE "saml_providers";
\ No newline at end of file
@@ -0,0 +1,37 @@
+CREATE TABLE "saml_providers" (
+	"uuid" uuid PRIMARY KEY DEFAULT gen_random_uuid() NOT NULL,
+	"name" varchar(1020) NOT NULL,
+	"description" text,
+	"idp_entity_id" varchar(255) NOT NULL,
+	"idp_sso_url" varchar(255) NOT NULL,
+	"idp_certificate" text NOT NULL,
+	"sp_certificate" text NOT NULL,
+	"sp_private_key" bytea NOT NULL,
+	"identifier_attribute" varchar(255),
+	"email_attribute" varchar(255),
+	"username_attribute" varchar(255),
+	"name_first_attribute" varchar(255),
+	"name_last_attribute" varchar(255),
+	"enabled" boolean DEFAULT false NOT NULL,
+	"login_only" boolean DEFAULT false NOT NULL,
+	"link_viewable" boolean DEFAULT false NOT NULL,
+	"user_manageable" boolean DEFAULT false NOT NULL,
+	"created" timestamp DEFAULT now() NOT NULL
+);
+
+CREATE TABLE "user_saml_links" (
+	"uuid" uuid PRIMARY KEY DEFAULT gen_random_uuid() NOT NULL,
+	"user_uuid" uuid NOT NULL,
+	"saml_provider_uuid" uuid NOT NULL,
+	"identifier" varchar(255) NOT NULL,
+	"last_used" timestamp,
+	"created" timestamp DEFAULT now() NOT NULL
+);
+
+ALTER TABLE "user_saml_links" ADD CONSTRAINT "user_saml_links_user_uuid_users_uuid_fk" FOREIGN KEY ("user_uuid") REFERENCES "public"."users"("uuid") ON DELETE cascade ON UPDATE no action;
+ALTER TABLE "user_saml_links" ADD CONSTRAINT "user_saml_links_saml_provider_uuid_saml_providers_uuid_fk" FOREIGN KEY ("saml_provider_uuid") REFERENCES "public"."saml_providers"("uuid") ON DELETE cascade ON UPDATE no action;
+CREATE UNIQUE INDEX "saml_providers_name_idx" ON "saml_providers" USING btree ("name");
+CREATE INDEX "user_saml_links_user_uuid_idx" ON "user_saml_links" USING btree ("user_uuid");
+CREATE INDEX "user_saml_links_saml_provider_uuid_idx" ON "user_saml_links" USING btree ("saml_provider_uuid");
+CREATE UNIQUE INDEX "user_saml_links_user_uuid_saml_provider_uuid_idx" ON "user_saml_links" USING btree ("user_uuid","saml_provider_uuid");
+CREATE UNIQUE INDEX "user_saml_links_saml_provider_uuid_identifier_idx" ON "user_saml_links" USING btree ("saml_provider_uuid","identifier");
\ No newline at end of file
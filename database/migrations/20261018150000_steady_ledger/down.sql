@@ -0,0 +1,2 @@
+DROP TABLE "import_mappings";
+DROP TABLE "import_checkpoints";
\ No newline at end of file
@@ -0,0 +1,16 @@
+CREATE TABLE "import_checkpoints" (
+	"source" varchar(31) NOT NULL,
+	"source_table" varchar(63) NOT NULL,
+	"updated" timestamp NOT NULL,
+	"created" timestamp DEFAULT now() NOT NULL,
+	CONSTRAINT "import_checkpoints_pk" PRIMARY KEY("source","source_table")
+);
+
+CREATE TABLE "import_mappings" (
+	"source" varchar(31) NOT NULL,
+	"source_table" varchar(63) NOT NULL,
+	"source_id" bigint NOT NULL,
+	"target_uuid" uuid NOT NULL,
+	"created" timestamp DEFAULT now() NOT NULL,
+	CONSTRAINT "import_mappings_pk" PRIMARY KEY("source","source_table","source_id")
+);
\ No newline at end of file
@@ -0,0 +1 @@
+DROP TABLE "mail_templates";
\ No newline at end of file
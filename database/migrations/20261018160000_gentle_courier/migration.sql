@@ -0,0 +1,11 @@
+CREATE TABLE "mail_templates" (
+	"uuid" uuid PRIMARY KEY DEFAULT gen_random_uuid() NOT NULL,
+	"identifier" varchar(63) NOT NULL,
+	"language" varchar(15) NOT NULL,
+	"subject" varchar(255) NOT NULL,
+	"body_html" text NOT NULL,
+	"body_text" text,
+	"created" timestamp DEFAULT now() NOT NULL
+);
+
+CREATE UNIQUE INDEX "mail_templates_identifier_language_idx" ON "mail_templates" USING btree ("identifier","language");
\ No newline at end of file
@@ -0,0 +1,2 @@
+DROP TABLE "user_email_verifications";
+ALTER TABLE "users" DROP COLUMN "email_verified";
\ No newline at end of file
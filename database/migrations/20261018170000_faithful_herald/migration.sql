@@ -0,0 +1,15 @@
+CREATE TABLE "user_email_verifications" (
+	"uuid" uuid PRIMARY KEY DEFAULT gen_random_uuid() NOT NULL,
+	"user_uuid" uuid NOT NULL,
+	"email" varchar(255) NOT NULL,
+	"token" text NOT NULL,
+	"created" timestamp DEFAULT now() NOT NULL
+);
+
+ALTER TABLE "users" ADD COLUMN "email_verified" boolean DEFAULT true NOT NULL;
+
+ALTER TABLE "user_email_verifications" ADD CONSTRAINT "user_email_verifications_user_uuid_users_uuid_fk" FOREIGN KEY ("user_uuid") REFERENCES "public"."users"("uuid") ON DELETE cascade ON UPDATE no action;
+
+CREATE INDEX "user_email_verifications_user_uuid_idx" ON "user_email_verifications" USING btree ("user_uuid");
+
+CREATE UNIQUE INDEX "user_email_verifications_token_idx" ON "user_email_verifications" USING btree ("token");
\ No newline at end of file
@@ -0,0 +1,3 @@
+ALTER TABLE "users" ADD COLUMN "failed_login_attempts" integer DEFAULT 0 NOT NULL;
+
+ALTER TABLE "users" ADD COLUMN "locked_until" timestamp;
\ No newline at end of file
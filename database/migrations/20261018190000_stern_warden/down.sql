@@ -0,0 +1,2 @@
+DROP TABLE "user_password_histories";
+DROP TABLE "breached_password_ranges";
\ No newline at end of file
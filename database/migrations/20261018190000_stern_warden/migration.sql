@@ -0,0 +1,16 @@
+CREATE TABLE "user_password_histories" (
+	"uuid" uuid PRIMARY KEY DEFAULT gen_random_uuid() NOT NULL,
+	"user_uuid" uuid NOT NULL,
+	"password" text NOT NULL,
+	"created" timestamp DEFAULT now() NOT NULL
+);
+
+CREATE TABLE "breached_password_ranges" (
+	"prefix" char(5) PRIMARY KEY NOT NULL,
+	"suffixes" char(35)[] NOT NULL,
+	"created" timestamp DEFAULT now() NOT NULL
+);
+
+ALTER TABLE "user_password_histories" ADD CONSTRAINT "user_password_histories_user_uuid_users_uuid_fk" FOREIGN KEY ("user_uuid") REFERENCES "public"."users"("uuid") ON DELETE cascade ON UPDATE no action;
+
+CREATE INDEX "user_password_histories_user_uuid_idx" ON "user_password_histories" USING btree ("user_uuid");
\ No newline at end of file
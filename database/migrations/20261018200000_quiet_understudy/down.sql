@@ -0,0 +1,3 @@
+DROP INDEX "user_sessions_impersonator_uuid_idx";
+ALTER TABLE "user_sessions" DROP CONSTRAINT "user_sessions_impersonator_uuid_users_uuid_fk";
+ALTER TABLE "user_sessions" DROP COLUMN "impersonator_uuid";
\ No newline at end of file
@@ -0,0 +1,5 @@
+ALTER TABLE "user_sessions" ADD COLUMN "impersonator_uuid" uuid;
+
+ALTER TABLE "user_sessions" ADD CONSTRAINT "user_sessions_impersonator_uuid_users_uuid_fk" FOREIGN KEY ("impersonator_uuid") REFERENCES "public"."users"("uuid") ON DELETE cascade ON UPDATE no action;
+
+CREATE INDEX "user_sessions_impersonator_uuid_idx" ON "user_sessions" USING btree ("impersonator_uuid");
\ No newline at end of file
@@ -0,0 +1,4 @@
+DROP INDEX "user_api_keys_server_group_uuid_idx";
+ALTER TABLE "user_api_keys" DROP CONSTRAINT "user_api_keys_server_group_uuid_user_server_groups_uuid_fk";
+ALTER TABLE "user_api_keys" DROP COLUMN "server_group_uuid";
+ALTER TABLE "user_api_keys" DROP COLUMN "server_uuids";
\ No newline at end of file
@@ -0,0 +1,7 @@
+ALTER TABLE "user_api_keys" ADD COLUMN "server_uuids" uuid[];
+
+ALTER TABLE "user_api_keys" ADD COLUMN "server_group_uuid" uuid;
+
+ALTER TABLE "user_api_keys" ADD CONSTRAINT "user_api_keys_server_group_uuid_user_server_groups_uuid_fk" FOREIGN KEY ("server_group_uuid") REFERENCES "public"."user_server_groups"("uuid") ON DELETE cascade ON UPDATE no action;
+
+CREATE INDEX "user_api_keys_server_group_uuid_idx" ON "user_api_keys" USING btree ("server_group_uuid");
\ No newline at end of file
@@ -0,0 +1,7 @@
+DO $$
+BEGIN
+    IF EXISTS (SELECT 1 FROM "server_variables" WHERE "value_encrypted" IS NOT NULL) THEN
+        RAISE EXCEPTION 'server_variables contains encrypted values, unmark all variables as secret and restart the panel to decrypt them before rolling back';
+    END IF;
+END $$;
+ALTER TABLE "server_variables" DROP COLUMN "value_encrypted";
\ No newline at end of file
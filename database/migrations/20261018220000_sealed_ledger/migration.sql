@@ -0,0 +1 @@
+ALTER TABLE "server_variables" ADD COLUMN "value_encrypted" bytea;
\ No newline at end of file
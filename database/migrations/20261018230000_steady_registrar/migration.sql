@@ -0,0 +1 @@
+ALTER TABLE "users" ADD COLUMN "active" boolean DEFAULT true NOT NULL;
\ No newline at end of file
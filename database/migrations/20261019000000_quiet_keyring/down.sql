@@ -0,0 +1,4 @@
+-- passwordless accounts get a random password, they can still log in with a passkey or reset it
+UPDATE "users" SET "password" = crypt(gen_random_uuid()::text, gen_salt('bf')) WHERE "password" IS NULL;
+ALTER TABLE "users" ALTER COLUMN "password" SET NOT NULL;
+ALTER TABLE "roles" DROP COLUMN "require_passkey";
\ No newline at end of file
@@ -0,0 +1,3 @@
+ALTER TABLE "roles" ADD COLUMN "require_passkey" boolean DEFAULT false NOT NULL;
+
+ALTER TABLE "users" ALTER COLUMN "password" DROP NOT NULL;
\ No newline at end of file
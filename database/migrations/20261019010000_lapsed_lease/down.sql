@@ -0,0 +1,9 @@
+DROP INDEX "servers_expires_at_idx";
+DROP INDEX "servers_unsuspend_at_idx";
+ALTER TABLE "servers" DROP CONSTRAINT "servers_suspended_by_uuid_users_uuid_fk";
+ALTER TABLE "servers" DROP COLUMN "expiry_notified";
+ALTER TABLE "servers" DROP COLUMN "expires_at";
+ALTER TABLE "servers" DROP COLUMN "unsuspend_at";
+ALTER TABLE "servers" DROP COLUMN "suspended_at";
+ALTER TABLE "servers" DROP COLUMN "suspended_by_uuid";
+ALTER TABLE "servers" DROP COLUMN "suspension_reason";
\ No newline at end of file
@@ -0,0 +1,17 @@
+ALTER TABLE "servers" ADD COLUMN "suspension_reason" text;
+
+ALTER TABLE "servers" ADD COLUMN "suspended_by_uuid" uuid;
+
+ALTER TABLE "servers" ADD COLUMN "suspended_at" timestamp;
+
+ALTER TABLE "servers" ADD COLUMN "unsuspend_at" timestamp;
+
+ALTER TABLE "servers" ADD COLUMN "expires_at" timestamp;
+
+ALTER TABLE "servers" ADD COLUMN "expiry_notified" boolean DEFAULT false NOT NULL;
+
+ALTER TABLE "servers" ADD CONSTRAINT "servers_suspended_by_uuid_users_uuid_fk" FOREIGN KEY ("suspended_by_uuid") REFERENCES "public"."users"("uuid") ON DELETE set null ON UPDATE no action;
+
+CREATE INDEX "servers_unsuspend_at_idx" ON "servers" USING btree ("unsuspend_at");
+
+CREATE INDEX "servers_expires_at_idx" ON "servers" USING btree ("expires_at");
\ No newline at end of file
@@ -0,0 +1,2 @@
+DROP TABLE "server_bulk_operation_results";
+DROP TABLE "server_bulk_operations";
\ No newline at end of file
@@ -0,0 +1,26 @@
+CREATE TABLE "server_bulk_operations" (
+	"uuid" uuid PRIMARY KEY DEFAULT gen_random_uuid() NOT NULL,
+	"user_uuid" uuid,
+	"action" jsonb NOT NULL,
+	"filter" jsonb NOT NULL,
+	"concurrency" smallint NOT NULL,
+	"total" integer NOT NULL,
+	"successful" integer DEFAULT 0 NOT NULL,
+	"failed" integer DEFAULT 0 NOT NULL,
+	"completed" timestamp,
+	"created" timestamp DEFAULT now() NOT NULL
+);
+
+CREATE TABLE "server_bulk_operation_results" (
+	"bulk_operation_uuid" uuid NOT NULL,
+	"server_uuid" uuid NOT NULL,
+	"server_name" varchar(1020) NOT NULL,
+	"successful" boolean NOT NULL,
+	"error" text,
+	"created" timestamp DEFAULT now() NOT NULL,
+	CONSTRAINT "server_bulk_operation_results_pk" PRIMARY KEY("bulk_operation_uuid","server_uuid")
+);
+
+ALTER TABLE "server_bulk_operations" ADD CONSTRAINT "server_bulk_operations_user_uuid_users_uuid_fk" FOREIGN KEY ("user_uuid") REFERENCES "public"."users"("uuid") ON DELETE set null ON UPDATE no action;
+ALTER TABLE "server_bulk_operation_results" ADD CONSTRAINT "server_bulk_operation_results_bulk_operation_uuid_server_bulk_operations_uuid_fk" FOREIGN KEY ("bulk_operation_uuid") REFERENCES "public"."server_bulk_operations"("uuid") ON DELETE cascade ON UPDATE no action;
+CREATE INDEX "server_bulk_operations_created_idx" ON "server_bulk_operations" USING btree ("created");
\ No newline at end of file
@@ -0,0 +1 @@
+DROP TABLE "server_templates";
\ No newline at end of file
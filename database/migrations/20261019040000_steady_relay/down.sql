@@ -0,0 +1,2 @@
+ALTER TABLE "server_bulk_operations" DROP COLUMN "heartbeat";
+ALTER TABLE "server_bulk_operations" DROP COLUMN "server_uuids";
\ No newline at end of file
@@ -0,0 +1,3 @@
+ALTER TABLE "server_bulk_operations" ADD COLUMN "server_uuids" uuid[] DEFAULT '{}' NOT NULL;
+
+ALTER TABLE "server_bulk_operations" ADD COLUMN "heartbeat" timestamp DEFAULT now() NOT NULL;
\ No newline at end of file
@@ -65,22 +65,28 @@ export const usersTable = new DatabaseTable('users')
     uuid().references(() => rolesTable.join().uuid, { onDelete: 'set null' }),
   )
   .addColumn('external_id', varchar({ length: 255 }))
+  .addColumn('ldap_identifier', varchar({ length: 255 }))
   .addColumn('avatar', varchar({ length: 255 }))
   .addColumn('username', varchar({ length: 15 * UTF8_MAX_SCALAR_SIZE }).notNull())
   .addColumn('email', varchar({ length: 255 }).notNull())
+  .addColumn('email_verified', boolean().default(true).notNull())
   .addColumn('name_first', varchar({ length: 255 }).notNull())
   .addColumn('name_last', varchar({ length: 255 }).notNull())
-  .addColumn('password', text().notNull())
+  .addColumn('password', text())
   .addColumn('admin', boolean().default(false).notNull())
   .addColumn('totp_enabled', boolean().default(false).notNull())
   .addColumn('totp_last_used', timestamp())
   .addColumn('totp_secret', char({ length: 32 }))
+  .addColumn('failed_login_attempts', integer().default(0).notNull())
+  .addColumn('locked_until', timestamp())
+  .addColumn('active', boolean().default(true).notNull())
   .addColumn('language', varchar({ length: 15 }).default('en-US').notNull())
   .addColumn('toast_position', userToastPositionEnum.intoDrizzleEnum()().default('BOTTOM_RIGHT').notNull())
   .addColumn('start_on_grouped_servers', boolean().default(false).notNull())
   .addColumn('created', timestamp().defaultNow().notNull())
   .addConfigBuilder((cols) => [
     uniqueIndex('users_external_id_idx').on(cols.external_id),
+    uniqueIndex('users_ldap_identifier_idx').on(cols.ldap_identifier),
     uniqueIndex('users_username_idx').on(sql`lower(${cols.username})`),
     uniqueIndex('users_email_idx').on(sql`lower(${cols.email})`),
   ]);
@@ -117,10 +123,15 @@ export const userSessionsTable = new DatabaseTable('user_sessions')
   .addColumn('key', text().notNull())
   .addColumn('ip', inet().notNull())
   .addColumn('user_agent', varchar({ length: 255 }).notNull())
+  .addColumn(
+    'impersonator_uuid',
+    uuid().references(() => usersTable.join().uuid, { onDelete: 'cascade' }),
+  )
   .addColumn('last_used', timestamp().defaultNow().notNull())
   .addColumn('created', timestamp().defaultNow().notNull())
   .addConfigBuilder((cols) => [
     index('user_sessions_user_id_idx').on(cols.user_uuid),
+    index('user_sessions_impersonator_uuid_idx').on(cols.impersonator_uuid),
     uniqueIndex('user_sessions_key_idx').on(cols.key),
   ]);
 
@@ -153,6 +164,39 @@ export const userPasswordResetsTable = new DatabaseTable('user_password_resets')
     uniqueIndex('user_password_resets_token_idx').on(cols.token),
   ]);
 
+export const userEmailVerificationsTable = new DatabaseTable('user_email_verifications')
+  .addColumn('uuid', uuid().default(sql`gen_random_uuid()`).primaryKey().notNull())
+  .addColumn(
+    'user_uuid',
+    uuid()
+      .references(() => usersTable.join().uuid, { onDelete: 'cascade' })
+      .notNull(),
+  )
+  .addColumn('email', varchar({ length: 255 }).notNull())
+  .addColumn('token', text().notNull())
+  .addColumn('created', timestamp().defaultNow().notNull())
+  .addConfigBuilder((cols) => [
+    index('user_email_verifications_user_uuid_idx').on(cols.user_uuid),
+    uniqueIndex('user_email_verifications_token_idx').on(cols.token),
+  ]);
+
+export const userPasswordHistoriesTable = new DatabaseTable('user_password_histories')
+  .addColumn('uuid', uuid().default(sql`gen_random_uuid()`).primaryKey().notNull())
+  .addColumn(
+    'user_uuid',
+    uuid()
+      .references(() => usersTable.join().uuid, { onDelete: 'cascade' })
+      .notNull(),
+  )
+  .addColumn('password', text().notNull())
+  .addColumn('created', timestamp().defaultNow().notNull())
+  .addConfigBuilder((cols) => [index('user_password_histories_user_uuid_idx').on(cols.user_uuid)]);
+
+export const breachedPasswordRangesTable = new DatabaseTable('breached_password_ranges')
+  .addColumn('prefix', char({ length: 5 }).primaryKey().notNull())
+  .addColumn('suffixes', char({ length: 35 }).array().notNull())
+  .addColumn('created', timestamp().defaultNow().notNull());
+
 export const userSecurityKeysTable = new DatabaseTable('user_security_keys')
   .addColumn('uuid', uuid().default(sql`gen_random_uuid()`).primaryKey().notNull())
   .addColumn(
@@ -206,11 +250,17 @@ export const userApiKeysTable = new DatabaseTable('user_api_keys')
   .addColumn('user_permissions', varchar({ length: 64 }).array().notNull())
   .addColumn('admin_permissions', varchar({ length: 64 }).array().notNull())
   .addColumn('server_permissions', varchar({ length: 64 }).array().notNull())
+  .addColumn('server_uuids', uuid().array())
+  .addColumn(
+    'server_group_uuid',
+    uuid().references(() => userServerGroupsTable.join().uuid, { onDelete: 'cascade' }),
+  )
   .addColumn('last_used', timestamp())
   .addColumn('expires', timestamp())
   .addColumn('created', timestamp().defaultNow().notNull())
   .addConfigBuilder((cols) => [
     index('user_api_keys_user_uuid_idx').on(cols.user_uuid),
+    index('user_api_keys_server_group_uuid_idx').on(cols.server_group_uuid),
     uniqueIndex('user_api_keys_user_uuid_name_idx').on(cols.user_uuid, cols.name),
     uniqueIndex('user_api_keys_user_uuid_key_start_idx').on(cols.user_uuid, cols.key_start),
     uniqueIndex('user_api_keys_key_idx').on(cols.key),
@@ -240,6 +290,30 @@ export const userOauthLinksTable = new DatabaseTable('user_oauth_links')
     uniqueIndex('user_oauth_links_oauth_provider_uuid_identifier_idx').on(cols.oauth_provider_uuid, cols.identifier),
   ]);
 
+export const userSamlLinksTable = new DatabaseTable('user_saml_links')
+  .addColumn('uuid', uuid().default(sql`gen_random_uuid()`).primaryKey().notNull())
+  .addColumn(
+    'user_uuid',
+    uuid()
+      .references(() => usersTable.join().uuid, { onDelete: 'cascade' })
+      .notNull(),
+  )
+  .addColumn(
+    'saml_provider_uuid',
+    uuid()
+      .references(() => samlProvidersTable.join().uuid, { onDelete: 'cascade' })
+      .notNull(),
+  )
+  .addColumn('identifier', varchar({ length: 255 }).notNull())
+  .addColumn('last_used', timestamp())
+  .addColumn('created', timestamp().defaultNow().notNull())
+  .addConfigBuilder((cols) => [
+    index('user_saml_links_user_uuid_idx').on(cols.user_uuid),
+    index('user_saml_links_saml_provider_uuid_idx').on(cols.saml_provider_uuid),
+    uniqueIndex('user_saml_links_user_uuid_saml_provider_uuid_idx').on(cols.user_uuid, cols.saml_provider_uuid),
+    uniqueIndex('user_saml_links_saml_provider_uuid_identifier_idx').on(cols.saml_provider_uuid, cols.identifier),
+  ]);
+
 export const userServerGroupsTable = new DatabaseTable('user_server_groups')
   .addColumn('uuid', uuid().default(sql`gen_random_uuid()`).primaryKey().notNull())
   .addColumn(
@@ -259,6 +333,7 @@ export const rolesTable = new DatabaseTable('roles')
   .addColumn('name', varchar({ length: 255 * UTF8_MAX_SCALAR_SIZE }).notNull())
   .addColumn('description', text())
   .addColumn('require_two_factor', boolean().default(false).notNull())
+  .addColumn('require_passkey', boolean().default(false).notNull())
   .addColumn('admin_permissions', varchar({ length: 64 }).array().notNull())
   .addColumn('server_permissions', varchar({ length: 64 }).array().notNull())
   .addColumn('created', timestamp().defaultNow().notNull())
@@ -272,13 +347,17 @@ export const oauthProvidersTable = new DatabaseTable('oauth_providers')
   .addColumn('client_secret', bytea().notNull())
   .addColumn('auth_url', varchar({ length: 255 }).notNull())
   .addColumn('token_url', varchar({ length: 255 }).notNull())
-  .addColumn('info_url', varchar({ length: 64 }).notNull())
+  .addColumn('info_url', varchar({ length: 255 }).notNull())
   .addColumn('scopes', varchar({ length: 64 }).array().notNull())
   .addColumn('identifier_path', varchar({ length: 255 }).notNull())
   .addColumn('email_path', varchar({ length: 255 }))
   .addColumn('username_path', varchar({ length: 255 }))
   .addColumn('name_first_path', varchar({ length: 255 }))
   .addColumn('name_last_path', varchar({ length: 255 }))
+  .addColumn('oidc_issuer_url', varchar({ length: 255 }))
+  .addColumn('oidc_logout_enabled', boolean().default(false).notNull())
+  .addColumn('role_claim_path', varchar({ length: 255 }))
+  .addColumn('role_mappings', jsonb().default([]).notNull())
   .addColumn('enabled', boolean().default(false).notNull())
   .addColumn('login_only', boolean().default(false).notNull())
   .addColumn('link_viewable', boolean().default(false).notNull())
@@ -287,6 +366,27 @@ export const oauthProvidersTable = new DatabaseTable('oauth_providers')
   .addColumn('created', timestamp().defaultNow().notNull())
   .addConfigBuilder((cols) => [uniqueIndex('oauth_providers_name_idx').on(cols.name)]);
 
+export const samlProvidersTable = new DatabaseTable('saml_providers')
+  .addColumn('uuid', uuid().default(sql`gen_random_uuid()`).primaryKey().notNull())
+  .addColumn('name', varchar({ length: 255 * UTF8_MAX_SCALAR_SIZE }).notNull())
+  .addColumn('description', text())
+  .addColumn('idp_entity_id', varchar({ length: 255 }).notNull())
+  .addColumn('idp_sso_url', varchar({ length: 255 }).notNull())
+  .addColumn('idp_certificate', text().notNull())
+  .addColumn('sp_certificate', text().notNull())
+  .addColumn('sp_private_key', bytea().notNull())
+  .addColumn('identifier_attribute', varchar({ length: 255 }))
+  .addColumn('email_attribute', varchar({ length: 255 }))
+  .addColumn('username_attribute', varchar({ length: 255 }))
+  .addColumn('name_first_attribute', varchar({ length: 255 }))
+  .addColumn('name_last_attribute', varchar({ length: 255 }))
+  .addColumn('enabled', boolean().default(false).notNull())
+  .addColumn('login_only', boolean().default(false).notNull())
+  .addColumn('link_viewable', boolean().default(false).notNull())
+  .addColumn('user_manageable', boolean().default(false).notNull())
+  .addColumn('created', timestamp().defaultNow().notNull())
+  .addConfigBuilder((cols) => [uniqueIndex('saml_providers_name_idx').on(cols.name)]);
+
 export const mountsTable = new DatabaseTable('mounts')
   .addColumn('uuid', uuid().default(sql`gen_random_uuid()`).primaryKey().notNull())
   .addColumn('name', varchar({ length: 255 * UTF8_MAX_SCALAR_SIZE }).notNull())
@@ -367,6 +467,10 @@ export const nodesTable = new DatabaseTable('nodes')
   .addColumn('sftp_port', integer().notNull())
   .addColumn('memory', bigint({ mode: 'number' }).notNull())
   .addColumn('disk', bigint({ mode: 'number' }).notNull())
+  .addColumn('cpu', integer().default(0).notNull())
+  .addColumn('memory_overallocation', smallint().default(0).notNull())
+  .addColumn('disk_overallocation', smallint().default(0).notNull())
+  .addColumn('cpu_overallocation', smallint().default(0).notNull())
   .addColumn('token_id', char({ length: 16 }).notNull())
   .addColumn('token', bytea().notNull())
   .addColumn('created', timestamp().defaultNow().notNull())
@@ -591,6 +695,15 @@ export const serversTable = new DatabaseTable('servers')
   .addColumn('description', text())
   .addColumn('status', serverStatusEnum.intoDrizzleEnum()())
   .addColumn('suspended', boolean().default(false).notNull())
+  .addColumn('suspension_reason', text())
+  .addColumn(
+    'suspended_by_uuid',
+    uuid().references(() => usersTable.join().uuid, { onDelete: 'set null' }),
+  )
+  .addColumn('suspended_at', timestamp())
+  .addColumn('unsuspend_at', timestamp())
+  .addColumn('expires_at', timestamp())
+  .addColumn('expiry_notified', boolean().default(false).notNull())
   .addColumn('memory', bigint({ mode: 'number' }).notNull())
   .addColumn('swap', bigint({ mode: 'number' }).notNull())
   .addColumn('disk', bigint({ mode: 'number' }).notNull())
@@ -616,6 +729,8 @@ export const serversTable = new DatabaseTable('servers')
     index('servers_owner_uuid_idx').on(cols.owner_uuid),
     index('servers_egg_uuid_idx').on(cols.egg_uuid),
     index('servers_backup_configuration_uuid_idx').on(cols.backup_configuration_uuid),
+    index('servers_unsuspend_at_idx').on(cols.unsuspend_at),
+    index('servers_expires_at_idx').on(cols.expires_at),
     uniqueIndex('servers_uuid_short_idx').on(cols.uuid_short),
   ]);
 
@@ -706,6 +821,7 @@ export const serverVariablesTable = new DatabaseTable('server_variables')
       .notNull(),
   )
   .addColumn('value', text().notNull())
+  .addColumn('value_encrypted', bytea())
   .addColumn('created', timestamp().defaultNow().notNull())
   .addConfigBuilder((cols) => [
     primaryKey({ name: 'server_variables_pk', columns: [cols.server_uuid, cols.variable_uuid] }),
@@ -831,3 +947,134 @@ export const serverScheduleStepsTable = new DatabaseTable('server_schedule_steps
   .addColumn('error', text())
   .addColumn('created', timestamp().defaultNow().notNull())
   .addConfigBuilder((cols) => [index('server_schedule_steps_schedule_uuid_idx').on(cols.schedule_uuid)]);
+
+export const webhooksTable = new DatabaseTable('webhooks')
+  .addColumn('uuid', uuid().default(sql`gen_random_uuid()`).primaryKey().notNull())
+  .addColumn('name', varchar({ length: 255 * UTF8_MAX_SCALAR_SIZE }).notNull())
+  .addColumn('description', text())
+  .addColumn('url', text().notNull())
+  .addColumn('secret', bytea().notNull())
+  .addColumn('events', varchar({ length: 64 }).array().notNull())
+  .addColumn('enabled', boolean().default(true).notNull())
+  .addColumn('created', timestamp().defaultNow().notNull())
+  .addConfigBuilder((cols) => [uniqueIndex('webhooks_name_idx').on(cols.name)]);
+
+export const webhookDeliveriesTable = new DatabaseTable('webhook_deliveries')
+  .addColumn('uuid', uuid().default(sql`gen_random_uuid()`).primaryKey().notNull())
+  .addColumn(
+    'webhook_uuid',
+    uuid()
+      .references(() => webhooksTable.join().uuid, { onDelete: 'cascade' })
+      .notNull(),
+  )
+  .addColumn('event', varchar({ length: 64 }).notNull())
+  .addColumn('payload', jsonb().notNull())
+  .addColumn('attempts', smallint().default(0).notNull())
+  .addColumn('response_status', smallint())
+  .addColumn('response_body', text())
+  .addColumn('next_attempt', timestamp())
+  .addColumn('delivered', timestamp())
+  .addColumn('created', timestamp().defaultNow().notNull())
+  .addConfigBuilder((cols) => [
+    index('webhook_deliveries_webhook_uuid_idx').on(cols.webhook_uuid),
+    index('webhook_deliveries_next_attempt_idx').on(cols.next_attempt),
+  ]);
+
+export const importCheckpointsTable = new DatabaseTable('import_checkpoints')
+  .addColumn('source', varchar({ length: 31 }).notNull())
+  .addColumn('source_table', varchar({ length: 63 }).notNull())
+  .addColumn('updated', timestamp().notNull())
+  .addColumn('created', timestamp().defaultNow().notNull())
+  .addConfigBuilder((cols) => [
+    primaryKey({ name: 'import_checkpoints_pk', columns: [cols.source, cols.source_table] }),
+  ]);
+
+export const importMappingsTable = new DatabaseTable('import_mappings')
+  .addColumn('source', varchar({ length: 31 }).notNull())
+  .addColumn('source_table', varchar({ length: 63 }).notNull())
+  .addColumn('source_id', bigint({ mode: 'number' }).notNull())
+  .addColumn('target_uuid', uuid().notNull())
+  .addColumn('created', timestamp().defaultNow().notNull())
+  .addConfigBuilder((cols) => [
+    primaryKey({ name: 'import_mappings_pk', columns: [cols.source, cols.source_table, cols.source_id] }),
+  ]);
+
+export const mailTemplatesTable = new DatabaseTable('mail_templates')
+  .addColumn('uuid', uuid().default(sql`gen_random_uuid()`).primaryKey().notNull())
+  .addColumn('identifier', varchar({ length: 63 }).notNull())
+  .addColumn('language', varchar({ length: 15 }).notNull())
+  .addColumn('subject', varchar({ length: 255 }).notNull())
+  .addColumn('body_html', text().notNull())
+  .addColumn('body_text', text())
+  .addColumn('created', timestamp().defaultNow().notNull())
+  .addConfigBuilder((cols) => [
+    uniqueIndex('mail_templates_identifier_language_idx').on(cols.identifier, cols.language),
+  ]);
+
+export const serverBulkOperationsTable = new DatabaseTable('server_bulk_operations')
+  .addColumn('uuid', uuid().default(sql`gen_random_uuid()`).primaryKey().notNull())
+  .addColumn('user_uuid', uuid().references(() => usersTable.join().uuid, { onDelete: 'set null' }))
+  .addColumn('action', jsonb().notNull())
+  .addColumn('filter', jsonb().notNull())
+  .addColumn('concurrency', smallint().notNull())
+  .addColumn('total', integer().notNull())
+  .addColumn('successful', integer().default(0).notNull())
+  .addColumn('failed', integer().default(0).notNull())
+  .addColumn('server_uuids', uuid().array().default([]).notNull())
+  .addColumn('heartbeat', timestamp().defaultNow().notNull())
+  .addColumn('completed', timestamp())
+  .addColumn('created', timestamp().defaultNow().notNull())
+  .addConfigBuilder((cols) => [index('server_bulk_operations_created_idx').on(cols.created)]);
+
+export const serverBulkOperationResultsTable = new DatabaseTable('server_bulk_operation_results')
+  .addColumn(
+    'bulk_operation_uuid',
+    uuid()
+      .references(() => serverBulkOperationsTable.join().uuid, { onDelete: 'cascade' })
+      .notNull(),
+  )
+  .addColumn('server_uuid', uuid().notNull())
+  .addColumn('server_name', varchar({ length: 255 * UTF8_MAX_SCALAR_SIZE }).notNull())
+  .addColumn('successful', boolean().notNull())
+  .addColumn('error', text())
+  .addColumn('created', timestamp().defaultNow().notNull())
+  .addConfigBuilder((cols) => [
+    primaryKey({ name: 'server_bulk_operation_results_pk', columns: [cols.bulk_operation_uuid, cols.server_uuid] }),
+  ]);
+
+export const serverTemplatesTable = new DatabaseTable('server_templates')
+  .addColumn('uuid', uuid().default(sql`gen_random_uuid()`).primaryKey().notNull())
+  .addColumn('name', varchar({ length: 255 * UTF8_MAX_SCALAR_SIZE }).notNull())
+  .addColumn('description', text())
+  .addColumn(
+    'egg_uuid',
+    uuid()
+      .references(() => nestEggsTable.join().uuid, { onDelete: 'cascade' })
+      .notNull(),
+  )
+  .addColumn(
+    'backup_configuration_uuid',
+    uuid().references(() => backupConfigurationsTable.join().uuid, { onDelete: 'set null' }),
+  )
+  .addColumn('memory', bigint({ mode: 'number' }).notNull())
+  .addColumn('swap', bigint({ mode: 'number' }).notNull())
+  .addColumn('disk', bigint({ mode: 'number' }).notNull())
+  .addColumn('io_weight', smallint())
+  .addColumn('cpu', integer().notNull())
+  .addColumn('pinned_cpus', smallint().array().notNull())
+  .addColumn('startup', text().notNull())
+  .addColumn('image', varchar({ length: 255 }).notNull())
+  .addColumn('timezone', varchar({ length: 255 }))
+  .addColumn('hugepages_passthrough_enabled', boolean().default(false).notNull())
+  .addColumn('kvm_passthrough_enabled', boolean().default(false).notNull())
+  .addColumn('allocation_limit', integer().default(0).notNull())
+  .addColumn('database_limit', integer().default(0).notNull())
+  .addColumn('backup_limit', integer().default(0).notNull())
+  .addColumn('schedule_limit', integer().default(0).notNull())
+  .addColumn('variables', jsonb().notNull())
+  .addColumn('deployment', jsonb())
+  .addColumn('created', timestamp().defaultNow().notNull())
+  .addConfigBuilder((cols) => [
+    uniqueIndex('server_templates_name_idx').on(cols.name),
+    index('server_templates_egg_uuid_idx').on(cols.egg_uuid),
+  ]);
@@ -0,0 +1,10 @@
+import { axiosInstance } from '@/api/axios.ts';
+
+export default async (nodeUuid: string, file: string): Promise<string> => {
+  return new Promise((resolve, reject) => {
+    axiosInstance
+      .get(`/api/admin/nodes/${nodeUuid}/system/logs/${encodeURIComponent(file)}`, { responseType: 'text' })
+      .then(({ data }) => resolve(data))
+      .catch(reject);
+  });
+};
@@ -0,0 +1,16 @@
+import { axiosInstance } from '@/api/axios.ts';
+
+export interface NodeLog {
+  name: string;
+  size: number;
+  lastModified: Date;
+}
+
+export default async (nodeUuid: string): Promise<NodeLog[]> => {
+  return new Promise((resolve, reject) => {
+    axiosInstance
+      .get(`/api/admin/nodes/${nodeUuid}/system/logs`)
+      .then(({ data }) => resolve(data.logFiles))
+      .catch(reject);
+  });
+};
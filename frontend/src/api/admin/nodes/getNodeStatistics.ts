@@ -0,0 +1,10 @@
+import { axiosInstance } from '@/api/axios.ts';
+
+export default async (nodeUuid: string): Promise<NodeStatistics> => {
+  return new Promise((resolve, reject) => {
+    axiosInstance
+      .get(`/api/admin/nodes/${nodeUuid}/system/stats`)
+      .then(({ data }) => resolve(data.stats))
+      .catch(reject);
+  });
+};
@@ -0,0 +1,10 @@
+import { axiosInstance } from '@/api/axios.ts';
+
+export default async (nodeUuid: string): Promise<{ version: string }> => {
+  return new Promise((resolve, reject) => {
+    axiosInstance
+      .get(`/api/admin/nodes/${nodeUuid}/system`)
+      .then(({ data }) => resolve(data.system))
+      .catch(reject);
+  });
+};
@@ -0,0 +1,10 @@
+import { axiosInstance } from '@/api/axios.ts';
+
+export default async (nodeUuid: string): Promise<{ tokenId: string; token: string }> => {
+  return new Promise((resolve, reject) => {
+    axiosInstance
+      .post(`/api/admin/nodes/${nodeUuid}/reveal-token`)
+      .then(({ data }) => resolve(data))
+      .catch(reject);
+  });
+};
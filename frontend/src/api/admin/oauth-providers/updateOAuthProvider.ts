@@ -1,9 +1,9 @@
 import { z } from 'zod';
 import { axiosInstance } from '@/api/axios.ts';
-import { adminOAuthProviderSchema } from '@/lib/schemas/admin/oauthProviders.ts';
+import { adminOAuthProviderUpdateSchema } from '@/lib/schemas/admin/oauthProviders.ts';
 import { transformKeysToSnakeCase } from '@/lib/transformers.ts';
 
-export default async (oauthProviderUuid: string, data: z.infer<typeof adminOAuthProviderSchema>): Promise<void> => {
+export default async (oauthProviderUuid: string, data: z.infer<typeof adminOAuthProviderUpdateSchema>): Promise<void> => {
   return new Promise((resolve, reject) => {
     axiosInstance
       .patch(`/api/admin/oauth-providers/${oauthProviderUuid}`, transformKeysToSnakeCase(data))
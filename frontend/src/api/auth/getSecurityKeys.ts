@@ -1,7 +1,7 @@
 import { axiosInstance } from '@/api/axios.ts';
 import { base64ToArrayBuffer } from '@/lib/transformers.ts';
 
-function prepareCredentialOptions(options: CredentialRequestOptions): CredentialRequestOptions {
+export function prepareCredentialOptions(options: CredentialRequestOptions): CredentialRequestOptions {
   if (!options.publicKey) {
     return options;
   }
@@ -36,7 +36,7 @@ interface Response {
   options: CredentialRequestOptions;
 }
 
-export default async (user: string): Promise<Response> => {
+export default async (user?: string): Promise<Response> => {
   return new Promise((resolve, reject) => {
     axiosInstance
       .get('/api/auth/login/security-key', { params: { user } })
@@ -1,11 +1,15 @@
 import { z } from 'zod';
 import { axiosInstance } from '@/api/axios.ts';
+import { SecurityKeyConfirmation } from '@/api/me/security-keys/confirmSecurityKey.ts';
 import { dashboardEmailSchema } from '@/lib/schemas/dashboard.ts';
 
-export default async (data: z.infer<typeof dashboardEmailSchema>): Promise<void> => {
+export default async (
+  data: z.infer<typeof dashboardEmailSchema>,
+  securityKey?: SecurityKeyConfirmation,
+): Promise<void> => {
   return new Promise((resolve, reject) => {
     axiosInstance
-      .put('/api/client/account/email', data)
+      .put('/api/client/account/email', { ...data, security_key: securityKey })
       .then(() => resolve())
       .catch(reject);
   });
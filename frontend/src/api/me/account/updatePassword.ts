@@ -1,15 +1,20 @@
 import { axiosInstance } from '@/api/axios.ts';
-import { transformKeysToSnakeCase } from '@/lib/transformers.ts';
+import { SecurityKeyConfirmation } from '@/api/me/security-keys/confirmSecurityKey.ts';
 
 interface Data {
   password: string;
   newPassword: string;
+  securityKey?: SecurityKeyConfirmation;
 }
 
 export default async (data: Data): Promise<void> => {
   return new Promise((resolve, reject) => {
     axiosInstance
-      .put('/api/client/account/password', transformKeysToSnakeCase(data))
+      .put('/api/client/account/password', {
+        password: data.password,
+        new_password: data.newPassword,
+        security_key: data.securityKey,
+      })
       .then(() => resolve())
       .catch(reject);
   });
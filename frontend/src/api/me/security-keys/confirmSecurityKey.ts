@@ -0,0 +1,22 @@
+import { axiosInstance } from '@/api/axios.ts';
+import { prepareCredentialOptions } from '../../auth/getSecurityKeys.ts';
+import { prepareCredentialForTransport } from './postSecurityKeyChallenge.ts';
+
+export interface SecurityKeyConfirmation {
+  uuid: string;
+  public_key_credential: ReturnType<typeof prepareCredentialForTransport>;
+}
+
+export default async (): Promise<SecurityKeyConfirmation> => {
+  if (!window.navigator.credentials) {
+    throw new Error('Your browser does not support passkeys.');
+  }
+
+  const { data } = await axiosInstance.get('/api/client/account/security-keys/confirm');
+  const credential = await window.navigator.credentials.get(prepareCredentialOptions(data.options));
+
+  return {
+    uuid: data.uuid,
+    public_key_credential: prepareCredentialForTransport(credential as PublicKeyCredential),
+  };
+};
@@ -20,3 +20,7 @@ export const adminOAuthProviderSchema = z.object({
   userManageable: z.boolean(),
   basicAuth: z.boolean(),
 });
+
+export const adminOAuthProviderUpdateSchema = adminOAuthProviderSchema.extend({
+  clientSecret: z.string().min(3).max(255).nullable(),
+});
@@ -83,6 +83,9 @@ export const adminSettingsServerSchema = z.object({
   allowOverwritingCustomDockerImage: z.boolean(),
   allowEditingStartupCommand: z.boolean(),
   allowViewingInstallationLogs: z.boolean(),
+  expiryNoticeDays: z.number().min(0).max(365),
+  expiryGracePeriodDays: z.number().min(0).max(3650),
+  expiryDeleteServers: z.boolean(),
 });
 
 export const adminSettingsActivitySchema = z.object({
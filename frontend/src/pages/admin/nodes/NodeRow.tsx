@@ -2,7 +2,7 @@ import { faGlobe, faHeart, faHeartBroken } from '@fortawesome/free-solid-svg-ico
 import { FontAwesomeIcon } from '@fortawesome/react-fontawesome';
 import { useEffect, useState } from 'react';
 import { NavLink } from 'react-router';
-import { axiosInstance } from '@/api/axios.ts';
+import getNodeSystem from '@/api/admin/nodes/getNodeSystem.ts';
 import Code from '@/elements/Code.tsx';
 import Spinner from '@/elements/Spinner.tsx';
 import { TableData, TableRow } from '@/elements/Table.tsx';
@@ -13,14 +13,9 @@ export default function NodeRow({ node }: { node: Node }) {
   const [version, setVersion] = useState<string | null>(null);
 
   useEffect(() => {
-    axiosInstance
-      .get(`${new URL(node.publicUrl ?? node.url).origin}/api/system`, {
-        headers: {
-          Authorization: `Bearer ${node.token}`,
-        },
-      })
-      .then(({ data }) => {
-        setVersion(data.version);
+    getNodeSystem(node.uuid)
+      .then((system) => {
+        setVersion(system.version);
       })
       .catch((msg) => {
         console.error('Error while connecting to node', msg);
@@ -60,6 +60,7 @@ export default function NodeView() {
             icon: faPenRuler,
             path: `/configuration`,
             element: <AdminNodeConfiguration node={node} />,
+            permission: 'nodes.reveal-token',
           },
           {
             name: 'Statistics',
@@ -72,6 +73,7 @@ export default function NodeView() {
             icon: faFileLines,
             path: `/logs`,
             element: <AdminNodeLogs node={node} />,
+            permission: 'nodes.reveal-token',
           },
           {
             name: 'Allocations',
@@ -2,7 +2,9 @@ import { faCopy } from '@fortawesome/free-solid-svg-icons';
 import { FontAwesomeIcon } from '@fortawesome/react-fontawesome';
 import { ActionIcon, Group, Stack, Title, Tooltip } from '@mantine/core';
 import jsYaml from 'js-yaml';
-import { useState } from 'react';
+import { useEffect, useState } from 'react';
+import revealNodeToken from '@/api/admin/nodes/revealNodeToken.ts';
+import { httpErrorToHuman } from '@/api/axios.ts';
 import Card from '@/elements/Card.tsx';
 import Code from '@/elements/Code.tsx';
 import AdminSubContentContainer from '@/elements/containers/AdminSubContentContainer.tsx';
@@ -17,6 +19,15 @@ export default function AdminNodeConfiguration({ node }: { node: Node }) {
   const [remote, setRemote] = useState(window.location.origin);
   const [apiPort, setApiPort] = useState(parseInt(new URL(node.url).port || '8080'));
   const [sftpPort, setSftpPort] = useState(node.sftpPort);
+  const [token, setToken] = useState('');
+
+  useEffect(() => {
+    revealNodeToken(node.uuid)
+      .then(({ token }) => setToken(token))
+      .catch((msg) => {
+        addToast(httpErrorToHuman(msg), 'error');
+      });
+  }, []);
 
   const getNodeConfiguration = () => {
     let origin = window.location.origin;
@@ -29,7 +40,7 @@ export default function AdminNodeConfiguration({ node }: { node: Node }) {
     return {
       uuid: node.uuid,
       token_id: node.tokenId,
-      token: node.token,
+      token,
       api: {
         port: apiPort,
         disable_openapi_docs: true,
@@ -1,6 +1,8 @@
 import { useEffect, useState } from 'react';
 import stripAnsi from 'strip-ansi';
-import { axiosInstance, httpErrorToHuman } from '@/api/axios.ts';
+import getNodeLog from '@/api/admin/nodes/getNodeLog.ts';
+import getNodeLogs, { NodeLog } from '@/api/admin/nodes/getNodeLogs.ts';
+import { httpErrorToHuman } from '@/api/axios.ts';
 import Button from '@/elements/Button.tsx';
 import AdminSubContentContainer from '@/elements/containers/AdminSubContentContainer.tsx';
 import Select from '@/elements/input/Select.tsx';
@@ -9,12 +11,6 @@ import Spinner from '@/elements/Spinner.tsx';
 import { bytesToString } from '@/lib/size.ts';
 import { useToast } from '@/providers/ToastProvider.tsx';
 
-interface NodeLog {
-  name: string;
-  size: number;
-  lastModified: Date;
-}
-
 export default function AdminNodeLogs({ node }: { node: Node }) {
   const { addToast } = useToast();
 
@@ -24,14 +20,9 @@ export default function AdminNodeLogs({ node }: { node: Node }) {
   const [loading, setLoading] = useState(false);
 
   useEffect(() => {
-    axiosInstance
-      .get(`${new URL(node.publicUrl ?? node.url).origin}/api/system/logs`, {
-        headers: {
-          Authorization: `Bearer ${node.token}`,
-        },
-      })
-      .then(({ data }) => {
-        setLogs(data.logFiles.reverse());
+    getNodeLogs(node.uuid)
+      .then((logFiles) => {
+        setLogs(logFiles.reverse());
       })
       .catch((msg) => {
         addToast(httpErrorToHuman(msg), 'error');
@@ -51,15 +42,9 @@ export default function AdminNodeLogs({ node }: { node: Node }) {
 
     setLoading(true);
 
-    axiosInstance
-      .get(`${new URL(node.publicUrl ?? node.url).origin}/api/system/logs/${selectedLog.name}`, {
-        headers: {
-          Authorization: `Bearer ${node.token}`,
-        },
-        responseType: 'blob',
-      })
-      .then(({ request }) => {
-        const fileURL = URL.createObjectURL(request.response);
+    getNodeLog(node.uuid, selectedLog.name)
+      .then((data) => {
+        const fileURL = URL.createObjectURL(new Blob([data], { type: 'text/plain' }));
         const downloadLink = document.createElement('a');
         downloadLink.href = fileURL;
         downloadLink.download = selectedLog.name.endsWith('.gz') ? selectedLog.name.slice(0, -3) : selectedLog.name;
@@ -80,14 +65,8 @@ export default function AdminNodeLogs({ node }: { node: Node }) {
 
     setLoading(true);
 
-    axiosInstance
-      .get(`${new URL(node.publicUrl ?? node.url).origin}/api/system/logs/${selectedLog.name}`, {
-        headers: {
-          Authorization: `Bearer ${node.token}`,
-        },
-        responseType: 'text',
-      })
-      .then(({ data }) => {
+    getNodeLog(node.uuid, selectedLog.name)
+      .then((data) => {
         setContent(stripAnsi(data));
       })
       .catch((msg) => {
@@ -14,7 +14,8 @@ import { FontAwesomeIcon } from '@fortawesome/react-fontawesome';
 import { Title } from '@mantine/core';
 import { useEffect, useState } from 'react';
 import { Line } from 'react-chartjs-2';
-import { axiosInstance, httpErrorToHuman } from '@/api/axios.ts';
+import getNodeStatistics from '@/api/admin/nodes/getNodeStatistics.ts';
+import { httpErrorToHuman } from '@/api/axios.ts';
 import Card from '@/elements/Card.tsx';
 import ChartBlock from '@/elements/ChartBlock.tsx';
 import AdminSubContentContainer from '@/elements/containers/AdminSubContentContainer.tsx';
@@ -108,14 +109,9 @@ export default function AdminNodeStatistics({ node }: { node: Node }) {
 
   useEffect(() => {
     const run = () => {
-      axiosInstance
-        .get(`${new URL(node.publicUrl ?? node.url).origin}/api/system/stats`, {
-          headers: {
-            Authorization: `Bearer ${node.token}`,
-          },
-        })
-        .then(({ data }) => {
-          setStats(data.stats);
+      getNodeStatistics(node.uuid)
+        .then((stats) => {
+          setStats(stats);
         })
         .catch((msg) => {
           addToast(httpErrorToHuman(msg), 'error');
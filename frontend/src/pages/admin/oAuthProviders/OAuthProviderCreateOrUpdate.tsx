@@ -20,7 +20,7 @@ import TagsInput from '@/elements/input/TagsInput.tsx';
 import TextArea from '@/elements/input/TextArea.tsx';
 import TextInput from '@/elements/input/TextInput.tsx';
 import ConfirmationModal from '@/elements/modals/ConfirmationModal.tsx';
-import { adminOAuthProviderSchema } from '@/lib/schemas/admin/oauthProviders.ts';
+import { adminOAuthProviderSchema, adminOAuthProviderUpdateSchema } from '@/lib/schemas/admin/oauthProviders.ts';
 import { transformKeysToSnakeCase } from '@/lib/transformers.ts';
 import { useResourceForm } from '@/plugins/useResourceForm.ts';
 import { useToast } from '@/providers/ToastProvider.tsx';
@@ -36,7 +36,7 @@ export default function OAuthProviderCreateOrUpdate({
 
   const [openModal, setOpenModal] = useState<'delete' | null>(null);
 
-  const form = useForm<z.infer<typeof adminOAuthProviderSchema>>({
+  const form = useForm<z.infer<typeof adminOAuthProviderUpdateSchema>>({
     initialValues: {
       name: '',
       description: null,
@@ -58,11 +58,11 @@ export default function OAuthProviderCreateOrUpdate({
       basicAuth: false,
     },
     validateInputOnBlur: true,
-    validate: zod4Resolver(adminOAuthProviderSchema),
+    validate: zod4Resolver(contextOAuthProvider ? adminOAuthProviderUpdateSchema : adminOAuthProviderSchema),
   });
 
   const { loading, doCreateOrUpdate, doDelete } = useResourceForm<
-    z.infer<typeof adminOAuthProviderSchema>,
+    z.infer<typeof adminOAuthProviderUpdateSchema>,
     AdminOAuthProvider
   >({
     form,
@@ -80,7 +80,7 @@ export default function OAuthProviderCreateOrUpdate({
         name: contextOAuthProvider.name,
         description: contextOAuthProvider.description,
         clientId: contextOAuthProvider.clientId,
-        clientSecret: contextOAuthProvider.clientSecret,
+        clientSecret: null,
         authUrl: contextOAuthProvider.authUrl,
         tokenUrl: contextOAuthProvider.tokenUrl,
         infoUrl: contextOAuthProvider.infoUrl,
@@ -183,6 +183,7 @@ export default function OAuthProviderCreateOrUpdate({
               placeholder='Client Secret'
               type='password'
               {...form.getInputProps('clientSecret')}
+              onChange={(e) => form.setFieldValue('clientSecret', e.target.value || null)}
             />
           </Group>
 
@@ -30,6 +30,7 @@ export default function RoleCreateOrUpdate({ contextRole }: { contextRole?: Role
       name: '',
       description: null,
       requireTwoFactor: false,
+      requirePasskey: false,
       adminPermissions: [],
       serverPermissions: [],
     },
@@ -52,6 +53,8 @@ export default function RoleCreateOrUpdate({ contextRole }: { contextRole?: Role
       form.setValues({
         name: contextRole.name,
         description: contextRole.description,
+        requireTwoFactor: contextRole.requireTwoFactor,
+        requirePasskey: contextRole.requirePasskey,
         adminPermissions: contextRole.adminPermissions,
         serverPermissions: contextRole.serverPermissions,
       });
@@ -95,6 +98,12 @@ export default function RoleCreateOrUpdate({ contextRole }: { contextRole?: Role
             {...form.getInputProps('requireTwoFactor', { type: 'checkbox' })}
           />
 
+          <Switch
+            label='Require Passkey'
+            description='Require users with this role to log in with a passkey instead of their password.'
+            {...form.getInputProps('requirePasskey', { type: 'checkbox' })}
+          />
+
           <Group grow align='normal'>
             {availablePermissions?.serverPermissions && (
               <PermissionSelector
@@ -30,6 +30,9 @@ export default function ServerContainer() {
       allowOverwritingCustomDockerImage: false,
       allowEditingStartupCommand: false,
       allowViewingInstallationLogs: false,
+      expiryNoticeDays: 0,
+      expiryGracePeriodDays: 0,
+      expiryDeleteServers: false,
     },
     validateInputOnBlur: true,
     validate: zod4Resolver(adminSettingsServerSchema),
@@ -114,6 +117,33 @@ export default function ServerContainer() {
           {...form.getInputProps('allowViewingInstallationLogs', { type: 'checkbox' })}
         />
 
+        <Group grow mt='md'>
+          <NumberInput
+            withAsterisk
+            label='Expiry Notice Days'
+            description='How many days before a server expires its owner is notified by email, 0 disables the notice.'
+            min={0}
+            max={365}
+            {...form.getInputProps('expiryNoticeDays')}
+          />
+
+          <NumberInput
+            withAsterisk
+            label='Expiry Grace Period Days'
+            description='How many days an expired server stays suspended before it can be deleted.'
+            min={0}
+            max={3650}
+            {...form.getInputProps('expiryGracePeriodDays')}
+          />
+        </Group>
+
+        <Switch
+          mt='md'
+          label='Delete Expired Servers'
+          description='If enabled, expired servers are deleted automatically once their grace period is over.'
+          {...form.getInputProps('expiryDeleteServers', { type: 'checkbox' })}
+        />
+
         <Group mt='md'>
           <AdminCan
             action='settings.update'
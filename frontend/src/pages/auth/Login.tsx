@@ -91,18 +91,31 @@ export default function Login() {
       .finally(() => setLoading(false));
   };
 
-  const doPasskeyAuth = () => {
+  const doDiscoverablePasskeyAuth = () => {
+    setLoading(true);
+    setError('');
+
+    getSecurityKeys()
+      .then((keys) => doPasskeyAuth(keys.uuid, keys.options))
+      .catch((err) => {
+        setError(httpErrorToHuman(err));
+        setLoading(false);
+      });
+  };
+
+  const doPasskeyAuth = (uuid = passkeyUuid, options = passkeyOptions) => {
     setLoading(true);
 
     if (!window.navigator.credentials) {
       setError('Your browser does not support passkeys.');
+      setLoading(false);
       return;
     }
 
     window.navigator.credentials
-      .get(passkeyOptions)
+      .get(options)
       .then((credential) => {
-        postSecurityKeyChallenge(passkeyUuid, credential as PublicKeyCredential)
+        postSecurityKeyChallenge(uuid, credential as PublicKeyCredential)
           .then((response) => {
             doLogin(response.user);
           })
@@ -231,6 +244,16 @@ export default function Login() {
 
               <Divider label='OR' labelPosition='center' />
 
+              <Button
+                variant='light'
+                onClick={doDiscoverablePasskeyAuth}
+                loading={loading}
+                leftSection={<FontAwesomeIcon icon={faFingerprint} />}
+                size='md'
+                fullWidth
+              >
+                Sign in with Passkey
+              </Button>
               {settings.app.registrationEnabled && (
                 <Button variant='light' onClick={() => navigate('/auth/register')} size='md' fullWidth>
                   Register
@@ -261,7 +284,7 @@ export default function Login() {
               </Text>
 
               <Button
-                onClick={doPasskeyAuth}
+                onClick={() => doPasskeyAuth()}
                 loading={loading}
                 leftSection={<FontAwesomeIcon icon={faFingerprint} />}
                 size='md'
@@ -7,6 +7,7 @@ import { useEffect, useState } from 'react';
 import { z } from 'zod';
 import { httpErrorToHuman } from '@/api/axios.ts';
 import updateEmail from '@/api/me/account/updateEmail.ts';
+import confirmSecurityKey from '@/api/me/security-keys/confirmSecurityKey.ts';
 import Button from '@/elements/Button.tsx';
 import PasswordInput from '@/elements/input/PasswordInput.tsx';
 import TextInput from '@/elements/input/TextInput.tsx';
@@ -42,7 +43,9 @@ export default function EmailContainer({ blurred }: AccountCardProps) {
   const doUpdate = () => {
     setLoading(true);
 
-    updateEmail(form.values)
+    // accounts without a password confirm the change with one of their passkeys
+    (user?.hasPassword ? Promise.resolve(undefined) : confirmSecurityKey())
+      .then((securityKey) => updateEmail(form.values, securityKey))
       .then(() => {
         addToast(t('pages.account.account.containers.email.toast.updated', {}), 'success');
 
@@ -71,13 +74,15 @@ export default function EmailContainer({ blurred }: AccountCardProps) {
               autoComplete='email'
               {...form.getInputProps('email')}
             />
-            <PasswordInput
-              withAsterisk
-              label={t('pages.account.account.containers.email.form.currentPassword', {})}
-              placeholder={t('pages.account.account.containers.email.form.currentPassword', {})}
-              autoComplete='current-password'
-              {...form.getInputProps('password')}
-            />
+            {user?.hasPassword && (
+              <PasswordInput
+                withAsterisk
+                label={t('pages.account.account.containers.email.form.currentPassword', {})}
+                placeholder={t('pages.account.account.containers.email.form.currentPassword', {})}
+                autoComplete='current-password'
+                {...form.getInputProps('password')}
+              />
+            )}
           </Stack>
           <Group className='mt-auto pt-4'>
             <Button type='submit' disabled={!form.isValid()} loading={loading}>
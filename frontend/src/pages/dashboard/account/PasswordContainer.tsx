@@ -7,10 +7,12 @@ import { useState } from 'react';
 import { z } from 'zod';
 import { httpErrorToHuman } from '@/api/axios.ts';
 import updatePassword from '@/api/me/account/updatePassword.ts';
+import confirmSecurityKey from '@/api/me/security-keys/confirmSecurityKey.ts';
 import Button from '@/elements/Button.tsx';
 import PasswordInput from '@/elements/input/PasswordInput.tsx';
 import TitleCard from '@/elements/TitleCard.tsx';
 import { dashboardPasswordSchema } from '@/lib/schemas/dashboard.ts';
+import { useAuth } from '@/providers/AuthProvider.tsx';
 import { useToast } from '@/providers/ToastProvider.tsx';
 import { useTranslations } from '@/providers/TranslationProvider.tsx';
 import { AccountCardProps } from './DashboardAccount.tsx';
@@ -18,6 +20,7 @@ import { AccountCardProps } from './DashboardAccount.tsx';
 export default function PasswordContainer({ blurred }: AccountCardProps) {
   const { t } = useTranslations();
   const { addToast } = useToast();
+  const { user, setUser } = useAuth();
 
   const [loading, setLoading] = useState(false);
 
@@ -34,12 +37,18 @@ export default function PasswordContainer({ blurred }: AccountCardProps) {
   const doUpdate = () => {
     setLoading(true);
 
-    updatePassword({
-      password: form.values.currentPassword,
-      newPassword: form.values.newPassword,
-    })
+    // accounts without a password confirm the change with one of their passkeys
+    (user?.hasPassword ? Promise.resolve(undefined) : confirmSecurityKey())
+      .then((securityKey) =>
+        updatePassword({
+          password: form.values.currentPassword,
+          newPassword: form.values.newPassword,
+          securityKey,
+        }),
+      )
       .then(() => {
         addToast(t('pages.account.account.containers.password.toast.updated', {}), 'success');
+        setUser({ ...user!, hasPassword: true });
         form.reset();
       })
       .catch((msg) => {
@@ -57,13 +66,15 @@ export default function PasswordContainer({ blurred }: AccountCardProps) {
       >
         <form onSubmit={form.onSubmit(() => doUpdate())}>
           <Stack>
-            <PasswordInput
-              withAsterisk
-              label={t('pages.account.account.containers.password.form.currentPassword', {})}
-              placeholder={t('pages.account.account.containers.password.form.currentPassword', {})}
-              autoComplete='current-password'
-              {...form.getInputProps('currentPassword')}
-            />
+            {user?.hasPassword && (
+              <PasswordInput
+                withAsterisk
+                label={t('pages.account.account.containers.password.form.currentPassword', {})}
+                placeholder={t('pages.account.account.containers.password.form.currentPassword', {})}
+                autoComplete='current-password'
+                {...form.getInputProps('currentPassword')}
+              />
+            )}
             <PasswordInput
               withAsterisk
               label={t('pages.account.account.containers.password.form.newPassword', {})}
@@ -33,6 +33,9 @@ export const createSettingsSlice: StateCreator<AdminStore, [], [], SettingsSlice
     allowOverwritingCustomDockerImage: true,
     allowEditingStartupCommand: false,
     allowViewingInstallationLogs: true,
+    expiryNoticeDays: 3,
+    expiryGracePeriodDays: 7,
+    expiryDeleteServers: false,
   },
   webauthn: {
     rpId: '',
@@ -238,6 +238,7 @@ declare global {
     name: string;
     description: string | null;
     requireTwoFactor: boolean;
+    requirePasskey: boolean;
     serverPermissions: string[];
     adminPermissions: string[];
     created: Date;
@@ -933,9 +934,11 @@ declare global {
     uuid: string;
     role: Role;
     avatar?: string;
+    hasPassword: boolean;
     totpEnabled: boolean;
     totpLastUsed: Date | null;
     requireTwoFactor: boolean;
+    requirePasskey: boolean;
     toastPosition: UserToastPosition;
     startOnGroupedServers: boolean;
     created: Date;
@@ -1215,6 +1218,9 @@ declare global {
       allowOverwritingCustomDockerImage: boolean;
       allowEditingStartupCommand: boolean;
       allowViewingInstallationLogs: boolean;
+      expiryNoticeDays: number;
+      expiryGracePeriodDays: number;
+      expiryDeleteServers: boolean;
     };
     activity: {
       adminLogRetentionDays: number;
@@ -1,5 +1,8 @@
 use colored::Colorize;
-use sqlx::postgres::PgPoolOptions;
+use sqlx::{
+    TransactionManager,
+    postgres::{PgPoolOptions, PgTransactionManager},
+};
 use std::{collections::HashMap, fmt::Display, pin::Pin, sync::Arc};
 use tokio::sync::Mutex;
 
@@ -12,6 +15,7 @@ pub struct Database {
     read: Option<sqlx::PgPool>,
 
     encryption_key: Arc<str>,
+    previous_encryption_key: Option<Arc<str>>,
     use_decryption_cache: bool,
     batch_actions: Arc<Mutex<HashMap<(&'static str, uuid::Uuid), BatchFuture>>>,
 }
@@ -55,6 +59,7 @@ impl Database {
             },
 
             encryption_key: env.app_encryption_key.clone().into(),
+            previous_encryption_key: env.app_encryption_key_previous.clone().map(Arc::from),
             use_decryption_cache: env.app_use_decryption_cache,
             batch_actions: Arc::new(Mutex::new(HashMap::new())),
         };
@@ -106,6 +111,53 @@ impl Database {
         instance
     }
 
+    /// Connects with a single connection that runs everything inside one transaction, which is
+    /// never committed. Used for dry runs, call [`Self::rollback`] to discard the changes.
+    ///
+    /// The transaction is opened through sqlx's transaction manager, so transactions started on
+    /// top of it with [`sqlx::Pool::begin`] become savepoints and committing them does not
+    /// commit the dry run.
+    pub async fn new_dry_run(env: &crate::env::Env, cache: Arc<crate::cache::Cache>) -> Self {
+        Self {
+            cache,
+
+            write: PgPoolOptions::new()
+                .min_connections(1)
+                .max_connections(1)
+                .idle_timeout(None)
+                .max_lifetime(None)
+                .test_before_acquire(false)
+                .after_connect(|connection, _| {
+                    Box::pin(async move {
+                        PgTransactionManager::begin(connection, None).await?;
+
+                        Ok(())
+                    })
+                })
+                .connect(
+                    env.database_url_primary
+                        .as_deref()
+                        .unwrap_or(&env.database_url),
+                )
+                .await
+                .unwrap(),
+            read: None,
+
+            encryption_key: env.app_encryption_key.clone().into(),
+            previous_encryption_key: env.app_encryption_key_previous.clone().map(Arc::from),
+            use_decryption_cache: false,
+            batch_actions: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Discards everything written through a database created by [`Self::new_dry_run`].
+    pub async fn rollback(&self) -> Result<(), sqlx::Error> {
+        let mut connection = self.write.acquire().await?;
+        PgTransactionManager::rollback(&mut *connection).await?;
+
+        Ok(())
+    }
+
     pub async fn flush_batch_actions(&self) {
         let mut actions = self.batch_actions.lock().await;
         for (key, action) in actions.drain() {
@@ -187,11 +239,16 @@ impl Database {
                     30,
                     || async {
                         let encryption_key = self.encryption_key.clone();
+                        let previous_encryption_key = self.previous_encryption_key.clone();
                         let data = data.as_ref().to_vec();
 
                         tokio::task::spawn_blocking(move || {
-                            simple_crypt::decrypt(&data, encryption_key.as_bytes())
-                                .map(|s| compact_str::CompactString::from_utf8_lossy(&s))
+                            Self::decrypt_with_keys(
+                                &data,
+                                &encryption_key,
+                                previous_encryption_key.as_deref(),
+                            )
+                            .map(|s| compact_str::CompactString::from_utf8_lossy(&s))
                         })
                         .await?
                     },
@@ -200,19 +257,51 @@ impl Database {
         }
 
         let encryption_key = self.encryption_key.clone();
+        let previous_encryption_key = self.previous_encryption_key.clone();
 
         tokio::task::spawn_blocking(move || {
-            simple_crypt::decrypt(data.as_ref(), encryption_key.as_bytes())
-                .map(|s| compact_str::CompactString::from_utf8_lossy(&s))
+            Self::decrypt_with_keys(
+                data.as_ref(),
+                &encryption_key,
+                previous_encryption_key.as_deref(),
+            )
+            .map(|s| compact_str::CompactString::from_utf8_lossy(&s))
         })
         .await?
     }
 
     #[inline]
     pub fn decrypt_sync(&self, data: impl AsRef<[u8]>) -> Option<compact_str::CompactString> {
-        simple_crypt::decrypt(data.as_ref(), self.encryption_key.as_bytes())
-            .map(|s| compact_str::CompactString::from_utf8_lossy(&s))
-            .ok()
+        Self::decrypt_with_keys(
+            data.as_ref(),
+            &self.encryption_key,
+            self.previous_encryption_key.as_deref(),
+        )
+        .map(|s| compact_str::CompactString::from_utf8_lossy(&s))
+        .ok()
+    }
+
+    /// Decrypts with the given key, falling back to the previous key while a key rotation
+    /// is rolled out, so values written by instances on either key stay readable.
+    pub fn decrypt_with_keys(
+        data: &[u8],
+        encryption_key: &str,
+        previous_encryption_key: Option<&str>,
+    ) -> Result<Vec<u8>, anyhow::Error> {
+        match simple_crypt::decrypt(data, encryption_key.as_bytes()) {
+            Ok(decrypted) => Ok(decrypted),
+            Err(err) => match previous_encryption_key {
+                Some(previous_encryption_key) => {
+                    simple_crypt::decrypt(data, previous_encryption_key.as_bytes())
+                }
+                None => Err(err),
+            },
+        }
+    }
+
+    #[inline]
+    pub fn encrypt_with_key(data: &[u8], encryption_key: &str) -> Result<Vec<u8>, anyhow::Error> {
+        simple_crypt::encrypt(data, encryption_key.as_bytes())
     }
 
     #[inline]
@@ -49,6 +49,7 @@ pub struct Env {
     pub app_trusted_proxies: Vec<cidr::IpCidr>,
     pub app_log_directory: Option<String>,
     pub app_encryption_key: String,
+    pub app_encryption_key_previous: Option<String>,
     pub server_name: Option<String>,
 }
 
@@ -144,6 +145,10 @@ impl Env {
                 .expect("APP_ENCRYPTION_KEY is required")
                 .trim_matches('"')
                 .to_string(),
+            app_encryption_key_previous: std::env::var("APP_ENCRYPTION_KEY_PREVIOUS")
+                .ok()
+                .map(|s| s.trim_matches('"').to_string())
+                .filter(|s| !s.is_empty()),
             server_name: std::env::var("SERVER_NAME")
                 .ok()
                 .map(|s| s.trim_matches('"').to_string()),
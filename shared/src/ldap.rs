@@ -0,0 +1,280 @@
+use crate::{
+    models::{ByUuid, user::User},
+    response::DisplayError,
+    settings::{LdapGroupRoleMapping, LdapMode, LdapSettings},
+};
+use axum::http::StatusCode;
+use compact_str::ToCompactString;
+use rand::distr::SampleString;
+use std::{fmt::Write, sync::Arc, time::Duration};
+
+/// LDAP result code returned when a bind was attempted with wrong credentials.
+const INVALID_CREDENTIALS: u32 = 49;
+
+#[derive(Debug, Clone)]
+pub struct LdapUser {
+    pub dn: String,
+    pub identifier: compact_str::CompactString,
+
+    pub username: compact_str::CompactString,
+    pub email: compact_str::CompactString,
+    pub name_first: compact_str::CompactString,
+    pub name_last: compact_str::CompactString,
+
+    pub groups: Vec<String>,
+}
+
+impl LdapUser {
+    /// Returns the role of the first mapping whose group this user is a member of.
+    pub fn mapped_role_uuid(
+        &self,
+        group_role_mappings: &[LdapGroupRoleMapping],
+    ) -> Option<uuid::Uuid> {
+        group_role_mappings
+            .iter()
+            .find(|mapping| {
+                self.groups
+                    .iter()
+                    .any(|group| group.eq_ignore_ascii_case(&mapping.group))
+            })
+            .map(|mapping| mapping.role_uuid)
+    }
+}
+
+pub struct Ldap {
+    settings: Arc<super::settings::Settings>,
+    database: Arc<super::database::Database>,
+}
+
+impl Ldap {
+    pub fn new(
+        settings: Arc<super::settings::Settings>,
+        database: Arc<super::database::Database>,
+    ) -> Self {
+        Self { settings, database }
+    }
+
+    /// Looks up the given user in the directory and verifies their password by binding as them.
+    ///
+    /// Returns `None` if the user does not exist, the password is wrong or LDAP is disabled.
+    pub async fn authenticate(
+        mode: &LdapMode,
+        username: &str,
+        password: &str,
+    ) -> Result<Option<LdapUser>, anyhow::Error> {
+        let LdapMode::Ldap(ldap_settings) = mode else {
+            return Ok(None);
+        };
+        let LdapSettings {
+            url,
+            starttls,
+            verify_tls,
+            bind_dn,
+            bind_password,
+            search_base,
+            user_filter,
+            identifier_attribute,
+            username_attribute,
+            email_attribute,
+            name_first_attribute,
+            name_last_attribute,
+            group_attribute,
+            ..
+        } = &**ldap_settings;
+
+        // an empty password would result in an unauthenticated bind, which most servers accept
+        if username.is_empty() || password.is_empty() {
+            return Ok(None);
+        }
+
+        let (conn, mut ldap) = ldap3::LdapConnAsync::with_settings(
+            ldap3::LdapConnSettings::new()
+                .set_conn_timeout(Duration::from_secs(10))
+                .set_starttls(*starttls)
+                .set_no_tls_verify(!*verify_tls),
+            url,
+        )
+        .await?;
+        ldap3::drive!(conn);
+
+        if let Some(bind_dn) = bind_dn {
+            ldap.simple_bind(bind_dn, bind_password.as_deref().unwrap_or_default())
+                .await?
+                .success()?;
+        }
+
+        let filter = user_filter.replace("{username}", &ldap3::ldap_escape(username));
+        let (entries, _) = ldap
+            .with_timeout(Duration::from_secs(10))
+            .search(
+                search_base,
+                ldap3::Scope::Subtree,
+                &filter,
+                vec![
+                    identifier_attribute.as_str(),
+                    username_attribute.as_str(),
+                    email_attribute.as_str(),
+                    name_first_attribute.as_str(),
+                    name_last_attribute.as_str(),
+                    group_attribute.as_str(),
+                ],
+            )
+            .await?
+            .success()?;
+
+        if entries.len() != 1 {
+            ldap.unbind().await.ok();
+
+            return Ok(None);
+        }
+
+        let entry = ldap3::SearchEntry::construct(entries.into_iter().next().unwrap());
+
+        let result = ldap.simple_bind(&entry.dn, password).await?;
+        ldap.unbind().await.ok();
+
+        if result.rc == INVALID_CREDENTIALS {
+            return Ok(None);
+        }
+        result.success()?;
+
+        let attribute = |name: &str| -> Option<compact_str::CompactString> {
+            entry
+                .attrs
+                .get(name)
+                .and_then(|values| values.first())
+                .map(|value| value.to_compact_string())
+        };
+
+        let identifier = match attribute(identifier_attribute.as_str()) {
+            Some(identifier) => identifier,
+            None => match entry
+                .bin_attrs
+                .get(identifier_attribute.as_str())
+                .and_then(|values| values.first())
+            {
+                Some(bytes) => bytes.iter().fold(
+                    compact_str::CompactString::default(),
+                    |mut identifier, byte| {
+                        let _ = write!(identifier, "{byte:02x}");
+                        identifier
+                    },
+                ),
+                None => {
+                    return Err(anyhow::anyhow!(
+                        "ldap entry {} is missing identifier attribute {identifier_attribute}",
+                        entry.dn
+                    ));
+                }
+            },
+        };
+
+        let username = attribute(username_attribute.as_str()).ok_or_else(|| {
+            anyhow::anyhow!(
+                "ldap entry {} is missing username attribute {username_attribute}",
+                entry.dn
+            )
+        })?;
+        let email = attribute(email_attribute.as_str()).ok_or_else(|| {
+            anyhow::anyhow!(
+                "ldap entry {} is missing email attribute {email_attribute}",
+                entry.dn
+            )
+        })?;
+
+        Ok(Some(LdapUser {
+            identifier,
+            username,
+            email,
+            name_first: attribute(name_first_attribute.as_str()).unwrap_or_default(),
+            name_last: attribute(name_last_attribute.as_str()).unwrap_or_default(),
+            groups: entry
+                .attrs
+                .get(group_attribute.as_str())
+                .cloned()
+                .unwrap_or_default(),
+            dn: entry.dn,
+        }))
+    }
+
+    /// Authenticates the given credentials against the configured directory and returns the
+    /// matching local user, creating it on first login if enabled.
+    ///
+    /// If any group-to-role mappings are configured, the role of the user is synced on every login.
+    pub async fn login(
+        &self,
+        username: &str,
+        password: &str,
+    ) -> Result<Option<User>, anyhow::Error> {
+        let settings = self.settings.get().await?;
+        let mode = settings.ldap_mode.clone();
+        let language = settings.app.language.clone();
+        drop(settings);
+
+        let LdapMode::Ldap(ldap_settings) = &mode else {
+            return Ok(None);
+        };
+        let LdapSettings {
+            group_role_mappings,
+            create_users,
+            ..
+        } = &**ldap_settings;
+
+        let ldap_user = match Self::authenticate(&mode, username, password).await {
+            Ok(Some(ldap_user)) => ldap_user,
+            Ok(None) => return Ok(None),
+            Err(err) => {
+                tracing::error!("failed to authenticate user against ldap: {:?}", err);
+
+                return Ok(None);
+            }
+        };
+
+        let role_uuid = ldap_user.mapped_role_uuid(group_role_mappings);
+
+        let user = match User::by_ldap_identifier(&self.database, &ldap_user.identifier).await? {
+            Some(user) => user,
+            None if *create_users => {
+                let password = rand::distr::Alphanumeric.sample_string(&mut rand::rng(), 32);
+
+                let user_uuid = match User::create_ldap(
+                    &self.database,
+                    &ldap_user.identifier,
+                    role_uuid,
+                    &ldap_user.username,
+                    &ldap_user.email,
+                    &ldap_user.name_first,
+                    &ldap_user.name_last,
+                    &password,
+                    &language,
+                )
+                .await
+                {
+                    Ok(user_uuid) => user_uuid,
+                    Err(err) if err.is_unique_violation() => {
+                        // never link a directory account to an existing local account on our own,
+                        // that would hand the local account to whoever controls the directory entry
+                        return Err(DisplayError::new(
+                            "a user with this username or email already exists, sign in with its password instead",
+                        )
+                        .with_status(StatusCode::CONFLICT)
+                        .into());
+                    }
+                    Err(err) => return Err(err.into()),
+                };
+
+                return Ok(Some(User::by_uuid(&self.database, user_uuid).await?));
+            }
+            None => return Ok(None),
+        };
+
+        if !group_role_mappings.is_empty() && user.role.as_ref().map(|role| role.uuid) != role_uuid
+        {
+            User::update_role_uuid(&self.database, user.uuid, role_uuid).await?;
+
+            return Ok(Some(User::by_uuid(&self.database, user.uuid).await?));
+        }
+
+        Ok(Some(user))
+    }
+}
@@ -24,12 +24,15 @@ pub mod events;
 pub mod extensions;
 pub mod extract;
 pub mod jwt;
+pub mod ldap;
 pub mod mail;
 pub mod models;
 pub mod payload;
 pub mod permissions;
 pub mod prelude;
 pub mod response;
+pub mod saml;
+pub mod scim;
 pub mod settings;
 pub mod storage;
 pub mod telemetry;
@@ -95,6 +98,7 @@ pub struct AppState {
     pub jwt: Arc<jwt::Jwt>,
     pub storage: Arc<storage::Storage>,
     pub captcha: Arc<captcha::Captcha>,
+    pub ldap: Arc<ldap::Ldap>,
     pub mail: Arc<mail::Mail>,
     pub database: Arc<database::Database>,
     pub cache: Arc<cache::Cache>,
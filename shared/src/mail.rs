@@ -1,8 +1,240 @@
+use crate::models::mail_template::MailTemplate;
 use lettre::AsyncTransport;
+use serde::{Deserialize, Serialize};
 use std::sync::Arc;
+use utoipa::ToSchema;
 
 pub const MAIL_PASSWORD_RESET: &str = include_str!("../mails/password_reset.html");
+pub const MAIL_PASSWORD_RESET_TEXT: &str = include_str!("../mails/password_reset.txt");
 pub const MAIL_ACCOUNT_CREATED: &str = include_str!("../mails/account_created.html");
+pub const MAIL_ACCOUNT_CREATED_TEXT: &str = include_str!("../mails/account_created.txt");
+pub const MAIL_VERIFY_EMAIL: &str = include_str!("../mails/verify_email.html");
+pub const MAIL_VERIFY_EMAIL_TEXT: &str = include_str!("../mails/verify_email.txt");
+pub const MAIL_ACCOUNT_LOCKED: &str = include_str!("../mails/account_locked.html");
+pub const MAIL_ACCOUNT_LOCKED_TEXT: &str = include_str!("../mails/account_locked.txt");
+pub const MAIL_SERVER_EXPIRING: &str = include_str!("../mails/server_expiring.html");
+pub const MAIL_SERVER_EXPIRING_TEXT: &str = include_str!("../mails/server_expiring.txt");
+
+/// The mails sent by the panel, each of them can be overridden per language by a [`MailTemplate`].
+#[derive(ToSchema, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum MailTemplateIdentifier {
+    PasswordReset,
+    AccountCreated,
+    VerifyEmail,
+    AccountLocked,
+    ServerExpiring,
+}
+
+impl MailTemplateIdentifier {
+    pub const ALL: &[Self] = &[
+        Self::PasswordReset,
+        Self::AccountCreated,
+        Self::VerifyEmail,
+        Self::AccountLocked,
+        Self::ServerExpiring,
+    ];
+
+    #[inline]
+    pub fn identifier(self) -> &'static str {
+        match self {
+            Self::PasswordReset => "password_reset",
+            Self::AccountCreated => "account_created",
+            Self::VerifyEmail => "verify_email",
+            Self::AccountLocked => "account_locked",
+            Self::ServerExpiring => "server_expiring",
+        }
+    }
+
+    #[inline]
+    pub fn from_identifier(identifier: &str) -> Option<Self> {
+        Self::ALL
+            .iter()
+            .copied()
+            .find(|template| template.identifier() == identifier)
+    }
+
+    #[inline]
+    pub fn default_subject(self) -> &'static str {
+        match self {
+            Self::PasswordReset => "{{ app.name }} - Password Reset",
+            Self::AccountCreated => "{{ app.name }} - Account Created",
+            Self::VerifyEmail => "{{ app.name }} - Verify Email",
+            Self::AccountLocked => "{{ app.name }} - Account Locked",
+            Self::ServerExpiring => "{{ app.name }} - Server Expiring",
+        }
+    }
+
+    #[inline]
+    pub fn default_body_html(self) -> &'static str {
+        match self {
+            Self::PasswordReset => MAIL_PASSWORD_RESET,
+            Self::AccountCreated => MAIL_ACCOUNT_CREATED,
+            Self::VerifyEmail => MAIL_VERIFY_EMAIL,
+            Self::AccountLocked => MAIL_ACCOUNT_LOCKED,
+            Self::ServerExpiring => MAIL_SERVER_EXPIRING,
+        }
+    }
+
+    #[inline]
+    pub fn default_body_text(self) -> &'static str {
+        match self {
+            Self::PasswordReset => MAIL_PASSWORD_RESET_TEXT,
+            Self::AccountCreated => MAIL_ACCOUNT_CREATED_TEXT,
+            Self::VerifyEmail => MAIL_VERIFY_EMAIL_TEXT,
+            Self::AccountLocked => MAIL_ACCOUNT_LOCKED_TEXT,
+            Self::ServerExpiring => MAIL_SERVER_EXPIRING_TEXT,
+        }
+    }
+
+    /// Placeholder values for the variables specific to this mail, used for previews and test mails.
+    pub fn example_context(self, app_url: &str) -> serde_json::Value {
+        let reset_link = format!("{app_url}/auth/reset-password?token=example");
+
+        match self {
+            Self::PasswordReset => serde_json::json!({
+                "reset_link": reset_link,
+            }),
+            Self::AccountCreated => serde_json::json!({
+                "reset_link": reset_link,
+                "server": {
+                    "uuid": uuid::Uuid::nil(),
+                    "uuid_short": "00000000",
+                    "name": "Example Server",
+                },
+            }),
+            Self::VerifyEmail => serde_json::json!({
+                "email": "user@example.com",
+                "verify_link": format!("{app_url}/auth/verify-email?token=example"),
+            }),
+            Self::AccountLocked => serde_json::json!({
+                "ip": "127.0.0.1",
+                "failed_login_attempts": 5,
+                "locked_until": chrono::Utc::now().to_rfc2822(),
+            }),
+            Self::ServerExpiring => serde_json::json!({
+                "server": {
+                    "uuid": uuid::Uuid::nil(),
+                    "uuid_short": "00000000",
+                    "name": "Example Server",
+                },
+                "expires_at": (chrono::Utc::now() + chrono::Duration::days(3)).to_rfc2822(),
+                "delete_at": (chrono::Utc::now() + chrono::Duration::days(10)).to_rfc2822(),
+            }),
+        }
+    }
+}
+
+/// Template variables describing a user, available as `user` in every template.
+pub fn user_context(user: &crate::models::user::User) -> serde_json::Value {
+    serde_json::json!({
+        "uuid": user.uuid,
+        "username": user.username,
+        "email": user.email,
+        "name_first": user.name_first,
+        "name_last": user.name_last,
+        "language": user.language,
+    })
+}
+
+/// Template variables describing a server, available as `server` in templates sent about one.
+pub fn server_context(server: &crate::models::server::Server) -> serde_json::Value {
+    serde_json::json!({
+        "uuid": server.uuid,
+        "uuid_short": format!("{:08x}", server.uuid_short),
+        "name": server.name,
+    })
+}
+
+pub struct RenderedMail {
+    pub subject: String,
+    pub body_html: String,
+    pub body_text: Option<String>,
+}
+
+impl RenderedMail {
+    fn environment<'a>(
+        subject: &'a str,
+        body_html: &'a str,
+        body_text: Option<&'a str>,
+    ) -> Result<minijinja::Environment<'a>, minijinja::Error> {
+        let mut environment = minijinja::Environment::new();
+        environment.set_auto_escape_callback(|name| {
+            if name == "body_html" {
+                minijinja::AutoEscape::Html
+            } else {
+                minijinja::AutoEscape::None
+            }
+        });
+
+        environment.add_template("subject", subject)?;
+        environment.add_template("body_html", body_html)?;
+        if let Some(body_text) = body_text {
+            environment.add_template("body_text", body_text)?;
+        }
+
+        Ok(environment)
+    }
+
+    /// Checks the syntax of the parts of a template without rendering them,
+    /// the variables available depend on the mail the template is used for.
+    pub fn check(
+        subject: &str,
+        body_html: &str,
+        body_text: Option<&str>,
+    ) -> Result<(), minijinja::Error> {
+        Self::environment(subject, body_html, body_text)?;
+
+        Ok(())
+    }
+
+    /// Renders the parts of a template, the html body is escaped and the others are not.
+    pub fn render(
+        subject: &str,
+        body_html: &str,
+        body_text: Option<&str>,
+        context: &serde_json::Value,
+    ) -> Result<Self, minijinja::Error> {
+        let environment = Self::environment(subject, body_html, body_text)?;
+
+        Ok(Self {
+            subject: environment
+                .get_template("subject")?
+                .render(context)?
+                .trim()
+                .to_string(),
+            body_html: environment.get_template("body_html")?.render(context)?,
+            body_text: match body_text {
+                Some(_) => Some(environment.get_template("body_text")?.render(context)?),
+                None => None,
+            },
+        })
+    }
+
+    fn into_message(
+        self,
+        destination: &str,
+        from_address: &str,
+        from_name: Option<compact_str::CompactString>,
+    ) -> Result<lettre::message::Message, anyhow::Error> {
+        let builder = lettre::message::Message::builder()
+            .subject(self.subject)
+            .to(lettre::message::Mailbox::new(None, destination.parse()?))
+            .from(lettre::message::Mailbox::new(
+                from_name.map(String::from),
+                from_address.parse()?,
+            ));
+
+        Ok(match self.body_text {
+            Some(body_text) => builder.multipart(
+                lettre::message::MultiPart::alternative_plain_html(body_text, self.body_html),
+            )?,
+            None => builder
+                .header(lettre::message::header::ContentType::TEXT_HTML)
+                .body(self.body_html)?,
+        })
+    }
+}
 
 #[derive(Debug)]
 enum Transport {
@@ -26,11 +258,15 @@ enum Transport {
 
 pub struct Mail {
     settings: Arc<super::settings::Settings>,
+    database: Arc<super::database::Database>,
 }
 
 impl Mail {
-    pub fn new(settings: Arc<super::settings::Settings>) -> Self {
-        Self { settings }
+    pub fn new(
+        settings: Arc<super::settings::Settings>,
+        database: Arc<super::database::Database>,
+    ) -> Self {
+        Self { settings, database }
     }
 
     async fn get_transport(&self) -> Result<Transport, anyhow::Error> {
@@ -111,12 +347,140 @@ impl Mail {
         }
     }
 
-    pub async fn send(
+    /// Renders a mail in the language of its recipient, falling back to the panel language and
+    /// then to the built in template.
+    pub async fn render(
+        &self,
+        template: MailTemplateIdentifier,
+        language: &str,
+        context: serde_json::Value,
+    ) -> Result<RenderedMail, anyhow::Error> {
+        let app_language = self.settings.get().await?.app.language.clone();
+
+        let mail_template = MailTemplate::by_identifier_languages(
+            &self.database,
+            template.identifier(),
+            &[language, app_language.as_str()],
+        )
+        .await?;
+
+        match mail_template {
+            Some(mail_template) => {
+                self.render_raw(
+                    &mail_template.subject,
+                    &mail_template.body_html,
+                    mail_template.body_text.as_deref(),
+                    context,
+                )
+                .await
+            }
+            None => {
+                self.render_raw(
+                    template.default_subject(),
+                    template.default_body_html(),
+                    Some(template.default_body_text()),
+                    context,
+                )
+                .await
+            }
+        }
+    }
+
+    /// Renders the given template parts, the `app` variable is always added to the context.
+    pub async fn render_raw(
+        &self,
+        subject: &str,
+        body_html: &str,
+        body_text: Option<&str>,
+        mut context: serde_json::Value,
+    ) -> Result<RenderedMail, anyhow::Error> {
+        let settings = self.settings.get().await?;
+        if let Some(context) = context.as_object_mut() {
+            context.insert(
+                "app".into(),
+                serde_json::json!({
+                    "name": settings.app.name,
+                    "url": settings.app.url,
+                    "language": settings.app.language,
+                }),
+            );
+        }
+        drop(settings);
+
+        Ok(RenderedMail::render(
+            subject, body_html, body_text, &context,
+        )?)
+    }
+
+    /// Renders and sends a mail in the background, failures are only logged.
+    pub async fn send_template(
         &self,
         destination: compact_str::CompactString,
-        subject: compact_str::CompactString,
-        body: String,
+        template: MailTemplateIdentifier,
+        language: &str,
+        context: serde_json::Value,
     ) {
+        match self.render(template, language, context).await {
+            Ok(mail) => self.send(destination, mail).await,
+            Err(err) => tracing::error!(
+                template = template.identifier(),
+                "failed to render mail template: {:?}",
+                err
+            ),
+        }
+    }
+
+    /// Sends a mail and waits for the transport to accept it.
+    pub async fn try_send(
+        &self,
+        destination: compact_str::CompactString,
+        mail: RenderedMail,
+    ) -> Result<(), anyhow::Error> {
+        let transport = self.get_transport().await?;
+
+        Self::deliver(transport, destination, mail).await
+    }
+
+    async fn deliver(
+        transport: Transport,
+        destination: compact_str::CompactString,
+        mail: RenderedMail,
+    ) -> Result<(), anyhow::Error> {
+        match transport {
+            Transport::None => {}
+            Transport::Smtp {
+                transport,
+                from_address,
+                from_name,
+            } => {
+                transport
+                    .send(mail.into_message(&destination, &from_address, from_name)?)
+                    .await?;
+            }
+            Transport::Sendmail {
+                transport,
+                from_address,
+                from_name,
+            } => {
+                transport
+                    .send(mail.into_message(&destination, &from_address, from_name)?)
+                    .await?;
+            }
+            Transport::Filesystem {
+                transport,
+                from_address,
+                from_name,
+            } => {
+                transport
+                    .send(mail.into_message(&destination, &from_address, from_name)?)
+                    .await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    pub async fn send(&self, destination: compact_str::CompactString, mail: RenderedMail) {
         let transport = match self.get_transport().await {
             Ok(transport) => transport,
             Err(err) => {
@@ -128,77 +492,12 @@ impl Mail {
         tracing::debug!(
             transport = ?transport,
             destination = ?destination,
-            subject = ?subject,
+            subject = ?mail.subject,
             "sending email"
         );
 
         tokio::spawn(async move {
-            let run = async || -> Result<(), anyhow::Error> {
-                match transport {
-                    Transport::None => {}
-                    Transport::Smtp {
-                        transport,
-                        from_address,
-                        from_name,
-                    } => {
-                        transport
-                            .send(
-                                lettre::message::Message::builder()
-                                    .subject(subject)
-                                    .to(lettre::message::Mailbox::new(None, destination.parse()?))
-                                    .from(lettre::message::Mailbox::new(
-                                        from_name.map(String::from),
-                                        from_address.parse()?,
-                                    ))
-                                    .header(lettre::message::header::ContentType::TEXT_HTML)
-                                    .body(body)?,
-                            )
-                            .await?;
-                    }
-                    Transport::Sendmail {
-                        transport,
-                        from_address,
-                        from_name,
-                    } => {
-                        transport
-                            .send(
-                                lettre::message::Message::builder()
-                                    .subject(subject)
-                                    .to(lettre::message::Mailbox::new(None, destination.parse()?))
-                                    .from(lettre::message::Mailbox::new(
-                                        from_name.map(String::from),
-                                        from_address.parse()?,
-                                    ))
-                                    .header(lettre::message::header::ContentType::TEXT_HTML)
-                                    .body(body)?,
-                            )
-                            .await?;
-                    }
-                    Transport::Filesystem {
-                        transport,
-                        from_address,
-                        from_name,
-                    } => {
-                        transport
-                            .send(
-                                lettre::message::Message::builder()
-                                    .subject(subject)
-                                    .to(lettre::message::Mailbox::new(None, destination.parse()?))
-                                    .from(lettre::message::Mailbox::new(
-                                        from_name.map(String::from),
-                                        from_address.parse()?,
-                                    ))
-                                    .header(lettre::message::header::ContentType::TEXT_HTML)
-                                    .body(body)?,
-                            )
-                            .await?;
-                    }
-                }
-
-                Ok(())
-            };
-
-            match run().await {
+            match Self::deliver(transport, destination, mail).await {
                 Ok(_) => tracing::debug!("email sent successfully"),
                 Err(err) => tracing::error!("failed to send email: {:?}", err),
             }
@@ -48,6 +48,12 @@ impl BackupConfigsS3 {
         self.secret_key = "".into();
     }
 
+    pub fn restore_censored(&mut self, existing: &Self) {
+        if self.secret_key.is_empty() {
+            self.secret_key = existing.secret_key.clone();
+        }
+    }
+
     pub fn into_client(self) -> Result<Box<s3::Bucket>, s3::error::S3Error> {
         let mut bucket = s3::Bucket::new(
             &self.bucket,
@@ -107,10 +113,21 @@ impl BackupConfigsRestic {
         Ok(())
     }
 
+    /// Blanks every environment value, any of them may hold credentials such as a cloud
+    /// storage key or a repository url with a password in it.
     pub fn censor(&mut self) {
+        for value in self.environment.values_mut() {
+            *value = "".into();
+        }
+    }
+
+    /// Fills the values left blank by [`Self::censor`] with their stored values.
+    pub fn restore_censored(&mut self, existing: &Self) {
         for (key, value) in self.environment.iter_mut() {
-            if key == "RESTIC_PASSWORD" || key == "AWS_SECRET_ACCESS_KEY" {
-                *value = "".into();
+            if value.is_empty()
+                && let Some(existing_value) = existing.environment.get(key)
+            {
+                *value = existing_value.clone();
             }
         }
     }
@@ -163,6 +180,16 @@ impl BackupConfigs {
             restic.censor();
         }
     }
+
+    /// Fills secrets left blank by [`Self::censor`] from the (decrypted) existing configs.
+    pub fn restore_censored(&mut self, existing: &Self) {
+        if let (Some(s3), Some(existing_s3)) = (&mut self.s3, &existing.s3) {
+            s3.restore_censored(existing_s3);
+        }
+        if let (Some(restic), Some(existing_restic)) = (&mut self.restic, &existing.restic) {
+            restic.restore_censored(existing_restic);
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize, Clone)]
@@ -308,12 +335,14 @@ impl BackupConfiguration {
         })
     }
 
+    /// Secrets in the backup configs are censored, use [`BackupConfigs::decrypt`] to read them.
     #[inline]
     pub async fn into_admin_api_object(
         mut self,
         database: &crate::database::Database,
     ) -> Result<AdminApiBackupConfiguration, crate::database::DatabaseError> {
         self.backup_configs.decrypt(database).await?;
+        self.backup_configs.censor();
 
         Ok(AdminApiBackupConfiguration {
             uuid: self.uuid,
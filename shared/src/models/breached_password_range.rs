@@ -0,0 +1,159 @@
+use crate::prelude::*;
+use serde::{Deserialize, Serialize};
+use sqlx::{Row, postgres::PgRow};
+use std::collections::BTreeMap;
+
+/// A range of breached password hashes sharing the first five characters of their SHA-1,
+/// in the same k-anonymity format the public breached password services use.
+#[derive(Serialize, Deserialize)]
+pub struct BreachedPasswordRange {
+    pub prefix: compact_str::CompactString,
+    pub suffixes: Vec<compact_str::CompactString>,
+
+    pub created: chrono::NaiveDateTime,
+}
+
+impl BaseModel for BreachedPasswordRange {
+    const NAME: &'static str = "breached_password_range";
+
+    #[inline]
+    fn columns(prefix: Option<&str>) -> BTreeMap<&'static str, compact_str::CompactString> {
+        let prefix = prefix.unwrap_or_default();
+
+        BTreeMap::from([
+            (
+                "breached_password_ranges.prefix",
+                compact_str::format_compact!("{prefix}prefix"),
+            ),
+            (
+                "breached_password_ranges.suffixes",
+                compact_str::format_compact!("{prefix}suffixes"),
+            ),
+            (
+                "breached_password_ranges.created",
+                compact_str::format_compact!("{prefix}created"),
+            ),
+        ])
+    }
+
+    #[inline]
+    fn map(prefix: Option<&str>, row: &PgRow) -> Result<Self, crate::database::DatabaseError> {
+        let prefix = prefix.unwrap_or_default();
+
+        Ok(Self {
+            prefix: row.try_get(compact_str::format_compact!("{prefix}prefix").as_str())?,
+            suffixes: row.try_get(compact_str::format_compact!("{prefix}suffixes").as_str())?,
+            created: row.try_get(compact_str::format_compact!("{prefix}created").as_str())?,
+        })
+    }
+}
+
+impl BreachedPasswordRange {
+    /// Parses a range body, one `SUFFIX` or `SUFFIX:COUNT` per line.
+    pub fn parse_suffixes(body: &str) -> Result<Vec<compact_str::CompactString>, anyhow::Error> {
+        let mut suffixes = Vec::new();
+
+        for line in body.lines() {
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            let suffix = line.split_once(':').map_or(line, |(suffix, _)| suffix);
+            if suffix.len() != 35 || !suffix.chars().all(|c| c.is_ascii_hexdigit()) {
+                return Err(crate::response::DisplayError::new(format!(
+                    "invalid hash suffix in range: {}",
+                    crate::utils::slice_up_to(suffix, 40)
+                ))
+                .into());
+            }
+
+            suffixes.push(suffix.to_ascii_uppercase().into());
+        }
+
+        suffixes.sort_unstable();
+        suffixes.dedup();
+
+        Ok(suffixes)
+    }
+
+    pub async fn create_or_replace(
+        database: &crate::database::Database,
+        prefix: &str,
+        suffixes: &[compact_str::CompactString],
+    ) -> Result<Self, crate::database::DatabaseError> {
+        let row = sqlx::query(&format!(
+            r#"
+            INSERT INTO breached_password_ranges (prefix, suffixes)
+            VALUES ($1, $2)
+            ON CONFLICT (prefix) DO UPDATE SET
+                suffixes = EXCLUDED.suffixes,
+                created = NOW()
+            RETURNING {}
+            "#,
+            Self::columns_sql(None)
+        ))
+        .bind(prefix.to_ascii_uppercase())
+        .bind(suffixes)
+        .fetch_one(database.write())
+        .await?;
+
+        Self::map(None, &row)
+    }
+
+    /// Returns the amount of ranges and the total amount of hashes in them.
+    pub async fn count(
+        database: &crate::database::Database,
+    ) -> Result<(i64, i64), crate::database::DatabaseError> {
+        let row = sqlx::query(
+            r#"
+            SELECT
+                COUNT(*) AS ranges,
+                COALESCE(SUM(cardinality(breached_password_ranges.suffixes)), 0)::bigint AS hashes
+            FROM breached_password_ranges
+            "#,
+        )
+        .fetch_one(database.read())
+        .await?;
+
+        Ok((row.try_get("ranges")?, row.try_get("hashes")?))
+    }
+
+    pub async fn delete_all(
+        database: &crate::database::Database,
+    ) -> Result<u64, crate::database::DatabaseError> {
+        let result = sqlx::query(
+            r#"
+            DELETE FROM breached_password_ranges
+            "#,
+        )
+        .execute(database.write())
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// Whether the SHA-1 of the password is in one of the uploaded ranges, hashed by the database
+    /// so the check never leaves the panel.
+    pub async fn contains_password(
+        database: &crate::database::Database,
+        password: &str,
+    ) -> Result<bool, crate::database::DatabaseError> {
+        let row = sqlx::query(
+            r#"
+            SELECT 1
+            FROM breached_password_ranges, (
+                SELECT upper(encode(digest($1, 'sha1'), 'hex')) AS hash
+            ) password
+            WHERE
+                breached_password_ranges.prefix = left(password.hash, 5)
+                AND substring(password.hash FROM 6)::char(35) = ANY(breached_password_ranges.suffixes)
+            "#,
+        )
+        .bind(password)
+        .fetch_optional(database.read())
+        .await?;
+
+        Ok(row.is_some())
+    }
+}
@@ -0,0 +1,254 @@
+use crate::prelude::*;
+use serde::{Deserialize, Serialize};
+use sqlx::{Row, postgres::PgRow};
+use std::{
+    collections::BTreeMap,
+    sync::{Arc, LazyLock},
+};
+use utoipa::ToSchema;
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct MailTemplate {
+    pub uuid: uuid::Uuid,
+
+    pub identifier: compact_str::CompactString,
+    pub language: compact_str::CompactString,
+
+    pub subject: String,
+    pub body_html: String,
+    pub body_text: Option<String>,
+
+    pub created: chrono::NaiveDateTime,
+}
+
+impl BaseModel for MailTemplate {
+    const NAME: &'static str = "mail_template";
+
+    #[inline]
+    fn columns(prefix: Option<&str>) -> BTreeMap<&'static str, compact_str::CompactString> {
+        let prefix = prefix.unwrap_or_default();
+
+        BTreeMap::from([
+            (
+                "mail_templates.uuid",
+                compact_str::format_compact!("{prefix}uuid"),
+            ),
+            (
+                "mail_templates.identifier",
+                compact_str::format_compact!("{prefix}identifier"),
+            ),
+            (
+                "mail_templates.language",
+                compact_str::format_compact!("{prefix}language"),
+            ),
+            (
+                "mail_templates.subject",
+                compact_str::format_compact!("{prefix}subject"),
+            ),
+            (
+                "mail_templates.body_html",
+                compact_str::format_compact!("{prefix}body_html"),
+            ),
+            (
+                "mail_templates.body_text",
+                compact_str::format_compact!("{prefix}body_text"),
+            ),
+            (
+                "mail_templates.created",
+                compact_str::format_compact!("{prefix}created"),
+            ),
+        ])
+    }
+
+    #[inline]
+    fn map(prefix: Option<&str>, row: &PgRow) -> Result<Self, crate::database::DatabaseError> {
+        let prefix = prefix.unwrap_or_default();
+
+        Ok(Self {
+            uuid: row.try_get(compact_str::format_compact!("{prefix}uuid").as_str())?,
+            identifier: row.try_get(compact_str::format_compact!("{prefix}identifier").as_str())?,
+            language: row.try_get(compact_str::format_compact!("{prefix}language").as_str())?,
+            subject: row.try_get(compact_str::format_compact!("{prefix}subject").as_str())?,
+            body_html: row.try_get(compact_str::format_compact!("{prefix}body_html").as_str())?,
+            body_text: row.try_get(compact_str::format_compact!("{prefix}body_text").as_str())?,
+            created: row.try_get(compact_str::format_compact!("{prefix}created").as_str())?,
+        })
+    }
+}
+
+impl MailTemplate {
+    pub async fn create(
+        database: &crate::database::Database,
+        identifier: &str,
+        language: &str,
+        subject: &str,
+        body_html: &str,
+        body_text: Option<&str>,
+    ) -> Result<Self, crate::database::DatabaseError> {
+        let row = sqlx::query(&format!(
+            r#"
+            INSERT INTO mail_templates (identifier, language, subject, body_html, body_text)
+            VALUES ($1, $2, $3, $4, $5)
+            RETURNING {}
+            "#,
+            Self::columns_sql(None)
+        ))
+        .bind(identifier)
+        .bind(language)
+        .bind(subject)
+        .bind(body_html)
+        .bind(body_text)
+        .fetch_one(database.write())
+        .await?;
+
+        Self::map(None, &row)
+    }
+
+    pub async fn all_with_pagination(
+        database: &crate::database::Database,
+        page: i64,
+        per_page: i64,
+        search: Option<&str>,
+    ) -> Result<super::Pagination<Self>, crate::database::DatabaseError> {
+        let offset = (page - 1) * per_page;
+
+        let rows = sqlx::query(&format!(
+            r#"
+            SELECT {}, COUNT(*) OVER() AS total_count
+            FROM mail_templates
+            WHERE $1 IS NULL OR mail_templates.identifier ILIKE '%' || $1 || '%' OR mail_templates.subject ILIKE '%' || $1 || '%'
+            ORDER BY mail_templates.identifier, mail_templates.language
+            LIMIT $2 OFFSET $3
+            "#,
+            Self::columns_sql(None)
+        ))
+        .bind(search)
+        .bind(per_page)
+        .bind(offset)
+        .fetch_all(database.read())
+        .await?;
+
+        Ok(super::Pagination {
+            total: rows
+                .first()
+                .map_or(Ok(0), |row| row.try_get("total_count"))?,
+            per_page,
+            page,
+            data: rows
+                .into_iter()
+                .map(|row| Self::map(None, &row))
+                .try_collect_vec()?,
+        })
+    }
+
+    /// Returns the variant of a template for the first of the given languages that has one.
+    pub async fn by_identifier_languages(
+        database: &crate::database::Database,
+        identifier: &str,
+        languages: &[&str],
+    ) -> Result<Option<Self>, crate::database::DatabaseError> {
+        let row = sqlx::query(&format!(
+            r#"
+            SELECT {}
+            FROM mail_templates
+            WHERE mail_templates.identifier = $1 AND mail_templates.language = ANY($2)
+            ORDER BY array_position($2, mail_templates.language::text)
+            LIMIT 1
+            "#,
+            Self::columns_sql(None)
+        ))
+        .bind(identifier)
+        .bind(languages)
+        .fetch_optional(database.read())
+        .await?;
+
+        row.try_map(|row| Self::map(None, &row))
+    }
+
+    #[inline]
+    pub fn into_admin_api_object(self) -> AdminApiMailTemplate {
+        AdminApiMailTemplate {
+            uuid: self.uuid,
+            identifier: self.identifier,
+            language: self.language,
+            subject: self.subject,
+            body_html: self.body_html,
+            body_text: self.body_text,
+            created: self.created.and_utc(),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl ByUuid for MailTemplate {
+    async fn by_uuid(
+        database: &crate::database::Database,
+        uuid: uuid::Uuid,
+    ) -> Result<Self, crate::database::DatabaseError> {
+        let row = sqlx::query(&format!(
+            r#"
+            SELECT {}
+            FROM mail_templates
+            WHERE mail_templates.uuid = $1
+            "#,
+            Self::columns_sql(None)
+        ))
+        .bind(uuid)
+        .fetch_one(database.read())
+        .await?;
+
+        Self::map(None, &row)
+    }
+}
+
+#[async_trait::async_trait]
+impl DeletableModel for MailTemplate {
+    type DeleteOptions = ();
+
+    fn get_delete_handlers() -> &'static LazyLock<DeleteListenerList<Self>> {
+        static DELETE_LISTENERS: LazyLock<DeleteListenerList<MailTemplate>> =
+            LazyLock::new(|| Arc::new(ModelHandlerList::default()));
+
+        &DELETE_LISTENERS
+    }
+
+    async fn delete(
+        &self,
+        state: &crate::State,
+        options: Self::DeleteOptions,
+    ) -> Result<(), anyhow::Error> {
+        let mut transaction = state.database.write().begin().await?;
+
+        self.run_delete_handlers(&options, state, &mut transaction)
+            .await?;
+
+        sqlx::query(
+            r#"
+            DELETE FROM mail_templates
+            WHERE mail_templates.uuid = $1
+            "#,
+        )
+        .bind(self.uuid)
+        .execute(&mut *transaction)
+        .await?;
+
+        transaction.commit().await?;
+
+        Ok(())
+    }
+}
+
+#[derive(ToSchema, Serialize)]
+#[schema(title = "AdminMailTemplate")]
+pub struct AdminApiMailTemplate {
+    pub uuid: uuid::Uuid,
+
+    pub identifier: compact_str::CompactString,
+    pub language: compact_str::CompactString,
+
+    pub subject: String,
+    pub body_html: String,
+    pub body_text: Option<String>,
+
+    pub created: chrono::DateTime<chrono::Utc>,
+}
@@ -15,11 +15,13 @@ use validator::Validate;
 
 pub mod admin_activity;
 pub mod backup_configurations;
+pub mod breached_password_range;
 pub mod database_host;
 pub mod egg_repository;
 pub mod egg_repository_egg;
 pub mod location;
 pub mod location_database_host;
+pub mod mail_template;
 pub mod mount;
 pub mod nest;
 pub mod nest_egg;
@@ -30,26 +32,33 @@ pub mod node_allocation;
 pub mod node_mount;
 pub mod oauth_provider;
 pub mod role;
+pub mod saml_provider;
 pub mod server;
 pub mod server_activity;
 pub mod server_allocation;
 pub mod server_backup;
+pub mod server_bulk_operation;
 pub mod server_database;
 pub mod server_mount;
 pub mod server_schedule;
 pub mod server_schedule_step;
 pub mod server_subuser;
+pub mod server_template;
 pub mod server_variable;
 pub mod user;
 pub mod user_activity;
 pub mod user_api_key;
+pub mod user_email_verification;
 pub mod user_oauth_link;
 pub mod user_password_reset;
 pub mod user_recovery_code;
+pub mod user_saml_link;
 pub mod user_security_key;
 pub mod user_server_group;
 pub mod user_session;
 pub mod user_ssh_key;
+pub mod webhook;
+pub mod webhook_delivery;
 
 #[derive(ToSchema, Validate, Deserialize)]
 pub struct PaginationParams {
@@ -13,6 +13,64 @@ pub use events::NodeEvent;
 
 pub type GetNode = crate::extract::ConsumingExtension<Node>;
 
+/// Sums up the resources of all servers per node. Servers that are being transferred count
+/// against both their source and destination node.
+const COMMITTED_RESOURCES_SQL: &str = r#"
+    SELECT committed_servers.node_uuid, SUM(committed_servers.memory) AS memory,
+        SUM(committed_servers.disk) AS disk, SUM(committed_servers.cpu) AS cpu
+    FROM (
+        SELECT servers.node_uuid, servers.memory, servers.disk, servers.cpu
+        FROM servers
+        UNION ALL
+        SELECT servers.destination_node_uuid, servers.memory, servers.disk, servers.cpu
+        FROM servers
+        WHERE servers.destination_node_uuid IS NOT NULL
+    ) AS committed_servers
+    GROUP BY committed_servers.node_uuid
+"#;
+
+#[derive(ToSchema, Serialize, Deserialize, Clone, Copy, Default)]
+pub struct NodeResources {
+    pub memory: i64,
+    pub disk: i64,
+    pub cpu: i64,
+}
+
+impl std::ops::Add for NodeResources {
+    type Output = Self;
+
+    #[inline]
+    fn add(self, rhs: Self) -> Self::Output {
+        Self {
+            memory: self.memory + rhs.memory,
+            disk: self.disk + rhs.disk,
+            cpu: self.cpu + rhs.cpu,
+        }
+    }
+}
+
+#[derive(ToSchema, Serialize, Deserialize, Clone, Copy, Default)]
+pub struct NodeResourceLimits {
+    pub memory: Option<i64>,
+    pub disk: Option<i64>,
+    pub cpu: Option<i64>,
+}
+
+impl NodeResourceLimits {
+    /// Returns the name of the first resource in `resources` that exceeds these limits.
+    pub fn exceeded_by(&self, resources: NodeResources) -> Option<&'static str> {
+        if self.memory.is_some_and(|limit| resources.memory > limit) {
+            Some("memory")
+        } else if self.disk.is_some_and(|limit| resources.disk > limit) {
+            Some("disk")
+        } else if self.cpu.is_some_and(|limit| resources.cpu > limit) {
+            Some("cpu")
+        } else {
+            None
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize, Clone)]
 pub struct Node {
     pub uuid: uuid::Uuid,
@@ -32,6 +90,11 @@ pub struct Node {
 
     pub memory: i64,
     pub disk: i64,
+    pub cpu: i32,
+
+    pub memory_overallocation: i16,
+    pub disk_overallocation: i16,
+    pub cpu_overallocation: i16,
 
     pub token_id: compact_str::CompactString,
     pub token: Vec<u8>,
@@ -83,6 +146,19 @@ impl BaseModel for Node {
                 compact_str::format_compact!("{prefix}memory"),
             ),
             ("nodes.disk", compact_str::format_compact!("{prefix}disk")),
+            ("nodes.cpu", compact_str::format_compact!("{prefix}cpu")),
+            (
+                "nodes.memory_overallocation",
+                compact_str::format_compact!("{prefix}memory_overallocation"),
+            ),
+            (
+                "nodes.disk_overallocation",
+                compact_str::format_compact!("{prefix}disk_overallocation"),
+            ),
+            (
+                "nodes.cpu_overallocation",
+                compact_str::format_compact!("{prefix}cpu_overallocation"),
+            ),
             (
                 "nodes.token_id",
                 compact_str::format_compact!("{prefix}token_id"),
@@ -132,6 +208,13 @@ impl BaseModel for Node {
             sftp_port: row.try_get(compact_str::format_compact!("{prefix}sftp_port").as_str())?,
             memory: row.try_get(compact_str::format_compact!("{prefix}memory").as_str())?,
             disk: row.try_get(compact_str::format_compact!("{prefix}disk").as_str())?,
+            cpu: row.try_get(compact_str::format_compact!("{prefix}cpu").as_str())?,
+            memory_overallocation: row
+                .try_get(compact_str::format_compact!("{prefix}memory_overallocation").as_str())?,
+            disk_overallocation: row
+                .try_get(compact_str::format_compact!("{prefix}disk_overallocation").as_str())?,
+            cpu_overallocation: row
+                .try_get(compact_str::format_compact!("{prefix}cpu_overallocation").as_str())?,
             token_id: row.try_get(compact_str::format_compact!("{prefix}token_id").as_str())?,
             token: row.try_get(compact_str::format_compact!("{prefix}token").as_str())?,
             created: row.try_get(compact_str::format_compact!("{prefix}created").as_str())?,
@@ -155,14 +238,18 @@ impl Node {
         sftp_port: i32,
         memory: i64,
         disk: i64,
+        cpu: i32,
+        memory_overallocation: i16,
+        disk_overallocation: i16,
+        cpu_overallocation: i16,
     ) -> Result<uuid::Uuid, crate::database::DatabaseError> {
         let token_id = rand::distr::Alphanumeric.sample_string(&mut rand::rng(), 16);
         let token = rand::distr::Alphanumeric.sample_string(&mut rand::rng(), 64);
 
         let row = sqlx::query(
             r#"
-            INSERT INTO nodes (location_uuid, backup_configuration_uuid, name, description, deployment_enabled, maintenance_enabled, public_url, url, sftp_host, sftp_port, memory, disk, token_id, token)
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14)
+            INSERT INTO nodes (location_uuid, backup_configuration_uuid, name, description, deployment_enabled, maintenance_enabled, public_url, url, sftp_host, sftp_port, memory, disk, cpu, memory_overallocation, disk_overallocation, cpu_overallocation, token_id, token)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18)
             RETURNING uuid
             "#
         )
@@ -178,6 +265,10 @@ impl Node {
         .bind(sftp_port)
         .bind(memory)
         .bind(disk)
+        .bind(cpu)
+        .bind(memory_overallocation)
+        .bind(disk_overallocation)
+        .bind(cpu_overallocation)
         .bind(token_id)
         .bind(database.encrypt(token).await?)
         .fetch_one(database.write())
@@ -343,6 +434,155 @@ impl Node {
         })
     }
 
+    /// Returns all nodes in the given locations that accept new deployments and still have
+    /// room for the requested resources within their effective limits, ordered from least to
+    /// most committed memory.
+    ///
+    /// Servers that are being transferred count against both their source and destination node.
+    pub async fn all_deployable_by_location_uuids(
+        database: &crate::database::Database,
+        location_uuids: &[uuid::Uuid],
+        memory: i64,
+        disk: i64,
+        cpu: i64,
+    ) -> Result<Vec<Self>, crate::database::DatabaseError> {
+        let rows = sqlx::query(&format!(
+            r#"
+            SELECT {}
+            FROM nodes
+            JOIN locations ON locations.uuid = nodes.location_uuid
+            LEFT JOIN ({COMMITTED_RESOURCES_SQL}) AS committed ON committed.node_uuid = nodes.uuid
+            WHERE nodes.location_uuid = ANY($1)
+                AND nodes.deployment_enabled AND NOT nodes.maintenance_enabled
+                AND (
+                    nodes.memory_overallocation < 0
+                    OR COALESCE(committed.memory, 0) + $2 <= nodes.memory * (100 + nodes.memory_overallocation) / 100
+                )
+                AND (
+                    nodes.disk_overallocation < 0
+                    OR COALESCE(committed.disk, 0) + $3 <= nodes.disk * (100 + nodes.disk_overallocation) / 100
+                )
+                AND (
+                    nodes.cpu = 0 OR nodes.cpu_overallocation < 0
+                    OR COALESCE(committed.cpu, 0) + $4 <= nodes.cpu::bigint * (100 + nodes.cpu_overallocation) / 100
+                )
+            ORDER BY COALESCE(committed.memory, 0) / GREATEST(nodes.memory, 1), nodes.created
+            "#,
+            Self::columns_sql(None)
+        ))
+        .bind(location_uuids)
+        .bind(memory)
+        .bind(disk)
+        .bind(cpu)
+        .fetch_all(database.read())
+        .await?;
+
+        rows.into_iter()
+            .map(|row| Self::map(None, &row))
+            .try_collect_vec()
+    }
+
+    /// Returns every node together with the resources committed to it by its servers.
+    pub async fn all_with_committed_resources(
+        database: &crate::database::Database,
+    ) -> Result<Vec<(Self, NodeResources)>, crate::database::DatabaseError> {
+        let rows = sqlx::query(&format!(
+            r#"
+            SELECT {}, COALESCE(committed.memory, 0)::bigint AS committed_memory,
+                COALESCE(committed.disk, 0)::bigint AS committed_disk,
+                COALESCE(committed.cpu, 0)::bigint AS committed_cpu
+            FROM nodes
+            JOIN locations ON locations.uuid = nodes.location_uuid
+            LEFT JOIN ({COMMITTED_RESOURCES_SQL}) AS committed ON committed.node_uuid = nodes.uuid
+            ORDER BY nodes.created
+            "#,
+            Self::columns_sql(None)
+        ))
+        .fetch_all(database.read())
+        .await?;
+
+        rows.into_iter()
+            .map(|row| {
+                Ok::<_, crate::database::DatabaseError>((
+                    Self::map(None, &row)?,
+                    NodeResources {
+                        memory: row.try_get("committed_memory")?,
+                        disk: row.try_get("committed_disk")?,
+                        cpu: row.try_get("committed_cpu")?,
+                    },
+                ))
+            })
+            .try_collect_vec()
+    }
+
+    /// Returns the resources committed to this node by its servers, optionally ignoring
+    /// a single server (e.g. the one that is being updated).
+    pub async fn fetch_committed_resources(
+        &self,
+        database: &crate::database::Database,
+        exclude_server_uuid: Option<uuid::Uuid>,
+    ) -> Result<NodeResources, crate::database::DatabaseError> {
+        let row = sqlx::query(
+            r#"
+            SELECT COALESCE(SUM(servers.memory), 0)::bigint AS memory,
+                COALESCE(SUM(servers.disk), 0)::bigint AS disk,
+                COALESCE(SUM(servers.cpu), 0)::bigint AS cpu
+            FROM servers
+            WHERE (servers.node_uuid = $1 OR servers.destination_node_uuid = $1)
+                AND ($2::uuid IS NULL OR servers.uuid != $2)
+            "#,
+        )
+        .bind(self.uuid)
+        .bind(exclude_server_uuid)
+        .fetch_one(database.read())
+        .await?;
+
+        Ok(NodeResources {
+            memory: row.try_get("memory")?,
+            disk: row.try_get("disk")?,
+            cpu: row.try_get("cpu")?,
+        })
+    }
+
+    /// Checks whether the node can fit the requested resources on top of what is already
+    /// committed to it, returning the name of the first resource that would exceed its
+    /// effective limit.
+    pub async fn exceeded_resource(
+        &self,
+        database: &crate::database::Database,
+        requested: NodeResources,
+        exclude_server_uuid: Option<uuid::Uuid>,
+    ) -> Result<Option<&'static str>, crate::database::DatabaseError> {
+        let committed = self
+            .fetch_committed_resources(database, exclude_server_uuid)
+            .await?;
+
+        Ok(self.effective_limits().exceeded_by(committed + requested))
+    }
+
+    /// Returns the effective resource limits of this node after applying the overallocation
+    /// percentages. `None` means the resource is not limited.
+    pub fn effective_limits(&self) -> NodeResourceLimits {
+        #[inline]
+        fn apply(base: i64, overallocation: i16) -> Option<i64> {
+            if overallocation < 0 {
+                None
+            } else {
+                Some(base * (100 + overallocation as i64) / 100)
+            }
+        }
+
+        NodeResourceLimits {
+            memory: apply(self.memory, self.memory_overallocation),
+            disk: apply(self.disk, self.disk_overallocation),
+            cpu: if self.cpu == 0 {
+                None
+            } else {
+                apply(self.cpu as i64, self.cpu_overallocation)
+            },
+        }
+    }
+
     pub async fn count_by_location_uuid(
         database: &crate::database::Database,
         location_uuid: uuid::Uuid,
@@ -464,6 +704,7 @@ impl Node {
         )
     }
 
+    /// The token is redacted, use [`Self::reveal_token`] to read it.
     #[inline]
     pub async fn into_admin_api_object(
         self,
@@ -501,11 +742,22 @@ impl Node {
             sftp_port: self.sftp_port,
             memory: self.memory,
             disk: self.disk,
+            cpu: self.cpu,
+            memory_overallocation: self.memory_overallocation,
+            disk_overallocation: self.disk_overallocation,
+            cpu_overallocation: self.cpu_overallocation,
             token_id: self.token_id,
-            token: database.decrypt(self.token).await?,
+            token: "".into(),
             created: self.created.and_utc(),
         })
     }
+
+    pub async fn reveal_token(
+        &self,
+        database: &crate::database::Database,
+    ) -> Result<String, anyhow::Error> {
+        Ok(database.decrypt(self.token.clone()).await?.into())
+    }
 }
 
 #[async_trait::async_trait]
@@ -591,6 +843,11 @@ pub struct AdminApiNode {
 
     pub memory: i64,
     pub disk: i64,
+    pub cpu: i32,
+
+    pub memory_overallocation: i16,
+    pub disk_overallocation: i16,
+    pub cpu_overallocation: i16,
 
     pub token_id: compact_str::CompactString,
     pub token: compact_str::CompactString,
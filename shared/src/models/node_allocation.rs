@@ -153,6 +153,51 @@ impl NodeAllocation {
         })
     }
 
+    /// Locks and returns up to `limit` unassigned allocations of a node whose port lies within
+    /// one of the given inclusive port ranges. An empty list of ranges matches every port.
+    ///
+    /// Allocations locked by another transaction are skipped, so concurrent deployments never
+    /// pick the same allocations. The locks are held until the transaction ends.
+    pub async fn lock_available_by_node_uuid_port_ranges(
+        transaction: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        node_uuid: uuid::Uuid,
+        port_ranges: &[(i32, i32)],
+        limit: i64,
+    ) -> Result<Vec<Self>, crate::database::DatabaseError> {
+        let (start_ports, end_ports): (Vec<i32>, Vec<i32>) = port_ranges.iter().copied().unzip();
+
+        let rows = sqlx::query(&format!(
+            r#"
+            SELECT {}
+            FROM node_allocations
+            LEFT JOIN server_allocations ON server_allocations.allocation_uuid = node_allocations.uuid
+            WHERE node_allocations.node_uuid = $1 AND server_allocations.uuid IS NULL
+                AND (
+                    CARDINALITY($2::int[]) = 0
+                    OR EXISTS (
+                        SELECT 1
+                        FROM UNNEST($2::int[], $3::int[]) AS port_ranges(start_port, end_port)
+                        WHERE node_allocations.port BETWEEN port_ranges.start_port AND port_ranges.end_port
+                    )
+                )
+            ORDER BY node_allocations.ip, node_allocations.port
+            LIMIT $4
+            FOR UPDATE OF node_allocations SKIP LOCKED
+            "#,
+            Self::columns_sql(None)
+        ))
+        .bind(node_uuid)
+        .bind(start_ports)
+        .bind(end_ports)
+        .bind(limit)
+        .fetch_all(&mut **transaction)
+        .await?;
+
+        rows.into_iter()
+            .map(|row| Self::map(None, &row))
+            .try_collect_vec()
+    }
+
     pub async fn by_node_uuid_with_pagination(
         database: &crate::database::Database,
         node_uuid: uuid::Uuid,
@@ -8,6 +8,14 @@ use std::{
 };
 use utoipa::ToSchema;
 
+pub mod oidc;
+
+#[derive(ToSchema, Serialize, Deserialize, Clone)]
+pub struct OAuthProviderRoleMapping {
+    pub value: compact_str::CompactString,
+    pub role_uuid: uuid::Uuid,
+}
+
 #[derive(Serialize, Deserialize, Clone)]
 pub struct OAuthProvider {
     pub uuid: uuid::Uuid,
@@ -28,6 +36,11 @@ pub struct OAuthProvider {
     pub name_first_path: Option<String>,
     pub name_last_path: Option<String>,
 
+    pub oidc_issuer_url: Option<String>,
+    pub oidc_logout_enabled: bool,
+    pub role_claim_path: Option<String>,
+    pub role_mappings: Vec<OAuthProviderRoleMapping>,
+
     pub enabled: bool,
     pub login_only: bool,
     pub link_viewable: bool,
@@ -101,6 +114,22 @@ impl BaseModel for OAuthProvider {
                 "oauth_providers.name_last_path",
                 compact_str::format_compact!("{prefix}name_last_path"),
             ),
+            (
+                "oauth_providers.oidc_issuer_url",
+                compact_str::format_compact!("{prefix}oidc_issuer_url"),
+            ),
+            (
+                "oauth_providers.oidc_logout_enabled",
+                compact_str::format_compact!("{prefix}oidc_logout_enabled"),
+            ),
+            (
+                "oauth_providers.role_claim_path",
+                compact_str::format_compact!("{prefix}role_claim_path"),
+            ),
+            (
+                "oauth_providers.role_mappings",
+                compact_str::format_compact!("{prefix}role_mappings"),
+            ),
             (
                 "oauth_providers.enabled",
                 compact_str::format_compact!("{prefix}enabled"),
@@ -153,6 +182,15 @@ impl BaseModel for OAuthProvider {
                 .try_get(compact_str::format_compact!("{prefix}name_first_path").as_str())?,
             name_last_path: row
                 .try_get(compact_str::format_compact!("{prefix}name_last_path").as_str())?,
+            oidc_issuer_url: row
+                .try_get(compact_str::format_compact!("{prefix}oidc_issuer_url").as_str())?,
+            oidc_logout_enabled: row
+                .try_get(compact_str::format_compact!("{prefix}oidc_logout_enabled").as_str())?,
+            role_claim_path: row
+                .try_get(compact_str::format_compact!("{prefix}role_claim_path").as_str())?,
+            role_mappings: serde_json::from_value(
+                row.try_get(compact_str::format_compact!("{prefix}role_mappings").as_str())?,
+            )?,
             enabled: row.try_get(compact_str::format_compact!("{prefix}enabled").as_str())?,
             login_only: row.try_get(compact_str::format_compact!("{prefix}login_only").as_str())?,
             link_viewable: row
@@ -182,6 +220,10 @@ impl OAuthProvider {
         username_path: Option<&str>,
         name_first_path: Option<&str>,
         name_last_path: Option<&str>,
+        oidc_issuer_url: Option<&str>,
+        oidc_logout_enabled: bool,
+        role_claim_path: Option<&str>,
+        role_mappings: &[OAuthProviderRoleMapping],
         enabled: bool,
         login_only: bool,
         link_viewable: bool,
@@ -193,10 +235,11 @@ impl OAuthProvider {
             INSERT INTO oauth_providers (
                 name, description, client_id, client_secret, auth_url,
                 token_url, info_url, scopes, identifier_path, email_path,
-                username_path, name_first_path, name_last_path, enabled,
+                username_path, name_first_path, name_last_path, oidc_issuer_url,
+                oidc_logout_enabled, role_claim_path, role_mappings, enabled,
                 login_only, link_viewable, user_manageable, basic_auth
             )
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18, $19, $20, $21, $22)
             RETURNING {}
             "#,
             Self::columns_sql(None)
@@ -219,6 +262,10 @@ impl OAuthProvider {
         .bind(username_path)
         .bind(name_first_path)
         .bind(name_last_path)
+        .bind(oidc_issuer_url)
+        .bind(oidc_logout_enabled)
+        .bind(role_claim_path)
+        .bind(serde_json::to_value(role_mappings)?)
         .bind(enabled)
         .bind(login_only)
         .bind(link_viewable)
@@ -389,17 +436,57 @@ impl OAuthProvider {
         )
     }
 
+    /// Returns the role of the first mapping whose value is found at the role claim path, or
+    /// `Some(None)` if the claim holds no mapped value.
+    ///
+    /// Returns `None` if no role claim path is set or the claim is missing entirely, in which case
+    /// the role of an existing user should be left untouched.
+    ///
+    /// The claim may either be a single value or an array of values, as is common for group claims.
+    pub fn extract_role_uuid(
+        &self,
+        value: &serde_json::Value,
+    ) -> Result<Option<Option<uuid::Uuid>>, anyhow::Error> {
+        let path = match &self.role_claim_path {
+            Some(path) => serde_json_path::JsonPath::parse(path)?,
+            None => return Ok(None),
+        };
+
+        let claims: Vec<String> = path
+            .query(value)
+            .all()
+            .into_iter()
+            .flat_map(|value| match value {
+                serde_json::Value::Array(values) => values.iter().collect(),
+                value => vec![value],
+            })
+            .map(|value| match value {
+                serde_json::Value::String(string) => string.clone(),
+                val => val.to_string(),
+            })
+            .collect();
+
+        if claims.is_empty() {
+            return Ok(None);
+        }
+
+        Ok(Some(
+            self.role_mappings
+                .iter()
+                .find(|mapping| claims.iter().any(|claim| claim == mapping.value.as_str()))
+                .map(|mapping| mapping.role_uuid),
+        ))
+    }
+
+    /// The client secret is redacted, use [`Self::reveal_client_secret`] to read it.
     #[inline]
-    pub async fn into_admin_api_object(
-        self,
-        database: &crate::database::Database,
-    ) -> Result<AdminApiOAuthProvider, anyhow::Error> {
-        Ok(AdminApiOAuthProvider {
+    pub fn into_admin_api_object(self) -> AdminApiOAuthProvider {
+        AdminApiOAuthProvider {
             uuid: self.uuid,
             name: self.name,
             description: self.description,
             client_id: self.client_id,
-            client_secret: database.decrypt(self.client_secret).await?,
+            client_secret: "".into(),
             auth_url: self.auth_url,
             token_url: self.token_url,
             info_url: self.info_url,
@@ -409,13 +496,24 @@ impl OAuthProvider {
             username_path: self.username_path,
             name_first_path: self.name_first_path,
             name_last_path: self.name_last_path,
+            oidc_issuer_url: self.oidc_issuer_url,
+            oidc_logout_enabled: self.oidc_logout_enabled,
+            role_claim_path: self.role_claim_path,
+            role_mappings: self.role_mappings,
             enabled: self.enabled,
             login_only: self.login_only,
             link_viewable: self.link_viewable,
             user_manageable: self.user_manageable,
             basic_auth: self.basic_auth,
             created: self.created.and_utc(),
-        })
+        }
+    }
+
+    pub async fn reveal_client_secret(
+        &self,
+        database: &crate::database::Database,
+    ) -> Result<compact_str::CompactString, anyhow::Error> {
+        database.decrypt(self.client_secret.clone()).await
     }
 
     #[inline]
@@ -509,6 +607,11 @@ pub struct AdminApiOAuthProvider {
     pub name_first_path: Option<String>,
     pub name_last_path: Option<String>,
 
+    pub oidc_issuer_url: Option<String>,
+    pub oidc_logout_enabled: bool,
+    pub role_claim_path: Option<String>,
+    pub role_mappings: Vec<OAuthProviderRoleMapping>,
+
     pub enabled: bool,
     pub login_only: bool,
     pub link_viewable: bool,
@@ -0,0 +1,221 @@
+use super::OAuthProvider;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct IdTokenFields {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id_token: Option<String>,
+}
+
+impl oauth2::ExtraTokenFields for IdTokenFields {}
+
+/// An oauth2 client that also keeps the `id_token` of the token response, if the provider sent one.
+pub type OAuthClient = oauth2::Client<
+    oauth2::basic::BasicErrorResponse,
+    oauth2::StandardTokenResponse<IdTokenFields, oauth2::basic::BasicTokenType>,
+    oauth2::basic::BasicTokenIntrospectionResponse,
+    oauth2::StandardRevocableToken,
+    oauth2::basic::BasicRevocationErrorResponse,
+>;
+
+/// State kept in redis between redirecting the user to the provider and handling the callback.
+#[derive(Serialize, Deserialize, Default)]
+pub struct OAuthRedirectState {
+    pub nonce: Option<String>,
+    pub pkce_verifier: Option<String>,
+}
+
+/// State kept in redis for each session created through an OIDC provider with logout enabled.
+#[derive(Serialize, Deserialize)]
+pub struct OidcLogoutState {
+    pub oauth_provider_uuid: uuid::Uuid,
+    pub id_token: String,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct OidcDiscovery {
+    pub issuer: String,
+    pub authorization_endpoint: String,
+    pub token_endpoint: String,
+    pub userinfo_endpoint: Option<String>,
+    pub jwks_uri: String,
+    pub end_session_endpoint: Option<String>,
+}
+
+impl OidcDiscovery {
+    /// Fetches the discovery document of the given issuer, cached for an hour.
+    pub async fn fetch(state: &crate::State, issuer_url: &str) -> Result<Self, anyhow::Error> {
+        let issuer_url = issuer_url.trim_end_matches('/');
+
+        state
+            .cache
+            .cached(&format!("oidc::discovery::{issuer_url}"), 3600, || async {
+                let discovery: Self = state
+                    .client
+                    .get(format!("{issuer_url}/.well-known/openid-configuration"))
+                    .send()
+                    .await?
+                    .error_for_status()?
+                    .json()
+                    .await?;
+
+                if discovery.issuer.trim_end_matches('/') != issuer_url {
+                    return Err(anyhow::anyhow!(
+                        "issuer mismatch in discovery document, expected {issuer_url}, got {}",
+                        discovery.issuer
+                    ));
+                }
+
+                Ok(discovery)
+            })
+            .await
+    }
+
+    async fn fetch_jwks(
+        &self,
+        state: &crate::State,
+    ) -> Result<jsonwebtoken::jwk::JwkSet, anyhow::Error> {
+        state
+            .cache
+            .cached(&format!("oidc::jwks::{}", self.jwks_uri), 3600, || async {
+                Ok::<_, anyhow::Error>(
+                    state
+                        .client
+                        .get(&self.jwks_uri)
+                        .send()
+                        .await?
+                        .error_for_status()?
+                        .json()
+                        .await?,
+                )
+            })
+            .await
+    }
+
+    /// Finds the key the given token header refers to, refetching the key set once
+    /// if it is unknown in case the provider rotated its keys.
+    async fn find_jwk(
+        &self,
+        state: &crate::State,
+        header: &jsonwebtoken::Header,
+    ) -> Result<jsonwebtoken::jwk::Jwk, anyhow::Error> {
+        let find = |jwks: &jsonwebtoken::jwk::JwkSet| match &header.kid {
+            Some(kid) => jwks.find(kid).cloned(),
+            None if jwks.keys.len() == 1 => jwks.keys.first().cloned(),
+            None => None,
+        };
+
+        if let Some(jwk) = find(&self.fetch_jwks(state).await?) {
+            return Ok(jwk);
+        }
+
+        state
+            .cache
+            .invalidate(&format!("oidc::jwks::{}", self.jwks_uri))
+            .await?;
+
+        find(&self.fetch_jwks(state).await?).ok_or_else(|| {
+            crate::response::DisplayError::new("unable to find signing key of id token").into()
+        })
+    }
+}
+
+impl OAuthProvider {
+    #[inline]
+    pub fn is_oidc(&self) -> bool {
+        self.oidc_issuer_url.is_some()
+    }
+
+    pub async fn fetch_oidc_discovery(
+        &self,
+        state: &crate::State,
+    ) -> Result<Option<OidcDiscovery>, anyhow::Error> {
+        match &self.oidc_issuer_url {
+            Some(issuer_url) => Ok(Some(OidcDiscovery::fetch(state, issuer_url).await?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Verifies the signature, issuer, audience, expiry and nonce of the given id token
+    /// and returns its claims.
+    pub async fn validate_id_token(
+        &self,
+        state: &crate::State,
+        discovery: &OidcDiscovery,
+        id_token: &str,
+        nonce: &str,
+    ) -> Result<serde_json::Value, anyhow::Error> {
+        let header = jsonwebtoken::decode_header(id_token)?;
+
+        // symmetric algorithms would require the client secret as key, which is never used here
+        if matches!(
+            header.alg,
+            jsonwebtoken::Algorithm::HS256
+                | jsonwebtoken::Algorithm::HS384
+                | jsonwebtoken::Algorithm::HS512
+        ) {
+            return Err(crate::response::DisplayError::new(
+                "id token is signed with an unsupported algorithm",
+            )
+            .into());
+        }
+
+        let jwk = discovery.find_jwk(state, &header).await?;
+
+        let mut validation = jsonwebtoken::Validation::new(header.alg);
+        validation.set_audience(&[self.client_id.as_str()]);
+        validation.set_issuer(&[discovery.issuer.as_str()]);
+        validation.set_required_spec_claims(&["exp", "iat", "iss", "aud", "sub"]);
+
+        let claims = jsonwebtoken::decode::<serde_json::Value>(
+            id_token,
+            &jsonwebtoken::DecodingKey::from_jwk(&jwk)?,
+            &validation,
+        )?
+        .claims;
+
+        if let Some(azp) = claims.get("azp").and_then(|azp| azp.as_str())
+            && azp != self.client_id
+        {
+            return Err(crate::response::DisplayError::new(
+                "id token was issued to a different client",
+            )
+            .into());
+        }
+
+        match claims.get("nonce").and_then(|nonce| nonce.as_str()) {
+            Some(claim)
+                if constant_time_eq::constant_time_eq(claim.as_bytes(), nonce.as_bytes()) =>
+            {
+                Ok(claims)
+            }
+            _ => Err(crate::response::DisplayError::new("id token nonce mismatch").into()),
+        }
+    }
+
+    /// Builds the url to end the session at the provider, if it supports RP-initiated logout.
+    pub fn oidc_logout_url(
+        &self,
+        discovery: &OidcDiscovery,
+        id_token: &str,
+        post_logout_redirect_uri: &str,
+    ) -> Result<Option<reqwest::Url>, anyhow::Error> {
+        if !self.oidc_logout_enabled {
+            return Ok(None);
+        }
+
+        let end_session_endpoint = match &discovery.end_session_endpoint {
+            Some(end_session_endpoint) => end_session_endpoint,
+            None => return Ok(None),
+        };
+
+        Ok(Some(reqwest::Url::parse_with_params(
+            end_session_endpoint,
+            &[
+                ("id_token_hint", id_token),
+                ("client_id", self.client_id.as_str()),
+                ("post_logout_redirect_uri", post_logout_redirect_uri),
+            ],
+        )?))
+    }
+}
@@ -1,4 +1,5 @@
 use crate::prelude::*;
+use compact_str::ToCompactString;
 use serde::{Deserialize, Serialize};
 use sqlx::{Row, postgres::PgRow};
 use std::{
@@ -7,6 +8,20 @@ use std::{
 };
 use utoipa::ToSchema;
 
+/// The SCIM attributes roles can be filtered on when exposed as groups.
+pub const SCIM_FILTER_ATTRIBUTES: &[crate::scim::FilterAttribute] = &[
+    crate::scim::FilterAttribute {
+        name: "id",
+        column: "roles.uuid",
+        kind: crate::scim::FilterAttributeKind::Uuid,
+    },
+    crate::scim::FilterAttribute {
+        name: "displayName",
+        column: "roles.name",
+        kind: crate::scim::FilterAttributeKind::Text,
+    },
+];
+
 #[derive(Serialize, Deserialize, Clone)]
 pub struct Role {
     pub uuid: uuid::Uuid,
@@ -15,6 +30,7 @@ pub struct Role {
     pub description: Option<compact_str::CompactString>,
 
     pub require_two_factor: bool,
+    pub require_passkey: bool,
 
     pub admin_permissions: Arc<Vec<compact_str::CompactString>>,
     pub server_permissions: Arc<Vec<compact_str::CompactString>>,
@@ -40,6 +56,10 @@ impl BaseModel for Role {
                 "roles.require_two_factor",
                 compact_str::format_compact!("{prefix}require_two_factor"),
             ),
+            (
+                "roles.require_passkey",
+                compact_str::format_compact!("{prefix}require_passkey"),
+            ),
             (
                 "roles.admin_permissions",
                 compact_str::format_compact!("{prefix}admin_permissions"),
@@ -66,6 +86,8 @@ impl BaseModel for Role {
                 .try_get(compact_str::format_compact!("{prefix}description").as_str())?,
             require_two_factor: row
                 .try_get(compact_str::format_compact!("{prefix}require_two_factor").as_str())?,
+            require_passkey: row
+                .try_get(compact_str::format_compact!("{prefix}require_passkey").as_str())?,
             admin_permissions: Arc::new(
                 row.try_get(compact_str::format_compact!("{prefix}admin_permissions").as_str())?,
             ),
@@ -83,13 +105,14 @@ impl Role {
         name: &str,
         description: Option<&str>,
         two_factor_required: bool,
+        passkey_required: bool,
         admin_permissions: &[compact_str::CompactString],
         server_permissions: &[compact_str::CompactString],
     ) -> Result<Self, crate::database::DatabaseError> {
         let row = sqlx::query(&format!(
             r#"
-            INSERT INTO roles (name, description, require_two_factor, admin_permissions, server_permissions)
-            VALUES ($1, $2, $3, $4, $5)
+            INSERT INTO roles (name, description, require_two_factor, require_passkey, admin_permissions, server_permissions)
+            VALUES ($1, $2, $3, $4, $5, $6)
             RETURNING {}
             "#,
             Self::columns_sql(None)
@@ -97,6 +120,7 @@ impl Role {
         .bind(name)
         .bind(description)
         .bind(two_factor_required)
+        .bind(passkey_required)
         .bind(admin_permissions)
         .bind(server_permissions)
         .fetch_one(database.write())
@@ -142,17 +166,167 @@ impl Role {
         })
     }
 
+    /// Lists roles matching a SCIM filter, returning the total amount of matches alongside
+    /// the requested window. `start_index` is 1-based like in SCIM.
+    pub async fn all_with_scim_filter(
+        database: &crate::database::Database,
+        filter: Option<&crate::scim::Filter>,
+        start_index: i64,
+        count: i64,
+    ) -> Result<(i64, Vec<Self>), anyhow::Error> {
+        let sql_filter = |first_param| match filter {
+            Some(filter) => filter.to_sql(SCIM_FILTER_ATTRIBUTES, first_param),
+            None => Ok(("TRUE".to_string(), Vec::new())),
+        };
+
+        let (condition, binds) = sql_filter(1)?;
+        let query = format!(
+            r#"
+            SELECT COUNT(*)
+            FROM roles
+            WHERE {condition}
+            "#
+        );
+        let mut query = sqlx::query_scalar(&query);
+        for bind in &binds {
+            query = query.bind(bind);
+        }
+        let total: i64 = query.fetch_one(database.read()).await?;
+
+        let (condition, binds) = sql_filter(3)?;
+        let query = format!(
+            r#"
+            SELECT {}
+            FROM roles
+            WHERE {condition}
+            ORDER BY roles.created
+            LIMIT $1 OFFSET $2 - 1
+            "#,
+            Self::columns_sql(None)
+        );
+        let mut query = sqlx::query(&query).bind(count).bind(start_index);
+        for bind in &binds {
+            query = query.bind(bind);
+        }
+        let rows = query.fetch_all(database.read()).await?;
+
+        Ok((
+            total,
+            rows.into_iter()
+                .map(|row| Self::map(None, &row))
+                .try_collect_vec()?,
+        ))
+    }
+
+    pub async fn update_name(
+        &mut self,
+        database: &crate::database::Database,
+        name: &str,
+    ) -> Result<(), crate::database::DatabaseError> {
+        sqlx::query(
+            r#"
+            UPDATE roles
+            SET name = $2
+            WHERE roles.uuid = $1
+            "#,
+        )
+        .bind(self.uuid)
+        .bind(name)
+        .execute(database.write())
+        .await?;
+
+        self.name = name.into();
+
+        Ok(())
+    }
+
+    /// Assigns the role to the given users, replacing whichever role they had before.
+    pub async fn add_users(
+        &self,
+        database: &crate::database::Database,
+        user_uuids: &[uuid::Uuid],
+    ) -> Result<(), crate::database::DatabaseError> {
+        sqlx::query(
+            r#"
+            UPDATE users
+            SET role_uuid = $1
+            WHERE users.uuid = ANY($2)
+            "#,
+        )
+        .bind(self.uuid)
+        .bind(user_uuids)
+        .execute(database.write())
+        .await?;
+
+        Ok(())
+    }
+
+    /// Removes the role from the given users, or from every user holding it if `user_uuids`
+    /// is `None`.
+    pub async fn remove_users(
+        &self,
+        database: &crate::database::Database,
+        user_uuids: Option<&[uuid::Uuid]>,
+    ) -> Result<(), crate::database::DatabaseError> {
+        sqlx::query(
+            r#"
+            UPDATE users
+            SET role_uuid = NULL
+            WHERE users.role_uuid = $1 AND ($2::uuid[] IS NULL OR users.uuid = ANY($2))
+            "#,
+        )
+        .bind(self.uuid)
+        .bind(user_uuids)
+        .execute(database.write())
+        .await?;
+
+        Ok(())
+    }
+
     #[inline]
     pub fn into_admin_api_object(self) -> AdminApiRole {
         AdminApiRole {
             uuid: self.uuid,
             name: self.name,
             description: self.description,
+            require_two_factor: self.require_two_factor,
+            require_passkey: self.require_passkey,
             admin_permissions: self.admin_permissions,
             server_permissions: self.server_permissions,
             created: self.created.and_utc(),
         }
     }
+
+    /// Converts the role into its SCIM group representation, `base_url` being the panel url.
+    /// Members are left out when `members` is `None`.
+    pub fn into_scim_object(
+        self,
+        base_url: &str,
+        members: Option<Vec<super::user::User>>,
+    ) -> crate::scim::ScimGroup {
+        let base_url = base_url.trim_end_matches('/');
+
+        crate::scim::ScimGroup {
+            schemas: [crate::scim::SCHEMA_GROUP],
+            id: self.uuid,
+            display_name: self.name,
+            members: members.map(|members| {
+                members
+                    .into_iter()
+                    .map(|user| crate::scim::ScimReference {
+                        value: user.uuid.to_compact_string(),
+                        display: Some(user.username),
+                        r#ref: Some(format!("{base_url}/scim/v2/Users/{}", user.uuid)),
+                    })
+                    .collect()
+            }),
+            meta: crate::scim::ScimMeta {
+                resource_type: "Group",
+                created: self.created.and_utc(),
+                location: format!("{base_url}/scim/v2/Groups/{}", self.uuid),
+            },
+        }
+    }
 }
 
 #[async_trait::async_trait]
@@ -222,6 +396,9 @@ pub struct AdminApiRole {
     pub name: compact_str::CompactString,
     pub description: Option<compact_str::CompactString>,
 
+    pub require_two_factor: bool,
+    pub require_passkey: bool,
+
     pub admin_permissions: Arc<Vec<compact_str::CompactString>>,
     pub server_permissions: Arc<Vec<compact_str::CompactString>>,
 
@@ -0,0 +1,532 @@
+use crate::prelude::*;
+use rand::distr::SampleString;
+use serde::{Deserialize, Serialize};
+use sqlx::{Row, postgres::PgRow};
+use std::{
+    collections::BTreeMap,
+    sync::{Arc, LazyLock},
+};
+use utoipa::ToSchema;
+
+/// State kept in redis between sending the AuthnRequest and receiving the response.
+#[derive(Serialize, Deserialize, Default)]
+pub struct SamlRedirectState {
+    /// The user that started linking this provider to their account, if any.
+    pub link_user_uuid: Option<uuid::Uuid>,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct SamlProvider {
+    pub uuid: uuid::Uuid,
+
+    pub name: compact_str::CompactString,
+    pub description: Option<compact_str::CompactString>,
+
+    pub idp_entity_id: String,
+    pub idp_sso_url: String,
+    pub idp_certificate: String,
+    pub sp_certificate: String,
+    pub sp_private_key: Vec<u8>,
+
+    pub identifier_attribute: Option<String>,
+    pub email_attribute: Option<String>,
+    pub username_attribute: Option<String>,
+    pub name_first_attribute: Option<String>,
+    pub name_last_attribute: Option<String>,
+
+    pub enabled: bool,
+    pub login_only: bool,
+    pub link_viewable: bool,
+    pub user_manageable: bool,
+
+    pub created: chrono::NaiveDateTime,
+}
+
+impl BaseModel for SamlProvider {
+    const NAME: &'static str = "saml_provider";
+
+    #[inline]
+    fn columns(prefix: Option<&str>) -> BTreeMap<&'static str, compact_str::CompactString> {
+        let prefix = prefix.unwrap_or_default();
+
+        BTreeMap::from([
+            (
+                "saml_providers.uuid",
+                compact_str::format_compact!("{prefix}uuid"),
+            ),
+            (
+                "saml_providers.name",
+                compact_str::format_compact!("{prefix}name"),
+            ),
+            (
+                "saml_providers.description",
+                compact_str::format_compact!("{prefix}description"),
+            ),
+            (
+                "saml_providers.idp_entity_id",
+                compact_str::format_compact!("{prefix}idp_entity_id"),
+            ),
+            (
+                "saml_providers.idp_sso_url",
+                compact_str::format_compact!("{prefix}idp_sso_url"),
+            ),
+            (
+                "saml_providers.idp_certificate",
+                compact_str::format_compact!("{prefix}idp_certificate"),
+            ),
+            (
+                "saml_providers.sp_certificate",
+                compact_str::format_compact!("{prefix}sp_certificate"),
+            ),
+            (
+                "saml_providers.sp_private_key",
+                compact_str::format_compact!("{prefix}sp_private_key"),
+            ),
+            (
+                "saml_providers.identifier_attribute",
+                compact_str::format_compact!("{prefix}identifier_attribute"),
+            ),
+            (
+                "saml_providers.email_attribute",
+                compact_str::format_compact!("{prefix}email_attribute"),
+            ),
+            (
+                "saml_providers.username_attribute",
+                compact_str::format_compact!("{prefix}username_attribute"),
+            ),
+            (
+                "saml_providers.name_first_attribute",
+                compact_str::format_compact!("{prefix}name_first_attribute"),
+            ),
+            (
+                "saml_providers.name_last_attribute",
+                compact_str::format_compact!("{prefix}name_last_attribute"),
+            ),
+            (
+                "saml_providers.enabled",
+                compact_str::format_compact!("{prefix}enabled"),
+            ),
+            (
+                "saml_providers.login_only",
+                compact_str::format_compact!("{prefix}login_only"),
+            ),
+            (
+                "saml_providers.link_viewable",
+                compact_str::format_compact!("{prefix}link_viewable"),
+            ),
+            (
+                "saml_providers.user_manageable",
+                compact_str::format_compact!("{prefix}user_manageable"),
+            ),
+            (
+                "saml_providers.created",
+                compact_str::format_compact!("{prefix}created"),
+            ),
+        ])
+    }
+
+    #[inline]
+    fn map(prefix: Option<&str>, row: &PgRow) -> Result<Self, crate::database::DatabaseError> {
+        let prefix = prefix.unwrap_or_default();
+
+        Ok(Self {
+            uuid: row.try_get(compact_str::format_compact!("{prefix}uuid").as_str())?,
+            name: row.try_get(compact_str::format_compact!("{prefix}name").as_str())?,
+            description: row
+                .try_get(compact_str::format_compact!("{prefix}description").as_str())?,
+            idp_entity_id: row
+                .try_get(compact_str::format_compact!("{prefix}idp_entity_id").as_str())?,
+            idp_sso_url: row
+                .try_get(compact_str::format_compact!("{prefix}idp_sso_url").as_str())?,
+            idp_certificate: row
+                .try_get(compact_str::format_compact!("{prefix}idp_certificate").as_str())?,
+            sp_certificate: row
+                .try_get(compact_str::format_compact!("{prefix}sp_certificate").as_str())?,
+            sp_private_key: row
+                .try_get(compact_str::format_compact!("{prefix}sp_private_key").as_str())?,
+            identifier_attribute: row
+                .try_get(compact_str::format_compact!("{prefix}identifier_attribute").as_str())?,
+            email_attribute: row
+                .try_get(compact_str::format_compact!("{prefix}email_attribute").as_str())?,
+            username_attribute: row
+                .try_get(compact_str::format_compact!("{prefix}username_attribute").as_str())?,
+            name_first_attribute: row
+                .try_get(compact_str::format_compact!("{prefix}name_first_attribute").as_str())?,
+            name_last_attribute: row
+                .try_get(compact_str::format_compact!("{prefix}name_last_attribute").as_str())?,
+            enabled: row.try_get(compact_str::format_compact!("{prefix}enabled").as_str())?,
+            login_only: row.try_get(compact_str::format_compact!("{prefix}login_only").as_str())?,
+            link_viewable: row
+                .try_get(compact_str::format_compact!("{prefix}link_viewable").as_str())?,
+            user_manageable: row
+                .try_get(compact_str::format_compact!("{prefix}user_manageable").as_str())?,
+            created: row.try_get(compact_str::format_compact!("{prefix}created").as_str())?,
+        })
+    }
+}
+
+impl SamlProvider {
+    #[allow(clippy::too_many_arguments)]
+    pub async fn create(
+        database: &crate::database::Database,
+        name: &str,
+        description: Option<&str>,
+        idp_entity_id: &str,
+        idp_sso_url: &str,
+        idp_certificate: &str,
+        sp_certificate: &str,
+        sp_private_key: &str,
+        identifier_attribute: Option<&str>,
+        email_attribute: Option<&str>,
+        username_attribute: Option<&str>,
+        name_first_attribute: Option<&str>,
+        name_last_attribute: Option<&str>,
+        enabled: bool,
+        login_only: bool,
+        link_viewable: bool,
+        user_manageable: bool,
+    ) -> Result<Self, crate::database::DatabaseError> {
+        let row = sqlx::query(&format!(
+            r#"
+            INSERT INTO saml_providers (
+                name, description, idp_entity_id, idp_sso_url, idp_certificate,
+                sp_certificate, sp_private_key, identifier_attribute, email_attribute,
+                username_attribute, name_first_attribute, name_last_attribute, enabled,
+                login_only, link_viewable, user_manageable
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16)
+            RETURNING {}
+            "#,
+            Self::columns_sql(None)
+        ))
+        .bind(name)
+        .bind(description)
+        .bind(idp_entity_id)
+        .bind(idp_sso_url)
+        .bind(idp_certificate)
+        .bind(sp_certificate)
+        .bind(
+            database
+                .encrypt(sp_private_key.to_string())
+                .await
+                .map_err(|err| sqlx::Error::Encode(err.into()))?,
+        )
+        .bind(identifier_attribute)
+        .bind(email_attribute)
+        .bind(username_attribute)
+        .bind(name_first_attribute)
+        .bind(name_last_attribute)
+        .bind(enabled)
+        .bind(login_only)
+        .bind(link_viewable)
+        .bind(user_manageable)
+        .fetch_one(database.write())
+        .await?;
+
+        Self::map(None, &row)
+    }
+
+    pub async fn all_with_pagination(
+        database: &crate::database::Database,
+        page: i64,
+        per_page: i64,
+        search: Option<&str>,
+    ) -> Result<super::Pagination<Self>, crate::database::DatabaseError> {
+        let offset = (page - 1) * per_page;
+
+        let rows = sqlx::query(&format!(
+            r#"
+            SELECT {}, COUNT(*) OVER() AS total_count
+            FROM saml_providers
+            WHERE ($1 IS NULL OR saml_providers.name ILIKE '%' || $1 || '%')
+            ORDER BY saml_providers.created
+            LIMIT $2 OFFSET $3
+            "#,
+            Self::columns_sql(None)
+        ))
+        .bind(search)
+        .bind(per_page)
+        .bind(offset)
+        .fetch_all(database.read())
+        .await?;
+
+        Ok(super::Pagination {
+            total: rows
+                .first()
+                .map_or(Ok(0), |row| row.try_get("total_count"))?,
+            per_page,
+            page,
+            data: rows
+                .into_iter()
+                .map(|row| Self::map(None, &row))
+                .try_collect_vec()?,
+        })
+    }
+
+    pub async fn all_by_usable(
+        database: &crate::database::Database,
+    ) -> Result<Vec<Self>, crate::database::DatabaseError> {
+        let rows = sqlx::query(&format!(
+            r#"
+            SELECT {}
+            FROM saml_providers
+            WHERE saml_providers.enabled = true
+            ORDER BY saml_providers.created
+            "#,
+            Self::columns_sql(None)
+        ))
+        .fetch_all(database.read())
+        .await?;
+
+        rows.into_iter()
+            .map(|row| Self::map(None, &row))
+            .try_collect_vec()
+    }
+
+    /// The entity ID of the panel towards this provider, which is also where its metadata is served.
+    #[inline]
+    pub fn sp_entity_id(&self, app_url: &str) -> String {
+        format!(
+            "{}/api/auth/saml/{}/metadata",
+            app_url.trim_end_matches('/'),
+            self.uuid
+        )
+    }
+
+    #[inline]
+    pub fn acs_url(&self, app_url: &str) -> String {
+        format!(
+            "{}/api/auth/saml/{}",
+            app_url.trim_end_matches('/'),
+            self.uuid
+        )
+    }
+
+    pub fn extract_identifier(
+        &self,
+        assertion: &crate::saml::SamlAssertion,
+    ) -> Result<String, anyhow::Error> {
+        match &self.identifier_attribute {
+            Some(attribute) => Ok(assertion
+                .attribute(attribute)
+                .ok_or_else(|| {
+                    crate::response::DisplayError::new(format!(
+                        "unable to extract identifier from attribute {attribute}"
+                    ))
+                })?
+                .to_string()),
+            None => Ok(assertion.name_id.clone()),
+        }
+    }
+
+    pub fn extract_email(
+        &self,
+        assertion: &crate::saml::SamlAssertion,
+    ) -> Result<String, anyhow::Error> {
+        let attribute = match &self.email_attribute {
+            Some(attribute) => attribute,
+            None => {
+                return Err(crate::response::DisplayError::new(
+                    "no email attribute defined, unable to register",
+                )
+                .into());
+            }
+        };
+
+        Ok(assertion
+            .attribute(attribute)
+            .ok_or_else(|| {
+                crate::response::DisplayError::new(format!(
+                    "unable to extract email from attribute {attribute}"
+                ))
+            })?
+            .to_string())
+    }
+
+    pub fn extract_username(
+        &self,
+        assertion: &crate::saml::SamlAssertion,
+    ) -> Result<String, anyhow::Error> {
+        let attribute = match &self.username_attribute {
+            Some(attribute) => attribute,
+            None => return Ok(rand::distr::Alphanumeric.sample_string(&mut rand::rng(), 10)),
+        };
+
+        Ok(assertion
+            .attribute(attribute)
+            .ok_or_else(|| {
+                crate::response::DisplayError::new(format!(
+                    "unable to extract username from attribute {attribute}"
+                ))
+            })?
+            .to_string())
+    }
+
+    pub fn extract_name_first(
+        &self,
+        assertion: &crate::saml::SamlAssertion,
+    ) -> Result<String, anyhow::Error> {
+        let attribute = match &self.name_first_attribute {
+            Some(attribute) => attribute,
+            None => return Ok("First".to_string()),
+        };
+
+        Ok(assertion
+            .attribute(attribute)
+            .ok_or_else(|| {
+                crate::response::DisplayError::new(format!(
+                    "unable to extract first name from attribute {attribute}"
+                ))
+            })?
+            .to_string())
+    }
+
+    pub fn extract_name_last(
+        &self,
+        assertion: &crate::saml::SamlAssertion,
+    ) -> Result<String, anyhow::Error> {
+        let attribute = match &self.name_last_attribute {
+            Some(attribute) => attribute,
+            None => return Ok("Last".to_string()),
+        };
+
+        Ok(assertion
+            .attribute(attribute)
+            .ok_or_else(|| {
+                crate::response::DisplayError::new(format!(
+                    "unable to extract last name from attribute {attribute}"
+                ))
+            })?
+            .to_string())
+    }
+
+    #[inline]
+    pub fn into_admin_api_object(self) -> AdminApiSamlProvider {
+        AdminApiSamlProvider {
+            uuid: self.uuid,
+            name: self.name,
+            description: self.description,
+            idp_entity_id: self.idp_entity_id,
+            idp_sso_url: self.idp_sso_url,
+            idp_certificate: self.idp_certificate,
+            sp_certificate: self.sp_certificate,
+            identifier_attribute: self.identifier_attribute,
+            email_attribute: self.email_attribute,
+            username_attribute: self.username_attribute,
+            name_first_attribute: self.name_first_attribute,
+            name_last_attribute: self.name_last_attribute,
+            enabled: self.enabled,
+            login_only: self.login_only,
+            link_viewable: self.link_viewable,
+            user_manageable: self.user_manageable,
+            created: self.created.and_utc(),
+        }
+    }
+
+    #[inline]
+    pub fn into_api_object(self) -> ApiSamlProvider {
+        ApiSamlProvider {
+            uuid: self.uuid,
+            name: self.name,
+            link_viewable: self.link_viewable,
+            user_manageable: self.user_manageable,
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl ByUuid for SamlProvider {
+    async fn by_uuid(
+        database: &crate::database::Database,
+        uuid: uuid::Uuid,
+    ) -> Result<Self, crate::database::DatabaseError> {
+        let row = sqlx::query(&format!(
+            r#"
+            SELECT {}
+            FROM saml_providers
+            WHERE saml_providers.uuid = $1
+            "#,
+            Self::columns_sql(None)
+        ))
+        .bind(uuid)
+        .fetch_one(database.read())
+        .await?;
+
+        Self::map(None, &row)
+    }
+}
+
+#[async_trait::async_trait]
+impl DeletableModel for SamlProvider {
+    type DeleteOptions = ();
+
+    fn get_delete_handlers() -> &'static LazyLock<DeleteListenerList<Self>> {
+        static DELETE_LISTENERS: LazyLock<DeleteListenerList<SamlProvider>> =
+            LazyLock::new(|| Arc::new(ModelHandlerList::default()));
+
+        &DELETE_LISTENERS
+    }
+
+    async fn delete(
+        &self,
+        state: &crate::State,
+        options: Self::DeleteOptions,
+    ) -> Result<(), anyhow::Error> {
+        let mut transaction = state.database.write().begin().await?;
+
+        self.run_delete_handlers(&options, state, &mut transaction)
+            .await?;
+
+        sqlx::query(
+            r#"
+            DELETE FROM saml_providers
+            WHERE saml_providers.uuid = $1
+            "#,
+        )
+        .bind(self.uuid)
+        .execute(&mut *transaction)
+        .await?;
+
+        transaction.commit().await?;
+
+        Ok(())
+    }
+}
+
+#[derive(ToSchema, Serialize)]
+#[schema(title = "AdminSamlProvider")]
+pub struct AdminApiSamlProvider {
+    pub uuid: uuid::Uuid,
+
+    pub name: compact_str::CompactString,
+    pub description: Option<compact_str::CompactString>,
+
+    pub idp_entity_id: String,
+    pub idp_sso_url: String,
+    pub idp_certificate: String,
+    pub sp_certificate: String,
+
+    pub identifier_attribute: Option<String>,
+    pub email_attribute: Option<String>,
+    pub username_attribute: Option<String>,
+    pub name_first_attribute: Option<String>,
+    pub name_last_attribute: Option<String>,
+
+    pub enabled: bool,
+    pub login_only: bool,
+    pub link_viewable: bool,
+    pub user_manageable: bool,
+
+    pub created: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(ToSchema, Serialize)]
+#[schema(title = "SamlProvider")]
+pub struct ApiSamlProvider {
+    pub uuid: uuid::Uuid,
+
+    pub name: compact_str::CompactString,
+
+    pub link_viewable: bool,
+    pub user_manageable: bool,
+}
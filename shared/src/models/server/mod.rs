@@ -113,6 +113,12 @@ pub struct Server {
 
     pub status: Option<ServerStatus>,
     pub suspended: bool,
+    pub suspension_reason: Option<compact_str::CompactString>,
+    pub suspended_by_uuid: Option<uuid::Uuid>,
+    pub suspended_at: Option<chrono::NaiveDateTime>,
+    pub unsuspend_at: Option<chrono::NaiveDateTime>,
+    pub expires_at: Option<chrono::NaiveDateTime>,
+    pub expiry_notified: bool,
 
     pub name: compact_str::CompactString,
     pub description: Option<compact_str::CompactString>,
@@ -187,6 +193,30 @@ impl BaseModel for Server {
                 "servers.suspended",
                 compact_str::format_compact!("{prefix}suspended"),
             ),
+            (
+                "servers.suspension_reason",
+                compact_str::format_compact!("{prefix}suspension_reason"),
+            ),
+            (
+                "servers.suspended_by_uuid",
+                compact_str::format_compact!("{prefix}suspended_by_uuid"),
+            ),
+            (
+                "servers.suspended_at",
+                compact_str::format_compact!("{prefix}suspended_at"),
+            ),
+            (
+                "servers.unsuspend_at",
+                compact_str::format_compact!("{prefix}unsuspend_at"),
+            ),
+            (
+                "servers.expires_at",
+                compact_str::format_compact!("{prefix}expires_at"),
+            ),
+            (
+                "servers.expiry_notified",
+                compact_str::format_compact!("{prefix}expiry_notified"),
+            ),
             ("servers.name", compact_str::format_compact!("{prefix}name")),
             (
                 "servers.description",
@@ -311,6 +341,17 @@ impl BaseModel for Server {
                 ),
             status: row.try_get(compact_str::format_compact!("{prefix}status").as_str())?,
             suspended: row.try_get(compact_str::format_compact!("{prefix}suspended").as_str())?,
+            suspension_reason: row
+                .try_get(compact_str::format_compact!("{prefix}suspension_reason").as_str())?,
+            suspended_by_uuid: row
+                .try_get(compact_str::format_compact!("{prefix}suspended_by_uuid").as_str())?,
+            suspended_at: row
+                .try_get(compact_str::format_compact!("{prefix}suspended_at").as_str())?,
+            unsuspend_at: row
+                .try_get(compact_str::format_compact!("{prefix}unsuspend_at").as_str())?,
+            expires_at: row.try_get(compact_str::format_compact!("{prefix}expires_at").as_str())?,
+            expiry_notified: row
+                .try_get(compact_str::format_compact!("{prefix}expiry_notified").as_str())?,
             name: row.try_get(compact_str::format_compact!("{prefix}name").as_str())?,
             description: row
                 .try_get(compact_str::format_compact!("{prefix}description").as_str())?,
@@ -379,9 +420,69 @@ impl Server {
         hugepages_passthrough_enabled: bool,
         kvm_passthrough_enabled: bool,
         feature_limits: &ApiServerFeatureLimits,
-        variables: &HashMap<uuid::Uuid, &'_ str>,
+        variables: &[(&super::nest_egg_variable::NestEggVariable, &'_ str)],
     ) -> Result<uuid::Uuid, crate::database::DatabaseError> {
         let mut transaction = database.write().begin().await?;
+
+        let uuid = Self::create_with_transaction(
+            &mut transaction,
+            database,
+            node,
+            owner_uuid,
+            egg_uuid,
+            backup_configuration_uuid,
+            allocation_uuid,
+            allocation_uuids,
+            external_id,
+            skip_installer,
+            name,
+            description,
+            limits,
+            pinned_cpus,
+            startup,
+            image,
+            timezone,
+            hugepages_passthrough_enabled,
+            kvm_passthrough_enabled,
+            feature_limits,
+            variables,
+        )
+        .await?;
+
+        transaction.commit().await?;
+
+        Self::create_on_node(database, node, uuid, start_on_completion, skip_installer).await?;
+
+        Ok(uuid)
+    }
+
+    /// Inserts the server with its allocations and variables, without creating it on the node,
+    /// so more rows can be added in the same transaction. Call [`Self::create_on_node`] once
+    /// the transaction is committed.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn create_with_transaction(
+        transaction: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        database: &crate::database::Database,
+        node: &super::node::Node,
+        owner_uuid: uuid::Uuid,
+        egg_uuid: uuid::Uuid,
+        backup_configuration_uuid: Option<uuid::Uuid>,
+        allocation_uuid: Option<uuid::Uuid>,
+        allocation_uuids: &[uuid::Uuid],
+        external_id: Option<&str>,
+        skip_installer: bool,
+        name: &str,
+        description: Option<&str>,
+        limits: &ApiServerLimits,
+        pinned_cpus: &[i16],
+        startup: &str,
+        image: &str,
+        timezone: Option<&str>,
+        hugepages_passthrough_enabled: bool,
+        kvm_passthrough_enabled: bool,
+        feature_limits: &ApiServerFeatureLimits,
+        variables: &[(&super::nest_egg_variable::NestEggVariable, &'_ str)],
+    ) -> Result<uuid::Uuid, crate::database::DatabaseError> {
         let mut attempts = 0;
 
         loop {
@@ -455,7 +556,7 @@ impl Server {
             .bind(feature_limits.databases)
             .bind(feature_limits.backups)
             .bind(feature_limits.schedules)
-            .fetch_one(&mut *transaction)
+            .fetch_one(&mut **transaction)
             .await
             {
                 Ok(row) => {
@@ -472,7 +573,7 @@ impl Server {
                             )
                             .bind(uuid)
                             .bind(allocation_uuid)
-                            .fetch_one(&mut *transaction)
+                            .fetch_one(&mut **transaction)
                             .await?;
 
                             Some(row.get("uuid"))
@@ -489,7 +590,7 @@ impl Server {
                         )
                         .bind(uuid)
                         .bind(allocation_uuid)
-                        .execute(&mut *transaction)
+                        .execute(&mut **transaction)
                         .await?;
                     }
 
@@ -502,43 +603,30 @@ impl Server {
                     )
                     .bind(allocation_uuid)
                     .bind(uuid)
-                    .execute(&mut *transaction)
+                    .execute(&mut **transaction)
                     .await?;
 
-                    for (variable_uuid, value) in variables {
+                    for (variable, value) in variables {
+                        let (value, value_encrypted) =
+                            super::server_variable::ServerVariable::encode_value(
+                                database, variable, value,
+                            )
+                            .await?;
+
                         sqlx::query(
                             r#"
-                            INSERT INTO server_variables (server_uuid, variable_uuid, value)
-                            VALUES ($1, $2, $3)
+                            INSERT INTO server_variables (server_uuid, variable_uuid, value, value_encrypted)
+                            VALUES ($1, $2, $3, $4)
                             "#,
                         )
                         .bind(uuid)
-                        .bind(variable_uuid)
+                        .bind(variable.uuid)
                         .bind(value)
-                        .execute(&mut *transaction)
+                        .bind(value_encrypted)
+                        .execute(&mut **transaction)
                         .await?;
                     }
 
-                    transaction.commit().await?;
-
-                    if let Err(err) = node
-                        .api_client(database)
-                        .post_servers(&wings_api::servers::post::RequestBody {
-                            uuid,
-                            start_on_completion,
-                            skip_scripts: skip_installer,
-                        })
-                        .await
-                    {
-                        tracing::error!(server = %uuid, node = %node.uuid, "failed to create server: {:?}", err);
-
-                        sqlx::query!("DELETE FROM servers WHERE servers.uuid = $1", uuid)
-                            .execute(database.write())
-                            .await?;
-
-                        return Err(err.into());
-                    }
-
                     return Ok(uuid);
                 }
                 Err(err) => {
@@ -547,7 +635,6 @@ impl Server {
                             "failed to create server after 8 attempts, giving up: {:#?}",
                             err
                         );
-                        transaction.rollback().await?;
 
                         return Err(err.into());
                     }
@@ -564,6 +651,36 @@ impl Server {
         }
     }
 
+    /// Creates a server inserted with [`Self::create_with_transaction`] on its node, and
+    /// deletes it again if the node rejects it.
+    pub async fn create_on_node(
+        database: &crate::database::Database,
+        node: &super::node::Node,
+        uuid: uuid::Uuid,
+        start_on_completion: bool,
+        skip_installer: bool,
+    ) -> Result<(), crate::database::DatabaseError> {
+        if let Err(err) = node
+            .api_client(database)
+            .post_servers(&wings_api::servers::post::RequestBody {
+                uuid,
+                start_on_completion,
+                skip_scripts: skip_installer,
+            })
+            .await
+        {
+            tracing::error!(server = %uuid, node = %node.uuid, "failed to create server: {:?}", err);
+
+            sqlx::query!("DELETE FROM servers WHERE servers.uuid = $1", uuid)
+                .execute(database.write())
+                .await?;
+
+            return Err(err.into());
+        }
+
+        Ok(())
+    }
+
     pub async fn by_node_uuid_uuid(
         database: &crate::database::Database,
         node_uuid: uuid::Uuid,
@@ -799,6 +916,7 @@ impl Server {
     pub async fn by_user_uuid_with_pagination(
         database: &crate::database::Database,
         user_uuid: uuid::Uuid,
+        server_uuids: Option<&[uuid::Uuid]>,
         page: i64,
         per_page: i64,
         search: Option<&str>,
@@ -819,6 +937,7 @@ impl Server {
             WHERE
                 (servers.owner_uuid = $1 OR server_subusers.user_uuid = $1)
                 AND ($2 IS NULL OR servers.name ILIKE '%' || $2 || '%' OR users.username ILIKE '%' || $2 || '%' OR users.email ILIKE '%' || $2 || '%')
+                AND ($5::uuid[] IS NULL OR servers.uuid = ANY($5))
             ORDER BY servers.created
             LIMIT $3 OFFSET $4
             "#,
@@ -828,6 +947,7 @@ impl Server {
         .bind(search)
         .bind(per_page)
         .bind(offset)
+        .bind(server_uuids)
         .fetch_all(database.read())
         .await?;
 
@@ -872,6 +992,7 @@ impl Server {
     pub async fn by_not_user_uuid_with_pagination(
         database: &crate::database::Database,
         user_uuid: uuid::Uuid,
+        server_uuids: Option<&[uuid::Uuid]>,
         page: i64,
         per_page: i64,
         search: Option<&str>,
@@ -892,6 +1013,7 @@ impl Server {
             WHERE
                 servers.owner_uuid != $1 AND (server_subusers.user_uuid IS NULL OR server_subusers.user_uuid != $1)
                 AND ($2 IS NULL OR servers.name ILIKE '%' || $2 || '%' OR users.username ILIKE '%' || $2 || '%' OR users.email ILIKE '%' || $2 || '%')
+                AND ($5::uuid[] IS NULL OR servers.uuid = ANY($5))
             ORDER BY servers.created
             LIMIT $3 OFFSET $4
             "#,
@@ -901,6 +1023,7 @@ impl Server {
         .bind(search)
         .bind(per_page)
         .bind(offset)
+        .bind(server_uuids)
         .fetch_all(database.read())
         .await?;
 
@@ -1146,6 +1269,310 @@ impl Server {
         .unwrap_or(0)
     }
 
+    async fn all_with_condition(
+        database: &crate::database::Database,
+        condition: &str,
+        time: chrono::NaiveDateTime,
+    ) -> Result<Vec<Self>, crate::database::DatabaseError> {
+        let rows = sqlx::query(&format!(
+            r#"
+            SELECT {}
+            FROM servers
+            LEFT JOIN server_allocations ON server_allocations.uuid = servers.allocation_uuid
+            LEFT JOIN node_allocations ON node_allocations.uuid = server_allocations.allocation_uuid
+            JOIN users ON users.uuid = servers.owner_uuid
+            LEFT JOIN roles ON roles.uuid = users.role_uuid
+            JOIN nest_eggs ON nest_eggs.uuid = servers.egg_uuid
+            JOIN nests ON nests.uuid = nest_eggs.nest_uuid
+            WHERE {condition}
+            ORDER BY servers.created
+            "#,
+            Self::columns_sql(None)
+        ))
+        .bind(time)
+        .fetch_all(database.read())
+        .await?;
+
+        rows.into_iter()
+            .map(|row| Self::map(None, &row))
+            .try_collect_vec()
+    }
+
+    /// Returns suspended servers whose automatic un-suspend time has been reached.
+    pub async fn all_pending_unsuspension(
+        database: &crate::database::Database,
+    ) -> Result<Vec<Self>, crate::database::DatabaseError> {
+        Self::all_with_condition(
+            database,
+            "servers.suspended AND servers.unsuspend_at <= $1",
+            chrono::Utc::now().naive_utc(),
+        )
+        .await
+    }
+
+    /// Returns servers expiring before `before` whose owner has not been notified yet.
+    /// Servers that have already expired are left out.
+    pub async fn all_pending_expiry_notice(
+        database: &crate::database::Database,
+        before: chrono::NaiveDateTime,
+    ) -> Result<Vec<Self>, crate::database::DatabaseError> {
+        Self::all_with_condition(
+            database,
+            "NOT servers.expiry_notified AND servers.expires_at <= $1 AND servers.expires_at > NOW()",
+            before,
+        )
+        .await
+    }
+
+    /// Returns expired servers that have not been suspended yet.
+    pub async fn all_expired_unsuspended(
+        database: &crate::database::Database,
+    ) -> Result<Vec<Self>, crate::database::DatabaseError> {
+        Self::all_with_condition(
+            database,
+            "NOT servers.suspended AND servers.expires_at <= $1",
+            chrono::Utc::now().naive_utc(),
+        )
+        .await
+    }
+
+    /// Returns servers that expired before `before`.
+    pub async fn all_expired_before(
+        database: &crate::database::Database,
+        before: chrono::NaiveDateTime,
+    ) -> Result<Vec<Self>, crate::database::DatabaseError> {
+        Self::all_with_condition(database, "servers.expires_at <= $1", before).await
+    }
+
+    /// Returns all servers matching every non-empty list of the filter.
+    pub async fn all_by_bulk_filter(
+        database: &crate::database::Database,
+        filter: &super::server_bulk_operation::ServerBulkOperationFilter,
+    ) -> Result<Vec<Self>, crate::database::DatabaseError> {
+        let rows = sqlx::query(&format!(
+            r#"
+            SELECT {}
+            FROM servers
+            LEFT JOIN server_allocations ON server_allocations.uuid = servers.allocation_uuid
+            LEFT JOIN node_allocations ON node_allocations.uuid = server_allocations.allocation_uuid
+            JOIN users ON users.uuid = servers.owner_uuid
+            LEFT JOIN roles ON roles.uuid = users.role_uuid
+            JOIN nest_eggs ON nest_eggs.uuid = servers.egg_uuid
+            JOIN nests ON nests.uuid = nest_eggs.nest_uuid
+            JOIN nodes ON nodes.uuid = servers.node_uuid
+            WHERE (cardinality($1::uuid[]) = 0 OR servers.node_uuid = ANY($1))
+                AND (cardinality($2::uuid[]) = 0 OR nodes.location_uuid = ANY($2))
+                AND (cardinality($3::uuid[]) = 0 OR nests.uuid = ANY($3))
+                AND (cardinality($4::uuid[]) = 0 OR servers.egg_uuid = ANY($4))
+                AND (cardinality($5::uuid[]) = 0 OR servers.owner_uuid = ANY($5))
+                AND (cardinality($6::uuid[]) = 0 OR servers.uuid = ANY($6))
+            ORDER BY servers.created
+            "#,
+            Self::columns_sql(None)
+        ))
+        .bind(&filter.node_uuids)
+        .bind(&filter.location_uuids)
+        .bind(&filter.nest_uuids)
+        .bind(&filter.egg_uuids)
+        .bind(&filter.owner_uuids)
+        .bind(&filter.server_uuids)
+        .fetch_all(database.read())
+        .await?;
+
+        rows.into_iter()
+            .map(|row| Self::map(None, &row))
+            .try_collect_vec()
+    }
+
+    /// Suspends the server, the node has to be synced afterwards for it to take effect.
+    pub async fn suspend(
+        &mut self,
+        database: &crate::database::Database,
+        reason: Option<&str>,
+        suspended_by_uuid: Option<uuid::Uuid>,
+        unsuspend_at: Option<chrono::NaiveDateTime>,
+    ) -> Result<(), crate::database::DatabaseError> {
+        self.set_suspended(reason, suspended_by_uuid, unsuspend_at);
+
+        sqlx::query(
+            r#"
+            UPDATE servers
+            SET suspended = true, suspension_reason = $2, suspended_by_uuid = $3, suspended_at = $4, unsuspend_at = $5
+            WHERE servers.uuid = $1
+            "#,
+        )
+        .bind(self.uuid)
+        .bind(&self.suspension_reason)
+        .bind(self.suspended_by_uuid)
+        .bind(self.suspended_at)
+        .bind(self.unsuspend_at)
+        .execute(database.write())
+        .await?;
+
+        Ok(())
+    }
+
+    /// Marks the server as suspended without persisting it, see [`Self::suspend`].
+    pub fn set_suspended(
+        &mut self,
+        reason: Option<&str>,
+        suspended_by_uuid: Option<uuid::Uuid>,
+        unsuspend_at: Option<chrono::NaiveDateTime>,
+    ) {
+        // updating an existing suspension keeps when it started
+        let suspended_at = match self.suspended_at {
+            Some(suspended_at) if self.suspended => suspended_at,
+            _ => chrono::Utc::now().naive_utc(),
+        };
+
+        self.suspended = true;
+        self.suspension_reason = reason.map(compact_str::CompactString::from);
+        self.suspended_by_uuid = suspended_by_uuid;
+        self.suspended_at = Some(suspended_at);
+        self.unsuspend_at = unsuspend_at;
+    }
+
+    /// Lifts the suspension of the server, the node has to be synced afterwards for it to take effect.
+    pub async fn unsuspend(
+        &mut self,
+        database: &crate::database::Database,
+    ) -> Result<(), crate::database::DatabaseError> {
+        sqlx::query(
+            r#"
+            UPDATE servers
+            SET suspended = false, suspension_reason = NULL, suspended_by_uuid = NULL, suspended_at = NULL, unsuspend_at = NULL
+            WHERE servers.uuid = $1
+            "#,
+        )
+        .bind(self.uuid)
+        .execute(database.write())
+        .await?;
+
+        self.set_unsuspended();
+
+        Ok(())
+    }
+
+    /// Marks the server as no longer suspended without persisting it, see [`Self::unsuspend`].
+    pub fn set_unsuspended(&mut self) {
+        self.suspended = false;
+        self.suspension_reason = None;
+        self.suspended_by_uuid = None;
+        self.suspended_at = None;
+        self.unsuspend_at = None;
+    }
+
+    /// Sets when the server expires, resetting whether its owner has been notified about it.
+    pub async fn update_expires_at(
+        &mut self,
+        database: &crate::database::Database,
+        expires_at: Option<chrono::NaiveDateTime>,
+    ) -> Result<(), crate::database::DatabaseError> {
+        sqlx::query(
+            r#"
+            UPDATE servers
+            SET expires_at = $2, expiry_notified = false
+            WHERE servers.uuid = $1
+            "#,
+        )
+        .bind(self.uuid)
+        .bind(expires_at)
+        .execute(database.write())
+        .await?;
+
+        self.expires_at = expires_at;
+        self.expiry_notified = false;
+
+        Ok(())
+    }
+
+    pub async fn mark_expiry_notified(
+        &mut self,
+        database: &crate::database::Database,
+    ) -> Result<(), crate::database::DatabaseError> {
+        sqlx::query(
+            r#"
+            UPDATE servers
+            SET expiry_notified = true
+            WHERE servers.uuid = $1
+            "#,
+        )
+        .bind(self.uuid)
+        .execute(database.write())
+        .await?;
+
+        self.expiry_notified = true;
+
+        Ok(())
+    }
+
+    /// Moves the server to another egg, replacing all stored variable values with
+    /// the given values for the variables of the new egg.
+    pub async fn change_egg(
+        &mut self,
+        database: &crate::database::Database,
+        egg: super::nest_egg::NestEgg,
+        variables: &[(super::nest_egg_variable::NestEggVariable, String)],
+        startup: Option<compact_str::CompactString>,
+        image: Option<compact_str::CompactString>,
+    ) -> Result<(), anyhow::Error> {
+        let startup = startup.unwrap_or_else(|| self.startup.clone());
+        let image = image.unwrap_or_else(|| self.image.clone());
+
+        let mut transaction = database.write().begin().await?;
+
+        sqlx::query(
+            r#"
+            UPDATE servers
+            SET egg_uuid = $2, startup = $3, image = $4
+            WHERE servers.uuid = $1
+            "#,
+        )
+        .bind(self.uuid)
+        .bind(egg.uuid)
+        .bind(startup.as_str())
+        .bind(image.as_str())
+        .execute(&mut *transaction)
+        .await?;
+
+        sqlx::query(
+            r#"
+            DELETE FROM server_variables
+            WHERE server_variables.server_uuid = $1
+            "#,
+        )
+        .bind(self.uuid)
+        .execute(&mut *transaction)
+        .await?;
+
+        for (variable, value) in variables {
+            let (value, value_encrypted) =
+                super::server_variable::ServerVariable::encode_value(database, variable, value)
+                    .await?;
+
+            sqlx::query(
+                r#"
+                INSERT INTO server_variables (server_uuid, variable_uuid, value, value_encrypted)
+                VALUES ($1, $2, $3, $4)
+                "#,
+            )
+            .bind(self.uuid)
+            .bind(variable.uuid)
+            .bind(value)
+            .bind(value_encrypted)
+            .execute(&mut *transaction)
+            .await?;
+        }
+
+        transaction.commit().await?;
+
+        *self.egg = egg;
+        self.startup = startup;
+        self.image = image;
+
+        Ok(())
+    }
+
     pub async fn sync(self, database: &crate::database::Database) -> Result<(), anyhow::Error> {
         self.node
             .fetch_cached(database)
@@ -1383,16 +1810,15 @@ impl Server {
         self,
         database: &crate::database::Database,
     ) -> Result<RemoteApiServer, anyhow::Error> {
-        let (variables, backups, schedules, mounts, allocations) = tokio::try_join!(
-            sqlx::query!(
-                "SELECT nest_egg_variables.env_variable, COALESCE(server_variables.value, nest_egg_variables.default_value) AS value
-                FROM nest_egg_variables
-                LEFT JOIN server_variables ON server_variables.variable_uuid = nest_egg_variables.uuid AND server_variables.server_uuid = $1
-                WHERE nest_egg_variables.egg_uuid = $2",
-                self.uuid,
-                self.egg.uuid
-            )
-            .fetch_all(database.read()),
+        let mut variables = super::server_variable::ServerVariable::all_by_server_uuid_egg_uuid(
+            database,
+            self.uuid,
+            self.egg.uuid,
+        )
+        .await?;
+        super::server_variable::ServerVariable::decrypt_all(database, &mut variables).await?;
+
+        let (backups, schedules, mounts, allocations) = tokio::try_join!(
             sqlx::query!(
                 "SELECT server_backups.uuid
                 FROM server_backups
@@ -1462,12 +1888,7 @@ impl Server {
                 skip_egg_scripts: false,
                 environment: variables
                     .into_iter()
-                    .map(|v| {
-                        (
-                            v.env_variable.into(),
-                            serde_json::Value::String(v.value.unwrap_or_default()),
-                        )
-                    })
+                    .map(|v| (v.variable.env_variable, serde_json::Value::String(v.value)))
                     .collect(),
                 labels: IndexMap::new(),
                 backups: backups.into_iter().map(|b| b.uuid).collect(),
@@ -1613,6 +2034,11 @@ impl Server {
             backup_configuration,
             status: self.status,
             suspended: self.suspended,
+            suspension_reason: self.suspension_reason,
+            suspended_by_uuid: self.suspended_by_uuid,
+            suspended_at: self.suspended_at.map(|dt| dt.and_utc()),
+            unsuspend_at: self.unsuspend_at.map(|dt| dt.and_utc()),
+            expires_at: self.expires_at.map(|dt| dt.and_utc()),
             name: self.name,
             description: self.description,
             limits: ApiServerLimits {
@@ -1674,6 +2100,8 @@ impl Server {
             sftp_port: node.sftp_port,
             status: self.status,
             suspended: self.suspended,
+            suspension_reason: self.suspension_reason,
+            expires_at: self.expires_at.map(|dt| dt.and_utc()),
             name: self.name,
             description: self.description,
             limits: ApiServerLimits {
@@ -1845,6 +2273,32 @@ pub struct ApiServerFeatureLimits {
     pub schedules: i32,
 }
 
+#[derive(ToSchema, Validate, Serialize, Deserialize, Clone)]
+pub struct ApiServerDeploymentPortRange {
+    #[validate(range(min = 1))]
+    #[schema(minimum = 1)]
+    pub start: u16,
+    #[validate(range(min = 1))]
+    #[schema(minimum = 1)]
+    pub end: u16,
+}
+
+/// Where a server may be deployed, the first node with enough free resources and
+/// allocations in one of the locations is picked.
+#[derive(ToSchema, Validate, Serialize, Deserialize, Clone)]
+pub struct ApiServerDeployment {
+    #[validate(length(min = 1))]
+    #[schema(min_items = 1)]
+    pub location_uuids: Vec<uuid::Uuid>,
+    #[validate(nested)]
+    #[schema(inline)]
+    #[serde(default)]
+    pub port_ranges: Vec<ApiServerDeploymentPortRange>,
+    #[validate(range(max = 100))]
+    #[schema(maximum = 100)]
+    pub allocations: u16,
+}
+
 #[derive(ToSchema, Serialize)]
 #[schema(title = "AdminServer")]
 pub struct AdminApiServer {
@@ -1860,6 +2314,11 @@ pub struct AdminApiServer {
 
     pub status: Option<ServerStatus>,
     pub suspended: bool,
+    pub suspension_reason: Option<compact_str::CompactString>,
+    pub suspended_by_uuid: Option<uuid::Uuid>,
+    pub suspended_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub unsuspend_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub expires_at: Option<chrono::DateTime<chrono::Utc>>,
 
     pub name: compact_str::CompactString,
     pub description: Option<compact_str::CompactString>,
@@ -1893,6 +2352,8 @@ pub struct ApiServer {
 
     pub status: Option<ServerStatus>,
     pub suspended: bool,
+    pub suspension_reason: Option<compact_str::CompactString>,
+    pub expires_at: Option<chrono::DateTime<chrono::Utc>>,
 
     pub is_owner: bool,
     pub permissions: Vec<compact_str::CompactString>,
@@ -569,6 +569,31 @@ impl ServerBackup {
             .try_collect_vec()
     }
 
+    /// Returns the most recent successful backup of a server.
+    pub async fn latest_successful_by_server_uuid(
+        database: &crate::database::Database,
+        server_uuid: uuid::Uuid,
+    ) -> Result<Option<Self>, crate::database::DatabaseError> {
+        let row = sqlx::query(&format!(
+            r#"
+            SELECT {}
+            FROM server_backups
+            WHERE server_backups.server_uuid = $1
+                AND server_backups.successful = true
+                AND server_backups.completed IS NOT NULL
+                AND server_backups.deleted IS NULL
+            ORDER BY server_backups.created DESC
+            LIMIT 1
+            "#,
+            Self::columns_sql(None)
+        ))
+        .bind(server_uuid)
+        .fetch_optional(database.read())
+        .await?;
+
+        row.try_map(|row| Self::map(None, &row))
+    }
+
     pub async fn count_by_server_uuid(
         database: &crate::database::Database,
         server_uuid: uuid::Uuid,
@@ -631,7 +656,12 @@ impl ServerBackup {
                                 let client = s3_configuration.into_client()?;
                                 let file_path = match &self.upload_path {
                                     Some(path) => path.as_str(),
-                                    None => &Self::s3_path(server.uuid, self.uuid),
+                                    None => &Self::s3_path(
+                                        self.server.as_ref().map_or(server.uuid, |backup_server| {
+                                            backup_server.uuid
+                                        }),
+                                        self.uuid,
+                                    ),
                                 };
 
                                 Some(client.presign_get(file_path, 60 * 60, None).await?.into())
@@ -0,0 +1,804 @@
+use crate::prelude::*;
+use serde::{Deserialize, Serialize};
+use sqlx::{Row, postgres::PgRow};
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::Arc,
+};
+use utoipa::ToSchema;
+
+/// How often a running operation records that it is still alive.
+const HEARTBEAT_INTERVAL: std::time::Duration = std::time::Duration::from_secs(30);
+
+/// How long an operation may go without a heartbeat before it is considered orphaned.
+const HEARTBEAT_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(120);
+
+#[derive(ToSchema, Serialize, Deserialize, Default, Clone)]
+pub struct ServerBulkOperationFilter {
+    #[serde(default)]
+    pub node_uuids: Vec<uuid::Uuid>,
+    #[serde(default)]
+    pub location_uuids: Vec<uuid::Uuid>,
+    #[serde(default)]
+    pub nest_uuids: Vec<uuid::Uuid>,
+    #[serde(default)]
+    pub egg_uuids: Vec<uuid::Uuid>,
+    #[serde(default)]
+    pub owner_uuids: Vec<uuid::Uuid>,
+    #[serde(default)]
+    pub server_uuids: Vec<uuid::Uuid>,
+}
+
+impl ServerBulkOperationFilter {
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.node_uuids.is_empty()
+            && self.location_uuids.is_empty()
+            && self.nest_uuids.is_empty()
+            && self.egg_uuids.is_empty()
+            && self.owner_uuids.is_empty()
+            && self.server_uuids.is_empty()
+    }
+}
+
+#[derive(ToSchema, Serialize, Deserialize, Clone)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ServerBulkOperationAction {
+    Power {
+        action: wings_api::ServerPowerAction,
+    },
+    Suspend {
+        reason: Option<compact_str::CompactString>,
+    },
+    Unsuspend,
+    Reinstall {
+        #[serde(default)]
+        truncate_directory: bool,
+    },
+    UpdateImage {
+        image: compact_str::CompactString,
+    },
+    UpdateVariable {
+        env_variable: compact_str::CompactString,
+        value: compact_str::CompactString,
+    },
+    Sync,
+}
+
+impl ServerBulkOperationAction {
+    /// The admin permission needed to run this action on a single server.
+    #[inline]
+    pub fn permission(&self) -> &'static str {
+        match self {
+            Self::Power { .. } => "nodes.power",
+            Self::UpdateVariable { .. } => "servers.variables",
+            _ => "servers.update",
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), &'static str> {
+        match self {
+            Self::Suspend {
+                reason: Some(reason),
+            } if reason.len() > 1024 => Err("suspension reason must be at most 1024 characters"),
+            Self::UpdateImage { image } if image.len() < 2 || image.len() > 255 => {
+                Err("image must be between 2 and 255 characters")
+            }
+            Self::UpdateVariable { env_variable, .. }
+                if env_variable.is_empty() || env_variable.len() > 255 =>
+            {
+                Err("env_variable must be between 1 and 255 characters")
+            }
+            Self::UpdateVariable { value, .. } if value.len() > 4096 => {
+                Err("value must be at most 4096 characters")
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Returns a copy that is safe to persist, variable values may be secret.
+    pub fn redacted(&self) -> Self {
+        match self {
+            Self::UpdateVariable { env_variable, .. } => Self::UpdateVariable {
+                env_variable: env_variable.clone(),
+                value: compact_str::CompactString::default(),
+            },
+            action => action.clone(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct ServerBulkOperation {
+    pub uuid: uuid::Uuid,
+    pub user_uuid: Option<uuid::Uuid>,
+
+    pub action: ServerBulkOperationAction,
+    pub filter: ServerBulkOperationFilter,
+    pub concurrency: i16,
+
+    pub total: i32,
+    pub successful: i32,
+    pub failed: i32,
+
+    pub server_uuids: Vec<uuid::Uuid>,
+
+    pub completed: Option<chrono::NaiveDateTime>,
+    pub created: chrono::NaiveDateTime,
+}
+
+impl BaseModel for ServerBulkOperation {
+    const NAME: &'static str = "server_bulk_operation";
+
+    #[inline]
+    fn columns(prefix: Option<&str>) -> BTreeMap<&'static str, compact_str::CompactString> {
+        let prefix = prefix.unwrap_or_default();
+
+        BTreeMap::from([
+            (
+                "server_bulk_operations.uuid",
+                compact_str::format_compact!("{prefix}uuid"),
+            ),
+            (
+                "server_bulk_operations.user_uuid",
+                compact_str::format_compact!("{prefix}user_uuid"),
+            ),
+            (
+                "server_bulk_operations.action",
+                compact_str::format_compact!("{prefix}action"),
+            ),
+            (
+                "server_bulk_operations.filter",
+                compact_str::format_compact!("{prefix}filter"),
+            ),
+            (
+                "server_bulk_operations.concurrency",
+                compact_str::format_compact!("{prefix}concurrency"),
+            ),
+            (
+                "server_bulk_operations.total",
+                compact_str::format_compact!("{prefix}total"),
+            ),
+            (
+                "server_bulk_operations.successful",
+                compact_str::format_compact!("{prefix}successful"),
+            ),
+            (
+                "server_bulk_operations.failed",
+                compact_str::format_compact!("{prefix}failed"),
+            ),
+            (
+                "server_bulk_operations.server_uuids",
+                compact_str::format_compact!("{prefix}server_uuids"),
+            ),
+            (
+                "server_bulk_operations.completed",
+                compact_str::format_compact!("{prefix}completed"),
+            ),
+            (
+                "server_bulk_operations.created",
+                compact_str::format_compact!("{prefix}created"),
+            ),
+        ])
+    }
+
+    #[inline]
+    fn map(prefix: Option<&str>, row: &PgRow) -> Result<Self, crate::database::DatabaseError> {
+        let prefix = prefix.unwrap_or_default();
+
+        Ok(Self {
+            uuid: row.try_get(compact_str::format_compact!("{prefix}uuid").as_str())?,
+            user_uuid: row.try_get(compact_str::format_compact!("{prefix}user_uuid").as_str())?,
+            action: serde_json::from_value(
+                row.try_get(compact_str::format_compact!("{prefix}action").as_str())?,
+            )?,
+            filter: serde_json::from_value(
+                row.try_get(compact_str::format_compact!("{prefix}filter").as_str())?,
+            )?,
+            concurrency: row
+                .try_get(compact_str::format_compact!("{prefix}concurrency").as_str())?,
+            total: row.try_get(compact_str::format_compact!("{prefix}total").as_str())?,
+            successful: row.try_get(compact_str::format_compact!("{prefix}successful").as_str())?,
+            failed: row.try_get(compact_str::format_compact!("{prefix}failed").as_str())?,
+            server_uuids: row
+                .try_get(compact_str::format_compact!("{prefix}server_uuids").as_str())?,
+            completed: row.try_get(compact_str::format_compact!("{prefix}completed").as_str())?,
+            created: row.try_get(compact_str::format_compact!("{prefix}created").as_str())?,
+        })
+    }
+}
+
+impl ServerBulkOperation {
+    /// Creates the operation, the stored action is redacted while the returned one is not.
+    pub async fn create(
+        database: &crate::database::Database,
+        user_uuid: Option<uuid::Uuid>,
+        action: &ServerBulkOperationAction,
+        filter: &ServerBulkOperationFilter,
+        concurrency: i16,
+        server_uuids: &[uuid::Uuid],
+    ) -> Result<Self, crate::database::DatabaseError> {
+        let row = sqlx::query(&format!(
+            r#"
+            INSERT INTO server_bulk_operations (user_uuid, action, filter, concurrency, total, server_uuids)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            RETURNING {}
+            "#,
+            Self::columns_sql(None)
+        ))
+        .bind(user_uuid)
+        .bind(serde_json::to_value(action.redacted())?)
+        .bind(serde_json::to_value(filter)?)
+        .bind(concurrency)
+        .bind(server_uuids.len() as i32)
+        .bind(server_uuids)
+        .fetch_one(database.write())
+        .await?;
+
+        let mut operation = Self::map(None, &row)?;
+        operation.action = action.clone();
+
+        Ok(operation)
+    }
+
+    pub async fn by_uuid_optional(
+        database: &crate::database::Database,
+        uuid: uuid::Uuid,
+    ) -> Result<Option<Self>, crate::database::DatabaseError> {
+        let row = sqlx::query(&format!(
+            r#"
+            SELECT {}
+            FROM server_bulk_operations
+            WHERE server_bulk_operations.uuid = $1
+            "#,
+            Self::columns_sql(None)
+        ))
+        .bind(uuid)
+        .fetch_optional(database.read())
+        .await?;
+
+        row.try_map(|row| Self::map(None, &row))
+    }
+
+    pub async fn all_with_pagination(
+        database: &crate::database::Database,
+        page: i64,
+        per_page: i64,
+    ) -> Result<super::Pagination<Self>, crate::database::DatabaseError> {
+        let offset = (page - 1) * per_page;
+
+        let rows = sqlx::query(&format!(
+            r#"
+            SELECT {}, COUNT(*) OVER() AS total_count
+            FROM server_bulk_operations
+            ORDER BY server_bulk_operations.created DESC
+            LIMIT $1 OFFSET $2
+            "#,
+            Self::columns_sql(None)
+        ))
+        .bind(per_page)
+        .bind(offset)
+        .fetch_all(database.read())
+        .await?;
+
+        Ok(super::Pagination {
+            total: rows
+                .first()
+                .map_or(Ok(0), |row| row.try_get("total_count"))?,
+            per_page,
+            page,
+            data: rows
+                .into_iter()
+                .map(|row| Self::map(None, &row))
+                .try_collect_vec()?,
+        })
+    }
+
+    /// Claims the operations that are not completed and whose runner stopped sending
+    /// heartbeats, usually because the panel running them was restarted.
+    pub async fn claim_orphaned(
+        database: &crate::database::Database,
+    ) -> Result<Vec<Self>, crate::database::DatabaseError> {
+        let rows = sqlx::query(&format!(
+            r#"
+            UPDATE server_bulk_operations
+            SET heartbeat = NOW()
+            WHERE server_bulk_operations.completed IS NULL
+                AND server_bulk_operations.heartbeat < NOW() - make_interval(secs => $1)
+            RETURNING {}
+            "#,
+            Self::columns_sql(None)
+        ))
+        .bind(HEARTBEAT_TIMEOUT.as_secs_f64())
+        .fetch_all(database.write())
+        .await?;
+
+        rows.into_iter()
+            .map(|row| Self::map(None, &row))
+            .try_collect_vec()
+    }
+
+    /// Continues an orphaned operation on the servers that have no result yet. Servers that
+    /// were deleted in the meantime are recorded as failed, as are all remaining servers of
+    /// variable updates, because their value is never stored.
+    pub async fn resume(self, state: crate::State) -> Result<(), anyhow::Error> {
+        let remaining: Vec<(uuid::Uuid, Option<compact_str::CompactString>)> = sqlx::query_as(
+            r#"
+            SELECT pending.server_uuid, servers.name
+            FROM UNNEST($2::uuid[]) AS pending(server_uuid)
+            LEFT JOIN servers ON servers.uuid = pending.server_uuid
+            WHERE NOT EXISTS (
+                SELECT 1
+                FROM server_bulk_operation_results
+                WHERE server_bulk_operation_results.bulk_operation_uuid = $1
+                    AND server_bulk_operation_results.server_uuid = pending.server_uuid
+            )
+            "#,
+        )
+        .bind(self.uuid)
+        .bind(&self.server_uuids)
+        .fetch_all(state.database.write())
+        .await?;
+
+        let interrupted = matches!(
+            self.action,
+            ServerBulkOperationAction::UpdateVariable { .. }
+        );
+
+        let mut server_uuids = Vec::with_capacity(remaining.len());
+        for (server_uuid, server_name) in remaining {
+            let error = match &server_name {
+                None => "server no longer exists",
+                Some(_) if interrupted => {
+                    "the operation was interrupted by a panel restart and the variable value is not stored"
+                }
+                Some(_) => {
+                    server_uuids.push(server_uuid);
+                    continue;
+                }
+            };
+
+            self.record_result(
+                &state.database,
+                server_uuid,
+                server_name.as_deref().unwrap_or_default(),
+                Some(error),
+            )
+            .await?;
+        }
+
+        let servers = if server_uuids.is_empty() {
+            Vec::new()
+        } else {
+            super::server::Server::all_by_bulk_filter(
+                &state.database,
+                &ServerBulkOperationFilter {
+                    server_uuids,
+                    ..Default::default()
+                },
+            )
+            .await?
+        };
+
+        tracing::info!(
+            bulk_operation = %self.uuid,
+            "resuming bulk operation on {} servers",
+            servers.len()
+        );
+
+        self.spawn(state, servers);
+
+        Ok(())
+    }
+
+    /// Runs the action on every server in the background. Servers on the same node are
+    /// processed at most `concurrency` at a time, nodes are processed in parallel.
+    pub fn spawn(self, state: crate::State, servers: Vec<super::server::Server>) {
+        tokio::spawn(async move {
+            let mut servers_by_node: HashMap<uuid::Uuid, Vec<super::server::Server>> =
+                HashMap::new();
+            for server in servers {
+                servers_by_node
+                    .entry(server.node.uuid)
+                    .or_default()
+                    .push(server);
+            }
+
+            let concurrency = self.concurrency.max(1) as usize;
+            let operation = Arc::new(self);
+
+            let mut tasks = Vec::new();
+            for servers in servers_by_node.into_values() {
+                let semaphore = Arc::new(tokio::sync::Semaphore::new(concurrency));
+
+                for server in servers {
+                    let operation = Arc::clone(&operation);
+                    let state = state.clone();
+                    let semaphore = Arc::clone(&semaphore);
+
+                    tasks.push(tokio::spawn(async move {
+                        let _permit = semaphore.acquire_owned().await;
+
+                        let server_uuid = server.uuid;
+                        let server_name = server.name.clone();
+                        let result = operation.run_on(&state, server).await;
+
+                        if let Err(err) = &result {
+                            tracing::warn!(
+                                bulk_operation = %operation.uuid,
+                                server = %server_uuid,
+                                "bulk operation failed on server: {:?}",
+                                err
+                            );
+                        }
+
+                        if let Err(err) = operation
+                            .record_result(
+                                &state.database,
+                                server_uuid,
+                                &server_name,
+                                result.err().map(|err| err.to_string()).as_deref(),
+                            )
+                            .await
+                        {
+                            tracing::error!(
+                                bulk_operation = %operation.uuid,
+                                server = %server_uuid,
+                                "failed to record bulk operation result: {:?}",
+                                err
+                            );
+                        }
+                    }));
+                }
+            }
+
+            let run = futures_util::future::join_all(tasks);
+            tokio::pin!(run);
+
+            let mut heartbeat = tokio::time::interval(HEARTBEAT_INTERVAL);
+            loop {
+                tokio::select! {
+                    _ = &mut run => break,
+                    _ = heartbeat.tick() => {
+                        if let Err(err) = sqlx::query(
+                            r#"
+                            UPDATE server_bulk_operations
+                            SET heartbeat = NOW()
+                            WHERE server_bulk_operations.uuid = $1
+                            "#,
+                        )
+                        .bind(operation.uuid)
+                        .execute(state.database.write())
+                        .await
+                        {
+                            tracing::error!(
+                                bulk_operation = %operation.uuid,
+                                "failed to record bulk operation heartbeat: {:?}",
+                                err
+                            );
+                        }
+                    }
+                }
+            }
+
+            if let Err(err) = sqlx::query(
+                r#"
+                UPDATE server_bulk_operations
+                SET completed = NOW()
+                WHERE server_bulk_operations.uuid = $1
+                "#,
+            )
+            .bind(operation.uuid)
+            .execute(state.database.write())
+            .await
+            {
+                tracing::error!(
+                    bulk_operation = %operation.uuid,
+                    "failed to mark bulk operation as completed: {:?}",
+                    err
+                );
+            }
+        });
+    }
+
+    async fn run_on(
+        &self,
+        state: &crate::State,
+        mut server: super::server::Server,
+    ) -> Result<(), anyhow::Error> {
+        match &self.action {
+            ServerBulkOperationAction::Power { action } => {
+                server
+                    .node
+                    .fetch_cached(&state.database)
+                    .await?
+                    .api_client(&state.database)
+                    .post_servers_server_power(
+                        server.uuid,
+                        &wings_api::servers_server_power::post::RequestBody {
+                            action: *action,
+                            wait_seconds: None,
+                        },
+                    )
+                    .await?;
+            }
+            ServerBulkOperationAction::Suspend { reason } => {
+                let suspended_by_uuid = if server.suspended {
+                    server.suspended_by_uuid
+                } else {
+                    self.user_uuid
+                };
+                let unsuspend_at = server.unsuspend_at;
+
+                server
+                    .suspend(
+                        &state.database,
+                        reason.as_deref().filter(|reason| !reason.is_empty()),
+                        suspended_by_uuid,
+                        unsuspend_at,
+                    )
+                    .await?;
+                server.sync(&state.database).await?;
+            }
+            ServerBulkOperationAction::Unsuspend => {
+                server.unsuspend(&state.database).await?;
+                server.sync(&state.database).await?;
+            }
+            ServerBulkOperationAction::Reinstall { truncate_directory } => {
+                server.install(state, *truncate_directory, None).await?;
+            }
+            ServerBulkOperationAction::UpdateImage { image } => {
+                sqlx::query(
+                    r#"
+                    UPDATE servers
+                    SET image = $2
+                    WHERE servers.uuid = $1
+                    "#,
+                )
+                .bind(server.uuid)
+                .bind(image.as_str())
+                .execute(state.database.write())
+                .await?;
+
+                server.image = image.clone();
+                server.sync(&state.database).await?;
+            }
+            ServerBulkOperationAction::UpdateVariable {
+                env_variable,
+                value,
+            } => {
+                let mut variables =
+                    super::server_variable::ServerVariable::all_by_server_uuid_egg_uuid(
+                        &state.database,
+                        server.uuid,
+                        server.egg.uuid,
+                    )
+                    .await?;
+                super::server_variable::ServerVariable::decrypt_all(
+                    &state.database,
+                    &mut variables,
+                )
+                .await?;
+
+                let variable = match variables
+                    .iter()
+                    .find(|variable| variable.variable.env_variable == *env_variable)
+                {
+                    Some(variable) => &variable.variable,
+                    None => {
+                        return Err(anyhow::anyhow!(
+                            "egg {} has no variable {env_variable}",
+                            server.egg.name
+                        ));
+                    }
+                };
+
+                let validator = rule_validator::Validator::new(HashMap::from([(
+                    variable.env_variable.as_str(),
+                    (variable.rules.as_slice(), value.as_str()),
+                )]))
+                .map_err(|err| anyhow::anyhow!(err))?;
+                validator.validate().map_err(|err| anyhow::anyhow!(err))?;
+
+                super::server_variable::ServerVariable::create(
+                    &state.database,
+                    server.uuid,
+                    variable,
+                    value,
+                )
+                .await?;
+            }
+            ServerBulkOperationAction::Sync => {
+                server.sync(&state.database).await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn record_result(
+        &self,
+        database: &crate::database::Database,
+        server_uuid: uuid::Uuid,
+        server_name: &str,
+        error: Option<&str>,
+    ) -> Result<(), crate::database::DatabaseError> {
+        sqlx::query(
+            r#"
+            WITH result AS (
+                INSERT INTO server_bulk_operation_results (bulk_operation_uuid, server_uuid, server_name, successful, error)
+                VALUES ($1, $2, $3, $4, $5)
+            )
+            UPDATE server_bulk_operations
+            SET successful = successful + $4::boolean::integer, failed = failed + (NOT $4)::integer
+            WHERE server_bulk_operations.uuid = $1
+            "#,
+        )
+        .bind(self.uuid)
+        .bind(server_uuid)
+        .bind(server_name)
+        .bind(error.is_none())
+        .bind(error)
+        .execute(database.write())
+        .await?;
+
+        Ok(())
+    }
+
+    #[inline]
+    pub fn into_admin_api_object(self) -> AdminApiServerBulkOperation {
+        AdminApiServerBulkOperation {
+            uuid: self.uuid,
+            user_uuid: self.user_uuid,
+            action: self.action.redacted(),
+            filter: self.filter,
+            concurrency: self.concurrency,
+            total: self.total,
+            successful: self.successful,
+            failed: self.failed,
+            completed: self.completed.map(|dt| dt.and_utc()),
+            created: self.created.and_utc(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct ServerBulkOperationResult {
+    pub server_uuid: uuid::Uuid,
+    pub server_name: compact_str::CompactString,
+
+    pub successful: bool,
+    pub error: Option<String>,
+
+    pub created: chrono::NaiveDateTime,
+}
+
+impl BaseModel for ServerBulkOperationResult {
+    const NAME: &'static str = "server_bulk_operation_result";
+
+    #[inline]
+    fn columns(prefix: Option<&str>) -> BTreeMap<&'static str, compact_str::CompactString> {
+        let prefix = prefix.unwrap_or_default();
+
+        BTreeMap::from([
+            (
+                "server_bulk_operation_results.server_uuid",
+                compact_str::format_compact!("{prefix}server_uuid"),
+            ),
+            (
+                "server_bulk_operation_results.server_name",
+                compact_str::format_compact!("{prefix}server_name"),
+            ),
+            (
+                "server_bulk_operation_results.successful",
+                compact_str::format_compact!("{prefix}successful"),
+            ),
+            (
+                "server_bulk_operation_results.error",
+                compact_str::format_compact!("{prefix}error"),
+            ),
+            (
+                "server_bulk_operation_results.created",
+                compact_str::format_compact!("{prefix}created"),
+            ),
+        ])
+    }
+
+    #[inline]
+    fn map(prefix: Option<&str>, row: &PgRow) -> Result<Self, crate::database::DatabaseError> {
+        let prefix = prefix.unwrap_or_default();
+
+        Ok(Self {
+            server_uuid: row
+                .try_get(compact_str::format_compact!("{prefix}server_uuid").as_str())?,
+            server_name: row
+                .try_get(compact_str::format_compact!("{prefix}server_name").as_str())?,
+            successful: row.try_get(compact_str::format_compact!("{prefix}successful").as_str())?,
+            error: row.try_get(compact_str::format_compact!("{prefix}error").as_str())?,
+            created: row.try_get(compact_str::format_compact!("{prefix}created").as_str())?,
+        })
+    }
+}
+
+impl ServerBulkOperationResult {
+    pub async fn by_bulk_operation_uuid_with_pagination(
+        database: &crate::database::Database,
+        bulk_operation_uuid: uuid::Uuid,
+        page: i64,
+        per_page: i64,
+        failed_only: bool,
+    ) -> Result<super::Pagination<Self>, crate::database::DatabaseError> {
+        let offset = (page - 1) * per_page;
+
+        let rows = sqlx::query(&format!(
+            r#"
+            SELECT {}, COUNT(*) OVER() AS total_count
+            FROM server_bulk_operation_results
+            WHERE server_bulk_operation_results.bulk_operation_uuid = $1
+                AND (NOT $2 OR NOT server_bulk_operation_results.successful)
+            ORDER BY server_bulk_operation_results.created
+            LIMIT $3 OFFSET $4
+            "#,
+            Self::columns_sql(None)
+        ))
+        .bind(bulk_operation_uuid)
+        .bind(failed_only)
+        .bind(per_page)
+        .bind(offset)
+        .fetch_all(database.read())
+        .await?;
+
+        Ok(super::Pagination {
+            total: rows
+                .first()
+                .map_or(Ok(0), |row| row.try_get("total_count"))?,
+            per_page,
+            page,
+            data: rows
+                .into_iter()
+                .map(|row| Self::map(None, &row))
+                .try_collect_vec()?,
+        })
+    }
+
+    #[inline]
+    pub fn into_admin_api_object(self) -> AdminApiServerBulkOperationResult {
+        AdminApiServerBulkOperationResult {
+            server_uuid: self.server_uuid,
+            server_name: self.server_name,
+            successful: self.successful,
+            error: self.error,
+            created: self.created.and_utc(),
+        }
+    }
+}
+
+#[derive(ToSchema, Serialize)]
+#[schema(title = "ServerBulkOperation")]
+pub struct AdminApiServerBulkOperation {
+    pub uuid: uuid::Uuid,
+    pub user_uuid: Option<uuid::Uuid>,
+
+    pub action: ServerBulkOperationAction,
+    pub filter: ServerBulkOperationFilter,
+    pub concurrency: i16,
+
+    pub total: i32,
+    pub successful: i32,
+    pub failed: i32,
+
+    pub completed: Option<chrono::DateTime<chrono::Utc>>,
+    pub created: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(ToSchema, Serialize)]
+#[schema(title = "ServerBulkOperationResult")]
+pub struct AdminApiServerBulkOperationResult {
+    pub server_uuid: uuid::Uuid,
+    pub server_name: compact_str::CompactString,
+
+    pub successful: bool,
+    pub error: Option<String>,
+
+    pub created: chrono::DateTime<chrono::Utc>,
+}
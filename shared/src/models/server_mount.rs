@@ -76,6 +76,36 @@ impl ServerMount {
         Ok(())
     }
 
+    /// Copies the mounts of a server to another server, skipping mounts that
+    /// are not available on the node and egg of the target server.
+    pub async fn copy_by_server_uuid(
+        transaction: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        server_uuid: uuid::Uuid,
+        target_server_uuid: uuid::Uuid,
+        target_node_uuid: uuid::Uuid,
+        target_egg_uuid: uuid::Uuid,
+    ) -> Result<u64, crate::database::DatabaseError> {
+        let result = sqlx::query(
+            r#"
+            INSERT INTO server_mounts (server_uuid, mount_uuid)
+            SELECT $2, server_mounts.mount_uuid
+            FROM server_mounts
+            JOIN node_mounts ON node_mounts.mount_uuid = server_mounts.mount_uuid AND node_mounts.node_uuid = $3
+            JOIN nest_egg_mounts ON nest_egg_mounts.mount_uuid = server_mounts.mount_uuid AND nest_egg_mounts.egg_uuid = $4
+            WHERE server_mounts.server_uuid = $1
+            ON CONFLICT DO NOTHING
+            "#,
+        )
+        .bind(server_uuid)
+        .bind(target_server_uuid)
+        .bind(target_node_uuid)
+        .bind(target_egg_uuid)
+        .execute(&mut **transaction)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+
     pub async fn by_server_uuid_mount_uuid(
         database: &crate::database::Database,
         server_uuid: uuid::Uuid,
@@ -169,6 +169,73 @@ impl ServerSchedule {
         row.try_map(|row| Self::map(None, &row))
     }
 
+    pub async fn all_by_server_uuid(
+        database: &crate::database::Database,
+        server_uuid: uuid::Uuid,
+    ) -> Result<Vec<Self>, crate::database::DatabaseError> {
+        let rows = sqlx::query(&format!(
+            r#"
+            SELECT {}
+            FROM server_schedules
+            WHERE server_schedules.server_uuid = $1
+            ORDER BY server_schedules.created
+            "#,
+            Self::columns_sql(None)
+        ))
+        .bind(server_uuid)
+        .fetch_all(database.read())
+        .await?;
+
+        rows.into_iter()
+            .map(|row| Self::map(None, &row))
+            .try_collect_vec()
+    }
+
+    /// Copies the oldest schedules of a server including their steps to another server,
+    /// at most `limit` of them. Run history is not copied.
+    pub async fn copy_by_server_uuid(
+        database: &crate::database::Database,
+        transaction: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        server_uuid: uuid::Uuid,
+        target_server_uuid: uuid::Uuid,
+        limit: i32,
+    ) -> Result<u64, crate::database::DatabaseError> {
+        let schedules = Self::all_by_server_uuid(database, server_uuid).await?;
+        let schedules = &schedules[..schedules.len().min(limit.max(0) as usize)];
+
+        for schedule in schedules {
+            let row = sqlx::query(
+                r#"
+                INSERT INTO server_schedules (server_uuid, name, enabled, triggers, condition, created)
+                VALUES ($1, $2, $3, $4, $5, NOW())
+                RETURNING uuid
+                "#,
+            )
+            .bind(target_server_uuid)
+            .bind(&schedule.name)
+            .bind(schedule.enabled)
+            .bind(serde_json::to_value(&schedule.triggers)?)
+            .bind(serde_json::to_value(&schedule.condition)?)
+            .fetch_one(&mut **transaction)
+            .await?;
+
+            sqlx::query(
+                r#"
+                INSERT INTO server_schedule_steps (schedule_uuid, action, order_, created)
+                SELECT $2, server_schedule_steps.action, server_schedule_steps.order_, NOW()
+                FROM server_schedule_steps
+                WHERE server_schedule_steps.schedule_uuid = $1
+                "#,
+            )
+            .bind(schedule.uuid)
+            .bind(row.get::<uuid::Uuid, _>("uuid"))
+            .execute(&mut **transaction)
+            .await?;
+        }
+
+        Ok(schedules.len() as u64)
+    }
+
     pub async fn by_server_uuid_with_pagination(
         database: &crate::database::Database,
         server_uuid: uuid::Uuid,
@@ -126,18 +126,6 @@ impl ServerSubuser {
                     Ok(token) => {
                         let settings = settings.get().await?;
 
-                        let mail_content = crate::mail::MAIL_ACCOUNT_CREATED
-                            .replace("{{app_name}}", &settings.app.name)
-                            .replace("{{user_username}}", &user.username)
-                            .replace(
-                                "{{reset_link}}",
-                                &format!(
-                                    "{}/auth/reset-password?token={}",
-                                    settings.app.url,
-                                    urlencoding::encode(&token),
-                                ),
-                            );
-
                         super::user_activity::UserActivity::log(
                             database,
                             user.uuid,
@@ -148,10 +136,22 @@ impl ServerSubuser {
                         )
                         .await?;
 
-                        mail.send(
+                        let reset_link = format!(
+                            "{}/auth/reset-password?token={}",
+                            settings.app.url,
+                            urlencoding::encode(&token),
+                        );
+                        drop(settings);
+
+                        mail.send_template(
                             user.email.clone(),
-                            format!("{} - Account Created", settings.app.name).into(),
-                            mail_content,
+                            crate::mail::MailTemplateIdentifier::AccountCreated,
+                            &user.language,
+                            serde_json::json!({
+                                "user": crate::mail::user_context(&user),
+                                "server": crate::mail::server_context(server),
+                                "reset_link": reset_link,
+                            }),
                         )
                         .await;
 
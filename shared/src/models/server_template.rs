@@ -0,0 +1,461 @@
+use crate::{prelude::*, response::DisplayError};
+use serde::{Deserialize, Serialize};
+use sqlx::{Row, postgres::PgRow};
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::{Arc, LazyLock},
+};
+use utoipa::ToSchema;
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct ServerTemplate {
+    pub uuid: uuid::Uuid,
+    pub name: compact_str::CompactString,
+    pub description: Option<compact_str::CompactString>,
+
+    pub egg_uuid: uuid::Uuid,
+    pub backup_configuration_uuid: Option<uuid::Uuid>,
+
+    pub memory: i64,
+    pub swap: i64,
+    pub disk: i64,
+    pub io_weight: Option<i16>,
+    pub cpu: i32,
+    pub pinned_cpus: Vec<i16>,
+
+    pub startup: compact_str::CompactString,
+    pub image: compact_str::CompactString,
+    pub timezone: Option<compact_str::CompactString>,
+
+    pub hugepages_passthrough_enabled: bool,
+    pub kvm_passthrough_enabled: bool,
+
+    pub allocation_limit: i32,
+    pub database_limit: i32,
+    pub backup_limit: i32,
+    pub schedule_limit: i32,
+
+    pub variables: BTreeMap<compact_str::CompactString, compact_str::CompactString>,
+    pub deployment: Option<super::server::ApiServerDeployment>,
+
+    pub created: chrono::NaiveDateTime,
+}
+
+impl BaseModel for ServerTemplate {
+    const NAME: &'static str = "server_template";
+
+    #[inline]
+    fn columns(prefix: Option<&str>) -> BTreeMap<&'static str, compact_str::CompactString> {
+        let prefix = prefix.unwrap_or_default();
+
+        BTreeMap::from([
+            (
+                "server_templates.uuid",
+                compact_str::format_compact!("{prefix}uuid"),
+            ),
+            (
+                "server_templates.name",
+                compact_str::format_compact!("{prefix}name"),
+            ),
+            (
+                "server_templates.description",
+                compact_str::format_compact!("{prefix}description"),
+            ),
+            (
+                "server_templates.egg_uuid",
+                compact_str::format_compact!("{prefix}egg_uuid"),
+            ),
+            (
+                "server_templates.backup_configuration_uuid",
+                compact_str::format_compact!("{prefix}backup_configuration_uuid"),
+            ),
+            (
+                "server_templates.memory",
+                compact_str::format_compact!("{prefix}memory"),
+            ),
+            (
+                "server_templates.swap",
+                compact_str::format_compact!("{prefix}swap"),
+            ),
+            (
+                "server_templates.disk",
+                compact_str::format_compact!("{prefix}disk"),
+            ),
+            (
+                "server_templates.io_weight",
+                compact_str::format_compact!("{prefix}io_weight"),
+            ),
+            (
+                "server_templates.cpu",
+                compact_str::format_compact!("{prefix}cpu"),
+            ),
+            (
+                "server_templates.pinned_cpus",
+                compact_str::format_compact!("{prefix}pinned_cpus"),
+            ),
+            (
+                "server_templates.startup",
+                compact_str::format_compact!("{prefix}startup"),
+            ),
+            (
+                "server_templates.image",
+                compact_str::format_compact!("{prefix}image"),
+            ),
+            (
+                "server_templates.timezone",
+                compact_str::format_compact!("{prefix}timezone"),
+            ),
+            (
+                "server_templates.hugepages_passthrough_enabled",
+                compact_str::format_compact!("{prefix}hugepages_passthrough_enabled"),
+            ),
+            (
+                "server_templates.kvm_passthrough_enabled",
+                compact_str::format_compact!("{prefix}kvm_passthrough_enabled"),
+            ),
+            (
+                "server_templates.allocation_limit",
+                compact_str::format_compact!("{prefix}allocation_limit"),
+            ),
+            (
+                "server_templates.database_limit",
+                compact_str::format_compact!("{prefix}database_limit"),
+            ),
+            (
+                "server_templates.backup_limit",
+                compact_str::format_compact!("{prefix}backup_limit"),
+            ),
+            (
+                "server_templates.schedule_limit",
+                compact_str::format_compact!("{prefix}schedule_limit"),
+            ),
+            (
+                "server_templates.variables",
+                compact_str::format_compact!("{prefix}variables"),
+            ),
+            (
+                "server_templates.deployment",
+                compact_str::format_compact!("{prefix}deployment"),
+            ),
+            (
+                "server_templates.created",
+                compact_str::format_compact!("{prefix}created"),
+            ),
+        ])
+    }
+
+    #[inline]
+    fn map(prefix: Option<&str>, row: &PgRow) -> Result<Self, crate::database::DatabaseError> {
+        let prefix = prefix.unwrap_or_default();
+
+        Ok(Self {
+            uuid: row.try_get(compact_str::format_compact!("{prefix}uuid").as_str())?,
+            name: row.try_get(compact_str::format_compact!("{prefix}name").as_str())?,
+            description: row
+                .try_get(compact_str::format_compact!("{prefix}description").as_str())?,
+            egg_uuid: row.try_get(compact_str::format_compact!("{prefix}egg_uuid").as_str())?,
+            backup_configuration_uuid: row.try_get(
+                compact_str::format_compact!("{prefix}backup_configuration_uuid").as_str(),
+            )?,
+            memory: row.try_get(compact_str::format_compact!("{prefix}memory").as_str())?,
+            swap: row.try_get(compact_str::format_compact!("{prefix}swap").as_str())?,
+            disk: row.try_get(compact_str::format_compact!("{prefix}disk").as_str())?,
+            io_weight: row.try_get(compact_str::format_compact!("{prefix}io_weight").as_str())?,
+            cpu: row.try_get(compact_str::format_compact!("{prefix}cpu").as_str())?,
+            pinned_cpus: row
+                .try_get(compact_str::format_compact!("{prefix}pinned_cpus").as_str())?,
+            startup: row.try_get(compact_str::format_compact!("{prefix}startup").as_str())?,
+            image: row.try_get(compact_str::format_compact!("{prefix}image").as_str())?,
+            timezone: row.try_get(compact_str::format_compact!("{prefix}timezone").as_str())?,
+            hugepages_passthrough_enabled: row.try_get(
+                compact_str::format_compact!("{prefix}hugepages_passthrough_enabled").as_str(),
+            )?,
+            kvm_passthrough_enabled: row.try_get(
+                compact_str::format_compact!("{prefix}kvm_passthrough_enabled").as_str(),
+            )?,
+            allocation_limit: row
+                .try_get(compact_str::format_compact!("{prefix}allocation_limit").as_str())?,
+            database_limit: row
+                .try_get(compact_str::format_compact!("{prefix}database_limit").as_str())?,
+            backup_limit: row
+                .try_get(compact_str::format_compact!("{prefix}backup_limit").as_str())?,
+            schedule_limit: row
+                .try_get(compact_str::format_compact!("{prefix}schedule_limit").as_str())?,
+            variables: serde_json::from_value(
+                row.try_get(compact_str::format_compact!("{prefix}variables").as_str())?,
+            )?,
+            deployment: row
+                .try_get::<Option<serde_json::Value>, _>(
+                    compact_str::format_compact!("{prefix}deployment").as_str(),
+                )?
+                .map(serde_json::from_value)
+                .transpose()?,
+            created: row.try_get(compact_str::format_compact!("{prefix}created").as_str())?,
+        })
+    }
+}
+
+impl ServerTemplate {
+    #[allow(clippy::too_many_arguments)]
+    pub async fn create(
+        database: &crate::database::Database,
+        name: &str,
+        description: Option<&str>,
+        egg_uuid: uuid::Uuid,
+        backup_configuration_uuid: Option<uuid::Uuid>,
+        limits: &super::server::ApiServerLimits,
+        pinned_cpus: &[i16],
+        startup: &str,
+        image: &str,
+        timezone: Option<&str>,
+        hugepages_passthrough_enabled: bool,
+        kvm_passthrough_enabled: bool,
+        feature_limits: &super::server::ApiServerFeatureLimits,
+        variables: &BTreeMap<compact_str::CompactString, compact_str::CompactString>,
+        deployment: Option<&super::server::ApiServerDeployment>,
+    ) -> Result<Self, crate::database::DatabaseError> {
+        let row = sqlx::query(&format!(
+            r#"
+            INSERT INTO server_templates (
+                name, description, egg_uuid, backup_configuration_uuid,
+                memory, swap, disk, io_weight, cpu, pinned_cpus,
+                startup, image, timezone,
+                hugepages_passthrough_enabled, kvm_passthrough_enabled,
+                allocation_limit, database_limit, backup_limit, schedule_limit,
+                variables, deployment
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18, $19, $20, $21)
+            RETURNING {}
+            "#,
+            Self::columns_sql(None)
+        ))
+        .bind(name)
+        .bind(description)
+        .bind(egg_uuid)
+        .bind(backup_configuration_uuid)
+        .bind(limits.memory)
+        .bind(limits.swap)
+        .bind(limits.disk)
+        .bind(limits.io_weight)
+        .bind(limits.cpu)
+        .bind(pinned_cpus)
+        .bind(startup)
+        .bind(image)
+        .bind(timezone)
+        .bind(hugepages_passthrough_enabled)
+        .bind(kvm_passthrough_enabled)
+        .bind(feature_limits.allocations)
+        .bind(feature_limits.databases)
+        .bind(feature_limits.backups)
+        .bind(feature_limits.schedules)
+        .bind(serde_json::to_value(variables)?)
+        .bind(deployment.map(serde_json::to_value).transpose()?)
+        .fetch_one(database.write())
+        .await?;
+
+        Self::map(None, &row)
+    }
+
+    pub async fn all_with_pagination(
+        database: &crate::database::Database,
+        page: i64,
+        per_page: i64,
+        search: Option<&str>,
+    ) -> Result<super::Pagination<Self>, crate::database::DatabaseError> {
+        let offset = (page - 1) * per_page;
+
+        let rows = sqlx::query(&format!(
+            r#"
+            SELECT {}, COUNT(*) OVER() AS total_count
+            FROM server_templates
+            WHERE ($1 IS NULL OR server_templates.name ILIKE '%' || $1 || '%')
+            ORDER BY server_templates.created
+            LIMIT $2 OFFSET $3
+            "#,
+            Self::columns_sql(None)
+        ))
+        .bind(search)
+        .bind(per_page)
+        .bind(offset)
+        .fetch_all(database.read())
+        .await?;
+
+        Ok(super::Pagination {
+            total: rows
+                .first()
+                .map_or(Ok(0), |row| row.try_get("total_count"))?,
+            per_page,
+            page,
+            data: rows
+                .into_iter()
+                .map(|row| Self::map(None, &row))
+                .try_collect_vec()?,
+        })
+    }
+
+    /// Checks template variables against the variables of the egg. Secret variables are
+    /// refused, templates are readable by anyone who can view them.
+    /// Returns a `DisplayError` describing the first invalid variable.
+    pub async fn validate_variables(
+        database: &crate::database::Database,
+        egg_uuid: uuid::Uuid,
+        variables: &BTreeMap<compact_str::CompactString, compact_str::CompactString>,
+    ) -> Result<(), anyhow::Error> {
+        let egg_variables =
+            super::nest_egg_variable::NestEggVariable::all_by_egg_uuid(database, egg_uuid).await?;
+
+        let mut validator_variables = HashMap::new();
+        validator_variables.reserve(variables.len());
+
+        for (env_variable, value) in variables {
+            let variable = match egg_variables
+                .iter()
+                .find(|variable| variable.env_variable == *env_variable)
+            {
+                Some(variable) => variable,
+                None => {
+                    return Err(
+                        DisplayError::new(format!("egg has no variable {env_variable}")).into(),
+                    );
+                }
+            };
+
+            if variable.secret {
+                return Err(DisplayError::new(format!(
+                    "secret variable {env_variable} can not be stored in a template"
+                ))
+                .into());
+            }
+
+            validator_variables.insert(
+                variable.env_variable.as_str(),
+                (variable.rules.as_slice(), value.as_str()),
+            );
+        }
+
+        let validator = rule_validator::Validator::new(validator_variables)
+            .map_err(|err| DisplayError::new(err.to_string()))?;
+        validator.validate().map_err(DisplayError::new)?;
+
+        Ok(())
+    }
+
+    #[inline]
+    pub fn into_admin_api_object(self) -> AdminApiServerTemplate {
+        AdminApiServerTemplate {
+            uuid: self.uuid,
+            name: self.name,
+            description: self.description,
+            egg_uuid: self.egg_uuid,
+            backup_configuration_uuid: self.backup_configuration_uuid,
+            limits: super::server::ApiServerLimits {
+                cpu: self.cpu,
+                memory: self.memory,
+                swap: self.swap,
+                disk: self.disk,
+                io_weight: self.io_weight,
+            },
+            pinned_cpus: self.pinned_cpus,
+            startup: self.startup,
+            image: self.image,
+            timezone: self.timezone,
+            hugepages_passthrough_enabled: self.hugepages_passthrough_enabled,
+            kvm_passthrough_enabled: self.kvm_passthrough_enabled,
+            feature_limits: super::server::ApiServerFeatureLimits {
+                allocations: self.allocation_limit,
+                databases: self.database_limit,
+                backups: self.backup_limit,
+                schedules: self.schedule_limit,
+            },
+            variables: self.variables,
+            deployment: self.deployment,
+            created: self.created.and_utc(),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl ByUuid for ServerTemplate {
+    async fn by_uuid(
+        database: &crate::database::Database,
+        uuid: uuid::Uuid,
+    ) -> Result<Self, crate::database::DatabaseError> {
+        let row = sqlx::query(&format!(
+            r#"
+            SELECT {}
+            FROM server_templates
+            WHERE server_templates.uuid = $1
+            "#,
+            Self::columns_sql(None)
+        ))
+        .bind(uuid)
+        .fetch_one(database.read())
+        .await?;
+
+        Self::map(None, &row)
+    }
+}
+
+#[async_trait::async_trait]
+impl DeletableModel for ServerTemplate {
+    type DeleteOptions = ();
+
+    fn get_delete_handlers() -> &'static LazyLock<DeleteListenerList<Self>> {
+        static DELETE_LISTENERS: LazyLock<DeleteListenerList<ServerTemplate>> =
+            LazyLock::new(|| Arc::new(ModelHandlerList::default()));
+
+        &DELETE_LISTENERS
+    }
+
+    async fn delete(
+        &self,
+        state: &crate::State,
+        options: Self::DeleteOptions,
+    ) -> Result<(), anyhow::Error> {
+        let mut transaction = state.database.write().begin().await?;
+
+        self.run_delete_handlers(&options, state, &mut transaction)
+            .await?;
+
+        sqlx::query(
+            r#"
+            DELETE FROM server_templates
+            WHERE server_templates.uuid = $1
+            "#,
+        )
+        .bind(self.uuid)
+        .execute(&mut *transaction)
+        .await?;
+
+        transaction.commit().await?;
+
+        Ok(())
+    }
+}
+
+#[derive(ToSchema, Serialize)]
+#[schema(title = "ServerTemplate")]
+pub struct AdminApiServerTemplate {
+    pub uuid: uuid::Uuid,
+    pub name: compact_str::CompactString,
+    pub description: Option<compact_str::CompactString>,
+
+    pub egg_uuid: uuid::Uuid,
+    pub backup_configuration_uuid: Option<uuid::Uuid>,
+
+    pub limits: super::server::ApiServerLimits,
+    pub pinned_cpus: Vec<i16>,
+
+    pub startup: compact_str::CompactString,
+    pub image: compact_str::CompactString,
+    pub timezone: Option<compact_str::CompactString>,
+
+    pub hugepages_passthrough_enabled: bool,
+    pub kvm_passthrough_enabled: bool,
+
+    pub feature_limits: super::server::ApiServerFeatureLimits,
+    pub variables: BTreeMap<compact_str::CompactString, compact_str::CompactString>,
+    pub deployment: Option<super::server::ApiServerDeployment>,
+
+    pub created: chrono::DateTime<chrono::Utc>,
+}
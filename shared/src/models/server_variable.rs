@@ -9,6 +9,7 @@ pub struct ServerVariable {
     pub variable: super::nest_egg_variable::NestEggVariable,
 
     pub value: String,
+    pub value_encrypted: Option<Vec<u8>>,
 
     pub created: chrono::NaiveDateTime,
 }
@@ -25,6 +26,10 @@ impl BaseModel for ServerVariable {
                 "server_variables.value",
                 compact_str::format_compact!("{prefix}value"),
             ),
+            (
+                "server_variables.value_encrypted",
+                compact_str::format_compact!("{prefix}value_encrypted"),
+            ),
             (
                 "server_variables.created",
                 compact_str::format_compact!("{prefix}created"),
@@ -55,6 +60,8 @@ impl BaseModel for ServerVariable {
         Ok(Self {
             variable,
             value,
+            value_encrypted: row
+                .try_get(compact_str::format_compact!("{prefix}value_encrypted").as_str())?,
             created: row
                 .try_get(compact_str::format_compact!("{prefix}created").as_str())
                 .unwrap_or_else(|_| chrono::Utc::now().naive_utc()),
@@ -66,25 +73,95 @@ impl ServerVariable {
     pub async fn create(
         database: &crate::database::Database,
         server_uuid: uuid::Uuid,
-        variable_uuid: uuid::Uuid,
+        variable: &super::nest_egg_variable::NestEggVariable,
         value: &str,
     ) -> Result<(), crate::database::DatabaseError> {
+        let (value, value_encrypted) = Self::encode_value(database, variable, value).await?;
+
         sqlx::query(
             r#"
-            INSERT INTO server_variables (server_uuid, variable_uuid, value)
-            VALUES ($1, $2, $3)
-            ON CONFLICT (server_uuid, variable_uuid) DO UPDATE SET value = EXCLUDED.value
+            INSERT INTO server_variables (server_uuid, variable_uuid, value, value_encrypted)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (server_uuid, variable_uuid) DO UPDATE SET value = EXCLUDED.value, value_encrypted = EXCLUDED.value_encrypted
             "#,
         )
         .bind(server_uuid)
-        .bind(variable_uuid)
+        .bind(variable.uuid)
         .bind(value)
+        .bind(value_encrypted)
         .execute(database.write())
         .await?;
 
         Ok(())
     }
 
+    /// Returns the `value` and `value_encrypted` columns to store for a variable,
+    /// secret variables only ever have their value stored encrypted.
+    pub async fn encode_value<'a>(
+        database: &crate::database::Database,
+        variable: &super::nest_egg_variable::NestEggVariable,
+        value: &'a str,
+    ) -> Result<(&'a str, Option<Vec<u8>>), anyhow::Error> {
+        if variable.secret {
+            Ok(("", Some(database.encrypt(value.to_string()).await?)))
+        } else {
+            Ok((value, None))
+        }
+    }
+
+    /// Brings stored values in line with the `secret` flag of their egg variable,
+    /// encrypting plaintext secrets and decrypting values of variables that are no longer secret.
+    pub async fn sync_encryption(
+        database: &crate::database::Database,
+        variable_uuid: Option<uuid::Uuid>,
+    ) -> Result<u64, anyhow::Error> {
+        let rows = sqlx::query(
+            r#"
+            SELECT server_variables.server_uuid, server_variables.variable_uuid, server_variables.value,
+                server_variables.value_encrypted, nest_egg_variables.secret
+            FROM server_variables
+            JOIN nest_egg_variables ON nest_egg_variables.uuid = server_variables.variable_uuid
+            WHERE ($1 IS NULL OR server_variables.variable_uuid = $1)
+                AND (nest_egg_variables.secret = (server_variables.value_encrypted IS NULL))
+            "#,
+        )
+        .bind(variable_uuid)
+        .fetch_all(database.read())
+        .await?;
+
+        let mut transaction = database.write().begin().await?;
+
+        for row in &rows {
+            let (value, value_encrypted) = if row.try_get("secret")? {
+                let value: String = row.try_get("value")?;
+
+                (String::new(), Some(database.encrypt(value).await?))
+            } else {
+                let value_encrypted: Vec<u8> = row.try_get("value_encrypted")?;
+
+                (database.decrypt(value_encrypted).await?.into(), None)
+            };
+
+            sqlx::query(
+                r#"
+                UPDATE server_variables
+                SET value = $3, value_encrypted = $4
+                WHERE server_variables.server_uuid = $1 AND server_variables.variable_uuid = $2
+                "#,
+            )
+            .bind(row.try_get::<uuid::Uuid, _>("server_uuid")?)
+            .bind(row.try_get::<uuid::Uuid, _>("variable_uuid")?)
+            .bind(value)
+            .bind(value_encrypted)
+            .execute(&mut *transaction)
+            .await?;
+        }
+
+        transaction.commit().await?;
+
+        Ok(rows.len() as u64)
+    }
+
     pub async fn all_by_server_uuid_egg_uuid(
         database: &crate::database::Database,
         server_uuid: uuid::Uuid,
@@ -110,9 +187,41 @@ impl ServerVariable {
             .try_collect_vec()
     }
 
-    #[inline]
-    pub fn into_api_object(self) -> ApiServerVariable {
-        ApiServerVariable {
+    pub async fn decrypt(
+        &mut self,
+        database: &crate::database::Database,
+    ) -> Result<(), anyhow::Error> {
+        if let Some(value_encrypted) = self.value_encrypted.take() {
+            self.value = database.decrypt(value_encrypted).await?.into();
+        }
+
+        Ok(())
+    }
+
+    pub async fn decrypt_all(
+        database: &crate::database::Database,
+        variables: &mut [Self],
+    ) -> Result<(), anyhow::Error> {
+        for variable in variables {
+            variable.decrypt(database).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Secret values are only included when `reveal` is set, otherwise they are returned empty.
+    pub async fn into_api_object(
+        mut self,
+        database: &crate::database::Database,
+        reveal: bool,
+    ) -> Result<ApiServerVariable, anyhow::Error> {
+        if self.variable.secret && !reveal {
+            self.value = String::new();
+        } else {
+            self.decrypt(database).await?;
+        }
+
+        Ok(ApiServerVariable {
             name: self.variable.name,
             description: self.variable.description,
             env_variable: self.variable.env_variable,
@@ -122,7 +231,7 @@ impl ServerVariable {
             is_secret: self.variable.secret,
             rules: self.variable.rules,
             created: self.created.and_utc(),
-        }
+        })
     }
 }
 
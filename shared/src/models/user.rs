@@ -1,6 +1,8 @@
 use crate::{prelude::*, response::ApiResponse, storage::StorageUrlRetriever};
 use axum::http::StatusCode;
+use compact_str::ToCompactString;
 use regex::Regex;
+use rustis::commands::{GenericCommands, SetExpiration, StringCommands};
 use serde::{Deserialize, Serialize};
 use sqlx::{Row, postgres::PgRow, prelude::Type};
 use std::{
@@ -8,7 +10,56 @@ use std::{
     sync::{Arc, LazyLock},
 };
 use utoipa::ToSchema;
-use webauthn_rs::prelude::CredentialID;
+use webauthn_rs::prelude::{
+    CredentialID, Passkey, PasskeyAuthentication, PublicKeyCredential, RequestChallengeResponse,
+};
+
+/// The amount of previous password hashes kept per user for the password history policy.
+pub const PASSWORD_HISTORY_LIMIT: i64 = 24;
+
+/// The SCIM attributes users can be filtered on.
+pub const SCIM_FILTER_ATTRIBUTES: &[crate::scim::FilterAttribute] = &[
+    crate::scim::FilterAttribute {
+        name: "id",
+        column: "users.uuid",
+        kind: crate::scim::FilterAttributeKind::Uuid,
+    },
+    crate::scim::FilterAttribute {
+        name: "externalId",
+        column: "users.external_id",
+        kind: crate::scim::FilterAttributeKind::Text,
+    },
+    crate::scim::FilterAttribute {
+        name: "userName",
+        column: "users.username",
+        kind: crate::scim::FilterAttributeKind::Text,
+    },
+    crate::scim::FilterAttribute {
+        name: "emails",
+        column: "users.email",
+        kind: crate::scim::FilterAttributeKind::Text,
+    },
+    crate::scim::FilterAttribute {
+        name: "emails.value",
+        column: "users.email",
+        kind: crate::scim::FilterAttributeKind::Text,
+    },
+    crate::scim::FilterAttribute {
+        name: "name.givenName",
+        column: "users.name_first",
+        kind: crate::scim::FilterAttributeKind::Text,
+    },
+    crate::scim::FilterAttribute {
+        name: "name.familyName",
+        column: "users.name_last",
+        kind: crate::scim::FilterAttributeKind::Text,
+    },
+    crate::scim::FilterAttribute {
+        name: "active",
+        column: "users.active",
+        kind: crate::scim::FilterAttributeKind::Boolean,
+    },
+];
 
 pub static USERNAME_REGEX: LazyLock<Regex> =
     LazyLock::new(|| Regex::new(r"^[a-zA-Z0-9_]+$").expect("Failed to compile username regex"));
@@ -32,6 +83,7 @@ pub struct PermissionManager {
     api_key_user_permissions: Option<Arc<Vec<compact_str::CompactString>>>,
     api_key_admin_permissions: Option<Arc<Vec<compact_str::CompactString>>>,
     api_key_server_permissions: Option<Arc<Vec<compact_str::CompactString>>>,
+    api_key_server_scope: Option<Arc<Vec<uuid::Uuid>>>,
     server_subuser_permissions: Option<Arc<Vec<compact_str::CompactString>>>,
 }
 
@@ -45,6 +97,7 @@ impl PermissionManager {
             api_key_user_permissions: None,
             api_key_admin_permissions: None,
             api_key_server_permissions: None,
+            api_key_server_scope: None,
             server_subuser_permissions: None,
         }
     }
@@ -53,6 +106,9 @@ impl PermissionManager {
         self.api_key_user_permissions = Some(api_key.user_permissions.clone());
         self.api_key_admin_permissions = Some(api_key.admin_permissions.clone());
         self.api_key_server_permissions = Some(api_key.server_permissions.clone());
+        self.api_key_server_scope = api_key
+            .server_scope()
+            .map(|server_uuids| Arc::new(server_uuids.to_vec()));
 
         self
     }
@@ -72,6 +128,18 @@ impl PermissionManager {
         self
     }
 
+    #[inline]
+    pub fn server_scope(&self) -> Option<&[uuid::Uuid]> {
+        self.api_key_server_scope.as_deref().map(Vec::as_slice)
+    }
+
+    #[inline]
+    pub fn can_access_server(&self, server_uuid: uuid::Uuid) -> bool {
+        self.api_key_server_scope
+            .as_ref()
+            .is_none_or(|server_uuids| server_uuids.contains(&server_uuid))
+    }
+
     pub fn has_user_permission(&self, permission: &str) -> Result<(), ApiResponse> {
         if let Some(permissions) = &self.api_key_user_permissions {
             if permissions.iter().any(|p| p == permission) {
@@ -181,6 +249,14 @@ pub enum UserToastPosition {
     BottomRight,
 }
 
+/// A finished passkey assertion, see [`User::start_passkey_confirmation`].
+#[derive(ToSchema, Deserialize)]
+pub struct UserPasskeyConfirmation {
+    pub uuid: uuid::Uuid,
+    #[schema(value_type = serde_json::Value)]
+    pub public_key_credential: PublicKeyCredential,
+}
+
 #[derive(Serialize, Deserialize, Clone)]
 pub struct User {
     pub uuid: uuid::Uuid,
@@ -190,15 +266,21 @@ pub struct User {
     pub avatar: Option<String>,
     pub username: compact_str::CompactString,
     pub email: compact_str::CompactString,
+    pub email_verified: bool,
 
     pub name_first: compact_str::CompactString,
     pub name_last: compact_str::CompactString,
 
     pub admin: bool,
+    pub has_password: bool,
     pub totp_enabled: bool,
     pub totp_last_used: Option<chrono::NaiveDateTime>,
     pub totp_secret: Option<String>,
 
+    pub failed_login_attempts: i32,
+    pub locked_until: Option<chrono::NaiveDateTime>,
+    pub active: bool,
+
     pub language: compact_str::CompactString,
     pub toast_position: UserToastPosition,
     pub start_on_grouped_servers: bool,
@@ -228,6 +310,10 @@ impl BaseModel for User {
                 compact_str::format_compact!("{prefix}username"),
             ),
             ("users.email", compact_str::format_compact!("{prefix}email")),
+            (
+                "users.email_verified",
+                compact_str::format_compact!("{prefix}email_verified"),
+            ),
             (
                 "users.name_first",
                 compact_str::format_compact!("{prefix}name_first"),
@@ -237,6 +323,10 @@ impl BaseModel for User {
                 compact_str::format_compact!("{prefix}name_last"),
             ),
             ("users.admin", compact_str::format_compact!("{prefix}admin")),
+            (
+                "users.password IS NOT NULL",
+                compact_str::format_compact!("{prefix}has_password"),
+            ),
             (
                 "users.totp_enabled",
                 compact_str::format_compact!("{prefix}totp_enabled"),
@@ -249,6 +339,18 @@ impl BaseModel for User {
                 "users.totp_secret",
                 compact_str::format_compact!("{prefix}totp_secret"),
             ),
+            (
+                "users.failed_login_attempts",
+                compact_str::format_compact!("{prefix}failed_login_attempts"),
+            ),
+            (
+                "users.locked_until",
+                compact_str::format_compact!("{prefix}locked_until"),
+            ),
+            (
+                "users.active",
+                compact_str::format_compact!("{prefix}active"),
+            ),
             (
                 "users.language",
                 compact_str::format_compact!("{prefix}language"),
@@ -293,15 +395,24 @@ impl BaseModel for User {
             avatar: row.try_get(compact_str::format_compact!("{prefix}avatar").as_str())?,
             username: row.try_get(compact_str::format_compact!("{prefix}username").as_str())?,
             email: row.try_get(compact_str::format_compact!("{prefix}email").as_str())?,
+            email_verified: row
+                .try_get(compact_str::format_compact!("{prefix}email_verified").as_str())?,
             name_first: row.try_get(compact_str::format_compact!("{prefix}name_first").as_str())?,
             name_last: row.try_get(compact_str::format_compact!("{prefix}name_last").as_str())?,
             admin: row.try_get(compact_str::format_compact!("{prefix}admin").as_str())?,
+            has_password: row
+                .try_get(compact_str::format_compact!("{prefix}has_password").as_str())?,
             totp_enabled: row
                 .try_get(compact_str::format_compact!("{prefix}totp_enabled").as_str())?,
             totp_last_used: row
                 .try_get(compact_str::format_compact!("{prefix}totp_last_used").as_str())?,
             totp_secret: row
                 .try_get(compact_str::format_compact!("{prefix}totp_secret").as_str())?,
+            failed_login_attempts: row
+                .try_get(compact_str::format_compact!("{prefix}failed_login_attempts").as_str())?,
+            locked_until: row
+                .try_get(compact_str::format_compact!("{prefix}locked_until").as_str())?,
+            active: row.try_get(compact_str::format_compact!("{prefix}active").as_str())?,
             language: row.try_get(compact_str::format_compact!("{prefix}language").as_str())?,
             toast_position: row
                 .try_get(compact_str::format_compact!("{prefix}toast_position").as_str())?,
@@ -359,11 +470,12 @@ impl User {
         name_first: &str,
         name_last: &str,
         password: &str,
+        email_verified: bool,
     ) -> Result<uuid::Uuid, crate::database::DatabaseError> {
         let row = sqlx::query(
             r#"
-            INSERT INTO users (role_uuid, external_id, username, email, name_first, name_last, password, admin)
-            VALUES ($1, $2, $3, $4, $5, $6, crypt($7, gen_salt('bf', 8)), (SELECT COUNT(*) = 0 FROM users))
+            INSERT INTO users (role_uuid, external_id, username, email, name_first, name_last, password, admin, email_verified)
+            VALUES ($1, $2, $3, $4, $5, $6, crypt($7, gen_salt('bf', 8)), (SELECT COUNT(*) = 0 FROM users), $8)
             RETURNING users.uuid
             "#,
         )
@@ -374,6 +486,7 @@ impl User {
         .bind(name_first)
         .bind(name_last)
         .bind(password)
+        .bind(email_verified)
         .fetch_one(database.write())
         .await?;
 
@@ -401,6 +514,80 @@ impl User {
         row.try_map(|row| Self::map(None, &row))
     }
 
+    /// Creates a user that is linked to a directory entry through its LDAP identifier.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn create_ldap(
+        database: &crate::database::Database,
+        ldap_identifier: &str,
+        role_uuid: Option<uuid::Uuid>,
+        username: &str,
+        email: &str,
+        name_first: &str,
+        name_last: &str,
+        password: &str,
+        language: &str,
+    ) -> Result<uuid::Uuid, crate::database::DatabaseError> {
+        let row = sqlx::query(
+            r#"
+            INSERT INTO users (ldap_identifier, role_uuid, username, email, name_first, name_last, password, admin, language)
+            VALUES ($1, $2, $3, $4, $5, $6, crypt($7, gen_salt('bf', 8)), false, $8)
+            RETURNING users.uuid
+            "#,
+        )
+        .bind(ldap_identifier)
+        .bind(role_uuid)
+        .bind(username)
+        .bind(email)
+        .bind(name_first)
+        .bind(name_last)
+        .bind(password)
+        .bind(language)
+        .fetch_one(database.write())
+        .await?;
+
+        Ok(row.try_get("uuid")?)
+    }
+
+    pub async fn by_ldap_identifier(
+        database: &crate::database::Database,
+        ldap_identifier: &str,
+    ) -> Result<Option<Self>, crate::database::DatabaseError> {
+        let row = sqlx::query(&format!(
+            r#"
+            SELECT {}
+            FROM users
+            LEFT JOIN roles ON roles.uuid = users.role_uuid
+            WHERE users.ldap_identifier = $1
+            "#,
+            Self::columns_sql(None)
+        ))
+        .bind(ldap_identifier)
+        .fetch_optional(database.read())
+        .await?;
+
+        row.try_map(|row| Self::map(None, &row))
+    }
+
+    pub async fn update_role_uuid(
+        database: &crate::database::Database,
+        uuid: uuid::Uuid,
+        role_uuid: Option<uuid::Uuid>,
+    ) -> Result<(), crate::database::DatabaseError> {
+        sqlx::query(
+            r#"
+            UPDATE users
+            SET role_uuid = $2
+            WHERE users.uuid = $1
+            "#,
+        )
+        .bind(uuid)
+        .bind(role_uuid)
+        .execute(database.write())
+        .await?;
+
+        Ok(())
+    }
+
     /// Returns the user and session associated with the given session string, if valid.
     ///
     /// Cached for 5 seconds.
@@ -422,7 +609,14 @@ impl User {
                     FROM users
                     LEFT JOIN roles ON roles.uuid = users.role_uuid
                     JOIN user_sessions ON user_sessions.user_uuid = users.uuid
-                    WHERE user_sessions.key_id = $1 AND user_sessions.key = crypt($2, user_sessions.key)
+                    WHERE
+                        users.active
+                        AND user_sessions.key_id = $1
+                        AND user_sessions.key = crypt($2, user_sessions.key)
+                        AND (
+                            user_sessions.impersonator_uuid IS NULL
+                            OR user_sessions.created > NOW() - INTERVAL '1 hour'
+                        )
                     "#,
                     Self::columns_sql(None),
                     super::user_session::UserSession::columns_sql(Some("session_"))
@@ -458,7 +652,10 @@ impl User {
                     FROM users
                     LEFT JOIN roles ON roles.uuid = users.role_uuid
                     JOIN user_api_keys ON user_api_keys.user_uuid = users.uuid
-                    WHERE user_api_keys.key_start = $1 AND user_api_keys.key = crypt($2, user_api_keys.key)
+                    WHERE
+                        users.active
+                        AND user_api_keys.key_start = $1
+                        AND user_api_keys.key = crypt($2, user_api_keys.key)
                     "#,
                     Self::columns_sql(None),
                     super::user_api_key::UserApiKey::columns_sql(Some("api_key_"))
@@ -528,6 +725,26 @@ impl User {
         row.try_map(|row| Self::map(None, &row))
     }
 
+    pub async fn by_username(
+        database: &crate::database::Database,
+        username: &str,
+    ) -> Result<Option<Self>, crate::database::DatabaseError> {
+        let row = sqlx::query(&format!(
+            r#"
+            SELECT {}
+            FROM users
+            LEFT JOIN roles ON roles.uuid = users.role_uuid
+            WHERE lower(users.username) = lower($1)
+            "#,
+            Self::columns_sql(None)
+        ))
+        .bind(username)
+        .fetch_optional(database.read())
+        .await?;
+
+        row.try_map(|row| Self::map(None, &row))
+    }
+
     pub async fn by_username_password(
         database: &crate::database::Database,
         username: &str,
@@ -639,6 +856,82 @@ impl User {
         })
     }
 
+    pub async fn all_by_role_uuid(
+        database: &crate::database::Database,
+        role_uuid: uuid::Uuid,
+    ) -> Result<Vec<Self>, crate::database::DatabaseError> {
+        let rows = sqlx::query(&format!(
+            r#"
+            SELECT {}
+            FROM users
+            LEFT JOIN roles ON roles.uuid = users.role_uuid
+            WHERE users.role_uuid = $1
+            ORDER BY users.created
+            "#,
+            Self::columns_sql(None)
+        ))
+        .bind(role_uuid)
+        .fetch_all(database.read())
+        .await?;
+
+        rows.into_iter()
+            .map(|row| Self::map(None, &row))
+            .try_collect_vec()
+    }
+
+    /// Lists users matching a SCIM filter, returning the total amount of matches alongside
+    /// the requested window. `start_index` is 1-based like in SCIM.
+    pub async fn all_with_scim_filter(
+        database: &crate::database::Database,
+        filter: Option<&crate::scim::Filter>,
+        start_index: i64,
+        count: i64,
+    ) -> Result<(i64, Vec<Self>), anyhow::Error> {
+        let sql_filter = |first_param| match filter {
+            Some(filter) => filter.to_sql(SCIM_FILTER_ATTRIBUTES, first_param),
+            None => Ok(("TRUE".to_string(), Vec::new())),
+        };
+
+        let (condition, binds) = sql_filter(1)?;
+        let query = format!(
+            r#"
+            SELECT COUNT(*)
+            FROM users
+            WHERE {condition}
+            "#
+        );
+        let mut query = sqlx::query_scalar(&query);
+        for bind in &binds {
+            query = query.bind(bind);
+        }
+        let total: i64 = query.fetch_one(database.read()).await?;
+
+        let (condition, binds) = sql_filter(3)?;
+        let query = format!(
+            r#"
+            SELECT {}
+            FROM users
+            LEFT JOIN roles ON roles.uuid = users.role_uuid
+            WHERE {condition}
+            ORDER BY users.created
+            LIMIT $1 OFFSET $2 - 1
+            "#,
+            Self::columns_sql(None)
+        );
+        let mut query = sqlx::query(&query).bind(count).bind(start_index);
+        for bind in &binds {
+            query = query.bind(bind);
+        }
+        let rows = query.fetch_all(database.read()).await?;
+
+        Ok((
+            total,
+            rows.into_iter()
+                .map(|row| Self::map(None, &row))
+                .try_collect_vec()?,
+        ))
+    }
+
     pub async fn all_with_pagination(
         database: &crate::database::Database,
         page: i64,
@@ -714,6 +1007,37 @@ impl User {
         database: &crate::database::Database,
         password: &str,
     ) -> Result<(), crate::database::DatabaseError> {
+        let mut transaction = database.write().begin().await?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO user_password_histories (user_uuid, password)
+            SELECT users.uuid, users.password
+            FROM users
+            WHERE users.uuid = $1 AND users.password IS NOT NULL
+            "#,
+        )
+        .bind(self.uuid)
+        .execute(&mut *transaction)
+        .await?;
+
+        sqlx::query(
+            r#"
+            DELETE FROM user_password_histories
+            WHERE user_password_histories.user_uuid = $1 AND user_password_histories.uuid NOT IN (
+                SELECT user_password_histories.uuid
+                FROM user_password_histories
+                WHERE user_password_histories.user_uuid = $1
+                ORDER BY user_password_histories.created DESC
+                LIMIT $2
+            )
+            "#,
+        )
+        .bind(self.uuid)
+        .bind(PASSWORD_HISTORY_LIMIT)
+        .execute(&mut *transaction)
+        .await?;
+
         sqlx::query(
             r#"
             UPDATE users
@@ -723,6 +1047,449 @@ impl User {
         )
         .bind(self.uuid)
         .bind(password)
+        .execute(&mut *transaction)
+        .await?;
+
+        transaction.commit().await?;
+
+        Ok(())
+    }
+
+    /// Removes the password of the user, leaving passkeys as the only way to log in.
+    pub async fn remove_password(
+        &self,
+        database: &crate::database::Database,
+    ) -> Result<(), crate::database::DatabaseError> {
+        sqlx::query(
+            r#"
+            UPDATE users
+            SET password = NULL
+            WHERE users.uuid = $1
+            "#,
+        )
+        .bind(self.uuid)
+        .execute(database.write())
+        .await?;
+
+        Ok(())
+    }
+
+    /// Whether the user has at least one passkey registered.
+    ///
+    /// Cached for 5 seconds.
+    pub async fn has_passkey(
+        &self,
+        database: &crate::database::Database,
+    ) -> Result<bool, anyhow::Error> {
+        database
+            .cache
+            .cached(&format!("user::has_passkey::{}", self.uuid), 5, || async {
+                sqlx::query_scalar::<_, bool>(
+                    r#"
+                    SELECT EXISTS (
+                        SELECT 1
+                        FROM user_security_keys
+                        WHERE user_security_keys.user_uuid = $1 AND user_security_keys.passkey IS NOT NULL
+                    )
+                    "#,
+                )
+                .bind(self.uuid)
+                .fetch_one(database.read())
+                .await
+            })
+            .await
+    }
+
+    /// Confirms a sensitive change with the current password, or with a passkey assertion for
+    /// accounts without a password.
+    pub async fn validate_password_or_passkey(
+        &self,
+        database: &crate::database::Database,
+        settings: &crate::settings::Settings,
+        password: &str,
+        confirmation: Option<&UserPasskeyConfirmation>,
+    ) -> Result<bool, anyhow::Error> {
+        if self.has_password {
+            return Ok(self.validate_password(database, password).await?);
+        }
+
+        match confirmation {
+            Some(confirmation) => {
+                self.validate_passkey_confirmation(database, settings, confirmation)
+                    .await
+            }
+            None => Ok(false),
+        }
+    }
+
+    /// Starts a passkey assertion confirming a sensitive change, accounts without a password
+    /// confirm with one of their passkeys instead of the current password.
+    pub async fn start_passkey_confirmation(
+        &self,
+        database: &crate::database::Database,
+        settings: &crate::settings::Settings,
+    ) -> Result<(uuid::Uuid, RequestChallengeResponse), anyhow::Error> {
+        let raw_passkeys: Vec<serde_json::Value> = sqlx::query_scalar(
+            r#"
+            SELECT user_security_keys.passkey
+            FROM user_security_keys
+            WHERE user_security_keys.user_uuid = $1 AND user_security_keys.passkey IS NOT NULL
+            "#,
+        )
+        .bind(self.uuid)
+        .fetch_all(database.read())
+        .await?;
+
+        let passkeys = raw_passkeys
+            .into_iter()
+            .filter_map(|passkey| serde_json::from_value::<Passkey>(passkey).ok())
+            .collect::<Vec<_>>();
+
+        let (options, authentication) = settings
+            .get_webauthn()
+            .await?
+            .start_passkey_authentication(&passkeys)?;
+        let uuid = uuid::Uuid::new_v4();
+
+        database
+            .cache
+            .client
+            .set_with_options(
+                format!("user::passkey_confirmation::{}::{uuid}", self.uuid),
+                serde_json::to_string(&authentication)?,
+                None,
+                SetExpiration::Ex(options.public_key.timeout.unwrap_or(300000) as u64 / 1000),
+            )
+            .await?;
+
+        Ok((uuid, options))
+    }
+
+    /// Finishes a passkey assertion started by [`Self::start_passkey_confirmation`], every
+    /// assertion can only be used once.
+    pub async fn validate_passkey_confirmation(
+        &self,
+        database: &crate::database::Database,
+        settings: &crate::settings::Settings,
+        confirmation: &UserPasskeyConfirmation,
+    ) -> Result<bool, anyhow::Error> {
+        let key = format!(
+            "user::passkey_confirmation::{}::{}",
+            self.uuid, confirmation.uuid
+        );
+
+        let authentication = match database.cache.client.get::<String>(key.as_str()).await {
+            Ok(authentication) => authentication,
+            Err(_) => return Ok(false),
+        };
+        database.cache.client.del(key.as_str()).await?;
+
+        let authentication: PasskeyAuthentication = serde_json::from_str(&authentication)?;
+        let passkey = match settings
+            .get_webauthn()
+            .await?
+            .finish_passkey_authentication(&confirmation.public_key_credential, &authentication)
+        {
+            Ok(passkey) => passkey,
+            Err(_) => return Ok(false),
+        };
+
+        let security_key = match Self::by_credential_id(database, passkey.cred_id()).await? {
+            Some((user, security_key)) if user.uuid == self.uuid => security_key,
+            _ => return Ok(false),
+        };
+
+        if let Some(mut db_passkey) = security_key.passkey {
+            db_passkey.update_credential(&passkey);
+
+            sqlx::query(
+                r#"
+                UPDATE user_security_keys
+                SET passkey = $2, last_used = NOW()
+                WHERE user_security_keys.uuid = $1
+                "#,
+            )
+            .bind(security_key.uuid)
+            .bind(serde_json::to_value(db_passkey)?)
+            .execute(database.write())
+            .await?;
+        }
+
+        Ok(true)
+    }
+
+    /// Checks a new password against the configured password policy, the user is [`None`]
+    /// when the password is for an account that does not exist yet.
+    pub async fn validate_password_policy(
+        database: &crate::database::Database,
+        security: &crate::settings::AppSettingsSecurity,
+        user_uuid: Option<uuid::Uuid>,
+        username: &str,
+        email: &str,
+        password: &str,
+    ) -> Result<(), anyhow::Error> {
+        if password.chars().count() < security.password_min_length as usize {
+            return Err(crate::response::DisplayError::new(format!(
+                "password must be at least {} characters long",
+                security.password_min_length
+            ))
+            .into());
+        }
+
+        let requirements = [
+            (
+                security.password_require_uppercase,
+                password.chars().any(char::is_uppercase),
+                "password must contain an uppercase letter",
+            ),
+            (
+                security.password_require_lowercase,
+                password.chars().any(char::is_lowercase),
+                "password must contain a lowercase letter",
+            ),
+            (
+                security.password_require_number,
+                password.chars().any(char::is_numeric),
+                "password must contain a number",
+            ),
+            (
+                security.password_require_symbol,
+                password
+                    .chars()
+                    .any(|c| !c.is_alphanumeric() && !c.is_whitespace()),
+                "password must contain a symbol",
+            ),
+        ];
+
+        for (required, fulfilled, message) in requirements {
+            if required && !fulfilled {
+                return Err(crate::response::DisplayError::new(message).into());
+            }
+        }
+
+        if security.password_forbid_user_details {
+            let password = password.to_lowercase();
+            let email_local_part = email.split_once('@').map_or(email, |(local, _)| local);
+
+            if username.len() >= 3 && password.contains(&username.to_lowercase()) {
+                return Err(crate::response::DisplayError::new(
+                    "password must not contain your username",
+                )
+                .into());
+            }
+            if email_local_part.len() >= 3 && password.contains(&email_local_part.to_lowercase()) {
+                return Err(crate::response::DisplayError::new(
+                    "password must not contain your email address",
+                )
+                .into());
+            }
+        }
+
+        if let Some(user_uuid) = user_uuid
+            && security.password_history_count > 0
+        {
+            let row = sqlx::query(
+                r#"
+                SELECT 1
+                FROM (
+                    SELECT users.password
+                    FROM users
+                    WHERE users.uuid = $1
+                    UNION ALL
+                    (
+                        SELECT user_password_histories.password
+                        FROM user_password_histories
+                        WHERE user_password_histories.user_uuid = $1
+                        ORDER BY user_password_histories.created DESC
+                        LIMIT $2
+                    )
+                ) passwords
+                WHERE passwords.password = crypt($3, passwords.password)
+                "#,
+            )
+            .bind(user_uuid)
+            .bind(security.password_history_count as i64 - 1)
+            .bind(password)
+            .fetch_optional(database.read())
+            .await?;
+
+            if row.is_some() {
+                return Err(crate::response::DisplayError::new(format!(
+                    "password must not be one of your last {} passwords",
+                    security.password_history_count
+                ))
+                .into());
+            }
+        }
+
+        if security.password_check_breached
+            && super::breached_password_range::BreachedPasswordRange::contains_password(
+                database, password,
+            )
+            .await?
+        {
+            return Err(crate::response::DisplayError::new(
+                "password has appeared in a data breach, please choose a different one",
+            )
+            .into());
+        }
+
+        Ok(())
+    }
+
+    /// Whether the account is temporarily locked because of too many failed login attempts.
+    #[inline]
+    pub fn is_locked(&self) -> bool {
+        self.locked_until
+            .is_some_and(|locked_until| locked_until > chrono::Utc::now().naive_utc())
+    }
+
+    /// Activates or deactivates the account. Deactivating revokes every session and API key
+    /// of the user, servers owned by the user are left untouched.
+    pub async fn set_active(
+        &mut self,
+        database: &crate::database::Database,
+        active: bool,
+    ) -> Result<(), crate::database::DatabaseError> {
+        let mut transaction = database.write().begin().await?;
+
+        sqlx::query(
+            r#"
+            UPDATE users
+            SET active = $2
+            WHERE users.uuid = $1
+            "#,
+        )
+        .bind(self.uuid)
+        .bind(active)
+        .execute(&mut *transaction)
+        .await?;
+
+        if !active {
+            sqlx::query(
+                r#"
+                DELETE FROM user_sessions
+                WHERE user_sessions.user_uuid = $1
+                "#,
+            )
+            .bind(self.uuid)
+            .execute(&mut *transaction)
+            .await?;
+
+            sqlx::query(
+                r#"
+                DELETE FROM user_api_keys
+                WHERE user_api_keys.user_uuid = $1
+                "#,
+            )
+            .bind(self.uuid)
+            .execute(&mut *transaction)
+            .await?;
+        }
+
+        transaction.commit().await?;
+        self.active = active;
+
+        Ok(())
+    }
+
+    /// Counts a failed login attempt, locking the account for an exponentially growing
+    /// duration every time the configured threshold is reached again.
+    pub async fn record_failed_login(
+        &self,
+        state: &crate::State,
+        ip: Option<std::net::IpAddr>,
+    ) -> Result<(), anyhow::Error> {
+        let failed_login_attempts: i32 = sqlx::query_scalar(
+            r#"
+            UPDATE users
+            SET failed_login_attempts = users.failed_login_attempts + 1
+            WHERE users.uuid = $1
+            RETURNING users.failed_login_attempts
+            "#,
+        )
+        .bind(self.uuid)
+        .fetch_one(state.database.write())
+        .await?;
+
+        let settings = state.settings.get().await?;
+        let lockout_duration = settings.security.lockout_duration(failed_login_attempts);
+        drop(settings);
+
+        let lockout_duration = match lockout_duration {
+            Some(lockout_duration) => lockout_duration,
+            None => return Ok(()),
+        };
+
+        let locked_until = chrono::Utc::now().naive_utc() + lockout_duration;
+
+        sqlx::query(
+            r#"
+            UPDATE users
+            SET locked_until = $2
+            WHERE users.uuid = $1
+            "#,
+        )
+        .bind(self.uuid)
+        .bind(locked_until)
+        .execute(state.database.write())
+        .await?;
+
+        if let Err(err) = super::user_activity::UserActivity::log(
+            &state.database,
+            self.uuid,
+            None,
+            "auth:locked",
+            ip.map(|ip| ip.into()),
+            serde_json::json!({
+                "failed_login_attempts": failed_login_attempts,
+                "locked_until": locked_until.and_utc(),
+            }),
+        )
+        .await
+        {
+            tracing::warn!(
+                user = %self.uuid,
+                "failed to log user activity: {:#?}",
+                err
+            );
+        }
+
+        state
+            .mail
+            .send_template(
+                self.email.clone(),
+                crate::mail::MailTemplateIdentifier::AccountLocked,
+                &self.language,
+                serde_json::json!({
+                    "user": crate::mail::user_context(self),
+                    "ip": ip.map(|ip| ip.to_string()),
+                    "failed_login_attempts": failed_login_attempts,
+                    "locked_until": locked_until.and_utc().to_rfc2822(),
+                }),
+            )
+            .await;
+
+        Ok(())
+    }
+
+    /// Clears the failed login attempts and any lockout of the account.
+    pub async fn reset_failed_logins(
+        &self,
+        database: &crate::database::Database,
+    ) -> Result<(), crate::database::DatabaseError> {
+        if self.failed_login_attempts == 0 && self.locked_until.is_none() {
+            return Ok(());
+        }
+
+        sqlx::query(
+            r#"
+            UPDATE users
+            SET failed_login_attempts = 0, locked_until = NULL
+            WHERE users.uuid = $1
+            "#,
+        )
+        .bind(self.uuid)
         .execute(database.write())
         .await?;
 
@@ -741,6 +1508,34 @@ impl User {
         }
     }
 
+    /// Whether the role of the user requires logging in with a passkey.
+    #[inline]
+    pub fn require_passkey(&self) -> bool {
+        self.role.as_ref().is_some_and(|role| role.require_passkey)
+    }
+
+    /// Whether the user may not log in until their email address is verified.
+    #[inline]
+    pub fn email_verification_blocks_login(&self, settings: &crate::settings::AppSettings) -> bool {
+        !self.email_verified
+            && settings.app.email_verification
+                == crate::settings::EmailVerificationMode::RequiredForLogin
+    }
+
+    /// Whether no servers may be created for the user until their email address is verified.
+    #[inline]
+    pub fn email_verification_blocks_servers(
+        &self,
+        settings: &crate::settings::AppSettings,
+    ) -> bool {
+        !self.email_verified
+            && matches!(
+                settings.app.email_verification,
+                crate::settings::EmailVerificationMode::RequiredForServers
+                    | crate::settings::EmailVerificationMode::RequiredForLogin
+            )
+    }
+
     #[inline]
     pub fn into_api_object(self, storage_url_retriever: &StorageUrlRetriever<'_>) -> ApiUser {
         ApiUser {
@@ -763,6 +1558,7 @@ impl User {
         storage_url_retriever: &StorageUrlRetriever<'_>,
     ) -> ApiFullUser {
         let require_two_factor = self.require_two_factor(storage_url_retriever.get_settings());
+        let require_passkey = self.require_passkey();
 
         ApiFullUser {
             uuid: self.uuid,
@@ -773,18 +1569,65 @@ impl User {
                 .as_ref()
                 .map(|a| storage_url_retriever.get_url(a)),
             email: self.email,
+            email_verified: self.email_verified,
             name_first: self.name_first,
             name_last: self.name_last,
             admin: self.admin,
+            has_password: self.has_password,
             totp_enabled: self.totp_enabled,
             totp_last_used: self.totp_last_used.map(|dt| dt.and_utc()),
             require_two_factor,
+            require_passkey,
+            locked_until: self.locked_until.map(|dt| dt.and_utc()),
+            active: self.active,
             language: self.language,
             toast_position: self.toast_position,
             start_on_grouped_servers: self.start_on_grouped_servers,
             created: self.created.and_utc(),
         }
     }
+
+    /// Converts the user into its SCIM representation, `base_url` being the panel url.
+    pub fn into_scim_object(self, base_url: &str) -> crate::scim::ScimUser {
+        let base_url = base_url.trim_end_matches('/');
+
+        crate::scim::ScimUser {
+            schemas: [crate::scim::SCHEMA_USER],
+            id: self.uuid,
+            external_id: self.external_id,
+            display_name: compact_str::format_compact!("{} {}", self.name_first, self.name_last),
+            name: crate::scim::ScimName {
+                formatted: Some(compact_str::format_compact!(
+                    "{} {}",
+                    self.name_first,
+                    self.name_last
+                )),
+                given_name: Some(self.name_first),
+                family_name: Some(self.name_last),
+            },
+            user_name: self.username,
+            emails: vec![crate::scim::ScimEmail {
+                value: self.email,
+                r#type: Some("work".into()),
+                primary: true,
+            }],
+            active: self.active,
+            groups: self
+                .role
+                .into_iter()
+                .map(|role| crate::scim::ScimReference {
+                    value: role.uuid.to_compact_string(),
+                    display: Some(role.name),
+                    r#ref: Some(format!("{base_url}/scim/v2/Groups/{}", role.uuid)),
+                })
+                .collect(),
+            meta: crate::scim::ScimMeta {
+                resource_type: "User",
+                created: self.created.and_utc(),
+                location: format!("{base_url}/scim/v2/Users/{}", self.uuid),
+            },
+        }
+    }
 }
 
 #[async_trait::async_trait]
@@ -873,14 +1716,19 @@ pub struct ApiFullUser {
     pub role: Option<super::role::AdminApiRole>,
     pub avatar: Option<String>,
     pub email: compact_str::CompactString,
+    pub email_verified: bool,
 
     pub name_first: compact_str::CompactString,
     pub name_last: compact_str::CompactString,
 
     pub admin: bool,
+    pub has_password: bool,
     pub totp_enabled: bool,
     pub totp_last_used: Option<chrono::DateTime<chrono::Utc>>,
     pub require_two_factor: bool,
+    pub require_passkey: bool,
+    pub locked_until: Option<chrono::DateTime<chrono::Utc>>,
+    pub active: bool,
 
     pub language: compact_str::CompactString,
     pub toast_position: UserToastPosition,
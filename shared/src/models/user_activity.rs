@@ -11,11 +11,18 @@ pub struct UserActivityLogger {
     pub state: State,
     pub user_uuid: uuid::Uuid,
     pub api_key_uuid: Option<uuid::Uuid>,
+    pub impersonator_uuid: Option<uuid::Uuid>,
     pub ip: std::net::IpAddr,
 }
 
 impl UserActivityLogger {
-    pub async fn log(&self, event: &str, data: serde_json::Value) {
+    pub async fn log(&self, event: &str, mut data: serde_json::Value) {
+        if let Some(impersonator_uuid) = self.impersonator_uuid
+            && let Some(data) = data.as_object_mut()
+        {
+            data.insert("impersonator".into(), impersonator_uuid.to_string().into());
+        }
+
         if let Err(err) = crate::models::user_activity::UserActivity::log(
             &self.state.database,
             self.user_uuid,
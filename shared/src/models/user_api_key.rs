@@ -20,6 +20,10 @@ pub struct UserApiKey {
     pub admin_permissions: Arc<Vec<compact_str::CompactString>>,
     pub server_permissions: Arc<Vec<compact_str::CompactString>>,
 
+    pub server_uuids: Option<Vec<uuid::Uuid>>,
+    pub server_group_uuid: Option<uuid::Uuid>,
+    pub server_group_server_uuids: Option<Vec<uuid::Uuid>>,
+
     pub last_used: Option<chrono::NaiveDateTime>,
     pub expires: Option<chrono::NaiveDateTime>,
     pub created: chrono::NaiveDateTime,
@@ -61,6 +65,18 @@ impl BaseModel for UserApiKey {
                 "user_api_keys.server_permissions",
                 compact_str::format_compact!("{prefix}server_permissions"),
             ),
+            (
+                "user_api_keys.server_uuids",
+                compact_str::format_compact!("{prefix}server_uuids"),
+            ),
+            (
+                "user_api_keys.server_group_uuid",
+                compact_str::format_compact!("{prefix}server_group_uuid"),
+            ),
+            (
+                "(SELECT user_server_groups.server_order FROM user_server_groups WHERE user_server_groups.uuid = user_api_keys.server_group_uuid)",
+                compact_str::format_compact!("{prefix}server_group_server_uuids"),
+            ),
             (
                 "user_api_keys.last_used",
                 compact_str::format_compact!("{prefix}last_used"),
@@ -95,6 +111,13 @@ impl BaseModel for UserApiKey {
             server_permissions: Arc::new(
                 row.try_get(compact_str::format_compact!("{prefix}server_permissions").as_str())?,
             ),
+            server_uuids: row
+                .try_get(compact_str::format_compact!("{prefix}server_uuids").as_str())?,
+            server_group_uuid: row
+                .try_get(compact_str::format_compact!("{prefix}server_group_uuid").as_str())?,
+            server_group_server_uuids: row.try_get(
+                compact_str::format_compact!("{prefix}server_group_server_uuids").as_str(),
+            )?,
             last_used: row.try_get(compact_str::format_compact!("{prefix}last_used").as_str())?,
             expires: row.try_get(compact_str::format_compact!("{prefix}expires").as_str())?,
             created: row.try_get(compact_str::format_compact!("{prefix}created").as_str())?,
@@ -112,6 +135,8 @@ impl UserApiKey {
         user_permissions: &[compact_str::CompactString],
         admin_permissions: &[compact_str::CompactString],
         server_permissions: &[compact_str::CompactString],
+        server_uuids: Option<&[uuid::Uuid]>,
+        server_group_uuid: Option<uuid::Uuid>,
         expires: Option<chrono::NaiveDateTime>,
     ) -> Result<(String, Self), crate::database::DatabaseError> {
         let key = format!(
@@ -121,8 +146,8 @@ impl UserApiKey {
 
         let row = sqlx::query(&format!(
             r#"
-            INSERT INTO user_api_keys (user_uuid, name, key_start, key, allowed_ips, user_permissions, admin_permissions, server_permissions, expires, created)
-            VALUES ($1, $2, $3, crypt($4, gen_salt('xdes', 321)), $5, $6, $7, $8, $9, NOW())
+            INSERT INTO user_api_keys (user_uuid, name, key_start, key, allowed_ips, user_permissions, admin_permissions, server_permissions, server_uuids, server_group_uuid, expires, created)
+            VALUES ($1, $2, $3, crypt($4, gen_salt('xdes', 321)), $5, $6, $7, $8, $9, $10, $11, NOW())
             RETURNING {}
             "#,
             Self::columns_sql(None)
@@ -135,6 +160,8 @@ impl UserApiKey {
         .bind(user_permissions)
         .bind(admin_permissions)
         .bind(server_permissions)
+        .bind(server_uuids)
+        .bind(server_group_uuid)
         .bind(expires)
         .fetch_one(database.write())
         .await?;
@@ -203,6 +230,41 @@ impl UserApiKey {
         })
     }
 
+    pub async fn update_server_scope(
+        &self,
+        database: &crate::database::Database,
+    ) -> Result<(), crate::database::DatabaseError> {
+        sqlx::query(
+            r#"
+            UPDATE user_api_keys
+            SET server_uuids = $2, server_group_uuid = $3
+            WHERE user_api_keys.uuid = $1
+            "#,
+        )
+        .bind(self.uuid)
+        .bind(self.server_uuids.as_deref())
+        .bind(self.server_group_uuid)
+        .execute(database.write())
+        .await?;
+
+        Ok(())
+    }
+
+    /// The servers this key is restricted to, `None` if it may access every server of its user.
+    /// Keys scoped to a server group follow the group's current members.
+    #[inline]
+    pub fn server_scope(&self) -> Option<&[uuid::Uuid]> {
+        if self.server_group_uuid.is_some() {
+            Some(
+                self.server_group_server_uuids
+                    .as_deref()
+                    .unwrap_or_default(),
+            )
+        } else {
+            self.server_uuids.as_deref()
+        }
+    }
+
     pub async fn delete_expired(database: &crate::database::Database) -> Result<u64, sqlx::Error> {
         Ok(sqlx::query(
             r#"
@@ -225,6 +287,8 @@ impl UserApiKey {
             user_permissions: self.user_permissions,
             admin_permissions: self.admin_permissions,
             server_permissions: self.server_permissions,
+            server_uuids: self.server_uuids,
+            server_group_uuid: self.server_group_uuid,
             last_used: self.last_used.map(|dt| dt.and_utc()),
             expires: self.expires.map(|dt| dt.and_utc()),
             created: self.created.and_utc(),
@@ -283,6 +347,9 @@ pub struct ApiUserApiKey {
     pub admin_permissions: Arc<Vec<compact_str::CompactString>>,
     pub server_permissions: Arc<Vec<compact_str::CompactString>>,
 
+    pub server_uuids: Option<Vec<uuid::Uuid>>,
+    pub server_group_uuid: Option<uuid::Uuid>,
+
     pub last_used: Option<chrono::DateTime<chrono::Utc>>,
     pub expires: Option<chrono::DateTime<chrono::Utc>>,
     pub created: chrono::DateTime<chrono::Utc>,
@@ -0,0 +1,212 @@
+use crate::prelude::*;
+use rand::distr::SampleString;
+use serde::{Deserialize, Serialize};
+use sqlx::{Row, postgres::PgRow};
+use std::collections::BTreeMap;
+
+#[derive(Serialize, Deserialize)]
+pub struct UserEmailVerification {
+    pub uuid: uuid::Uuid,
+    pub user: super::user::User,
+
+    pub email: compact_str::CompactString,
+    pub token: String,
+
+    pub created: chrono::NaiveDateTime,
+}
+
+impl BaseModel for UserEmailVerification {
+    const NAME: &'static str = "user_email_verification";
+
+    #[inline]
+    fn columns(prefix: Option<&str>) -> BTreeMap<&'static str, compact_str::CompactString> {
+        let prefix = prefix.unwrap_or_default();
+
+        let mut columns = BTreeMap::from([
+            (
+                "user_email_verifications.uuid",
+                compact_str::format_compact!("{prefix}uuid"),
+            ),
+            (
+                "user_email_verifications.email",
+                compact_str::format_compact!("{prefix}email"),
+            ),
+            (
+                "user_email_verifications.token",
+                compact_str::format_compact!("{prefix}token"),
+            ),
+            (
+                "user_email_verifications.created",
+                compact_str::format_compact!("{prefix}created"),
+            ),
+        ]);
+
+        columns.extend(super::user::User::columns(Some("user_")));
+
+        columns
+    }
+
+    #[inline]
+    fn map(prefix: Option<&str>, row: &PgRow) -> Result<Self, crate::database::DatabaseError> {
+        let prefix = prefix.unwrap_or_default();
+
+        Ok(Self {
+            uuid: row.try_get(compact_str::format_compact!("{prefix}uuid").as_str())?,
+            user: super::user::User::map(Some("user_"), row)?,
+            email: row.try_get(compact_str::format_compact!("{prefix}email").as_str())?,
+            token: row.try_get(compact_str::format_compact!("{prefix}token").as_str())?,
+            created: row.try_get(compact_str::format_compact!("{prefix}created").as_str())?,
+        })
+    }
+}
+
+impl UserEmailVerification {
+    /// Creates a verification for the given address, replacing any previous one of the user.
+    pub async fn create(
+        database: &crate::database::Database,
+        user_uuid: uuid::Uuid,
+        email: &str,
+    ) -> Result<String, anyhow::Error> {
+        let existing = sqlx::query(
+            r#"
+            SELECT COUNT(*)
+            FROM user_email_verifications
+            WHERE user_email_verifications.user_uuid = $1 AND user_email_verifications.created > NOW() - INTERVAL '1 minute'
+            "#,
+        )
+        .bind(user_uuid)
+        .fetch_optional(database.read())
+        .await?;
+
+        if let Some(row) = existing
+            && row.get::<i64, _>(0) > 0
+        {
+            return Err(crate::response::DisplayError::new(
+                "a verification mail was already sent recently",
+            )
+            .into());
+        }
+
+        let token = rand::distr::Alphanumeric.sample_string(&mut rand::rng(), 96);
+
+        let mut transaction = database.write().begin().await?;
+
+        sqlx::query(
+            r#"
+            DELETE FROM user_email_verifications
+            WHERE user_email_verifications.user_uuid = $1
+            "#,
+        )
+        .bind(user_uuid)
+        .execute(&mut *transaction)
+        .await?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO user_email_verifications (user_uuid, email, token, created)
+            VALUES ($1, $2, crypt($3, gen_salt('bf')), NOW())
+            "#,
+        )
+        .bind(user_uuid)
+        .bind(email)
+        .bind(&token)
+        .execute(&mut *transaction)
+        .await?;
+
+        transaction.commit().await?;
+
+        Ok(token)
+    }
+
+    /// Creates a verification for the given address and mails the link to it.
+    pub async fn send(
+        database: &crate::database::Database,
+        settings: &crate::settings::Settings,
+        mail: &crate::mail::Mail,
+        user: &super::user::User,
+        email: &str,
+    ) -> Result<(), anyhow::Error> {
+        let token = Self::create(database, user.uuid, email).await?;
+
+        let settings = settings.get().await?;
+        let verify_link = format!(
+            "{}/auth/verify-email?token={}",
+            settings.app.url,
+            urlencoding::encode(&token),
+        );
+        drop(settings);
+
+        mail.send_template(
+            email.into(),
+            crate::mail::MailTemplateIdentifier::VerifyEmail,
+            &user.language,
+            serde_json::json!({
+                "user": crate::mail::user_context(user),
+                "email": email,
+                "verify_link": verify_link,
+            }),
+        )
+        .await;
+
+        Ok(())
+    }
+
+    /// Returns the address the user still has to confirm, if the verification has not expired.
+    pub async fn pending_email_by_user_uuid(
+        database: &crate::database::Database,
+        user_uuid: uuid::Uuid,
+    ) -> Result<Option<compact_str::CompactString>, crate::database::DatabaseError> {
+        let row = sqlx::query(
+            r#"
+            SELECT user_email_verifications.email
+            FROM user_email_verifications
+            WHERE
+                user_email_verifications.user_uuid = $1
+                AND user_email_verifications.created > NOW() - INTERVAL '24 hours'
+            "#,
+        )
+        .bind(user_uuid)
+        .fetch_optional(database.read())
+        .await?;
+
+        Ok(row.try_map(|row| row.try_get("email"))?)
+    }
+
+    pub async fn delete_by_token(
+        database: &crate::database::Database,
+        token: &str,
+    ) -> Result<Option<Self>, crate::database::DatabaseError> {
+        let row = sqlx::query(&format!(
+            r#"
+            SELECT {}, {} FROM user_email_verifications
+            JOIN users ON users.uuid = user_email_verifications.user_uuid
+            LEFT JOIN roles ON roles.uuid = users.role_uuid
+            WHERE
+                user_email_verifications.token = crypt($1, user_email_verifications.token)
+                AND user_email_verifications.created > NOW() - INTERVAL '24 hours'
+            "#,
+            Self::columns_sql(None),
+            super::user::User::columns_sql(Some("user_"))
+        ))
+        .bind(token)
+        .fetch_optional(database.read())
+        .await?;
+
+        let row = match row {
+            Some(row) => row,
+            None => return Ok(None),
+        };
+
+        sqlx::query(
+            r#"
+            DELETE FROM user_email_verifications
+            WHERE user_email_verifications.uuid = $1
+            "#,
+        )
+        .bind(row.get::<uuid::Uuid, _>("uuid"))
+        .execute(database.write())
+        .await?;
+
+        Ok(Some(Self::map(None, &row)?))
+    }
+}
@@ -94,7 +94,7 @@ impl UserPasswordReset {
         Ok(token)
     }
 
-    pub async fn delete_by_token(
+    pub async fn by_token(
         database: &crate::database::Database,
         token: &str,
     ) -> Result<Option<Self>, crate::database::DatabaseError> {
@@ -114,21 +114,23 @@ impl UserPasswordReset {
         .fetch_optional(database.read())
         .await?;
 
-        let row = match row {
-            Some(row) => row,
-            None => return Ok(None),
-        };
+        row.try_map(|row| Self::map(None, &row))
+    }
 
+    pub async fn delete(
+        &self,
+        database: &crate::database::Database,
+    ) -> Result<(), crate::database::DatabaseError> {
         sqlx::query(
             r#"
             DELETE FROM user_password_resets
             WHERE user_password_resets.uuid = $1
             "#,
         )
-        .bind(row.get::<uuid::Uuid, _>("uuid"))
+        .bind(self.uuid)
         .execute(database.write())
         .await?;
 
-        Ok(Some(Self::map(None, &row)?))
+        Ok(())
     }
 }
@@ -0,0 +1,376 @@
+use crate::{prelude::*, storage::StorageUrlRetriever};
+use serde::{Deserialize, Serialize};
+use sqlx::{Row, postgres::PgRow};
+use std::{
+    collections::BTreeMap,
+    sync::{Arc, LazyLock},
+};
+use utoipa::ToSchema;
+
+#[derive(Serialize, Deserialize)]
+pub struct UserSamlLink {
+    pub uuid: uuid::Uuid,
+    pub user: Fetchable<super::user::User>,
+    pub saml_provider: Fetchable<super::saml_provider::SamlProvider>,
+
+    pub identifier: compact_str::CompactString,
+
+    pub last_used: Option<chrono::NaiveDateTime>,
+    pub created: chrono::NaiveDateTime,
+}
+
+impl BaseModel for UserSamlLink {
+    const NAME: &'static str = "user_saml_link";
+
+    #[inline]
+    fn columns(prefix: Option<&str>) -> BTreeMap<&'static str, compact_str::CompactString> {
+        let prefix = prefix.unwrap_or_default();
+
+        BTreeMap::from([
+            (
+                "user_saml_links.uuid",
+                compact_str::format_compact!("{prefix}uuid"),
+            ),
+            (
+                "user_saml_links.user_uuid",
+                compact_str::format_compact!("{prefix}user_uuid"),
+            ),
+            (
+                "user_saml_links.saml_provider_uuid",
+                compact_str::format_compact!("{prefix}saml_provider_uuid"),
+            ),
+            (
+                "user_saml_links.identifier",
+                compact_str::format_compact!("{prefix}identifier"),
+            ),
+            (
+                "user_saml_links.last_used",
+                compact_str::format_compact!("{prefix}last_used"),
+            ),
+            (
+                "user_saml_links.created",
+                compact_str::format_compact!("{prefix}created"),
+            ),
+        ])
+    }
+
+    #[inline]
+    fn map(prefix: Option<&str>, row: &PgRow) -> Result<Self, crate::database::DatabaseError> {
+        let prefix = prefix.unwrap_or_default();
+
+        Ok(Self {
+            uuid: row.try_get(compact_str::format_compact!("{prefix}uuid").as_str())?,
+            user: super::user::User::get_fetchable(
+                row.try_get(compact_str::format_compact!("{prefix}user_uuid").as_str())?,
+            ),
+            saml_provider: super::saml_provider::SamlProvider::get_fetchable(
+                row.try_get(compact_str::format_compact!("{prefix}saml_provider_uuid").as_str())?,
+            ),
+            identifier: row.try_get(compact_str::format_compact!("{prefix}identifier").as_str())?,
+            last_used: row.try_get(compact_str::format_compact!("{prefix}last_used").as_str())?,
+            created: row.try_get(compact_str::format_compact!("{prefix}created").as_str())?,
+        })
+    }
+}
+
+impl UserSamlLink {
+    pub async fn create(
+        database: &crate::database::Database,
+        user_uuid: uuid::Uuid,
+        saml_provider_uuid: uuid::Uuid,
+        identifier: &str,
+    ) -> Result<Self, crate::database::DatabaseError> {
+        let row = sqlx::query(&format!(
+            r#"
+            INSERT INTO user_saml_links (user_uuid, saml_provider_uuid, identifier, created)
+            VALUES ($1, $2, $3, NOW())
+            RETURNING {}
+            "#,
+            Self::columns_sql(None)
+        ))
+        .bind(user_uuid)
+        .bind(saml_provider_uuid)
+        .bind(identifier)
+        .fetch_one(database.write())
+        .await?;
+
+        Self::map(None, &row)
+    }
+
+    pub async fn by_saml_provider_uuid_identifier(
+        database: &crate::database::Database,
+        saml_provider_uuid: uuid::Uuid,
+        identifier: &str,
+    ) -> Result<Option<Self>, crate::database::DatabaseError> {
+        let row = sqlx::query(&format!(
+            r#"
+            SELECT {}
+            FROM user_saml_links
+            WHERE user_saml_links.saml_provider_uuid = $1 AND user_saml_links.identifier = $2
+            "#,
+            Self::columns_sql(None)
+        ))
+        .bind(saml_provider_uuid)
+        .bind(identifier)
+        .fetch_optional(database.read())
+        .await?;
+
+        row.try_map(|row| Self::map(None, &row))
+    }
+
+    pub async fn by_saml_provider_uuid_uuid(
+        database: &crate::database::Database,
+        saml_provider_uuid: uuid::Uuid,
+        uuid: uuid::Uuid,
+    ) -> Result<Option<Self>, crate::database::DatabaseError> {
+        let row = sqlx::query(&format!(
+            r#"
+            SELECT {}
+            FROM user_saml_links
+            WHERE user_saml_links.saml_provider_uuid = $1 AND user_saml_links.uuid = $2
+            "#,
+            Self::columns_sql(None)
+        ))
+        .bind(saml_provider_uuid)
+        .bind(uuid)
+        .fetch_optional(database.read())
+        .await?;
+
+        row.try_map(|row| Self::map(None, &row))
+    }
+
+    pub async fn by_user_uuid_uuid(
+        database: &crate::database::Database,
+        user_uuid: uuid::Uuid,
+        uuid: uuid::Uuid,
+    ) -> Result<Option<Self>, crate::database::DatabaseError> {
+        let row = sqlx::query(&format!(
+            r#"
+            SELECT {}
+            FROM user_saml_links
+            WHERE user_saml_links.user_uuid = $1 AND user_saml_links.uuid = $2
+            "#,
+            Self::columns_sql(None)
+        ))
+        .bind(user_uuid)
+        .bind(uuid)
+        .fetch_optional(database.read())
+        .await?;
+
+        row.try_map(|row| Self::map(None, &row))
+    }
+
+    pub async fn by_user_uuid_with_pagination(
+        database: &crate::database::Database,
+        user_uuid: uuid::Uuid,
+        page: i64,
+        per_page: i64,
+        search: Option<&str>,
+    ) -> Result<super::Pagination<Self>, crate::database::DatabaseError> {
+        let offset = (page - 1) * per_page;
+
+        let rows = sqlx::query(&format!(
+            r#"
+            SELECT {}, COUNT(*) OVER() AS total_count
+            FROM user_saml_links
+            WHERE user_saml_links.user_uuid = $1 AND ($2 IS NULL OR user_saml_links.identifier ILIKE '%' || $2 || '%')
+            ORDER BY user_saml_links.created
+            LIMIT $3 OFFSET $4
+            "#,
+            Self::columns_sql(None)
+        ))
+        .bind(user_uuid)
+        .bind(search)
+        .bind(per_page)
+        .bind(offset)
+        .fetch_all(database.read())
+        .await?;
+
+        Ok(super::Pagination {
+            total: rows
+                .first()
+                .map_or(Ok(0), |row| row.try_get("total_count"))?,
+            per_page,
+            page,
+            data: rows
+                .into_iter()
+                .map(|row| Self::map(None, &row))
+                .try_collect_vec()?,
+        })
+    }
+
+    pub async fn filtered_by_user_uuid_with_pagination(
+        database: &crate::database::Database,
+        user_uuid: uuid::Uuid,
+        page: i64,
+        per_page: i64,
+    ) -> Result<super::Pagination<Self>, crate::database::DatabaseError> {
+        let offset = (page - 1) * per_page;
+
+        let rows = sqlx::query(&format!(
+            r#"
+            SELECT {}, COUNT(*) OVER() AS total_count
+            FROM user_saml_links
+            INNER JOIN saml_providers ON user_saml_links.saml_provider_uuid = saml_providers.uuid
+            WHERE user_saml_links.user_uuid = $1 AND saml_providers.link_viewable = true
+            ORDER BY user_saml_links.created
+            LIMIT $2 OFFSET $3
+            "#,
+            Self::columns_sql(None)
+        ))
+        .bind(user_uuid)
+        .bind(per_page)
+        .bind(offset)
+        .fetch_all(database.read())
+        .await?;
+
+        Ok(super::Pagination {
+            total: rows
+                .first()
+                .map_or(Ok(0), |row| row.try_get("total_count"))?,
+            per_page,
+            page,
+            data: rows
+                .into_iter()
+                .map(|row| Self::map(None, &row))
+                .try_collect_vec()?,
+        })
+    }
+
+    pub async fn by_saml_provider_uuid_with_pagination(
+        database: &crate::database::Database,
+        saml_provider_uuid: uuid::Uuid,
+        page: i64,
+        per_page: i64,
+        search: Option<&str>,
+    ) -> Result<super::Pagination<Self>, crate::database::DatabaseError> {
+        let offset = (page - 1) * per_page;
+
+        let rows = sqlx::query(&format!(
+            r#"
+            SELECT {}, COUNT(*) OVER() AS total_count
+            FROM user_saml_links
+            WHERE user_saml_links.saml_provider_uuid = $1 AND ($2 IS NULL OR user_saml_links.identifier ILIKE '%' || $2 || '%')
+            ORDER BY user_saml_links.created
+            LIMIT $3 OFFSET $4
+            "#,
+            Self::columns_sql(None)
+        ))
+        .bind(saml_provider_uuid)
+        .bind(search)
+        .bind(per_page)
+        .bind(offset)
+        .fetch_all(database.read())
+        .await?;
+
+        Ok(super::Pagination {
+            total: rows
+                .first()
+                .map_or(Ok(0), |row| row.try_get("total_count"))?,
+            per_page,
+            page,
+            data: rows
+                .into_iter()
+                .map(|row| Self::map(None, &row))
+                .try_collect_vec()?,
+        })
+    }
+
+    #[inline]
+    pub async fn into_admin_api_object(
+        self,
+        database: &crate::database::Database,
+        storage_url_retriever: &StorageUrlRetriever<'_>,
+    ) -> Result<AdminApiUserSamlLink, anyhow::Error> {
+        Ok(AdminApiUserSamlLink {
+            uuid: self.uuid,
+            user: self
+                .user
+                .fetch_cached(database)
+                .await?
+                .into_api_full_object(storage_url_retriever),
+            identifier: self.identifier,
+            last_used: self.last_used.map(|dt| dt.and_utc()),
+            created: self.created.and_utc(),
+        })
+    }
+
+    #[inline]
+    pub async fn into_api_object(
+        self,
+        database: &crate::database::Database,
+    ) -> Result<ApiUserSamlLink, anyhow::Error> {
+        Ok(ApiUserSamlLink {
+            uuid: self.uuid,
+            saml_provider: self
+                .saml_provider
+                .fetch_cached(database)
+                .await?
+                .into_api_object(),
+            identifier: self.identifier,
+            last_used: self.last_used.map(|dt| dt.and_utc()),
+            created: self.created.and_utc(),
+        })
+    }
+}
+
+#[async_trait::async_trait]
+impl DeletableModel for UserSamlLink {
+    type DeleteOptions = ();
+
+    fn get_delete_handlers() -> &'static LazyLock<DeleteListenerList<Self>> {
+        static DELETE_LISTENERS: LazyLock<DeleteListenerList<UserSamlLink>> =
+            LazyLock::new(|| Arc::new(ModelHandlerList::default()));
+
+        &DELETE_LISTENERS
+    }
+
+    async fn delete(
+        &self,
+        state: &crate::State,
+        options: Self::DeleteOptions,
+    ) -> Result<(), anyhow::Error> {
+        let mut transaction = state.database.write().begin().await?;
+
+        self.run_delete_handlers(&options, state, &mut transaction)
+            .await?;
+
+        sqlx::query(
+            r#"
+            DELETE FROM user_saml_links
+            WHERE user_saml_links.uuid = $1
+            "#,
+        )
+        .bind(self.uuid)
+        .execute(&mut *transaction)
+        .await?;
+
+        transaction.commit().await?;
+
+        Ok(())
+    }
+}
+
+#[derive(ToSchema, Serialize)]
+#[schema(title = "AdminUserSamlLink")]
+pub struct AdminApiUserSamlLink {
+    pub uuid: uuid::Uuid,
+    pub user: super::user::ApiFullUser,
+
+    pub identifier: compact_str::CompactString,
+
+    pub last_used: Option<chrono::DateTime<chrono::Utc>>,
+    pub created: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(ToSchema, Serialize)]
+#[schema(title = "UserSamlLink")]
+pub struct ApiUserSamlLink {
+    pub uuid: uuid::Uuid,
+    pub saml_provider: super::saml_provider::ApiSamlProvider,
+
+    pub identifier: compact_str::CompactString,
+
+    pub last_used: Option<chrono::DateTime<chrono::Utc>>,
+    pub created: chrono::DateTime<chrono::Utc>,
+}
@@ -18,6 +18,7 @@ pub struct UserSession {
 
     pub ip: sqlx::types::ipnetwork::IpNetwork,
     pub user_agent: String,
+    pub impersonator_uuid: Option<uuid::Uuid>,
 
     pub last_used: chrono::NaiveDateTime,
     pub created: chrono::NaiveDateTime,
@@ -43,6 +44,10 @@ impl BaseModel for UserSession {
                 "user_sessions.user_agent",
                 compact_str::format_compact!("{prefix}user_agent"),
             ),
+            (
+                "user_sessions.impersonator_uuid",
+                compact_str::format_compact!("{prefix}impersonator_uuid"),
+            ),
             (
                 "user_sessions.last_used",
                 compact_str::format_compact!("{prefix}last_used"),
@@ -62,6 +67,8 @@ impl BaseModel for UserSession {
             uuid: row.try_get(compact_str::format_compact!("{prefix}uuid").as_str())?,
             ip: row.try_get(compact_str::format_compact!("{prefix}ip").as_str())?,
             user_agent: row.try_get(compact_str::format_compact!("{prefix}user_agent").as_str())?,
+            impersonator_uuid: row
+                .try_get(compact_str::format_compact!("{prefix}impersonator_uuid").as_str())?,
             last_used: row.try_get(compact_str::format_compact!("{prefix}last_used").as_str())?,
             created: row.try_get(compact_str::format_compact!("{prefix}created").as_str())?,
         })
@@ -74,6 +81,27 @@ impl UserSession {
         user_uuid: uuid::Uuid,
         ip: sqlx::types::ipnetwork::IpNetwork,
         user_agent: &str,
+    ) -> Result<String, crate::database::DatabaseError> {
+        Self::create_internal(database, user_uuid, None, ip, user_agent).await
+    }
+
+    /// Creates a session that lets an admin act as the given user, it expires after an hour.
+    pub async fn create_impersonation(
+        database: &crate::database::Database,
+        user_uuid: uuid::Uuid,
+        impersonator_uuid: uuid::Uuid,
+        ip: sqlx::types::ipnetwork::IpNetwork,
+        user_agent: &str,
+    ) -> Result<String, crate::database::DatabaseError> {
+        Self::create_internal(database, user_uuid, Some(impersonator_uuid), ip, user_agent).await
+    }
+
+    async fn create_internal(
+        database: &crate::database::Database,
+        user_uuid: uuid::Uuid,
+        impersonator_uuid: Option<uuid::Uuid>,
+        ip: sqlx::types::ipnetwork::IpNetwork,
+        user_agent: &str,
     ) -> Result<String, crate::database::DatabaseError> {
         let key_id = rand::distr::Alphanumeric.sample_string(&mut rand::rng(), 16);
 
@@ -84,8 +112,8 @@ impl UserSession {
 
         sqlx::query(
             r#"
-            INSERT INTO user_sessions (user_uuid, key_id, key, ip, user_agent, last_used, created)
-            VALUES ($1, $2, crypt($3, gen_salt('xdes', 321)), $4, $5, NOW(), NOW())
+            INSERT INTO user_sessions (user_uuid, key_id, key, ip, user_agent, impersonator_uuid, last_used, created)
+            VALUES ($1, $2, crypt($3, gen_salt('xdes', 321)), $4, $5, $6, NOW(), NOW())
             "#,
         )
         .bind(user_uuid)
@@ -93,6 +121,7 @@ impl UserSession {
         .bind(&hash)
         .bind(ip)
         .bind(user_agent)
+        .bind(impersonator_uuid)
         .execute(database.write())
         .await?;
 
@@ -177,6 +206,7 @@ impl UserSession {
             uuid: self.uuid,
             ip: self.ip.ip().to_string(),
             user_agent: self.user_agent,
+            impersonated: self.impersonator_uuid.is_some(),
             is_using: match &**auth {
                 AuthMethod::Session(session) => session.uuid == self.uuid,
                 _ => false,
@@ -232,6 +262,7 @@ pub struct ApiUserSession {
     pub ip: String,
     pub user_agent: String,
 
+    pub impersonated: bool,
     pub is_using: bool,
 
     pub last_used: chrono::DateTime<chrono::Utc>,
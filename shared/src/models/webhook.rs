@@ -0,0 +1,449 @@
+use crate::{
+    models::{node::NodeEvent, server::ServerEvent, webhook_delivery::WebhookDelivery},
+    prelude::*,
+};
+use rand::distr::SampleString;
+use serde::{Deserialize, Serialize};
+use sqlx::{Row, postgres::PgRow};
+use std::{
+    collections::BTreeMap,
+    sync::{Arc, LazyLock},
+};
+use utoipa::ToSchema;
+use validator::ValidationError;
+
+pub const WEBHOOK_EVENTS: &[&str] = &[
+    "server:transfer_started",
+    "server:transfer_completed",
+    "server:install_started",
+    "server:install_completed",
+    "server:state_reset",
+    "node:token_reset",
+    "node:state_reset",
+];
+
+pub fn validate_webhook_events(
+    events: &[compact_str::CompactString],
+) -> Result<(), ValidationError> {
+    for event in events {
+        if !WEBHOOK_EVENTS.contains(&event.as_str()) {
+            return Err(ValidationError::new("invalid_webhook_event")
+                .with_message(format!("invalid webhook event: {}", event).into()));
+        }
+    }
+
+    Ok(())
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Webhook {
+    pub uuid: uuid::Uuid,
+
+    pub name: compact_str::CompactString,
+    pub description: Option<compact_str::CompactString>,
+
+    pub url: compact_str::CompactString,
+    pub secret: Vec<u8>,
+    pub events: Vec<compact_str::CompactString>,
+
+    pub enabled: bool,
+
+    pub created: chrono::NaiveDateTime,
+}
+
+impl BaseModel for Webhook {
+    const NAME: &'static str = "webhook";
+
+    #[inline]
+    fn columns(prefix: Option<&str>) -> BTreeMap<&'static str, compact_str::CompactString> {
+        let prefix = prefix.unwrap_or_default();
+
+        BTreeMap::from([
+            (
+                "webhooks.uuid",
+                compact_str::format_compact!("{prefix}uuid"),
+            ),
+            (
+                "webhooks.name",
+                compact_str::format_compact!("{prefix}name"),
+            ),
+            (
+                "webhooks.description",
+                compact_str::format_compact!("{prefix}description"),
+            ),
+            ("webhooks.url", compact_str::format_compact!("{prefix}url")),
+            (
+                "webhooks.secret",
+                compact_str::format_compact!("{prefix}secret"),
+            ),
+            (
+                "webhooks.events",
+                compact_str::format_compact!("{prefix}events"),
+            ),
+            (
+                "webhooks.enabled",
+                compact_str::format_compact!("{prefix}enabled"),
+            ),
+            (
+                "webhooks.created",
+                compact_str::format_compact!("{prefix}created"),
+            ),
+        ])
+    }
+
+    #[inline]
+    fn map(prefix: Option<&str>, row: &PgRow) -> Result<Self, crate::database::DatabaseError> {
+        let prefix = prefix.unwrap_or_default();
+
+        Ok(Self {
+            uuid: row.try_get(compact_str::format_compact!("{prefix}uuid").as_str())?,
+            name: row.try_get(compact_str::format_compact!("{prefix}name").as_str())?,
+            description: row
+                .try_get(compact_str::format_compact!("{prefix}description").as_str())?,
+            url: row.try_get(compact_str::format_compact!("{prefix}url").as_str())?,
+            secret: row.try_get(compact_str::format_compact!("{prefix}secret").as_str())?,
+            events: row.try_get(compact_str::format_compact!("{prefix}events").as_str())?,
+            enabled: row.try_get(compact_str::format_compact!("{prefix}enabled").as_str())?,
+            created: row.try_get(compact_str::format_compact!("{prefix}created").as_str())?,
+        })
+    }
+}
+
+impl Webhook {
+    pub async fn create(
+        database: &crate::database::Database,
+        name: &str,
+        description: Option<&str>,
+        url: &str,
+        events: &[compact_str::CompactString],
+        enabled: bool,
+    ) -> Result<Self, crate::database::DatabaseError> {
+        let secret = rand::distr::Alphanumeric.sample_string(&mut rand::rng(), 48);
+
+        let row = sqlx::query(&format!(
+            r#"
+            INSERT INTO webhooks (name, description, url, secret, events, enabled)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            RETURNING {}
+            "#,
+            Self::columns_sql(None)
+        ))
+        .bind(name)
+        .bind(description)
+        .bind(url)
+        .bind(
+            database
+                .encrypt(secret)
+                .await
+                .map_err(|err| sqlx::Error::Encode(err.into()))?,
+        )
+        .bind(events)
+        .bind(enabled)
+        .fetch_one(database.write())
+        .await?;
+
+        Self::map(None, &row)
+    }
+
+    pub async fn all_enabled_by_event(
+        database: &crate::database::Database,
+        event: &str,
+    ) -> Result<Vec<Self>, crate::database::DatabaseError> {
+        let rows = sqlx::query(&format!(
+            r#"
+            SELECT {}
+            FROM webhooks
+            WHERE webhooks.enabled AND $1 = ANY(webhooks.events)
+            "#,
+            Self::columns_sql(None)
+        ))
+        .bind(event)
+        .fetch_all(database.read())
+        .await?;
+
+        rows.into_iter()
+            .map(|row| Self::map(None, &row))
+            .try_collect_vec()
+    }
+
+    pub async fn all_with_pagination(
+        database: &crate::database::Database,
+        page: i64,
+        per_page: i64,
+        search: Option<&str>,
+    ) -> Result<super::Pagination<Self>, crate::database::DatabaseError> {
+        let offset = (page - 1) * per_page;
+
+        let rows = sqlx::query(&format!(
+            r#"
+            SELECT {}, COUNT(*) OVER() AS total_count
+            FROM webhooks
+            WHERE ($1 IS NULL OR webhooks.name ILIKE '%' || $1 || '%')
+            ORDER BY webhooks.created
+            LIMIT $2 OFFSET $3
+            "#,
+            Self::columns_sql(None)
+        ))
+        .bind(search)
+        .bind(per_page)
+        .bind(offset)
+        .fetch_all(database.read())
+        .await?;
+
+        Ok(super::Pagination {
+            total: rows
+                .first()
+                .map_or(Ok(0), |row| row.try_get("total_count"))?,
+            per_page,
+            page,
+            data: rows
+                .into_iter()
+                .map(|row| Self::map(None, &row))
+                .try_collect_vec()?,
+        })
+    }
+
+    pub async fn reset_secret(
+        &mut self,
+        database: &crate::database::Database,
+    ) -> Result<compact_str::CompactString, crate::database::DatabaseError> {
+        let secret = rand::distr::Alphanumeric.sample_string(&mut rand::rng(), 48);
+        let encrypted_secret = database
+            .encrypt(secret.clone())
+            .await
+            .map_err(|err| sqlx::Error::Encode(err.into()))?;
+
+        sqlx::query(
+            r#"
+            UPDATE webhooks
+            SET secret = $1
+            WHERE webhooks.uuid = $2
+            "#,
+        )
+        .bind(&encrypted_secret)
+        .bind(self.uuid)
+        .execute(database.write())
+        .await?;
+
+        self.secret = encrypted_secret;
+
+        Ok(secret.into())
+    }
+
+    /// Queues a delivery of the given event to every enabled webhook subscribed to it.
+    /// The deliveries are sent by the `send_webhook_deliveries` background task.
+    pub async fn trigger(
+        state: &crate::State,
+        event: &str,
+        data: serde_json::Value,
+    ) -> Result<(), anyhow::Error> {
+        let webhooks = Self::all_enabled_by_event(&state.database, event).await?;
+        if webhooks.is_empty() {
+            return Ok(());
+        }
+
+        let payload = serde_json::json!({
+            "event": event,
+            "timestamp": chrono::Utc::now(),
+            "data": data,
+        });
+
+        for webhook in webhooks {
+            WebhookDelivery::create(&state.database, webhook.uuid, event, &payload).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Registers the server and node event handlers that feed webhook deliveries.
+    pub async fn register_event_handlers() {
+        super::server::Server::register_event_handler(
+            |state, event: Arc<ServerEvent>| async move {
+                let (event, data) = match &*event {
+                    ServerEvent::TransferStarted {
+                        server,
+                        destination_node,
+                        ..
+                    } => (
+                        "server:transfer_started",
+                        serde_json::json!({
+                            "server": webhook_server_object(server),
+                            "destination_node_uuid": destination_node.uuid,
+                        }),
+                    ),
+                    ServerEvent::TransferCompleted {
+                        server,
+                        destination_node,
+                        successful,
+                    } => (
+                        "server:transfer_completed",
+                        serde_json::json!({
+                            "server": webhook_server_object(server),
+                            "destination_node_uuid": destination_node.uuid,
+                            "successful": successful,
+                        }),
+                    ),
+                    ServerEvent::InstallStarted { server, .. } => (
+                        "server:install_started",
+                        serde_json::json!({
+                            "server": webhook_server_object(server),
+                        }),
+                    ),
+                    ServerEvent::InstallCompleted { server, successful } => (
+                        "server:install_completed",
+                        serde_json::json!({
+                            "server": webhook_server_object(server),
+                            "successful": successful,
+                        }),
+                    ),
+                    ServerEvent::StateReset { server } => (
+                        "server:state_reset",
+                        serde_json::json!({
+                            "server": webhook_server_object(server),
+                        }),
+                    ),
+                };
+
+                Self::trigger(&state, event, data).await
+            },
+        )
+        .await;
+
+        super::node::Node::register_event_handler(|state, event: Arc<NodeEvent>| async move {
+            let (event, data) = match &*event {
+                NodeEvent::TokenReset { node, token_id, .. } => (
+                    "node:token_reset",
+                    serde_json::json!({
+                        "node": webhook_node_object(node),
+                        "token_id": token_id,
+                    }),
+                ),
+                NodeEvent::StateReset { node } => (
+                    "node:state_reset",
+                    serde_json::json!({
+                        "node": webhook_node_object(node),
+                    }),
+                ),
+            };
+
+            Self::trigger(&state, event, data).await
+        })
+        .await;
+    }
+
+    pub async fn reveal_secret(
+        &self,
+        database: &crate::database::Database,
+    ) -> Result<compact_str::CompactString, anyhow::Error> {
+        database.decrypt(self.secret.clone()).await
+    }
+
+    /// The secret is not included, use [`Self::reveal_secret`] to read it.
+    #[inline]
+    pub fn into_admin_api_object(self) -> AdminApiWebhook {
+        AdminApiWebhook {
+            uuid: self.uuid,
+            name: self.name,
+            description: self.description,
+            url: self.url,
+            events: self.events,
+            enabled: self.enabled,
+            created: self.created.and_utc(),
+        }
+    }
+}
+
+fn webhook_server_object(server: &super::server::Server) -> serde_json::Value {
+    serde_json::json!({
+        "uuid": server.uuid,
+        "uuid_short": compact_str::format_compact!("{:08x}", server.uuid_short),
+        "external_id": server.external_id,
+        "name": server.name,
+        "node_uuid": server.node.uuid,
+        "owner_uuid": server.owner.uuid,
+        "egg_uuid": server.egg.uuid,
+    })
+}
+
+fn webhook_node_object(node: &super::node::Node) -> serde_json::Value {
+    serde_json::json!({
+        "uuid": node.uuid,
+        "name": node.name,
+        "location_uuid": node.location.uuid,
+    })
+}
+
+#[async_trait::async_trait]
+impl ByUuid for Webhook {
+    async fn by_uuid(
+        database: &crate::database::Database,
+        uuid: uuid::Uuid,
+    ) -> Result<Self, crate::database::DatabaseError> {
+        let row = sqlx::query(&format!(
+            r#"
+            SELECT {}
+            FROM webhooks
+            WHERE webhooks.uuid = $1
+            "#,
+            Self::columns_sql(None)
+        ))
+        .bind(uuid)
+        .fetch_one(database.read())
+        .await?;
+
+        Self::map(None, &row)
+    }
+}
+
+#[async_trait::async_trait]
+impl DeletableModel for Webhook {
+    type DeleteOptions = ();
+
+    fn get_delete_handlers() -> &'static LazyLock<DeleteListenerList<Self>> {
+        static DELETE_LISTENERS: LazyLock<DeleteListenerList<Webhook>> =
+            LazyLock::new(|| Arc::new(ModelHandlerList::default()));
+
+        &DELETE_LISTENERS
+    }
+
+    async fn delete(
+        &self,
+        state: &crate::State,
+        options: Self::DeleteOptions,
+    ) -> Result<(), anyhow::Error> {
+        let mut transaction = state.database.write().begin().await?;
+
+        self.run_delete_handlers(&options, state, &mut transaction)
+            .await?;
+
+        sqlx::query(
+            r#"
+            DELETE FROM webhooks
+            WHERE webhooks.uuid = $1
+            "#,
+        )
+        .bind(self.uuid)
+        .execute(&mut *transaction)
+        .await?;
+
+        transaction.commit().await?;
+
+        Ok(())
+    }
+}
+
+#[derive(ToSchema, Serialize)]
+#[schema(title = "Webhook")]
+pub struct AdminApiWebhook {
+    pub uuid: uuid::Uuid,
+
+    pub name: compact_str::CompactString,
+    pub description: Option<compact_str::CompactString>,
+
+    pub url: compact_str::CompactString,
+    pub events: Vec<compact_str::CompactString>,
+
+    pub enabled: bool,
+
+    pub created: chrono::DateTime<chrono::Utc>,
+}
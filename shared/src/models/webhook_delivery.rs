@@ -0,0 +1,386 @@
+use crate::prelude::*;
+use hmac::Mac;
+use serde::{Deserialize, Serialize};
+use sqlx::{Row, postgres::PgRow};
+use std::collections::BTreeMap;
+use utoipa::ToSchema;
+
+/// The maximum amount of attempts a delivery gets before it is given up on.
+pub const MAX_WEBHOOK_DELIVERY_ATTEMPTS: i16 = 8;
+
+/// How long a claimed delivery is held before another sender may pick it up again.
+pub const WEBHOOK_DELIVERY_LEASE: std::time::Duration = std::time::Duration::from_secs(120);
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct WebhookDelivery {
+    pub uuid: uuid::Uuid,
+    pub webhook_uuid: uuid::Uuid,
+
+    pub event: compact_str::CompactString,
+    pub payload: serde_json::Value,
+
+    pub attempts: i16,
+    pub response_status: Option<i16>,
+    pub response_body: Option<String>,
+
+    pub next_attempt: Option<chrono::NaiveDateTime>,
+    pub delivered: Option<chrono::NaiveDateTime>,
+    pub created: chrono::NaiveDateTime,
+}
+
+impl BaseModel for WebhookDelivery {
+    const NAME: &'static str = "webhook_delivery";
+
+    #[inline]
+    fn columns(prefix: Option<&str>) -> BTreeMap<&'static str, compact_str::CompactString> {
+        let prefix = prefix.unwrap_or_default();
+
+        BTreeMap::from([
+            (
+                "webhook_deliveries.uuid",
+                compact_str::format_compact!("{prefix}uuid"),
+            ),
+            (
+                "webhook_deliveries.webhook_uuid",
+                compact_str::format_compact!("{prefix}webhook_uuid"),
+            ),
+            (
+                "webhook_deliveries.event",
+                compact_str::format_compact!("{prefix}event"),
+            ),
+            (
+                "webhook_deliveries.payload",
+                compact_str::format_compact!("{prefix}payload"),
+            ),
+            (
+                "webhook_deliveries.attempts",
+                compact_str::format_compact!("{prefix}attempts"),
+            ),
+            (
+                "webhook_deliveries.response_status",
+                compact_str::format_compact!("{prefix}response_status"),
+            ),
+            (
+                "webhook_deliveries.response_body",
+                compact_str::format_compact!("{prefix}response_body"),
+            ),
+            (
+                "webhook_deliveries.next_attempt",
+                compact_str::format_compact!("{prefix}next_attempt"),
+            ),
+            (
+                "webhook_deliveries.delivered",
+                compact_str::format_compact!("{prefix}delivered"),
+            ),
+            (
+                "webhook_deliveries.created",
+                compact_str::format_compact!("{prefix}created"),
+            ),
+        ])
+    }
+
+    #[inline]
+    fn map(prefix: Option<&str>, row: &PgRow) -> Result<Self, crate::database::DatabaseError> {
+        let prefix = prefix.unwrap_or_default();
+
+        Ok(Self {
+            uuid: row.try_get(compact_str::format_compact!("{prefix}uuid").as_str())?,
+            webhook_uuid: row
+                .try_get(compact_str::format_compact!("{prefix}webhook_uuid").as_str())?,
+            event: row.try_get(compact_str::format_compact!("{prefix}event").as_str())?,
+            payload: row.try_get(compact_str::format_compact!("{prefix}payload").as_str())?,
+            attempts: row.try_get(compact_str::format_compact!("{prefix}attempts").as_str())?,
+            response_status: row
+                .try_get(compact_str::format_compact!("{prefix}response_status").as_str())?,
+            response_body: row
+                .try_get(compact_str::format_compact!("{prefix}response_body").as_str())?,
+            next_attempt: row
+                .try_get(compact_str::format_compact!("{prefix}next_attempt").as_str())?,
+            delivered: row.try_get(compact_str::format_compact!("{prefix}delivered").as_str())?,
+            created: row.try_get(compact_str::format_compact!("{prefix}created").as_str())?,
+        })
+    }
+}
+
+impl WebhookDelivery {
+    /// Queues a delivery, it is sent by the `send_webhook_deliveries` background task.
+    pub async fn create(
+        database: &crate::database::Database,
+        webhook_uuid: uuid::Uuid,
+        event: &str,
+        payload: &serde_json::Value,
+    ) -> Result<Self, crate::database::DatabaseError> {
+        let row = sqlx::query(&format!(
+            r#"
+            INSERT INTO webhook_deliveries (webhook_uuid, event, payload, next_attempt)
+            VALUES ($1, $2, $3, NOW())
+            RETURNING {}
+            "#,
+            Self::columns_sql(None)
+        ))
+        .bind(webhook_uuid)
+        .bind(event)
+        .bind(payload)
+        .fetch_one(database.write())
+        .await?;
+
+        Self::map(None, &row)
+    }
+
+    pub async fn by_webhook_uuid_uuid(
+        database: &crate::database::Database,
+        webhook_uuid: uuid::Uuid,
+        uuid: uuid::Uuid,
+    ) -> Result<Option<Self>, crate::database::DatabaseError> {
+        let row = sqlx::query(&format!(
+            r#"
+            SELECT {}
+            FROM webhook_deliveries
+            WHERE webhook_deliveries.webhook_uuid = $1 AND webhook_deliveries.uuid = $2
+            "#,
+            Self::columns_sql(None)
+        ))
+        .bind(webhook_uuid)
+        .bind(uuid)
+        .fetch_optional(database.read())
+        .await?;
+
+        row.try_map(|row| Self::map(None, &row))
+    }
+
+    pub async fn by_webhook_uuid_with_pagination(
+        database: &crate::database::Database,
+        webhook_uuid: uuid::Uuid,
+        page: i64,
+        per_page: i64,
+        search: Option<&str>,
+    ) -> Result<super::Pagination<Self>, crate::database::DatabaseError> {
+        let offset = (page - 1) * per_page;
+
+        let rows = sqlx::query(&format!(
+            r#"
+            SELECT {}, COUNT(*) OVER() AS total_count
+            FROM webhook_deliveries
+            WHERE webhook_deliveries.webhook_uuid = $1 AND ($2 IS NULL OR webhook_deliveries.event ILIKE '%' || $2 || '%')
+            ORDER BY webhook_deliveries.created DESC
+            LIMIT $3 OFFSET $4
+            "#,
+            Self::columns_sql(None)
+        ))
+        .bind(webhook_uuid)
+        .bind(search)
+        .bind(per_page)
+        .bind(offset)
+        .fetch_all(database.read())
+        .await?;
+
+        Ok(super::Pagination {
+            total: rows
+                .first()
+                .map_or(Ok(0), |row| row.try_get("total_count"))?,
+            per_page,
+            page,
+            data: rows
+                .into_iter()
+                .map(|row| Self::map(None, &row))
+                .try_collect_vec()?,
+        })
+    }
+
+    /// Claims up to `limit` undelivered deliveries whose next attempt is due, along with
+    /// their webhooks. Deliveries of disabled webhooks are skipped.
+    ///
+    /// Claimed deliveries have their next attempt pushed back by [`WEBHOOK_DELIVERY_LEASE`],
+    /// so concurrent panels never send the same delivery twice, and a delivery whose sender
+    /// died is picked up again once the lease runs out.
+    pub async fn claim_due(
+        database: &crate::database::Database,
+        limit: i64,
+    ) -> Result<Vec<(Self, super::webhook::Webhook)>, crate::database::DatabaseError> {
+        let rows = sqlx::query(&format!(
+            r#"
+            WITH claimed AS (
+                UPDATE webhook_deliveries
+                SET next_attempt = NOW() + make_interval(secs => $2)
+                WHERE webhook_deliveries.uuid IN (
+                    SELECT webhook_deliveries.uuid
+                    FROM webhook_deliveries
+                    JOIN webhooks ON webhooks.uuid = webhook_deliveries.webhook_uuid
+                    WHERE webhooks.enabled
+                        AND webhook_deliveries.delivered IS NULL
+                        AND webhook_deliveries.next_attempt <= NOW()
+                    ORDER BY webhook_deliveries.next_attempt
+                    LIMIT $1
+                    FOR UPDATE OF webhook_deliveries SKIP LOCKED
+                )
+                RETURNING webhook_deliveries.*
+            )
+            SELECT {}, {}
+            FROM claimed AS webhook_deliveries
+            JOIN webhooks ON webhooks.uuid = webhook_deliveries.webhook_uuid
+            "#,
+            Self::columns_sql(None),
+            super::webhook::Webhook::columns_sql(Some("webhook_"))
+        ))
+        .bind(limit)
+        .bind(WEBHOOK_DELIVERY_LEASE.as_secs_f64())
+        .fetch_all(database.write())
+        .await?;
+
+        rows.into_iter()
+            .map(|row| {
+                Ok::<_, crate::database::DatabaseError>((
+                    Self::map(None, &row)?,
+                    super::webhook::Webhook::map(Some("webhook_"), &row)?,
+                ))
+            })
+            .try_collect_vec()
+    }
+
+    pub async fn delete_older_than(
+        database: &crate::database::Database,
+        cutoff: chrono::DateTime<chrono::Utc>,
+    ) -> Result<u64, crate::database::DatabaseError> {
+        let result = sqlx::query(
+            r#"
+            DELETE FROM webhook_deliveries
+            WHERE webhook_deliveries.created < $1
+            "#,
+        )
+        .bind(cutoff.naive_utc())
+        .execute(database.write())
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// Exponential backoff starting at 30 seconds, capped at 6 hours.
+    #[inline]
+    fn backoff(attempts: i16) -> chrono::NaiveDateTime {
+        let seconds = (30i64 << attempts.clamp(0, 16)).min(6 * 60 * 60);
+
+        chrono::Utc::now().naive_utc() + chrono::Duration::seconds(seconds)
+    }
+
+    /// Signs and sends the delivery to the webhook, recording the outcome.
+    ///
+    /// The request carries an `X-Webhook-Signature` header of the form `sha256=<hex>`, the
+    /// HMAC-SHA256 of `<X-Webhook-Timestamp>.<body>` keyed with the webhook secret.
+    pub async fn attempt(
+        &mut self,
+        state: &crate::State,
+        webhook: &super::webhook::Webhook,
+    ) -> Result<(), anyhow::Error> {
+        let secret = state.database.decrypt(webhook.secret.clone()).await?;
+        let body = serde_json::to_string(&self.payload)?;
+        let timestamp = chrono::Utc::now().timestamp();
+
+        let mut mac = hmac::Hmac::<sha2::Sha256>::new_from_slice(secret.as_bytes())?;
+        mac.update(timestamp.to_string().as_bytes());
+        mac.update(b".");
+        mac.update(body.as_bytes());
+        let signature = format!("sha256={:x}", mac.finalize().into_bytes());
+
+        let result = state
+            .client
+            .post(webhook.url.as_str())
+            .timeout(std::time::Duration::from_secs(15))
+            .header("Content-Type", "application/json")
+            .header("X-Webhook-Event", self.event.as_str())
+            .header("X-Webhook-Delivery", self.uuid.to_string())
+            .header("X-Webhook-Timestamp", timestamp.to_string())
+            .header("X-Webhook-Signature", signature)
+            .body(body)
+            .send()
+            .await;
+
+        self.attempts += 1;
+
+        let (status, response_body, error) = match result {
+            Ok(response) => {
+                let status = response.status();
+                let mut response_body = response.text().await.unwrap_or_default();
+                if response_body.len() > 4096 {
+                    let mut end = 4096;
+                    while !response_body.is_char_boundary(end) {
+                        end -= 1;
+                    }
+
+                    response_body.truncate(end);
+                }
+
+                (
+                    Some(status.as_u16() as i16),
+                    Some(response_body),
+                    (!status.is_success())
+                        .then(|| anyhow::anyhow!("webhook responded with status {status}")),
+                )
+            }
+            Err(err) => (None, Some(err.to_string()), Some(anyhow::Error::from(err))),
+        };
+
+        self.response_status = status;
+        self.response_body = response_body;
+        if error.is_none() {
+            self.delivered = Some(chrono::Utc::now().naive_utc());
+            self.next_attempt = None;
+        } else if self.attempts >= MAX_WEBHOOK_DELIVERY_ATTEMPTS {
+            self.next_attempt = None;
+        } else {
+            self.next_attempt = Some(Self::backoff(self.attempts));
+        }
+
+        sqlx::query(
+            r#"
+            UPDATE webhook_deliveries
+            SET attempts = $1, response_status = $2, response_body = $3, next_attempt = $4, delivered = $5
+            WHERE webhook_deliveries.uuid = $6
+            "#,
+        )
+        .bind(self.attempts)
+        .bind(self.response_status)
+        .bind(&self.response_body)
+        .bind(self.next_attempt)
+        .bind(self.delivered)
+        .bind(self.uuid)
+        .execute(state.database.write())
+        .await?;
+
+        match error {
+            Some(err) => Err(err),
+            None => Ok(()),
+        }
+    }
+
+    #[inline]
+    pub fn into_admin_api_object(self) -> AdminApiWebhookDelivery {
+        AdminApiWebhookDelivery {
+            uuid: self.uuid,
+            event: self.event,
+            payload: self.payload,
+            attempts: self.attempts,
+            response_status: self.response_status,
+            response_body: self.response_body,
+            next_attempt: self.next_attempt.map(|dt| dt.and_utc()),
+            delivered: self.delivered.map(|dt| dt.and_utc()),
+            created: self.created.and_utc(),
+        }
+    }
+}
+
+#[derive(ToSchema, Serialize)]
+#[schema(title = "WebhookDelivery")]
+pub struct AdminApiWebhookDelivery {
+    pub uuid: uuid::Uuid,
+
+    pub event: compact_str::CompactString,
+    pub payload: serde_json::Value,
+
+    pub attempts: i16,
+    pub response_status: Option<i16>,
+    pub response_body: Option<String>,
+
+    pub next_attempt: Option<chrono::DateTime<chrono::Utc>>,
+    pub delivered: Option<chrono::DateTime<chrono::Utc>>,
+    pub created: chrono::DateTime<chrono::Utc>,
+}
@@ -164,6 +164,16 @@ pub(crate) static BASE_USER_PERMISSIONS: LazyLock<IndexMap<&'static str, Permiss
                     ]),
                 },
             ),
+            (
+                "saml-links",
+                PermissionGroup {
+                    description: "Permissions that control the ability to manage saml links on an account.",
+                    permissions: IndexMap::from([
+                        ("read", "Allows to view saml links for the account."),
+                        ("delete", "Allows to delete saml links from the account."),
+                    ]),
+                },
+            ),
             (
                 "sessions",
                 PermissionGroup {
@@ -205,6 +215,19 @@ pub fn validate_user_permissions(
     get_user_permissions().validate_permissions(permissions)
 }
 
+/// Lists the built-in user permissions, permissions added by extensions are not included.
+pub fn list_base_user_permissions() -> Vec<compact_str::CompactString> {
+    BASE_USER_PERMISSIONS
+        .iter()
+        .flat_map(|(key, group)| {
+            group
+                .permissions
+                .keys()
+                .map(move |permission| compact_str::format_compact!("{key}.{permission}"))
+        })
+        .collect()
+}
+
 pub(crate) static BASE_ADMIN_PERMISSIONS: LazyLock<IndexMap<&'static str, PermissionGroup>> =
     LazyLock::new(|| {
         IndexMap::from([
@@ -247,6 +270,14 @@ pub(crate) static BASE_ADMIN_PERMISSIONS: LazyLock<IndexMap<&'static str, Permis
                         ("read", "Allows to view users for the panel."),
                         ("update", "Allows to modify users."),
                         ("disable-two-factor", "Allows to remove users' two-factor."),
+                        (
+                            "unlock",
+                            "Allows to unlock users locked out by failed logins.",
+                        ),
+                        (
+                            "impersonate",
+                            "Allows to sign in as users to see the panel the way they do.",
+                        ),
                         ("delete", "Allows to delete users from the panel."),
                         ("activity", "Allows to view a users' activity log."),
                         (
@@ -295,12 +326,16 @@ pub(crate) static BASE_ADMIN_PERMISSIONS: LazyLock<IndexMap<&'static str, Permis
                         ),
                         (
                             "read",
-                            "Allows to view backup configurations and their passwords for the panel.",
+                            "Allows to view backup configurations for the panel.",
                         ),
                         (
                             "update",
                             "Allows to modify backup configurations and their passwords.",
                         ),
+                        (
+                            "reveal",
+                            "Allows to view the passwords of backup configurations.",
+                        ),
                         (
                             "delete",
                             "Allows to delete backup configurations from the panel.",
@@ -318,13 +353,11 @@ pub(crate) static BASE_ADMIN_PERMISSIONS: LazyLock<IndexMap<&'static str, Permis
                     description: "Permissions that control the ability to manage nodes for the panel.",
                     permissions: IndexMap::from([
                         ("create", "Allows to create new nodes for the panel."),
-                        (
-                            "read",
-                            "Allows to view nodes and their token for the panel.",
-                        ),
+                        ("read", "Allows to view nodes for the panel."),
                         ("update", "Allows to modify nodes."),
                         ("delete", "Allows to delete nodes from the panel."),
                         ("reset-token", "Allows to reset a nodes' token."),
+                        ("reveal-token", "Allows to view a nodes' token."),
                         (
                             "allocations",
                             "Allows to view and manage a nodes' allocations.",
@@ -353,7 +386,33 @@ pub(crate) static BASE_ADMIN_PERMISSIONS: LazyLock<IndexMap<&'static str, Permis
                             "variables",
                             "Allows to view and manage a servers' variables.",
                         ),
+                        (
+                            "variables-reveal",
+                            "Allows to view the values of a servers' secret variables.",
+                        ),
                         ("mounts", "Allows to view and manage a servers' mounts."),
+                        (
+                            "bulk",
+                            "Allows to run and view bulk operations across many servers.",
+                        ),
+                    ]),
+                },
+            ),
+            (
+                "server-templates",
+                PermissionGroup {
+                    description: "Permissions that control the ability to manage server templates for the panel.",
+                    permissions: IndexMap::from([
+                        (
+                            "create",
+                            "Allows to create new server templates for the panel.",
+                        ),
+                        ("read", "Allows to view server templates for the panel."),
+                        ("update", "Allows to modify server templates."),
+                        (
+                            "delete",
+                            "Allows to delete server templates from the panel.",
+                        ),
                     ]),
                 },
             ),
@@ -435,6 +494,44 @@ pub(crate) static BASE_ADMIN_PERMISSIONS: LazyLock<IndexMap<&'static str, Permis
                         ("read", "Allows to view oauth providers for the panel."),
                         ("update", "Allows to modify oauth providers."),
                         ("delete", "Allows to delete oauth providers from the panel."),
+                        (
+                            "reveal",
+                            "Allows to view the client secret of oauth providers.",
+                        ),
+                    ]),
+                },
+            ),
+            (
+                "saml-providers",
+                PermissionGroup {
+                    description: "Permissions that control the ability to manage saml providers for the panel.",
+                    permissions: IndexMap::from([
+                        (
+                            "create",
+                            "Allows to create new saml providers for the panel.",
+                        ),
+                        ("read", "Allows to view saml providers for the panel."),
+                        ("update", "Allows to modify saml providers."),
+                        ("delete", "Allows to delete saml providers from the panel."),
+                    ]),
+                },
+            ),
+            (
+                "mail-templates",
+                PermissionGroup {
+                    description: "Permissions that control the ability to manage mail templates for the panel.",
+                    permissions: IndexMap::from([
+                        (
+                            "create",
+                            "Allows to create new mail templates for the panel.",
+                        ),
+                        (
+                            "read",
+                            "Allows to view and preview mail templates for the panel.",
+                        ),
+                        ("update", "Allows to modify mail templates."),
+                        ("delete", "Allows to delete mail templates from the panel."),
+                        ("test", "Allows sending test mails of mail templates."),
                     ]),
                 },
             ),
@@ -450,6 +547,26 @@ pub(crate) static BASE_ADMIN_PERMISSIONS: LazyLock<IndexMap<&'static str, Permis
                     ]),
                 },
             ),
+            (
+                "webhooks",
+                PermissionGroup {
+                    description: "Permissions that control the ability to manage outbound webhooks for the panel.",
+                    permissions: IndexMap::from([
+                        ("create", "Allows to create new webhooks for the panel."),
+                        ("read", "Allows to view webhooks for the panel."),
+                        (
+                            "update",
+                            "Allows to modify webhooks and reset their secrets.",
+                        ),
+                        ("delete", "Allows to delete webhooks from the panel."),
+                        ("reveal", "Allows to view the secret of webhooks."),
+                        (
+                            "deliveries",
+                            "Allows to view and redeliver a webhooks' deliveries.",
+                        ),
+                    ]),
+                },
+            ),
             (
                 "activity",
                 PermissionGroup {
@@ -613,6 +730,10 @@ pub(crate) static BASE_SERVER_PERMISSIONS: LazyLock<IndexMap<&'static str, Permi
                             "docker-image",
                             "Allows to modify the Docker image used when running the server.",
                         ),
+                        (
+                            "reveal",
+                            "Allows to view the values of secret startup variables.",
+                        ),
                     ]),
                 },
             ),
@@ -705,3 +826,16 @@ pub fn validate_server_permissions(
 ) -> Result<(), ValidationError> {
     get_server_permissions().validate_permissions(permissions)
 }
+
+/// Lists the built-in server permissions, permissions added by extensions are not included.
+pub fn list_base_server_permissions() -> Vec<compact_str::CompactString> {
+    BASE_SERVER_PERMISSIONS
+        .iter()
+        .flat_map(|(key, group)| {
+            group
+                .permissions
+                .keys()
+                .map(move |permission| compact_str::format_compact!("{key}.{permission}"))
+        })
+        .collect()
+}
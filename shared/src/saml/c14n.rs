@@ -0,0 +1,249 @@
+//! Exclusive XML canonicalization without comments, as used by XML signatures.
+//!
+//! See <https://www.w3.org/TR/xml-exc-c14n/>.
+
+/// Canonicalizes the given element and its descendants.
+///
+/// The `excluded` node is left out of the output, which implements the enveloped signature
+/// transform. The namespaces of `inclusive_prefixes` are rendered whenever they are in scope,
+/// with `#default` standing for the default namespace.
+pub fn canonicalize<'a, 'input: 'a>(
+    node: roxmltree::Node<'a, 'input>,
+    excluded: Option<roxmltree::NodeId>,
+    inclusive_prefixes: &[&'a str],
+) -> String {
+    let mut output = String::new();
+    write_element(&mut output, node, excluded, inclusive_prefixes, &[]);
+
+    output
+}
+
+/// Returns the qualified name of an element as written in the source document.
+fn element_qname<'input>(node: roxmltree::Node<'_, 'input>) -> &'input str {
+    let input = node.document().input_text();
+    let start = node.range().start + 1;
+
+    input[start..]
+        .split(|c: char| c.is_whitespace() || c == '>' || c == '/')
+        .next()
+        .unwrap_or_default()
+}
+
+#[inline]
+fn qname_prefix(qname: &str) -> &str {
+    qname.split_once(':').map_or("", |(prefix, _)| prefix)
+}
+
+fn write_element<'a, 'input: 'a>(
+    output: &mut String,
+    node: roxmltree::Node<'a, 'input>,
+    excluded: Option<roxmltree::NodeId>,
+    inclusive_prefixes: &[&'a str],
+    rendered: &[(&'a str, &'a str)],
+) {
+    let input = node.document().input_text();
+    let qname = element_qname(node);
+
+    let mut utilized = vec![qname_prefix(qname)];
+    for attribute in node.attributes() {
+        let prefix = qname_prefix(&input[attribute.range_qname()]);
+        if !prefix.is_empty() {
+            utilized.push(prefix);
+        }
+    }
+    for prefix in inclusive_prefixes.iter().copied() {
+        utilized.push(if prefix == "#default" { "" } else { prefix });
+    }
+
+    let mut namespaces: Vec<(&'a str, &'a str)> = Vec::new();
+    for prefix in utilized {
+        if prefix == "xml" || namespaces.iter().any(|(p, _)| *p == prefix) {
+            continue;
+        }
+
+        let uri = node
+            .namespaces()
+            .find(|namespace| namespace.name().unwrap_or_default() == prefix)
+            .map(|namespace| namespace.uri())
+            .unwrap_or_default();
+        let previous = rendered
+            .iter()
+            .rev()
+            .find(|(p, _)| *p == prefix)
+            .map(|(_, uri)| *uri);
+
+        if uri.is_empty() {
+            // an unqualified element only needs to undeclare a default namespace rendered above it
+            if prefix.is_empty() && previous.is_some_and(|previous| !previous.is_empty()) {
+                namespaces.push(("", ""));
+            }
+
+            continue;
+        }
+
+        if previous != Some(uri) {
+            namespaces.push((prefix, uri));
+        }
+    }
+    namespaces.sort_by(|a, b| a.0.cmp(b.0));
+
+    output.push('<');
+    output.push_str(qname);
+
+    for (prefix, uri) in &namespaces {
+        if prefix.is_empty() {
+            output.push_str(" xmlns=\"");
+        } else {
+            output.push_str(" xmlns:");
+            output.push_str(prefix);
+            output.push_str("=\"");
+        }
+        escape_attribute(output, uri);
+        output.push('"');
+    }
+
+    let mut attributes: Vec<_> = node.attributes().collect();
+    attributes.sort_by(|a, b| {
+        (a.namespace().unwrap_or_default(), a.name())
+            .cmp(&(b.namespace().unwrap_or_default(), b.name()))
+    });
+
+    for attribute in attributes {
+        output.push(' ');
+        output.push_str(&input[attribute.range_qname()]);
+        output.push_str("=\"");
+        escape_attribute(output, attribute.value());
+        output.push('"');
+    }
+
+    output.push('>');
+
+    let mut rendered = rendered.to_vec();
+    rendered.extend(namespaces);
+
+    for child in node.children() {
+        if Some(child.id()) == excluded {
+            continue;
+        }
+
+        match child.node_type() {
+            roxmltree::NodeType::Element => {
+                write_element(output, child, excluded, inclusive_prefixes, &rendered)
+            }
+            roxmltree::NodeType::Text => escape_text(output, child.text().unwrap_or_default()),
+            roxmltree::NodeType::PI => {
+                if let Some(pi) = child.pi() {
+                    output.push_str("<?");
+                    output.push_str(pi.target);
+                    if let Some(value) = pi.value {
+                        output.push(' ');
+                        output.push_str(value);
+                    }
+                    output.push_str("?>");
+                }
+            }
+            roxmltree::NodeType::Comment | roxmltree::NodeType::Root => {}
+        }
+    }
+
+    output.push_str("</");
+    output.push_str(qname);
+    output.push('>');
+}
+
+fn escape_attribute(output: &mut String, value: &str) {
+    for c in value.chars() {
+        match c {
+            '&' => output.push_str("&amp;"),
+            '<' => output.push_str("&lt;"),
+            '"' => output.push_str("&quot;"),
+            '\t' => output.push_str("&#x9;"),
+            '\n' => output.push_str("&#xA;"),
+            '\r' => output.push_str("&#xD;"),
+            c => output.push(c),
+        }
+    }
+}
+
+fn escape_text(output: &mut String, value: &str) {
+    for c in value.chars() {
+        match c {
+            '&' => output.push_str("&amp;"),
+            '<' => output.push_str("&lt;"),
+            '>' => output.push_str("&gt;"),
+            '\r' => output.push_str("&#xD;"),
+            c => output.push(c),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::canonicalize;
+
+    fn canonicalize_root(xml: &str, inclusive_prefixes: &[&str]) -> String {
+        let document = roxmltree::Document::parse(xml).unwrap();
+
+        canonicalize(document.root_element(), None, inclusive_prefixes)
+    }
+
+    #[test]
+    fn sorts_attributes_and_pushes_namespaces_down() {
+        let xml =
+            r#"<a:root xmlns:a="urn:a" xmlns:b="urn:b"><a:child b:attr="1" z="2" a="3"/></a:root>"#;
+        let document = roxmltree::Document::parse(xml).unwrap();
+        let child = document.root_element().first_element_child().unwrap();
+
+        assert_eq!(
+            canonicalize(child, None, &[]),
+            r#"<a:child xmlns:a="urn:a" xmlns:b="urn:b" a="3" z="2" b:attr="1"></a:child>"#
+        );
+    }
+
+    #[test]
+    fn drops_unused_namespaces_unless_inclusive() {
+        let xml = r#"<root xmlns:unused="urn:u"><child>text</child></root>"#;
+
+        assert_eq!(
+            canonicalize_root(xml, &[]),
+            "<root><child>text</child></root>"
+        );
+        assert_eq!(
+            canonicalize_root(xml, &["unused"]),
+            r#"<root xmlns:unused="urn:u"><child>text</child></root>"#
+        );
+    }
+
+    #[test]
+    fn undeclares_the_default_namespace_once() {
+        let xml = r#"<root xmlns="urn:d"><child xmlns=""><leaf/></child></root>"#;
+
+        assert_eq!(
+            canonicalize_root(xml, &[]),
+            r#"<root xmlns="urn:d"><child xmlns=""><leaf></leaf></child></root>"#
+        );
+    }
+
+    #[test]
+    fn escapes_text_and_attributes() {
+        let xml = r#"<r a="&quot;&lt;&gt;">&lt;&amp;&gt;&#13;</r>"#;
+
+        assert_eq!(
+            canonicalize_root(xml, &[]),
+            r#"<r a="&quot;&lt;>">&lt;&amp;&gt;&#xD;</r>"#
+        );
+    }
+
+    #[test]
+    fn skips_comments_and_the_excluded_node() {
+        let xml = r#"<r><s/><!-- comment --><?target data?><t/></r>"#;
+        let document = roxmltree::Document::parse(xml).unwrap();
+        let root = document.root_element();
+        let signature = root.first_element_child().unwrap();
+
+        assert_eq!(
+            canonicalize(root, Some(signature.id()), &[]),
+            "<r><?target data?><t></t></r>"
+        );
+    }
+}
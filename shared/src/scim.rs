@@ -0,0 +1,667 @@
+//! Helpers for the SCIM 2.0 provisioning endpoints (RFC 7643 and RFC 7644).
+//!
+//! Identity providers use these endpoints to create, update and deactivate users and to
+//! manage their group memberships, groups being backed by roles.
+
+use crate::response::ApiResponse;
+use axum::{
+    body::Bytes,
+    extract::{FromRequest, Request},
+    http::StatusCode,
+};
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use sha2::Digest;
+use std::{fmt::Write, str::FromStr};
+use utoipa::ToSchema;
+
+pub const CONTENT_TYPE: &str = "application/scim+json";
+
+pub const SCHEMA_USER: &str = "urn:ietf:params:scim:schemas:core:2.0:User";
+pub const SCHEMA_GROUP: &str = "urn:ietf:params:scim:schemas:core:2.0:Group";
+pub const SCHEMA_LIST_RESPONSE: &str = "urn:ietf:params:scim:api:messages:2.0:ListResponse";
+pub const SCHEMA_PATCH_OP: &str = "urn:ietf:params:scim:api:messages:2.0:PatchOp";
+pub const SCHEMA_ERROR: &str = "urn:ietf:params:scim:api:messages:2.0:Error";
+pub const SCHEMA_SERVICE_PROVIDER_CONFIG: &str =
+    "urn:ietf:params:scim:schemas:core:2.0:ServiceProviderConfig";
+
+/// The maximum amount of resources returned by a single list request.
+pub const MAX_RESULTS: i64 = 100;
+
+/// Hashes a SCIM bearer token the way it is stored in the settings.
+pub fn hash_token(token: &str) -> compact_str::CompactString {
+    let digest = sha2::Sha256::digest(token.as_bytes());
+
+    let mut hash = compact_str::CompactString::with_capacity(digest.len() * 2);
+    for byte in digest {
+        let _ = write!(hash, "{byte:02x}");
+    }
+
+    hash
+}
+
+/// Checks the given bearer token against the configured token hash in constant time.
+pub fn verify_token(settings: &crate::settings::AppSettingsScim, token: &str) -> bool {
+    let Some(token_hash) = &settings.token_hash else {
+        return false;
+    };
+
+    settings.enabled
+        && constant_time_eq::constant_time_eq(hash_token(token).as_bytes(), token_hash.as_bytes())
+}
+
+/// Serializes a SCIM resource, SCIM clients expect JSON regardless of the `Accept` header.
+pub fn response(body: impl Serialize) -> ApiResponse {
+    let bytes = serde_json::to_vec(&body).unwrap_or_else(|err| {
+        tracing::error!("failed to serialize scim response body: {:?}", err);
+        b"{}".to_vec()
+    });
+
+    ApiResponse::new(axum::body::Body::from(bytes)).with_header("Content-Type", CONTENT_TYPE)
+}
+
+#[derive(ToSchema, Serialize)]
+pub struct ScimError<'a> {
+    #[schema(value_type = Vec<String>)]
+    pub schemas: [&'static str; 1],
+    pub status: compact_str::CompactString,
+    #[serde(rename = "scimType", skip_serializing_if = "Option::is_none")]
+    pub scim_type: Option<&'a str>,
+    pub detail: &'a str,
+}
+
+/// Creates a SCIM error response, `scim_type` is one of the error types defined in RFC 7644.
+pub fn error(status: StatusCode, scim_type: Option<&str>, detail: &str) -> ApiResponse {
+    response(ScimError {
+        schemas: [SCHEMA_ERROR],
+        status: compact_str::format_compact!("{}", status.as_u16()),
+        scim_type,
+        detail,
+    })
+    .with_status(status)
+}
+
+#[derive(ToSchema, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ListResponse<T: Serialize> {
+    #[schema(value_type = Vec<String>)]
+    pub schemas: [&'static str; 1],
+    pub total_results: i64,
+    pub start_index: i64,
+    pub items_per_page: i64,
+    #[serde(rename = "Resources")]
+    pub resources: Vec<T>,
+}
+
+impl<T: Serialize> ListResponse<T> {
+    pub fn new(total_results: i64, start_index: i64, resources: Vec<T>) -> Self {
+        Self {
+            schemas: [SCHEMA_LIST_RESPONSE],
+            total_results,
+            start_index,
+            items_per_page: resources.len() as i64,
+            resources,
+        }
+    }
+}
+
+#[derive(ToSchema, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ListParams {
+    pub filter: Option<String>,
+    pub start_index: Option<i64>,
+    pub count: Option<i64>,
+    pub excluded_attributes: Option<String>,
+}
+
+impl ListParams {
+    /// The 1-based index of the first resource to return.
+    #[inline]
+    pub fn start_index(&self) -> i64 {
+        self.start_index.unwrap_or(1).max(1)
+    }
+
+    #[inline]
+    pub fn count(&self) -> i64 {
+        self.count.unwrap_or(MAX_RESULTS).clamp(0, MAX_RESULTS)
+    }
+
+    /// Whether the client asked for the given attribute to be left out of the resources.
+    pub fn is_excluded(&self, attribute: &str) -> bool {
+        self.excluded_attributes.as_ref().is_some_and(|excluded| {
+            excluded
+                .split(',')
+                .any(|excluded| excluded.trim().eq_ignore_ascii_case(attribute))
+        })
+    }
+}
+
+#[derive(ToSchema, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScimMeta {
+    pub resource_type: &'static str,
+    pub created: chrono::DateTime<chrono::Utc>,
+    pub location: String,
+}
+
+#[derive(ToSchema, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ScimName {
+    pub formatted: Option<compact_str::CompactString>,
+    pub given_name: Option<compact_str::CompactString>,
+    pub family_name: Option<compact_str::CompactString>,
+}
+
+#[derive(ToSchema, Serialize, Deserialize)]
+pub struct ScimEmail {
+    pub value: compact_str::CompactString,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub r#type: Option<compact_str::CompactString>,
+    #[serde(default, deserialize_with = "deserialize_bool")]
+    pub primary: bool,
+}
+
+#[derive(ToSchema, Serialize, Deserialize)]
+pub struct ScimReference {
+    pub value: compact_str::CompactString,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub display: Option<compact_str::CompactString>,
+    #[serde(rename = "$ref", default, skip_serializing_if = "Option::is_none")]
+    pub r#ref: Option<String>,
+}
+
+#[derive(ToSchema, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScimUser {
+    #[schema(value_type = Vec<String>)]
+    pub schemas: [&'static str; 1],
+    pub id: uuid::Uuid,
+    pub external_id: Option<compact_str::CompactString>,
+    pub user_name: compact_str::CompactString,
+    pub name: ScimName,
+    pub display_name: compact_str::CompactString,
+    pub emails: Vec<ScimEmail>,
+    pub active: bool,
+    pub groups: Vec<ScimReference>,
+    pub meta: ScimMeta,
+}
+
+#[derive(ToSchema, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScimGroup {
+    #[schema(value_type = Vec<String>)]
+    pub schemas: [&'static str; 1],
+    pub id: uuid::Uuid,
+    pub display_name: compact_str::CompactString,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub members: Option<Vec<ScimReference>>,
+    pub meta: ScimMeta,
+}
+
+/// The request body of a SCIM endpoint, accepting both `application/scim+json` and
+/// `application/json` and rejecting with SCIM formatted errors.
+pub struct ScimPayload<T: DeserializeOwned>(pub T);
+
+impl<T: DeserializeOwned, S: Send + Sync> FromRequest<S> for ScimPayload<T> {
+    type Rejection = ApiResponse;
+
+    async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
+        let content_type = req
+            .headers()
+            .get(axum::http::header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|s| s.parse::<mime::Mime>().ok());
+
+        match content_type {
+            Some(content_type)
+                if content_type.essence_str() == CONTENT_TYPE
+                    || content_type.essence_str() == mime::APPLICATION_JSON.essence_str() => {}
+            _ => {
+                return Err(error(
+                    StatusCode::BAD_REQUEST,
+                    Some("invalidSyntax"),
+                    "unsupported content type",
+                ));
+            }
+        }
+
+        let bytes = Bytes::from_request(req, state).await.map_err(|_| {
+            error(
+                StatusCode::BAD_REQUEST,
+                Some("invalidSyntax"),
+                "failed to read request body",
+            )
+        })?;
+
+        serde_json::from_slice(&bytes).map(Self).map_err(|err| {
+            error(
+                StatusCode::BAD_REQUEST,
+                Some("invalidSyntax"),
+                &format!("invalid payload: {err}"),
+            )
+        })
+    }
+}
+
+/// Reads a boolean that some identity providers send as a string, e.g. `"False"`.
+pub fn value_as_bool(value: &serde_json::Value) -> Option<bool> {
+    match value {
+        serde_json::Value::Bool(value) => Some(*value),
+        serde_json::Value::String(value) if value.eq_ignore_ascii_case("true") => Some(true),
+        serde_json::Value::String(value) if value.eq_ignore_ascii_case("false") => Some(false),
+        _ => None,
+    }
+}
+
+pub fn deserialize_bool<'de, D: serde::Deserializer<'de>>(
+    deserializer: D,
+) -> Result<bool, D::Error> {
+    let value = serde_json::Value::deserialize(deserializer)?;
+
+    value_as_bool(&value).ok_or_else(|| serde::de::Error::custom("expected a boolean"))
+}
+
+pub fn deserialize_optional_bool<'de, D: serde::Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<bool>, D::Error> {
+    let value = Option::<serde_json::Value>::deserialize(deserializer)?;
+
+    match value {
+        None | Some(serde_json::Value::Null) => Ok(None),
+        Some(value) => value_as_bool(&value)
+            .map(Some)
+            .ok_or_else(|| serde::de::Error::custom("expected a boolean")),
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum FilterOperator {
+    Eq,
+    Ne,
+    Co,
+    Sw,
+    Ew,
+    Pr,
+}
+
+impl FromStr for FilterOperator {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "eq" => Ok(Self::Eq),
+            "ne" => Ok(Self::Ne),
+            "co" => Ok(Self::Co),
+            "sw" => Ok(Self::Sw),
+            "ew" => Ok(Self::Ew),
+            "pr" => Ok(Self::Pr),
+            _ => Err(anyhow::anyhow!("unsupported filter operator `{s}`")),
+        }
+    }
+}
+
+pub struct FilterExpression {
+    pub attribute: compact_str::CompactString,
+    pub operator: FilterOperator,
+    pub value: serde_json::Value,
+}
+
+impl FilterExpression {
+    /// The compared value as a string, booleans and numbers included.
+    pub fn value_string(&self) -> Option<String> {
+        match &self.value {
+            serde_json::Value::String(value) => Some(value.clone()),
+            serde_json::Value::Bool(value) => Some(value.to_string()),
+            serde_json::Value::Number(value) => Some(value.to_string()),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+pub enum FilterAttributeKind {
+    Text,
+    Boolean,
+    Uuid,
+}
+
+/// Maps a SCIM attribute name to the column it is filtered on.
+pub struct FilterAttribute {
+    pub name: &'static str,
+    pub column: &'static str,
+    pub kind: FilterAttributeKind,
+}
+
+/// A filter made up of comparisons joined by `and`, which covers what identity providers
+/// send in practice. `or`, `not` and grouping are rejected.
+pub struct Filter(pub Vec<FilterExpression>);
+
+enum FilterToken {
+    Word(String),
+    String(String),
+}
+
+fn tokenize_filter(filter: &str) -> Result<Vec<FilterToken>, anyhow::Error> {
+    let mut tokens = Vec::new();
+    let mut chars = filter.char_indices().peekable();
+
+    while let Some(&(start, c)) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+        } else if c == '"' {
+            chars.next();
+
+            let mut escaped = false;
+            let mut end = None;
+            for (i, c) in chars.by_ref() {
+                match c {
+                    '\\' if !escaped => escaped = true,
+                    '"' if !escaped => {
+                        end = Some(i);
+                        break;
+                    }
+                    _ => escaped = false,
+                }
+            }
+
+            let end = end.ok_or_else(|| anyhow::anyhow!("unterminated string in filter"))?;
+            tokens.push(FilterToken::String(serde_json::from_str(
+                &filter[start..=end],
+            )?));
+        } else if matches!(c, '(' | ')' | '[' | ']') {
+            return Err(anyhow::anyhow!("grouping is not supported in filters"));
+        } else {
+            let mut end = filter.len();
+            while let Some(&(i, c)) = chars.peek() {
+                if c.is_whitespace() {
+                    end = i;
+                    break;
+                }
+                chars.next();
+            }
+
+            tokens.push(FilterToken::Word(filter[start..end].to_string()));
+        }
+    }
+
+    Ok(tokens)
+}
+
+impl FromStr for Filter {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut tokens = tokenize_filter(s)?.into_iter();
+        let mut expressions = Vec::new();
+
+        loop {
+            let attribute = match tokens.next() {
+                Some(FilterToken::Word(attribute)) => attribute,
+                _ => return Err(anyhow::anyhow!("expected an attribute name in filter")),
+            };
+            let operator: FilterOperator = match tokens.next() {
+                Some(FilterToken::Word(operator)) => operator.parse()?,
+                _ => return Err(anyhow::anyhow!("expected an operator in filter")),
+            };
+
+            let value = if operator == FilterOperator::Pr {
+                serde_json::Value::Null
+            } else {
+                match tokens.next() {
+                    Some(FilterToken::String(value)) => serde_json::Value::String(value),
+                    Some(FilterToken::Word(value)) => serde_json::from_str(&value)
+                        .map_err(|_| anyhow::anyhow!("invalid filter value `{value}`"))?,
+                    None => return Err(anyhow::anyhow!("expected a value in filter")),
+                }
+            };
+
+            expressions.push(FilterExpression {
+                attribute: attribute.into(),
+                operator,
+                value,
+            });
+
+            match tokens.next() {
+                None => break,
+                Some(FilterToken::Word(word)) if word.eq_ignore_ascii_case("and") => {}
+                Some(FilterToken::Word(word)) => {
+                    return Err(anyhow::anyhow!("unsupported filter keyword `{word}`"));
+                }
+                Some(FilterToken::String(_)) => {
+                    return Err(anyhow::anyhow!("unexpected value in filter"));
+                }
+            }
+        }
+
+        Ok(Self(expressions))
+    }
+}
+
+impl Filter {
+    /// Returns the value the given attribute is compared to with `eq`, if any.
+    pub fn eq_value(&self, attribute: &str) -> Option<String> {
+        self.0
+            .iter()
+            .find(|expression| {
+                expression.operator == FilterOperator::Eq
+                    && expression.attribute.eq_ignore_ascii_case(attribute)
+            })
+            .and_then(|expression| expression.value_string())
+    }
+
+    /// Translates the filter into a SQL condition. Every value is returned separately to be
+    /// bound in order, starting at the `$first_param` placeholder.
+    pub fn to_sql(
+        &self,
+        attributes: &[FilterAttribute],
+        first_param: usize,
+    ) -> Result<(String, Vec<String>), anyhow::Error> {
+        let mut conditions = Vec::with_capacity(self.0.len());
+        let mut binds = Vec::new();
+
+        for expression in &self.0 {
+            let attribute = attributes
+                .iter()
+                .find(|attribute| expression.attribute.eq_ignore_ascii_case(attribute.name))
+                .ok_or_else(|| {
+                    anyhow::anyhow!("filtering on `{}` is not supported", expression.attribute)
+                })?;
+            let column = attribute.column;
+
+            if expression.operator == FilterOperator::Pr {
+                conditions.push(format!("{column} IS NOT NULL"));
+                continue;
+            }
+
+            let value = expression
+                .value_string()
+                .ok_or_else(|| anyhow::anyhow!("invalid value for `{}`", attribute.name))?;
+            let param = first_param + binds.len();
+
+            let condition = match (attribute.kind, expression.operator) {
+                (FilterAttributeKind::Text, FilterOperator::Eq) => {
+                    format!("LOWER({column}) = LOWER(${param})")
+                }
+                (FilterAttributeKind::Text, FilterOperator::Ne) => {
+                    format!("({column} IS NULL OR LOWER({column}) <> LOWER(${param}))")
+                }
+                (FilterAttributeKind::Text, FilterOperator::Co) => {
+                    format!("{column} ILIKE '%' || ${param} || '%'")
+                }
+                (FilterAttributeKind::Text, FilterOperator::Sw) => {
+                    format!("{column} ILIKE ${param} || '%'")
+                }
+                (FilterAttributeKind::Text, FilterOperator::Ew) => {
+                    format!("{column} ILIKE '%' || ${param}")
+                }
+                (FilterAttributeKind::Boolean, FilterOperator::Eq) => {
+                    format!("{column} = ${param}::boolean")
+                }
+                (FilterAttributeKind::Boolean, FilterOperator::Ne) => {
+                    format!("{column} <> ${param}::boolean")
+                }
+                (FilterAttributeKind::Uuid, FilterOperator::Eq) => {
+                    format!("{column}::text = LOWER(${param})")
+                }
+                (FilterAttributeKind::Uuid, FilterOperator::Ne) => {
+                    format!("{column}::text <> LOWER(${param})")
+                }
+                _ => {
+                    return Err(anyhow::anyhow!(
+                        "unsupported operator for `{}`",
+                        attribute.name
+                    ));
+                }
+            };
+
+            let value = match (attribute.kind, expression.operator) {
+                (FilterAttributeKind::Boolean, _) => match value.to_ascii_lowercase().as_str() {
+                    "true" => "true".to_string(),
+                    "false" => "false".to_string(),
+                    _ => return Err(anyhow::anyhow!("invalid value for `{}`", attribute.name)),
+                },
+                (
+                    FilterAttributeKind::Text,
+                    FilterOperator::Co | FilterOperator::Sw | FilterOperator::Ew,
+                ) => value
+                    .replace('\\', "\\\\")
+                    .replace('%', "\\%")
+                    .replace('_', "\\_"),
+                _ => value,
+            };
+
+            conditions.push(condition);
+            binds.push(value);
+        }
+
+        Ok((conditions.join(" AND "), binds))
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum PatchOp {
+    Add,
+    Remove,
+    Replace,
+}
+
+impl<'de> Deserialize<'de> for PatchOp {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let op = compact_str::CompactString::deserialize(deserializer)?;
+
+        // some identity providers send the operation capitalized
+        match op.to_ascii_lowercase().as_str() {
+            "add" => Ok(Self::Add),
+            "remove" => Ok(Self::Remove),
+            "replace" => Ok(Self::Replace),
+            _ => Err(serde::de::Error::custom(format!(
+                "unsupported patch operation `{op}`"
+            ))),
+        }
+    }
+}
+
+#[derive(ToSchema, Deserialize)]
+pub struct PatchOperation {
+    #[schema(value_type = String)]
+    pub op: PatchOp,
+    pub path: Option<String>,
+    #[serde(default)]
+    pub value: serde_json::Value,
+}
+
+#[derive(ToSchema, Deserialize)]
+pub struct PatchRequest {
+    #[serde(rename = "Operations", alias = "operations")]
+    pub operations: Vec<PatchOperation>,
+}
+
+/// A parsed PATCH operation path like `name.givenName` or `members[value eq "..."]`.
+pub struct PatchPath {
+    pub attribute: compact_str::CompactString,
+    pub filter: Option<Filter>,
+    pub sub_attribute: Option<compact_str::CompactString>,
+}
+
+impl FromStr for PatchPath {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        // fully qualified paths are prefixed with the schema urn, e.g.
+        // `urn:ietf:params:scim:schemas:core:2.0:User:userName`
+        let s = match s.strip_prefix("urn:") {
+            Some(path) => {
+                let schema_end = path.find('[').unwrap_or(path.len());
+
+                match path[..schema_end].rfind(':') {
+                    Some(index) => &path[index + 1..],
+                    None => return Err(anyhow::anyhow!("invalid path `{s}`")),
+                }
+            }
+            None => s,
+        };
+
+        let (attribute, filter, rest) = match s.split_once('[') {
+            Some((attribute, rest)) => {
+                let (filter, rest) = rest
+                    .split_once(']')
+                    .ok_or_else(|| anyhow::anyhow!("invalid path `{s}`"))?;
+
+                (
+                    attribute,
+                    Some(filter.parse()?),
+                    rest.trim_start_matches('.'),
+                )
+            }
+            None => match s.split_once('.') {
+                Some((attribute, rest)) => (attribute, None, rest),
+                None => (s, None, ""),
+            },
+        };
+
+        if attribute.is_empty() {
+            return Err(anyhow::anyhow!("invalid path `{s}`"));
+        }
+
+        Ok(Self {
+            attribute: attribute.into(),
+            filter,
+            sub_attribute: if rest.is_empty() {
+                None
+            } else {
+                Some(rest.into())
+            },
+        })
+    }
+}
+
+#[derive(ToSchema, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScimUserPayload {
+    pub external_id: Option<compact_str::CompactString>,
+    pub user_name: compact_str::CompactString,
+    #[serde(default)]
+    pub name: ScimName,
+    pub display_name: Option<compact_str::CompactString>,
+    #[serde(default)]
+    pub emails: Vec<ScimEmail>,
+    #[serde(default, deserialize_with = "deserialize_optional_bool")]
+    pub active: Option<bool>,
+    pub password: Option<String>,
+}
+
+#[derive(ToSchema, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScimGroupPayload {
+    pub display_name: compact_str::CompactString,
+    #[serde(default)]
+    pub members: Vec<ScimReference>,
+}
+
+/// Picks the primary email out of a list of SCIM emails, falling back to the first one.
+pub fn primary_email(emails: &[ScimEmail]) -> Option<&compact_str::CompactString> {
+    emails
+        .iter()
+        .find(|email| email.primary)
+        .or_else(|| emails.first())
+        .map(|email| &email.value)
+}
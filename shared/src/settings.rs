@@ -29,6 +29,15 @@ pub enum TwoFactorRequirement {
     None,
 }
 
+#[derive(ToSchema, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum EmailVerificationMode {
+    Disabled,
+    Optional,
+    RequiredForServers,
+    RequiredForLogin,
+}
+
 #[derive(ToSchema, Serialize, Deserialize, Clone)]
 #[serde(tag = "type", rename_all = "snake_case")]
 pub enum StorageDriver {
@@ -162,12 +171,49 @@ pub enum PublicCaptchaProvider<'a> {
     Hcaptcha { site_key: &'a str },
 }
 
+#[derive(ToSchema, Serialize, Deserialize, Clone)]
+pub struct LdapGroupRoleMapping {
+    pub group: compact_str::CompactString,
+    pub role_uuid: uuid::Uuid,
+}
+
+#[derive(ToSchema, Serialize, Deserialize, Clone)]
+pub struct LdapSettings {
+    pub url: compact_str::CompactString,
+    pub starttls: bool,
+    pub verify_tls: bool,
+
+    pub bind_dn: Option<compact_str::CompactString>,
+    pub bind_password: Option<compact_str::CompactString>,
+
+    pub search_base: compact_str::CompactString,
+    pub user_filter: compact_str::CompactString,
+
+    pub identifier_attribute: compact_str::CompactString,
+    pub username_attribute: compact_str::CompactString,
+    pub email_attribute: compact_str::CompactString,
+    pub name_first_attribute: compact_str::CompactString,
+    pub name_last_attribute: compact_str::CompactString,
+    pub group_attribute: compact_str::CompactString,
+
+    pub group_role_mappings: Vec<LdapGroupRoleMapping>,
+    pub create_users: bool,
+}
+
+#[derive(ToSchema, Serialize, Deserialize, Clone)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum LdapMode {
+    None,
+    Ldap(Box<LdapSettings>),
+}
+
 #[derive(Clone, ToSchema, Serialize, Deserialize)]
 pub struct AppSettingsApp {
     pub name: compact_str::CompactString,
     pub url: compact_str::CompactString,
     pub language: compact_str::CompactString,
     pub two_factor_requirement: TwoFactorRequirement,
+    pub email_verification: EmailVerificationMode,
 
     pub telemetry_enabled: bool,
     pub registration_enabled: bool,
@@ -191,6 +237,15 @@ impl SettingsSerializeExt for AppSettingsApp {
                     TwoFactorRequirement::None => "none",
                 },
             )
+            .write_raw_setting(
+                "email_verification",
+                match self.email_verification {
+                    EmailVerificationMode::Disabled => "disabled",
+                    EmailVerificationMode::Optional => "optional",
+                    EmailVerificationMode::RequiredForServers => "required_for_servers",
+                    EmailVerificationMode::RequiredForLogin => "required_for_login",
+                },
+            )
             .write_raw_setting(
                 "telemetry_enabled",
                 self.telemetry_enabled.to_compact_string(),
@@ -228,6 +283,15 @@ impl SettingsDeserializeExt for AppSettingsAppDeserializer {
                 Some("all_users") => TwoFactorRequirement::AllUsers,
                 _ => TwoFactorRequirement::None,
             },
+            email_verification: match deserializer
+                .take_raw_setting("email_verification")
+                .as_deref()
+            {
+                Some("optional") => EmailVerificationMode::Optional,
+                Some("required_for_servers") => EmailVerificationMode::RequiredForServers,
+                Some("required_for_login") => EmailVerificationMode::RequiredForLogin,
+                _ => EmailVerificationMode::Disabled,
+            },
             telemetry_enabled: deserializer
                 .take_raw_setting("telemetry_enabled")
                 .map(|s| s == "true")
@@ -287,6 +351,10 @@ pub struct AppSettingsServer {
     pub allow_overwriting_custom_docker_image: bool,
     pub allow_editing_startup_command: bool,
     pub allow_viewing_installation_logs: bool,
+
+    pub expiry_notice_days: u16,
+    pub expiry_grace_period_days: u16,
+    pub expiry_delete_servers: bool,
 }
 
 #[async_trait::async_trait]
@@ -325,6 +393,18 @@ impl SettingsSerializeExt for AppSettingsServer {
             .write_raw_setting(
                 "allow_viewing_installation_logs",
                 self.allow_viewing_installation_logs.to_compact_string(),
+            )
+            .write_raw_setting(
+                "expiry_notice_days",
+                self.expiry_notice_days.to_compact_string(),
+            )
+            .write_raw_setting(
+                "expiry_grace_period_days",
+                self.expiry_grace_period_days.to_compact_string(),
+            )
+            .write_raw_setting(
+                "expiry_delete_servers",
+                self.expiry_delete_servers.to_compact_string(),
             ))
     }
 }
@@ -366,6 +446,18 @@ impl SettingsDeserializeExt for AppSettingsServerDeserializer {
                 .take_raw_setting("allow_viewing_installation_logs")
                 .map(|s| s == "true")
                 .unwrap_or(true),
+            expiry_notice_days: deserializer
+                .take_raw_setting("expiry_notice_days")
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(3),
+            expiry_grace_period_days: deserializer
+                .take_raw_setting("expiry_grace_period_days")
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(7),
+            expiry_delete_servers: deserializer
+                .take_raw_setting("expiry_delete_servers")
+                .map(|s| s == "true")
+                .unwrap_or(false),
         }))
     }
 }
@@ -443,6 +535,199 @@ impl SettingsDeserializeExt for AppSettingsActivityDeserializer {
     }
 }
 
+#[derive(Clone, ToSchema, Serialize, Deserialize)]
+pub struct AppSettingsSecurity {
+    pub lockout_enabled: bool,
+    pub lockout_threshold: u16,
+    pub lockout_duration_minutes: u32,
+    pub lockout_max_duration_minutes: u32,
+
+    pub password_min_length: u16,
+    pub password_require_uppercase: bool,
+    pub password_require_lowercase: bool,
+    pub password_require_number: bool,
+    pub password_require_symbol: bool,
+    pub password_forbid_user_details: bool,
+    pub password_history_count: u16,
+    pub password_check_breached: bool,
+}
+
+impl AppSettingsSecurity {
+    /// How long an account is locked for after the given amount of consecutive failed attempts,
+    /// doubling for every time the threshold was reached again.
+    pub fn lockout_duration(&self, failed_attempts: i32) -> Option<chrono::Duration> {
+        if !self.lockout_enabled
+            || self.lockout_threshold == 0
+            || failed_attempts <= 0
+            || failed_attempts % self.lockout_threshold as i32 != 0
+        {
+            return None;
+        }
+
+        let lockouts = (failed_attempts / self.lockout_threshold as i32 - 1).min(16) as u32;
+        let minutes = (self.lockout_duration_minutes as u64)
+            .saturating_mul(1 << lockouts)
+            .min(self.lockout_max_duration_minutes as u64);
+
+        Some(chrono::Duration::minutes(minutes as i64))
+    }
+}
+
+#[async_trait::async_trait]
+impl SettingsSerializeExt for AppSettingsSecurity {
+    async fn serialize(
+        &self,
+        serializer: SettingsSerializer,
+    ) -> Result<SettingsSerializer, anyhow::Error> {
+        Ok(serializer
+            .write_raw_setting("lockout_enabled", self.lockout_enabled.to_compact_string())
+            .write_raw_setting(
+                "lockout_threshold",
+                self.lockout_threshold.to_compact_string(),
+            )
+            .write_raw_setting(
+                "lockout_duration_minutes",
+                self.lockout_duration_minutes.to_compact_string(),
+            )
+            .write_raw_setting(
+                "lockout_max_duration_minutes",
+                self.lockout_max_duration_minutes.to_compact_string(),
+            )
+            .write_raw_setting(
+                "password_min_length",
+                self.password_min_length.to_compact_string(),
+            )
+            .write_raw_setting(
+                "password_require_uppercase",
+                self.password_require_uppercase.to_compact_string(),
+            )
+            .write_raw_setting(
+                "password_require_lowercase",
+                self.password_require_lowercase.to_compact_string(),
+            )
+            .write_raw_setting(
+                "password_require_number",
+                self.password_require_number.to_compact_string(),
+            )
+            .write_raw_setting(
+                "password_require_symbol",
+                self.password_require_symbol.to_compact_string(),
+            )
+            .write_raw_setting(
+                "password_forbid_user_details",
+                self.password_forbid_user_details.to_compact_string(),
+            )
+            .write_raw_setting(
+                "password_history_count",
+                self.password_history_count.to_compact_string(),
+            )
+            .write_raw_setting(
+                "password_check_breached",
+                self.password_check_breached.to_compact_string(),
+            ))
+    }
+}
+
+pub struct AppSettingsSecurityDeserializer;
+
+#[async_trait::async_trait]
+impl SettingsDeserializeExt for AppSettingsSecurityDeserializer {
+    async fn deserialize_boxed(
+        &self,
+        mut deserializer: SettingsDeserializer<'_>,
+    ) -> Result<ExtensionSettings, anyhow::Error> {
+        Ok(Box::new(AppSettingsSecurity {
+            lockout_enabled: deserializer
+                .take_raw_setting("lockout_enabled")
+                .map(|s| s == "true")
+                .unwrap_or(true),
+            lockout_threshold: deserializer
+                .take_raw_setting("lockout_threshold")
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(5),
+            lockout_duration_minutes: deserializer
+                .take_raw_setting("lockout_duration_minutes")
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(5),
+            lockout_max_duration_minutes: deserializer
+                .take_raw_setting("lockout_max_duration_minutes")
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(1440),
+            password_min_length: deserializer
+                .take_raw_setting("password_min_length")
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(8),
+            password_require_uppercase: deserializer
+                .take_raw_setting("password_require_uppercase")
+                .map(|s| s == "true")
+                .unwrap_or(false),
+            password_require_lowercase: deserializer
+                .take_raw_setting("password_require_lowercase")
+                .map(|s| s == "true")
+                .unwrap_or(false),
+            password_require_number: deserializer
+                .take_raw_setting("password_require_number")
+                .map(|s| s == "true")
+                .unwrap_or(false),
+            password_require_symbol: deserializer
+                .take_raw_setting("password_require_symbol")
+                .map(|s| s == "true")
+                .unwrap_or(false),
+            password_forbid_user_details: deserializer
+                .take_raw_setting("password_forbid_user_details")
+                .map(|s| s == "true")
+                .unwrap_or(false),
+            password_history_count: deserializer
+                .take_raw_setting("password_history_count")
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(0),
+            password_check_breached: deserializer
+                .take_raw_setting("password_check_breached")
+                .map(|s| s == "true")
+                .unwrap_or(false),
+        }))
+    }
+}
+
+#[derive(Clone, ToSchema, Serialize, Deserialize)]
+pub struct AppSettingsScim {
+    pub enabled: bool,
+    /// Hex encoded SHA-256 hash of the SCIM bearer token, the token itself is never stored.
+    pub token_hash: Option<compact_str::CompactString>,
+}
+
+#[async_trait::async_trait]
+impl SettingsSerializeExt for AppSettingsScim {
+    async fn serialize(
+        &self,
+        serializer: SettingsSerializer,
+    ) -> Result<SettingsSerializer, anyhow::Error> {
+        Ok(serializer
+            .write_raw_setting("enabled", self.enabled.to_compact_string())
+            .write_raw_setting("token_hash", self.token_hash.clone().unwrap_or_default()))
+    }
+}
+
+pub struct AppSettingsScimDeserializer;
+
+#[async_trait::async_trait]
+impl SettingsDeserializeExt for AppSettingsScimDeserializer {
+    async fn deserialize_boxed(
+        &self,
+        mut deserializer: SettingsDeserializer<'_>,
+    ) -> Result<ExtensionSettings, anyhow::Error> {
+        Ok(Box::new(AppSettingsScim {
+            enabled: deserializer
+                .take_raw_setting("enabled")
+                .map(|s| s == "true")
+                .unwrap_or(false),
+            token_hash: deserializer
+                .take_raw_setting("token_hash")
+                .filter(|s| !s.is_empty()),
+        }))
+    }
+}
+
 #[derive(ToSchema, Serialize, Deserialize)]
 pub struct AppSettings {
     pub telemetry_uuid: Option<uuid::Uuid>,
@@ -453,6 +738,7 @@ pub struct AppSettings {
     pub storage_driver: StorageDriver,
     pub mail_mode: MailMode,
     pub captcha_provider: CaptchaProvider,
+    pub ldap_mode: LdapMode,
 
     #[schema(inline)]
     pub app: AppSettingsApp,
@@ -462,6 +748,10 @@ pub struct AppSettings {
     pub server: AppSettingsServer,
     #[schema(inline)]
     pub activity: AppSettingsActivity,
+    #[schema(inline)]
+    pub security: AppSettingsSecurity,
+    #[schema(inline)]
+    pub scim: AppSettingsScim,
 
     #[serde(skip)]
     pub extensions: HashMap<&'static str, ExtensionSettings>,
@@ -688,6 +978,59 @@ impl SettingsSerializeExt for AppSettings {
             }
         }
 
+        match &self.ldap_mode {
+            LdapMode::None => {
+                serializer = serializer.write_raw_setting("ldap_mode", "none");
+            }
+            LdapMode::Ldap(ldap) => {
+                let LdapSettings {
+                    url,
+                    starttls,
+                    verify_tls,
+                    bind_dn,
+                    bind_password,
+                    search_base,
+                    user_filter,
+                    identifier_attribute,
+                    username_attribute,
+                    email_attribute,
+                    name_first_attribute,
+                    name_last_attribute,
+                    group_attribute,
+                    group_role_mappings,
+                    create_users,
+                } = &**ldap;
+
+                serializer = serializer
+                    .write_raw_setting("ldap_mode", "ldap")
+                    .write_raw_setting("ldap_url", &**url)
+                    .write_raw_setting("ldap_starttls", starttls.to_compact_string())
+                    .write_raw_setting("ldap_verify_tls", verify_tls.to_compact_string())
+                    .write_raw_setting("ldap_bind_dn", bind_dn.clone().unwrap_or_default())
+                    .write_raw_setting(
+                        "ldap_bind_password",
+                        if let Some(p) = bind_password {
+                            base32::encode(base32::Alphabet::Z, &database.encrypt(p.clone()).await?)
+                        } else {
+                            "".into()
+                        },
+                    )
+                    .write_raw_setting("ldap_search_base", &**search_base)
+                    .write_raw_setting("ldap_user_filter", &**user_filter)
+                    .write_raw_setting("ldap_identifier_attribute", &**identifier_attribute)
+                    .write_raw_setting("ldap_username_attribute", &**username_attribute)
+                    .write_raw_setting("ldap_email_attribute", &**email_attribute)
+                    .write_raw_setting("ldap_name_first_attribute", &**name_first_attribute)
+                    .write_raw_setting("ldap_name_last_attribute", &**name_last_attribute)
+                    .write_raw_setting("ldap_group_attribute", &**group_attribute)
+                    .write_raw_setting(
+                        "ldap_group_role_mappings",
+                        serde_json::to_string(group_role_mappings)?,
+                    )
+                    .write_raw_setting("ldap_create_users", create_users.to_compact_string());
+            }
+        }
+
         serializer = serializer
             .nest("app", &self.app)
             .await?
@@ -696,6 +1039,10 @@ impl SettingsSerializeExt for AppSettings {
             .nest("server", &self.server)
             .await?
             .nest("activity", &self.activity)
+            .await?
+            .nest("security", &self.security)
+            .await?
+            .nest("scim", &self.scim)
             .await?;
 
         for (ext_identifier, ext_settings) in self.extensions.iter() {
@@ -908,6 +1255,72 @@ impl SettingsDeserializeExt for AppSettingsDeserializer {
                 },
                 _ => CaptchaProvider::None,
             },
+            ldap_mode: match deserializer.take_raw_setting("ldap_mode").as_deref() {
+                Some("ldap") => LdapMode::Ldap(Box::new(LdapSettings {
+                    url: deserializer
+                        .take_raw_setting("ldap_url")
+                        .unwrap_or_else(|| "ldap://localhost:389".into()),
+                    starttls: deserializer
+                        .take_raw_setting("ldap_starttls")
+                        .map(|s| s == "true")
+                        .unwrap_or(false),
+                    verify_tls: deserializer
+                        .take_raw_setting("ldap_verify_tls")
+                        .map(|s| s == "true")
+                        .unwrap_or(true),
+                    bind_dn: deserializer
+                        .take_raw_setting("ldap_bind_dn")
+                        .and_then(|s| s.into_optional()),
+                    bind_password: if let Some(bind_password) = deserializer
+                        .take_raw_setting("ldap_bind_password")
+                        .and_then(|s| s.into_optional())
+                    {
+                        base32::decode(base32::Alphabet::Z, &bind_password)
+                            .map(|encrypted| deserializer.database.decrypt(encrypted))
+                            .awaited()
+                            .await
+                            .transpose()?
+                    } else {
+                        None
+                    },
+                    search_base: deserializer
+                        .take_raw_setting("ldap_search_base")
+                        .unwrap_or_else(|| "dc=example,dc=com".into()),
+                    user_filter: deserializer
+                        .take_raw_setting("ldap_user_filter")
+                        .unwrap_or_else(|| {
+                            "(&(objectClass=inetOrgPerson)(|(uid={username})(mail={username})))"
+                                .into()
+                        }),
+                    identifier_attribute: deserializer
+                        .take_raw_setting("ldap_identifier_attribute")
+                        .unwrap_or_else(|| "entryUUID".into()),
+                    username_attribute: deserializer
+                        .take_raw_setting("ldap_username_attribute")
+                        .unwrap_or_else(|| "uid".into()),
+                    email_attribute: deserializer
+                        .take_raw_setting("ldap_email_attribute")
+                        .unwrap_or_else(|| "mail".into()),
+                    name_first_attribute: deserializer
+                        .take_raw_setting("ldap_name_first_attribute")
+                        .unwrap_or_else(|| "givenName".into()),
+                    name_last_attribute: deserializer
+                        .take_raw_setting("ldap_name_last_attribute")
+                        .unwrap_or_else(|| "sn".into()),
+                    group_attribute: deserializer
+                        .take_raw_setting("ldap_group_attribute")
+                        .unwrap_or_else(|| "memberOf".into()),
+                    group_role_mappings: deserializer
+                        .take_raw_setting("ldap_group_role_mappings")
+                        .and_then(|s| serde_json::from_str(&s).ok())
+                        .unwrap_or_default(),
+                    create_users: deserializer
+                        .take_raw_setting("ldap_create_users")
+                        .map(|s| s == "true")
+                        .unwrap_or(true),
+                })),
+                _ => LdapMode::None,
+            },
             app: deserializer
                 .nest("app", &AppSettingsAppDeserializer)
                 .await?,
@@ -920,6 +1333,12 @@ impl SettingsDeserializeExt for AppSettingsDeserializer {
             activity: deserializer
                 .nest("activity", &AppSettingsActivityDeserializer)
                 .await?,
+            security: deserializer
+                .nest("security", &AppSettingsSecurityDeserializer)
+                .await?,
+            scim: deserializer
+                .nest("scim", &AppSettingsScimDeserializer)
+                .await?,
             extensions,
         }))
     }